@@ -38,10 +38,14 @@ def py_clean_business_name(name):
 
 from _leadswarm_native import (
     normalize_domain as rust_normalize_domain,
+    normalize_domains_batch as rust_normalize_domains_batch,
     clean_business_name as rust_clean_business_name,
     extract_emails as rust_extract_emails,
+    extract_phones as rust_extract_phones,
+    extract_contacts as rust_extract_contacts,
     detect_cms as rust_detect_cms,
     detect_tracking as rust_detect_tracking,
+    extract_visible_text as rust_extract_visible_text,
 )
 
 # Email regex (Python)
@@ -52,6 +56,52 @@ def py_extract_emails(html):
     return list(set(EMAIL_RE.findall(html)))[:5]
 
 
+HREF_RE = re.compile(r"""<a\b[^>]*?\shref\s*=\s*["']([^"']+)["']""", re.IGNORECASE)
+SOCIAL_DOMAINS = ["facebook.com", "instagram.com", "twitter.com", "linkedin.com", "youtube.com", "tiktok.com"]
+
+
+def py_extract_social_links(html):
+    links = []
+    for href in HREF_RE.findall(html):
+        if any(domain in href for domain in SOCIAL_DOMAINS) and href not in links:
+            links.append(href)
+    return links
+
+
+try:
+    from bs4 import BeautifulSoup
+
+    def py_visible_text(html):
+        soup = BeautifulSoup(html, "html.parser")
+        for tag in soup(["script", "style", "noscript", "template", "svg"]):
+            tag.decompose()
+        return " ".join(soup.get_text(" ").split())
+except ImportError:
+    from html.parser import HTMLParser
+
+    class _VisibleText(HTMLParser):
+        SKIP = {"script", "style", "noscript", "template", "svg"}
+
+        def __init__(self):
+            super().__init__()
+            self.parts, self.depth = [], 0
+
+        def handle_starttag(self, tag, attrs):
+            self.depth += tag in self.SKIP
+
+        def handle_endtag(self, tag):
+            self.depth -= tag in self.SKIP and self.depth > 0
+
+        def handle_data(self, data):
+            if not self.depth:
+                self.parts.append(data)
+
+    def py_visible_text(html):
+        parser = _VisibleText()
+        parser.feed(html)
+        return " ".join(" ".join(parser.parts).split())
+
+
 # Test data
 URLS = [
     "https://www.example.com/page",
@@ -76,6 +126,8 @@ SAMPLE_HTML = """
 Contact us at info@example-biz.com or sales@example-biz.com
 Call 0412 345 678 or (07) 1234 5678
 <a href="https://calendly.com/booking">Book now</a>
+<a href="https://www.facebook.com/examplebiz/">Facebook</a>
+<a href="https://www.facebook.com/sharer/sharer.php?u=https://example-biz.com">Share</a>
 </body></html>
 """ * 100  # ~10KB HTML repeated
 
@@ -105,3 +157,45 @@ bench("clean_business_name", py_clean_business_name, rust_clean_business_name, N
 bench("extract_emails (HTML)", py_extract_emails, rust_extract_emails, [SAMPLE_HTML] * 100)
 bench("detect_cms (HTML)", lambda h: None, rust_detect_cms, [SAMPLE_HTML] * 100)
 bench("detect_tracking (HTML)", lambda h: {}, rust_detect_tracking, [SAMPLE_HTML] * 100)
+
+# Batch vs per-call FFI on a large synthetic URL column
+BATCH_URLS = [f"https://www.business{i}.com.au/page?id={i}" for i in range(50_000)]
+
+start = time.perf_counter()
+looped = [rust_normalize_domain(u) for u in BATCH_URLS]
+loop_time = time.perf_counter() - start
+
+start = time.perf_counter()
+batched = rust_normalize_domains_batch(BATCH_URLS)
+batch_time = time.perf_counter() - start
+
+assert batched == looped
+speedup = loop_time / batch_time if batch_time > 0 else float('inf')
+print(f"{'normalize_domains_batch (50k)':30s}  Loop: {loop_time*1000:8.2f}ms  Batch: {batch_time*1000:8.2f}ms  Speedup: {speedup:.1f}x")
+
+# The signature index behind analyze_tech_stack is benched against the
+# substring scans it replaced in rust/benches (cargo bench --features bench)
+LARGE_HTML = (SAMPLE_HTML * 120)[:1_000_000]
+RUNS = 20
+
+# Contacts on the ~1 MB page: emails + phones + social links in one call,
+# against the two native calls plus the Python social-link scraper
+start = time.perf_counter()
+for _ in range(RUNS):
+    rust_extract_emails(LARGE_HTML)
+    rust_extract_phones(LARGE_HTML)
+    py_extract_social_links(LARGE_HTML)
+separate_time = (time.perf_counter() - start) / RUNS
+
+start = time.perf_counter()
+for _ in range(RUNS):
+    rust_extract_contacts(LARGE_HTML)
+combined_time = (time.perf_counter() - start) / RUNS
+
+speedup = separate_time / combined_time if combined_time > 0 else float('inf')
+print(f"{'extract_contacts (1 MB)':30s}  Separate: {separate_time*1000:6.2f}ms  Combined: {combined_time*1000:6.2f}ms  Speedup: {speedup:.1f}x")
+
+# Visible text of a ~100 KB page: streaming tag-skipper against the Python
+# parser path (BeautifulSoup when installed, else html.parser)
+TEXT_HTML = SAMPLE_HTML * 10
+bench("extract_visible_text (100 KB)", py_visible_text, rust_extract_visible_text, [TEXT_HTML] * 20)
@@ -14,33 +14,146 @@ _logger = logging.getLogger(__name__)
 
 # Text processing (dedup.py / validation.py)
 normalize_domain = None
+normalize_domains_batch = None
+register_extra_tlds = None
+unique_domains = None
+canonicalize_url = None
+canonicalize_urls_batch = None
+display_domain = None
+canonical_domain = None
 normalize_name = None
 clean_business_name = None
+parse_business_name = None
+strip_location_from_name = None
+register_localities = None
+detect_franchise = None
+register_franchise_brands = None
+is_placeholder_name = None
+placeholder_name_reason = None
+is_placeholder_name_batch = None
+slugify = None
+slugify_unique = None
+name_from_domain = None
+parse_au_address = None
+normalize_au_address = None
+extract_postcode_state = None
+extract_postcode_state_batch = None
+name_tokens = None
+set_name_stopwords = None
+list_name_stopwords = None
+name_similarity = None
+names_match = None
+match_names = None
 normalize_phone = None
 is_directory_domain = None
+classify_domain = None
 is_directory_url = None
+register_directory_domains = None
+register_directory_url_patterns = None
+list_directory_domains = None
+list_directory_url_patterns = None
+reset_directory_overrides = None
+registrable_domain = None
+split_domain = None
 validate_email_domain = None
+validate_email_domain_legacy = None
 filter_emails_for_domain = None
+filter_emails_for_domain_detailed = None
+dedupe_and_rank_emails = None
+validate_email_syntax = None
+is_disposable_email = None
+register_disposable_domains = None
+register_generic_providers = None
+list_generic_providers = None
+classify_email = None
+classify_emails_batch = None
+infer_name_from_email = None
+infer_name_from_email_batch = None
 
 # HTML extraction (contacts.py / technology.py)
 extract_emails = None
+extract_emails_detailed = None
 extract_phones = None
+extract_contacts = None
+extract_social_links = None
+detect_messaging_links = None
+detect_gbp_links = None
+detect_map_embed = None
+detect_video = None
+detect_contact_form = None
+detect_email_capture = None
+detect_captcha = None
+extract_phones_with_labels = None
 detect_cms = None
+detect_cms_detailed = None
+detect_cms_version = None
+detect_page_builder = None
+detect_wp_theme = None
+detect_wp_plugins = None
+detect_ecommerce = None
+detect_payment_providers = None
+detect_chat_widget = None
+detect_marketing_tools = None
+detect_review_widgets = None
+detect_testimonials = None
+detect_hiring_signals = None
+detect_pricing = None
+detect_blog = None
+detect_languages = None
+detect_app_links = None
+register_cms_signature = None
+load_cms_signatures = None
+list_cms_signatures = None
+reset_cms_signatures = None
 detect_tracking = None
+extract_tracking_ids = None
 detect_booking_system = None
+detect_booking_system_detailed = None
+register_booking_signatures = None
 detect_frameworks = None
+detect_outdated_libraries = None
+register_library_latest = None
+detect_legacy_tech = None
 detect_responsive = None
+detect_responsive_detailed = None
+detect_amp = None
+detect_mdot_reference = None
+detect_pwa = None
+detect_parked_domain = None
+detect_placeholder_page = None
+detect_soft_404 = None
+extract_address = None
+detect_directory_page = None
+extract_headings = None
+extract_visible_text = None
+visible_word_count = None
+analyze_content_depth = None
+detect_content_language = None
+extract_copyright_year = None
+is_copyright_stale = None
+extract_meta_seo = None
+seo_audit = None
+analyze_images = None
 analyze_tech_stack = None
+detect_mixed_content = None
+analyze_page_weight = None
+detect_render_blocking = None
+analyze_tech_stack_batch = None
 
 # Scoring (scoring/fit.py / scoring/opportunity.py)
 calculate_fit_score = None
 calculate_opportunity_score = None
 score_prospects_batch = None
 
+# Deduplication (dedup.py)
+dedupe_prospects = None
+
 # Geo / cache (orchestrator.py / locations.py)
 fast_cache_key = None
 haversine_distance = None
 batch_haversine = None
+infer_country = None
+extract_geo = None
 
 # Export serialization (export.py)
 serialize_prospects_csv = None
@@ -48,6 +161,12 @@ serialize_prospects_json = None
 
 # HTML metadata extraction (crawler.py)
 extract_html_metadata = None
+extract_local_business_schema = None
+extract_aggregate_rating = None
+detect_schema_types = None
+extract_og_metadata = None
+best_business_name = None
+extract_opening_hours = None
 
 AVAILABLE = False
 
@@ -55,35 +174,153 @@ try:
     import _leadswarm_native as _n
 
     normalize_domain = _n.normalize_domain
+    normalize_domains_batch = _n.normalize_domains_batch
+    register_extra_tlds = _n.register_extra_tlds
+    unique_domains = _n.unique_domains
+    canonicalize_url = _n.canonicalize_url
+    canonicalize_urls_batch = _n.canonicalize_urls_batch
+    display_domain = _n.display_domain
+    canonical_domain = _n.canonical_domain
     normalize_name = _n.normalize_name
     clean_business_name = _n.clean_business_name
+    parse_business_name = _n.parse_business_name
+    strip_location_from_name = _n.strip_location_from_name
+    register_localities = _n.register_localities
+    detect_franchise = _n.detect_franchise
+    register_franchise_brands = _n.register_franchise_brands
+    is_placeholder_name = _n.is_placeholder_name
+    placeholder_name_reason = _n.placeholder_name_reason
+    is_placeholder_name_batch = _n.is_placeholder_name_batch
+    slugify = _n.slugify
+    slugify_unique = _n.slugify_unique
+    name_from_domain = _n.name_from_domain
+    parse_au_address = _n.parse_au_address
+    normalize_au_address = _n.normalize_au_address
+    extract_postcode_state = _n.extract_postcode_state
+    extract_postcode_state_batch = _n.extract_postcode_state_batch
+    name_tokens = _n.name_tokens
+    set_name_stopwords = _n.set_name_stopwords
+    list_name_stopwords = _n.list_name_stopwords
+    name_similarity = _n.name_similarity
+    names_match = _n.names_match
+    match_names = _n.match_names
     normalize_phone = _n.normalize_phone
     is_directory_domain = _n.is_directory_domain
+    classify_domain = _n.classify_domain
     is_directory_url = _n.is_directory_url
+    register_directory_domains = _n.register_directory_domains
+    register_directory_url_patterns = _n.register_directory_url_patterns
+    list_directory_domains = _n.list_directory_domains
+    list_directory_url_patterns = _n.list_directory_url_patterns
+    reset_directory_overrides = _n.reset_directory_overrides
+    registrable_domain = _n.registrable_domain
+    split_domain = _n.split_domain
     validate_email_domain = _n.validate_email_domain
+    validate_email_domain_legacy = _n.validate_email_domain_legacy
     filter_emails_for_domain = _n.filter_emails_for_domain
+    filter_emails_for_domain_detailed = _n.filter_emails_for_domain_detailed
+    dedupe_and_rank_emails = _n.dedupe_and_rank_emails
+    validate_email_syntax = _n.validate_email_syntax
+    is_disposable_email = _n.is_disposable_email
+    register_disposable_domains = _n.register_disposable_domains
+    register_generic_providers = _n.register_generic_providers
+    list_generic_providers = _n.list_generic_providers
+    classify_email = _n.classify_email
+    classify_emails_batch = _n.classify_emails_batch
+    infer_name_from_email = _n.infer_name_from_email
+    infer_name_from_email_batch = _n.infer_name_from_email_batch
 
     extract_emails = _n.extract_emails
+    extract_emails_detailed = _n.extract_emails_detailed
     extract_phones = _n.extract_phones
+    extract_contacts = _n.extract_contacts
+    extract_social_links = _n.extract_social_links
+    detect_messaging_links = _n.detect_messaging_links
+    detect_gbp_links = _n.detect_gbp_links
+    detect_map_embed = _n.detect_map_embed
+    detect_video = _n.detect_video
+    detect_contact_form = _n.detect_contact_form
+    detect_email_capture = _n.detect_email_capture
+    detect_captcha = _n.detect_captcha
+    extract_phones_with_labels = _n.extract_phones_with_labels
     detect_cms = _n.detect_cms
+    detect_cms_detailed = _n.detect_cms_detailed
+    detect_cms_version = _n.detect_cms_version
+    detect_page_builder = _n.detect_page_builder
+    detect_wp_theme = _n.detect_wp_theme
+    detect_wp_plugins = _n.detect_wp_plugins
+    detect_ecommerce = _n.detect_ecommerce
+    detect_payment_providers = _n.detect_payment_providers
+    detect_chat_widget = _n.detect_chat_widget
+    detect_marketing_tools = _n.detect_marketing_tools
+    detect_review_widgets = _n.detect_review_widgets
+    detect_testimonials = _n.detect_testimonials
+    detect_hiring_signals = _n.detect_hiring_signals
+    detect_pricing = _n.detect_pricing
+    detect_blog = _n.detect_blog
+    detect_languages = _n.detect_languages
+    detect_app_links = _n.detect_app_links
+    register_cms_signature = _n.register_cms_signature
+    load_cms_signatures = _n.load_cms_signatures
+    list_cms_signatures = _n.list_cms_signatures
+    reset_cms_signatures = _n.reset_cms_signatures
     detect_tracking = _n.detect_tracking
+    extract_tracking_ids = _n.extract_tracking_ids
     detect_booking_system = _n.detect_booking_system
+    detect_booking_system_detailed = _n.detect_booking_system_detailed
+    register_booking_signatures = _n.register_booking_signatures
     detect_frameworks = _n.detect_frameworks
+    detect_outdated_libraries = _n.detect_outdated_libraries
+    register_library_latest = _n.register_library_latest
+    detect_legacy_tech = _n.detect_legacy_tech
     detect_responsive = _n.detect_responsive
+    detect_responsive_detailed = _n.detect_responsive_detailed
+    detect_amp = _n.detect_amp
+    detect_mdot_reference = _n.detect_mdot_reference
+    detect_pwa = _n.detect_pwa
+    detect_parked_domain = _n.detect_parked_domain
+    detect_placeholder_page = _n.detect_placeholder_page
+    detect_soft_404 = _n.detect_soft_404
+    extract_address = _n.extract_address
+    detect_directory_page = _n.detect_directory_page
+    extract_headings = _n.extract_headings
+    extract_visible_text = _n.extract_visible_text
+    visible_word_count = _n.visible_word_count
+    analyze_content_depth = _n.analyze_content_depth
+    detect_content_language = _n.detect_content_language
+    extract_copyright_year = _n.extract_copyright_year
+    is_copyright_stale = _n.is_copyright_stale
+    extract_meta_seo = _n.extract_meta_seo
+    seo_audit = _n.seo_audit
+    analyze_images = _n.analyze_images
     analyze_tech_stack = _n.analyze_tech_stack
+    detect_mixed_content = _n.detect_mixed_content
+    analyze_page_weight = _n.analyze_page_weight
+    detect_render_blocking = _n.detect_render_blocking
+    analyze_tech_stack_batch = _n.analyze_tech_stack_batch
 
     calculate_fit_score = _n.calculate_fit_score
     calculate_opportunity_score = _n.calculate_opportunity_score
     score_prospects_batch = _n.score_prospects_batch
 
+    dedupe_prospects = _n.dedupe_prospects
+
     fast_cache_key = _n.fast_cache_key
     haversine_distance = _n.haversine_distance
     batch_haversine = _n.batch_haversine
+    infer_country = _n.infer_country
+    extract_geo = _n.extract_geo
 
     serialize_prospects_csv = _n.serialize_prospects_csv
     serialize_prospects_json = _n.serialize_prospects_json
 
     extract_html_metadata = _n.extract_html_metadata
+    extract_local_business_schema = _n.extract_local_business_schema
+    extract_aggregate_rating = _n.extract_aggregate_rating
+    detect_schema_types = _n.detect_schema_types
+    extract_og_metadata = _n.extract_og_metadata
+    best_business_name = _n.best_business_name
+    extract_opening_hours = _n.extract_opening_hours
 
     AVAILABLE = True
     _logger.info("Rust native acceleration loaded successfully")
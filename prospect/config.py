@@ -268,20 +268,33 @@ CMS_SIGNATURES = {
     "Shopify": ["cdn.shopify.com", "myshopify.com", "shopify"],
     "Webflow": ["webflow.com", "assets-global.website-files", "webflow.io"],
     "Weebly": ["weebly.com", "weeblycloud.com"],
-    "GoDaddy Website Builder": ["godaddy.com", "secureserver.net", "godaddysites"],
+    "GoDaddy Website Builder": [
+        "img1.wsimg.com/isteam", "img1.wsimg.com/blobby", "secureserver.net", "godaddysites",
+    ],
     "Joomla": ["joomla", "/components/com_"],
     "Drupal": ["drupal", "/sites/default/"],
+    "Duda": ["multiscreensite.com", "cdn-website.com", "dudamobile.com"],
+    "Yola": ["yolacdn.net", "yolasite.com", "yola.com/"],
+    "Kajabi": ["kajabi-cdn.com", "kajabi-storefronts", "mykajabi.com"],
+    "Framer": ["framerusercontent.com", "framer.app", "data-framer-"],
+    "Carrd": ["carrd.co", "carrd"],
+    "Hostinger Website Builder": ["zyrosite.com", "zyro.com", "hostinger"],
+    "Square Online": ["square.site", "squarecdn.com", "squareup.com/online"],
 }
 
 # Tracking signatures for detection
 TRACKING_SIGNATURES = {
-    "google_analytics": [
-        "google-analytics.com",
-        "gtag(",
-        "ga(",
-        "G-",
-        "UA-",
-        "googletagmanager.com",
+    "google_tag_manager": [
+        "googletagmanager.com/gtm.js",
+        "googletagmanager.com/ns.html",
+    ],
+    "ga4": [],
+    "universal_analytics": [
+        "google-analytics.com/analytics.js",
+        "google-analytics.com/ga.js",
+        "ga('create'",
+        'ga("create"',
+        "_gaq.push",
     ],
     "facebook_pixel": [
         "facebook.com/tr",
@@ -291,9 +304,23 @@ TRACKING_SIGNATURES = {
     "google_ads": [
         "googleadservices.com",
         "googlesyndication.com",
-        "AW-",
         "google_conversion",
     ],
+    "hotjar": ["static.hotjar.com", "script.hotjar.com", "_hjSettings"],
+    "microsoft_clarity": ["clarity.ms/tag"],
+    "tiktok_pixel": ["analytics.tiktok.com", "ttq.load("],
+    "linkedin_insight": ["snap.licdn.com", "_linkedin_partner_id", "px.ads.linkedin.com"],
+    "pinterest_tag": ["s.pinimg.com/ct/core.js", "ct.pinterest.com", "pintrk("],
+    "snapchat_pixel": ["sc-static.net/scevent", "tr.snapchat.com", "snaptr("],
+}
+
+# Tracking IDs, matched case-sensitively on the raw HTML so "g-recaptcha"
+# or a "ua-" class never counts
+TRACKING_ID_PATTERNS = {
+    "google_tag_manager": r"""(?:["']|id=)GTM-[A-Z0-9]{4,9}\b""",
+    "ga4": r"""(?:["']|id=)G-[A-Z0-9]{6,12}\b""",
+    "universal_analytics": r"""(?:["']|id=)UA-[0-9]{4,10}-[0-9]{1,4}\b""",
+    "google_ads": r"""(?:["']|id=)AW-[0-9]{6,12}\b""",
 }
 
 # Booking system signatures
@@ -312,6 +339,17 @@ BOOKING_SIGNATURES = [
     "appointlet.com",
     "simplybook.me",
     "timify.com",
+    "squareup.com/appointments",
+    "meetings.hubspot.com",
+    "gettimely.com",
+    "healthengine.com.au",
+    "hotdoc.com.au",
+    "cliniko.com",
+    "mindbodyonline.com",
+    "healcode",
+    "book.servicem8.com",
+    "servicem8.com/book",
+    "housecallpro.com",
 ]
 
 # Australian phone patterns
@@ -1,7 +1,9 @@
 """Contact information extraction (emails, phones)."""
 
+import html as html_lib
 import re
 from typing import List
+from urllib.parse import unquote, urlparse
 
 from .. import _native
 from ..config import PHONE_PATTERNS, EMAIL_PATTERN, SPAM_EMAIL_PATTERNS, SPAM_EMAIL_DOMAINS
@@ -116,18 +118,25 @@ def extract_emails(html: str) -> List[str]:
     return valid_emails[:5]
 
 
-def extract_phones(html: str) -> List[str]:
+def extract_phones(
+    html: str, include_whatsapp: bool = False, with_source: bool = False
+) -> List:
     """
     Extract Australian phone numbers from HTML content.
 
     Args:
         html: Raw HTML content
+        include_whatsapp: Also include numbers found only in WhatsApp
+            click-to-chat links (wa.me, api.whatsapp.com, whatsapp://send).
+            Australian ones are formatted like the rest, others stay E.164.
+        with_source: Return dicts {"phone", "source": "text" | "whatsapp"}
+            instead of plain strings
 
     Returns:
         List of unique phone numbers found
     """
     if _native.extract_phones is not None:
-        return _native.extract_phones(html or "")
+        return _native.extract_phones(html or "", include_whatsapp, with_source)
 
     if not html:
         return []
@@ -142,9 +151,85 @@ def extract_phones(html: str) -> List[str]:
             normalized = normalize_phone(match)
             if normalized and normalized not in seen:
                 seen.add(normalized)
-                phones.append(normalized)
+                phones.append((normalized, "text"))
+
+    if include_whatsapp:
+        for href in re.findall(r"<a\b[^>]*?\bhref\s*=\s*[\"']([^\"']+)", html, re.I):
+            phone = _whatsapp_phone(html_lib.unescape(href))
+            if not phone:
+                continue
+            formatted = normalize_phone(phone) if phone.startswith("+61") else phone
+            if formatted not in seen:
+                seen.add(formatted)
+                phones.append((formatted, "whatsapp"))
+
+    if with_source:
+        return [{"phone": phone, "source": source} for phone, source in phones]
+    return [phone for phone, _ in phones]
+
+
+def _whatsapp_phone(href: str) -> str:
+    """The E.164 number a WhatsApp click-to-chat link points at, or ""."""
+    href = href.strip()
+    if href.startswith("//"):
+        href = "https:" + href
+    parsed = urlparse(href)
+    host = (parsed.hostname or "").lower()
+    if host.startswith("www."):
+        host = host[4:]
+
+    if parsed.scheme.lower() == "whatsapp" and href.lower().startswith("whatsapp://send"):
+        raw = _query_param(parsed.query, "phone")
+    elif parsed.scheme.lower() not in ("http", "https"):
+        return ""
+    elif host == "wa.me":
+        segments = [s for s in parsed.path.split("/") if s]
+        raw = unquote(segments[0]) if segments else ""
+    elif host in ("api.whatsapp.com", "web.whatsapp.com", "whatsapp.com"):
+        raw = _query_param(parsed.query, "phone")
+    else:
+        return ""
+    return _phone_e164(raw)
 
-    return phones
+
+def _query_param(query: str, name: str) -> str:
+    for pair in query.split("&"):
+        key, sep, value = pair.partition("=")
+        if sep and key.lower() == name:
+            return unquote(value)
+    return ""
+
+
+def _phone_e164(raw: str) -> str:
+    """
+    E.164 for a click-to-chat number: a leading 0 is Australian, 00 is an
+    international prefix. "" for placeholders such as wa.me/0000000000.
+    """
+    raw = raw.strip()
+    digits = "".join(c for c in raw if c in "0123456789")
+    if digits in ("0123456789", "1234567890"):
+        return ""
+    if raw.startswith("+"):
+        international = digits
+    elif digits.startswith("00"):
+        international = digits[2:]
+    elif digits.startswith("0"):
+        international = "61" + digits[1:]
+    else:
+        international = digits
+    if not 8 <= len(international) <= 15:
+        return ""
+
+    national = international
+    if international.startswith("61"):
+        national = international[2:]
+        if national.startswith("0"):
+            national = national[1:]
+        if len(national) != 9:
+            return ""
+    if not national or national == national[0] * len(national):
+        return ""
+    return "+" + international
 
 
 def normalize_phone(phone: str) -> str:
@@ -20,6 +20,10 @@ from .. import _native
 
 logger = logging.getLogger(__name__)
 
+# detect_soft_404 confidence at which a 200 response is treated as an error
+# page and its tech signals are not recorded
+SOFT_404_SKIP_CONFIDENCE = 0.8
+
 
 class WebsiteCrawler:
     """Crawls websites to extract marketing signals."""
@@ -167,6 +171,15 @@ class WebsiteCrawler:
         signals.reachable = True
         signals.load_time_ms = result.load_time_ms
 
+        if _native.detect_soft_404 is not None:
+            try:
+                soft_404 = _native.detect_soft_404(result.html, result.final_url or url)
+                if soft_404["confidence"] >= SOFT_404_SKIP_CONFIDENCE:
+                    logger.debug("Skipping soft 404 at %s: %s", url, soft_404["signals"])
+                    return signals
+            except Exception as e:
+                logger.debug("Failed to check soft 404 for %s: %s", url, e)
+
         # Parse HTML
         try:
             soup = BeautifulSoup(result.html, "lxml")
@@ -203,6 +216,18 @@ class WebsiteCrawler:
         except Exception as e:
             logger.debug("Failed to detect booking system for %s: %s", url, e)
 
+        if _native.detect_parked_domain is not None:
+            try:
+                signals.is_parked = _native.detect_parked_domain(result.html)["parked"]
+            except Exception as e:
+                logger.debug("Failed to detect parked domain for %s: %s", url, e)
+
+        if _native.detect_placeholder_page is not None:
+            try:
+                signals.is_placeholder = _native.detect_placeholder_page(result.html)["is_placeholder"]
+            except Exception as e:
+                logger.debug("Failed to detect placeholder page for %s: %s", url, e)
+
         # Extract metadata + social links (native Rust or BeautifulSoup fallback)
         try:
             if _native.extract_html_metadata is not None:
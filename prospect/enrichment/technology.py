@@ -1,9 +1,15 @@
 """Technology detection (CMS, tracking, booking systems)."""
 
+import re
 from typing import Optional, Dict
 
 from .. import _native
-from ..config import CMS_SIGNATURES, TRACKING_SIGNATURES, BOOKING_SIGNATURES
+from ..config import (
+    CMS_SIGNATURES,
+    TRACKING_SIGNATURES,
+    TRACKING_ID_PATTERNS,
+    BOOKING_SIGNATURES,
+)
 
 
 def detect_cms(html: str) -> Optional[str]:
@@ -45,11 +51,8 @@ def detect_tracking(html: str) -> Dict[str, bool]:
     if _native.detect_tracking is not None:
         return _native.detect_tracking(html or "")
 
-    result = {
-        "google_analytics": False,
-        "facebook_pixel": False,
-        "google_ads": False,
-    }
+    result = {tracker: False for tracker in TRACKING_SIGNATURES}
+    result["google_analytics"] = False
 
     if not html:
         return result
@@ -62,6 +65,11 @@ def detect_tracking(html: str) -> Dict[str, bool]:
                 result[tracker] = True
                 break
 
+    for tracker, pattern in TRACKING_ID_PATTERNS.items():
+        if re.search(pattern, html):
+            result[tracker] = True
+
+    result["google_analytics"] = result["ga4"] or result["universal_analytics"]
     return result
 
 
@@ -90,22 +98,24 @@ def detect_booking_system(html: str) -> bool:
     return False
 
 
-def analyze_tech_stack(html: str) -> dict:
+def analyze_tech_stack(html: str, url: Optional[str] = None) -> dict:
     """
     Perform comprehensive tech stack analysis.
 
     Args:
         html: Raw HTML content
+        url: Final URL after redirects, if known
 
     Returns:
-        Dictionary with all detected technologies
+        Dictionary with all detected technologies. has_ssl is None when
+        url isn't given.
     """
     result = {
         "cms": detect_cms(html),
         "tracking": detect_tracking(html),
         "has_booking": detect_booking_system(html),
         "frameworks": detect_frameworks(html),
-        "has_ssl": False,  # Would need to check URL
+        "has_ssl": url.lower().startswith("https://") if url else None,
         "has_responsive": detect_responsive(html),
     }
 
@@ -130,22 +140,68 @@ def detect_frameworks(html: str) -> list[str]:
     if not html:
         return frameworks
 
-    html_lower = html.lower()
-
+    # Only asset URLs, inline scripts and attribute names count; prose like
+    # "react to our new dishes" must not
+    urls = "\n".join(
+        m.group(1).lower()
+        for m in re.finditer(
+            r"""<(?:script|link)\b[^>]*?\s(?:src|href)\s*=\s*["']?([^"'\s>]+)""", html, re.I
+        )
+    )
+    code = "\n".join(
+        m.group(1).lower()
+        for m in re.finditer(r"<script\b[^>]*>(.*?)</script>", html, re.I | re.S)
+    )
+    attributes = set()
+    for tag in re.finditer(r"<[a-zA-Z][a-zA-Z0-9-]*(\s[^>]*)>", html):
+        for attr in re.finditer(
+            r"""([^\s"'=<>/]+)(?:\s*=\s*(?:"[^"]*"|'[^']*'|[^\s"'>]+))?""", tag.group(1)
+        ):
+            attributes.add(attr.group(1).lower())
+
+    # name: (url patterns, inline script patterns, attribute names; a
+    # trailing "-" matches as a prefix)
     framework_signatures = {
-        "React": ["react", "reactdom", "__react"],
-        "Vue.js": ["vue.js", "vuejs", "__vue__"],
-        "Angular": ["ng-app", "ng-controller", "angular"],
-        "jQuery": ["jquery", "$(document)", "$.ajax"],
-        "Bootstrap": ["bootstrap.min", "bootstrap.css"],
-        "Tailwind": ["tailwindcss", "tailwind.css"],
+        "React": (
+            ["react.production", "react.development", "react-dom", "react.min.js", "/react@"],
+            ["reactdom.", "react.createelement", "__react_devtools_global_hook__", "_reactrootcontainer"],
+            ["data-reactroot", "data-reactid"],
+        ),
+        "Vue.js": (
+            ["vue.js", "vue.min.js", "vue.global", "vue.runtime", "/vue@"],
+            ["__vue_devtools_global_hook__", "new vue(", "vue.createapp("],
+            ["data-v-", "v-cloak"],
+        ),
+        "Angular": (
+            ["angular.js", "angular.min.js", "/angular@", "@angular/"],
+            ["angular.module(", "platformbrowserdynamic", "ng.probe"],
+            ["ng-app", "data-ng-app", "ng-controller", "ng-version"],
+        ),
+        "jQuery": (["jquery"], ["jquery(", "$(document)", "$.ajax"], []),
+        "Bootstrap": (["bootstrap.min", "bootstrap.css", "bootstrap.bundle", "/bootstrap@"], [], []),
+        "Tailwind": (["tailwindcss", "tailwind.css", "tailwind.min.css"], ["tailwind.config"], []),
+        "Svelte": (
+            ["/_app/immutable/", "svelte"],
+            ["__sveltekit_"],
+            ["data-svelte-h", "data-sveltekit-"],
+        ),
+        "Next.js": (["/_next/static/"], ["self.__next_f", "__next_data__"], []),
+        "Nuxt": (["/_nuxt/"], ["window.__nuxt__", "__nuxt__"], ["data-n-head"]),
+        "Alpine.js": (["alpinejs", "alpine.min.js"], [], ["x-data", "x-init", "x-cloak"]),
     }
 
-    for framework, signatures in framework_signatures.items():
-        for signature in signatures:
-            if signature in html_lower:
-                frameworks.append(framework)
-                break
+    def has_attribute(sig: str) -> bool:
+        if sig.endswith("-"):
+            return any(a.startswith(sig) for a in attributes)
+        return sig in attributes
+
+    for framework, (url_sigs, code_sigs, attr_sigs) in framework_signatures.items():
+        if (
+            any(sig in urls for sig in url_sigs)
+            or any(sig in code for sig in code_sigs)
+            or any(has_attribute(sig) for sig in attr_sigs)
+        ):
+            frameworks.append(framework)
 
     return frameworks
 
@@ -166,19 +222,22 @@ def detect_responsive(html: str) -> bool:
     if not html:
         return False
 
-    html_lower = html.lower()
+    # A device-width viewport meta is the real test; "mobile" in a link to
+    # an m. site is not
+    for tag in re.finditer(r"""<meta\b[^>]*\bname\s*=\s*["']?viewport\b[^>]*>""", html, re.I | re.S):
+        content = re.search(r"""\bcontent\s*=\s*["']([^"']*)["']""", tag.group(0), re.I)
+        if content and "width=device-width" in re.sub(r"\s", "", content.group(1).lower()):
+            return True
+
+    for style in re.finditer(r"<style\b[^>]*>(.*?)</style>", html, re.I | re.S):
+        if re.search(r"@media[^{]*\((?:max|min)-(?:device-)?width\s*:", style.group(1), re.I):
+            return True
+    if re.search(r"""<link\b[^>]*\bmedia\s*=\s*["'][^"']*(?:max|min)-(?:device-)?width""", html, re.I):
+        return True
 
-    responsive_indicators = [
-        'viewport',
-        'media=',
-        '@media',
-        'responsive',
-        'mobile',
-        'bootstrap',
-        'tailwind',
-    ]
-
-    return any(indicator in html_lower for indicator in responsive_indicators)
+    return "tailwindcss" in html.lower() or bool(
+        re.search(r"bootstrap[@/\-]v?([3-9]|[1-9][0-9])\.", html, re.I)
+    )
 
 
 def get_cms_quality_tier(cms: Optional[str]) -> str:
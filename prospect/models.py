@@ -75,6 +75,8 @@ class WebsiteSignals:
     has_facebook_pixel: Optional[bool] = None
     has_google_ads: Optional[bool] = None
     has_booking_system: Optional[bool] = None
+    is_parked: Optional[bool] = None
+    is_placeholder: Optional[bool] = None
     load_time_ms: Optional[int] = None
     title: Optional[str] = None
     meta_description: Optional[str] = None
@@ -190,6 +192,8 @@ class Prospect:
                 "has_facebook_pixel": self.signals.has_facebook_pixel,
                 "has_google_ads": self.signals.has_google_ads,
                 "has_booking_system": self.signals.has_booking_system,
+                "is_parked": self.signals.is_parked,
+                "is_placeholder": self.signals.is_placeholder,
                 "load_time_ms": self.signals.load_time_ms,
             }
 
@@ -38,6 +38,14 @@ def calculate_opportunity_score(
         # Can't analyse, assume moderate opportunity
         return 50
 
+    # Parked / for-sale domain is effectively no website
+    if signals.is_parked:
+        return 80
+
+    # Coming-soon, default server or suspended page is no website either
+    if signals.is_placeholder:
+        return 80
+
     # Missing Google Analytics (15 points) - only if confirmed absent
     if signals.has_google_analytics is False:
         score += config.no_analytics_weight
@@ -125,6 +133,24 @@ def get_opportunity_breakdown(prospect: Prospect) -> dict:
         breakdown["total"] = 50
         return breakdown
 
+    if signals.is_parked:
+        breakdown["opportunities"].append({
+            "factor": "Parked domain",
+            "points": 80,
+            "note": "Domain is parked or for sale - effectively no website",
+        })
+        breakdown["total"] = 80
+        return breakdown
+
+    if signals.is_placeholder:
+        breakdown["opportunities"].append({
+            "factor": "Placeholder page",
+            "points": 80,
+            "note": "Site is a coming-soon, default server or suspended page - effectively no website",
+        })
+        breakdown["total"] = 80
+        return breakdown
+
     # Opportunities (positive points) - only if confirmed absent, not unknown
     if signals.has_google_analytics is False:
         breakdown["opportunities"].append({
@@ -265,7 +265,8 @@ def validate_email_domain(email: str, website_domain: str) -> Tuple[bool, str]:
         Tuple of (is_valid, reason)
     """
     if _native.validate_email_domain is not None:
-        return _native.validate_email_domain(email, website_domain)
+        result = _native.validate_email_domain(email, website_domain)
+        return result["valid"], result["message"]
 
     if not email or not website_domain:
         return True, "No email or domain"
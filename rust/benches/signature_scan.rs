@@ -0,0 +1,62 @@
+//! Signature detection on a ~1 MB page through the single-pass index,
+//! against the per-detector lowercasing and substring search it replaced:
+//! first the signature-table detectors alone, then the whole tech-stack
+//! pass.
+//!
+//!     cargo bench --features bench
+
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+use std::fs;
+use std::path::Path;
+
+const PAGE_BYTES: usize = 1_000_000;
+
+/// The saved pages, repeated until the page passes 1 MB.
+fn large_page() -> String {
+    let dir = Path::new(env!("CARGO_MANIFEST_DIR")).join("fixtures/pages");
+    let mut paths: Vec<_> = fs::read_dir(dir)
+        .unwrap()
+        .map(|entry| entry.unwrap().path())
+        .filter(|path| path.extension().is_some_and(|ext| ext == "html"))
+        .collect();
+    paths.sort();
+    let pages: Vec<String> = paths
+        .iter()
+        .map(|path| fs::read_to_string(path).unwrap())
+        .collect();
+
+    let mut html = String::with_capacity(PAGE_BYTES + 16_384);
+    while html.len() < PAGE_BYTES {
+        for page in &pages {
+            html.push_str(page);
+        }
+    }
+    html
+}
+
+fn signature_scan(c: &mut Criterion) {
+    let html = large_page();
+
+    let mut group = c.benchmark_group("signature_detectors_1mb");
+    group.sample_size(20);
+    group.bench_function("indexed", |b| {
+        b.iter(|| _leadswarm_native::bench_signature_detectors(black_box(&html), true))
+    });
+    group.bench_function("substring", |b| {
+        b.iter(|| _leadswarm_native::bench_signature_detectors(black_box(&html), false))
+    });
+    group.finish();
+
+    let mut group = c.benchmark_group("tech_stack_1mb");
+    group.sample_size(20);
+    group.bench_function("indexed", |b| {
+        b.iter(|| _leadswarm_native::bench_tech_stack(black_box(&html), true))
+    });
+    group.bench_function("substring", |b| {
+        b.iter(|| _leadswarm_native::bench_tech_stack(black_box(&html), false))
+    });
+    group.finish();
+}
+
+criterion_group!(benches, signature_scan);
+criterion_main!(benches);
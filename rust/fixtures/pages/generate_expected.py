@@ -0,0 +1,44 @@
+"""Record detect_cms, detect_tracking and analyze_tech_stack outputs for
+the saved pages in this directory into expected.json.
+
+expected.json holds the outputs of the per-detector substring scans that
+preceded the single-pass signature index, so run this against a build of
+that code, not the current tree:
+
+    python generate_expected.py /path/to/old/build
+
+has_ssl is left out: it describes the fetch, not the page.
+"""
+
+import glob
+import json
+import os
+import sys
+
+sys.path.insert(0, sys.argv[1])
+import _leadswarm_native as native  # noqa: E402
+
+# The bare "godaddy.com" pattern the old table shipped with matched
+# registrar and parking links; record the old scan with today's patterns
+native.register_cms_signature(
+    "GoDaddy Website Builder",
+    ["img1.wsimg.com/isteam", "img1.wsimg.com/blobby", "secureserver.net", "godaddysites"],
+    True,
+)
+
+here = os.path.dirname(os.path.abspath(__file__))
+expected = {}
+for path in sorted(glob.glob(os.path.join(here, "*.html"))):
+    with open(path, encoding="utf-8") as f:
+        html = f.read()
+    stack = native.analyze_tech_stack(html)
+    stack.pop("has_ssl")
+    expected[os.path.basename(path)] = {
+        "detect_cms": native.detect_cms(html),
+        "detect_tracking": native.detect_tracking(html),
+        "analyze_tech_stack": stack,
+    }
+
+with open(os.path.join(here, "expected.json"), "w", encoding="utf-8") as f:
+    json.dump(expected, f, indent=2, ensure_ascii=False, sort_keys=True)
+    f.write("\n")
@@ -0,0 +1,264 @@
+use pyo3::prelude::*;
+use rayon::prelude::*;
+use std::collections::HashMap;
+
+use crate::scoring::{extract_opt_i64, extract_opt_string};
+use crate::text::{normalize_domain, normalize_name, tokens_of_normalized, tokens_similarity};
+
+// ---------------------------------------------------------------------------
+// Cross-source prospect deduplication  (prospect/dedup.py)
+// ---------------------------------------------------------------------------
+
+// How many of a record's least frequent tokens are used to find candidates
+const RAREST_TOKENS: usize = 2;
+
+struct DedupRecord {
+    domain: Option<String>,
+    name: String,
+    suburb: Option<String>,
+    postcode: Option<String>,
+}
+
+fn extract_record(py: Python<'_>, prospect: &HashMap<String, PyObject>) -> DedupRecord {
+    let domain = extract_opt_string(py, prospect, "website")
+        .and_then(|w| normalize_domain(&w, false, false, true));
+    let name = extract_opt_string(py, prospect, "name")
+        .map(|n| normalize_name(&n))
+        .unwrap_or_default();
+    let suburb = extract_opt_string(py, prospect, "suburb")
+        .map(|s| s.trim().to_lowercase())
+        .filter(|s| !s.is_empty());
+    let postcode = extract_opt_string(py, prospect, "postcode")
+        .or_else(|| extract_opt_i64(py, prospect, "postcode").map(|p| format!("{:04}", p)))
+        .map(|p| p.trim().to_string())
+        .filter(|p| !p.is_empty());
+
+    DedupRecord {
+        domain,
+        name,
+        suburb,
+        postcode,
+    }
+}
+
+/// Two records can only be the same business if their locations agree
+/// wherever both sides have one.
+fn locations_compatible(a: &DedupRecord, b: &DedupRecord) -> bool {
+    if let (Some(pa), Some(pb)) = (&a.postcode, &b.postcode) {
+        return pa == pb;
+    }
+    if let (Some(sa), Some(sb)) = (&a.suburb, &b.suburb) {
+        return sa == sb;
+    }
+    true
+}
+
+fn find(parent: &mut [usize], i: usize) -> usize {
+    let mut root = i;
+    while parent[root] != root {
+        root = parent[root];
+    }
+    let mut node = i;
+    while parent[node] != root {
+        let next = parent[node];
+        parent[node] = root;
+        node = next;
+    }
+    root
+}
+
+/// Union keeping the smaller index as root so grouping is order-independent.
+fn union(parent: &mut [usize], a: usize, b: usize) {
+    let ra = find(parent, a);
+    let rb = find(parent, b);
+    if ra != rb {
+        let (lo, hi) = if ra < rb { (ra, rb) } else { (rb, ra) };
+        parent[hi] = lo;
+    }
+}
+
+fn group_records(records: &[DedupRecord], name_threshold: f64) -> Vec<Vec<usize>> {
+    let mut parent: Vec<usize> = (0..records.len()).collect();
+
+    // Pass 1: identical normalized domains
+    let mut by_domain: HashMap<&str, usize> = HashMap::new();
+    for (i, record) in records.iter().enumerate() {
+        if let Some(domain) = &record.domain {
+            match by_domain.get(domain.as_str()) {
+                Some(&first) => union(&mut parent, first, i),
+                None => {
+                    by_domain.insert(domain, i);
+                }
+            }
+        }
+    }
+
+    // Pass 2: fuzzy names. Candidates must share one of a record's two rarest
+    // tokens (common words like "plumbing" would otherwise pair everything);
+    // records with two different domains are never merged on name alone.
+    let tokens: Vec<Vec<String>> = records
+        .iter()
+        .map(|r| tokens_of_normalized(&r.name, true))
+        .collect();
+    let mut postings: HashMap<&str, Vec<usize>> = HashMap::new();
+    for (i, toks) in tokens.iter().enumerate() {
+        for token in toks {
+            postings.entry(token.as_str()).or_default().push(i);
+        }
+    }
+
+    let tokens = &tokens;
+    let pairs: Vec<(usize, usize)> = (0..records.len())
+        .into_par_iter()
+        .flat_map_iter(|i| {
+            let a = &records[i];
+            let mut rarest: Vec<&str> = tokens[i].iter().map(String::as_str).collect();
+            rarest.sort_by_key(|t| (postings[t].len(), *t));
+            let mut candidates: Vec<usize> = rarest
+                .iter()
+                .take(RAREST_TOKENS)
+                .flat_map(|t| postings[t].iter().copied())
+                .filter(|&j| j > i)
+                .collect();
+            candidates.sort_unstable();
+            candidates.dedup();
+            candidates
+                .into_iter()
+                .filter(move |&j| {
+                    let b = &records[j];
+                    !(a.domain.is_some() && b.domain.is_some())
+                        && locations_compatible(a, b)
+                        && !a.name.is_empty()
+                        && (a.name == b.name
+                            || tokens_similarity(&tokens[i], &tokens[j]) >= name_threshold)
+                })
+                .map(move |j| (i, j))
+        })
+        .collect();
+
+    // Apply in index order, refusing merges that would chain two different
+    // domains together through a domainless record.
+    let mut group_domain: Vec<Option<&str>> = records.iter().map(|r| r.domain.as_deref()).collect();
+    for (i, j) in pairs {
+        let ri = find(&mut parent, i);
+        let rj = find(&mut parent, j);
+        if ri == rj {
+            continue;
+        }
+        let domain = match (group_domain[ri], group_domain[rj]) {
+            (Some(a), Some(b)) if a != b => continue,
+            (a, b) => a.or(b),
+        };
+        union(&mut parent, ri, rj);
+        group_domain[ri.min(rj)] = domain;
+    }
+
+    let mut groups: Vec<Vec<usize>> = Vec::new();
+    let mut group_of_root: HashMap<usize, usize> = HashMap::new();
+    for i in 0..records.len() {
+        let root = find(&mut parent, i);
+        match group_of_root.get(&root) {
+            Some(&g) => groups[g].push(i),
+            None => {
+                group_of_root.insert(root, groups.len());
+                groups.push(vec![i]);
+            }
+        }
+    }
+    groups
+}
+
+/// Group prospects that refer to the same business.
+///
+/// Records sharing a normalized website domain are grouped first; the rest
+/// are matched on normalized name (similarity >= `name_threshold`) with
+/// optional "suburb"/"postcode" fields required to agree. Returns groups of
+/// original indices, ordered by their first index, singletons included.
+#[pyfunction]
+#[pyo3(signature = (prospects, name_threshold=0.9))]
+pub fn dedupe_prospects(
+    py: Python<'_>,
+    prospects: Vec<HashMap<String, PyObject>>,
+    name_threshold: f64,
+) -> Vec<Vec<usize>> {
+    let records: Vec<DedupRecord> = prospects.iter().map(|p| extract_record(py, p)).collect();
+    py.allow_threads(|| group_records(&records, name_threshold))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn record(website: Option<&str>, name: &str, suburb: Option<&str>) -> DedupRecord {
+        DedupRecord {
+            domain: website.and_then(|w| normalize_domain(w, false, false, true)),
+            name: normalize_name(name),
+            suburb: suburb.map(str::to_string),
+            postcode: None,
+        }
+    }
+
+    #[test]
+    fn groups_by_domain_then_name() {
+        let records = vec![
+            record(
+                Some("https://www.acmeplumbing.com.au/"),
+                "Acme Plumbing",
+                None,
+            ),
+            record(
+                Some("acmeplumbing.com.au/contact"),
+                "ACME Plumbing & Gas",
+                None,
+            ),
+            record(None, "Acme Plumbing Pty Ltd", Some("paddington")),
+            record(None, "Apex Electrical", Some("paddington")),
+            record(None, "Apex Electrical", Some("toowong")),
+            record(Some("apex.com.au"), "Apex Electrical", Some("paddington")),
+        ];
+        assert_eq!(
+            group_records(&records, 0.9),
+            vec![vec![0, 1, 2], vec![3, 5], vec![4]]
+        );
+    }
+
+    #[test]
+    fn different_domains_are_never_merged_on_name() {
+        let records = vec![
+            record(Some("acme-north.com.au"), "Acme Plumbing", None),
+            record(None, "Acme Plumbing", None),
+            record(Some("acme-south.com.au"), "Acme Plumbing", None),
+        ];
+        assert_eq!(group_records(&records, 0.9), vec![vec![0, 1], vec![2]]);
+    }
+
+    #[test]
+    fn grouping_is_deterministic() {
+        let trades = ["Plumbing", "Electrical", "Roofing", "Painting", "Fencing"];
+        let owners = [
+            "Acme", "Apex", "Bayside", "Coastal", "Summit", "Harbour", "Metro",
+        ];
+        let records: Vec<DedupRecord> = (0..600)
+            .map(|i| {
+                let name = format!(
+                    "{} {} {}",
+                    owners[i % owners.len()],
+                    trades[(i / 7) % trades.len()],
+                    if i % 3 == 0 { "Pty Ltd" } else { "" }
+                );
+                let website = format!("{}{}.com.au", owners[i % owners.len()], i % 11);
+                record(
+                    (i % 4 == 0).then_some(website.as_str()),
+                    &name,
+                    Some(["paddington", "toowong", "ashgrove"][i % 3]),
+                )
+            })
+            .collect();
+
+        let first = group_records(&records, 0.9);
+        assert_eq!(first.iter().map(Vec::len).sum::<usize>(), records.len());
+        for _ in 0..3 {
+            assert_eq!(group_records(&records, 0.9), first);
+        }
+    }
+}
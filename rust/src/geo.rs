@@ -1,6 +1,13 @@
 use pyo3::prelude::*;
+use pyo3::types::PyDict;
+use regex::Regex;
+use std::collections::HashMap;
+use std::sync::LazyLock;
 use xxhash_rust::xxh3::xxh3_64;
 
+use crate::html::{decode_html_entities, phones_of};
+use crate::metadata::schema_geo_of;
+
 /// Generate a cache key from query + location using xxHash3.
 /// Lowercases both inputs, joins with "|", returns hex digest.
 #[pyfunction]
@@ -37,3 +44,545 @@ pub fn batch_haversine(base_lat: f64, base_lng: f64, points: Vec<(f64, f64)>) ->
         .map(|&(lat, lng)| haversine_distance(base_lat, base_lng, lat, lng))
         .collect()
 }
+
+// ---------------------------------------------------------------------------
+// Country inference
+// ---------------------------------------------------------------------------
+
+// Country-code TLDs we route on, keyed by the final label of the host
+static CCTLD_COUNTRIES: &[(&str, &str)] = &[
+    ("au", "AU"),
+    ("nz", "NZ"),
+    ("uk", "UK"),
+    ("ie", "IE"),
+    ("ca", "CA"),
+    ("us", "US"),
+    ("sg", "SG"),
+    ("za", "ZA"),
+];
+
+// (country, weight, signal, pattern). Structured evidence (postcodes, phone
+// prefixes, locale tags) outweighs stray mentions of a country name.
+static COUNTRY_HINTS: LazyLock<Vec<(&str, f64, &str, Regex)>> = LazyLock::new(|| {
+    vec![
+        ("AU", 3.0, "postcode:AU", Regex::new(r"\b(?:NSW|VIC|QLD|SA|WA|TAS|NT|ACT)[ ,]+\d{4}\b").unwrap()),
+        ("AU", 1.0, "state:AU", Regex::new(r"\b(?:New South Wales|Queensland|Tasmania|Northern Territory|Western Australia|South Australia|Australian Capital Territory)\b").unwrap()),
+        ("NZ", 3.0, "phone:NZ", Regex::new(r"\+64[ \-]?\(?\d").unwrap()),
+        ("UK", 3.0, "phone:UK", Regex::new(r"\+44[ \-]?\(?\d").unwrap()),
+        ("US", 3.0, "phone:US", Regex::new(r"\+1[ \-]?\(?\d{3}\)?[ \-]?\d{3}[ \-]?\d{4}\b").unwrap()),
+        ("UK", 2.0, "postcode:UK", Regex::new(r"\b[A-Z]{1,2}\d[A-Z\d]? \d[A-Z]{2}\b").unwrap()),
+        ("AU", 1.0, "currency:AU", Regex::new(r"\b(?:AUD|A\$)").unwrap()),
+        ("NZ", 1.0, "currency:NZ", Regex::new(r"\b(?:NZD|NZ\$)").unwrap()),
+        ("UK", 1.0, "currency:UK", Regex::new(r"£|\bGBP\b").unwrap()),
+        ("AU", 2.0, "locale:AU", Regex::new(r#"(?i)(?:lang|content)\s*=\s*["']en[-_]au["']"#).unwrap()),
+        ("NZ", 2.0, "locale:NZ", Regex::new(r#"(?i)(?:lang|content)\s*=\s*["']en[-_]nz["']"#).unwrap()),
+        ("UK", 2.0, "locale:UK", Regex::new(r#"(?i)(?:lang|content)\s*=\s*["']en[-_](?:gb|uk)["']"#).unwrap()),
+        ("US", 2.0, "locale:US", Regex::new(r#"(?i)(?:lang|content)\s*=\s*["']en[-_]us["']"#).unwrap()),
+        ("AU", 0.5, "mention:AU", Regex::new(r"\bAustralia\b").unwrap()),
+        ("NZ", 0.5, "mention:NZ", Regex::new(r"\bNew Zealand\b").unwrap()),
+        ("UK", 0.5, "mention:UK", Regex::new(r"\bUnited Kingdom\b").unwrap()),
+        // Bare "US" only next to country context, so "CONTACT US" doesn't count
+        ("US", 0.5, "mention:US", Regex::new(r"\b(?:United States|USA)\b|\bU\.S\.|\b(?:in|across|throughout) the US\b").unwrap()),
+    ]
+});
+
+// Minimum evidence before guessing a country from page content
+const MIN_COUNTRY_SCORE: f64 = 2.0;
+
+/// Host portion of a domain or URL, lowercased.
+fn host_of(domain: &str) -> String {
+    let lower = domain.trim().to_lowercase();
+    let rest = lower.split_once("://").map_or(lower.as_str(), |(_, r)| r);
+    rest.split(['/', '?', '#', ':'])
+        .next()
+        .unwrap_or("")
+        .trim_end_matches('.')
+        .to_string()
+}
+
+/// Guess the country a business operates in.
+///
+/// Country-code suffixes (.com.au, .co.nz, .co.uk) decide outright. For
+/// generic TLDs the HTML is scored for postcodes, phone prefixes, currency,
+/// locale tags and country mentions. Returns a dict with `country`
+/// (None when the evidence is too weak), `confidence` (0.0-1.0) and the
+/// `signals` that contributed.
+#[pyfunction]
+#[pyo3(signature = (domain, html=None))]
+pub fn infer_country(py: Python<'_>, domain: &str, html: Option<&str>) -> PyResult<PyObject> {
+    let guess = country_guess_of(domain, html);
+    let dict = PyDict::new(py);
+    dict.set_item("country", guess.country)?;
+    dict.set_item("confidence", guess.confidence)?;
+    dict.set_item("signals", guess.signals)?;
+    Ok(dict.into())
+}
+
+struct CountryGuess {
+    country: Option<&'static str>,
+    confidence: f64,
+    signals: Vec<String>,
+}
+
+fn country_guess_of(domain: &str, html: Option<&str>) -> CountryGuess {
+    let host = host_of(domain);
+    let tld = host.rsplit('.').next().unwrap_or("");
+
+    if let Some(&(_, country)) = CCTLD_COUNTRIES.iter().find(|(cc, _)| *cc == tld) {
+        return CountryGuess {
+            country: Some(country),
+            confidence: 0.95,
+            signals: vec![format!("tld:.{}", tld)],
+        };
+    }
+
+    let mut scores: HashMap<&'static str, f64> = HashMap::new();
+    let mut signals: Vec<String> = Vec::new();
+
+    if let Some(html) = html {
+        if !phones_of(html).is_empty() {
+            *scores.entry("AU").or_insert(0.0) += 3.0;
+            signals.push("phone:AU".to_string());
+        }
+        for (country, weight, signal, pattern) in COUNTRY_HINTS.iter() {
+            if pattern.is_match(html) {
+                *scores.entry(country).or_insert(0.0) += weight;
+                signals.push(signal.to_string());
+            }
+        }
+    }
+
+    let total: f64 = scores.values().sum();
+    let best = scores
+        .iter()
+        .max_by(|a, b| a.1.total_cmp(b.1).then_with(|| b.0.cmp(a.0)))
+        .map(|(country, score)| (*country, *score));
+
+    match best {
+        Some((country, score)) if score >= MIN_COUNTRY_SCORE => {
+            let confidence = (score / total) * (score / 6.0).min(1.0) * 0.9;
+            CountryGuess {
+                country: Some(country),
+                confidence: (confidence * 100.0).round() / 100.0,
+                signals,
+            }
+        }
+        _ => CountryGuess {
+            country: None,
+            confidence: 0.0,
+            signals,
+        },
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Coordinates from page markup
+// ---------------------------------------------------------------------------
+
+// A decimal degree with at least one decimal place, so "q=1,2" isn't read
+// as a location
+const DEGREES: &str = r"-?[0-9]{1,3}\.[0-9]+";
+
+/// Maps zoomed out further than this show a country, not a business.
+const MIN_MAP_ZOOM: f64 = 8.0;
+/// Viewport height in metres (the "!1d" of an embed's pb parameter, or an
+/// "@lat,lng,NNNm" link) beyond which a map is country-scale.
+const MAX_MAP_VIEWPORT_METRES: f64 = 250_000.0;
+
+static GEO_META_RE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(&format!(
+        r#"(?is)<meta\b[^>]*\bname\s*=\s*["']?(?:geo\.position|icbm)["']?[^>]*\bcontent\s*=\s*["']\s*({d})\s*[;,]\s*({d})"#,
+        d = DEGREES
+    ))
+    .unwrap()
+});
+
+static MAP_IFRAME_RE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r#"(?is)<iframe\b[^>]*?\bsrc\s*=\s*["']([^"']*google\.[^"']*maps[^"']*)["']"#)
+        .unwrap()
+});
+
+static MAP_LINK_RE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(
+        r#"(?i)\bhref\s*=\s*["']((?:https?:)?//(?:www\.)?(?:google\.[a-z.]+/maps|maps\.google\.[a-z.]+)[^"']*)["']"#,
+    )
+    .unwrap()
+});
+
+// Place pin in a maps URL: "!3d-33.8688!4d151.2093"
+static PIN_PARAM_RE: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(&format!(r"!3d({d})!4d({d})", d = DEGREES)).unwrap());
+
+// Viewport centre in an embed's pb parameter: "!1d3312.5!2d151.2!3d-33.86"
+static PB_CENTRE_RE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(&format!(r"(?:!1d([0-9.]+))?!2d({d})!3d({d})", d = DEGREES)).unwrap()
+});
+
+// "/@-33.8688,151.2093,15z" or ",500m"
+static AT_PATH_RE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(&format!(r"@({d}),({d})(?:,([0-9.]+)([zm]))?", d = DEGREES)).unwrap()
+});
+
+static COORD_PARAM_RE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(&format!(
+        r"[?&](?:ll|sll|q|center|daddr)=({d})(?:,|%2C)\s*({d})",
+        d = DEGREES
+    ))
+    .unwrap()
+});
+
+static ZOOM_PARAM_RE: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"[?&](?:z|zoom)=([0-9.]+)").unwrap());
+
+// Leaflet: map.setView([lat, lng], zoom)
+static SET_VIEW_RE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(&format!(
+        r"setView\(\s*\[\s*({d})\s*,\s*({d})\s*\]\s*(?:,\s*([0-9.]+))?",
+        d = DEGREES
+    ))
+    .unwrap()
+});
+
+// Leaflet: L.marker([lat, lng])
+static MARKER_RE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(&format!(
+        r"\.marker\(\s*\[\s*({d})\s*,\s*({d})\s*\]",
+        d = DEGREES
+    ))
+    .unwrap()
+});
+
+// Mapbox GL: center: [lng, lat] (longitude first), with zoom: N nearby
+static MAPBOX_CENTER_RE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(&format!(
+        r#"["']?center["']?\s*:\s*\[\s*({d})\s*,\s*({d})\s*\]"#,
+        d = DEGREES
+    ))
+    .unwrap()
+});
+
+static ZOOM_OPTION_RE: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r#"["']?zoom["']?\s*:\s*([0-9.]+)"#).unwrap());
+
+// Google Maps JS: {lat: -33.86, lng: 151.2}
+static LAT_LNG_OBJECT_RE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(&format!(
+        r#"\{{\s*["']?lat["']?\s*:\s*({d})\s*,\s*["']?lng["']?\s*:\s*({d})\s*\}}"#,
+        d = DEGREES
+    ))
+    .unwrap()
+});
+
+fn valid_coordinates(lat: f64, lng: f64) -> Option<(f64, f64)> {
+    // (0, 0) is an unset default, not a place in the Gulf of Guinea
+    let valid = (-90.0..=90.0).contains(&lat)
+        && (-180.0..=180.0).contains(&lng)
+        && (lat != 0.0 || lng != 0.0);
+    valid.then_some((lat, lng))
+}
+
+fn coordinates(lat: &str, lng: &str) -> Option<(f64, f64)> {
+    valid_coordinates(lat.parse().ok()?, lng.parse().ok()?)
+}
+
+fn too_coarse_zoom(zoom: Option<&str>) -> bool {
+    zoom.and_then(|z| z.parse::<f64>().ok())
+        .is_some_and(|z| z < MIN_MAP_ZOOM)
+}
+
+/// Coordinates a Google Maps URL points at: a place pin ("!3d..!4d.."),
+/// an "@lat,lng" path, an embed's pb centre, or an ll= / q= / center=
+/// parameter. None when there are none or the map is country-scale.
+fn maps_url_coordinates(url: &str) -> Option<(f64, f64)> {
+    let url = decode_html_entities(url);
+
+    if let Some(caps) = PIN_PARAM_RE.captures(&url) {
+        return coordinates(&caps[1], &caps[2]);
+    }
+    if let Some(caps) = AT_PATH_RE.captures(&url) {
+        let coarse = match caps.get(4).map(|u| u.as_str()) {
+            Some("z") => too_coarse_zoom(caps.get(3).map(|m| m.as_str())),
+            Some(_) => caps[3]
+                .parse::<f64>()
+                .is_ok_and(|m| m > MAX_MAP_VIEWPORT_METRES),
+            None => false,
+        };
+        return if coarse {
+            None
+        } else {
+            coordinates(&caps[1], &caps[2])
+        };
+    }
+    if let Some(caps) = PB_CENTRE_RE.captures(&url) {
+        let coarse = caps
+            .get(1)
+            .and_then(|m| m.as_str().parse::<f64>().ok())
+            .is_some_and(|m| m > MAX_MAP_VIEWPORT_METRES);
+        return if coarse {
+            None
+        } else {
+            coordinates(&caps[3], &caps[2])
+        };
+    }
+    let caps = COORD_PARAM_RE.captures(&url)?;
+    let zoom = ZOOM_PARAM_RE.captures(&url);
+    if too_coarse_zoom(zoom.as_ref().map(|z| z.get(1).unwrap().as_str())) {
+        return None;
+    }
+    coordinates(&caps[1], &caps[2])
+}
+
+/// Coordinates from inline map scripts: a Leaflet setView or marker, a
+/// Mapbox GL centre, or a Google Maps {lat, lng} literal.
+fn map_script_coordinates(html: &str) -> Option<(f64, f64)> {
+    for caps in SET_VIEW_RE.captures_iter(html) {
+        if !too_coarse_zoom(caps.get(3).map(|m| m.as_str())) {
+            if let Some(found) = coordinates(&caps[1], &caps[2]) {
+                return Some(found);
+            }
+        }
+    }
+    for caps in MAPBOX_CENTER_RE.captures_iter(html) {
+        let m = caps.get(0).unwrap();
+        let mut start = m.start().saturating_sub(300);
+        while !html.is_char_boundary(start) {
+            start += 1;
+        }
+        let mut end = (m.end() + 300).min(html.len());
+        while !html.is_char_boundary(end) {
+            end -= 1;
+        }
+        let zoom = ZOOM_OPTION_RE.captures(&html[start..end]);
+        if too_coarse_zoom(zoom.as_ref().map(|z| z.get(1).unwrap().as_str())) {
+            continue;
+        }
+        if let Some(found) = coordinates(&caps[2], &caps[1]) {
+            return Some(found);
+        }
+    }
+    MARKER_RE
+        .captures_iter(html)
+        .chain(LAT_LNG_OBJECT_RE.captures_iter(html))
+        .find_map(|caps| coordinates(&caps[1], &caps[2]))
+}
+
+/// The business's coordinates and where they came from, in order of trust.
+fn geo_of(html: &str) -> Option<(f64, f64, &'static str)> {
+    let tagged = |source| move |(lat, lng)| (lat, lng, source);
+    schema_geo_of(html)
+        .map(tagged("schema"))
+        .or_else(|| {
+            GEO_META_RE
+                .captures(html)
+                .and_then(|caps| coordinates(&caps[1], &caps[2]))
+                .map(tagged("meta"))
+        })
+        .or_else(|| {
+            MAP_IFRAME_RE
+                .captures_iter(html)
+                .find_map(|caps| maps_url_coordinates(&caps[1]))
+                .map(tagged("map_embed"))
+        })
+        .or_else(|| {
+            MAP_LINK_RE
+                .captures_iter(html)
+                .find_map(|caps| maps_url_coordinates(&caps[1]))
+                .map(tagged("map_link"))
+        })
+        .or_else(|| map_script_coordinates(html).map(tagged("map_script")))
+}
+
+/// Latitude and longitude of the business from the page, without a
+/// geocoding call.
+///
+/// Sources, most trusted first: schema.org geo (JSON-LD or microdata),
+/// geo.position / ICBM meta tags, Google Maps embed iframes (place pins,
+/// the pb parameter's centre, ll= / q= / center= parameters), links to
+/// Google Maps ("/@lat,lng,15z"), and inline Leaflet, Mapbox GL or Google
+/// Maps script configuration. Maps zoomed out to country scale (zoom below
+/// 8) are ignored, as are out-of-range coordinates and (0, 0).
+///
+/// Returns None when nothing usable is found, else a dict with keys
+/// "lat", "lng" (rounded to 6 decimal places) and "source": "schema",
+/// "meta", "map_embed", "map_link" or "map_script".
+#[pyfunction]
+pub fn extract_geo(py: Python<'_>, html: &str) -> PyResult<Option<PyObject>> {
+    let Some((lat, lng, source)) = py.allow_threads(|| geo_of(html)) else {
+        return Ok(None);
+    };
+    let round = |degrees: f64| (degrees * 1e6).round() / 1e6;
+    let dict = PyDict::new(py);
+    dict.set_item("lat", round(lat))?;
+    dict.set_item("lng", round(lng))?;
+    dict.set_item("source", source)?;
+    Ok(Some(dict.into()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Country inference
+
+    #[test]
+    fn country_code_suffixes_decide_outright() {
+        for (domain, country) in [
+            ("acmeplumbing.com.au", "AU"),
+            ("https://www.acme.net.au/contact", "AU"),
+            ("acme.co.nz", "NZ"),
+            ("acme.co.uk", "UK"),
+        ] {
+            let guess = country_guess_of(domain, Some("<p>Serving customers in the US</p>"));
+            assert_eq!(guess.country, Some(country), "{}", domain);
+            assert_eq!(guess.signals.len(), 1);
+        }
+    }
+
+    #[test]
+    fn au_business_on_dot_com_mentioning_us() {
+        let html = concat!(
+            "<nav><a href=\"/contact\">CONTACT US</a> | ABOUT US</nav>",
+            "<p>US customers welcome!</p>",
+            "<p>12 Smith St, Paddington QLD 4064. Call 07 3123 4567</p>",
+        );
+        let guess = country_guess_of("acmeplumbing.com", Some(html));
+        assert_eq!(guess.country, Some("AU"));
+        assert!(!guess.signals.iter().any(|s| s == "mention:US"));
+    }
+
+    #[test]
+    fn us_mentions_need_country_context() {
+        for (html, expected) in [
+            (
+                "<a>Contact Us</a><p>ABOUT US</p><p>WHO WE HELP: US</p>",
+                false,
+            ),
+            ("<p>Shipping across the US</p>", true),
+            ("<p>Made in the USA</p>", true),
+            ("<p>Offices in the U.S. and Canada</p>", true),
+        ] {
+            let guess = country_guess_of("acme.com", Some(html));
+            assert_eq!(
+                guess.signals.iter().any(|s| s == "mention:US"),
+                expected,
+                "{}",
+                html
+            );
+        }
+    }
+
+    #[test]
+    fn generic_tld_with_structured_evidence() {
+        let guess = country_guess_of(
+            "acme.com",
+            Some("<html lang=\"en-NZ\"><p>Call +64 9 123 4567, prices in NZD</p></html>"),
+        );
+        assert_eq!(guess.country, Some("NZ"));
+
+        let guess = country_guess_of("acme.com", Some("<p>Welcome to our website</p>"));
+        assert_eq!(guess.country, None);
+        assert_eq!(guess.confidence, 0.0);
+
+        assert_eq!(country_guess_of("acme.com", None).country, None);
+    }
+
+    // Coordinates from page markup
+
+    /// geo_of rounded to 6 decimal places, as extract_geo returns it.
+    fn geo(html: &str) -> Option<(f64, f64, &'static str)> {
+        let round = |degrees: f64| (degrees * 1e6).round() / 1e6;
+        geo_of(html).map(|(lat, lng, source)| (round(lat), round(lng), source))
+    }
+
+    #[test]
+    fn coordinates_from_each_source() {
+        let cases = [
+            (
+                r#"<script type="application/ld+json">{"@type": "Plumber",
+                    "geo": {"@type": "GeoCoordinates", "latitude": -27.4698, "longitude": "153.0251"}}</script>"#,
+                (-27.4698, 153.0251, "schema"),
+            ),
+            (
+                r#"<div itemscope><meta itemprop="latitude" content="-37.8136">
+                    <meta itemprop="longitude" content="144.9631"></div>"#,
+                (-37.8136, 144.9631, "schema"),
+            ),
+            (
+                r#"<meta name="geo.position" content="-31.9505;115.8605">"#,
+                (-31.9505, 115.8605, "meta"),
+            ),
+            (
+                r#"<meta name="ICBM" content="-34.9285, 138.6007">"#,
+                (-34.9285, 138.6007, "meta"),
+            ),
+            (
+                r#"<iframe src="https://www.google.com/maps/embed?pb=!1m18!1m12!1m3!1d3540.1!2d153.0229!3d-27.4697!2m3!1f0!2f0!3f0!3m2!1i1024!2i768!4f13.1!3m3!1m2!1s0x6b915a1d:0x1!2sAcme%20Plumbing!5e0!3m2!1sen!2sau!4v1"></iframe>"#,
+                (-27.4697, 153.0229, "map_embed"),
+            ),
+            (
+                r#"<iframe src="https://www.google.com/maps/embed?pb=!1m14!1m8!1m3!1d3540.1!2d153.0!3d-27.4!3m2!1i1024!2i768!4f13.1!3m3!1m2!1s0x0:0x0!2zQWNtZQ!5e0!3m2!1sen!2sau!4v1!4m5!3m4!1s0x0:0x0!8m2!3d-27.469812!4d153.025123"></iframe>"#,
+                (-27.469812, 153.025123, "map_embed"),
+            ),
+            (
+                r#"<iframe src="https://maps.google.com/maps?ll=-33.8688,151.2093&amp;z=15&amp;output=embed"></iframe>"#,
+                (-33.8688, 151.2093, "map_embed"),
+            ),
+            (
+                r#"<iframe src="https://maps.google.com/maps?q=-33.86881,151.20929&hl=en&output=embed"></iframe>"#,
+                (-33.86881, 151.20929, "map_embed"),
+            ),
+            (
+                r#"<a href="https://www.google.com/maps/place/Acme/@-42.8821,147.3272,17z/data=!3m1">Directions</a>"#,
+                (-42.8821, 147.3272, "map_link"),
+            ),
+            (
+                r#"<a href="https://maps.google.com/?daddr=-12.4634%2C130.8456">Get here</a>"#,
+                (-12.4634, 130.8456, "map_link"),
+            ),
+            (
+                "<script>var map = L.map('map').setView([-35.2809, 149.1300], 14);</script>",
+                (-35.2809, 149.13, "map_script"),
+            ),
+            (
+                "<script>new mapboxgl.Map({container: 'map', center: [145.7781, -16.9186], zoom: 12});</script>",
+                (-16.9186, 145.7781, "map_script"),
+            ),
+            (
+                "<script>new google.maps.Marker({position: {lat: -19.2590, lng: 146.8169}});</script>",
+                (-19.259, 146.8169, "map_script"),
+            ),
+        ];
+        for (html, expected) in cases {
+            assert_eq!(geo(html), Some(expected), "{}", html);
+        }
+    }
+
+    #[test]
+    fn schema_beats_embeds_and_coordinates_round() {
+        let html = r#"<iframe src="https://maps.google.com/maps?ll=-33.8688,151.2093&z=15&output=embed"></iframe>
+            <script type="application/ld+json">{"@type": "Dentist",
+                "geo": {"latitude": -27.46981234567, "longitude": 153.02512345678}}</script>"#;
+        assert_eq!(geo(html), Some((-27.469812, 153.025123, "schema")));
+    }
+
+    #[test]
+    fn country_scale_and_invalid_maps_are_ignored() {
+        for html in [
+            // Zoomed out to the whole country
+            r#"<iframe src="https://maps.google.com/maps?ll=-25.2744,133.7751&z=4&output=embed"></iframe>"#,
+            r#"<a href="https://www.google.com/maps/@-25.2744,133.7751,5z">Map</a>"#,
+            r#"<a href="https://www.google.com/maps/@-25.2744,133.7751,4500000m/data=!3m1">Map</a>"#,
+            r#"<iframe src="https://www.google.com/maps/embed?pb=!1m14!1m12!1m3!1d40000000!2d133.77!3d-25.27!2m3"></iframe>"#,
+            "<script>L.map('map').setView([-25.27, 133.77], 4);</script>",
+            "<script>new mapboxgl.Map({center: [133.77, -25.27], zoom: 3});</script>",
+            // Out of range, the (0, 0) default and integer "coordinates"
+            r#"<meta name="geo.position" content="95.0;200.0">"#,
+            "<script>var center = {lat: 0.0, lng: 0.0};</script>",
+            r#"<iframe src="https://maps.google.com/maps?q=1,2&output=embed"></iframe>"#,
+            r#"<script type="application/ld+json">{"@type": "Store", "geo": {"latitude": -127.5, "longitude": 153.0}}</script>"#,
+            "<p>No map here</p>",
+        ] {
+            assert_eq!(geo(html), None, "{}", html);
+        }
+    }
+}
@@ -1,8 +1,24 @@
+use aho_corasick::{AhoCorasick, AhoCorasickKind, MatchKind};
+use pyo3::exceptions::PyValueError;
 use pyo3::prelude::*;
-use pyo3::types::PyDict;
+use pyo3::types::{PyDict, PyList};
+use rayon::prelude::*;
 use regex::Regex;
+use scraper::{ElementRef, Html, Node, Selector};
+use std::borrow::Cow;
 use std::collections::{HashMap, HashSet};
-use std::sync::LazyLock;
+use std::sync::{Arc, LazyLock, RwLock};
+use std::time::{SystemTime, UNIX_EPOCH};
+use whatlang::Lang;
+
+use crate::metadata::{
+    is_local_business_type, job_posting_titles_of, schema_offer_prices_of,
+    schema_person_reviews_of, schema_post_dates_of, schema_types_of,
+};
+use crate::text::{
+    au_address_dict, canonical_domain, clean_business_name, find_au_addresses, is_disposable_email,
+    normalize_domain, title_case_words, AuAddress,
+};
 
 // ---------------------------------------------------------------------------
 // Compiled regexes
@@ -12,24 +28,134 @@ static EMAIL_RE: LazyLock<Regex> = LazyLock::new(|| {
     Regex::new(r"[a-zA-Z0-9._%+\-]+@[a-zA-Z0-9.\-]+\.[a-zA-Z]{2,}").unwrap()
 });
 
+// Numeric (&#64; / &#x40;) and named (&commat;) HTML entities
+static HTML_ENTITY_RE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"&(?:#([0-9]{1,7})|#[xX]([0-9a-fA-F]{1,6})|([a-zA-Z]{2,8}));").unwrap()
+});
+
+// Cloudflare email protection: data-cfemail="..." or /cdn-cgi/l/email-protection#...
+static CFEMAIL_RE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r#"(?:data-cfemail\s*=\s*["']?|/cdn-cgi/l/email-protection#)([0-9a-fA-F]+)"#)
+        .unwrap()
+});
+
+static MAILTO_RE: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r#"(?i)mailto:([^"'<>\s]+)"#).unwrap());
+
+// JSON / JS string escapes ("info\u0040acme.com.au")
+static UNICODE_ESCAPE_RE: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"\\u([0-9a-fA-F]{4})").unwrap());
+
+// Script bodies: JSON-LD, application/json settings blobs, inline JS config
+static SCRIPT_BODY_RE: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"(?is)<script\b[^>]*>(.*?)</script>").unwrap());
+
+// Attributes that hold a bare (possibly percent-encoded) address
+static EMAIL_ATTRIBUTE_RE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(
+        r#"(?i)\b(?:data-(?:email|e-mail|mail|contact|address)|content|value)\s*=\s*["']([^"']+)["']"#,
+    )
+    .unwrap()
+});
+
+// Spelled-out addresses: "info [at] acme [dot] com", "info AT acme.com", "info(at)acme(dot)com"
+static OBFUSCATED_EMAIL_RE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(concat!(
+        r"(?i)(?P<local>[a-z0-9._%+\-]+)",
+        r"(?P<at>\s*[\[\(\{]\s*at\s*[\]\)\}]\s*|\s+at\s+|@)",
+        r"(?P<domain>[a-z0-9\-]+(?:(?:\s*[\[\(\{]\s*dot\s*[\]\)\}]\s*|\s+dot\s+|\.)[a-z0-9\-]+)+)",
+    ))
+    .unwrap()
+});
+
+static SPELLED_DOT_RE: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"(?i)\s*[\[\(\{]\s*dot\s*[\]\)\}]\s*|\s+dot\s+").unwrap());
+
+// Common English words that precede a bare "at" in running prose; a local
+// part spelled like one of these is not rebuilt into an address
+const PROSE_WORDS_BEFORE_AT: &[&str] = &[
+    "a",
+    "all",
+    "an",
+    "and",
+    "anyone",
+    "are",
+    "arrive",
+    "available",
+    "back",
+    "based",
+    "be",
+    "best",
+    "book",
+    "call",
+    "day",
+    "drop",
+    "email",
+    "everyone",
+    "find",
+    "found",
+    "here",
+    "home",
+    "is",
+    "it",
+    "just",
+    "live",
+    "located",
+    "look",
+    "looking",
+    "me",
+    "meet",
+    "now",
+    "off",
+    "on",
+    "open",
+    "or",
+    "pop",
+    "see",
+    "shop",
+    "start",
+    "starts",
+    "stay",
+    "stop",
+    "text",
+    "that",
+    "the",
+    "them",
+    "there",
+    "this",
+    "today",
+    "up",
+    "us",
+    "visit",
+    "was",
+    "we",
+    "were",
+    "work",
+    "working",
+    "you",
+];
+
+// ASCII digits only: Unicode \d would let Arabic-Indic or full-width digits
+// through, and normalize_phone slices the result by byte offset
 static PHONE_PATTERNS: LazyLock<Vec<Regex>> = LazyLock::new(|| {
     vec![
-        Regex::new(r"(?:\+61|0)[2-478](?:[ \-]?\d){8}").unwrap(),
-        Regex::new(r"\(\d{2}\)[ \-]?\d{4}[ \-]?\d{4}").unwrap(),
-        Regex::new(r"1[38]00[ \-]?\d{3}[ \-]?\d{3}").unwrap(),
-        Regex::new(r"13[ \-]?\d{2}[ \-]?\d{2}").unwrap(),
+        Regex::new(r"(?:\+61|0)[2-478](?:[ \-]?[0-9]){8}").unwrap(),
+        Regex::new(r"\([0-9]{2}\)[ \-]?[0-9]{4}[ \-]?[0-9]{4}").unwrap(),
+        Regex::new(r"1[38]00[ \-]?[0-9]{3}[ \-]?[0-9]{3}").unwrap(),
+        Regex::new(r"13[ \-]?[0-9]{2}[ \-]?[0-9]{2}").unwrap(),
     ]
 });
 
 static PHONE_NORMALIZE_RE: LazyLock<Regex> = LazyLock::new(|| {
-    Regex::new(r"[^\d+]").unwrap()
+    Regex::new(r"[^0-9+]").unwrap()
 });
 
 // Spam email patterns (compiled)
 static SPAM_EMAIL_RES: LazyLock<Vec<Regex>> = LazyLock::new(|| {
     vec![
         Regex::new(r"(?i).*@error-tracking\..*").unwrap(),
-        Regex::new(r"(?i).*@sentry\.io").unwrap(),
+        Regex::new(r"(?i).*@(?:[a-z0-9\-]+\.)*sentry\.io").unwrap(),
+        Regex::new(r"(?i).*@sentry(?:-next)?\.wixpress\.com").unwrap(),
         Regex::new(r"(?i).*@bugsnag\.com").unwrap(),
         Regex::new(r"(?i).*@errortracking\..*").unwrap(),
         Regex::new(r"(?i).*@tracking\..*").unwrap(),
@@ -99,320 +225,13523 @@ static SPAM_EMAIL_DOMAINS: LazyLock<HashSet<&'static str>> = LazyLock::new(|| {
     ])
 });
 
+// Phone context labels, checked against the text preceding a number
+static PHONE_LABEL_PATTERNS: LazyLock<Vec<(PhoneLabel, Regex)>> = LazyLock::new(|| {
+    vec![
+        (
+            PhoneLabel::Fax,
+            Regex::new(r"(?i)\b(?:fax|facsimile)\b|\bf\s*:").unwrap(),
+        ),
+        (
+            PhoneLabel::Mobile,
+            Regex::new(r"(?i)\b(?:mobile|mob|cell|sms|text)\b|\bm\s*:").unwrap(),
+        ),
+        (
+            PhoneLabel::AfterHours,
+            Regex::new(r"(?i)\b(?:after[\s\-]?hours|a/h|emergency|urgent)\b|24/7|\b24\s*hrs?\b")
+                .unwrap(),
+        ),
+        (
+            PhoneLabel::Sales,
+            Regex::new(r"(?i)\b(?:sales|quotes?|enquiries|enquiry|new\s+business)\b").unwrap(),
+        ),
+        (
+            PhoneLabel::Support,
+            Regex::new(r"(?i)\b(?:support|help\s*desk|helpline|customer\s+care)\b").unwrap(),
+        ),
+        (
+            PhoneLabel::Office,
+            Regex::new(r"(?i)\b(?:office|phone|ph|tel|telephone|landline|reception)\b|\b[pt]\s*:")
+                .unwrap(),
+        ),
+    ]
+});
+
+// Elements whose text never reaches the rendered page
+static INVISIBLE_ELEMENTS: &[&str] = &["script", "style", "noscript", "template", "head"];
+
 // ---------------------------------------------------------------------------
 // CMS / Tracking / Booking / Framework signatures
 // ---------------------------------------------------------------------------
 
-static CMS_SIGNATURES: LazyLock<Vec<(&str, Vec<&str>)>> = LazyLock::new(|| {
-    vec![
-        ("WordPress", vec!["/wp-content/", "/wp-includes/", "wp-json", "wordpress"]),
-        ("Wix", vec!["wix.com", "wixsite.com", "_wix_browser_sess", "wix-code"]),
-        ("Squarespace", vec!["squarespace.com", "static.squarespace", "sqsp.net"]),
-        ("Shopify", vec!["cdn.shopify.com", "myshopify.com", "shopify"]),
-        ("Webflow", vec!["webflow.com", "assets-global.website-files", "webflow.io"]),
-        ("Weebly", vec!["weebly.com", "weeblycloud.com"]),
-        ("GoDaddy Website Builder", vec!["godaddy.com", "secureserver.net", "godaddysites"]),
-        ("Joomla", vec!["joomla", "/components/com_"]),
-        ("Drupal", vec!["drupal", "/sites/default/"]),
-    ]
+// Asset paths and hostnames are strong evidence; a bare brand word
+// ("wordpress", "shopify") may just be page copy, so it weighs far less
+const CMS_ASSET_WEIGHT: f64 = 1.0;
+const CMS_KEYWORD_WEIGHT: f64 = 0.25;
+// Score at which a CMS is fully confident, and the least detect_cms reports
+const CMS_FULL_SCORE: f64 = 2.0;
+const MIN_CMS_SCORE: f64 = 0.5;
+
+static DEFAULT_CMS_SIGNATURES: &[(&str, &[&str])] = &[
+    (
+        "WordPress",
+        &["/wp-content/", "/wp-includes/", "wp-json", "wordpress"],
+    ),
+    (
+        "Wix",
+        &["wix.com", "wixsite.com", "_wix_browser_sess", "wix-code"],
+    ),
+    (
+        "Squarespace",
+        &["squarespace.com", "static.squarespace", "sqsp.net"],
+    ),
+    ("Shopify", &["cdn.shopify.com", "myshopify.com", "shopify"]),
+    (
+        "Webflow",
+        &["webflow.com", "assets-global.website-files", "webflow.io"],
+    ),
+    ("Weebly", &["weebly.com", "weeblycloud.com"]),
+    (
+        "GoDaddy Website Builder",
+        &[
+            "img1.wsimg.com/isteam",
+            "img1.wsimg.com/blobby",
+            "secureserver.net",
+            "godaddysites",
+        ],
+    ),
+    ("Joomla", &["joomla", "/components/com_"]),
+    ("Drupal", &["drupal", "/sites/default/"]),
+    (
+        "Duda",
+        &["multiscreensite.com", "cdn-website.com", "dudamobile.com"],
+    ),
+    ("Yola", &["yolacdn.net", "yolasite.com", "yola.com/"]),
+    (
+        "Kajabi",
+        &["kajabi-cdn.com", "kajabi-storefronts", "mykajabi.com"],
+    ),
+    (
+        "Framer",
+        &["framerusercontent.com", "framer.app", "data-framer-"],
+    ),
+    ("Carrd", &["carrd.co", "carrd"]),
+    (
+        "Hostinger Website Builder",
+        &["zyrosite.com", "zyro.com", "hostinger"],
+    ),
+    (
+        "Square Online",
+        &["square.site", "squarecdn.com", "squareup.com/online"],
+    ),
+];
+
+// (name, lowercase patterns) in match order
+type SignatureTable = Vec<(String, Vec<String>)>;
+
+// Active signature table: the built-ins merged with anything registered at
+// runtime via register_cms_signature() / load_cms_signatures()
+static CMS_SIGNATURES: LazyLock<RwLock<SignatureTable>> =
+    LazyLock::new(|| RwLock::new(signature_table(DEFAULT_CMS_SIGNATURES)));
+
+// WordPress page builders by their class / asset markers
+static PAGE_BUILDER_SIGNATURES: &[(&str, &[&str])] = &[
+    (
+        "Elementor",
+        &[
+            "elementor-frontend",
+            "data-elementor-type",
+            "elementor-kit-",
+        ],
+    ),
+    ("Divi", &["et_pb_", "et-core", "et-divi"]),
+    ("WPBakery", &["vc_row", "js_composer", "wpb_wrapper"]),
+    ("Beaver Builder", &["fl-builder", "fl-row"]),
+    ("Oxygen", &["ct_section", "ct-section", "oxygen-body"]),
+];
+
+// Block-editor pages without another builder; below this many wp-block-
+// classes the blocks are just a theme's default markup
+const MIN_GUTENBERG_BLOCKS: usize = 15;
+const GUTENBERG_BLOCK_MARKER: &str = "wp-block-";
+
+// /wp-content/themes/<slug>/... and /wp-content/plugins/<slug>/..., also on
+// CDN-rewritten hosts that keep the path
+static WP_CONTENT_SLUG_RE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"(?i)/wp-content/(themes|plugins)/([a-z0-9][a-z0-9_.\-]*)/").unwrap()
+});
+
+// Bound on asset paths scanned, so pages with thousands of assets stay cheap
+const MAX_WP_ASSET_PATHS: usize = 2000;
+const MAX_WP_PLUGINS: usize = 50;
+
+// Plugins that rarely expose /wp-content/plugins/ paths but leave inline
+// markers (HTML comments, wrapper classes), keyed by their wordpress.org slug
+static WP_PLUGIN_MARKERS: &[(&str, &[&str])] = &[
+    ("wordpress-seo", &["optimized with the yoast seo plugin"]),
+    (
+        "seo-by-rank-math",
+        &["search engine optimization by rank math"],
+    ),
+    ("all-in-one-seo-pack", &["<!-- all in one seo"]),
+    ("wpforms", &["wpforms-form", "wpforms-container"]),
+    (
+        "elementor-pro",
+        &["elementor-pro-css", "elementor-pro-frontend"],
+    ),
+    ("wp-rocket", &["performance optimized by wp rocket"]),
+    (
+        "w3-total-cache",
+        &["performance optimized by w3 total cache"],
+    ),
+    ("litespeed-cache", &["page optimized by litespeed cache"]),
+    (
+        "wp-super-cache",
+        &["cached page generated by wp-super-cache"],
+    ),
+    (
+        "google-analytics-for-wordpress",
+        &["google analytics by monsterinsights"],
+    ),
+];
+
+// <meta name="generator" content="WordPress 6.4.2"> (either attribute order)
+static GENERATOR_META_RE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r#"(?is)<meta\b[^>]*\bname\s*=\s*["']generator["'][^>]*>"#).unwrap()
+});
+
+static META_CONTENT_RE: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r#"(?is)\bcontent\s*=\s*["']([^"']*)["']"#).unwrap());
+
+// "WordPress 6.4.2", "Joomla! 3.9 - Open Source Content Management", "Drupal 7 (http://drupal.org)"
+static GENERATOR_VERSION_RE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"(?i)^\s*(?P<cms>wordpress|joomla|drupal)!?\s+(?P<version>[0-9]+(?:\.[0-9]+)*)")
+        .unwrap()
+});
+
+// Core assets carry the WordPress version: /wp-includes/js/jquery/jquery.min.js?ver=3.7.1
+static WP_INCLUDES_VER_RE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(
+        r#"/wp-includes/[^"'\s?]+\?(?:[^"'\s]*&(?:amp;)?)?ver=(?P<version>[0-9]+(?:\.[0-9]+)+)"#,
+    )
+    .unwrap()
 });
 
+// "google_analytics" is derived (ga4 or universal_analytics) rather than
+// matched, so GTM on its own no longer counts as analytics
 static TRACKING_SIGNATURES: LazyLock<Vec<(&str, Vec<&str>)>> = LazyLock::new(|| {
     vec![
-        ("google_analytics", vec![
-            "google-analytics.com", "gtag(", "ga(", "g-", "ua-", "googletagmanager.com",
+        (
+            "google_tag_manager",
+            vec![
+                "googletagmanager.com/gtm.js",
+                "googletagmanager.com/ns.html",
+            ],
+        ),
+        ("ga4", vec![]),
+        ("universal_analytics", vec![
+            "google-analytics.com/analytics.js", "google-analytics.com/ga.js", "ga('create'",
+            "ga(\"create\"", "_gaq.push",
         ]),
         ("facebook_pixel", vec![
             "facebook.com/tr", "fbq(", "connect.facebook.net",
         ]),
         ("google_ads", vec![
-            "googleadservices.com", "googlesyndication.com", "aw-", "google_conversion",
+            "googleadservices.com", "googlesyndication.com", "google_conversion",
         ]),
+        ("hotjar", vec!["static.hotjar.com", "script.hotjar.com", "_hjsettings"]),
+        ("microsoft_clarity", vec!["clarity.ms/tag"]),
+        ("tiktok_pixel", vec!["analytics.tiktok.com", "ttq.load("]),
+        (
+            "linkedin_insight",
+            vec![
+                "snap.licdn.com",
+                "_linkedin_partner_id",
+                "px.ads.linkedin.com",
+            ],
+        ),
+        (
+            "pinterest_tag",
+            vec!["s.pinimg.com/ct/core.js", "ct.pinterest.com", "pintrk("],
+        ),
+        (
+            "snapchat_pixel",
+            vec!["sc-static.net/scevent", "tr.snapchat.com", "snaptr("],
+        ),
     ]
 });
 
-static BOOKING_SIGNATURES: LazyLock<Vec<&str>> = LazyLock::new(|| {
+// Measurement and container IDs, anchored to a quote or `id=` so the
+// prefixes don't match inside ordinary words ("big-", "aqua-", "draw-")
+static TRACKING_ID_PATTERNS: LazyLock<Vec<(&str, Regex)>> = LazyLock::new(|| {
     vec![
-        "calendly.com", "acuityscheduling", "youcanbook.me", "setmore.com",
-        "square.site/book", "fresha.com", "book-online", "book-now",
-        "schedule-appointment", "hubspot.com/meetings", "bookings.google.com",
-        "appointlet.com", "simplybook.me", "timify.com",
+        (
+            "google_tag_manager",
+            Regex::new(r#"(?:["']|id=)GTM-[A-Z0-9]{4,9}\b"#).unwrap(),
+        ),
+        (
+            "ga4",
+            Regex::new(r#"(?:["']|id=)G-[A-Z0-9]{6,12}\b"#).unwrap(),
+        ),
+        (
+            "universal_analytics",
+            Regex::new(r#"(?:["']|id=)UA-[0-9]{4,10}-[0-9]{1,4}\b"#).unwrap(),
+        ),
+        (
+            "google_ads",
+            Regex::new(r#"(?:["']|id=)AW-[0-9]{6,12}\b"#).unwrap(),
+        ),
     ]
 });
 
-static FRAMEWORK_SIGNATURES: LazyLock<Vec<(&str, Vec<&str>)>> = LazyLock::new(|| {
+const MAX_TRACKING_IDS: usize = 10;
+
+// IDs are only taken from the API call or loader URL that carries them, so
+// "G-FORCE" in copy or a "UA-" CSS class never shows up
+static TRACKING_ID_EXTRACTORS: LazyLock<Vec<(&str, Vec<Regex>)>> = LazyLock::new(|| {
     vec![
-        ("React", vec!["react", "reactdom", "__react"]),
-        ("Vue.js", vec!["vue.js", "vuejs", "__vue__"]),
-        ("Angular", vec!["ng-app", "ng-controller", "angular"]),
-        ("jQuery", vec!["jquery", "$(document)", "$.ajax"]),
-        ("Bootstrap", vec!["bootstrap.min", "bootstrap.css"]),
-        ("Tailwind", vec!["tailwindcss", "tailwind.css"]),
+        (
+            "ga4_ids",
+            vec![
+                Regex::new(r#"gtag\(\s*["']config["']\s*,\s*["'](G-[A-Z0-9]{6,12})["']"#).unwrap(),
+                Regex::new(r#"googletagmanager\.com/gtag/js\?id=(G-[A-Z0-9]{6,12})\b"#).unwrap(),
+            ],
+        ),
+        (
+            "ua_ids",
+            vec![
+                Regex::new(concat!(
+                    r#"(?:gtag\(\s*["']config["']|ga\(\s*["']create["']|_setAccount["'])"#,
+                    r#"\s*,\s*["'](UA-[0-9]{4,10}-[0-9]{1,4})["']"#,
+                ))
+                .unwrap(),
+                Regex::new(r#"googletagmanager\.com/gtag/js\?id=(UA-[0-9]{4,10}-[0-9]{1,4})\b"#)
+                    .unwrap(),
+            ],
+        ),
+        (
+            "gtm_ids",
+            vec![
+                Regex::new(
+                    r#"googletagmanager\.com/(?:gtm\.js|ns\.html)\?id=(GTM-[A-Z0-9]{6,8})\b"#,
+                )
+                .unwrap(),
+                Regex::new(r#"["']dataLayer["']\s*,\s*["'](GTM-[A-Z0-9]{6,8})["']"#).unwrap(),
+            ],
+        ),
+        (
+            "fb_pixel_ids",
+            vec![
+                Regex::new(r#"fbq\(\s*["']init["']\s*,\s*["']?([0-9]{15,16})\b"#).unwrap(),
+                Regex::new(r#"facebook\.com/tr/?\?id=([0-9]{15,16})\b"#).unwrap(),
+            ],
+        ),
     ]
 });
 
-static RESPONSIVE_INDICATORS: LazyLock<Vec<&str>> = LazyLock::new(|| {
-    vec!["viewport", "media=", "@media", "responsive", "mobile", "bootstrap", "tailwind"]
+static DEFAULT_BOOKING_SIGNATURES: &[(&str, &[&str])] = &[
+    ("Calendly", &["calendly.com"]),
+    ("Acuity Scheduling", &["acuityscheduling"]),
+    ("YouCanBookMe", &["youcanbook.me"]),
+    ("Setmore", &["setmore.com"]),
+    (
+        "Square Appointments",
+        &["square.site/book", "squareup.com/appointments"],
+    ),
+    ("Fresha", &["fresha.com"]),
+    (
+        "HubSpot Meetings",
+        &["hubspot.com/meetings", "meetings.hubspot.com"],
+    ),
+    ("Google Bookings", &["bookings.google.com"]),
+    ("Appointlet", &["appointlet.com"]),
+    ("SimplyBook.me", &["simplybook.me"]),
+    ("Timify", &["timify.com"]),
+    ("Timely", &["gettimely.com"]),
+    ("HealthEngine", &["healthengine.com.au"]),
+    ("HotDoc", &["hotdoc.com.au"]),
+    ("Cliniko", &["cliniko.com"]),
+    ("Mindbody", &["mindbodyonline.com", "healcode"]),
+    ("ServiceM8", &["book.servicem8.com", "servicem8.com/book"]),
+    ("Housecall Pro", &["housecallpro.com"]),
+];
+
+// Active booking table: the built-ins plus register_booking_signatures()
+static BOOKING_SIGNATURES: LazyLock<RwLock<SignatureTable>> =
+    LazyLock::new(|| RwLock::new(signature_table(DEFAULT_BOOKING_SIGNATURES)));
+
+// Booking buttons with no provider behind them; a site can have these and
+// still take bookings by phone
+static GENERIC_BOOKING_SIGNATURES: &[&str] = &["book-online", "book-now", "schedule-appointment"];
+
+// Pages are heavy enough that rayon pays off after a handful
+const TECH_STACK_PARALLEL_THRESHOLD: usize = 4;
+
+const BOOKING_PROVIDER_CONFIDENCE: f64 = 1.0;
+const GENERIC_BOOKING_CONFIDENCE: f64 = 0.4;
+
+/// Where a framework shows itself: in script/stylesheet URLs, inside inline
+/// scripts, or as an attribute name on an element. Prose never counts, so a
+/// menu that says "react to our new dishes" isn't React. An attribute
+/// pattern ending in '-' matches as a prefix ("data-v-" for Vue's scoped
+/// style hashes).
+struct FrameworkSignature {
+    name: &'static str,
+    urls: &'static [&'static str],
+    code: &'static [&'static str],
+    attributes: &'static [&'static str],
+}
+
+static FRAMEWORK_SIGNATURES: &[FrameworkSignature] = &[
+    FrameworkSignature {
+        name: "React",
+        urls: &[
+            "react.production",
+            "react.development",
+            "react-dom",
+            "react.min.js",
+            "/react@",
+        ],
+        code: &[
+            "reactdom.",
+            "react.createelement",
+            "__react_devtools_global_hook__",
+            "_reactrootcontainer",
+        ],
+        attributes: &["data-reactroot", "data-reactid"],
+    },
+    FrameworkSignature {
+        name: "Vue.js",
+        urls: &["vue.js", "vue.min.js", "vue.global", "vue.runtime", "/vue@"],
+        code: &["__vue_devtools_global_hook__", "new vue(", "vue.createapp("],
+        attributes: &["data-v-", "v-cloak"],
+    },
+    FrameworkSignature {
+        name: "Angular",
+        urls: &["angular.js", "angular.min.js", "/angular@", "@angular/"],
+        code: &["angular.module(", "platformbrowserdynamic", "ng.probe"],
+        attributes: &["ng-app", "data-ng-app", "ng-controller", "ng-version"],
+    },
+    FrameworkSignature {
+        name: "jQuery",
+        urls: &["jquery"],
+        code: &["jquery(", "$(document)", "$.ajax"],
+        attributes: &[],
+    },
+    FrameworkSignature {
+        name: "Bootstrap",
+        urls: &[
+            "bootstrap.min",
+            "bootstrap.css",
+            "bootstrap.bundle",
+            "/bootstrap@",
+        ],
+        code: &[],
+        attributes: &[],
+    },
+    FrameworkSignature {
+        name: "Tailwind",
+        urls: &["tailwindcss", "tailwind.css", "tailwind.min.css"],
+        code: &["tailwind.config"],
+        attributes: &[],
+    },
+    FrameworkSignature {
+        name: "Svelte",
+        urls: &["/_app/immutable/", "svelte"],
+        code: &["__sveltekit_"],
+        attributes: &["data-svelte-h", "data-sveltekit-"],
+    },
+    FrameworkSignature {
+        name: "Next.js",
+        urls: &["/_next/static/"],
+        code: &["self.__next_f", "__next_data__"],
+        attributes: &[],
+    },
+    FrameworkSignature {
+        name: "Nuxt",
+        urls: &["/_nuxt/"],
+        code: &["window.__nuxt__", "__nuxt__"],
+        attributes: &["data-n-head"],
+    },
+    FrameworkSignature {
+        name: "Alpine.js",
+        urls: &["alpinejs", "alpine.min.js"],
+        code: &[],
+        attributes: &["x-data", "x-init", "x-cloak"],
+    },
+];
+
+// src/href of script and link tags
+static ASSET_URL_RE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r#"(?i)<(?:script|link)\b[^>]*?\s(?:src|href)\s*=\s*["']?([^"'\s>]+)"#).unwrap()
+});
+
+// The attribute section of an opening tag
+static TAG_ATTRIBUTES_RE: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"<[a-zA-Z][a-zA-Z0-9-]*(\s[^>]*)>").unwrap());
+
+// One attribute; consuming the quoted value keeps words inside alt/title
+// text from being read as attribute names
+static ATTRIBUTE_NAME_RE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r#"([^\s"'=<>/]+)(?:\s*=\s*(?:"[^"]*"|'[^']*'|[^\s"'>]+))?"#).unwrap()
+});
+
+static VIEWPORT_META_RE: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r#"(?is)<meta\b[^>]*\bname\s*=\s*["']?viewport\b[^>]*>"#).unwrap());
+
+static STYLE_BODY_RE: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"(?is)<style\b[^>]*>(.*?)</style>").unwrap());
+
+// Width breakpoints only: a lone @media print rule says nothing about mobile
+static WIDTH_MEDIA_QUERY_RE: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"(?i)@media[^{]*\((?:max|min)-(?:device-)?width\s*:").unwrap());
+
+static LINK_MEDIA_WIDTH_RE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r#"(?i)<link\b[^>]*\bmedia\s*=\s*["'][^"']*(?:max|min)-(?:device-)?width"#).unwrap()
 });
 
+// "bootstrap@5.3.0", "bootstrap/3.3.7/", "bootstrap-4.1.min.css"
+static BOOTSTRAP_VERSION_RE: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"bootstrap[@/\-]v?([0-9]+)\.").unwrap());
+
+// Parking/for-sale landers: (provider, signatures). A provider hit is strong
+// evidence but still needs a second signal so brokers' own sites pass.
+static PARKING_PROVIDER_SIGNATURES: &[(&str, &[&str])] = &[
+    (
+        "Sedo",
+        &[
+            "sedoparking.com",
+            "sedo.com/search/details",
+            "sedo domain parking",
+        ],
+    ),
+    ("Dan.com", &["dan.com/buy-domain", "dan.com/lander"]),
+    (
+        "Afternic",
+        &["afternic.com/forsale", "afternic.com/domain/"],
+    ),
+    (
+        "GoDaddy",
+        &[
+            "parking-lander",
+            "godaddy.com/forsale",
+            "img1.wsimg.com/parking",
+        ],
+    ),
+    (
+        "Namecheap",
+        &[
+            "parkingpage.namecheap.com",
+            "namecheap.com/domains/registration",
+        ],
+    ),
+    ("Bodis", &["bodis.com", "bodiscdn.com"]),
+    ("ParkingCrew", &["parkingcrew.net"]),
+    ("HugeDomains", &["hugedomains.com/domain_profile"]),
+];
+
+static PARKING_PHRASES: &[&str] = &[
+    "buy this domain",
+    "this domain is for sale",
+    "this domain may be for sale",
+    "domain is parked",
+    "parked free, courtesy of",
+    "make an offer on this domain",
+    "inquire about this domain",
+    "the domain owner may be",
+    "related searches",
+    "sponsored listings",
+];
+
+// AdSense-for-domains script that renders the single ad block on landers.
+// Only the full Google path: a bare "/caf.js" also names unrelated scripts.
+static PARKING_AD_SCRIPTS: &[&str] = &["google.com/adsense/domains/caf.js"];
+
 // ---------------------------------------------------------------------------
-// Helpers
+// Placeholder page signatures
 // ---------------------------------------------------------------------------
 
-fn is_spam_email(email: &str) -> bool {
-    // Check domain blocklist
-    if let Some(pos) = email.rfind('@') {
-        let domain = &email[pos + 1..];
-        if SPAM_EMAIL_DOMAINS.contains(domain) {
-            return true;
-        }
-    }
+// (kind, label, markers): template strings that only appear on the
+// placeholder itself, so one is enough. Checked in order.
+static PLACEHOLDER_SIGNATURES: &[(&str, &str, &[&str])] = &[
+    (
+        "suspended",
+        "cPanel suspended page",
+        &[
+            "/cgi-sys/suspendedpage.cgi",
+            "this account has been suspended",
+        ],
+    ),
+    (
+        "default_server",
+        "cPanel default page",
+        &[
+            "/cgi-sys/defaultwebpage.cgi",
+            "future home of something quite cool",
+        ],
+    ),
+    (
+        "default_server",
+        "Apache default page",
+        &[
+            "apache2 ubuntu default page",
+            "apache2 debian default page",
+            "test page for the apache http server",
+        ],
+    ),
+    (
+        "default_server",
+        "nginx default page",
+        &[
+            "welcome to nginx!",
+            "the nginx web server is successfully installed",
+        ],
+    ),
+    (
+        "default_server",
+        "IIS default page",
+        &["iisstart.png", "iis-85.png"],
+    ),
+    (
+        "default_server",
+        "Plesk default page",
+        &[
+            "web server's default page",
+            "this page is generated by plesk",
+        ],
+    ),
+    (
+        "maintenance",
+        "WordPress maintenance page",
+        &["briefly unavailable for scheduled maintenance"],
+    ),
+    (
+        "maintenance",
+        "Elementor maintenance mode",
+        &["elementor-maintenance-mode"],
+    ),
+    (
+        "coming_soon",
+        "Wix unconnected domain",
+        // Matched from "connected" on, as the apostrophe before it may be
+        // curly or an entity in the markup
+        &["connected to a website yet"],
+    ),
+    (
+        "coming_soon",
+        "Shopify password page",
+        &["template-password"],
+    ),
+];
 
-    // Check spam patterns
-    for re in SPAM_EMAIL_RES.iter() {
-        if re.is_match(email) {
-            return true;
-        }
-    }
+// Coming-soon and maintenance plugins: their assets are strong evidence but
+// can linger after launch, so a title or <h1> phrase must agree.
+static PLACEHOLDER_PLUGIN_SIGNATURES: &[(&str, &[&str])] = &[
+    (
+        "SeedProd",
+        &["seedprod", "seed-csp4", "/plugins/coming-soon/"],
+    ),
+    (
+        "CMP",
+        &["cmp-coming-soon-maintenance", "/plugins/cmp-coming-soon"],
+    ),
+    ("WP Maintenance Mode", &["wp-maintenance-mode"]),
+    ("Under Construction", &["/plugins/under-construction-page/"]),
+];
 
-    false
-}
+// (kind, phrases) looked for in the <title> and <h1> only; "coming soon" in
+// a banner on a real page is not a placeholder.
+static PLACEHOLDER_PHRASES: &[(&str, &[&str])] = &[
+    (
+        "suspended",
+        &["account suspended", "website suspended", "site suspended"],
+    ),
+    (
+        "maintenance",
+        &[
+            "under maintenance",
+            "down for maintenance",
+            "maintenance mode",
+            "scheduled maintenance",
+        ],
+    ),
+    (
+        "coming_soon",
+        &[
+            "coming soon",
+            "launching soon",
+            "opening soon",
+            "under construction",
+        ],
+    ),
+    ("default_server", &["it works!"]),
+];
 
-fn format_au_number(digits: &str) -> String {
-    if digits.len() == 9 {
-        if digits.starts_with('4') {
-            // Mobile: 0XXX XXX XXX
-            format!("0{} {} {}", &digits[0..3], &digits[3..6], &digits[6..])
-        } else {
-            // Landline: 0X XXXX XXXX
-            format!("0{} {} {}", &digits[0..1], &digits[1..5], &digits[5..])
-        }
-    } else {
-        digits.to_string()
-    }
-}
+// A title/h1 phrase alone marks a placeholder only when the page has at
+// most this many words of visible text
+const PLACEHOLDER_MAX_WORDS: usize = 150;
 
-fn normalize_phone(phone: &str) -> String {
-    if phone.is_empty() {
-        return String::new();
-    }
+static H1_TEXT_RE: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"(?is)<h1\b[^>]*>(.*?)</h1\s*>").unwrap());
 
-    let digits = PHONE_NORMALIZE_RE.replace_all(phone, "").to_string();
+// ---------------------------------------------------------------------------
+// Soft 404 signatures
+// ---------------------------------------------------------------------------
 
-    // Count actual digits (excluding +)
-    let digit_count = digits.chars().filter(|c| c.is_ascii_digit()).count();
-    if digit_count < 8 {
-        return String::new();
-    }
+// Body classes of CMS "not found" templates (WordPress error404, Shopify
+// template-404, ...)
+static SOFT_404_BODY_CLASSES: &[&str] = &[
+    "error404",
+    "error-404",
+    "page-404",
+    "template-404",
+    "page-not-found",
+];
 
-    if digits.starts_with("+61") {
-        let mut rest = &digits[3..];
-        if rest.starts_with('0') {
-            rest = &rest[1..];
-        }
-        format_au_number(rest)
-    } else if digits.starts_with('0') {
-        format_au_number(&digits[1..])
-    } else if digits.starts_with("1300") || digits.starts_with("1800") {
-        format!("{} {} {}", &digits[..4], &digits[4..7], &digits[7..])
-    } else if digits.starts_with("13") && digits.len() == 6 {
-        format!("{} {} {}", &digits[..2], &digits[2..4], &digits[4..])
-    } else {
-        phone.trim().to_string()
-    }
-}
+// Last path segments of the pages sites redirect missing URLs to
+static SOFT_404_PATHS: &[&str] = &[
+    "404",
+    "404.html",
+    "404.php",
+    "not-found",
+    "page-not-found",
+    "error-404",
+];
+
+// Headings that introduce a contact block
+static CONTACT_HEADING_RE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(
+        r"(?i)\b(?:contact(?:\s+us)?|find\s+us|visit\s+us|our\s+(?:location|address|office)|head\s+office|address|location)\b",
+    )
+    .unwrap()
+});
+
+// How far past a contact heading the address may run, in chars
+const CONTACT_WINDOW_CHARS: usize = 300;
+const MAX_OTHER_ADDRESSES: usize = 10;
+
+static BODY_CLASS_RE: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r#"(?is)<body\b[^>]*\bclass\s*=\s*["']([^"']*)["']"#).unwrap());
+
+// "404", "Page not found", "can't be found", "Nothing found", "no longer exists"
+static SOFT_404_HEADING_RE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(concat!(
+        r"(?i)\b404\b|\bnot\s+found\b",
+        r"|\b(?:can(?:no|['’])t|couldn['’]t|could\s+not)\s+be\s+found\b",
+        r"|\bnothing\s+(?:was\s+)?found\b",
+        r"|\b(?:does\s+not|doesn['’]t|no\s+longer)\s+exists?\b",
+    ))
+    .unwrap()
+});
+
+static SOFT_404_WEAK_HEADING_RE: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"(?i)\b(?:oops|whoops|uh[\s-]oh)\b").unwrap());
+
+// Headings about 404s rather than of one ("How to fix 404 errors")
+static SOFT_404_ARTICLE_RE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"(?i)\b(?:how\s+to|fix(?:es|ing)?|guide|tips|why|errors|redirects)\b").unwrap()
+});
+
+static SEARCH_BOX_RE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(
+        r#"(?is)<input\b[^>]*\btype\s*=\s*["']?search\b|\brole\s*=\s*["']?search\b|<input\b[^>]*\bname\s*=\s*["']?s(?:["'\s/>])"#,
+    )
+    .unwrap()
+});
+
+// Longest title or <h1> still read as a 404 heading, in words
+const SOFT_404_MAX_HEADING_WORDS: usize = 14;
+// Visible words below which a page with a search box looks like a 404 stub
+const SOFT_404_THIN_WORDS: usize = 150;
+const SOFT_404_MIN_SCORE: f32 = 3.0;
 
 // ---------------------------------------------------------------------------
-// PyO3 functions
+// Directory / aggregator page signatures
 // ---------------------------------------------------------------------------
 
-#[pyfunction]
-pub fn extract_emails(html: &str) -> Vec<String> {
-    if html.is_empty() {
-        return Vec::new();
-    }
+// Class names of one result card in a listing grid
+static LISTING_ITEM_CLASSES: &[&str] = &[
+    "listing",
+    "listing-item",
+    "listing-card",
+    "listing-row",
+    "search-result",
+    "search-result-item",
+    "result-item",
+    "result-card",
+    "business-card",
+    "business-listing",
+    "company-card",
+    "company-listing",
+    "directory-item",
+    "directory-listing",
+    "provider-card",
+    "vcard",
+];
 
-    let mut valid_emails = Vec::new();
-    let mut seen: HashSet<String> = HashSet::new();
+// Per-listing calls to action, repeated once per card on aggregators
+static DIRECTORY_CTA_PHRASES: &[&str] = &[
+    "get a quote",
+    "get quotes",
+    "request a quote",
+    "view profile",
+    "view listing",
+    "write a review",
+    "see reviews",
+];
 
-    for m in EMAIL_RE.find_iter(html) {
-        let email_lower = m.as_str().to_lowercase();
+// Only aggregators ask visitors to claim a listing
+static CLAIM_LISTING_PHRASES: &[&str] = &[
+    "claim this business",
+    "claim this listing",
+    "claim your business",
+    "claim your listing",
+    "is this your business",
+];
 
-        if email_lower.len() > 100 {
-            continue;
-        }
+// "Showing 1-20 of 348", "348 results for", "Page 1 of 18"
+static DIRECTORY_PAGINATION_RE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(concat!(
+        r"(?i)\bshowing\s+[0-9]+\s*(?:-|–|to)\s*[0-9]+\s+of\s+(?P<total>[0-9,]+)",
+        r"|\b(?P<count>[0-9,]+)\s+(?:results|businesses|listings|companies|providers)\b",
+        r"|\bpage\s+[0-9]+\s+of\s+(?P<pages>[0-9]+)",
+    ))
+    .unwrap()
+});
 
-        if seen.contains(&email_lower) {
-            continue;
-        }
+static LISTING_HEADING_SEL: LazyLock<Selector> =
+    LazyLock::new(|| Selector::parse("h2, h3, h4").unwrap());
 
-        if is_spam_email(&email_lower) {
-            continue;
-        }
+// Thresholds sized so a 3-4 branch business site stays below each one
+const MIN_LISTING_ITEMS: usize = 6;
+const MIN_LISTING_NAMES: usize = 4;
+const MIN_REPEATED_CTAS: usize = 5;
+const MIN_ITEMLIST_ENTRIES: usize = 5;
+const MIN_PAGINATION_RESULTS: u64 = 30;
+const MIN_PAGINATION_PAGES: u64 = 3;
 
-        if EXCLUDE_EMAIL_RES.iter().any(|re| re.is_match(&email_lower)) {
-            continue;
-        }
+// The document's own <title>: html5ever keeps an SVG <title> in the SVG
+static HEAD_TITLE_SEL: LazyLock<Selector> =
+    LazyLock::new(|| Selector::parse("head > title").unwrap());
+static META_DESCRIPTION_SEL: LazyLock<Selector> =
+    LazyLock::new(|| Selector::parse(r#"meta[name="description" i]"#).unwrap());
+static META_ROBOTS_SEL: LazyLock<Selector> = LazyLock::new(|| {
+    Selector::parse(r#"meta[name="robots" i], meta[name="googlebot" i]"#).unwrap()
+});
+static CANONICAL_LINK_SEL: LazyLock<Selector> =
+    LazyLock::new(|| Selector::parse(r#"link[rel~="canonical" i][href]"#).unwrap());
+static HEADING_SEL: LazyLock<Selector> =
+    LazyLock::new(|| Selector::parse("h1, h2, h3, h4, h5, h6").unwrap());
+static ADDRESS_TAG_SEL: LazyLock<Selector> = LazyLock::new(|| Selector::parse("address").unwrap());
+static ADDRESS_ELEMENT_SEL: LazyLock<Selector> = LazyLock::new(|| {
+    Selector::parse(concat!(
+        r#"[itemprop="address"], [class*="address" i], [id*="address" i], "#,
+        r#"[class*="location" i], [id*="location" i], [class*="contact" i], [id*="contact" i]"#,
+    ))
+    .unwrap()
+});
+static FOOTER_SEL: LazyLock<Selector> =
+    LazyLock::new(|| Selector::parse(r#"footer, [role="contentinfo"]"#).unwrap());
+static IMG_SEL: LazyLock<Selector> = LazyLock::new(|| Selector::parse("img").unwrap());
+static LINK_HREF_SEL: LazyLock<Selector> = LazyLock::new(|| Selector::parse("a[href]").unwrap());
 
-        // Skip hash-like local parts
-        if let Some(pos) = email_lower.find('@') {
-            let local_part = &email_lower[..pos];
-            if local_part.len() > 15 {
-                let hex_count = local_part
-                    .chars()
-                    .filter(|c| matches!(c, '0'..='9' | 'a'..='f'))
-                    .count();
-                if (hex_count as f64 / local_part.len() as f64) > 0.7 {
-                    continue;
-                }
-            }
-        }
+// seo_audit thresholds, in characters except where noted
+const SEO_TITLE_MIN: usize = 30;
+const SEO_TITLE_MAX: usize = 60;
+const SEO_DESCRIPTION_MIN: usize = 70;
+const SEO_DESCRIPTION_MAX: usize = 160;
+const SEO_THIN_CONTENT_WORDS: usize = 300;
+const SEO_LARGE_INLINE_IMAGE_BYTES: usize = 20_000;
+// Pages with fewer images aren't judged on format, srcset or lazy loading
+const SEO_IMAGE_FORMAT_MIN_IMAGES: usize = 5;
+// Render-blocking resources in the head before seo_audit flags them
+const SEO_MAX_BLOCKING_SCRIPTS: usize = 2;
+const SEO_MAX_BLOCKING_STYLES: usize = 4;
+const MIN_DIRECTORY_PHONES: usize = 6;
+const MIN_DIRECTORY_SIGNALS: usize = 3;
 
-        seen.insert(email_lower.clone());
-        valid_emails.push(email_lower);
+// ---------------------------------------------------------------------------
+// E-commerce signatures
+// ---------------------------------------------------------------------------
 
-        if valid_emails.len() >= 5 {
-            break;
-        }
-    }
+// Structural markers only (asset hosts, platform classes, JS globals): the
+// bare word "shop" shows up on plenty of brochure sites
+static ECOMMERCE_SIGNATURES: &[(&str, &[&str])] = &[
+    (
+        "WooCommerce",
+        &[
+            "wc-ajax",
+            "/plugins/woocommerce/",
+            "wc_add_to_cart_params",
+            "woocommerce-cart",
+            "woocommerce-product",
+        ],
+    ),
+    (
+        "Shopify",
+        &[
+            "cdn.shopify.com",
+            "shopify.theme",
+            "myshopify.com",
+            "shopify-section",
+        ],
+    ),
+    (
+        "BigCommerce",
+        &[
+            "cdn11.bigcommerce.com",
+            "bigcommerce.com/s-",
+            "data-stencil-",
+        ],
+    ),
+    (
+        "Magento",
+        &[
+            "data-mage-init",
+            "x-magento-init",
+            "magento_",
+            "mage/cookies",
+        ],
+    ),
+    (
+        "Squarespace Commerce",
+        &[
+            "sqs-add-to-cart-button",
+            "sqs-shopping-cart",
+            "squarespace-commerce",
+        ],
+    ),
+    (
+        "Ecwid",
+        &["app.ecwid.com", "ecwid-productbrowser", "ec-cart-widget"],
+    ),
+    (
+        "Neto",
+        &["cdn.neto.com.au", "netostatic.com", "maropost.com/commerce"],
+    ),
+    ("Wix Stores", &["wixstores", "wix-stores"]),
+];
 
-    valid_emails
-}
+// Script hosts, SDK globals and widget elements; "afterpay" or "paypal" in
+// body copy is not evidence the site takes them
+static PAYMENT_PROVIDER_SIGNATURES: &[(&str, &[&str])] = &[
+    (
+        "Stripe",
+        &[
+            "js.stripe.com",
+            "checkout.stripe.com",
+            "buy.stripe.com",
+            "stripe-buy-button",
+        ],
+    ),
+    (
+        "Square",
+        &[
+            "squarecdn.com",
+            "squareup.com/payments",
+            "square-payment-form",
+        ],
+    ),
+    (
+        "PayPal",
+        &[
+            "paypal.com/sdk",
+            "paypalobjects.com",
+            "paypal-button",
+            "paypal.com/cgi-bin/webscr",
+        ],
+    ),
+    (
+        "Afterpay",
+        &[
+            "static.afterpay.com",
+            "js.afterpay.com",
+            "afterpay-placement",
+            "portal.afterpay.com",
+        ],
+    ),
+    (
+        "Zip",
+        &[
+            "static.zipmoney.com.au",
+            "zip.co/widgets",
+            "zip-widget",
+            "zippay-widget",
+        ],
+    ),
+    (
+        "Klarna",
+        &["klarnaservices.com", "klarna-placement", "klarnacdn.net"],
+    ),
+    (
+        "eWAY",
+        &[
+            "secure.ewaypayments.com",
+            "api.ewaypayments.com",
+            "eway-paynow-button",
+        ],
+    ),
+    (
+        "Tyro",
+        &["tyro.com/pay", "pay.connect.tyro.com", "tyro-pay"],
+    ),
+];
 
-#[pyfunction]
-pub fn extract_phones(html: &str) -> Vec<String> {
-    if html.is_empty() {
-        return Vec::new();
-    }
+// Loader hosts and the globals each snippet sets up
+static CHAT_WIDGET_SIGNATURES: &[(&str, &[&str])] = &[
+    (
+        "Intercom",
+        &[
+            "widget.intercom.io",
+            "js.intercomcdn.com",
+            "intercomsettings",
+        ],
+    ),
+    ("Drift", &["js.driftt.com", "drift.load(", "drift-widget"]),
+    ("Tawk.to", &["embed.tawk.to", "tawk_api"]),
+    (
+        "Crisp",
+        &["client.crisp.chat", "$crisp", "crisp_website_id"],
+    ),
+    (
+        "Zendesk Chat",
+        &[
+            "static.zdassets.com/ekr/snippet.js",
+            "v2.zopim.com",
+            "$zopim",
+            "ze-snippet",
+        ],
+    ),
+    ("LiveChat", &["cdn.livechatinc.com", "__lc.license"]),
+    ("Tidio", &["code.tidio.co", "tidiochatapi"]),
+    (
+        "HubSpot Chat",
+        &["js.usemessages.com", "hubspotconversations"],
+    ),
+    (
+        "Facebook Customer Chat",
+        &["fb-customerchat", "xfbml.customerchat"],
+    ),
+    (
+        "Podium",
+        &["connect.podium.com", "podium-widget", "podiumwebchat"],
+    ),
+];
 
-    let mut phones = Vec::new();
-    let mut seen: HashSet<String> = HashSet::new();
+// Chat launchers from vendors we don't know (or home-grown ones)
+static GENERIC_CHAT_RE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(
+        r#"(?i)(?:class|id)\s*=\s*["'][^"']*\b(?:chat-widget|chat-launcher|chat-bubble|live-chat|livechat|webchat)\b"#,
+    )
+    .unwrap()
+});
 
-    for pattern in PHONE_PATTERNS.iter() {
-        for m in pattern.find_iter(html) {
-            let normalized = normalize_phone(m.as_str());
-            if !normalized.is_empty() && !seen.contains(&normalized) {
-                seen.insert(normalized.clone());
-                phones.push(normalized);
-            }
-        }
-    }
+// A chat widget may be injected by GTM, so its absence can't be confirmed
+const TAG_MANAGER_LOADER: &str = "googletagmanager.com/gtm.js";
 
-    phones
-}
+// Commented-out snippets are common on sites that dropped a vendor
+static HTML_COMMENT_RE: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"(?s)<!--.*?-->").unwrap());
 
-#[pyfunction]
-pub fn detect_cms(html: &str) -> Option<String> {
-    if html.is_empty() {
-        return None;
-    }
+// Tracking hosts and embedded-form markup; a mailchimp.com link in a footer
+// or newsletter credit is not the site running Mailchimp
+static MARKETING_TOOL_SIGNATURES: &[(&str, &[&str])] = &[
+    (
+        "HubSpot",
+        &[
+            "js.hs-scripts.com",
+            "js.hsforms.net",
+            "hbspt.forms",
+            "js.hs-analytics.net",
+        ],
+    ),
+    (
+        "ActiveCampaign",
+        &["trackcmp.net", "activehosted.com/f/embed"],
+    ),
+    (
+        "Klaviyo",
+        &["static.klaviyo.com", "_learnq", "klaviyo-form-"],
+    ),
+    (
+        "Mailchimp",
+        &[
+            "chimpstatic.com",
+            "mc-embedded-subscribe",
+            "list-manage.com/subscribe/post",
+        ],
+    ),
+    ("Pardot", &["pi.pardot.com", "go.pardot.com", "piaid"]),
+    (
+        "Marketo",
+        &["munchkin.marketo.net", "munchkin.init", "mktoforms2"],
+    ),
+    (
+        "Omnisend",
+        &["omnisnippet1.com", "omnisrc.com", "omnisend.com/inshop"],
+    ),
+];
 
-    let html_lower = html.to_lowercase();
+// Review widget scripts and container classes; a link to the business's
+// Trustpilot or Google reviews page is not a widget
+static REVIEW_WIDGET_SIGNATURES: &[(&str, &[&str])] = &[
+    (
+        "Trustpilot",
+        &["widget.trustpilot.com/bootstrap", "trustpilot-widget"],
+    ),
+    (
+        "Elfsight",
+        &[
+            "eapps-google-reviews",
+            "eapps-all-in-one-reviews",
+            "eapps-facebook-reviews",
+            "eapps-yelp-reviews",
+        ],
+    ),
+    (
+        "EmbedSocial",
+        &["embedsocial-reviews", "embedsocial.com/cdn/ri"],
+    ),
+    (
+        "ProductReview",
+        &["productreview.com.au/widget", "productreview-widget"],
+    ),
+    (
+        "Yotpo",
+        &[
+            "staticw2.yotpo.com",
+            "cdn-widgetsrepository.yotpo.com",
+            "yotpo-main-widget",
+            "yotpo-widget-instance",
+        ],
+    ),
+    (
+        "Judge.me",
+        &[
+            "cdn.judge.me",
+            "cdnwidget.judge.me",
+            "jdgm-widget",
+            "jdgm-preview-badge",
+        ],
+    ),
+    (
+        "Stamped",
+        &[
+            "cdn1.stamped.io",
+            "stamped-main-widget",
+            "stamped-product-reviews-badge",
+        ],
+    ),
+    ("Birdeye", &["birdeye.com/embed", "birdeye.com/badge"]),
+    ("Podium", &["podium-review-widget", "reviews.podium.com"]),
+];
 
-    for (cms_name, signatures) in CMS_SIGNATURES.iter() {
-        for sig in signatures {
-            if html_lower.contains(&sig.to_lowercase()) {
-                return Some(cms_name.to_string());
-            }
-        }
-    }
+// Class names of the common Google reviews widgets and plugins, with the
+// tag they're on (a link styled "google-review-link" is not a widget)
+static GOOGLE_REVIEWS_CLASS_RE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(
+        r#"(?i)<([a-z][a-z0-9-]*)\b[^>]*?\sclass\s*=\s*["'](?:[^"']*\s)?(?:wp-)?google-reviews?(?:[-_][\w-]*)?["'\s]"#,
+    )
+    .unwrap()
+});
+
+// Add-to-cart buttons and cart links on stores we don't recognise
+static CART_RE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(concat!(
+        r#"(?i)\badd[-_ ]to[-_ ](?:cart|bag|basket)\b"#,
+        r#"|href\s*=\s*["'][^"']*/(?:cart|basket|checkout)/?["'?#]"#,
+        r#"|class\s*=\s*["'][^"']*\b(?:cart-count|mini-cart|cart-icon)\b"#,
+    ))
+    .unwrap()
+});
+
+// ---------------------------------------------------------------------------
+// Social profile links
+// ---------------------------------------------------------------------------
 
-    None
+static ANCHOR_HREF_RE: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r#"(?is)<a\b[^>]*?\shref\s*=\s*["']([^"']+)["']"#).unwrap());
+
+/// A social platform: its hosts, and which paths are a profile. With
+/// `profile_prefixes` set only those paths count; otherwise any path not in
+/// `excluded_prefixes` (share buttons, intents, embeds) does. A path with
+/// one of `excluded_segments` after the profile (a single post) never does.
+struct SocialPlatform {
+    name: &'static str,
+    hosts: &'static [&'static str],
+    profile_prefixes: &'static [&'static str],
+    excluded_prefixes: &'static [&'static str],
+    excluded_segments: &'static [&'static str],
 }
 
-#[pyfunction]
-pub fn detect_tracking(html: &str) -> HashMap<String, bool> {
-    let mut result = HashMap::new();
-    result.insert("google_analytics".to_string(), false);
-    result.insert("facebook_pixel".to_string(), false);
-    result.insert("google_ads".to_string(), false);
+static SOCIAL_PLATFORMS: &[SocialPlatform] = &[
+    SocialPlatform {
+        name: "facebook",
+        hosts: &["facebook.com", "fb.com"],
+        profile_prefixes: &[],
+        excluded_prefixes: &[
+            "/sharer",
+            "/share",
+            "/dialog/",
+            "/plugins/",
+            "/tr",
+            "/hashtag/",
+            "/photo",
+            "/watch",
+            "/story.php",
+            "/permalink.php",
+            "/login",
+            "/events/",
+            "/l.php",
+            "/groups/",
+            "/help",
+            "/policies",
+            "/privacy",
+            "/business/",
+        ],
+        excluded_segments: &["posts", "videos", "photos"],
+    },
+    SocialPlatform {
+        name: "instagram",
+        hosts: &["instagram.com"],
+        profile_prefixes: &[],
+        excluded_prefixes: &[
+            "/p/",
+            "/reel/",
+            "/reels/",
+            "/tv/",
+            "/explore/",
+            "/stories/",
+            "/accounts/",
+            "/embed",
+            "/direct/",
+        ],
+        excluded_segments: &[],
+    },
+    SocialPlatform {
+        name: "linkedin_company",
+        hosts: &["linkedin.com"],
+        profile_prefixes: &["/company/", "/school/", "/showcase/"],
+        excluded_prefixes: &[],
+        excluded_segments: &[],
+    },
+    SocialPlatform {
+        name: "linkedin_personal",
+        hosts: &["linkedin.com"],
+        profile_prefixes: &["/in/"],
+        excluded_prefixes: &[],
+        excluded_segments: &[],
+    },
+    SocialPlatform {
+        name: "youtube",
+        hosts: &["youtube.com"],
+        profile_prefixes: &["/channel/", "/c/", "/user/", "/@"],
+        excluded_prefixes: &[],
+        excluded_segments: &[],
+    },
+    SocialPlatform {
+        name: "tiktok",
+        hosts: &["tiktok.com"],
+        profile_prefixes: &["/@"],
+        excluded_prefixes: &[],
+        excluded_segments: &["video"],
+    },
+    SocialPlatform {
+        name: "x_twitter",
+        hosts: &["x.com", "twitter.com"],
+        profile_prefixes: &[],
+        excluded_prefixes: &[
+            "/intent/",
+            "/share",
+            "/home",
+            "/hashtag/",
+            "/search",
+            "/i/",
+            "/explore",
+            "/login",
+            "/signup",
+            "/privacy",
+            "/tos",
+        ],
+        excluded_segments: &["status"],
+    },
+    SocialPlatform {
+        name: "pinterest",
+        hosts: &["pinterest.com", "pinterest.com.au"],
+        profile_prefixes: &[],
+        excluded_prefixes: &["/pin/", "/search/", "/ideas/", "/today/", "/_/"],
+        excluded_segments: &[],
+    },
+];
 
-    if html.is_empty() {
-        return result;
-    }
+/// Embedded posts (Instagram, X, TikTok) link to their author's profile,
+/// which is rarely the business itself.
+static SOCIAL_EMBED_RE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(
+        r#"(?is)<blockquote\b[^>]*\bclass\s*=\s*["'][^"']*\b(?:instagram-media|twitter-tweet|tiktok-embed)\b[^>]*>.*?</blockquote\s*>"#,
+    )
+    .unwrap()
+});
 
-    let html_lower = html.to_lowercase();
+static PAGE_CHROME_RE: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"(?is)<(?:header|footer)\b.*?</(?:header|footer)\s*>").unwrap());
 
-    for (tracker, signatures) in TRACKING_SIGNATURES.iter() {
-        for sig in signatures {
-            if html_lower.contains(sig) {
-                result.insert(tracker.to_string(), true);
-                break;
-            }
+static PAGE_URL_TAG_RE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(
+        r#"(?is)<(?:link\b[^>]*\brel\s*=\s*["']?canonical\b|meta\b[^>]*\bproperty\s*=\s*["']?og:url\b)[^>]*>"#,
+    )
+    .unwrap()
+});
+
+static PAGE_URL_ATTR_RE: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r#"(?i)\b(?:href|content)\s*=\s*["']([^"']+)["']"#).unwrap());
+
+pub(crate) static TITLE_TEXT_RE: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"(?is)<title\b[^>]*>(.*?)</title\s*>").unwrap());
+
+static COPYRIGHT_MARKER_RE: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"(?i)©|\(c\)|\bcopyright\b").unwrap());
+
+/// The same markers in raw markup, where © may still be an entity.
+static COPYRIGHT_MARKUP_RE: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"(?i)©|&copy;|&#0*169;|&#x0*a9;|\(c\)|\bcopyright\b").unwrap());
+
+/// Years following a copyright marker: "2019", "2012–2019", "2012-19",
+/// "2015, 2018 & 2021", with a trailing dash kept for "2015 - " ranges
+/// whose end is filled in by script.
+static COPYRIGHT_YEARS_RE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(
+        r"^[^0-9]{0,60}?\b((?:19|20)[0-9]{2}(?:\s*(?:[-–—,/&]|to|and)\s*(?:(?:19|20)[0-9]{2}|[0-9]{2})\b)*)(\s*[-–—])?",
+    )
+    .unwrap()
+});
+
+static DYNAMIC_YEAR_RE: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"get(?:UTC)?FullYear\s*\(\s*\)").unwrap());
+
+// ---------------------------------------------------------------------------
+// Single-pass signature scan
+// ---------------------------------------------------------------------------
+
+/// Every literal signature from the tables above compiled into one
+/// Aho-Corasick automaton, so a page is lowercased and scanned once however
+/// many detectors look at it. Rebuilt when a runtime table changes.
+struct SignatureIndex {
+    automaton: AhoCorasick,
+    ids: HashMap<String, usize>,
+}
+
+static SIGNATURE_INDEX: LazyLock<RwLock<Arc<SignatureIndex>>> =
+    LazyLock::new(|| RwLock::new(Arc::new(build_signature_index())));
+
+fn build_signature_index() -> SignatureIndex {
+    let mut literals: Vec<String> = Vec::new();
+    let mut add = |sig: &str| literals.push(sig.to_string());
+
+    let static_tables = [
+        PAGE_BUILDER_SIGNATURES,
+        WP_PLUGIN_MARKERS,
+        PARKING_PROVIDER_SIGNATURES,
+        PLACEHOLDER_PLUGIN_SIGNATURES,
+        ECOMMERCE_SIGNATURES,
+        PAYMENT_PROVIDER_SIGNATURES,
+        CHAT_WIDGET_SIGNATURES,
+        MARKETING_TOOL_SIGNATURES,
+        REVIEW_WIDGET_SIGNATURES,
+        EMAIL_CAPTURE_SIGNATURES,
+        TRANSLATION_SIGNATURES,
+    ];
+    for table in static_tables {
+        table
+            .iter()
+            .flat_map(|(_, sigs)| sigs.iter())
+            .for_each(|sig| add(sig));
+    }
+    for (_, sigs) in TRACKING_SIGNATURES.iter() {
+        sigs.iter().for_each(|sig| add(sig));
+    }
+    for sig in PARKING_PHRASES
+        .iter()
+        .chain(PARKING_AD_SCRIPTS)
+        .chain(GENERIC_BOOKING_SIGNATURES)
+    {
+        add(sig);
+    }
+    for (_, _, sigs) in PLACEHOLDER_SIGNATURES {
+        sigs.iter().for_each(|sig| add(sig));
+    }
+    add(GUTENBERG_BLOCK_MARKER);
+    add(TAG_MANAGER_LOADER);
+    for table in [&CMS_SIGNATURES, &BOOKING_SIGNATURES] {
+        for (_, sigs) in table.read().unwrap().iter() {
+            sigs.iter().for_each(|sig| add(sig));
         }
     }
-
-    result
+    signature_index_of(&literals)
 }
 
-#[pyfunction]
-pub fn detect_booking_system(html: &str) -> bool {
-    if html.is_empty() {
-        return false;
+/// An index over `literals`; a repeated literal keeps its first id.
+fn signature_index_of(literals: &[String]) -> SignatureIndex {
+    let mut ids: HashMap<String, usize> = HashMap::new();
+    let mut unique: Vec<&str> = Vec::new();
+    for literal in literals {
+        if !ids.contains_key(literal) {
+            ids.insert(literal.clone(), unique.len());
+            unique.push(literal);
+        }
     }
 
-    let html_lower = html.to_lowercase();
+    let automaton = AhoCorasick::builder()
+        .match_kind(MatchKind::Standard)
+        .kind(Some(AhoCorasickKind::DFA))
+        .build(&unique)
+        .expect("signature literals are plain strings");
+    SignatureIndex { automaton, ids }
+}
 
-    BOOKING_SIGNATURES.iter().any(|sig| html_lower.contains(&sig.to_lowercase()))
+fn rebuild_signature_index() {
+    *SIGNATURE_INDEX.write().unwrap() = Arc::new(build_signature_index());
 }
 
-#[pyfunction]
-pub fn detect_frameworks(html: &str) -> Vec<String> {
-    if html.is_empty() {
-        return Vec::new();
+/// A lowercased page with the first offset and hit count of every indexed
+/// signature, gathered in one pass. `find_live` skips hits inside HTML
+/// comments. Lookups for a literal the index doesn't know fall back to a
+/// plain substring search, so results never depend on the index being
+/// current.
+#[derive(Clone)]
+struct PageScan {
+    lower: String,
+    index: Arc<SignatureIndex>,
+    first: Vec<Option<usize>>,
+    first_live: Vec<Option<usize>>,
+    counts: Vec<usize>,
+    comments: Vec<std::ops::Range<usize>>,
+}
+
+impl PageScan {
+    fn new(html: &str) -> Self {
+        Self::with_index(html, Arc::clone(&SIGNATURE_INDEX.read().unwrap()))
     }
 
-    let html_lower = html.to_lowercase();
-    let mut frameworks = Vec::new();
+    /// A scan that indexes nothing, so every lookup is the plain substring
+    /// search the detectors ran before the signature index.
+    #[cfg(any(test, feature = "bench"))]
+    fn unindexed(html: &str) -> Self {
+        Self::with_index(html, Arc::new(signature_index_of(&[])))
+    }
 
-    for (name, signatures) in FRAMEWORK_SIGNATURES.iter() {
-        for sig in signatures {
-            if html_lower.contains(sig) {
-                frameworks.push(name.to_string());
-                break;
+    fn with_index(html: &str, index: Arc<SignatureIndex>) -> Self {
+        let lower = html.to_lowercase();
+        let comments = comment_ranges(&lower);
+
+        let patterns = index.automaton.patterns_len();
+        let mut first = vec![None; patterns];
+        let mut first_live = vec![None; patterns];
+        let mut counts = vec![0; patterns];
+        // Counts skip a pattern's own overlapping repeats, like str::matches
+        let mut next_count_at = vec![0; patterns];
+        for m in index.automaton.find_overlapping_iter(&lower) {
+            let id = m.pattern().as_usize();
+            if m.start() >= next_count_at[id] {
+                counts[id] += 1;
+                next_count_at[id] = m.end();
             }
+            first[id].get_or_insert(m.start());
+            if first_live[id].is_none() && !in_ranges(&comments, m.start()) {
+                first_live[id] = Some(m.start());
+            }
+        }
+
+        PageScan {
+            lower,
+            index,
+            first,
+            first_live,
+            counts,
+            comments,
         }
     }
 
-    frameworks
-}
+    fn find(&self, sig: &str) -> Option<usize> {
+        match self.index.ids.get(sig) {
+            Some(&id) => self.first[id],
+            None => self.lower.find(sig),
+        }
+    }
 
-#[pyfunction]
-pub fn detect_responsive(html: &str) -> bool {
-    if html.is_empty() {
-        return false;
+    fn contains(&self, sig: &str) -> bool {
+        self.find(sig).is_some()
     }
 
-    let html_lower = html.to_lowercase();
+    fn find_live(&self, sig: &str) -> Option<usize> {
+        match self.index.ids.get(sig) {
+            Some(&id) => self.first_live[id],
+            None => self
+                .lower
+                .match_indices(sig)
+                .map(|(pos, _)| pos)
+                .find(|pos| !in_ranges(&self.comments, *pos)),
+        }
+    }
 
-    RESPONSIVE_INDICATORS.iter().any(|ind| html_lower.contains(ind))
+    fn count(&self, sig: &str) -> usize {
+        match self.index.ids.get(sig) {
+            Some(&id) => self.counts[id],
+            None => self.lower.matches(sig).count(),
+        }
+    }
 }
 
-#[pyfunction]
-pub fn analyze_tech_stack(py: Python<'_>, html: &str) -> PyResult<PyObject> {
-    let dict = PyDict::new(py);
-
-    let cms = detect_cms(html);
-    let tracking = detect_tracking(html);
-    let has_booking = detect_booking_system(html);
-    let frameworks = detect_frameworks(html);
-    let has_responsive = detect_responsive(html);
+/// Byte ranges of the HTML comments in `html`, in order.
+pub(crate) fn comment_ranges(html: &str) -> Vec<std::ops::Range<usize>> {
+    HTML_COMMENT_RE.find_iter(html).map(|m| m.range()).collect()
+}
 
-    match cms {
-        Some(ref v) => dict.set_item("cms", v)?,
-        None => dict.set_item("cms", py.None())?,
-    }
+/// Whether `pos` falls inside one of the sorted, non-overlapping `ranges`.
+pub(crate) fn in_ranges(ranges: &[std::ops::Range<usize>], pos: usize) -> bool {
+    let idx = ranges.partition_point(|r| r.end <= pos);
+    ranges.get(idx).is_some_and(|r| r.start <= pos)
+}
 
-    let tracking_dict = PyDict::new(py);
-    for (k, v) in &tracking {
-        tracking_dict.set_item(k, *v)?;
-    }
-    dict.set_item("tracking", tracking_dict)?;
+// ---------------------------------------------------------------------------
+// Helpers
+// ---------------------------------------------------------------------------
 
-    dict.set_item("has_booking", has_booking)?;
-    dict.set_item("frameworks", &frameworks)?;
-    dict.set_item("has_ssl", false)?;
-    dict.set_item("has_responsive", has_responsive)?;
+pub(crate) fn is_spam_email(email: &str) -> bool {
+    // Check domain blocklist
+    if let Some(pos) = email.rfind('@') {
+        let domain = &email[pos + 1..];
+        if SPAM_EMAIL_DOMAINS.contains(domain) {
+            return true;
+        }
+    }
+
+    // Check spam patterns
+    for re in SPAM_EMAIL_RES.iter() {
+        if re.is_match(email) {
+            return true;
+        }
+    }
+
+    false
+}
+
+/// Placeholder, asset-filename and CDN addresses (example.com, logo@2x.png, ...).
+pub(crate) fn matches_exclude_pattern(email: &str) -> bool {
+    EXCLUDE_EMAIL_RES.iter().any(|re| re.is_match(email))
+}
+
+/// Length, spam, exclude-pattern and hash-like local part checks shared by
+/// every email extraction path. Expects a lowercased address.
+pub(crate) fn is_rejected_email(email_lower: &str) -> bool {
+    if email_lower.len() > 100 {
+        return true;
+    }
+
+    if is_spam_email(email_lower) || is_disposable_email(email_lower) {
+        return true;
+    }
+
+    if matches_exclude_pattern(email_lower) {
+        return true;
+    }
+
+    // Skip hash-like local parts
+    if let Some(pos) = email_lower.find('@') {
+        if is_hash_like_local_part(&email_lower[..pos]) {
+            return true;
+        }
+    }
+
+    false
+}
+
+/// Long local parts that are mostly hex digits ("5f2b9c0e41d7a8...") are
+/// tracking hashes or generated ids rather than a mailbox anyone reads.
+pub(crate) fn is_hash_like_local_part(local_part: &str) -> bool {
+    if local_part.len() <= 15 {
+        return false;
+    }
+    let hex_count = local_part
+        .chars()
+        .filter(|c| matches!(c, '0'..='9' | 'a'..='f'))
+        .count();
+    (hex_count as f64 / local_part.len() as f64) > 0.7
+}
+
+/// Decode a Cloudflare email-protection payload: the first byte is the XOR
+/// key for every following byte.
+fn decode_cfemail(hex: &str) -> Option<String> {
+    if hex.len() < 4 || !hex.len().is_multiple_of(2) {
+        return None;
+    }
+
+    let bytes: Vec<u8> = (0..hex.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(hex.get(i..i + 2)?, 16).ok())
+        .collect::<Option<_>>()?;
+
+    let key = bytes[0];
+    let decoded: Vec<u8> = bytes[1..].iter().map(|b| b ^ key).collect();
+    let email = String::from_utf8(decoded).ok()?.to_lowercase();
+
+    is_whole_email(&email).then_some(email)
+}
+
+/// True when EMAIL_RE matches the entire string, not just part of it.
+pub(crate) fn is_whole_email(candidate: &str) -> bool {
+    EMAIL_RE
+        .find(candidate)
+        .is_some_and(|m| m.start() == 0 && m.end() == candidate.len())
+}
+
+fn percent_decode(s: &str) -> String {
+    let bytes = s.as_bytes();
+    let mut out = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+
+    while i < bytes.len() {
+        if bytes[i] == b'%' {
+            if let Some(b) = s
+                .get(i + 1..i + 3)
+                .and_then(|h| u8::from_str_radix(h, 16).ok())
+            {
+                out.push(b);
+                i += 3;
+                continue;
+            }
+        }
+        out.push(bytes[i]);
+        i += 1;
+    }
+
+    String::from_utf8_lossy(&out).into_owned()
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum EmailSource {
+    Mailto,
+    Attribute,
+    Text,
+    Script,
+}
+
+impl EmailSource {
+    fn as_str(self) -> &'static str {
+        match self {
+            EmailSource::Mailto => "mailto",
+            EmailSource::Attribute => "attribute",
+            EmailSource::Text => "text",
+            EmailSource::Script => "script",
+        }
+    }
+
+    /// Mailto links first, script blobs (which also carry vendor and
+    /// tracking addresses) last.
+    fn rank(self) -> u8 {
+        match self {
+            EmailSource::Mailto => 0,
+            EmailSource::Attribute | EmailSource::Text => 1,
+            EmailSource::Script => 2,
+        }
+    }
+}
+
+struct EmailHit {
+    email: String,
+    source: EmailSource,
+    offset: usize,
+}
+
+/// True when `offset` falls inside a tag (`<a href="...">`) rather than in text.
+fn is_inside_tag(text: &str, offset: usize) -> bool {
+    let before = &text.as_bytes()[..offset];
+    let last_open = before.iter().rposition(|&b| b == b'<');
+    let last_close = before.iter().rposition(|&b| b == b'>');
+    match (last_open, last_close) {
+        (Some(open), Some(close)) => open > close,
+        (Some(_), None) => true,
+        _ => false,
+    }
+}
+
+/// Every valid address on the page, deduplicated and ranked: mailto links
+/// first, then Cloudflare-protected addresses, then attribute and text hits
+/// in page order, then addresses that only appear inside script blocks.
+/// Matching runs on the de-obfuscated page, but offsets and the
+/// tag/script classification refer to the original `html`.
+fn collect_email_hits(html: &str) -> Vec<EmailHit> {
+    let page = deobfuscate_emails(html);
+    let text = page.text.as_str();
+    let mut hits: Vec<EmailHit> = Vec::new();
+
+    let script_ranges: Vec<std::ops::Range<usize>> = SCRIPT_BODY_RE
+        .captures_iter(html)
+        .filter_map(|caps| caps.get(1).map(|m| m.range()))
+        .collect();
+    let in_script = |offset: usize| script_ranges.iter().any(|r| r.contains(&offset));
+
+    for caps in MAILTO_RE.captures_iter(text) {
+        let target = caps.get(1).unwrap();
+        let addresses = target.as_str().split('?').next().unwrap_or_default();
+        for addr in percent_decode(addresses).split([',', ';']) {
+            let email = addr.trim().to_lowercase();
+            if is_whole_email(&email) {
+                hits.push(EmailHit {
+                    email,
+                    source: EmailSource::Mailto,
+                    offset: page.source_offset(target.start()),
+                });
+            }
+        }
+    }
+
+    if text.contains("cfemail") || text.contains("email-protection#") {
+        for caps in CFEMAIL_RE.captures_iter(text) {
+            if let Some(email) = decode_cfemail(&caps[1]) {
+                hits.push(EmailHit {
+                    email,
+                    source: EmailSource::Attribute,
+                    offset: page.source_offset(caps.get(1).unwrap().start()),
+                });
+            }
+        }
+    }
+
+    // data-email="info%40acme.com.au" and friends
+    for caps in EMAIL_ATTRIBUTE_RE.captures_iter(text) {
+        let value = caps.get(1).unwrap();
+        let decoded = percent_decode(value.as_str());
+        let email = decoded.trim().trim_start_matches("mailto:").to_lowercase();
+        if is_whole_email(&email) {
+            hits.push(EmailHit {
+                email,
+                source: EmailSource::Attribute,
+                offset: page.source_offset(value.start()),
+            });
+        }
+    }
+
+    for m in EMAIL_RE.find_iter(text) {
+        let offset = page.source_offset(m.start());
+        let source = if in_script(offset) {
+            EmailSource::Script
+        } else if is_inside_tag(html, offset) {
+            EmailSource::Attribute
+        } else {
+            EmailSource::Text
+        };
+        hits.push(EmailHit {
+            email: m.as_str().to_lowercase(),
+            source,
+            offset,
+        });
+    }
+
+    // The stable sort keeps Cloudflare decodes ahead of regex hits and
+    // everything else in page order within each rank.
+    hits.sort_by_key(|h| h.source.rank());
+
+    let mut seen: HashSet<String> = HashSet::new();
+    hits.retain(|h| !is_rejected_email(&h.email) && seen.insert(h.email.clone()));
+    hits
+}
+
+fn named_entity(name: &str) -> Option<char> {
+    let c = match name {
+        "amp" => '&',
+        "lt" => '<',
+        "gt" => '>',
+        "quot" => '"',
+        "apos" => '\'',
+        "nbsp" => ' ',
+        "commat" => '@',
+        "period" => '.',
+        "lowbar" => '_',
+        "hyphen" | "dash" => '-',
+        "plus" => '+',
+        "percnt" => '%',
+        // Typographic entities common in visible text and meta tags
+        "ndash" => '–',
+        "mdash" => '—',
+        "hellip" => '…',
+        "lsquo" => '‘',
+        "rsquo" => '’',
+        "ldquo" => '“',
+        "rdquo" => '”',
+        "laquo" => '«',
+        "raquo" => '»',
+        "middot" => '·',
+        "bull" => '•',
+        "copy" => '©',
+        "reg" => '®',
+        "trade" => '™',
+        "deg" => '°',
+        "times" => '×',
+        "eacute" => 'é',
+        "egrave" => 'è',
+        "aacute" => 'á',
+        "agrave" => 'à',
+        "ouml" => 'ö',
+        "uuml" => 'ü',
+        "auml" => 'ä',
+        _ => return None,
+    };
+    Some(c)
+}
+
+/// Decode numeric and the common named HTML entities; unknown entities are
+/// left untouched.
+pub(crate) fn decode_html_entities(html: &str) -> Cow<'_, str> {
+    if !html.contains('&') {
+        return Cow::Borrowed(html);
+    }
+
+    HTML_ENTITY_RE.replace_all(html, decode_entity)
+}
+
+fn decode_entity(caps: &regex::Captures) -> String {
+    let decoded = if let Some(dec) = caps.get(1) {
+        dec.as_str().parse::<u32>().ok().and_then(char::from_u32)
+    } else if let Some(hex) = caps.get(2) {
+        u32::from_str_radix(hex.as_str(), 16)
+            .ok()
+            .and_then(char::from_u32)
+    } else {
+        caps.get(3).and_then(|n| named_entity(n.as_str()))
+    };
+    match decoded {
+        Some(c) => c.to_string(),
+        None => caps[0].to_string(),
+    }
+}
+
+/// Rebuild a spelled-out address, or None when the match is a plain address,
+/// ordinary prose, or an invalid reconstruction.
+fn rebuild_obfuscated_email(caps: &regex::Captures) -> Option<String> {
+    let at = caps["at"].trim();
+    let domain = &caps["domain"];
+    let spelled_dot = SPELLED_DOT_RE.is_match(domain);
+
+    if at == "@" && !spelled_dot {
+        return None;
+    }
+    // A bare " at " reads as prose when the word before it is an everyday
+    // word ("meet at home dot com", "US AT ACME.COM"); only the bracketed
+    // forms are trusted there
+    if at.eq_ignore_ascii_case("at") {
+        let local = caps["local"].to_ascii_lowercase();
+        if PROSE_WORDS_BEFORE_AT.contains(&local.as_str()) {
+            return None;
+        }
+        // A lowercase one also needs a spelled-out dot, otherwise "call
+        // reception at home.com" style prose would be rewritten
+        if at != "AT" && !spelled_dot {
+            return None;
+        }
+    }
+
+    let email = format!(
+        "{}@{}",
+        &caps["local"],
+        SPELLED_DOT_RE.replace_all(domain, ".")
+    );
+    if email.contains(char::is_whitespace) || email.matches('@').count() != 1 {
+        return None;
+    }
+    Some(email)
+}
+
+/// Decode a \uXXXX escape that spells an email character; any other escape
+/// becomes a space so its hex digits can't glue onto a neighbouring
+/// address ("\u003einfo@acme.com").
+fn decode_unicode_escape(caps: &regex::Captures) -> String {
+    u32::from_str_radix(&caps[1], 16)
+        .ok()
+        .and_then(char::from_u32)
+        .filter(|c| c.is_ascii_alphanumeric() || matches!(c, '@' | '.' | '_' | '%' | '+' | '-'))
+        .map_or_else(|| " ".to_string(), |c| c.to_string())
+}
+
+/// One replacement made by a decoding pass: `out` is the span in the
+/// decoded text, `src` the span of the input it replaced.
+struct Splice {
+    out: std::ops::Range<usize>,
+    src: std::ops::Range<usize>,
+}
+
+/// Regex::replace_all that also records every splice it made, so offsets
+/// in the result can be mapped back to the input.
+fn replace_all_mapped(
+    text: &str,
+    re: &Regex,
+    mut replacement: impl FnMut(&regex::Captures) -> String,
+) -> (String, Vec<Splice>) {
+    let mut out = String::with_capacity(text.len());
+    let mut splices = Vec::new();
+    let mut last = 0;
+    for caps in re.captures_iter(text) {
+        let m = caps.get(0).unwrap();
+        let rep = replacement(&caps);
+        if rep == m.as_str() {
+            continue;
+        }
+        out.push_str(&text[last..m.start()]);
+        let start = out.len();
+        out.push_str(&rep);
+        splices.push(Splice {
+            out: start..out.len(),
+            src: m.range(),
+        });
+        last = m.end();
+    }
+    out.push_str(&text[last..]);
+    (out, splices)
+}
+
+/// The page after the email de-obfuscation pre-pass, with the splices of
+/// each pass so hits can be reported against the original input.
+struct DeobfuscatedPage {
+    text: String,
+    passes: Vec<Vec<Splice>>,
+}
+
+impl DeobfuscatedPage {
+    fn apply(&mut self, re: &Regex, replacement: impl FnMut(&regex::Captures) -> String) {
+        if !re.is_match(&self.text) {
+            return;
+        }
+        let (text, splices) = replace_all_mapped(&self.text, re, replacement);
+        self.text = text;
+        self.passes.push(splices);
+    }
+
+    /// Byte offset in the original input of `offset` in the decoded text.
+    /// Offsets inside a replaced span map to the start of what it replaced.
+    fn source_offset(&self, offset: usize) -> usize {
+        self.passes.iter().rev().fold(offset, |offset, splices| {
+            let i = splices.partition_point(|s| s.out.start <= offset);
+            match i.checked_sub(1).map(|j| &splices[j]) {
+                None => offset,
+                Some(s) if offset < s.out.end => s.src.start,
+                Some(s) => s.src.end + (offset - s.out.end),
+            }
+        })
+    }
+}
+
+/// De-obfuscation pre-pass run before EMAIL_RE: decodes HTML entities and
+/// \uXXXX escapes, and rewrites "[at]" / "[dot]" spellings into literal
+/// addresses in place.
+fn deobfuscate_emails(html: &str) -> DeobfuscatedPage {
+    let mut page = DeobfuscatedPage {
+        text: html.to_string(),
+        passes: Vec::new(),
+    };
+    page.apply(&HTML_ENTITY_RE, decode_entity);
+    page.apply(&UNICODE_ESCAPE_RE, decode_unicode_escape);
+    page.apply(&OBFUSCATED_EMAIL_RE, |caps| {
+        rebuild_obfuscated_email(caps).unwrap_or_else(|| caps[0].to_string())
+    });
+    page
+}
+
+fn format_au_number(digits: &str) -> String {
+    if digits.len() == 9 {
+        if digits.starts_with('4') {
+            // Mobile: 0XXX XXX XXX
+            format!("0{} {} {}", &digits[0..3], &digits[3..6], &digits[6..])
+        } else {
+            // Landline: 0X XXXX XXXX
+            format!("0{} {} {}", &digits[0..1], &digits[1..5], &digits[5..])
+        }
+    } else {
+        digits.to_string()
+    }
+}
+
+pub(crate) fn normalize_phone(phone: &str) -> String {
+    if phone.is_empty() {
+        return String::new();
+    }
+
+    // ASCII-only from here on, so the byte slices below are char boundaries
+    let digits = PHONE_NORMALIZE_RE.replace_all(phone, "").to_string();
+
+    // Count actual digits (excluding +)
+    let digit_count = digits.chars().filter(|c| c.is_ascii_digit()).count();
+    if digit_count < 8 {
+        return String::new();
+    }
+
+    if let Some(rest) = digits.strip_prefix("+61") {
+        format_au_number(rest.strip_prefix('0').unwrap_or(rest))
+    } else if let Some(rest) = digits.strip_prefix('0') {
+        format_au_number(rest)
+    } else if digits.starts_with("1300") || digits.starts_with("1800") {
+        format!("{} {} {}", &digits[..4], &digits[4..7], &digits[7..])
+    } else if digits.starts_with("13") && digits.len() == 6 {
+        format!("{} {} {}", &digits[..2], &digits[2..4], &digits[4..])
+    } else {
+        phone.trim().to_string()
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum PhoneLabel {
+    Office,
+    Mobile,
+    Fax,
+    AfterHours,
+    Sales,
+    Support,
+    Unknown,
+}
+
+impl PhoneLabel {
+    fn as_str(self) -> &'static str {
+        match self {
+            PhoneLabel::Office => "office",
+            PhoneLabel::Mobile => "mobile",
+            PhoneLabel::Fax => "fax",
+            PhoneLabel::AfterHours => "after_hours",
+            PhoneLabel::Sales => "sales",
+            PhoneLabel::Support => "support",
+            PhoneLabel::Unknown => "unknown",
+        }
+    }
+}
+
+/// Classify a phone number by the label keyword closest to it in `context`.
+/// A specific label (fax, mobile, after hours, sales, support) beats the
+/// generic "phone"/"tel" words wherever they sit, so "Mobile phone:" is
+/// mobile. Unlabelled 04xx numbers fall back to mobile.
+fn classify_phone_context(context: &str, normalized: &str) -> PhoneLabel {
+    let mut best: Option<(usize, PhoneLabel)> = None;
+    let mut generic = false;
+
+    for (label, re) in PHONE_LABEL_PATTERNS.iter() {
+        if let Some(m) = re.find_iter(context).last() {
+            if *label == PhoneLabel::Office {
+                generic = true;
+            } else if best.is_none_or(|(pos, _)| m.start() > pos) {
+                best = Some((m.start(), *label));
+            }
+        }
+    }
+
+    match best {
+        Some((_, label)) => label,
+        None if generic => PhoneLabel::Office,
+        None if normalized.starts_with("04") => PhoneLabel::Mobile,
+        None => PhoneLabel::Unknown,
+    }
+}
+
+/// Flatten the visible text of a document, one space between text nodes so
+/// that labels in sibling cells (`<td>`, `<dt>`/`<dd>`) stay adjacent to
+/// the values they describe.
+fn visible_text(html: &str) -> String {
+    visible_text_in(&Html::parse_document(html))
+}
+
+fn visible_text_in(document: &Html) -> String {
+    joined_text(document.root_element())
+}
+
+/// The visible text nodes under `element`, each trimmed, joined with
+/// spaces so inline pieces ("<span>12 Smith St</span><span>Richmond</span>")
+/// stay separate words.
+fn joined_text(element: ElementRef) -> String {
+    let mut parts: Vec<&str> = Vec::new();
+
+    for node in element.descendants() {
+        if let Node::Text(text) = node.value() {
+            let hidden = node.ancestors().any(|a| {
+                a.value()
+                    .as_element()
+                    .is_some_and(|e| INVISIBLE_ELEMENTS.contains(&e.name()))
+            });
+            let trimmed = text.trim();
+            if !hidden && !trimmed.is_empty() {
+                parts.push(trimmed);
+            }
+        }
+    }
+
+    parts.join(" ")
+}
+
+/// Elements whose contents are never visible text. Script, style and
+/// noscript bodies are raw text, so they end at the first matching close
+/// tag; template and svg can nest.
+static SKIPPED_TEXT_ELEMENTS: &[&str] = &["script", "style", "noscript", "template", "svg"];
+
+/// Tags that break a line when rendered, so the words either side of them
+/// stay separate ("<li>One</li><li>Two</li>" is "One Two", not "OneTwo").
+static BLOCK_TAGS: &[&str] = &[
+    "address",
+    "article",
+    "aside",
+    "blockquote",
+    "br",
+    "button",
+    "dd",
+    "div",
+    "dl",
+    "dt",
+    "fieldset",
+    "figcaption",
+    "figure",
+    "footer",
+    "form",
+    "h1",
+    "h2",
+    "h3",
+    "h4",
+    "h5",
+    "h6",
+    "header",
+    "hr",
+    "img",
+    "input",
+    "label",
+    "li",
+    "main",
+    "nav",
+    "ol",
+    "option",
+    "p",
+    "pre",
+    "section",
+    "select",
+    "table",
+    "td",
+    "textarea",
+    "th",
+    "title",
+    "tr",
+    "ul",
+];
+
+/// Case-insensitive byte search for an ASCII `needle` from `from`.
+fn find_ascii_ci(haystack: &[u8], needle: &[u8], from: usize) -> Option<usize> {
+    haystack
+        .get(from..)?
+        .windows(needle.len())
+        .position(|w| w.eq_ignore_ascii_case(needle))
+        .map(|i| i + from)
+}
 
+/// End of the tag starting at `start` (just past its `>`), skipping `>`
+/// inside quoted attribute values. Only a quote right after `=` opens a
+/// value, so stray apostrophes don't; an unterminated quote falls back to
+/// the first `>`, and a tag that never closes runs to the end.
+fn tag_end(bytes: &[u8], start: usize) -> usize {
+    let mut quote = None;
+    let mut after_equals = false;
+    for (i, &b) in bytes.iter().enumerate().skip(start + 1) {
+        match (quote, b) {
+            (Some(q), _) if b == q => quote = None,
+            (Some(_), _) => {}
+            (None, b'"' | b'\'') if after_equals => quote = Some(b),
+            (None, b'>') => return i + 1,
+            _ => {}
+        }
+        if quote.is_none() && !b.is_ascii_whitespace() {
+            after_equals = b == b'=';
+        }
+    }
+    if quote.is_some() {
+        if let Some(i) = bytes[start..].iter().position(|&b| b == b'>') {
+            return start + i + 1;
+        }
+    }
+    bytes.len()
+}
+
+/// Where the contents of a skipped element opened just before `from` end:
+/// just past its close tag, counting nested opens for template and svg.
+fn skip_element(bytes: &[u8], name: &str, from: usize) -> usize {
+    let close = format!("</{}", name);
+    if matches!(name, "script" | "style" | "noscript") {
+        return find_ascii_ci(bytes, close.as_bytes(), from)
+            .map_or(bytes.len(), |i| tag_end(bytes, i));
+    }
+
+    let open = format!("<{}", name);
+    let mut depth = 1;
+    let mut pos = from;
+    loop {
+        let next_close = find_ascii_ci(bytes, close.as_bytes(), pos);
+        let next_open = find_ascii_ci(bytes, open.as_bytes(), pos).filter(|&i| {
+            bytes
+                .get(i + open.len())
+                .is_some_and(|b| !b.is_ascii_alphanumeric() && *b != b'-')
+        });
+        match (next_open, next_close) {
+            (Some(o), Some(c)) if o < c => {
+                depth += 1;
+                pos = tag_end(bytes, o);
+            }
+            (_, Some(c)) => {
+                depth -= 1;
+                pos = tag_end(bytes, c);
+                if depth == 0 {
+                    return pos;
+                }
+            }
+            (_, None) => return bytes.len(),
+        }
+    }
+}
+
+/// Landmark elements whose text is site chrome rather than page copy.
+static LANDMARK_ELEMENTS: &[&str] = &["nav", "header", "footer"];
+
+struct StreamedText {
+    text: String,
+    /// <p> elements with at least one word of text
+    paragraph_count: usize,
+}
+
+/// Visible text by streaming over the markup rather than building a DOM:
+/// script, style, noscript, template and svg contents, comments, CDATA
+/// sections and doctypes are dropped, tags are stripped (block-level ones
+/// leave a space), entities decoded and whitespace collapsed. Unclosed
+/// tags and elements run to the end of the page rather than leaking into
+/// the text.
+pub(crate) fn stream_visible_text(html: &str) -> String {
+    stream_text(html, &[]).text
+}
+
+/// stream_visible_text, also dropping the contents of the `extra_skipped`
+/// elements (which may nest) and counting non-empty paragraphs.
+fn stream_text(html: &str, extra_skipped: &[&str]) -> StreamedText {
+    let bytes = html.as_bytes();
+    let mut text = String::with_capacity(html.len() / 4);
+    let mut paragraph_count = 0;
+    let mut paragraph_start: Option<usize> = None;
+    let mut run_start = 0;
+    let mut i = 0;
+
+    while i < bytes.len() {
+        if bytes[i] != b'<' {
+            i += 1;
+            continue;
+        }
+        let rest = &bytes[i..];
+        let mut paragraph_tag = None;
+        let (next, separator) = if rest.starts_with(b"<!--") {
+            let end = find_ascii_ci(bytes, b"-->", i + 4).map_or(bytes.len(), |e| e + 3);
+            (end, false)
+        } else if rest.len() >= 9 && rest[..9].eq_ignore_ascii_case(b"<![CDATA[") {
+            let end = find_ascii_ci(bytes, b"]]>", i + 9).map_or(bytes.len(), |e| e + 3);
+            (end, false)
+        } else if rest.starts_with(b"<!") || rest.starts_with(b"<?") {
+            (tag_end(bytes, i), false)
+        } else if rest
+            .get(1)
+            .is_some_and(|b| b.is_ascii_alphabetic() || *b == b'/')
+        {
+            let closing = rest[1] == b'/';
+            let name_start = i + 1 + usize::from(closing);
+            let name_len = bytes[name_start..]
+                .iter()
+                .take_while(|b| b.is_ascii_alphanumeric() || **b == b'-')
+                .count();
+            let name = html[name_start..name_start + name_len].to_ascii_lowercase();
+            let end = tag_end(bytes, i);
+            let self_closing = end >= 2 && bytes[end - 2] == b'/';
+
+            if !closing
+                && !self_closing
+                && (SKIPPED_TEXT_ELEMENTS.contains(&name.as_str())
+                    || extra_skipped.contains(&name.as_str()))
+            {
+                (skip_element(bytes, &name, end), true)
+            } else {
+                if name == "p" {
+                    paragraph_tag = Some(closing);
+                }
+                (end, BLOCK_TAGS.contains(&name.as_str()))
+            }
+        } else {
+            // A bare "<" in text ("a < b")
+            i += 1;
+            continue;
+        };
+
+        text.push_str(&html[run_start..i]);
+        if let Some(closing) = paragraph_tag {
+            // A <p> runs to its close tag or the next <p>
+            if let Some(start) = paragraph_start.take() {
+                if has_word(&decode_html_entities(&text[start..])) {
+                    paragraph_count += 1;
+                }
+            }
+            if !closing {
+                paragraph_start = Some(text.len());
+            }
+        }
+        if separator {
+            text.push(' ');
+        }
+        // Every range above ends just past an ASCII byte or at the end, so
+        // `next` is a char boundary
+        i = next;
+        run_start = next;
+    }
+    text.push_str(&html[run_start..]);
+    if let Some(start) = paragraph_start {
+        if has_word(&decode_html_entities(&text[start..])) {
+            paragraph_count += 1;
+        }
+    }
+
+    StreamedText {
+        text: collapse_whitespace(&decode_html_entities(&text)),
+        paragraph_count,
+    }
+}
+
+/// Whether `text` has a word with a letter or digit in it.
+fn has_word(text: &str) -> bool {
+    text.chars().any(char::is_alphanumeric)
+}
+
+/// Words in `text`; tokens without a letter or digit ("|", "-") don't count.
+fn word_count(text: &str) -> usize {
+    text.split_whitespace()
+        .filter(|word| has_word(word))
+        .count()
+}
+
+struct ContentDepth {
+    word_count: usize,
+    paragraph_count: usize,
+    bucket: &'static str,
+}
+
+/// Default word-count thresholds for analyze_content_depth: fewer than
+/// CONTENT_THIN_WORDS is thin, more than CONTENT_RICH_WORDS is rich.
+const CONTENT_THIN_WORDS: usize = 250;
+const CONTENT_RICH_WORDS: usize = 1500;
+
+/// Page copy depth, leaving out nav, header and footer text so a big menu
+/// doesn't make a one-paragraph site look substantial.
+fn content_depth_of(html: &str, thin_words: usize, rich_words: usize) -> ContentDepth {
+    let streamed = stream_text(html, LANDMARK_ELEMENTS);
+    let word_count = word_count(&streamed.text);
+    let bucket = match word_count {
+        0 => "empty",
+        n if n < thin_words => "thin",
+        n if n > rich_words => "rich",
+        _ => "moderate",
+    };
+    ContentDepth {
+        word_count,
+        paragraph_count: streamed.paragraph_count,
+        bucket,
+    }
+}
+
+/// Words of visible text a page needs before its language is guessed.
+const MIN_LANGUAGE_WORDS: usize = 40;
+// Text is identified in chunks of about this many words, so a page that
+// mixes languages shows up as chunks that disagree
+const LANGUAGE_CHUNK_WORDS: usize = 60;
+
+#[derive(Debug, PartialEq)]
+struct ContentLanguage {
+    language: &'static str,
+    confidence: f64,
+}
+
+fn content_language_dict(py: Python<'_>, language: ContentLanguage) -> PyResult<PyObject> {
+    let dict = PyDict::new(py);
+    dict.set_item("language", language.language)?;
+    dict.set_item("confidence", language.confidence)?;
     Ok(dict.into())
 }
+
+/// Whether `c` is from a script written without spaces between words
+/// (Chinese, Japanese kana, Thai, Lao, Khmer, Burmese).
+fn is_unspaced_script(c: char) -> bool {
+    matches!(c,
+        '\u{3400}'..='\u{4DBF}'
+        | '\u{4E00}'..='\u{9FFF}'
+        | '\u{3040}'..='\u{30FF}'
+        | '\u{0E00}'..='\u{0EFF}'
+        | '\u{1780}'..='\u{17FF}'
+        | '\u{1000}'..='\u{109F}')
+}
+
+/// Words in one whitespace-separated token, counting each character of an
+/// unspaced script as a word.
+fn token_words(token: &str) -> usize {
+    match token.chars().filter(|c| is_unspaced_script(*c)).count() {
+        0 => usize::from(has_word(token)),
+        n => n,
+    }
+}
+
+/// The ISO 639-1 code for a whatlang language.
+fn lang_code(lang: Lang) -> &'static str {
+    match lang {
+        Lang::Epo => "eo",
+        Lang::Eng => "en",
+        Lang::Rus => "ru",
+        Lang::Cmn => "zh",
+        Lang::Spa => "es",
+        Lang::Por => "pt",
+        Lang::Ita => "it",
+        Lang::Ben => "bn",
+        Lang::Fra => "fr",
+        Lang::Deu => "de",
+        Lang::Ukr => "uk",
+        Lang::Kat => "ka",
+        Lang::Ara => "ar",
+        Lang::Hin => "hi",
+        Lang::Jpn => "ja",
+        Lang::Heb => "he",
+        Lang::Yid => "yi",
+        Lang::Pol => "pl",
+        Lang::Amh => "am",
+        Lang::Jav => "jv",
+        Lang::Kor => "ko",
+        Lang::Nob => "nb",
+        Lang::Dan => "da",
+        Lang::Swe => "sv",
+        Lang::Fin => "fi",
+        Lang::Tur => "tr",
+        Lang::Nld => "nl",
+        Lang::Hun => "hu",
+        Lang::Ces => "cs",
+        Lang::Ell => "el",
+        Lang::Bul => "bg",
+        Lang::Bel => "be",
+        Lang::Mar => "mr",
+        Lang::Kan => "kn",
+        Lang::Ron => "ro",
+        Lang::Slv => "sl",
+        Lang::Hrv => "hr",
+        Lang::Srp => "sr",
+        Lang::Mkd => "mk",
+        Lang::Lit => "lt",
+        Lang::Lav => "lv",
+        Lang::Est => "et",
+        Lang::Tam => "ta",
+        Lang::Vie => "vi",
+        Lang::Urd => "ur",
+        Lang::Tha => "th",
+        Lang::Guj => "gu",
+        Lang::Uzb => "uz",
+        Lang::Pan => "pa",
+        Lang::Aze => "az",
+        Lang::Ind => "id",
+        Lang::Tel => "te",
+        Lang::Pes => "fa",
+        Lang::Mal => "ml",
+        Lang::Ori => "or",
+        Lang::Mya => "my",
+        Lang::Nep => "ne",
+        Lang::Sin => "si",
+        Lang::Khm => "km",
+        Lang::Tuk => "tk",
+        Lang::Aka => "ak",
+        Lang::Zul => "zu",
+        Lang::Sna => "sn",
+        Lang::Afr => "af",
+        Lang::Lat => "la",
+        Lang::Slk => "sk",
+        Lang::Cat => "ca",
+        Lang::Tgl => "tl",
+        Lang::Hye => "hy",
+    }
+}
+
+/// The dominant language of the page copy (nav, header and footer left
+/// out): the one most words are written in, by trigram identification of
+/// ~60-word chunks. Its confidence is that of the whole text (or of its
+/// own chunks, when the whole text reads as another language), scaled
+/// down by the share of words in chunks confidently identified as another
+/// language, so mixed-language pages score lower.
+fn content_language_of(html: &str) -> Option<ContentLanguage> {
+    let text = stream_text(html, LANDMARK_ELEMENTS).text;
+    let mut chunks: Vec<(String, usize)> = Vec::new();
+    let mut chunk = String::new();
+    let mut chunk_words = 0;
+    for token in text.split_whitespace() {
+        let words = token_words(token);
+        if words == 0 {
+            continue;
+        }
+        chunk.push_str(token);
+        chunk.push(' ');
+        chunk_words += words;
+        if chunk_words >= LANGUAGE_CHUNK_WORDS {
+            chunks.push((std::mem::take(&mut chunk), chunk_words));
+            chunk_words = 0;
+        }
+    }
+    match chunks.last_mut() {
+        // A short tail is identified with the chunk before it
+        Some((last, words)) if chunk_words < LANGUAGE_CHUNK_WORDS / 2 => {
+            last.push_str(&chunk);
+            *words += chunk_words;
+        }
+        _ if chunk_words > 0 => chunks.push((chunk, chunk_words)),
+        _ => {}
+    }
+    let total: usize = chunks.iter().map(|(_, words)| words).sum();
+    if total < MIN_LANGUAGE_WORDS {
+        return None;
+    }
+
+    let detected: Vec<(Option<whatlang::Info>, usize)> = chunks
+        .iter()
+        .map(|(chunk, words)| (whatlang::detect(chunk), *words))
+        .collect();
+    // Words per language, in order of first appearance so ties go to the
+    // language the page opens with
+    let mut weights: Vec<(Lang, usize)> = Vec::new();
+    for (info, words) in &detected {
+        let Some(info) = info else {
+            continue;
+        };
+        match weights.iter_mut().find(|(lang, _)| *lang == info.lang()) {
+            Some((_, total)) => *total += words,
+            None => weights.push((info.lang(), *words)),
+        }
+    }
+    let lang = weights
+        .iter()
+        .rev()
+        .max_by_key(|(_, words)| *words)
+        .map(|(lang, _)| *lang)?;
+
+    let mut all = String::new();
+    for (chunk, _) in &chunks {
+        all.push_str(chunk);
+    }
+    let base_confidence = match whatlang::detect(&all) {
+        Some(info) if info.lang() == lang => info.confidence(),
+        // Whole-text trigrams lean to the other language: average the
+        // dominant language's chunks instead
+        _ => {
+            let (sum, words) = detected
+                .iter()
+                .filter_map(|(info, words)| Some((info.as_ref()?, *words)))
+                .filter(|(info, _)| info.lang() == lang)
+                .fold((0.0, 0), |(sum, n), (info, words)| {
+                    (sum + info.confidence() * words as f64, n + words)
+                });
+            sum / words as f64
+        }
+    };
+    // Words in chunks confidently identified as some other language
+    let other: usize = detected
+        .iter()
+        .filter(|(info, _)| {
+            info.as_ref()
+                .is_some_and(|c| c.is_reliable() && c.lang() != lang)
+        })
+        .map(|(_, words)| words)
+        .sum();
+    let confidence = base_confidence * (total - other) as f64 / total as f64;
+    Some(ContentLanguage {
+        language: lang_code(lang),
+        confidence: (confidence * 100.0).round() / 100.0,
+    })
+}
+
+/// Today's date (UTC) as (year, month, day).
+fn today() -> (i32, u32, u32) {
+    let days = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |d| d.as_secs() / 86_400) as i64;
+    // Howard Hinnant's days-to-civil conversion
+    let z = days + 719_468;
+    let era = z.div_euclid(146_097);
+    let doe = z.rem_euclid(146_097);
+    let yoe = (doe - doe / 1_460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = (doy - (153 * mp + 2) / 5 + 1) as u32;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 } as u32;
+    ((yoe + era * 400 + i64::from(month <= 2)) as i32, month, day)
+}
+
+/// The current calendar year (UTC).
+fn current_year() -> i32 {
+    today().0
+}
+
+/// Oldest year accepted in a copyright notice; anything earlier is a
+/// founding date or a stray number, not a maintained footer.
+const COPYRIGHT_MIN_YEAR: i32 = 1990;
+
+struct CopyrightYear {
+    year: i32,
+    raw: String,
+    dynamic: bool,
+}
+
+/// Every year in a notice's year list, expanding two-digit range ends
+/// ("2012-19") in the century of the year before them.
+fn copyright_years(list: &str) -> Vec<i32> {
+    let mut years = Vec::new();
+    for token in list.split(|c: char| !c.is_ascii_digit()) {
+        let year = match token.len() {
+            4 => token.parse().ok(),
+            2 => years.last().and_then(|&prev: &i32| {
+                let year = prev - prev % 100 + token.parse::<i32>().ok()?;
+                (year > prev).then_some(year)
+            }),
+            _ => None,
+        };
+        years.extend(year);
+    }
+    years
+}
+
+/// Whether a `getFullYear()` call sits next to a copyright marker in the
+/// raw markup ("© <script>document.write(new Date().getFullYear())</script>").
+fn has_adjacent_dynamic_year(html: &str) -> bool {
+    DYNAMIC_YEAR_RE.find_iter(html).any(|m| {
+        let mut before = m.start().saturating_sub(200);
+        while !html.is_char_boundary(before) {
+            before += 1;
+        }
+        let mut after = (m.end() + 100).min(html.len());
+        while !html.is_char_boundary(after) {
+            after -= 1;
+        }
+        COPYRIGHT_MARKUP_RE.is_match(&html[before..after])
+    })
+}
+
+/// The latest copyright year on the page, from notices in the visible text.
+///
+/// A year written by script can't be read from the markup, so a
+/// getFullYear() call next to a notice, or elsewhere on a page whose notice
+/// has no year or an open range ("© 2015 – "), marks the notice dynamic:
+/// it shows the current year whenever the page is viewed.
+fn copyright_year_of(html: &str) -> Option<CopyrightYear> {
+    let text = stream_visible_text(html);
+    let current = current_year();
+    let valid = COPYRIGHT_MIN_YEAR..=current + 1;
+
+    let mut latest: Option<(i32, String)> = None;
+    let mut yearless: Option<String> = None;
+    let mut open_ended = false;
+    let mut resume = 0;
+    for marker in COPYRIGHT_MARKER_RE.find_iter(&text) {
+        // "© Copyright 2019" is one notice, not two
+        if marker.start() < resume {
+            continue;
+        }
+        let window_end = text[marker.end()..]
+            .char_indices()
+            .nth(80)
+            .map_or(text.len(), |(i, _)| marker.end() + i);
+        let window = &text[marker.end()..window_end];
+
+        let Some(caps) = COPYRIGHT_YEARS_RE.captures(window) else {
+            if yearless.is_none() {
+                let end = window.find(['.', '|']).unwrap_or_else(|| {
+                    window
+                        .char_indices()
+                        .nth(40)
+                        .map_or(window.len(), |(i, _)| i)
+                });
+                yearless = Some(text[marker.start()..marker.end() + end].trim().to_string());
+            }
+            continue;
+        };
+        let end = marker.end() + caps.get(0).unwrap().end();
+        resume = end;
+        open_ended |= caps.get(2).is_some();
+
+        let year = copyright_years(&caps[1])
+            .into_iter()
+            .filter(|y| valid.contains(y))
+            .max();
+        if let Some(year) = year {
+            if latest.as_ref().is_none_or(|(best, _)| year > *best) {
+                latest = Some((year, text[marker.start()..end].trim().to_string()));
+            }
+        }
+    }
+
+    let scripted = DYNAMIC_YEAR_RE.is_match(html)
+        && (open_ended || yearless.is_some() || has_adjacent_dynamic_year(html));
+    match (latest, yearless) {
+        (Some((year, raw)), _) if !scripted => Some(CopyrightYear {
+            year,
+            raw,
+            dynamic: false,
+        }),
+        (Some((_, raw)), _) | (None, Some(raw)) if scripted => Some(CopyrightYear {
+            year: current,
+            raw,
+            dynamic: true,
+        }),
+        _ => None,
+    }
+}
+
+/// Last `n` whitespace-separated words of `text`, joined with single spaces.
+fn last_words(text: &str, n: usize) -> String {
+    let words: Vec<&str> = text.split_whitespace().collect();
+    words[words.len().saturating_sub(n)..].join(" ")
+}
+
+struct ParkedCheck {
+    parked: bool,
+    provider: Option<&'static str>,
+    reason: String,
+}
+
+/// Score parking signals. A provider signature counts twice; the page is
+/// parked only with at least two distinct signals and a score of three.
+fn check_parked(scan: &PageScan) -> ParkedCheck {
+    let mut signals: Vec<String> = Vec::new();
+    let mut score = 0;
+
+    let provider = PARKING_PROVIDER_SIGNATURES
+        .iter()
+        .find(|(_, sigs)| sigs.iter().any(|sig| scan.contains(sig)))
+        .map(|(name, _)| *name);
+    if let Some(name) = provider {
+        signals.push(format!("{} parking signature", name));
+        score += 2;
+    }
+
+    if PARKING_AD_SCRIPTS.iter().any(|sig| scan.contains(sig)) {
+        signals.push("domain ad script".to_string());
+        score += 2;
+    }
+
+    for phrase in PARKING_PHRASES {
+        if scan.contains(phrase) {
+            signals.push(format!("\"{}\"", phrase));
+            score += 1;
+        }
+    }
+
+    let parked = signals.len() >= 2 && score >= 3;
+    let reason = if signals.is_empty() {
+        "no parking signals".to_string()
+    } else if parked {
+        signals.join("; ")
+    } else {
+        format!("insufficient signals: {}", signals.join("; "))
+    };
+
+    ParkedCheck {
+        parked,
+        provider: if parked { provider } else { None },
+        reason,
+    }
+}
+
+struct PlaceholderCheck {
+    kind: Option<&'static str>,
+    signal: String,
+}
+
+/// Coming-soon, default server, maintenance and suspended pages. A template
+/// marker decides on its own; otherwise a phrase in the <title> or <h1>
+/// must be backed by a coming-soon plugin or a near-empty page.
+fn check_placeholder(html: &str, scan: &PageScan) -> PlaceholderCheck {
+    for (kind, label, sigs) in PLACEHOLDER_SIGNATURES {
+        if sigs.iter().any(|sig| scan.find_live(sig).is_some()) {
+            return PlaceholderCheck {
+                kind: Some(kind),
+                signal: label.to_string(),
+            };
+        }
+    }
+
+    let headline_of = |re: &Regex| {
+        re.captures(html)
+            .map(|c| stream_visible_text(&c[1]))
+            .unwrap_or_default()
+    };
+    let headlines = [
+        ("title", headline_of(&TITLE_TEXT_RE)),
+        ("h1", headline_of(&H1_TEXT_RE)),
+    ];
+    let phrase = headlines.iter().find_map(|(tag, text)| {
+        let lower = text.to_lowercase();
+        PLACEHOLDER_PHRASES.iter().find_map(|(kind, phrases)| {
+            phrases
+                .iter()
+                .find(|p| lower.contains(*p))
+                .map(|p| (*kind, format!("\"{}\" in {}", p, tag)))
+        })
+    });
+    let Some((kind, found)) = phrase else {
+        return PlaceholderCheck {
+            kind: None,
+            signal: "no placeholder signals".to_string(),
+        };
+    };
+
+    let plugin = PLACEHOLDER_PLUGIN_SIGNATURES
+        .iter()
+        .find(|(_, sigs)| sigs.iter().any(|sig| scan.find_live(sig).is_some()))
+        .map(|(name, _)| *name);
+    if let Some(name) = plugin {
+        return PlaceholderCheck {
+            kind: Some(kind),
+            signal: format!("{} plugin; {}", name, found),
+        };
+    }
+
+    let words = word_count(&stream_visible_text(html));
+    if words <= PLACEHOLDER_MAX_WORDS {
+        PlaceholderCheck {
+            kind: Some(kind),
+            signal: format!("{}; {} words on the page", found, words),
+        }
+    } else {
+        PlaceholderCheck {
+            kind: None,
+            signal: format!("{} but {} words on the page", found, words),
+        }
+    }
+}
+
+struct Soft404Check {
+    likely_404: bool,
+    confidence: f32,
+    signals: Vec<String>,
+}
+
+/// Score "not found" evidence on a page served with 200. Phrases only count
+/// in the <title> and first <h1>, and not in headings about 404s, so an
+/// article on fixing 404 errors isn't one.
+fn check_soft_404(html: &str, url: Option<&str>) -> Soft404Check {
+    let mut signals: Vec<String> = Vec::new();
+    let mut score = 0.0;
+
+    if let Some(class) = BODY_CLASS_RE.captures(html).and_then(|c| {
+        c[1].split_whitespace()
+            .map(str::to_ascii_lowercase)
+            .find(|class| SOFT_404_BODY_CLASSES.contains(&class.as_str()))
+    }) {
+        signals.push(format!("body_class:{}", class));
+        score += 4.0;
+    }
+
+    for (tag, re) in [("title", &*TITLE_TEXT_RE), ("h1", &*H1_TEXT_RE)] {
+        let Some(text) = re.captures(html).map(|c| stream_visible_text(&c[1])) else {
+            continue;
+        };
+        if text.split_whitespace().count() > SOFT_404_MAX_HEADING_WORDS
+            || SOFT_404_ARTICLE_RE.is_match(&text)
+        {
+            continue;
+        }
+        if let Some(m) = SOFT_404_HEADING_RE.find(&text) {
+            signals.push(format!("{}:{}", tag, m.as_str().to_lowercase()));
+            score += 2.0;
+        } else if let Some(m) = SOFT_404_WEAK_HEADING_RE.find(&text) {
+            signals.push(format!("{}:{}", tag, m.as_str().to_lowercase()));
+            score += 1.0;
+        }
+    }
+
+    if SEARCH_BOX_RE.is_match(html) {
+        let words = word_count(&stream_visible_text(html));
+        if words < SOFT_404_THIN_WORDS {
+            signals.push(format!("thin_with_search:{}", words));
+            score += 1.0;
+        }
+    }
+
+    let segment = url
+        .and_then(|u| url::Url::parse(u.trim()).ok())
+        .and_then(|u| {
+            u.path_segments()?
+                .rfind(|s| !s.is_empty())
+                .map(str::to_ascii_lowercase)
+        });
+    if let Some(segment) = segment.filter(|s| SOFT_404_PATHS.contains(&s.as_str())) {
+        signals.push(format!("url:{}", segment));
+        score += 2.0;
+    }
+
+    Soft404Check {
+        likely_404: score >= SOFT_404_MIN_SCORE,
+        confidence: (score / 5.0_f32).min(1.0),
+        signals,
+    }
+}
+
+/// Addresses on the page in priority order with their raw text and where
+/// they were found: <address> elements, address/location/contact classes
+/// and ids, the footer, text after contact headings, then anywhere. Each
+/// address appears once, at its highest-priority source.
+fn addresses_of(html: &str) -> Vec<(AuAddress, String, &'static str)> {
+    let document = Html::parse_document(html);
+    let tidy = |text: String| text.replace(" ,", ",");
+    let page = tidy(visible_text_in(&document));
+
+    let mut sources: Vec<(&'static str, String)> = Vec::new();
+    for (source, selector) in [
+        ("address_tag", &*ADDRESS_TAG_SEL),
+        ("address_element", &*ADDRESS_ELEMENT_SEL),
+        ("footer", &*FOOTER_SEL),
+    ] {
+        for element in document.select(selector) {
+            sources.push((source, tidy(joined_text(element))));
+        }
+    }
+    for heading in CONTACT_HEADING_RE.find_iter(&page) {
+        let end = page[heading.end()..]
+            .char_indices()
+            .nth(CONTACT_WINDOW_CHARS)
+            .map_or(page.len(), |(i, _)| heading.end() + i);
+        sources.push(("contact_section", page[heading.end()..end].to_string()));
+    }
+    sources.push(("page", page.clone()));
+
+    let mut seen: HashSet<String> = HashSet::new();
+    let mut found = Vec::new();
+    for (source, text) in &sources {
+        for (raw, address) in find_au_addresses(text) {
+            if seen.insert(address.key()) {
+                found.push((address, raw, *source));
+            }
+        }
+    }
+    found
+}
+
+struct DirectoryCheck {
+    is_directory: bool,
+    confidence: f64,
+    signals: Vec<String>,
+}
+
+/// Listing cards on the page and the distinct business names in their
+/// headings, e.g. (24, 22).
+fn count_listing_cards(document: &Html) -> (usize, usize) {
+    let mut cards = 0;
+    let mut names: HashSet<String> = HashSet::new();
+
+    for node in document.root_element().descendants() {
+        let Some(element) = ElementRef::wrap(node) else {
+            continue;
+        };
+        let is_card = element
+            .value()
+            .classes()
+            .any(|c| LISTING_ITEM_CLASSES.contains(&c.to_ascii_lowercase().as_str()));
+        if !is_card {
+            continue;
+        }
+        cards += 1;
+        if let Some(heading) = element.select(&LISTING_HEADING_SEL).next() {
+            let name = clean_business_name(&heading.text().collect::<String>(), true);
+            if !name.is_empty() {
+                names.insert(name.to_lowercase());
+            }
+        }
+    }
+
+    (cards, names.len())
+}
+
+/// Entries of a schema.org ItemList, in JSON-LD or microdata.
+fn count_itemlist_entries(html_lower: &str) -> usize {
+    if !html_lower.contains("itemlist") {
+        return 0;
+    }
+    html_lower.matches("\"listitem\"").count() + html_lower.matches("schema.org/listitem").count()
+}
+
+/// Largest result count or page count advertised by pagination text.
+fn pagination_size(text: &str) -> Option<(u64, bool)> {
+    let parse = |m: regex::Match| m.as_str().replace(',', "").parse::<u64>().ok();
+    DIRECTORY_PAGINATION_RE
+        .captures_iter(text)
+        .filter_map(|caps| {
+            if let Some(pages) = caps.name("pages") {
+                parse(pages).map(|n| (n, true))
+            } else {
+                caps.name("total")
+                    .or_else(|| caps.name("count"))
+                    .and_then(parse)
+                    .map(|n| (n, false))
+            }
+        })
+        .find(|&(n, is_pages)| {
+            n >= if is_pages {
+                MIN_PAGINATION_PAGES
+            } else {
+                MIN_PAGINATION_RESULTS
+            }
+        })
+}
+
+/// Landmarks whose headings are site chrome rather than page content.
+fn is_boilerplate_element(element: &scraper::node::Element) -> bool {
+    matches!(element.name(), "noscript" | "nav" | "footer" | "aside")
+        || element
+            .attr("role")
+            .is_some_and(|role| matches!(role, "navigation" | "contentinfo"))
+}
+
+/// (level, text) of the h1..h`max_level` headings in document order, at most
+/// `limit`. Text is every text node inside the heading, whitespace-collapsed,
+/// or its images' alt text when there is none (a logo h1); empty headings
+/// are dropped. Headings in <template> never count; with `skip_boilerplate`
+/// neither do those in <noscript>, <nav>, <footer>, <aside> or navigation /
+/// contentinfo landmarks.
+fn headings_in(
+    document: &Html,
+    max_level: u8,
+    limit: usize,
+    skip_boilerplate: bool,
+) -> Vec<(u8, String)> {
+    let mut headings = Vec::new();
+    for heading in document.select(&HEADING_SEL) {
+        if headings.len() >= limit {
+            break;
+        }
+        let level = heading.value().name().as_bytes()[1] - b'0';
+        if level > max_level {
+            continue;
+        }
+        let skipped = heading.ancestors().filter_map(ElementRef::wrap).any(|a| {
+            a.value().name() == "template"
+                || (skip_boilerplate && is_boilerplate_element(a.value()))
+        });
+        if skipped {
+            continue;
+        }
+
+        let mut text = collapse_whitespace(&heading.text().collect::<String>());
+        if text.is_empty() {
+            let alts: Vec<&str> = heading
+                .select(&IMG_SEL)
+                .filter_map(|img| img.value().attr("alt"))
+                .collect();
+            text = collapse_whitespace(&alts.join(" "));
+        }
+        if !text.is_empty() {
+            headings.push((level, text));
+        }
+    }
+    headings
+}
+
+struct MetaSeo {
+    title: Option<String>,
+    meta_description: Option<String>,
+    noindex: bool,
+    canonical_url: Option<String>,
+    h1_count: usize,
+    h1_text: Vec<String>,
+}
+
+fn collapse_whitespace(text: &str) -> String {
+    text.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+fn meta_seo_of(html: &str) -> MetaSeo {
+    meta_seo_in(&Html::parse_document(html))
+}
+
+fn meta_seo_in(document: &Html) -> MetaSeo {
+    let non_empty = |text: String| (!text.is_empty()).then_some(text);
+
+    let title = document
+        .select(&HEAD_TITLE_SEL)
+        .next()
+        .and_then(|t| non_empty(collapse_whitespace(&t.text().collect::<String>())));
+    // Only the first description counts, as it is the one search engines use
+    let meta_description = document
+        .select(&META_DESCRIPTION_SEL)
+        .next()
+        .and_then(|m| m.value().attr("content"))
+        .and_then(|content| non_empty(collapse_whitespace(content)));
+    let noindex = document.select(&META_ROBOTS_SEL).any(|m| {
+        m.value().attr("content").is_some_and(|content| {
+            content.split([',', ' ']).any(|d| {
+                d.trim().eq_ignore_ascii_case("noindex") || d.trim().eq_ignore_ascii_case("none")
+            })
+        })
+    });
+    let canonical_url = document
+        .select(&CANONICAL_LINK_SEL)
+        .next()
+        .and_then(|l| l.value().attr("href"))
+        .and_then(|href| non_empty(href.trim().to_string()));
+
+    let h1_text: Vec<String> = headings_in(document, 1, usize::MAX, false)
+        .into_iter()
+        .map(|(_, text)| text)
+        .collect();
+    let h1_count = h1_text.len();
+
+    MetaSeo {
+        title,
+        meta_description,
+        noindex,
+        canonical_url,
+        h1_count,
+        h1_text,
+    }
+}
+
+// Image audit
+
+static PICTURE_SOURCE_SEL: LazyLock<Selector> =
+    LazyLock::new(|| Selector::parse("picture source").unwrap());
+static STYLED_SEL: LazyLock<Selector> = LazyLock::new(|| Selector::parse("[style]").unwrap());
+static BACKGROUND_URL_RE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r#"(?i)background(?:-image)?\s*:[^;]*?url\(\s*["']?([^"')]+)"#).unwrap()
+});
+
+// Hosts whose 1x1 images are tracking pixels, not content
+const TRACKING_PIXEL_HOSTS: &[&str] = &[
+    "facebook.com",
+    "google-analytics.com",
+    "googleadservices.com",
+    "doubleclick.net",
+    "bat.bing.com",
+    "px.ads.linkedin.com",
+    "analytics.twitter.com",
+    "t.co",
+    "ct.pinterest.com",
+    "analytics.tiktok.com",
+    "sb.scorecardresearch.com",
+    "pixel.quantserve.com",
+];
+
+#[derive(Default)]
+struct ImageAudit {
+    image_count: usize,
+    missing_alt: usize,
+    lazy_loaded: usize,
+    webp_or_avif: usize,
+    srcset: usize,
+    inline_base64: usize,
+    largest_inline_base64_bytes: usize,
+    background_images: usize,
+}
+
+impl ImageAudit {
+    fn note_inline(&mut self, url: &str) {
+        if let Some(bytes) = base64_data_uri_bytes(url) {
+            self.inline_base64 += 1;
+            self.largest_inline_base64_bytes = self.largest_inline_base64_bytes.max(bytes);
+        }
+    }
+}
+
+/// A 1x1 (or 0x0) image from a known tracker host.
+fn is_tracking_pixel(img: ElementRef) -> bool {
+    let tiny = ["width", "height"]
+        .iter()
+        .all(|dim| matches!(attr_of(img, dim).trim(), "0" | "1" | "0px" | "1px"));
+    tiny && url::Url::parse(attr_of(img, "src").trim())
+        .ok()
+        .and_then(|u| u.host_str().map(str::to_ascii_lowercase))
+        .is_some_and(|host| {
+            TRACKING_PIXEL_HOSTS
+                .iter()
+                .any(|h| host == *h || host.ends_with(&format!(".{}", h)))
+        })
+}
+
+/// Decoded size of a base64 data: URI, None for other URLs.
+fn base64_data_uri_bytes(url: &str) -> Option<usize> {
+    let url = url.trim();
+    if !url
+        .get(..5)
+        .is_some_and(|s| s.eq_ignore_ascii_case("data:"))
+    {
+        return None;
+    }
+    let (header, data) = url.split_once(',')?;
+    if !header.to_ascii_lowercase().ends_with(";base64") {
+        return None;
+    }
+    let data = data.trim_end();
+    let padding = data.len() - data.trim_end_matches('=').len();
+    Some((data.len() / 4 * 3).saturating_sub(padding))
+}
+
+/// The image candidates of a srcset: "a.webp 1x, b.webp 2x" -> a.webp, b.webp.
+fn srcset_urls(srcset: &str) -> Vec<&str> {
+    // A data: URI has a comma of its own
+    if base64_data_uri_bytes(srcset.split_whitespace().next().unwrap_or("")).is_some() {
+        return srcset.split_whitespace().take(1).collect();
+    }
+    srcset
+        .split(',')
+        .filter_map(|candidate| candidate.split_whitespace().next())
+        .collect()
+}
+
+fn is_modern_format(url: &str) -> bool {
+    let path = url
+        .split(['?', '#'])
+        .next()
+        .unwrap_or("")
+        .to_ascii_lowercase();
+    path.ends_with(".webp")
+        || path.ends_with(".avif")
+        || path.starts_with("data:image/webp")
+        || path.starts_with("data:image/avif")
+}
+
+/// `<img>` tags (tracking pixels left out), the `<picture>` sources around
+/// them and background images in inline styles.
+fn image_audit_in(document: &Html) -> ImageAudit {
+    let mut audit = ImageAudit::default();
+
+    for img in document.select(&IMG_SEL) {
+        if is_tracking_pixel(img) {
+            continue;
+        }
+        audit.image_count += 1;
+        // alt="" marks a decorative image; only a missing attribute counts
+        if img.value().attr("alt").is_none() {
+            audit.missing_alt += 1;
+        }
+        // Native lazy loading, or a lazy-loading script's data-src
+        if attr_of(img, "loading").eq_ignore_ascii_case("lazy")
+            || img
+                .value()
+                .attrs()
+                .any(|(name, _)| name.starts_with("data-") && name.ends_with("src"))
+        {
+            audit.lazy_loaded += 1;
+        }
+
+        let mut urls: Vec<&str> = vec![attr_of(img, "src")];
+        urls.extend(srcset_urls(attr_of(img, "srcset")));
+        let mut has_srcset = !attr_of(img, "srcset").trim().is_empty();
+        let mut modern_source = false;
+        let picture = img
+            .parent()
+            .and_then(ElementRef::wrap)
+            .filter(|p| p.value().name() == "picture");
+        if let Some(picture) = picture {
+            for source in picture.select(&PICTURE_SOURCE_SEL) {
+                let srcset = attr_of(source, "srcset");
+                has_srcset |= !srcset.trim().is_empty();
+                let kind = attr_of(source, "type").to_ascii_lowercase();
+                modern_source |= kind == "image/webp" || kind == "image/avif";
+                urls.extend(srcset_urls(srcset));
+            }
+        }
+        if has_srcset {
+            audit.srcset += 1;
+        }
+        if modern_source || urls.iter().any(|url| is_modern_format(url)) {
+            audit.webp_or_avif += 1;
+        }
+        for url in urls {
+            audit.note_inline(url);
+        }
+    }
+
+    for e in document.select(&STYLED_SEL) {
+        for caps in BACKGROUND_URL_RE.captures_iter(attr_of(e, "style")) {
+            audit.background_images += 1;
+            audit.note_inline(&caps[1]);
+        }
+    }
+    audit
+}
+
+struct SeoAudit {
+    meta: MetaSeo,
+    images: ImageAudit,
+    has_schema_markup: bool,
+    internal_links: usize,
+    external_links: usize,
+    word_count: usize,
+    has_viewport_meta: bool,
+    issues: Vec<&'static str>,
+}
+
+fn host_without_www(url: &str) -> Option<String> {
+    let url = url::Url::parse(url.trim()).ok()?;
+    let host = url.host_str()?.to_ascii_lowercase();
+    Some(host.strip_prefix("www.").unwrap_or(&host).to_string())
+}
+
+/// Everything seo_audit reports: one parse of the document and one
+/// signature scan of the lowercased page shared by every check.
+fn seo_audit_of(html: &str, url: Option<&str>) -> SeoAudit {
+    let document = Html::parse_document(html);
+    let scan = PageScan::new(html);
+    let meta = meta_seo_in(&document);
+
+    let images = image_audit_in(&document);
+
+    // Links are internal when relative or on the page's own host (from
+    // `url`, else the canonical)
+    let page_host = url
+        .or(meta.canonical_url.as_deref())
+        .and_then(host_without_www);
+    let mut internal_links = 0;
+    let mut external_links = 0;
+    for a in document.select(&LINK_HREF_SEL) {
+        let href = a.value().attr("href").unwrap_or("").trim();
+        let lower = href.to_ascii_lowercase();
+        if href.is_empty()
+            || href.starts_with('#')
+            || ["mailto:", "tel:", "sms:", "javascript:", "data:"]
+                .iter()
+                .any(|scheme| lower.starts_with(scheme))
+        {
+            continue;
+        }
+        let absolute = if lower.starts_with("//") {
+            Some(format!("https:{}", href))
+        } else if lower.starts_with("http://") || lower.starts_with("https://") {
+            Some(href.to_string())
+        } else {
+            None
+        };
+        match absolute {
+            None => internal_links += 1,
+            Some(absolute) => match (host_without_www(&absolute), &page_host) {
+                (Some(host), Some(page)) if host == *page => internal_links += 1,
+                _ => external_links += 1,
+            },
+        }
+    }
+
+    let word_count = visible_text_in(&document)
+        .split_whitespace()
+        .filter(|word| word.chars().any(char::is_alphanumeric))
+        .count();
+    let has_schema_markup =
+        scan.find_live("application/ld+json").is_some() || scan.find_live("itemtype").is_some();
+    let has_viewport_meta = has_device_width_viewport(html);
+    let blocking = render_blocking_in(&document);
+
+    let title_length = meta.title.as_ref().map_or(0, |t| t.chars().count());
+    let description_length = meta
+        .meta_description
+        .as_ref()
+        .map_or(0, |d| d.chars().count());
+    let mut issues = Vec::new();
+    match title_length {
+        0 => issues.push("missing_title"),
+        n if n < SEO_TITLE_MIN => issues.push("title_too_short"),
+        n if n > SEO_TITLE_MAX => issues.push("title_too_long"),
+        _ => {}
+    }
+    match description_length {
+        0 => issues.push("missing_meta_description"),
+        n if n < SEO_DESCRIPTION_MIN => issues.push("meta_description_too_short"),
+        n if n > SEO_DESCRIPTION_MAX => issues.push("meta_description_too_long"),
+        _ => {}
+    }
+    match meta.h1_count {
+        0 => issues.push("missing_h1"),
+        1 => {}
+        _ => issues.push("multiple_h1"),
+    }
+    if images.missing_alt > 0 {
+        issues.push("images_missing_alt");
+    }
+    if images.largest_inline_base64_bytes > SEO_LARGE_INLINE_IMAGE_BYTES {
+        issues.push("large_inline_image");
+    }
+    if images.image_count >= SEO_IMAGE_FORMAT_MIN_IMAGES {
+        if images.webp_or_avif == 0 {
+            issues.push("no_modern_image_formats");
+        }
+        if images.srcset == 0 {
+            issues.push("no_responsive_images");
+        }
+        if images.lazy_loaded == 0 {
+            issues.push("no_lazy_loading");
+        }
+    }
+    if meta.canonical_url.is_none() {
+        issues.push("missing_canonical");
+    }
+    if !has_schema_markup {
+        issues.push("no_schema_markup");
+    }
+    if !has_viewport_meta {
+        issues.push("missing_viewport_meta");
+    }
+    if blocking.scripts > SEO_MAX_BLOCKING_SCRIPTS {
+        issues.push("render_blocking_scripts");
+    }
+    if blocking.styles > SEO_MAX_BLOCKING_STYLES {
+        issues.push("render_blocking_styles");
+    }
+    if word_count < SEO_THIN_CONTENT_WORDS {
+        issues.push("thin_content");
+    }
+    if meta.noindex {
+        issues.push("noindex");
+    }
+
+    SeoAudit {
+        meta,
+        images,
+        has_schema_markup,
+        internal_links,
+        external_links,
+        word_count,
+        has_viewport_meta,
+        issues,
+    }
+}
+
+/// Score aggregator signals. Each signal is independent evidence (markup,
+/// repeated CTAs, claim prompts, ItemList, pagination, phone count); a page
+/// is a directory only with at least MIN_DIRECTORY_SIGNALS of them, so a
+/// multi-branch business with a few locations and quote buttons stays clear.
+fn check_directory_page(html: &str) -> DirectoryCheck {
+    let document = Html::parse_document(html);
+    let html_lower = html.to_lowercase();
+    let text_lower = visible_text(html).to_lowercase();
+    let mut signals: Vec<String> = Vec::new();
+    let mut score = 0.0;
+
+    let (cards, names) = count_listing_cards(&document);
+    if cards >= MIN_LISTING_ITEMS && names >= MIN_LISTING_NAMES {
+        signals.push(format!("listing_grid:{}", names));
+        score += 1.0;
+    }
+
+    let ctas: usize = DIRECTORY_CTA_PHRASES
+        .iter()
+        .map(|phrase| text_lower.matches(phrase).count())
+        .sum();
+    if ctas >= MIN_REPEATED_CTAS {
+        signals.push(format!("repeated_cta:{}", ctas));
+        score += 1.0;
+    }
+
+    if let Some(phrase) = CLAIM_LISTING_PHRASES
+        .iter()
+        .find(|phrase| text_lower.contains(*phrase))
+    {
+        signals.push(format!("claim_listing:{}", phrase));
+        score += 1.5;
+    }
+
+    let entries = count_itemlist_entries(&html_lower);
+    if entries >= MIN_ITEMLIST_ENTRIES {
+        signals.push(format!("itemlist:{}", entries));
+        score += 1.5;
+    }
+
+    if let Some((size, is_pages)) = pagination_size(&text_lower) {
+        let kind = if is_pages { "pages" } else { "results" };
+        signals.push(format!("pagination:{}:{}", kind, size));
+        score += 1.0;
+    }
+
+    let phones = phones_of(html).len();
+    if phones >= MIN_DIRECTORY_PHONES {
+        signals.push(format!("phones:{}", phones));
+        score += 1.0;
+    }
+
+    DirectoryCheck {
+        is_directory: signals.len() >= MIN_DIRECTORY_SIGNALS,
+        confidence: (score / 6.0_f64).min(1.0),
+        signals,
+    }
+}
+
+fn signature_table(defaults: &[(&str, &[&str])]) -> SignatureTable {
+    defaults
+        .iter()
+        .map(|(name, sigs)| {
+            (
+                name.to_string(),
+                sigs.iter().map(|s| s.to_string()).collect(),
+            )
+        })
+        .collect()
+}
+
+/// Merge (or with `replace`, override) one entry in a runtime signature
+/// table; `kind` names the table in error messages. Returns the number of
+/// patterns added.
+fn apply_signature(
+    table: &RwLock<SignatureTable>,
+    kind: &str,
+    name: &str,
+    patterns: &[String],
+    replace: bool,
+) -> PyResult<usize> {
+    let (name, normalized) =
+        signature_entry(kind, name, patterns).map_err(PyValueError::new_err)?;
+    Ok(merge_signature(table, name, normalized, replace))
+}
+
+/// Trimmed name and lowercased, deduplicated patterns, or why the entry is
+/// invalid.
+fn signature_entry<'a>(
+    kind: &str,
+    name: &'a str,
+    patterns: &[String],
+) -> Result<(&'a str, Vec<String>), String> {
+    let name = name.trim();
+    if name.is_empty() {
+        return Err(format!("{} name must not be empty", kind));
+    }
+    let mut normalized: Vec<String> = Vec::new();
+    for pattern in patterns {
+        let pattern = pattern.trim().to_lowercase();
+        if pattern.is_empty() {
+            return Err(format!("Empty signature pattern for {:?}", name));
+        }
+        if !normalized.contains(&pattern) {
+            normalized.push(pattern);
+        }
+    }
+    Ok((name, normalized))
+}
+
+fn merge_signature(
+    table: &RwLock<SignatureTable>,
+    name: &str,
+    normalized: Vec<String>,
+    replace: bool,
+) -> usize {
+    let mut entries = table.write().unwrap();
+    let existing = entries
+        .iter()
+        .position(|(n, _)| n.eq_ignore_ascii_case(name));
+    let added = match existing {
+        Some(idx) if replace && normalized.is_empty() => {
+            entries.remove(idx);
+            0
+        }
+        Some(idx) if replace => {
+            let added = normalized.len();
+            entries[idx].1 = normalized;
+            added
+        }
+        Some(idx) => {
+            let sigs = &mut entries[idx].1;
+            let before = sigs.len();
+            for pattern in normalized {
+                if !sigs.contains(&pattern) {
+                    sigs.push(pattern);
+                }
+            }
+            sigs.len() - before
+        }
+        None if normalized.is_empty() => 0,
+        None => {
+            let added = normalized.len();
+            entries.push((name.to_string(), normalized));
+            added
+        }
+    };
+    drop(entries);
+    rebuild_signature_index();
+    added
+}
+
+struct CmsMatch {
+    cms: String,
+    score: f64,
+    matched_signatures: Vec<String>,
+}
+
+impl CmsMatch {
+    fn confidence(&self) -> f64 {
+        (self.score / CMS_FULL_SCORE).min(1.0)
+    }
+}
+
+fn cms_signature_weight(signature: &str) -> f64 {
+    if signature.chars().all(|c| c.is_ascii_alphabetic()) {
+        CMS_KEYWORD_WEIGHT
+    } else {
+        CMS_ASSET_WEIGHT
+    }
+}
+
+/// Every CMS with at least one matching signature, best first. Ties keep
+/// signature table order.
+fn match_cms(scan: &PageScan) -> Vec<CmsMatch> {
+    let table = CMS_SIGNATURES.read().unwrap();
+    let mut matches: Vec<CmsMatch> = table
+        .iter()
+        .filter_map(|(cms_name, signatures)| {
+            let matched: Vec<String> = signatures
+                .iter()
+                .filter(|sig| scan.contains(sig))
+                .cloned()
+                .collect();
+            if matched.is_empty() {
+                return None;
+            }
+            Some(CmsMatch {
+                cms: cms_name.to_string(),
+                score: matched.iter().map(|sig| cms_signature_weight(sig)).sum(),
+                matched_signatures: matched,
+            })
+        })
+        .collect();
+    matches.sort_by(|a, b| b.score.total_cmp(&a.score));
+    matches
+}
+
+/// Builder with the most marker hits on a WordPress page, falling back to
+/// Gutenberg when wp-block- classes are dense. Callers check for WordPress.
+fn page_builder_of(scan: &PageScan) -> Option<&'static str> {
+    let mut best: Option<(&'static str, usize)> = None;
+    for (name, sigs) in PAGE_BUILDER_SIGNATURES {
+        let hits: usize = sigs.iter().map(|sig| scan.count(sig)).sum();
+        if hits > 0 && best.is_none_or(|(_, best_hits)| hits > best_hits) {
+            best = Some((name, hits));
+        }
+    }
+    best.map(|(name, _)| name).or_else(|| {
+        (scan.count(GUTENBERG_BLOCK_MARKER) >= MIN_GUTENBERG_BLOCKS).then_some("Gutenberg")
+    })
+}
+
+struct WpContentSlug {
+    slug: String,
+    count: usize,
+    offset: usize,
+}
+
+/// Lowercase theme or plugin slugs under /wp-content/<kind>/ with how often
+/// each appears and the byte offset of the first, in order of appearance.
+fn wp_content_slugs(html: &str, kind: &str) -> Vec<WpContentSlug> {
+    let mut slugs: Vec<WpContentSlug> = Vec::new();
+    for caps in WP_CONTENT_SLUG_RE
+        .captures_iter(html)
+        .take(MAX_WP_ASSET_PATHS)
+    {
+        if !caps[1].eq_ignore_ascii_case(kind) {
+            continue;
+        }
+        let slug = caps[2].to_lowercase();
+        match slugs.iter_mut().find(|s| s.slug == slug) {
+            Some(existing) => existing.count += 1,
+            None => slugs.push(WpContentSlug {
+                slug,
+                count: 1,
+                offset: caps.get(0).unwrap().start(),
+            }),
+        }
+    }
+    slugs
+}
+
+/// Plugin slugs from asset paths and inline markers, by first appearance.
+/// Markers only count on WordPress pages.
+fn wp_plugins_of(html: &str, scan: &PageScan) -> Vec<String> {
+    let mut found: Vec<(usize, String)> = wp_content_slugs(html, "plugins")
+        .into_iter()
+        .map(|s| (s.offset, s.slug))
+        .collect();
+
+    if !found.is_empty() || cms_of(scan).as_deref() == Some("WordPress") {
+        for (slug, markers) in WP_PLUGIN_MARKERS {
+            if found.iter().any(|(_, s)| s == slug) {
+                continue;
+            }
+            if let Some(offset) = markers.iter().filter_map(|m| scan.find(m)).min() {
+                found.push((offset, slug.to_string()));
+            }
+        }
+    }
+
+    found.sort_by_key(|(offset, _)| *offset);
+    found
+        .into_iter()
+        .map(|(_, slug)| slug)
+        .take(MAX_WP_PLUGINS)
+        .collect()
+}
+
+#[derive(Debug, PartialEq)]
+struct WpTheme {
+    slug: String,
+    is_child_theme_guess: bool,
+}
+
+fn wp_theme_of(html: &str) -> Option<WpTheme> {
+    let slugs = wp_content_slugs(html, "themes");
+    // Most frequent wins; max_by_key keeps the last of equals, so reverse
+    // to prefer the first-seen slug on ties
+    let top = slugs.iter().rev().max_by_key(|s| s.count)?;
+    let is_child_theme_guess = slugs.len() > 1
+        || slugs
+            .iter()
+            .any(|s| s.slug.ends_with("-child") || s.slug.ends_with("_child"));
+    Some(WpTheme {
+        slug: top.slug.clone(),
+        is_child_theme_guess,
+    })
+}
+
+impl WpTheme {
+    /// The slug title-cased for display: "astra-child" → "Astra Child".
+    fn name(&self) -> String {
+        title_case_words(&self.slug.replace(['-', '_'], " "))
+    }
+}
+
+fn wp_theme_dict(py: Python<'_>, theme: WpTheme) -> PyResult<PyObject> {
+    let dict = PyDict::new(py);
+    dict.set_item("name", theme.name())?;
+    dict.set_item("slug", theme.slug)?;
+    dict.set_item("is_child_theme_guess", theme.is_child_theme_guess)?;
+    Ok(dict.into())
+}
+
+/// One vendor from a signature table found on a page.
+struct SignatureHit {
+    name: &'static str,
+    offset: usize,
+    matched: Vec<&'static str>,
+}
+
+/// Every vendor in `table` with a signature located by `find`, ordered by
+/// the first signature's position on the page.
+fn match_signature_table(
+    table: &'static [(&'static str, &'static [&'static str])],
+    find: impl Fn(&str) -> Option<usize>,
+) -> Vec<SignatureHit> {
+    let mut hits: Vec<SignatureHit> = table
+        .iter()
+        .filter_map(|(name, sigs)| {
+            let positions: Vec<(usize, &'static str)> = sigs
+                .iter()
+                .filter_map(|sig| find(sig).map(|pos| (pos, *sig)))
+                .collect();
+            let offset = positions.iter().map(|(pos, _)| *pos).min()?;
+            Some(SignatureHit {
+                name,
+                offset,
+                matched: positions.into_iter().map(|(_, sig)| sig).collect(),
+            })
+        })
+        .collect();
+    hits.sort_by_key(|h| h.offset);
+    hits
+}
+
+#[derive(Debug, PartialEq)]
+struct EcommerceCheck {
+    platform: Option<&'static str>,
+    signals: Vec<&'static str>,
+    has_cart: bool,
+}
+
+/// The store platform with the most matching signatures (ties go to table
+/// order) plus the generic cart heuristic.
+fn check_ecommerce(html: &str, scan: &PageScan) -> EcommerceCheck {
+    let mut hits = match_signature_table(ECOMMERCE_SIGNATURES, |sig| scan.find(sig));
+    hits.sort_by_key(|h| {
+        let rank = ECOMMERCE_SIGNATURES
+            .iter()
+            .position(|(name, _)| *name == h.name)
+            .unwrap_or(usize::MAX);
+        (std::cmp::Reverse(h.matched.len()), rank)
+    });
+    let best = hits.into_iter().next();
+    EcommerceCheck {
+        platform: best.as_ref().map(|h| h.name),
+        signals: best.map(|h| h.matched).unwrap_or_default(),
+        has_cart: CART_RE.is_match(html),
+    }
+}
+
+fn payment_providers_of(scan: &PageScan) -> Vec<&'static str> {
+    match_signature_table(PAYMENT_PROVIDER_SIGNATURES, |sig| scan.find(sig))
+        .into_iter()
+        .map(|h| h.name)
+        .collect()
+}
+
+/// The chat vendor embedded on the page and whether the page has chat.
+///
+/// `has_chat` is three-valued: `Some(true)` for a vendor or generic chat
+/// launcher, `Some(false)` when the page was readable and has none, and
+/// `None` when absence can't be confirmed (blank HTML, or a tag manager that
+/// may inject the widget after load).
+#[derive(Debug, PartialEq)]
+struct ChatCheck {
+    vendor: Option<&'static str>,
+    has_chat: Option<bool>,
+}
+
+fn check_chat_widget(html: &str, scan: &PageScan) -> ChatCheck {
+    let live = HTML_COMMENT_RE.replace_all(html, " ");
+    let vendor = match_signature_table(CHAT_WIDGET_SIGNATURES, |sig| scan.find_live(sig))
+        .into_iter()
+        .next()
+        .map(|h| h.name);
+    let has_chat = if vendor.is_some() || GENERIC_CHAT_RE.is_match(&live) {
+        Some(true)
+    } else if live.trim().is_empty() || scan.find_live(TAG_MANAGER_LOADER).is_some() {
+        None
+    } else {
+        Some(false)
+    };
+    ChatCheck { vendor, has_chat }
+}
+
+fn marketing_tools_of(scan: &PageScan) -> Vec<&'static str> {
+    match_signature_table(MARKETING_TOOL_SIGNATURES, |sig| scan.find(sig))
+        .into_iter()
+        .map(|h| h.name)
+        .collect()
+}
+
+fn review_widgets_of(html: &str, scan: &PageScan) -> Vec<&'static str> {
+    let mut widgets: Vec<&'static str> =
+        match_signature_table(REVIEW_WIDGET_SIGNATURES, |sig| scan.find_live(sig))
+            .into_iter()
+            .map(|h| h.name)
+            .collect();
+    let live = HTML_COMMENT_RE.replace_all(html, " ");
+    if GOOGLE_REVIEWS_CLASS_RE
+        .captures_iter(&live)
+        .any(|caps| !caps[1].eq_ignore_ascii_case("a"))
+    {
+        widgets.push("Google Reviews");
+    }
+    widgets
+}
+
+/// Tracking IDs per kind (ga4_ids, ua_ids, gtm_ids, fb_pixel_ids) in page
+/// order, deduplicated and capped at `MAX_TRACKING_IDS`.
+fn tracking_ids_of(html: &str) -> Vec<(&'static str, Vec<String>)> {
+    TRACKING_ID_EXTRACTORS
+        .iter()
+        .map(|(key, patterns)| {
+            let mut found: Vec<(usize, &str)> = patterns
+                .iter()
+                .flat_map(|re| re.captures_iter(html))
+                .filter_map(|caps| caps.get(1))
+                .map(|m| (m.start(), m.as_str()))
+                .collect();
+            found.sort_unstable();
+            let mut seen = HashSet::new();
+            let ids = found
+                .into_iter()
+                .filter(|(_, id)| seen.insert(*id))
+                .map(|(_, id)| id.to_string())
+                .take(MAX_TRACKING_IDS)
+                .collect();
+            (*key, ids)
+        })
+        .collect()
+}
+
+fn tracking_ids_dict<'py>(
+    py: Python<'py>,
+    tracking_ids: Vec<(&'static str, Vec<String>)>,
+) -> PyResult<Bound<'py, PyDict>> {
+    let dict = PyDict::new(py);
+    for (key, ids) in tracking_ids {
+        dict.set_item(key, ids)?;
+    }
+    Ok(dict)
+}
+
+#[derive(Debug, PartialEq)]
+struct BookingMatch {
+    provider: String,
+    signal: String,
+    confidence: f64,
+}
+
+/// The booking provider whose signature appears first on the page, falling
+/// back to provider "generic" for bare book-now / book-online markup.
+fn booking_match_of(scan: &PageScan) -> Option<BookingMatch> {
+    let table = BOOKING_SIGNATURES.read().unwrap();
+    let mut first: Option<(usize, &String, &String)> = None;
+    for (name, sigs) in table.iter() {
+        for sig in sigs {
+            if let Some(pos) = scan.find(sig) {
+                if first.is_none_or(|(best, _, _)| pos < best) {
+                    first = Some((pos, name, sig));
+                }
+            }
+        }
+    }
+    if let Some((_, name, sig)) = first {
+        return Some(BookingMatch {
+            provider: name.clone(),
+            signal: sig.clone(),
+            confidence: BOOKING_PROVIDER_CONFIDENCE,
+        });
+    }
+
+    GENERIC_BOOKING_SIGNATURES
+        .iter()
+        .find(|sig| scan.contains(sig))
+        .map(|sig| BookingMatch {
+            provider: "generic".to_string(),
+            signal: sig.to_string(),
+            confidence: GENERIC_BOOKING_CONFIDENCE,
+        })
+}
+
+/// The parts of a page framework signatures are matched against, lowercased.
+struct FrameworkContexts {
+    urls: String,
+    code: String,
+    attributes: HashSet<String>,
+}
+
+fn framework_contexts(html: &str) -> FrameworkContexts {
+    let urls = ASSET_URL_RE
+        .captures_iter(html)
+        .map(|caps| caps[1].to_lowercase())
+        .collect::<Vec<_>>()
+        .join("\n");
+    let code = SCRIPT_BODY_RE
+        .captures_iter(html)
+        .map(|caps| caps[1].to_lowercase())
+        .collect::<Vec<_>>()
+        .join("\n");
+    let attributes = TAG_ATTRIBUTES_RE
+        .captures_iter(html)
+        .flat_map(|caps| {
+            let attrs = caps.get(1).map_or("", |m| m.as_str());
+            ATTRIBUTE_NAME_RE
+                .captures_iter(attrs)
+                .map(|a| a[1].to_lowercase())
+                .collect::<Vec<_>>()
+        })
+        .collect();
+    FrameworkContexts {
+        urls,
+        code,
+        attributes,
+    }
+}
+
+fn frameworks_in(ctx: &FrameworkContexts) -> Vec<&'static str> {
+    let has_attribute = |sig: &str| {
+        if sig.ends_with('-') {
+            ctx.attributes.iter().any(|a| a.starts_with(sig))
+        } else {
+            ctx.attributes.contains(sig)
+        }
+    };
+    FRAMEWORK_SIGNATURES
+        .iter()
+        .filter(|fw| {
+            fw.urls.iter().any(|sig| ctx.urls.contains(sig))
+                || fw.code.iter().any(|sig| ctx.code.contains(sig))
+                || fw.attributes.iter().any(|sig| has_attribute(sig))
+        })
+        .map(|fw| fw.name)
+        .collect()
+}
+
+struct JsLibrary {
+    name: &'static str,
+    latest_major: u32,
+    // Regex alternation for the library's name in a versioned CDN path
+    // ("jquery-1.8.3.min.js", "/jquery/1.8.3/", "jquery@3.6.0")
+    path_names: &'static str,
+    // Regex alternation for the file name stem WordPress appends ?ver= to
+    file_stems: &'static str,
+    // Other majors are a different library under the same name
+    only_major: Option<u32>,
+}
+
+static JS_LIBRARIES: &[JsLibrary] = &[
+    JsLibrary {
+        name: "jQuery",
+        latest_major: 3,
+        path_names: "jquery",
+        file_stems: r"jquery(?:\.slim)?",
+        only_major: None,
+    },
+    JsLibrary {
+        name: "jQuery UI",
+        latest_major: 1,
+        path_names: r"jquery-?ui|code\.jquery\.com/ui",
+        file_stems: r"jquery-ui|jquery/ui/[\w-]+",
+        only_major: None,
+    },
+    JsLibrary {
+        name: "Bootstrap",
+        latest_major: 5,
+        path_names: "bootstrap",
+        file_stems: r"bootstrap(?:\.bundle)?",
+        only_major: None,
+    },
+    JsLibrary {
+        name: "Moment",
+        latest_major: 2,
+        path_names: r"moment(?:\.js)?",
+        file_stems: "moment",
+        only_major: None,
+    },
+    JsLibrary {
+        name: "AngularJS",
+        latest_major: 1,
+        path_names: r"angular(?:\.?js)?",
+        file_stems: "angular",
+        only_major: Some(1),
+    },
+    JsLibrary {
+        name: "Modernizr",
+        latest_major: 3,
+        path_names: "modernizr",
+        file_stems: r"modernizr(?:\.custom)?",
+        only_major: None,
+    },
+];
+
+// Per library: a versioned CDN path and a WordPress "?ver=" asset
+static JS_LIBRARY_VERSION_RES: LazyLock<Vec<(Regex, Regex)>> = LazyLock::new(|| {
+    JS_LIBRARIES
+        .iter()
+        .map(|lib| {
+            let path = format!(
+                r"(?i)(?:^|[^a-z0-9])(?:{})[@/.-]v?(\d+\.\d+(?:\.\d+)?)\b",
+                lib.path_names
+            );
+            let ver = format!(
+                r"(?i)(?:^|/)(?:{})(?:\.min)?\.(?:js|css)\?(?:[^#]*&)?ver=(\d+\.\d+(?:\.\d+)?)\b",
+                lib.file_stems
+            );
+            (Regex::new(&path).unwrap(), Regex::new(&ver).unwrap())
+        })
+        .collect()
+});
+
+// Latest major per library, seeded from JS_LIBRARIES and updated by
+// register_library_latest
+static LIBRARY_LATEST: LazyLock<RwLock<HashMap<&'static str, u32>>> = LazyLock::new(|| {
+    RwLock::new(
+        JS_LIBRARIES
+            .iter()
+            .map(|lib| (lib.name, lib.latest_major))
+            .collect(),
+    )
+});
+
+#[derive(Debug, PartialEq)]
+struct LibraryVersion {
+    library: &'static str,
+    version: String,
+    latest_known_major: u32,
+    outdated: bool,
+}
+
+/// Versions of well-known JS/CSS libraries in the page's script and
+/// stylesheet URLs, one entry per library and version in page order.
+/// Assets inside HTML comments don't count.
+fn library_versions_of(html: &str) -> Vec<LibraryVersion> {
+    let live = HTML_COMMENT_RE.replace_all(html, " ");
+    let latest = LIBRARY_LATEST.read().unwrap();
+    let mut found: Vec<LibraryVersion> = Vec::new();
+    for caps in ASSET_URL_RE.captures_iter(&live) {
+        // WordPress escapes the query's "&" as "&#038;"
+        let url = decode_html_entities(&caps[1]);
+        for (lib, (path_re, ver_re)) in JS_LIBRARIES.iter().zip(JS_LIBRARY_VERSION_RES.iter()) {
+            let Some(version) = path_re
+                .captures(&url)
+                .or_else(|| ver_re.captures(&url))
+                .map(|c| c[1].to_string())
+            else {
+                continue;
+            };
+            let Some(major) = version
+                .split('.')
+                .next()
+                .and_then(|m| m.parse::<u32>().ok())
+            else {
+                continue;
+            };
+            if lib.only_major.is_some_and(|only| only != major)
+                || found
+                    .iter()
+                    .any(|f| f.library == lib.name && f.version == version)
+            {
+                continue;
+            }
+            let latest_known_major = latest.get(lib.name).copied().unwrap_or(lib.latest_major);
+            found.push(LibraryVersion {
+                library: lib.name,
+                version,
+                latest_known_major,
+                outdated: major < latest_known_major,
+            });
+            break;
+        }
+    }
+    found
+}
+
+fn library_version_dict(py: Python<'_>, lib: &LibraryVersion) -> PyResult<PyObject> {
+    let dict = PyDict::new(py);
+    dict.set_item("library", lib.library)?;
+    dict.set_item("version", &lib.version)?;
+    dict.set_item("latest_known_major", lib.latest_known_major)?;
+    dict.set_item("outdated", lib.outdated)?;
+    Ok(dict.into())
+}
+
+static FLASH_RE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(
+        r"(?i)<(?:object|embed)\b[^>]*(?:application/x-shockwave-flash|clsid:d27cdb6e-ae6d-11cf-96b8-444553540000|\.swf\b)|\bswfobject(?:\.min)?\.js\b|\bswfobject\.embedSWF\s*\(",
+    )
+    .unwrap()
+});
+static FRAMESET_RE: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"(?i)<frameset\b").unwrap());
+static MARQUEE_RE: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"(?i)<marquee\b").unwrap());
+// FrontPage also marks its pages <meta name="ProgId" content="FrontPage.Editor.Document">
+static FRONTPAGE_PROGID_RE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r#"(?i)<meta\b[^>]*\bcontent\s*=\s*["']FrontPage\.Editor\.Document"#).unwrap()
+});
+// mhtml: URLs, links to .mht archives, or the page itself saved as one
+static MHTML_RE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(
+        r#"(?i)\bmhtml:|<a\b[^>]*\bhref\s*=\s*["']?[^"'\s>]+\.mht(?:ml)?["'\s>]|\bContent-Type:\s*multipart/related"#,
+    )
+    .unwrap()
+});
+static VBSCRIPT_RE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r#"(?i)<script\b[^>]*\b(?:language|type)\s*=\s*["']?(?:text/)?vbscript|\bvbscript:"#)
+        .unwrap()
+});
+static NESTED_LAYOUT_TABLE_SEL: LazyLock<Selector> =
+    LazyLock::new(|| Selector::parse("table[width] table[width]").unwrap());
+
+// File names of transparent layout spacer images
+const SPACER_GIFS: &[&str] = &[
+    "spacer.gif",
+    "shim.gif",
+    "clear.gif",
+    "clearpixel.gif",
+    "trans.gif",
+    "transparent.gif",
+];
+
+/// Legacy-technology flags, in this order: "flash", "frameset",
+/// "marquee", "frontpage_generator", "dreamweaver_generator",
+/// "table_layout", "spacer_gif", "mhtml", "vbscript". Markup in HTML
+/// comments doesn't count.
+fn legacy_flags_of(html: &str, ctx: &FrameworkContexts) -> Vec<&'static str> {
+    let live = HTML_COMMENT_RE.replace_all(html, " ");
+    let generators: Vec<String> = GENERATOR_META_RE
+        .find_iter(&live)
+        .filter_map(|tag| META_CONTENT_RE.captures(tag.as_str()))
+        .map(|content| content[1].to_lowercase())
+        .collect();
+    let document = Html::parse_document(html);
+    // Nested width-sized tables are layout; a data table on a Bootstrap or
+    // Tailwind site isn't
+    let table_layout = document.select(&NESTED_LAYOUT_TABLE_SEL).next().is_some()
+        && !frameworks_in(ctx)
+            .iter()
+            .any(|fw| matches!(*fw, "Bootstrap" | "Tailwind"));
+    // Lazy-loading placeholders are blank gifs too, but carry the real src
+    let spacer_gif = document.select(&IMG_SEL).any(|img| {
+        let file = attr_of(img, "src")
+            .rsplit('/')
+            .next()
+            .unwrap_or("")
+            .to_ascii_lowercase();
+        SPACER_GIFS.contains(&file.as_str())
+            && !img
+                .value()
+                .attrs()
+                .any(|(name, _)| name.starts_with("data-") && name.ends_with("src"))
+    });
+
+    [
+        ("flash", FLASH_RE.is_match(&live)),
+        ("frameset", FRAMESET_RE.is_match(&live)),
+        ("marquee", MARQUEE_RE.is_match(&live)),
+        (
+            "frontpage_generator",
+            generators.iter().any(|g| g.contains("frontpage"))
+                || FRONTPAGE_PROGID_RE.is_match(&live),
+        ),
+        (
+            "dreamweaver_generator",
+            generators.iter().any(|g| g.contains("dreamweaver")),
+        ),
+        ("table_layout", table_layout),
+        ("spacer_gif", spacer_gif),
+        ("mhtml", MHTML_RE.is_match(&live)),
+        ("vbscript", VBSCRIPT_RE.is_match(&live)),
+    ]
+    .into_iter()
+    .filter_map(|(flag, found)| found.then_some(flag))
+    .collect()
+}
+
+struct ResponsiveCheck {
+    responsive: bool,
+    has_viewport_meta: bool,
+    has_media_queries: bool,
+    framework_hint: Option<&'static str>,
+}
+
+/// A viewport meta tag with width=device-width.
+fn has_device_width_viewport(html: &str) -> bool {
+    VIEWPORT_META_RE.find_iter(html).any(|tag| {
+        META_CONTENT_RE
+            .captures(tag.as_str())
+            .is_some_and(|content| {
+                content[1]
+                    .to_lowercase()
+                    .replace(char::is_whitespace, "")
+                    .contains("width=device-width")
+            })
+    })
+}
+
+/// A device-width viewport meta is the real test; width breakpoints and a
+/// responsive CSS framework (Bootstrap 3+, Tailwind) stand in when the meta
+/// is missing.
+fn check_responsive(html: &str, ctx: &FrameworkContexts) -> ResponsiveCheck {
+    let has_viewport_meta = has_device_width_viewport(html);
+
+    let urls = &ctx.urls;
+    let has_media_queries = STYLE_BODY_RE
+        .captures_iter(html)
+        .any(|caps| WIDTH_MEDIA_QUERY_RE.is_match(&caps[1]))
+        || LINK_MEDIA_WIDTH_RE.is_match(html)
+        || urls
+            .lines()
+            .any(|url| url.contains("responsive") && url.contains(".css"));
+
+    let frameworks = frameworks_in(ctx);
+    let bootstrap_modern = frameworks.contains(&"Bootstrap")
+        && BOOTSTRAP_VERSION_RE
+            .captures(urls)
+            .and_then(|caps| caps[1].parse::<u32>().ok())
+            .is_none_or(|major| major >= 3);
+    let framework_hint = if frameworks.contains(&"Tailwind") {
+        Some("Tailwind")
+    } else if bootstrap_modern {
+        Some("Bootstrap")
+    } else {
+        None
+    };
+
+    ResponsiveCheck {
+        responsive: has_viewport_meta || has_media_queries || framework_hint.is_some(),
+        has_viewport_meta,
+        has_media_queries,
+        framework_hint,
+    }
+}
+
+static AMP_LINK_SEL: LazyLock<Selector> =
+    LazyLock::new(|| Selector::parse("link[rel~=amphtml][href]").unwrap());
+static MOBILE_ALTERNATE_SEL: LazyLock<Selector> = LazyLock::new(|| {
+    Selector::parse("link[rel~=alternate][href], link[rel~=canonical][href]").unwrap()
+});
+
+#[derive(Debug, PartialEq)]
+struct AmpCheck {
+    is_amp: bool,
+    amp_url: Option<String>,
+}
+
+/// Whether the page is itself AMP (`<html amp>` / `<html ⚡>`) and where
+/// its AMP version lives (`<link rel="amphtml">`). The AMP runtime script
+/// alone decides nothing: third-party embeds load it on ordinary pages.
+fn amp_of(html: &str) -> AmpCheck {
+    let document = Html::parse_document(html);
+    let is_amp = document
+        .root_element()
+        .value()
+        .attrs()
+        .any(|(name, _)| matches!(name, "amp" | "⚡"));
+    let amp_url = document
+        .select(&AMP_LINK_SEL)
+        .map(|link| attr_of(link, "href").trim())
+        .find(|href| !href.is_empty())
+        .map(str::to_string);
+    AmpCheck { is_amp, amp_url }
+}
+
+/// The first alternate or canonical `<link>` pointing at the m-dot host of
+/// `domain` (m.example.com for www.example.com or example.com).
+fn mdot_reference_of(html: &str, domain: &str) -> Option<String> {
+    let mdot = format!("m.{}", normalize_domain(domain, false, false, true)?);
+    let document = Html::parse_document(html);
+    document
+        .select(&MOBILE_ALTERNATE_SEL)
+        .map(|link| attr_of(link, "href").trim())
+        .find(|href| {
+            let absolute = match href.strip_prefix("//") {
+                Some(rest) => Cow::Owned(format!("https://{}", rest)),
+                None if href.contains("://") => Cow::Borrowed(*href),
+                // Relative links stay on the page's own host
+                None => return false,
+            };
+            normalize_domain(&absolute, false, false, true).is_some_and(|host| host == mdot)
+        })
+        .map(str::to_string)
+}
+
+static MANIFEST_LINK_SEL: LazyLock<Selector> =
+    LazyLock::new(|| Selector::parse("link[rel~=manifest][href]").unwrap());
+static THEME_COLOR_SEL: LazyLock<Selector> =
+    LazyLock::new(|| Selector::parse(r#"meta[name="theme-color" i][content]"#).unwrap());
+static SCRIPT_SEL: LazyLock<Selector> = LazyLock::new(|| Selector::parse("script").unwrap());
+static SERVICE_WORKER_REGISTER_RE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"(?i)\bserviceWorker\s*\.\s*register\s*\(|\bnew\s+Workbox\s*\(|\bregisterSW\s*\(")
+        .unwrap()
+});
+// Block comments and // line comments (not the // of a URL)
+static JS_COMMENT_RE: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"(?s)/\*.*?\*/|(?m)(?:^|[\s;{}(])//.*$").unwrap());
+
+// Service worker loaders that register from a bundled script (Workbox,
+// next-pwa, vite-plugin-pwa)
+const SERVICE_WORKER_SCRIPTS: &[&str] = &[
+    "workbox-window",
+    "workbox-sw",
+    "next-pwa",
+    "registersw.js",
+    "register-sw.js",
+    "sw-register",
+];
+
+struct PwaCheck {
+    manifest_url: Option<String>,
+    registers_service_worker: bool,
+    has_theme_color: bool,
+}
+
+/// Web app manifest, service worker registration and theme-color meta.
+/// Only the page's own scripts count: commented-out code, HTML comments and
+/// iframe srcdoc documents are not scripts of this page.
+fn pwa_of(html: &str) -> PwaCheck {
+    let document = Html::parse_document(html);
+    let manifest_url = document
+        .select(&MANIFEST_LINK_SEL)
+        .map(|link| attr_of(link, "href").trim())
+        .find(|href| !href.is_empty())
+        .map(str::to_string);
+    let has_theme_color = document
+        .select(&THEME_COLOR_SEL)
+        .any(|meta| !attr_of(meta, "content").trim().is_empty());
+    let registers_service_worker = document.select(&SCRIPT_SEL).any(|script| {
+        let src = attr_of(script, "src").to_ascii_lowercase();
+        if !src.is_empty() {
+            return SERVICE_WORKER_SCRIPTS.iter().any(|sig| src.contains(sig));
+        }
+        let code = script.text().collect::<String>();
+        SERVICE_WORKER_REGISTER_RE.is_match(&JS_COMMENT_RE.replace_all(&code, " "))
+    });
+    PwaCheck {
+        manifest_url,
+        registers_service_worker,
+        has_theme_color,
+    }
+}
+
+fn cms_of(scan: &PageScan) -> Option<String> {
+    match_cms(scan)
+        .into_iter()
+        .next()
+        .filter(|m| m.score >= MIN_CMS_SCORE)
+        .map(|m| m.cms)
+}
+
+fn tracking_of(html: &str, scan: &PageScan) -> HashMap<String, bool> {
+    let mut result: HashMap<String, bool> = TRACKING_SIGNATURES
+        .iter()
+        .map(|(tracker, signatures)| {
+            let found = signatures.iter().any(|sig| scan.contains(sig));
+            (tracker.to_string(), found)
+        })
+        .collect();
+    for (tracker, pattern) in TRACKING_ID_PATTERNS.iter() {
+        if pattern.is_match(html) {
+            result.insert(tracker.to_string(), true);
+        }
+    }
+
+    let analytics = result["ga4"] || result["universal_analytics"];
+    result.insert("google_analytics".to_string(), analytics);
+    result
+}
+
+/// Everything analyze_tech_stack reports for one page, computed without
+/// touching Python so batches can run on the rayon pool.
+#[derive(Debug, PartialEq)]
+struct TechStack {
+    cms: Option<String>,
+    cms_version: Option<String>,
+    page_builder: Option<&'static str>,
+    wp_plugins: Vec<String>,
+    payment_providers: Vec<&'static str>,
+    marketing_tools: Vec<&'static str>,
+    review_widgets: Vec<&'static str>,
+    chat: ChatCheck,
+    ecommerce: EcommerceCheck,
+    wp_theme: Option<WpTheme>,
+    tracking: HashMap<String, bool>,
+    tracking_ids: Vec<(&'static str, Vec<String>)>,
+    booking: Option<BookingMatch>,
+    frameworks: Vec<&'static str>,
+    libraries: Vec<LibraryVersion>,
+    legacy_flags: Vec<&'static str>,
+    has_gbp_link: bool,
+    has_map: bool,
+    has_video: bool,
+    languages: Vec<String>,
+    has_contact_form: bool,
+    captcha: Option<&'static str>,
+    schema_types: Vec<String>,
+    content_bucket: &'static str,
+    content_language: Option<ContentLanguage>,
+    has_ssl: Option<bool>,
+    final_domain: Option<String>,
+    canonical_scheme_mismatch: Option<bool>,
+    mixed_content: Option<MixedContent>,
+    has_responsive: bool,
+    amp: AmpCheck,
+    is_pwa: bool,
+    parked: bool,
+}
+
+fn tech_stack_of(html: &str, final_url: Option<&str>) -> TechStack {
+    // One lowercase copy and one signature pass shared by every detector
+    tech_stack_in(html, final_url, &PageScan::new(html))
+}
+
+/// Benches the signature-table detectors on the signature index
+/// (`indexed`) or the way they ran before it, each lowercasing the page and
+/// searching it for every signature in turn.
+#[cfg(feature = "bench")]
+pub fn bench_signature_detectors(html: &str, indexed: bool) -> impl std::fmt::Debug {
+    let shared = indexed.then(|| PageScan::new(html));
+    let scan = || match &shared {
+        Some(scan) => std::borrow::Cow::Borrowed(scan),
+        None => std::borrow::Cow::Owned(PageScan::unindexed(html)),
+    };
+    (
+        cms_of(&scan()),
+        page_builder_of(&scan()),
+        wp_plugins_of(html, &scan()),
+        check_ecommerce(html, &scan()),
+        payment_providers_of(&scan()),
+        check_chat_widget(html, &scan()),
+        marketing_tools_of(&scan()),
+        review_widgets_of(html, &scan()),
+        tracking_of(html, &scan()),
+        booking_match_of(&scan()),
+        check_parked(&scan()).parked,
+    )
+}
+
+/// Benches the whole tech-stack pass on the signature index (`indexed`) or
+/// on one lowercase copy searched with the substring search it replaced.
+#[cfg(feature = "bench")]
+pub fn bench_tech_stack(html: &str, indexed: bool) -> impl std::fmt::Debug {
+    let scan = if indexed {
+        PageScan::new(html)
+    } else {
+        PageScan::unindexed(html)
+    };
+    tech_stack_in(html, None, &scan)
+}
+
+fn tech_stack_in(html: &str, final_url: Option<&str>, scan: &PageScan) -> TechStack {
+    let cms = cms_of(scan);
+    let page_builder = match cms.as_deref() {
+        Some("WordPress") => page_builder_of(scan),
+        _ => None,
+    };
+    let contexts = framework_contexts(html);
+    let pwa = pwa_of(html);
+    let has_ssl = final_url.map(is_https_url);
+
+    TechStack {
+        cms_version: cms_version_candidates(html)
+            .into_iter()
+            .next()
+            .map(|c| c.version),
+        cms,
+        page_builder,
+        wp_plugins: wp_plugins_of(html, scan),
+        payment_providers: payment_providers_of(scan),
+        marketing_tools: marketing_tools_of(scan),
+        review_widgets: review_widgets_of(html, scan),
+        chat: check_chat_widget(html, scan),
+        ecommerce: check_ecommerce(html, scan),
+        wp_theme: wp_theme_of(html),
+        tracking: tracking_of(html, scan),
+        tracking_ids: tracking_ids_of(html),
+        booking: booking_match_of(scan),
+        frameworks: frameworks_in(&contexts),
+        libraries: library_versions_of(html),
+        legacy_flags: legacy_flags_of(html, &contexts),
+        has_gbp_link: !gbp_links_of(html).urls.is_empty(),
+        has_map: map_embeds_of(html).count > 0,
+        has_video: videos_of(html).count > 0,
+        languages: languages_of(html, scan).languages,
+        has_contact_form: contact_forms_of(html).count > 0,
+        captcha: captcha_of(html).map(|c| c.provider),
+        schema_types: schema_types_of(html),
+        content_bucket: content_depth_of(html, CONTENT_THIN_WORDS, CONTENT_RICH_WORDS).bucket,
+        content_language: content_language_of(html),
+        has_ssl,
+        final_domain: final_url.and_then(|url| normalize_domain(url, false, false, true)),
+        canonical_scheme_mismatch: has_ssl.map(|https| canonical_scheme_mismatch_of(html, https)),
+        mixed_content: (has_ssl == Some(true)).then(|| mixed_content_of(html)),
+        has_responsive: check_responsive(html, &contexts).responsive,
+        amp: amp_of(html),
+        is_pwa: pwa.manifest_url.is_some() && pwa.registers_service_worker,
+        parked: check_parked(scan).parked,
+    }
+}
+
+fn tech_stack_dict(py: Python<'_>, stack: TechStack) -> PyResult<PyObject> {
+    let dict = PyDict::new(py);
+    dict.set_item("cms", stack.cms)?;
+    dict.set_item("cms_version", stack.cms_version)?;
+    dict.set_item("page_builder", stack.page_builder)?;
+    dict.set_item("wp_plugins", stack.wp_plugins)?;
+    dict.set_item("payment_providers", stack.payment_providers)?;
+    dict.set_item("marketing_tool_count", stack.marketing_tools.len())?;
+    dict.set_item("marketing_tools", stack.marketing_tools)?;
+    dict.set_item("has_social_proof", !stack.review_widgets.is_empty())?;
+    dict.set_item("review_widgets", stack.review_widgets)?;
+    dict.set_item("chat_widget", stack.chat.vendor)?;
+    dict.set_item("has_chat", stack.chat.has_chat)?;
+    dict.set_item("ecommerce", stack.ecommerce.platform)?;
+    dict.set_item(
+        "has_online_store",
+        stack.ecommerce.platform.is_some() || stack.ecommerce.has_cart,
+    )?;
+    match stack.wp_theme {
+        Some(theme) => dict.set_item("wp_theme", wp_theme_dict(py, theme)?)?,
+        None => dict.set_item("wp_theme", py.None())?,
+    }
+
+    let tracking_dict = PyDict::new(py);
+    for (k, v) in &stack.tracking {
+        tracking_dict.set_item(k, *v)?;
+    }
+    dict.set_item("tracking", tracking_dict)?;
+    dict.set_item("tracking_ids", tracking_ids_dict(py, stack.tracking_ids)?)?;
+
+    dict.set_item("has_booking", stack.booking.is_some())?;
+    dict.set_item("booking_provider", stack.booking.map(|b| b.provider))?;
+    dict.set_item("frameworks", stack.frameworks)?;
+    let outdated = PyList::empty(py);
+    for lib in stack.libraries.iter().filter(|lib| lib.outdated) {
+        outdated.append(library_version_dict(py, lib)?)?;
+    }
+    dict.set_item("outdated_libraries", outdated)?;
+    dict.set_item("legacy_flags", stack.legacy_flags)?;
+    dict.set_item("has_gbp_link", stack.has_gbp_link)?;
+    dict.set_item("has_map", stack.has_map)?;
+    dict.set_item("has_video", stack.has_video)?;
+    dict.set_item("languages", stack.languages)?;
+    dict.set_item("has_contact_form", stack.has_contact_form)?;
+    dict.set_item("captcha", stack.captcha)?;
+    dict.set_item(
+        "has_local_business_schema",
+        stack.schema_types.iter().any(|t| is_local_business_type(t)),
+    )?;
+    dict.set_item("schema_types", stack.schema_types)?;
+    dict.set_item("content_bucket", stack.content_bucket)?;
+    match stack.content_language {
+        Some(language) => {
+            dict.set_item("content_language", content_language_dict(py, language)?)?
+        }
+        None => dict.set_item("content_language", py.None())?,
+    }
+    dict.set_item("has_ssl", stack.has_ssl)?;
+    dict.set_item("final_domain", stack.final_domain)?;
+    dict.set_item("canonical_scheme_mismatch", stack.canonical_scheme_mismatch)?;
+    match stack.mixed_content {
+        Some(mixed) => dict.set_item("mixed_content", mixed_content_dict(py, mixed)?)?,
+        None => dict.set_item("mixed_content", py.None())?,
+    }
+    dict.set_item("has_responsive", stack.has_responsive)?;
+    dict.set_item("is_amp", stack.amp.is_amp)?;
+    dict.set_item("has_amp_link", stack.amp.amp_url.is_some())?;
+    dict.set_item("is_pwa", stack.is_pwa)?;
+    dict.set_item("parked", stack.parked)?;
+    Ok(dict.into())
+}
+
+fn is_https_url(url: &str) -> bool {
+    url.trim_start()
+        .get(..8)
+        .is_some_and(|scheme| scheme.eq_ignore_ascii_case("https://"))
+}
+
+fn is_http_url(url: &str) -> bool {
+    url.trim_start()
+        .get(..7)
+        .is_some_and(|scheme| scheme.eq_ignore_ascii_case("http://"))
+}
+
+/// Whether the canonical or og:url is on the other scheme from the page:
+/// https on an http page (an upgrade hint) or http on an https one.
+fn canonical_scheme_mismatch_of(html: &str, page_is_https: bool) -> bool {
+    let live = HTML_COMMENT_RE.replace_all(html, " ");
+    PAGE_URL_TAG_RE
+        .find_iter(&live)
+        .filter_map(|tag| PAGE_URL_ATTR_RE.captures(tag.as_str()))
+        .any(|caps| {
+            let url = decode_html_entities(&caps[1]);
+            (is_https_url(&url) && !page_is_https) || (is_http_url(&url) && page_is_https)
+        })
+}
+
+// Mixed content
+
+// CSS url() and @import references
+static CSS_URL_RE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r#"(?i)(@import\s+)?url\(\s*["']?([^"')\s]+)|@import\s+["']([^"']+)["']"#).unwrap()
+});
+static MIXED_CONTENT_SEL: LazyLock<Selector> = LazyLock::new(|| {
+    Selector::parse("[src], [srcset], link[href], object[data], [style], style").unwrap()
+});
+
+// Sample URLs reported per page
+const MAX_MIXED_CONTENT_SAMPLES: usize = 10;
+
+#[derive(Debug, Default, PartialEq)]
+struct MixedContent {
+    active: usize,
+    passive: usize,
+    samples: Vec<String>,
+}
+
+impl MixedContent {
+    fn note(&mut self, url: &str, active: bool) {
+        let url = url.trim();
+        if !is_http_url(url) {
+            return;
+        }
+        if active {
+            self.active += 1;
+        } else {
+            self.passive += 1;
+        }
+        if self.samples.len() < MAX_MIXED_CONTENT_SAMPLES && !self.samples.iter().any(|s| s == url)
+        {
+            self.samples.push(url.to_string());
+        }
+    }
+
+    fn note_css(&mut self, css: &str) {
+        for caps in CSS_URL_RE.captures_iter(css) {
+            // @import pulls in a stylesheet; other url()s are images and fonts
+            let import = caps.get(1).is_some() || caps.get(3).is_some();
+            if let Some(url) = caps.get(2).or(caps.get(3)) {
+                self.note(url.as_str(), import);
+            }
+        }
+    }
+}
+
+/// http:// subresources an https page loads. Scripts, iframes, stylesheets,
+/// plugins and @imports are active; images, media, icons and CSS url()s
+/// passive. Links, canonical/og meta and anything in comments don't load
+/// and are skipped.
+fn mixed_content_of(html: &str) -> MixedContent {
+    let live = HTML_COMMENT_RE.replace_all(html, " ");
+    let document = Html::parse_document(&live);
+    let mut mixed = MixedContent::default();
+    for e in document.select(&MIXED_CONTENT_SEL) {
+        let name = e.value().name();
+        if let Some(style) = e.value().attr("style") {
+            mixed.note_css(style);
+        }
+        match name {
+            "style" => mixed.note_css(&e.text().collect::<String>()),
+            "link" => {
+                let rel = attr_of(e, "rel").to_ascii_lowercase();
+                let rels: Vec<&str> = rel.split_whitespace().collect();
+                let active = if rels.contains(&"stylesheet") || rels.contains(&"modulepreload") {
+                    true
+                } else if rels.contains(&"preload") {
+                    matches!(attr_of(e, "as"), "script" | "style" | "document")
+                } else if rels.iter().any(|r| r.contains("icon")) {
+                    false
+                } else {
+                    continue;
+                };
+                mixed.note(attr_of(e, "href"), active);
+            }
+            "object" => mixed.note(attr_of(e, "data"), true),
+            _ => {
+                let active = matches!(name, "script" | "iframe" | "frame" | "embed");
+                mixed.note(attr_of(e, "src"), active);
+            }
+        }
+        for candidate in attr_of(e, "srcset").split(',') {
+            mixed.note(candidate.split_whitespace().next().unwrap_or(""), false);
+        }
+    }
+    mixed
+}
+
+fn mixed_content_dict(py: Python<'_>, mixed: MixedContent) -> PyResult<PyObject> {
+    let dict = PyDict::new(py);
+    dict.set_item("has_mixed_content", mixed.active + mixed.passive > 0)?;
+    dict.set_item("active_count", mixed.active)?;
+    dict.set_item("passive_count", mixed.passive)?;
+    dict.set_item("sample_urls", mixed.samples)?;
+    Ok(dict.into())
+}
+
+// Page weight
+
+static PAGE_ASSET_SEL: LazyLock<Selector> = LazyLock::new(|| {
+    Selector::parse("script, style, link[href], [src], object[data], [style]").unwrap()
+});
+static FONT_FILE_RE: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"(?i)\.(?:woff2?|ttf|otf|eot)(?:[?#]|$)").unwrap());
+
+// Third-party registrable domains reported per page
+const MAX_THIRD_PARTY_HOSTS: usize = 50;
+
+#[derive(Default)]
+struct PageWeight {
+    external_scripts: usize,
+    external_styles: usize,
+    inline_script_bytes: usize,
+    inline_style_bytes: usize,
+    font_files: usize,
+    third_party_hosts: Vec<String>,
+    html_bytes: usize,
+}
+
+/// Registrable domain of an absolute or protocol-relative URL; relative
+/// URLs are on the page's own host and give None.
+fn asset_domain(url: &str) -> Option<String> {
+    let url = url.trim();
+    if url.starts_with("//") {
+        canonical_domain(&format!("https:{}", url))
+    } else if is_http_url(url) || is_https_url(url) {
+        canonical_domain(url)
+    } else {
+        None
+    }
+}
+
+/// Asset counts and inline code sizes from the markup alone, with the
+/// registrable domains of assets served from anywhere other than
+/// `page_domain` (else the canonical URL's domain). Only references that
+/// load count: scripts, stylesheets, preloads, icons, media and frames,
+/// not links or meta. HTML comments are left out.
+fn page_weight_of(html: &str, page_domain: Option<&str>) -> PageWeight {
+    let live = HTML_COMMENT_RE.replace_all(html, " ");
+    let document = Html::parse_document(&live);
+    let own_domain = page_domain.and_then(canonical_domain).or_else(|| {
+        meta_seo_in(&document)
+            .canonical_url
+            .and_then(|url| asset_domain(&url))
+    });
+    let mut weight = PageWeight {
+        html_bytes: html.len(),
+        ..PageWeight::default()
+    };
+    let mut fonts: HashSet<String> = HashSet::new();
+    let mut asset_urls: Vec<&str> = Vec::new();
+
+    for e in document.select(&PAGE_ASSET_SEL) {
+        if let Some(style) = e.value().attr("style") {
+            weight.inline_style_bytes += style.len();
+        }
+        match e.value().name() {
+            "script" => match e.value().attr("src") {
+                Some(src) => {
+                    weight.external_scripts += 1;
+                    asset_urls.push(src);
+                }
+                None => weight.inline_script_bytes += e.text().map(str::len).sum::<usize>(),
+            },
+            "style" => {
+                let css = e.text().collect::<String>();
+                weight.inline_style_bytes += css.len();
+                for caps in CSS_URL_RE.captures_iter(&css) {
+                    if let Some(url) = caps.get(2).or(caps.get(3)) {
+                        if FONT_FILE_RE.is_match(url.as_str()) {
+                            fonts.insert(url.as_str().to_string());
+                        }
+                    }
+                }
+            }
+            "link" => {
+                let rel = attr_of(e, "rel").to_ascii_lowercase();
+                let href = attr_of(e, "href");
+                let loads = rel.split_whitespace().any(|r| {
+                    matches!(r, "stylesheet" | "preload" | "modulepreload" | "prefetch")
+                        || r.contains("icon")
+                });
+                if !loads {
+                    continue;
+                }
+                if rel.split_whitespace().any(|r| r == "stylesheet") {
+                    weight.external_styles += 1;
+                }
+                if attr_of(e, "as") == "font" || FONT_FILE_RE.is_match(href) {
+                    fonts.insert(href.trim().to_string());
+                }
+                asset_urls.push(href);
+            }
+            "object" => asset_urls.push(attr_of(e, "data")),
+            _ => asset_urls.push(attr_of(e, "src")),
+        }
+    }
+    weight.font_files = fonts.len();
+
+    for url in asset_urls {
+        if weight.third_party_hosts.len() >= MAX_THIRD_PARTY_HOSTS {
+            break;
+        }
+        let Some(domain) = asset_domain(url) else {
+            continue;
+        };
+        if own_domain.as_ref() != Some(&domain) && !weight.third_party_hosts.contains(&domain) {
+            weight.third_party_hosts.push(domain);
+        }
+    }
+    weight
+}
+
+// Render-blocking resources
+
+static HEAD_RESOURCE_SEL: LazyLock<Selector> = LazyLock::new(|| {
+    Selector::parse("head script[src], head link[rel~=stylesheet i][href], head style").unwrap()
+});
+
+// An inline <style> in the head larger than this is inlined critical CSS
+const CRITICAL_CSS_MIN_BYTES: usize = 2048;
+// Blocking resource URLs reported per page
+const MAX_RENDER_BLOCKING_SAMPLES: usize = 10;
+
+#[derive(Default)]
+struct RenderBlocking {
+    scripts: usize,
+    styles: usize,
+    inlined_critical_css: bool,
+    sample: Vec<String>,
+}
+
+impl RenderBlocking {
+    fn note(&mut self, url: &str) {
+        if self.sample.len() < MAX_RENDER_BLOCKING_SAMPLES {
+            self.sample.push(url.trim().to_string());
+        }
+    }
+}
+
+/// Resources in the `<head>` that hold up first paint: classic scripts
+/// without async/defer (modules are deferred; JSON and template blocks
+/// aren't run) and stylesheets that aren't media="print" or alternate.
+/// Preload and prefetch links aren't stylesheets and don't count.
+fn render_blocking_in(document: &Html) -> RenderBlocking {
+    let mut blocking = RenderBlocking::default();
+    for e in document.select(&HEAD_RESOURCE_SEL) {
+        let has = |name: &str| e.value().attr(name).is_some();
+        match e.value().name() {
+            "script" => {
+                let kind = attr_of(e, "type").trim().to_ascii_lowercase();
+                let classic =
+                    kind.is_empty() || kind.contains("javascript") || kind.contains("ecmascript");
+                if classic && !has("async") && !has("defer") {
+                    blocking.scripts += 1;
+                    blocking.note(attr_of(e, "src"));
+                }
+            }
+            "link" => {
+                let rel = attr_of(e, "rel").to_ascii_lowercase();
+                let media = attr_of(e, "media").trim().to_ascii_lowercase();
+                if !rel.split_whitespace().any(|r| r == "alternate")
+                    && media != "print"
+                    && !has("disabled")
+                {
+                    blocking.styles += 1;
+                    blocking.note(attr_of(e, "href"));
+                }
+            }
+            _ => {
+                let bytes: usize = e.text().map(str::len).sum();
+                blocking.inlined_critical_css |= bytes > CRITICAL_CSS_MIN_BYTES;
+            }
+        }
+    }
+    blocking
+}
+
+/// Canonical profile URL for a social link, with the platform it belongs to
+/// and the profile handle: https, no www./m. prefix, trimmed to the profile
+/// path, no query, fragment or trailing slash (except Facebook's
+/// profile.php?id=). None for other sites and for share, intent, embed and
+/// single-post links.
+fn social_profile_of(href: &str) -> Option<(&'static str, String, String)> {
+    let href = href.trim();
+    let absolute = if href.starts_with("//") {
+        format!("https:{}", href)
+    } else {
+        href.to_string()
+    };
+    let url = url::Url::parse(&absolute).ok()?;
+    if !matches!(url.scheme(), "http" | "https") {
+        return None;
+    }
+    let host = url.host_str()?.to_ascii_lowercase();
+    let host = ["www.", "m.", "mobile.", "web."]
+        .iter()
+        .find_map(|prefix| host.strip_prefix(prefix))
+        .unwrap_or(&host)
+        .to_string();
+    let path = url.path().trim_end_matches('/');
+    let path_lower = path.to_ascii_lowercase();
+    if path.is_empty() {
+        return None;
+    }
+
+    // linkedin.com is two platforms, told apart by the profile prefix
+    let (platform, prefix) = SOCIAL_PLATFORMS.iter().find_map(|p| {
+        if !p
+            .hosts
+            .iter()
+            .any(|h| host == *h || host.ends_with(&format!(".{}", h)))
+        {
+            return None;
+        }
+        if p.profile_prefixes.is_empty() {
+            return Some((p, ""));
+        }
+        p.profile_prefixes
+            .iter()
+            .find(|prefix| path_lower.starts_with(*prefix))
+            .map(|prefix| (p, *prefix))
+    })?;
+    if platform
+        .excluded_prefixes
+        .iter()
+        .any(|excluded| path_lower.starts_with(excluded))
+    {
+        return None;
+    }
+
+    let segments: Vec<&str> = path.split('/').filter(|s| !s.is_empty()).collect();
+    let prefix_segments = prefix.split('/').filter(|s| !s.is_empty()).count();
+    let mut kept = if prefix.is_empty() || prefix.ends_with('/') {
+        prefix_segments + 1
+    } else {
+        prefix_segments
+    };
+    if platform.name == "facebook"
+        && segments
+            .first()
+            .is_some_and(|s| s.eq_ignore_ascii_case("pages") || s.eq_ignore_ascii_case("people"))
+    {
+        // /pages/Acme-Plumbing/1234567890
+        kept = 3;
+    }
+    if segments.len() < kept
+        || segments[kept..].iter().any(|s| {
+            platform
+                .excluded_segments
+                .iter()
+                .any(|excluded| s.eq_ignore_ascii_case(excluded))
+        })
+    {
+        return None;
+    }
+
+    let profile_path = segments[..kept].join("/");
+    let mut canonical = format!("https://{}/{}", platform.hosts[0], profile_path);
+    let mut handle = segments[kept - 1].trim_start_matches('@').to_string();
+    if platform.name == "facebook" && path_lower == "/profile.php" {
+        let id = url.query_pairs().find(|(k, _)| k == "id")?.1;
+        canonical.push_str(&format!("?id={}", id));
+        handle.clear();
+    } else if platform.name == "facebook" && kept == 3 {
+        handle = segments[1].to_string();
+    }
+    Some((platform.name, canonical, handle))
+}
+
+/// A distinct profile URL found on a page.
+struct SocialCandidate {
+    platform: &'static str,
+    url: String,
+    handle: String,
+    first: usize,
+    count: usize,
+    in_chrome: bool,
+}
+
+/// Every profile link on the page, deduplicated by canonical URL, in page
+/// order. Links inside embedded posts or tagged as embeds are skipped.
+fn social_candidates_of(html: &str) -> Vec<SocialCandidate> {
+    let embeds: Vec<(usize, usize)> = SOCIAL_EMBED_RE
+        .find_iter(html)
+        .map(|m| (m.start(), m.end()))
+        .collect();
+    let chrome: Vec<(usize, usize)> = PAGE_CHROME_RE
+        .find_iter(html)
+        .map(|m| (m.start(), m.end()))
+        .collect();
+    let inside = |ranges: &[(usize, usize)], at: usize| {
+        ranges.iter().any(|&(start, end)| start <= at && at < end)
+    };
+
+    let mut candidates: Vec<SocialCandidate> = Vec::new();
+    let mut by_url: HashMap<String, usize> = HashMap::new();
+    let mut parsed: HashMap<String, Option<(&'static str, String, String)>> = HashMap::new();
+    for caps in ANCHOR_HREF_RE.captures_iter(html) {
+        let at = caps.get(0).map_or(0, |m| m.start());
+        let href = decode_html_entities(&caps[1]);
+        let href_lower = href.to_ascii_lowercase();
+        if !SOCIAL_PLATFORMS
+            .iter()
+            .any(|p| p.hosts.iter().any(|h| href_lower.contains(h)))
+            || href_lower.contains("ig_embed")
+            || inside(&embeds, at)
+        {
+            continue;
+        }
+        let profile = parsed
+            .entry(href.into_owned())
+            .or_insert_with_key(|href| social_profile_of(href));
+        let Some((platform, url, handle)) = profile else {
+            continue;
+        };
+        let in_chrome = inside(&chrome, at);
+        match by_url.get(url.as_str()) {
+            Some(&i) => {
+                candidates[i].count += 1;
+                candidates[i].in_chrome |= in_chrome;
+            }
+            None => {
+                by_url.insert(url.clone(), candidates.len());
+                candidates.push(SocialCandidate {
+                    platform,
+                    url: url.clone(),
+                    handle: handle.clone(),
+                    first: at,
+                    count: 1,
+                    in_chrome,
+                });
+            }
+        }
+    }
+    candidates
+}
+
+fn compact_alnum(s: &str) -> String {
+    s.chars()
+        .filter(|c| c.is_alphanumeric())
+        .flat_map(|c| c.to_lowercase())
+        .collect()
+}
+
+/// The page's own name: the site's domain label (from the canonical or
+/// og:url) and the words of its <title>, lowercase alphanumeric.
+fn page_identity_of(html: &str) -> (Option<String>, Vec<String>) {
+    let domain_label = PAGE_URL_TAG_RE
+        .find_iter(html)
+        .filter_map(|tag| PAGE_URL_ATTR_RE.captures(tag.as_str()))
+        .filter_map(|caps| normalize_domain(&decode_html_entities(&caps[1]), false, true, false))
+        .find(|domain| {
+            !SOCIAL_PLATFORMS
+                .iter()
+                .any(|p| p.hosts.iter().any(|h| domain.ends_with(h)))
+        })
+        .and_then(|domain| domain.split('.').next().map(compact_alnum))
+        .filter(|label| label.len() >= 3);
+    let title_words = TITLE_TEXT_RE
+        .captures(html)
+        .map(|caps| {
+            decode_html_entities(&caps[1])
+                .split_whitespace()
+                .map(compact_alnum)
+                .filter(|word| !word.is_empty())
+                .collect()
+        })
+        .unwrap_or_default();
+    (domain_label, title_words)
+}
+
+/// A handle resembles the page when it overlaps the domain label, is part of
+/// the title, or contains two or more title words ("bobsbakery" for "Bob
+/// Bakery").
+fn handle_resembles_page(handle: &str, domain_label: Option<&str>, title_words: &[String]) -> bool {
+    let handle = compact_alnum(handle);
+    if handle.len() < 3 {
+        return false;
+    }
+    if domain_label.is_some_and(|label| label.contains(&handle) || handle.contains(label)) {
+        return true;
+    }
+    if handle.len() >= 4 && title_words.concat().contains(&handle) {
+        return true;
+    }
+    title_words
+        .iter()
+        .filter(|word| word.len() >= 3 && handle.contains(word.as_str()))
+        .count()
+        >= 2
+}
+
+/// Profile links per platform, in SOCIAL_PLATFORMS order: the best candidate
+/// for each plus every candidate found. Links in the header or footer and
+/// handles that resemble the site's domain or title rank first, then links
+/// repeated on the page; ties go to the earliest.
+fn social_links_of(html: &str) -> Vec<(&'static str, Option<String>, Vec<String>)> {
+    let candidates = social_candidates_of(html);
+    let (domain_label, title_words) = if candidates.is_empty() {
+        (None, Vec::new())
+    } else {
+        page_identity_of(html)
+    };
+
+    SOCIAL_PLATFORMS
+        .iter()
+        .map(|p| {
+            let mine: Vec<&SocialCandidate> =
+                candidates.iter().filter(|c| c.platform == p.name).collect();
+            let best = mine
+                .iter()
+                .max_by_key(|c| {
+                    let mut score = (c.count - 1).min(2);
+                    if c.in_chrome {
+                        score += 2;
+                    }
+                    if handle_resembles_page(&c.handle, domain_label.as_deref(), &title_words) {
+                        score += 3;
+                    }
+                    (score, std::cmp::Reverse(c.first))
+                })
+                .map(|c| c.url.clone());
+            (p.name, best, mine.iter().map(|c| c.url.clone()).collect())
+        })
+        .collect()
+}
+
+pub(crate) fn phones_of(html: &str) -> Vec<String> {
+    let mut phones = Vec::new();
+    let mut seen: HashSet<String> = HashSet::new();
+
+    for pattern in PHONE_PATTERNS.iter() {
+        for m in pattern.find_iter(html) {
+            let normalized = normalize_phone(m.as_str());
+            if !normalized.is_empty() && !seen.contains(&normalized) {
+                seen.insert(normalized.clone());
+                phones.push(normalized);
+            }
+        }
+    }
+
+    phones
+}
+
+struct CmsVersionCandidate {
+    cms: &'static str,
+    version: String,
+    source: &'static str,
+}
+
+/// Version evidence in order of trust: generator tags, then the most common
+/// ?ver= on WordPress core assets, then Drupal settings objects.
+fn cms_version_candidates(html: &str) -> Vec<CmsVersionCandidate> {
+    let mut candidates: Vec<CmsVersionCandidate> = Vec::new();
+
+    for tag in GENERATOR_META_RE.find_iter(html) {
+        let Some(content) = META_CONTENT_RE.captures(tag.as_str()) else {
+            continue;
+        };
+        if let Some(caps) = GENERATOR_VERSION_RE.captures(&content[1]) {
+            let cms = match caps["cms"].to_lowercase().as_str() {
+                "wordpress" => "WordPress",
+                "joomla" => "Joomla",
+                _ => "Drupal",
+            };
+            candidates.push(CmsVersionCandidate {
+                cms,
+                version: caps["version"].to_string(),
+                source: "generator",
+            });
+        }
+    }
+
+    // Plugins sometimes pin their own ver=, so take the most common value
+    let mut counts: Vec<(&str, usize)> = Vec::new();
+    for caps in WP_INCLUDES_VER_RE.captures_iter(html) {
+        let version = caps.name("version").unwrap().as_str();
+        match counts.iter_mut().find(|(v, _)| *v == version) {
+            Some((_, n)) => *n += 1,
+            None => counts.push((version, 1)),
+        }
+    }
+    if let Some((version, _)) = counts.iter().max_by_key(|(_, n)| *n) {
+        candidates.push(CmsVersionCandidate {
+            cms: "WordPress",
+            version: version.to_string(),
+            source: "asset_ver",
+        });
+    }
+
+    // Drupal 7 exposes Drupal.settings; 8 and later use drupalSettings
+    if html.contains("Drupal.settings") {
+        candidates.push(CmsVersionCandidate {
+            cms: "Drupal",
+            version: "7".to_string(),
+            source: "drupal_settings",
+        });
+    } else if html.contains("drupalSettings") {
+        candidates.push(CmsVersionCandidate {
+            cms: "Drupal",
+            version: "8+".to_string(),
+            source: "drupal_settings",
+        });
+    }
+
+    candidates
+}
+
+// ---------------------------------------------------------------------------
+// PyO3 functions
+// ---------------------------------------------------------------------------
+
+#[pyfunction]
+pub fn extract_emails(html: &str) -> Vec<String> {
+    if html.is_empty() {
+        return Vec::new();
+    }
+
+    collect_email_hits(html)
+        .into_iter()
+        .take(5)
+        .map(|h| h.email)
+        .collect()
+}
+
+/// Extract emails along with where they were found.
+///
+/// Returns a list of dicts with keys:
+///   - "email": lowercased address
+///   - "source": "mailto" | "attribute" | "text" | "script"
+///   - "offset": byte offset in `html` where the address (or the encoded
+///     form it was decoded from) starts
+///
+/// Mailto addresses are listed first and addresses found only inside
+/// script blocks (JSON-LD, settings JSON, inline JS) last. `limit=None` returns every address.
+#[pyfunction]
+#[pyo3(signature = (html, limit=None))]
+pub fn extract_emails_detailed(
+    py: Python<'_>,
+    html: &str,
+    limit: Option<usize>,
+) -> PyResult<PyObject> {
+    let list = PyList::empty(py);
+    if html.is_empty() {
+        return Ok(list.into());
+    }
+
+    for hit in collect_email_hits(html)
+        .into_iter()
+        .take(limit.unwrap_or(usize::MAX))
+    {
+        let dict = PyDict::new(py);
+        dict.set_item("email", hit.email)?;
+        dict.set_item("source", hit.source.as_str())?;
+        dict.set_item("offset", hit.offset)?;
+        list.append(dict)?;
+    }
+
+    Ok(list.into())
+}
+
+/// Australian phone numbers in page order, normalized and deduplicated.
+/// Only ASCII digits count, so numbers written with full-width or
+/// Arabic-Indic digits are not extracted.
+///
+/// With `include_whatsapp`, numbers that only appear in WhatsApp
+/// click-to-chat links are appended after the rest, in E.164 unless they
+/// are Australian. With `with_source`, returns a list of dicts instead:
+///   - "phone": the number as above
+///   - "source": "text" | "whatsapp"
+#[pyfunction]
+#[pyo3(signature = (html, include_whatsapp=false, with_source=false))]
+pub fn extract_phones(
+    py: Python<'_>,
+    html: &str,
+    include_whatsapp: bool,
+    with_source: bool,
+) -> PyResult<PyObject> {
+    let list = PyList::empty(py);
+    for (phone, source) in phone_sources_of(html, include_whatsapp) {
+        if !with_source {
+            list.append(phone)?;
+            continue;
+        }
+        let dict = PyDict::new(py);
+        dict.set_item("phone", phone)?;
+        dict.set_item("source", source)?;
+        list.append(dict)?;
+    }
+    Ok(list.into())
+}
+
+/// extract_phones' numbers paired with where each was found: "text" for
+/// the page's own numbers, "whatsapp" for those only in click-to-chat links.
+pub(crate) fn phone_sources_of(html: &str, include_whatsapp: bool) -> Vec<(String, &'static str)> {
+    if html.is_empty() {
+        return Vec::new();
+    }
+
+    let mut phones: Vec<(String, &'static str)> = phones_of(html)
+        .into_iter()
+        .map(|phone| (phone, "text"))
+        .collect();
+    if include_whatsapp {
+        for phone in whatsapp_phones_of(html) {
+            if !phones.iter().any(|(known, _)| *known == phone) {
+                phones.push((phone, "whatsapp"));
+            }
+        }
+    }
+    phones
+}
+
+struct Contacts {
+    emails: Vec<String>,
+    phones: Vec<String>,
+    social_links: Vec<(&'static str, Option<String>, Vec<String>)>,
+}
+
+// Reuses the single extractors so the combined call can't drift from them
+fn contacts_of(html: &str) -> Contacts {
+    Contacts {
+        emails: extract_emails(html),
+        phones: phones_of(html),
+        social_links: social_links_of(html),
+    }
+}
+
+/// Emails, phone numbers and social profile links in one call.
+///
+/// Returns a dict with keys:
+///   - "emails": exactly what extract_emails returns
+///   - "phones": exactly what extract_phones returns
+///   - "social_links": {facebook, instagram, linkedin, youtube, tiktok},
+///     each the profile extract_social_links picks or None; "linkedin" is
+///     the company page, else the personal profile
+///
+/// All three run in one call with the GIL released.
+#[pyfunction]
+pub fn extract_contacts(py: Python<'_>, html: &str) -> PyResult<PyObject> {
+    let contacts = py.allow_threads(|| contacts_of(html));
+
+    let dict = PyDict::new(py);
+    dict.set_item("emails", contacts.emails)?;
+    dict.set_item("phones", contacts.phones)?;
+    let mut picks: HashMap<&str, Option<String>> = contacts
+        .social_links
+        .into_iter()
+        .map(|(platform, best, _)| (platform, best))
+        .collect();
+    let mut pick = |platform: &str| picks.remove(platform).flatten();
+    let socials = PyDict::new(py);
+    socials.set_item("facebook", pick("facebook"))?;
+    socials.set_item("instagram", pick("instagram"))?;
+    socials.set_item(
+        "linkedin",
+        pick("linkedin_company").or_else(|| pick("linkedin_personal")),
+    )?;
+    socials.set_item("youtube", pick("youtube"))?;
+    socials.set_item("tiktok", pick("tiktok"))?;
+    dict.set_item("social_links", socials)?;
+    Ok(dict.into())
+}
+
+/// Find the business's own social media profiles.
+///
+/// Returns a dict with one key per platform (facebook, instagram,
+/// linkedin_company, linkedin_personal, youtube, tiktok, x_twitter,
+/// pinterest), each the canonical profile URL or None, and "all": a dict of
+/// platform -> every distinct profile URL on the page, in page order.
+///
+/// Share buttons, intents, single posts and embedded posts are not profiles.
+/// With several candidates, links in the <header>/<footer> and handles that
+/// resemble the site's domain or title win, then links repeated on the page,
+/// then the first one.
+#[pyfunction]
+pub fn extract_social_links(py: Python<'_>, html: &str) -> PyResult<PyObject> {
+    let links = py.allow_threads(|| social_links_of(html));
+
+    let dict = PyDict::new(py);
+    let all = PyDict::new(py);
+    for (platform, best, candidates) in links {
+        dict.set_item(platform, best)?;
+        all.set_item(platform, candidates)?;
+    }
+    dict.set_item("all", all)?;
+    Ok(dict.into())
+}
+
+// ---------------------------------------------------------------------------
+// Click-to-chat links
+// ---------------------------------------------------------------------------
+
+/// Phone number in E.164 ("+61412345678") from a click-to-chat link. Numbers
+/// with a leading 0 are Australian national numbers; anything else without
+/// a + already carries its country code, as wa.me requires. None for
+/// placeholders: a run of one digit (wa.me/0000000000) or the digits
+/// counted out (0123456789, 1234567890).
+fn phone_e164(raw: &str) -> Option<String> {
+    let raw = raw.trim();
+    let digits: String = raw.chars().filter(|c| c.is_ascii_digit()).collect();
+    if matches!(digits.as_str(), "0123456789" | "1234567890") {
+        return None;
+    }
+    let international = if raw.starts_with('+') {
+        digits
+    } else if let Some(rest) = digits.strip_prefix("00") {
+        rest.to_string()
+    } else if let Some(rest) = digits.strip_prefix('0') {
+        format!("61{}", rest)
+    } else {
+        digits
+    };
+    if !(8..=15).contains(&international.len()) {
+        return None;
+    }
+
+    // The subscriber number after an Australian country code must be a
+    // real 9-digit number, and no number is a run of one digit. Real
+    // numbers can contain long runs (0412 345 678), so only the whole
+    // number counts.
+    let national = international
+        .strip_prefix("61")
+        .map(|rest| rest.strip_prefix('0').unwrap_or(rest))
+        .unwrap_or(&international);
+    if international.starts_with("61") && national.len() != 9 {
+        return None;
+    }
+    let first = national.chars().next()?;
+    if national.chars().all(|c| c == first) {
+        return None;
+    }
+    Some(format!("+{}", international))
+}
+
+/// A WhatsApp, SMS, Messenger or Telegram link's target: an E.164 phone for
+/// the first two, a canonical https://m.me/ or https://t.me/ URL for the
+/// others.
+fn messaging_target_of(href: &str) -> Option<(&'static str, String)> {
+    let href = href.trim();
+    let lower = href.to_ascii_lowercase();
+
+    if let Some(rest) = lower
+        .strip_prefix("sms:")
+        .map(|_| &href[4..])
+        .map(|rest| rest.trim_start_matches('/'))
+    {
+        let number = rest.split(['?', ';', '&', ',']).next().unwrap_or("");
+        return phone_e164(&percent_decode(number)).map(|phone| ("sms", phone));
+    }
+
+    let query_param = |name: &str| -> Option<String> {
+        let (_, query) = href.split_once('?')?;
+        query.split('&').find_map(|pair| {
+            let (key, value) = pair.split_once('=')?;
+            key.eq_ignore_ascii_case(name)
+                .then(|| percent_decode(value))
+        })
+    };
+
+    if lower.starts_with("whatsapp://send") {
+        return phone_e164(&query_param("phone")?).map(|phone| ("whatsapp", phone));
+    }
+
+    let absolute = if href.starts_with("//") {
+        format!("https:{}", href)
+    } else {
+        href.to_string()
+    };
+    let url = url::Url::parse(&absolute).ok()?;
+    if !matches!(url.scheme(), "http" | "https") {
+        return None;
+    }
+    let host = url.host_str()?.to_ascii_lowercase();
+    let host = host.strip_prefix("www.").unwrap_or(&host);
+    let mut segments = url.path_segments()?.filter(|s| !s.is_empty());
+    let first = segments.next().map(percent_decode);
+    // Handles are plain names; "{{page}}" and similar templates are not
+    let handle = |name: Option<String>| {
+        name.filter(|n| {
+            n.len() >= 3
+                && n.chars()
+                    .all(|c| c.is_ascii_alphanumeric() || matches!(c, '.' | '_' | '-'))
+        })
+    };
+
+    match host {
+        "wa.me" => phone_e164(&first?).map(|phone| ("whatsapp", phone)),
+        "api.whatsapp.com" | "web.whatsapp.com" | "whatsapp.com" => {
+            phone_e164(&query_param("phone")?).map(|phone| ("whatsapp", phone))
+        }
+        "m.me" => handle(first).map(|page| ("messenger", format!("https://m.me/{}", page))),
+        "messenger.com" if first.as_deref() == Some("t") => {
+            handle(segments.next().map(percent_decode))
+                .map(|page| ("messenger", format!("https://m.me/{}", page)))
+        }
+        "t.me" | "telegram.me" => {
+            let name = match first.as_deref() {
+                Some("share" | "joinchat" | "addstickers") => return None,
+                Some("s") => segments.next().map(percent_decode),
+                _ => first,
+            };
+            handle(name)
+                .filter(|name| !name.starts_with('+'))
+                .map(|name| ("telegram", format!("https://t.me/{}", name)))
+        }
+        _ => None,
+    }
+}
+
+const MESSAGING_KINDS: [&str; 4] = ["whatsapp", "sms", "messenger", "telegram"];
+
+/// The first valid target per kind, in MESSAGING_KINDS order.
+fn messaging_links_of(html: &str) -> Vec<(&'static str, Option<String>)> {
+    let mut found: HashMap<&'static str, String> = HashMap::new();
+    for caps in ANCHOR_HREF_RE.captures_iter(html) {
+        let href = decode_html_entities(&caps[1]);
+        if let Some((kind, target)) = messaging_target_of(&href) {
+            found.entry(kind).or_insert(target);
+        }
+        if found.len() == MESSAGING_KINDS.len() {
+            break;
+        }
+    }
+    MESSAGING_KINDS
+        .iter()
+        .map(|kind| (*kind, found.remove(kind)))
+        .collect()
+}
+
+/// Every distinct WhatsApp number on the page, in the same format as
+/// extract_phones (national for Australian numbers, E.164 otherwise).
+fn whatsapp_phones_of(html: &str) -> Vec<String> {
+    let mut phones = Vec::new();
+    for caps in ANCHOR_HREF_RE.captures_iter(html) {
+        let href = decode_html_entities(&caps[1]);
+        if let Some(("whatsapp", phone)) = messaging_target_of(&href) {
+            let formatted = if phone.starts_with("+61") {
+                normalize_phone(&phone)
+            } else {
+                phone
+            };
+            if !phones.contains(&formatted) {
+                phones.push(formatted);
+            }
+        }
+    }
+    phones
+}
+
+/// Detect click-to-chat links (wa.me, api.whatsapp.com, sms:, m.me, t.me).
+///
+/// Returns a dict with keys:
+///   - "whatsapp": the number in E.164 ("+61412345678") or None
+///   - "sms": the number in E.164 or None
+///   - "messenger": "https://m.me/<page>" or None
+///   - "telegram": "https://t.me/<name>" or None
+///
+/// Percent-encoded numbers (phone=%2B61...) are decoded; placeholder numbers
+/// such as wa.me/0000000000 are ignored.
+#[pyfunction]
+pub fn detect_messaging_links(py: Python<'_>, html: &str) -> PyResult<PyObject> {
+    let dict = PyDict::new(py);
+    for (kind, target) in messaging_links_of(html) {
+        dict.set_item(kind, target)?;
+    }
+    Ok(dict.into())
+}
+
+// ---------------------------------------------------------------------------
+// Google Business Profile links
+// ---------------------------------------------------------------------------
+
+static GOOGLE_REVIEW_CTA_RE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(
+        r"(?i)\b(?:review|rate)\s+us\b[^<]{0,20}\bgoogle\b|\bgoogle\s+reviews?\b|\bleave\s+(?:us\s+)?an?\s+(?:google\s+)?review\b|\bfind\s+us\s+on\s+google\b",
+    )
+    .unwrap()
+});
+
+struct GbpLinks {
+    urls: Vec<String>,
+    review_link: bool,
+}
+
+/// Whether a link points at a Google Business Profile, and whether it opens
+/// the write-a-review dialog. Map-pack short links (g.page, maps.app.goo.gl,
+/// goo.gl/maps, g.co/kgs), ?cid= map URLs, business.google.com and placeid
+/// review URLs count; any other Google link only with "review us on Google"
+/// style anchor text. Street-address map links and embeds do not.
+fn gbp_link_of(href: &str, anchor_text: &str) -> Option<bool> {
+    let href = href.trim();
+    let absolute = if href.starts_with("//") {
+        format!("https:{}", href)
+    } else {
+        href.to_string()
+    };
+    let url = url::Url::parse(&absolute).ok()?;
+    if !matches!(url.scheme(), "http" | "https") {
+        return None;
+    }
+    let host = url.host_str()?.to_ascii_lowercase();
+    let host = host.strip_prefix("www.").unwrap_or(&host);
+    let path = url.path().trim_end_matches('/').to_ascii_lowercase();
+    let has_param = |name: &str| url.query_pairs().any(|(k, v)| k == name && !v.is_empty());
+    let is_google = host.starts_with("google.") || host.ends_with(".google.com");
+
+    match host {
+        "g.page" if !path.is_empty() => {
+            // g.page/r/<id>/review and g.page/<name>/review
+            Some(path.ends_with("/review"))
+        }
+        "maps.app.goo.gl" | "g.co" if !path.is_empty() => {
+            (host == "maps.app.goo.gl" || path.starts_with("/kgs/")).then_some(false)
+        }
+        "goo.gl" if path.starts_with("/maps/") => Some(false),
+        "business.google.com" => Some(false),
+        "search.google.com" if path.starts_with("/local/") && has_param("placeid") => {
+            Some(path == "/local/writereview")
+        }
+        _ if is_google && has_param("cid") => Some(false),
+        // Search result links that open the review panel: #lrd=<id>,3 writes
+        _ if is_google && url.fragment().is_some_and(|f| f.starts_with("lrd=")) => {
+            Some(url.fragment().is_some_and(|f| f.contains(",3")))
+        }
+        _ if is_google && GOOGLE_REVIEW_CTA_RE.is_match(anchor_text) => Some(false),
+        _ => None,
+    }
+}
+
+/// Distinct Google Business Profile links on the page, in page order.
+fn gbp_links_of(html: &str) -> GbpLinks {
+    let mut links = GbpLinks {
+        urls: Vec::new(),
+        review_link: false,
+    };
+    for caps in ANCHOR_HREF_RE.captures_iter(html) {
+        let href = decode_html_entities(&caps[1]);
+        let href_lower = href.to_ascii_lowercase();
+        if !["google", "g.page", "goo.gl", "g.co/"]
+            .iter()
+            .any(|host| href_lower.contains(host))
+        {
+            continue;
+        }
+        // Anchor text up to the closing tag, for "review us on Google" CTAs
+        let rest = &html[caps.get(0).map_or(0, |m| m.end())..];
+        let close = rest
+            .char_indices()
+            .take(1000)
+            .find(|&(i, _)| {
+                rest[i..]
+                    .get(..3)
+                    .is_some_and(|t| t.eq_ignore_ascii_case("</a"))
+            })
+            .map_or(0, |(i, _)| i);
+        let Some(review) = gbp_link_of(&href, &rest[..close]) else {
+            continue;
+        };
+        links.review_link |= review;
+        let url = href.trim().to_string();
+        if !links.urls.contains(&url) {
+            links.urls.push(url);
+        }
+    }
+    links
+}
+
+/// Detect links to the business's Google Business Profile (g.page,
+/// maps.app.goo.gl, goo.gl/maps, maps.google.com/?cid=, business.google.com,
+/// "review us on Google" CTAs), which show the profile has been claimed.
+///
+/// Returns a dict with keys:
+///   - "has_gbp_link": bool
+///   - "urls": the distinct profile links, in page order
+///   - "review_link": whether any link opens Google's write-a-review dialog
+///     (search.google.com/local/writereview?placeid=..., g.page/r/.../review)
+///
+/// Embedded map iframes and plain street-address map links are not profile
+/// links.
+#[pyfunction]
+pub fn detect_gbp_links(py: Python<'_>, html: &str) -> PyResult<PyObject> {
+    let links = gbp_links_of(html);
+    let dict = PyDict::new(py);
+    dict.set_item("has_gbp_link", !links.urls.is_empty())?;
+    dict.set_item("urls", links.urls)?;
+    dict.set_item("review_link", links.review_link)?;
+    Ok(dict.into())
+}
+
+// Embedded maps
+
+static MAP_ASSET_RE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(
+        r#"(?is)<(iframe|script|link|img)\b[^>]*?\s(?:data-)?(?:src|href)\s*=\s*["']([^"']+)["']"#,
+    )
+    .unwrap()
+});
+
+struct MapEmbed {
+    provider: &'static str,
+    is_static: bool,
+    // A library loaded by several script/stylesheet tags is one map
+    library: bool,
+}
+
+/// Classify one iframe, script, stylesheet or image URL as a map embed.
+fn map_embed_of(tag: &str, url: &str) -> Option<MapEmbed> {
+    let url = url.to_ascii_lowercase();
+    let embed = |provider, is_static, library| {
+        Some(MapEmbed {
+            provider,
+            is_static,
+            library,
+        })
+    };
+    match tag {
+        "iframe" if url.contains("google.") && url.contains("/maps") && url.contains("embed") => {
+            embed("google", false, false)
+        }
+        "iframe" if url.contains("api.mapbox.com/styles/") => embed("mapbox", false, false),
+        "img" if url.contains("maps.googleapis.com/maps/api/staticmap") => {
+            embed("google", true, false)
+        }
+        "img" if url.contains("api.mapbox.com/styles/") && url.contains("/static/") => {
+            embed("mapbox", true, false)
+        }
+        "script" if url.contains("maps.googleapis.com/maps/api/js") => embed("google", false, true),
+        "script" | "link"
+            if url.contains("mapbox-gl") || url.contains("api.mapbox.com/mapbox.js") =>
+        {
+            embed("mapbox", false, true)
+        }
+        "script" | "link" if url.contains("leaflet") => embed("leaflet", false, true),
+        "script" if url.contains("apple-mapkit.com") || url.contains("/mapkit.js") => {
+            embed("apple", false, true)
+        }
+        _ => None,
+    }
+}
+
+struct MapEmbeds {
+    provider: Option<&'static str>,
+    count: usize,
+    is_static: bool,
+}
+
+/// Map embeds on the page. Each iframe and static image counts; a JS map
+/// library counts once however many assets load it. The provider is that
+/// of the first embed in page order.
+fn map_embeds_of(html: &str) -> MapEmbeds {
+    let live = HTML_COMMENT_RE.replace_all(html, " ");
+    let mut embeds = MapEmbeds {
+        provider: None,
+        count: 0,
+        is_static: false,
+    };
+    let mut libraries: Vec<&str> = Vec::new();
+    for caps in MAP_ASSET_RE.captures_iter(&live) {
+        let tag = caps[1].to_ascii_lowercase();
+        let Some(embed) = map_embed_of(&tag, &decode_html_entities(&caps[2])) else {
+            continue;
+        };
+        if embed.library {
+            if libraries.contains(&embed.provider) {
+                continue;
+            }
+            libraries.push(embed.provider);
+        }
+        embeds.provider.get_or_insert(embed.provider);
+        embeds.count += 1;
+        embeds.is_static |= embed.is_static;
+    }
+    embeds
+}
+
+/// Detect embedded maps: Google Maps iframes and the Maps JavaScript API,
+/// Mapbox GL, Leaflet and Apple MapKit JS, plus static map images.
+///
+/// Returns a dict with keys:
+///   - "has_map": bool
+///   - "provider": "google" | "mapbox" | "leaflet" | "apple" | None
+///   - "embed_count": map iframes and images, plus one per JS map library
+///   - "static": whether any of them is a static map image
+///
+/// Links to Google Maps are not embeds; detect_gbp_links covers those.
+#[pyfunction]
+pub fn detect_map_embed(py: Python<'_>, html: &str) -> PyResult<PyObject> {
+    let embeds = map_embeds_of(html);
+    let dict = PyDict::new(py);
+    dict.set_item("has_map", embeds.count > 0)?;
+    dict.set_item("provider", embeds.provider)?;
+    dict.set_item("embed_count", embeds.count)?;
+    dict.set_item("static", embeds.is_static)?;
+    Ok(dict.into())
+}
+
+// Video
+
+static VIDEO_EMBED_SEL: LazyLock<Selector> = LazyLock::new(|| {
+    Selector::parse(
+        "iframe, lite-youtube, lite-vimeo, video, [class*=wistia_async_], .vidyard-player-embed",
+    )
+    .unwrap()
+});
+static VIDEO_SOURCE_SEL: LazyLock<Selector> =
+    LazyLock::new(|| Selector::parse("source[src]").unwrap());
+
+// Player URLs and the video ID in them
+static VIDEO_PLAYER_RES: LazyLock<Vec<(&str, Regex)>> = LazyLock::new(|| {
+    [
+        (
+            "youtube",
+            r"(?i)youtube(?:-nocookie)?\.com/embed/([\w-]{6,})",
+        ),
+        ("vimeo", r"(?i)player\.vimeo\.com/video/(\d+)"),
+        (
+            "wistia",
+            r"(?i)(?:fast\.wistia\.(?:net|com)/embed/(?:iframe|medias)/|wistia\.com/medias/)(\w+)",
+        ),
+        ("vidyard", r"(?i)play\.vidyard\.com/(?:embed/)?([\w-]{10,})"),
+    ]
+    .into_iter()
+    .map(|(provider, pattern)| (provider, Regex::new(pattern).unwrap()))
+    .collect()
+});
+
+/// The provider and video ID (or source URL) of one embed.
+fn video_of(element: ElementRef) -> Option<(&'static str, String)> {
+    let value = element.value();
+    match value.name() {
+        "iframe" => {
+            let src = value.attr("src").or_else(|| value.attr("data-src"))?;
+            VIDEO_PLAYER_RES.iter().find_map(|(provider, re)| {
+                re.captures(src)
+                    .map(|caps| (*provider, caps[1].to_string()))
+            })
+        }
+        "lite-youtube" | "lite-vimeo" => {
+            let id = value.attr("videoid")?.trim();
+            let provider = if value.name() == "lite-youtube" {
+                "youtube"
+            } else {
+                "vimeo"
+            };
+            (!id.is_empty()).then(|| (provider, id.to_string()))
+        }
+        "video" => {
+            let src = value
+                .attr("src")
+                .or_else(|| {
+                    element
+                        .select(&VIDEO_SOURCE_SEL)
+                        .find_map(|s| s.value().attr("src"))
+                })?
+                .trim();
+            if src.is_empty() || src == "#" {
+                return None;
+            }
+            // Autoplaying, muted, looping: a hero background rather than
+            // content to watch
+            let background = ["autoplay", "muted", "loop"]
+                .iter()
+                .all(|attr| value.attr(attr).is_some());
+            let provider = if background {
+                "background_video"
+            } else {
+                "html5"
+            };
+            Some((provider, src.to_string()))
+        }
+        _ => {
+            if let Some(id) = value
+                .classes()
+                .find_map(|c| c.strip_prefix("wistia_async_"))
+            {
+                return Some(("wistia", id.to_string()));
+            }
+            let id = value.attr("data-uuid")?.trim();
+            (!id.is_empty()).then(|| ("vidyard", id.to_string()))
+        }
+    }
+}
+
+struct Videos {
+    providers: Vec<&'static str>,
+    count: usize,
+}
+
+/// Videos on the page, counting each provider and ID once. Links to a
+/// video (a social feed's YouTube thumbnail) aren't embeds.
+fn videos_of(html: &str) -> Videos {
+    let document = Html::parse_document(html);
+    let mut seen: HashSet<(&'static str, String)> = HashSet::new();
+    let mut providers = Vec::new();
+    for element in document.select(&VIDEO_EMBED_SEL) {
+        if element
+            .ancestors()
+            .filter_map(ElementRef::wrap)
+            .any(|a| a.value().name() == "template")
+        {
+            continue;
+        }
+        let Some((provider, id)) = video_of(element) else {
+            continue;
+        };
+        if seen.insert((provider, id)) && !providers.contains(&provider) {
+            providers.push(provider);
+        }
+    }
+    Videos {
+        providers,
+        count: seen.len(),
+    }
+}
+
+/// Detect video on the page: YouTube and Vimeo iframes and lite-embed
+/// components, Wistia and Vidyard players, and HTML5 `<video>` tags with a
+/// source.
+///
+/// Returns a dict with keys:
+///   - "has_video": bool
+///   - "providers": "youtube" | "vimeo" | "wistia" | "vidyard" | "html5" |
+///     "background_video" (an autoplaying, muted, looping hero video), in
+///     page order
+///   - "count": distinct videos; the same ID embedded twice counts once
+///
+/// Links to videos, such as thumbnails in a social feed, don't count.
+#[pyfunction]
+pub fn detect_video(py: Python<'_>, html: &str) -> PyResult<PyObject> {
+    let videos = py.allow_threads(|| videos_of(html));
+    let dict = PyDict::new(py);
+    dict.set_item("has_video", videos.count > 0)?;
+    dict.set_item("providers", videos.providers)?;
+    dict.set_item("count", videos.count)?;
+    Ok(dict.into())
+}
+
+// Contact forms
+
+static FORM_OR_EMBED_SEL: LazyLock<Selector> = LazyLock::new(|| {
+    Selector::parse("form, iframe[src], script, [data-tf-widget], [data-tf-live]").unwrap()
+});
+static FORM_SEL: LazyLock<Selector> = LazyLock::new(|| Selector::parse("form").unwrap());
+static FORM_FIELD_SEL: LazyLock<Selector> =
+    LazyLock::new(|| Selector::parse("input, select, textarea").unwrap());
+static FORM_BUTTON_SEL: LazyLock<Selector> =
+    LazyLock::new(|| Selector::parse(r#"button, input[type="submit"]"#).unwrap());
+
+static PHONE_FIELD_RE: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"(?i)phone|mobile|\btel\b").unwrap());
+static SUBSCRIBE_WORDING_RE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"(?i)subscri|newsletter|sign\s*(?:me\s+)?up|mailing\s+list|list-manage\.com")
+        .unwrap()
+});
+
+// Input types that are buttons or carry no user input
+static NON_FIELD_INPUT_TYPES: &[&str] = &["hidden", "submit", "button", "reset", "image"];
+// Sole field names of a site search box (WordPress uses ?s=)
+static SEARCH_FIELD_NAMES: &[&str] = &["s", "q", "query", "search", "keyword", "keywords"];
+
+/// Form plugins recognised from the class or id of the form or its
+/// wrapper: (provider, markers).
+static FORM_PLUGIN_MARKERS: &[(&str, &[&str])] = &[
+    ("Contact Form 7", &["wpcf7"]),
+    ("Gravity Forms", &["gform_"]),
+    ("WPForms", &["wpforms-"]),
+    ("HubSpot", &["hs-form", "hbspt-form"]),
+];
+
+/// Hosted forms embedded as an iframe or script: (provider, URL markers).
+static FORM_EMBED_MARKERS: &[(&str, &[&str])] = &[
+    (
+        "Typeform",
+        &[
+            "typeform.com/to/",
+            "form.typeform.com",
+            "embed.typeform.com",
+        ],
+    ),
+    (
+        "Jotform",
+        &[
+            "form.jotform.com",
+            "jotform.com/jsform/",
+            "submit.jotform.com",
+        ],
+    ),
+    ("HubSpot", &["js.hsforms.net/forms", "share.hsforms.com"]),
+];
+
+/// Email-capture vendors: (mechanism, markers). `_form` mechanisms also
+/// identify a newsletter form element by its action, class or id.
+static EMAIL_CAPTURE_SIGNATURES: &[(&str, &[&str])] = &[
+    (
+        "mailchimp_form",
+        &[
+            "list-manage.com",
+            "mc-embedded-subscribe",
+            "mc4wp-form",
+            "chimpstatic.com/mcjs",
+        ],
+    ),
+    (
+        "klaviyo_form",
+        &[
+            "static.klaviyo.com/onsite",
+            "klaviyo-form-",
+            "kmail-lists.com",
+        ],
+    ),
+    (
+        "convertkit_form",
+        &["app.convertkit.com/forms", "formkit-form", ".ck.page/"],
+    ),
+    (
+        "mailerlite_form",
+        &[
+            "assets.mailerlite.com",
+            "static.mailerlite.com",
+            "ml-embedded",
+            "ml-subscribe-form",
+        ],
+    ),
+    (
+        "optinmonster_popup",
+        &["a.omappapi.com", "api.omappapi.com"],
+    ),
+    ("sumo_popup", &["load.sumo.com", "load.sumome.com"]),
+    ("privy_popup", &["widget.privy.com"]),
+    ("poptin_popup", &["cdn.popt.in"]),
+];
+
+// "Download our free guide" style offers of gated content
+static LEAD_MAGNET_RE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(
+        r"(?i)\b(?:download|get|grab|claim)\s+(?:our|your|the|a|my)\s+(?:free\s+)?(?:[a-z0-9-]+\s+){0,3}?(?:guide|e-?book|checklist|cheat\s*sheet|white\s*paper|toolkit|workbook|playbook)s?\b|\bfree\s+(?:[a-z0-9-]+\s+){0,2}?(?:guide|e-?book|checklist|cheat\s*sheet|white\s*paper|toolkit|workbook|playbook)\s+(?:download|pdf)\b",
+    )
+    .unwrap()
+});
+
+/// Ways a form element is not a contact form. A newsletter form carries
+/// its vendor's email-capture mechanism when it posts to one.
+#[derive(PartialEq)]
+enum FormKind {
+    Contact,
+    Search,
+    Newsletter(Option<&'static str>),
+    Login,
+    Empty,
+}
+
+struct FormFields {
+    count: usize,
+    phone: bool,
+    email: bool,
+    file_upload: bool,
+}
+
+struct ContactForms {
+    count: usize,
+    field_count_min: Option<usize>,
+    has_phone_field: bool,
+    has_file_upload: bool,
+    provider: Option<&'static str>,
+}
+
+fn attr_of<'a>(element: ElementRef<'a>, name: &str) -> &'a str {
+    element.value().attr(name).unwrap_or("")
+}
+
+/// Whether a field is hidden from the visitor: by its own attributes, or by
+/// a wrapper inside the form (the display:none / aria-hidden honeypots
+/// Mailchimp and CF7 use to catch bots).
+fn is_hidden_field(field: ElementRef, form: ElementRef) -> bool {
+    let hidden = |e: ElementRef| {
+        e.value().attr("hidden").is_some()
+            || attr_of(e, "aria-hidden").eq_ignore_ascii_case("true")
+            || attr_of(e, "style")
+                .to_ascii_lowercase()
+                .replace(' ', "")
+                .contains("display:none")
+    };
+    hidden(field)
+        || attr_of(field, "tabindex").trim() == "-1"
+        || field
+            .ancestors()
+            .filter_map(ElementRef::wrap)
+            .take_while(|a| a.id() != form.id())
+            .any(hidden)
+}
+
+/// Whether `element` sits in a `<template>` or inside a form that is
+/// classified on its own.
+fn in_template_or_form(element: ElementRef) -> bool {
+    element
+        .ancestors()
+        .filter_map(ElementRef::wrap)
+        .any(|a| matches!(a.value().name(), "template" | "form"))
+}
+
+/// The lowercased class and id of `form` and its nearest wrappers, plus its
+/// action, which is where form plugins and email vendors mark their forms.
+fn form_markers(form: ElementRef) -> String {
+    let mut markers = std::iter::once(form)
+        .chain(form.ancestors().filter_map(ElementRef::wrap).take(3))
+        .map(|e| format!("{} {}", attr_of(e, "class"), attr_of(e, "id")))
+        .collect::<Vec<_>>()
+        .join(" ");
+    markers.push(' ');
+    markers.push_str(attr_of(form, "action"));
+    markers.to_ascii_lowercase()
+}
+
+/// Classify a form element and summarise its visible fields. Search,
+/// login and newsletter forms (posting to an email vendor, or a single
+/// email field with subscribe wording) are told apart from contact forms.
+fn form_kind_of(form: ElementRef) -> (FormKind, FormFields) {
+    let mut fields = FormFields {
+        count: 0,
+        phone: false,
+        email: false,
+        file_upload: false,
+    };
+    let mut names: Vec<String> = Vec::new();
+    let mut email_fields = 0;
+    let mut search_field = false;
+    let mut password_field = false;
+    for field in form.select(&FORM_FIELD_SEL) {
+        let input_type = if field.value().name() == "input" {
+            attr_of(field, "type").trim().to_ascii_lowercase()
+        } else {
+            String::new()
+        };
+        if NON_FIELD_INPUT_TYPES.contains(&input_type.as_str()) || is_hidden_field(field, form) {
+            continue;
+        }
+        let name = attr_of(field, "name").to_ascii_lowercase();
+        let labels = [
+            name.as_str(),
+            attr_of(field, "id"),
+            attr_of(field, "placeholder"),
+            attr_of(field, "autocomplete"),
+        ];
+        fields.count += 1;
+        fields.phone |= input_type == "tel" || labels.iter().any(|l| PHONE_FIELD_RE.is_match(l));
+        fields.file_upload |= input_type == "file";
+        search_field |= input_type == "search";
+        password_field |= input_type == "password";
+        if input_type == "email" || name.contains("email") {
+            email_fields += 1;
+            fields.email = true;
+        }
+        names.push(name);
+    }
+
+    let identity = format!(
+        "{} {} {}",
+        attr_of(form, "class"),
+        attr_of(form, "id"),
+        attr_of(form, "action")
+    )
+    .to_ascii_lowercase();
+    let markers = form_markers(form);
+    let vendor = EMAIL_CAPTURE_SIGNATURES
+        .iter()
+        .filter(|(mechanism, _)| mechanism.ends_with("_form"))
+        .find(|(_, sigs)| sigs.iter().any(|sig| markers.contains(sig)))
+        .map(|(mechanism, _)| *mechanism);
+    let is_search = attr_of(form, "role").eq_ignore_ascii_case("search")
+        || search_field
+        || identity.contains("search")
+        || (fields.count == 1 && SEARCH_FIELD_NAMES.contains(&names[0].as_str()));
+    let kind = if fields.count == 0 {
+        FormKind::Empty
+    } else if is_search {
+        FormKind::Search
+    } else if password_field {
+        FormKind::Login
+    } else if vendor.is_some() {
+        FormKind::Newsletter(vendor)
+    } else if fields.count == 1 && email_fields == 1 && {
+        let buttons = form
+            .select(&FORM_BUTTON_SEL)
+            .map(|b| format!("{} {}", attr_of(b, "value"), b.text().collect::<String>()))
+            .collect::<Vec<_>>()
+            .join(" ");
+        SUBSCRIBE_WORDING_RE.is_match(&identity)
+            || SUBSCRIBE_WORDING_RE.is_match(&buttons)
+            || SUBSCRIBE_WORDING_RE.is_match(&joined_text(form))
+    } {
+        FormKind::Newsletter(None)
+    } else {
+        FormKind::Contact
+    };
+    (kind, fields)
+}
+
+/// The form plugin that rendered `form`, from its own or a wrapper's class
+/// and id.
+fn form_plugin_of(form: ElementRef) -> Option<&'static str> {
+    let markers = form_markers(form);
+    FORM_PLUGIN_MARKERS
+        .iter()
+        .find(|(_, sigs)| sigs.iter().any(|sig| markers.contains(sig)))
+        .map(|(provider, _)| *provider)
+}
+
+/// The hosted form provider an iframe, script or Typeform widget embeds.
+fn form_embed_of(element: ElementRef) -> Option<&'static str> {
+    let value = element.value();
+    if value.attr("data-tf-widget").is_some() || value.attr("data-tf-live").is_some() {
+        return Some("Typeform");
+    }
+    let src = attr_of(element, "src").to_ascii_lowercase();
+    let code = if value.name() == "script" && src.is_empty() {
+        element.text().collect::<String>()
+    } else {
+        String::new()
+    };
+    if code.contains("hbspt.forms.create") {
+        return Some("HubSpot");
+    }
+    FORM_EMBED_MARKERS
+        .iter()
+        .find(|(_, sigs)| sigs.iter().any(|sig| src.contains(sig)))
+        .map(|(provider, _)| *provider)
+}
+
+/// Contact forms on the page: form elements other than search, login and
+/// newsletter sign-up forms, plus embedded hosted forms. A provider's
+/// scripts count as one form, or none alongside its iframe or widget
+/// (Typeform's embed.js with a data-tf-widget). Markup in comments or
+/// `<template>` is ignored.
+fn contact_forms_of(html: &str) -> ContactForms {
+    let document = Html::parse_document(html);
+    let mut forms = ContactForms {
+        count: 0,
+        field_count_min: None,
+        has_phone_field: false,
+        has_file_upload: false,
+        provider: None,
+    };
+    let mut embedded: Vec<&'static str> = Vec::new();
+    let mut scripted: Vec<&'static str> = Vec::new();
+    for element in document.select(&FORM_OR_EMBED_SEL) {
+        if in_template_or_form(element) {
+            continue;
+        }
+        if element.value().name() == "form" {
+            let (kind, fields) = form_kind_of(element);
+            if kind != FormKind::Contact {
+                continue;
+            }
+            forms.count += 1;
+            forms.field_count_min = Some(
+                forms
+                    .field_count_min
+                    .map_or(fields.count, |min| min.min(fields.count)),
+            );
+            forms.has_phone_field |= fields.phone;
+            forms.has_file_upload |= fields.file_upload;
+            if let Some(provider) = form_plugin_of(element) {
+                forms.provider.get_or_insert(provider);
+            }
+        } else if let Some(provider) = form_embed_of(element) {
+            forms.provider.get_or_insert(provider);
+            let seen = if element.value().name() == "script" {
+                &mut scripted
+            } else {
+                forms.count += 1;
+                &mut embedded
+            };
+            if !seen.contains(&provider) {
+                seen.push(provider);
+            }
+        }
+    }
+    // A provider's scripts (a loader plus its create() call, say) are one
+    // form, and none when they only drive an iframe or widget counted above
+    forms.count += scripted.iter().filter(|p| !embedded.contains(p)).count();
+    forms
+}
+
+/// Detect contact forms: form elements other than search boxes, logins and
+/// newsletter sign-ups (see detect_email_capture), plus hosted Typeform,
+/// Jotform and HubSpot embeds.
+///
+/// Returns a dict with keys:
+///   - "has_form": bool
+///   - "form_count": contact forms and form embeds on the page
+///   - "field_count_min": visible input/select/textarea fields in the
+///     shortest form element (None when only embeds were found)
+///   - "has_phone_field": bool
+///   - "has_file_upload": bool
+///   - "provider": "Contact Form 7" | "Gravity Forms" | "WPForms" |
+///     "Typeform" | "Jotform" | "HubSpot" | None
+///
+/// Forms in commented-out markup or `<template>` don't count.
+#[pyfunction]
+pub fn detect_contact_form(py: Python<'_>, html: &str) -> PyResult<PyObject> {
+    let forms = py.allow_threads(|| contact_forms_of(html));
+    let dict = PyDict::new(py);
+    dict.set_item("has_form", forms.count > 0)?;
+    dict.set_item("form_count", forms.count)?;
+    dict.set_item("field_count_min", forms.field_count_min)?;
+    dict.set_item("has_phone_field", forms.has_phone_field)?;
+    dict.set_item("has_file_upload", forms.has_file_upload)?;
+    dict.set_item("provider", forms.provider)?;
+    Ok(dict.into())
+}
+
+/// Email-capture mechanisms on the page, vendors in page order, then
+/// generic newsletter forms and gated lead magnets.
+fn email_capture_of(html: &str) -> Vec<&'static str> {
+    let scan = PageScan::new(html);
+    let mut mechanisms: Vec<&'static str> =
+        match_signature_table(EMAIL_CAPTURE_SIGNATURES, |sig| scan.find_live(sig))
+            .into_iter()
+            .map(|h| h.name)
+            .collect();
+
+    let document = Html::parse_document(html);
+    let mut email_form = false;
+    for form in document.select(&FORM_SEL) {
+        if in_template_or_form(form) {
+            continue;
+        }
+        let (kind, fields) = form_kind_of(form);
+        email_form |= fields.email;
+        let mechanism = match kind {
+            FormKind::Newsletter(vendor) => vendor.unwrap_or("newsletter_form"),
+            _ => continue,
+        };
+        if !mechanisms.contains(&mechanism) {
+            mechanisms.push(mechanism);
+        }
+    }
+    // Offer wording only gates content when there's somewhere to leave an
+    // address
+    if (email_form || !mechanisms.is_empty())
+        && LEAD_MAGNET_RE.is_match(&visible_text_in(&document))
+    {
+        mechanisms.push("lead_magnet");
+    }
+    mechanisms
+}
+
+/// Detect email-capture mechanisms: Mailchimp, Klaviyo, ConvertKit and
+/// MailerLite forms, OptinMonster, Sumo, Privy and Poptin popups, generic
+/// newsletter forms (a single email field with subscribe wording) and
+/// "download our free guide" gated content.
+///
+/// Returns a dict with keys:
+///   - "has_capture": bool
+///   - "mechanisms": identifiers such as "mailchimp_form", "privy_popup",
+///     "newsletter_form" and "lead_magnet", so popups can be weighted apart
+///     from footer forms
+///
+/// A form counted here is never also a contact form in detect_contact_form.
+#[pyfunction]
+pub fn detect_email_capture(py: Python<'_>, html: &str) -> PyResult<PyObject> {
+    let mechanisms = py.allow_threads(|| email_capture_of(html));
+    let dict = PyDict::new(py);
+    dict.set_item("has_capture", !mechanisms.is_empty())?;
+    dict.set_item("mechanisms", mechanisms)?;
+    Ok(dict.into())
+}
+
+// CAPTCHA widgets
+
+static CAPTCHA_MARKUP_SEL: LazyLock<Selector> = LazyLock::new(|| {
+    Selector::parse(".g-recaptcha, .h-captcha, .cf-turnstile, [data-sitekey], script[src]").unwrap()
+});
+
+static RECAPTCHA_KEY_RE: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"^6L[0-9A-Za-z_-]{38}$").unwrap());
+static HCAPTCHA_KEY_RE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"^[0-9a-f]{8}-[0-9a-f]{4}-[0-9a-f]{4}-[0-9a-f]{4}-[0-9a-f]{12}$").unwrap()
+});
+static TURNSTILE_KEY_RE: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"^[0-3]x[0-9A-Za-z_-]{20,}$").unwrap());
+
+// An explicit widget render call, with the sitekey option when inline
+static CAPTCHA_RENDER_RE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(
+        r#"\b(grecaptcha(?:\.enterprise)?|hcaptcha|turnstile)\.render\s*\([^)]{0,300}?\bsitekey['"]?\s*:\s*['"]([^'"]+)['"]|\b(grecaptcha(?:\.enterprise)?|hcaptcha|turnstile)\.render\s*\("#,
+    )
+    .unwrap()
+});
+// A reCAPTCHA v3 token request: grecaptcha.execute('<site key>', {action})
+static RECAPTCHA_EXECUTE_RE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r#"\bgrecaptcha(?:\.enterprise)?\.execute\s*\(\s*['"](6L[0-9A-Za-z_-]{38})['"]"#)
+        .unwrap()
+});
+
+/// The CAPTCHA a page loads and its site key, when the key has the
+/// provider's shape.
+struct Captcha {
+    provider: &'static str,
+    site_key: Option<String>,
+}
+
+fn captcha_key(provider: &str, key: &str) -> Option<String> {
+    let key = key.trim();
+    let shape = match provider {
+        "hcaptcha" => &*HCAPTCHA_KEY_RE,
+        "turnstile" => &*TURNSTILE_KEY_RE,
+        _ => &*RECAPTCHA_KEY_RE,
+    };
+    shape.is_match(key).then(|| key.to_string())
+}
+
+/// The CAPTCHA protecting the page. Turnstile and hCaptcha are checked
+/// first since hCaptcha's reCAPTCHA compatibility mode reuses g-recaptcha.
+/// reCAPTCHA is v3 when the loader asks for render=<site key> or a script
+/// calls grecaptcha.execute('<site key>'), and v2 with a g-recaptcha
+/// container, an explicit render call or a plain loader. A grecaptcha
+/// mention in a bundled library is neither.
+fn captcha_of(html: &str) -> Option<Captcha> {
+    let document = Html::parse_document(html);
+    // Providers seen, with the first well-formed site key for each
+    let mut found: HashMap<&'static str, Option<String>> = HashMap::new();
+    let mut recaptcha_v3_key: Option<String> = None;
+    let mut recaptcha_v3 = false;
+    let mut note = |provider: &'static str, key: Option<String>| {
+        let entry = found.entry(provider).or_insert(None);
+        if entry.is_none() {
+            *entry = key;
+        }
+    };
+
+    for element in document.select(&CAPTCHA_MARKUP_SEL) {
+        let value = element.value();
+        if value.name() == "script" {
+            let src = value.attr("src").unwrap_or("");
+            let lower = src.to_ascii_lowercase();
+            if lower.contains("challenges.cloudflare.com/turnstile") {
+                note("turnstile", None);
+            } else if lower.contains("hcaptcha.com/1/api.js") {
+                note("hcaptcha", None);
+            } else if lower.contains("/recaptcha/api.js")
+                || lower.contains("/recaptcha/enterprise.js")
+            {
+                let query = src.split_once('?').map_or("", |(_, query)| query);
+                let render = url::form_urlencoded::parse(query.as_bytes())
+                    .find(|(k, _)| k == "render")
+                    .map(|(_, v)| v.into_owned())
+                    .filter(|r| !["explicit", "onload"].contains(&r.to_ascii_lowercase().as_str()));
+                match render {
+                    Some(key) => {
+                        recaptcha_v3 = true;
+                        recaptcha_v3_key = captcha_key("recaptcha", &key);
+                    }
+                    None => note("recaptcha", None),
+                }
+            }
+            continue;
+        }
+
+        let classes: Vec<&str> = value.classes().collect();
+        let key = value.attr("data-sitekey").unwrap_or("");
+        let provider = if classes.contains(&"cf-turnstile") {
+            "turnstile"
+        } else if classes.contains(&"h-captcha") {
+            "hcaptcha"
+        } else if classes.contains(&"g-recaptcha") {
+            "recaptcha"
+        } else {
+            // A bare data-sitekey: whichever provider the key's shape fits
+            match ["turnstile", "hcaptcha", "recaptcha"]
+                .into_iter()
+                .find(|p| captcha_key(p, key).is_some())
+            {
+                Some(p) => p,
+                None => continue,
+            }
+        };
+        note(provider, captcha_key(provider, key));
+    }
+
+    let live = HTML_COMMENT_RE.replace_all(html, " ");
+    for code in SCRIPT_BODY_RE.captures_iter(&live) {
+        for caps in CAPTCHA_RENDER_RE.captures_iter(&code[1]) {
+            let (api, key) = match (caps.get(1), caps.get(2), caps.get(3)) {
+                (Some(api), Some(key), _) => (api.as_str(), Some(key.as_str())),
+                (_, _, Some(api)) => (api.as_str(), None),
+                _ => continue,
+            };
+            let provider = match api {
+                "hcaptcha" => "hcaptcha",
+                "turnstile" => "turnstile",
+                _ => "recaptcha",
+            };
+            note(provider, key.and_then(|k| captcha_key(provider, k)));
+        }
+        if let Some(caps) = RECAPTCHA_EXECUTE_RE.captures(&code[1]) {
+            recaptcha_v3 = true;
+            recaptcha_v3_key.get_or_insert_with(|| caps[1].to_string());
+        }
+    }
+
+    for provider in ["turnstile", "hcaptcha"] {
+        if let Some(site_key) = found.remove(provider) {
+            return Some(Captcha { provider, site_key });
+        }
+    }
+    if recaptcha_v3 {
+        return Some(Captcha {
+            provider: "recaptcha_v3",
+            site_key: recaptcha_v3_key,
+        });
+    }
+    found.remove("recaptcha").map(|site_key| Captcha {
+        provider: "recaptcha_v2",
+        site_key,
+    })
+}
+
+/// Detect the CAPTCHA protecting the page's forms: reCAPTCHA v2 or v3,
+/// hCaptcha or Cloudflare Turnstile, from loader scripts, widget containers
+/// (g-recaptcha, h-captcha, cf-turnstile), data-sitekey attributes and
+/// explicit render/execute calls.
+///
+/// Returns a dict with keys:
+///   - "provider": "recaptcha_v2" | "recaptcha_v3" | "hcaptcha" | "turnstile"
+///   - "site_key": the public site key, or None when absent or not shaped
+///     like one of the provider's keys
+///
+/// or None when the page has no CAPTCHA.
+#[pyfunction]
+pub fn detect_captcha(py: Python<'_>, html: &str) -> PyResult<Option<PyObject>> {
+    let Some(captcha) = py.allow_threads(|| captcha_of(html)) else {
+        return Ok(None);
+    };
+    let dict = PyDict::new(py);
+    dict.set_item("provider", captcha.provider)?;
+    dict.set_item("site_key", captcha.site_key)?;
+    Ok(Some(dict.into()))
+}
+
+// Testimonials
+
+const MAX_TESTIMONIALS: usize = 50;
+
+static TESTIMONIAL_CANDIDATE_SEL: LazyLock<Selector> =
+    LazyLock::new(|| Selector::parse("blockquote, [class]").unwrap());
+static QUOTE_ATTRIBUTION_SEL: LazyLock<Selector> = LazyLock::new(|| {
+    Selector::parse(r#"cite, footer, [itemprop~="author"], [class*="author"], [class*="name"]"#)
+        .unwrap()
+});
+static FIGCAPTION_SEL: LazyLock<Selector> =
+    LazyLock::new(|| Selector::parse("figcaption").unwrap());
+
+// Headings that introduce a testimonials section on their own
+static TESTIMONIAL_HEADING_RE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(
+        r"(?i)\btestimonials?\b|\bwhat\s+(?:our|my)\s+(?:customers|clients|patients|guests|students|members|families)\s+(?:say|are\s+saying|think)\b|\bkind\s+words\b",
+    )
+    .unwrap()
+});
+// Headings that only do so alongside testimonial blocks
+static REVIEWS_HEADING_RE: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"(?i)\breviews?\b").unwrap());
+// One testimonial's class token: "testimonial", "review-card",
+// "c-testimonial__item"; not a "testimonials" section or a
+// "testimonial-author" part
+static TESTIMONIAL_CLASS_RE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(
+        r"(?i)^(?:[a-z0-9-]*?[-_])?(?:testimonial|review)(?:[-_]+(?:item|slide|card|single|entry|block|box|quote))?$",
+    )
+    .unwrap()
+});
+
+// Carousel copies of slides, which would count every testimonial twice
+static CLONED_SLIDE_CLASSES: &[&str] = &["slick-cloned", "swiper-slide-duplicate"];
+
+struct Testimonials {
+    has_testimonials: bool,
+    count: usize,
+    signals: Vec<&'static str>,
+}
+
+/// Whether `element` or an ancestor is a third-party review widget.
+fn in_review_widget(element: ElementRef) -> bool {
+    std::iter::once(element)
+        .chain(element.ancestors().filter_map(ElementRef::wrap))
+        .any(|e| {
+            let class =
+                format!("{} {}", attr_of(e, "class"), attr_of(e, "id")).to_ascii_lowercase();
+            class.contains("google-review")
+                || REVIEW_WIDGET_SIGNATURES
+                    .iter()
+                    .flat_map(|(_, sigs)| sigs.iter())
+                    .any(|sig| class.contains(sig))
+        })
+}
+
+/// A blockquote attributed to someone: a cite, footer or author element
+/// inside it, or the caption of the figure around it.
+fn is_attributed_quote(quote: ElementRef) -> bool {
+    quote.select(&QUOTE_ATTRIBUTION_SEL).next().is_some()
+        || quote.parent().and_then(ElementRef::wrap).is_some_and(|p| {
+            p.value().name() == "figure" && p.select(&FIGCAPTION_SEL).next().is_some()
+        })
+}
+
+/// Hand-rolled testimonials: testimonial headings, blocks marked up with
+/// testimonial/review classes, attributed blockquotes and Person-authored
+/// schema.org Reviews. Third-party review widgets, navigation, templates
+/// and carousel clones don't count.
+fn testimonials_of(html: &str) -> Testimonials {
+    let document = Html::parse_document(html);
+    let skipped = |e: ElementRef| {
+        e.ancestors().filter_map(ElementRef::wrap).any(|a| {
+            matches!(a.value().name(), "nav" | "template")
+                || a.value()
+                    .classes()
+                    .any(|c| CLONED_SLIDE_CLASSES.contains(&c))
+        }) || in_review_widget(e)
+    };
+
+    let mut strong_heading = false;
+    let mut reviews_heading = false;
+    for heading in document.select(&HEADING_SEL) {
+        if skipped(heading)
+            || heading
+                .ancestors()
+                .filter_map(ElementRef::wrap)
+                .any(|a| a.value().name() == "footer")
+        {
+            continue;
+        }
+        let text = joined_text(heading);
+        if TESTIMONIAL_HEADING_RE.is_match(&text) {
+            strong_heading = true;
+        } else if REVIEWS_HEADING_RE.is_match(&text)
+            // A heading over a review widget belongs to the widget
+            && !heading
+                .parent()
+                .and_then(ElementRef::wrap)
+                .is_some_and(|section| section.select(&TESTIMONIAL_CANDIDATE_SEL).any(in_review_widget))
+        {
+            reviews_heading = true;
+        }
+    }
+
+    let mut blocks = HashSet::new();
+    let mut class_blocks = 0;
+    let mut quotes = 0;
+    for element in document.select(&TESTIMONIAL_CANDIDATE_SEL) {
+        let is_quote = element.value().name() == "blockquote";
+        let is_block = element
+            .value()
+            .classes()
+            .any(|c| TESTIMONIAL_CLASS_RE.is_match(c) && !CLONED_SLIDE_CLASSES.contains(&c));
+        if !(is_block || is_quote && is_attributed_quote(element))
+            || element.ancestors().any(|a| blocks.contains(&a.id()))
+            || element
+                .value()
+                .classes()
+                .any(|c| CLONED_SLIDE_CLASSES.contains(&c))
+            || skipped(element)
+            || joined_text(element).split_whitespace().count() < 3
+        {
+            continue;
+        }
+        blocks.insert(element.id());
+        if is_block {
+            class_blocks += 1;
+        } else {
+            quotes += 1;
+        }
+    }
+    // A lone quote is as likely a pull quote in an article
+    if quotes < 2 && !strong_heading && !reviews_heading {
+        quotes = 0;
+    }
+    let schema_reviews = schema_person_reviews_of(html);
+
+    let mut signals = Vec::new();
+    if strong_heading || reviews_heading {
+        signals.push("heading");
+    }
+    if class_blocks > 0 {
+        signals.push("testimonial_markup");
+    }
+    if quotes > 0 {
+        signals.push("quote_blocks");
+    }
+    if schema_reviews > 0 {
+        signals.push("schema_review");
+    }
+    // Schema reviews usually mark up the same blocks, so take the larger
+    let count = (class_blocks + quotes)
+        .max(schema_reviews)
+        .min(MAX_TESTIMONIALS);
+    Testimonials {
+        has_testimonials: count > 0 || strong_heading,
+        count,
+        signals,
+    }
+}
+
+/// Detect a hand-rolled testimonials or reviews section.
+///
+/// Returns a dict with keys:
+///   - "has_testimonials": bool
+///   - "count_estimate": distinct testimonial blocks found, capped at 50
+///   - "signals": which of "heading" ("Testimonials", "What our clients
+///     say", "Reviews"), "testimonial_markup" (testimonial/review classed
+///     blocks), "quote_blocks" (attributed blockquotes) and "schema_review"
+///     (schema.org Reviews by a Person) were found
+///
+/// Third-party review widgets (see detect_review_widgets) and "Reviews"
+/// navigation links don't count; a "Reviews" heading needs blocks under it.
+#[pyfunction]
+pub fn detect_testimonials(py: Python<'_>, html: &str) -> PyResult<PyObject> {
+    let testimonials = py.allow_threads(|| testimonials_of(html));
+    let dict = PyDict::new(py);
+    dict.set_item("has_testimonials", testimonials.has_testimonials)?;
+    dict.set_item("count_estimate", testimonials.count)?;
+    dict.set_item("signals", testimonials.signals)?;
+    Ok(dict.into())
+}
+
+// Hiring signals
+
+const MAX_JOB_TITLES: usize = 20;
+// List items longer than this are descriptions, not job titles
+const MAX_JOB_TITLE_WORDS: usize = 8;
+
+static JOB_EMBED_SEL: LazyLock<Selector> =
+    LazyLock::new(|| Selector::parse("iframe[src], script[src]").unwrap());
+static LIST_ITEM_SEL: LazyLock<Selector> = LazyLock::new(|| Selector::parse("li").unwrap());
+
+// Careers pages by link text or path
+static CAREERS_LINK_TEXT_RE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(
+        r"(?i)^(?:careers?|jobs|employment|vacancies|job\s+openings|join\s+(?:our|the)\s+team|work\s+(?:with|for)\s+us|we(?:'|’)?re\s+hiring)$",
+    )
+    .unwrap()
+});
+static CAREERS_LINK_PATH_RE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(
+        r"(?i)^/(?:[a-z]{2}(?:-[a-z]{2})?/)?(?:careers?|jobs|employment|vacancies|join-(?:our-|the-)?team|work-(?:with|for)-us)/?$",
+    )
+    .unwrap()
+});
+// Phrases that only a business recruiting uses; "hiring the right
+// plumber" is not one
+static HIRING_PHRASE_RE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(
+        r"(?i)\b(?:now|we(?:'|’)?re|we\s+are|currently)\s+hiring\b|\bpositions?\s+(?:available|vacant)\b|\bcurrent\s+(?:vacancies|openings|opportunities)\b",
+    )
+    .unwrap()
+});
+static HIRING_HEADING_RE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(
+        r"(?i)^(?:careers?|jobs|vacancies|job\s+openings|open\s+positions|join\s+(?:our|the)\s+team|work\s+(?:with|for)\s+us)\b",
+    )
+    .unwrap()
+});
+
+// Job board and applicant tracking embeds
+static JOB_WIDGET_HOSTS: &[&str] = &[
+    "seek.com.au",
+    "seek.co.nz",
+    "indeed.com",
+    "jobadder.com",
+    "workable.com",
+    "boards.greenhouse.io",
+];
+
+struct HiringSignals {
+    signals: Vec<&'static str>,
+    job_titles: Vec<String>,
+}
+
+impl HiringSignals {
+    /// Body-text phrases alone don't make a business hiring.
+    fn is_hiring(&self) -> bool {
+        self.signals.iter().any(|s| *s != "hiring_phrase")
+    }
+}
+
+/// Whether a link sits in the site's navigation, header, footer or a menu.
+fn is_menu_link(a: ElementRef) -> bool {
+    a.ancestors().filter_map(ElementRef::wrap).any(|e| {
+        matches!(e.value().name(), "nav" | "header" | "footer")
+            || attr_of(e, "class").to_ascii_lowercase().contains("menu")
+    })
+}
+
+/// The path of a link's href, absolute or relative.
+fn link_path(a: ElementRef) -> String {
+    let href = attr_of(a, "href").trim();
+    url::Url::parse(href)
+        .map(|u| u.path().to_string())
+        .unwrap_or_else(|_| href.split(['?', '#']).next().unwrap_or("").to_string())
+}
+
+/// Short list items after a hiring heading, up to the next heading.
+fn listed_job_titles(heading: ElementRef) -> Vec<String> {
+    heading
+        .next_siblings()
+        .filter_map(ElementRef::wrap)
+        .take_while(|e| !HEADING_SEL.matches(e))
+        .flat_map(|e| {
+            let items: Vec<ElementRef> = if e.value().name() == "li" {
+                vec![e]
+            } else {
+                e.select(&LIST_ITEM_SEL).collect()
+            };
+            items
+        })
+        .map(|li| joined_text(li))
+        .filter(|text| {
+            let words = text.split_whitespace().count();
+            (1..=MAX_JOB_TITLE_WORDS).contains(&words) && !text.ends_with(['.', '!', '?', ':'])
+        })
+        .collect()
+}
+
+/// Evidence the business is recruiting: careers links in navigation,
+/// JobPosting schema, job board embeds and hiring headings, with job titles
+/// from the schema or the list under a hiring heading.
+fn hiring_signals_of(html: &str) -> HiringSignals {
+    let document = Html::parse_document(html);
+    let mut signals = Vec::new();
+    let mut job_titles: Vec<String> = Vec::new();
+
+    let careers_link = document.select(&LINK_HREF_SEL).any(|a| {
+        is_menu_link(a)
+            && (CAREERS_LINK_TEXT_RE.is_match(&collapse_whitespace(&joined_text(a)))
+                || CAREERS_LINK_PATH_RE.is_match(&link_path(a)))
+    });
+    if careers_link {
+        signals.push("careers_link");
+    }
+
+    if let Some(titles) = job_posting_titles_of(html) {
+        signals.push("job_posting_schema");
+        job_titles.extend(titles);
+    }
+
+    if document.select(&JOB_EMBED_SEL).any(|e| {
+        let src = attr_of(e, "src").to_ascii_lowercase();
+        JOB_WIDGET_HOSTS.iter().any(|host| src.contains(host))
+    }) {
+        signals.push("job_widget");
+    }
+
+    let mut hiring_heading = false;
+    for heading in document.select(&HEADING_SEL) {
+        let text = collapse_whitespace(&joined_text(heading));
+        if HIRING_HEADING_RE.is_match(&text) || HIRING_PHRASE_RE.is_match(&text) {
+            hiring_heading = true;
+            for title in listed_job_titles(heading) {
+                if !job_titles.contains(&title) {
+                    job_titles.push(title);
+                }
+            }
+        }
+    }
+    if hiring_heading {
+        signals.push("hiring_heading");
+    } else if HIRING_PHRASE_RE.is_match(&visible_text_in(&document)) {
+        signals.push("hiring_phrase");
+    }
+
+    job_titles.truncate(MAX_JOB_TITLES);
+    HiringSignals {
+        signals,
+        job_titles,
+    }
+}
+
+/// Detect whether the business is hiring.
+///
+/// Returns a dict with keys:
+///   - "is_hiring": bool
+///   - "signals": which of "careers_link" (a Careers / Join our team link in
+///     the navigation, header or footer), "job_posting_schema",
+///     "job_widget" (Seek, Indeed and applicant-tracking embeds),
+///     "hiring_heading" and "hiring_phrase" ("now hiring", "positions
+///     available" in body text) were found
+///   - "job_titles": JobPosting titles, then short list items under a
+///     hiring heading; at most 20
+///
+/// A hiring phrase in body text is reported but isn't enough on its own, so
+/// a blog post about hiring a tradesperson doesn't count.
+#[pyfunction]
+pub fn detect_hiring_signals(py: Python<'_>, html: &str) -> PyResult<PyObject> {
+    let hiring = py.allow_threads(|| hiring_signals_of(html));
+    let dict = PyDict::new(py);
+    dict.set_item("is_hiring", hiring.is_hiring())?;
+    dict.set_item("signals", hiring.signals)?;
+    dict.set_item("job_titles", hiring.job_titles)?;
+    Ok(dict.into())
+}
+
+// Pricing
+
+const MAX_SAMPLE_PRICES: usize = 10;
+// Amounts in one table or list that make it a price list
+const MIN_BLOCK_PRICES: usize = 3;
+// Larger amounts are turnover or project values, not prices
+const MAX_PRICE: f64 = 1_000_000.0;
+
+static PRICE_BLOCK_SEL: LazyLock<Selector> =
+    LazyLock::new(|| Selector::parse("table, ul, ol, dl").unwrap());
+
+// A dollar amount: $99, AU$1,200, AUD 45.50, NZD$30, 250 AUD
+static PRICE_RE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(
+        r"(?i)(?:\b(?:AUD|NZD)\s?\$?|\b(?:AU|NZ|A)\$|\$)\s?(\d{1,3}(?:,\d{3})+|\d+)(?:\.(\d{2}))?\b|\b(\d{1,3}(?:,\d{3})+|\d+)(?:\.(\d{2}))?\s?(?:AUD|NZD)\b",
+    )
+    .unwrap()
+});
+// "From $99", "starting at $1,200"
+static FROM_PRICE_RE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"(?i)\b(?:from|starting\s+(?:at|from)|prices?\s+start\s+(?:at|from))\s+(?:just\s+|only\s+)?$")
+        .unwrap()
+});
+// Amounts quoted in crypto: "$0.42 ETH", "$1,200 in BTC"
+static CRYPTO_AFTER_RE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(
+        r"(?i)^\s*(?:in\s+|worth\s+of\s+)?(?:btc|eth|usdt|usdc|sol|doge|bitcoin|ethereum|crypto)\b",
+    )
+    .unwrap()
+});
+static PRICING_LINK_TEXT_RE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(
+        r"(?i)^(?:our\s+)?(?:pricing|prices|price\s+list|fees|rates|packages(?:\s*(?:&|and)\s*pricing)?|plans\s*(?:&|and)\s*pricing)$",
+    )
+    .unwrap()
+});
+static PRICING_LINK_PATH_RE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"(?i)^/(?:[\w-]+/)?(?:pricing|prices|price-list|fees|rates|our-prices|our-fees)/?$")
+        .unwrap()
+});
+static PRICING_HEADING_RE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"(?i)\b(?:pricing|prices|price\s+list|our\s+(?:fees|rates)|fees\s*(?:&|and)\s*charges|call-?out\s+fees?)\b")
+        .unwrap()
+});
+
+struct Pricing {
+    signals: Vec<&'static str>,
+    sample_prices: Vec<String>,
+}
+
+/// "$1,200" / "$99.50" for an amount in dollars.
+fn format_price(amount: f64) -> String {
+    let cents = (amount * 100.0).round() as u64;
+    let whole = (cents / 100).to_string();
+    let mut grouped = String::new();
+    for (i, digit) in whole.chars().enumerate() {
+        if i > 0 && (whole.len() - i).is_multiple_of(3) {
+            grouped.push(',');
+        }
+        grouped.push(digit);
+    }
+    match cents % 100 {
+        0 => format!("${}", grouped),
+        c => format!("${}.{:02}", grouped, c),
+    }
+}
+
+/// Dollar amounts in `text` with their match start, skipping crypto.
+fn prices_in(text: &str) -> Vec<(usize, f64)> {
+    PRICE_RE
+        .captures_iter(text)
+        .filter_map(|caps| {
+            let whole = caps.get(1).or_else(|| caps.get(3))?;
+            let cents = caps.get(2).or_else(|| caps.get(4));
+            let end = caps.get(0)?.end();
+            if CRYPTO_AFTER_RE.is_match(&text[end..]) {
+                return None;
+            }
+            let amount: f64 = format!(
+                "{}.{}",
+                whole.as_str().replace(',', ""),
+                cents.map_or("0", |c| c.as_str())
+            )
+            .parse()
+            .ok()?;
+            (amount > 0.0 && amount < MAX_PRICE).then_some((caps.get(0)?.start(), amount))
+        })
+        .collect()
+}
+
+/// Published prices: pricing links and headings, price tables and lists,
+/// "from $X" lines and schema.org offers. Samples come from the schema and
+/// the page text once one of those shows the amounts are prices.
+fn pricing_of(html: &str) -> Pricing {
+    let document = Html::parse_document(html);
+    let mut signals = Vec::new();
+
+    let pricing_link = document.select(&LINK_HREF_SEL).any(|a| {
+        is_menu_link(a)
+            && (PRICING_LINK_TEXT_RE.is_match(&collapse_whitespace(&joined_text(a)))
+                || PRICING_LINK_PATH_RE.is_match(&link_path(a)))
+    });
+    if pricing_link {
+        signals.push("pricing_link");
+    }
+    if document
+        .select(&HEADING_SEL)
+        .any(|h| PRICING_HEADING_RE.is_match(&joined_text(h)))
+    {
+        signals.push("pricing_heading");
+    }
+    if document
+        .select(&PRICE_BLOCK_SEL)
+        .any(|block| prices_in(&joined_text(block)).len() >= MIN_BLOCK_PRICES)
+    {
+        signals.push("price_list");
+    }
+
+    let text = visible_text_in(&document);
+    let text_prices = prices_in(&text);
+    if text_prices.iter().any(|(start, _)| {
+        let before = &text[text[..*start]
+            .char_indices()
+            .rev()
+            .nth(30)
+            .map_or(0, |(i, _)| i)..*start];
+        FROM_PRICE_RE.is_match(before)
+    }) {
+        signals.push("from_price");
+    }
+
+    let schema_prices = schema_offer_prices_of(html);
+    if !schema_prices.is_empty() {
+        signals.push("schema_offer");
+    }
+
+    let mut sample_prices: Vec<String> = Vec::new();
+    if !signals.is_empty() {
+        let amounts = schema_prices
+            .into_iter()
+            .chain(text_prices.into_iter().map(|(_, amount)| amount));
+        for price in amounts.map(format_price) {
+            if sample_prices.len() == MAX_SAMPLE_PRICES {
+                break;
+            }
+            if !sample_prices.contains(&price) {
+                sample_prices.push(price);
+            }
+        }
+    }
+    Pricing {
+        signals,
+        sample_prices,
+    }
+}
+
+/// Detect published prices.
+///
+/// Returns a dict with keys:
+///   - "has_pricing": bool
+///   - "signals": which of "pricing_link" (Pricing / Fees in the
+///     navigation), "pricing_heading", "price_list" (a table or list with
+///     three or more amounts), "from_price" ("from $99 call-out fee") and
+///     "schema_offer" (schema.org Offer / priceSpecification) were found
+///   - "sample_prices": up to 10 distinct amounts such as "$99" and
+///     "$1,200", schema prices first
+///
+/// Amounts are in $, AUD or NZD; ones quoted in crypto are ignored. An
+/// incidental dollar figure with none of the signals above isn't pricing.
+#[pyfunction]
+pub fn detect_pricing(py: Python<'_>, html: &str) -> PyResult<PyObject> {
+    let pricing = py.allow_threads(|| pricing_of(html));
+    let dict = PyDict::new(py);
+    dict.set_item("has_pricing", !pricing.signals.is_empty())?;
+    dict.set_item("signals", pricing.signals)?;
+    dict.set_item("sample_prices", pricing.sample_prices)?;
+    Ok(dict.into())
+}
+
+// Blog
+
+const MAX_POST_DATES: usize = 50;
+// Posts on a page before it reads as a blog index
+const MIN_INDEX_POSTS: usize = 2;
+
+static TIME_DATETIME_SEL: LazyLock<Selector> =
+    LazyLock::new(|| Selector::parse("time[datetime]").unwrap());
+static POST_CONTAINER_SEL: LazyLock<Selector> = LazyLock::new(|| {
+    Selector::parse(
+        r#"article, .post, .hentry, .blog-post, [class*="post-item"], [class*="blog-item"]"#,
+    )
+    .unwrap()
+});
+
+static BLOG_LINK_TEXT_RE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(
+        r"(?i)^(?:our\s+|the\s+|latest\s+)?(?:blog|news|articles|insights|journal)(?:\s*(?:&|and)\s*(?:events|updates|articles|media))?$",
+    )
+    .unwrap()
+});
+static BLOG_PATH_RE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"(?i)^/(?:[\w-]+/)?(?:blog|news|articles|insights|journal)(?:/|$)").unwrap()
+});
+
+static MONTH_NAMES: &[&str] = &[
+    "jan", "feb", "mar", "apr", "may", "jun", "jul", "aug", "sep", "oct", "nov", "dec",
+];
+const MONTH_PATTERN: &str = r"(jan(?:uary)?|feb(?:ruary)?|mar(?:ch)?|apr(?:il)?|may|june?|july?|aug(?:ust)?|sep(?:t(?:ember)?)?|oct(?:ober)?|nov(?:ember)?|dec(?:ember)?)\.?";
+
+// 2021-03-12, also as the start of a timestamp (2021-03-12T09:00)
+static ISO_DATE_RE: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"\b((?:19|20)\d{2})-(\d{1,2})-(\d{1,2})(?:\b|T)").unwrap());
+// 12 March 2021, 12th Mar, 2021
+static DAY_MONTH_DATE_RE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(&format!(
+        r"(?i)\b(\d{{1,2}})(?:st|nd|rd|th)?\s+{}\s*,?\s+((?:19|20)\d{{2}})\b",
+        MONTH_PATTERN
+    ))
+    .unwrap()
+});
+// March 12, 2021
+static MONTH_DAY_DATE_RE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(&format!(
+        r"(?i)\b{}\s+(\d{{1,2}})(?:st|nd|rd|th)?,?\s+((?:19|20)\d{{2}})\b",
+        MONTH_PATTERN
+    ))
+    .unwrap()
+});
+// 12/03/2021 and 12.03.2021, day first as written in Australia
+static NUMERIC_DATE_RE: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"\b(\d{1,2})([/.])(\d{1,2})([/.])((?:19|20)\d{2})\b").unwrap());
+static COPYRIGHT_NEARBY_RE: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"(?i)©|&copy;|\(c\)|\bcopyright\b").unwrap());
+
+struct BlogCheck {
+    blog_url: Option<String>,
+    post_dates: Vec<String>,
+}
+
+/// "YYYY-MM-DD" for a real calendar date no later than today.
+fn iso_date(year: i32, month: u32, day: u32) -> Option<String> {
+    let days_in_month = match month {
+        1 | 3 | 5 | 7 | 8 | 10 | 12 => 31,
+        4 | 6 | 9 | 11 => 30,
+        2 if year % 4 == 0 && (year % 100 != 0 || year % 400 == 0) => 29,
+        2 => 28,
+        _ => return None,
+    };
+    if day == 0 || day > days_in_month || (year, month, day) > today() {
+        return None;
+    }
+    Some(format!("{:04}-{:02}-{:02}", year, month, day))
+}
+
+fn month_number(name: &str) -> Option<u32> {
+    let prefix = name.get(..3)?.to_ascii_lowercase();
+    MONTH_NAMES
+        .iter()
+        .position(|m| *m == prefix)
+        .map(|i| i as u32 + 1)
+}
+
+/// Dates written in `text` as ISO dates, with their match start.
+fn dates_in(text: &str) -> Vec<(usize, String)> {
+    let mut dates = Vec::new();
+    let mut add = |start: usize, year: &str, month: Option<u32>, day: &str| {
+        let date = month.and_then(|month| iso_date(year.parse().ok()?, month, day.parse().ok()?));
+        dates.extend(date.map(|d| (start, d)));
+    };
+    let start = |caps: &regex::Captures| caps.get(0).map_or(0, |m| m.start());
+    for caps in ISO_DATE_RE.captures_iter(text) {
+        add(start(&caps), &caps[1], caps[2].parse().ok(), &caps[3]);
+    }
+    for caps in DAY_MONTH_DATE_RE.captures_iter(text) {
+        add(start(&caps), &caps[3], month_number(&caps[2]), &caps[1]);
+    }
+    for caps in MONTH_DAY_DATE_RE.captures_iter(text) {
+        add(start(&caps), &caps[3], month_number(&caps[1]), &caps[2]);
+    }
+    for caps in NUMERIC_DATE_RE.captures_iter(text) {
+        // Mixed separators ("1.2/2021") are version numbers or ratios
+        if caps[2] == caps[4] {
+            add(start(&caps), &caps[5], caps[3].parse().ok(), &caps[1]);
+        }
+    }
+    dates
+}
+
+/// The blog linked from the navigation and the post dates on this page.
+/// Dates come from post schema everywhere, and from `<time>` elements and
+/// written dates when the page itself is a blog index: its URL is a blog
+/// path, or it lists several posts. Dates next to a copyright mark and
+/// future dates are skipped.
+fn blog_check_of(html: &str, base: Option<&str>) -> BlogCheck {
+    let document = Html::parse_document(html);
+    let base = base.and_then(|b| url::Url::parse(b.trim()).ok());
+
+    let blog_url = document
+        .select(&LINK_HREF_SEL)
+        .find(|a| {
+            is_menu_link(*a)
+                && (BLOG_LINK_TEXT_RE.is_match(&collapse_whitespace(&joined_text(*a)))
+                    || BLOG_PATH_RE.is_match(&link_path(*a)))
+        })
+        .map(|a| {
+            let href = attr_of(a, "href").trim();
+            base.as_ref()
+                .and_then(|b| b.join(href).ok())
+                .map_or_else(|| href.to_string(), |u| u.to_string())
+        });
+
+    let mut dates: Vec<String> = schema_post_dates_of(html)
+        .iter()
+        .filter_map(|raw| dates_in(raw).into_iter().next().map(|(_, d)| d))
+        .collect();
+
+    let is_index = base
+        .as_ref()
+        .is_some_and(|b| BLOG_PATH_RE.is_match(b.path()))
+        || document.select(&POST_CONTAINER_SEL).count() >= MIN_INDEX_POSTS;
+    if is_index {
+        for time in document.select(&TIME_DATETIME_SEL) {
+            dates.extend(
+                dates_in(attr_of(time, "datetime"))
+                    .into_iter()
+                    .map(|(_, d)| d),
+            );
+        }
+        let text = stream_text(html, LANDMARK_ELEMENTS).text;
+        for (start, date) in dates_in(&text) {
+            let mut before = start.saturating_sub(40);
+            while !text.is_char_boundary(before) {
+                before += 1;
+            }
+            if !COPYRIGHT_NEARBY_RE.is_match(&text[before..start]) {
+                dates.push(date);
+            }
+        }
+    }
+
+    dates.sort_unstable_by(|a, b| b.cmp(a));
+    dates.dedup();
+    dates.truncate(MAX_POST_DATES);
+    BlogCheck {
+        blog_url,
+        post_dates: dates,
+    }
+}
+
+/// Find the blog and how recently it was posted to.
+///
+/// Returns a dict with keys:
+///   - "has_blog_link": bool
+///   - "blog_url": the Blog / News / Articles navigation link, resolved
+///     against `url` when given, or None
+///   - "latest_post_date": the most recent post date as "YYYY-MM-DD", or None
+///   - "post_dates_found": distinct post dates, newest first (at most 50)
+///
+/// Post dates are read from schema.org datePublished, and on a blog index
+/// (a blog URL, or a page listing several posts) from `<time>` elements and
+/// dates such as "12 March 2021", "March 12, 2021", "2021-03-12" and
+/// "12/03/2021" (day first). Copyright years are ignored. The blog itself
+/// isn't fetched; crawl blog_url for its dates.
+#[pyfunction]
+#[pyo3(signature = (html, url=None))]
+pub fn detect_blog(py: Python<'_>, html: &str, url: Option<&str>) -> PyResult<PyObject> {
+    let blog = py.allow_threads(|| blog_check_of(html, url));
+    let dict = PyDict::new(py);
+    dict.set_item("has_blog_link", blog.blog_url.is_some())?;
+    dict.set_item("blog_url", blog.blog_url)?;
+    dict.set_item("latest_post_date", blog.post_dates.first())?;
+    dict.set_item("post_dates_found", blog.post_dates)?;
+    Ok(dict.into())
+}
+
+// Languages
+
+static HREFLANG_SEL: LazyLock<Selector> =
+    LazyLock::new(|| Selector::parse("link[hreflang], a[hreflang]").unwrap());
+static HTML_LANG_SEL: LazyLock<Selector> = LazyLock::new(|| Selector::parse("html[lang]").unwrap());
+
+// Translation plugins and services: (mechanism, markers)
+static TRANSLATION_SIGNATURES: &[(&str, &[&str])] = &[
+    (
+        "WPML",
+        &["sitepress-multilingual-cms", "wpml-ls-", "icl_lang_sel"],
+    ),
+    (
+        "Polylang",
+        &["/plugins/polylang", "pll-parent-menu-item", "pll_switcher"],
+    ),
+    ("GTranslate", &["gtranslate", "gt_float_wrapper"]),
+    (
+        "Weglot",
+        &["cdn.weglot.com", "weglot.initialize", "weglot-container"],
+    ),
+    (
+        "Google Translate",
+        &[
+            "translate.google.com/translate_a/element.js",
+            "google_translate_element",
+        ],
+    ),
+];
+
+// Switcher labels in their own language, with the ISO 639-1 code
+static LANGUAGE_ENDONYMS: &[(&str, &str)] = &[
+    ("english", "en"),
+    ("中文", "zh"),
+    ("简体中文", "zh"),
+    ("繁體中文", "zh"),
+    ("tiếng việt", "vi"),
+    ("العربية", "ar"),
+    ("ελληνικά", "el"),
+    ("italiano", "it"),
+    ("español", "es"),
+    ("한국어", "ko"),
+    ("日本語", "ja"),
+    ("हिन्दी", "hi"),
+    ("français", "fr"),
+    ("deutsch", "de"),
+    ("bahasa indonesia", "id"),
+    ("filipino", "tl"),
+    ("ภาษาไทย", "th"),
+];
+
+// A language path prefix: /zh/, /en-au/
+static LANGUAGE_PATH_RE: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"(?i)^/([a-z]{2})(?:[-_][a-z]{2,4})?(?:/|$)").unwrap());
+
+struct Languages {
+    languages: Vec<String>,
+    mechanism: Option<&'static str>,
+}
+
+/// The ISO 639-1 code of a language tag ("en-AU" -> "en", "zh-Hans" ->
+/// "zh"), mapping the withdrawn iw/in/ji codes to he/id/yi.
+fn iso_639_1(tag: &str) -> Option<String> {
+    let primary = tag.trim().split(['-', '_']).next()?.to_ascii_lowercase();
+    if primary.len() != 2 || !primary.chars().all(|c| c.is_ascii_lowercase()) {
+        return None;
+    }
+    Some(
+        match primary.as_str() {
+            "iw" => "he",
+            "in" => "id",
+            "ji" => "yi",
+            code => code,
+        }
+        .to_string(),
+    )
+}
+
+/// Languages the site is served in: the html lang first, then hreflang
+/// alternates (x-default aside) and language-switcher links, which are
+/// links labelled with a language code or name that lead to that
+/// language's path prefix. The mechanism is the translation plugin or
+/// service when there is one, else how a second language was found.
+fn languages_of(html: &str, scan: &PageScan) -> Languages {
+    let document = Html::parse_document(html);
+    let html_lang = document
+        .select(&HTML_LANG_SEL)
+        .next()
+        .and_then(|h| iso_639_1(attr_of(h, "lang")));
+    let hreflang: Vec<String> = document
+        .select(&HREFLANG_SEL)
+        .filter_map(|link| iso_639_1(attr_of(link, "hreflang")))
+        .collect();
+    let switcher: Vec<String> = document
+        .select(&LINK_HREF_SEL)
+        .filter_map(|a| {
+            let code = LANGUAGE_PATH_RE
+                .captures(&link_path(a))
+                .and_then(|caps| iso_639_1(&caps[1]))?;
+            let label = collapse_whitespace(&joined_text(a)).to_lowercase();
+            let labelled = label == code
+                || LANGUAGE_ENDONYMS
+                    .iter()
+                    .any(|(name, c)| *c == code && label == *name);
+            labelled.then_some(code)
+        })
+        .collect();
+
+    let mut languages: Vec<String> = Vec::new();
+    let mut found_by = Vec::new();
+    for (source, codes) in [
+        ("html", Vec::from_iter(html_lang)),
+        ("hreflang", hreflang),
+        ("switcher", switcher),
+    ] {
+        for code in codes {
+            if !languages.contains(&code) {
+                languages.push(code);
+                found_by.push(source);
+            }
+        }
+    }
+
+    let plugin = match_signature_table(TRANSLATION_SIGNATURES, |sig| scan.find_live(sig))
+        .into_iter()
+        .next()
+        .map(|h| h.name);
+    // The source of the second language found
+    let mechanism = plugin.or_else(|| found_by.get(1).copied());
+    Languages {
+        languages,
+        mechanism,
+    }
+}
+
+/// Detect a site served in several languages.
+///
+/// Returns a dict with keys:
+///   - "is_multilingual": bool
+///   - "languages": ISO 639-1 codes: the html lang, hreflang alternates
+///     (x-default ignored) and language-switcher links, deduplicated
+///   - "mechanism": "WPML" | "Polylang" | "GTranslate" | "Weglot" |
+///     "Google Translate" | "hreflang" | "switcher" | None
+///
+/// A translation plugin or service makes a site multilingual on its own;
+/// otherwise it takes two languages, so an html lang alone never does.
+#[pyfunction]
+pub fn detect_languages(py: Python<'_>, html: &str) -> PyResult<PyObject> {
+    let languages = py.allow_threads(|| languages_of(html, &PageScan::new(html)));
+    let dict = PyDict::new(py);
+    dict.set_item("is_multilingual", languages.mechanism.is_some())?;
+    dict.set_item("languages", languages.languages)?;
+    dict.set_item("mechanism", languages.mechanism)?;
+    Ok(dict.into())
+}
+
+// App store links
+
+static APP_BANNER_SEL: LazyLock<Selector> =
+    LazyLock::new(|| Selector::parse(r#"meta[name="apple-itunes-app" i][content]"#).unwrap());
+static APP_BANNER_ID_RE: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"(?i)\bapp-id\s*=\s*(\d+)").unwrap());
+static APPLE_APP_ID_RE: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"/id(\d+)").unwrap());
+// Wording that makes a store link the business's own app ("the Snapseed
+// app" names someone else's)
+static OWN_APP_RE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"(?i)\bour\s+(?:\w+\s+)?app\b|\bdownload|\bget\s+it\s+on\b|\bavailable\s+on\b")
+        .unwrap()
+});
+
+// Class names of blog post and article bodies
+const ARTICLE_BODY_CLASSES: &[&str] =
+    &["entry-content", "post-content", "post-body", "article-body"];
+
+#[derive(Default)]
+struct AppLinks {
+    ios_url: Option<String>,
+    android_url: Option<String>,
+    app_ids: Vec<String>,
+}
+
+/// The store and app ID an App Store or Google Play link points at:
+/// ("ios", "<numeric id>") or ("android", "<package name>").
+fn store_app_of(href: &str) -> Option<(&'static str, String)> {
+    let url = url::Url::parse(href.trim()).ok()?;
+    let host = url.host_str()?.to_ascii_lowercase();
+    match host.as_str() {
+        "apps.apple.com" | "itunes.apple.com" => APPLE_APP_ID_RE
+            .captures(url.path())
+            .map(|caps| ("ios", caps[1].to_string())),
+        "play.google.com" if url.path().starts_with("/store/apps/details") => url
+            .query_pairs()
+            .find(|(k, v)| k == "id" && !v.is_empty())
+            .map(|(_, v)| ("android", v.into_owned())),
+        _ => None,
+    }
+}
+
+fn in_article_body(a: ElementRef) -> bool {
+    a.ancestors().filter_map(ElementRef::wrap).any(|e| {
+        let class = attr_of(e, "class").to_ascii_lowercase();
+        e.value().name() == "article" || ARTICLE_BODY_CLASSES.iter().any(|c| class.contains(c))
+    })
+}
+
+/// Whether a store link is presented as the business's own app: anywhere
+/// outside a post body, or in one with "download"/"our app" wording on the
+/// link (text, badge alt, label) or in its parent element. A blog post
+/// linking someone else's app by name doesn't count.
+fn is_own_app_link(a: ElementRef) -> bool {
+    if is_menu_link(a) || !in_article_body(a) {
+        return true;
+    }
+    let mut context = joined_text(a);
+    for img in a.select(&IMG_SEL) {
+        context.push(' ');
+        context.push_str(attr_of(img, "alt"));
+    }
+    for name in ["aria-label", "title"] {
+        context.push(' ');
+        context.push_str(attr_of(a, name));
+    }
+    if let Some(parent) = a.parent().and_then(ElementRef::wrap) {
+        context.push(' ');
+        context.push_str(&joined_text(parent));
+    }
+    OWN_APP_RE.is_match(&context)
+}
+
+fn app_links_of(html: &str) -> AppLinks {
+    let document = Html::parse_document(html);
+    let mut links = AppLinks::default();
+    let banner_id = document
+        .select(&APP_BANNER_SEL)
+        .find_map(|meta| APP_BANNER_ID_RE.captures(attr_of(meta, "content")))
+        .map(|caps| caps[1].to_string());
+    if let Some(id) = banner_id {
+        links.ios_url = Some(format!("https://apps.apple.com/app/id{}", id));
+        links.app_ids.push(id);
+    }
+    for a in document.select(&LINK_HREF_SEL) {
+        let href = attr_of(a, "href").trim();
+        let Some((store, id)) = store_app_of(href) else {
+            continue;
+        };
+        if !is_own_app_link(a) {
+            continue;
+        }
+        let url = match store {
+            "ios" => &mut links.ios_url,
+            _ => &mut links.android_url,
+        };
+        url.get_or_insert_with(|| href.to_string());
+        if !links.app_ids.contains(&id) {
+            links.app_ids.push(id);
+        }
+    }
+    links
+}
+
+/// Links to the business's own mobile app.
+///
+/// Returns a dict with keys:
+///   - "has_app": bool
+///   - "ios_url": the App Store link (or one built from the
+///     apple-itunes-app smart banner meta), or None
+///   - "android_url": the Google Play link, or None
+///   - "app_ids": App Store numeric IDs and Play package names
+///
+/// Store links in a blog post or article body only count with
+/// "download"/"our app" wording around them; header, footer and other page
+/// badges always do.
+#[pyfunction]
+pub fn detect_app_links(py: Python<'_>, html: &str) -> PyResult<PyObject> {
+    let links = py.allow_threads(|| app_links_of(html));
+    let dict = PyDict::new(py);
+    dict.set_item(
+        "has_app",
+        links.ios_url.is_some() || links.android_url.is_some(),
+    )?;
+    dict.set_item("ios_url", links.ios_url)?;
+    dict.set_item("android_url", links.android_url)?;
+    dict.set_item("app_ids", links.app_ids)?;
+    Ok(dict.into())
+}
+
+/// Normalized phone numbers in the visible text, each with its label and
+/// up to `context_words` words of the text before it.
+fn labelled_phones_of(html: &str, context_words: usize) -> Vec<(String, PhoneLabel, String)> {
+    let text = visible_text(html);
+
+    // Collect matches from every pattern, then keep the earliest/longest
+    // non-overlapping ones so context never spans another number.
+    let mut matches: Vec<(usize, usize)> = PHONE_PATTERNS
+        .iter()
+        .flat_map(|re| re.find_iter(&text).map(|m| (m.start(), m.end())))
+        .collect();
+    matches.sort_by(|a, b| a.0.cmp(&b.0).then(b.1.cmp(&a.1)));
+
+    let mut seen: HashSet<String> = HashSet::new();
+    let mut phones = Vec::new();
+    let mut prev_end = 0;
+
+    for (start, end) in matches {
+        if start < prev_end {
+            continue;
+        }
+        let context = last_words(&text[prev_end..start], context_words);
+        prev_end = end;
+
+        let normalized = normalize_phone(&text[start..end]);
+        if normalized.is_empty() || !seen.insert(normalized.clone()) {
+            continue;
+        }
+        let label = classify_phone_context(&context, &normalized);
+        phones.push((normalized, label, context));
+    }
+    phones
+}
+
+/// Extract phone numbers with a label inferred from the preceding text.
+///
+/// Returns a list of dicts with keys:
+///   - "number": normalized phone number
+///   - "label": "office" | "mobile" | "fax" | "after_hours" | "sales" | "support" | "unknown"
+///   - "context": up to `context_words` words of visible text before the number
+///   - "source": "text", or "whatsapp" for numbers that only appear in a
+///     WhatsApp click-to-chat link (with `include_whatsapp`)
+#[pyfunction]
+#[pyo3(signature = (html, context_words=6, include_whatsapp=false))]
+pub fn extract_phones_with_labels(
+    py: Python<'_>,
+    html: &str,
+    context_words: usize,
+    include_whatsapp: bool,
+) -> PyResult<PyObject> {
+    let list = PyList::empty(py);
+    if html.is_empty() {
+        return Ok(list.into());
+    }
+
+    let mut seen: HashSet<String> = HashSet::new();
+    for (number, label, context) in labelled_phones_of(html, context_words) {
+        seen.insert(number.clone());
+        let dict = PyDict::new(py);
+        dict.set_item("number", number)?;
+        dict.set_item("label", label.as_str())?;
+        dict.set_item("context", context)?;
+        dict.set_item("source", "text")?;
+        list.append(dict)?;
+    }
+
+    if include_whatsapp {
+        for phone in whatsapp_phones_of(html) {
+            if !seen.insert(phone.clone()) {
+                continue;
+            }
+            let label = classify_phone_context("", &phone);
+            let dict = PyDict::new(py);
+            dict.set_item("number", phone)?;
+            dict.set_item("label", label.as_str())?;
+            dict.set_item("context", "")?;
+            dict.set_item("source", "whatsapp")?;
+            list.append(dict)?;
+        }
+    }
+
+    Ok(list.into())
+}
+
+/// The most likely CMS, or None when nothing beyond a passing brand-name
+/// mention matched. See detect_cms_detailed for every candidate.
+#[pyfunction]
+pub fn detect_cms(html: &str) -> Option<String> {
+    if html.is_empty() {
+        return None;
+    }
+
+    cms_of(&PageScan::new(html))
+}
+
+/// Every CMS with a matching signature, sorted by confidence.
+///
+/// Returns a list of dicts with keys:
+///   - "cms": platform name
+///   - "confidence": 0.0-1.0; asset paths and CDN hosts count fully, bare
+///     brand words ("wordpress") only a little
+///   - "matched_signatures": the signatures found on the page
+#[pyfunction]
+pub fn detect_cms_detailed(py: Python<'_>, html: &str) -> PyResult<PyObject> {
+    let list = PyList::empty(py);
+    if html.is_empty() {
+        return Ok(list.into());
+    }
+
+    for m in match_cms(&PageScan::new(html)) {
+        let dict = PyDict::new(py);
+        dict.set_item("cms", &m.cms)?;
+        dict.set_item("confidence", (m.confidence() * 100.0).round() / 100.0)?;
+        dict.set_item("matched_signatures", m.matched_signatures)?;
+        list.append(dict)?;
+    }
+
+    Ok(list.into())
+}
+
+/// Add signature patterns for a CMS at runtime. Patterns are matched
+/// case-insensitively as substrings; ones that are a bare word ("carrd")
+/// count for little, asset paths and hosts count fully. A new name is
+/// appended to the table; an existing one (case-insensitive) gains the
+/// patterns, or with `replace=True` has its patterns swapped out entirely,
+/// which is how an overly broad built-in is demoted. Replacing with an empty
+/// list removes the CMS. Returns the number of patterns added.
+#[pyfunction]
+#[pyo3(signature = (name, patterns, replace=false))]
+pub fn register_cms_signature(name: &str, patterns: Vec<String>, replace: bool) -> PyResult<usize> {
+    apply_signature(&CMS_SIGNATURES, "CMS", name, &patterns, replace)
+}
+
+/// Load CMS signatures from a JSON object of {"CMS name": ["pattern", ...]},
+/// applying each entry as register_cms_signature would. Invalid JSON or
+/// entries raise ValueError before anything is applied. Returns the number
+/// of CMS entries applied.
+#[pyfunction]
+#[pyo3(signature = (json_str, replace=false))]
+pub fn load_cms_signatures(json_str: &str, replace: bool) -> PyResult<usize> {
+    let value: serde_json::Value = serde_json::from_str(json_str)
+        .map_err(|e| PyValueError::new_err(format!("Invalid CMS signature JSON: {}", e)))?;
+    let object = value
+        .as_object()
+        .ok_or_else(|| PyValueError::new_err("CMS signature JSON must be an object"))?;
+
+    let mut entries: Vec<(&str, Vec<String>)> = Vec::with_capacity(object.len());
+    for (name, patterns) in object {
+        let patterns = patterns
+            .as_array()
+            .and_then(|items| {
+                items
+                    .iter()
+                    .map(|p| p.as_str().map(str::to_string))
+                    .collect::<Option<Vec<_>>>()
+            })
+            .ok_or_else(|| {
+                PyValueError::new_err(format!(
+                    "Signatures for {:?} must be a list of strings",
+                    name
+                ))
+            })?;
+        entries.push(signature_entry("CMS", name, &patterns).map_err(PyValueError::new_err)?);
+    }
+
+    let applied = entries.len();
+    for (name, patterns) in entries {
+        merge_signature(&CMS_SIGNATURES, name, patterns, replace);
+    }
+    Ok(applied)
+}
+
+/// The active CMS signature table as (name, patterns) pairs, in match order.
+#[pyfunction]
+pub fn list_cms_signatures() -> SignatureTable {
+    CMS_SIGNATURES.read().unwrap().clone()
+}
+
+/// Restore the built-in CMS signature table.
+#[pyfunction]
+pub fn reset_cms_signatures() {
+    *CMS_SIGNATURES.write().unwrap() = signature_table(DEFAULT_CMS_SIGNATURES);
+    rebuild_signature_index();
+}
+
+/// Detect the WordPress, Joomla or Drupal version a page was built with.
+///
+/// Returns None when the page carries no version evidence (stripped
+/// generator tags are not guessed around). Otherwise a dict with keys:
+///   - "cms": "WordPress" | "Joomla" | "Drupal"
+///   - "version": best version string ("6.4.2", "3.9", "7", "8+")
+///   - "source": "generator" | "asset_ver" | "drupal_settings"
+///   - "candidates": every version found, as {cms, version, source} dicts,
+///     so a generator tag that disagrees with asset versions is visible
+///
+/// The generator tag wins over other sources.
+#[pyfunction]
+pub fn detect_cms_version(py: Python<'_>, html: &str) -> PyResult<Option<PyObject>> {
+    let candidates = cms_version_candidates(html);
+    let Some(best) = candidates.first() else {
+        return Ok(None);
+    };
+
+    let list = PyList::empty(py);
+    for candidate in &candidates {
+        let entry = PyDict::new(py);
+        entry.set_item("cms", candidate.cms)?;
+        entry.set_item("version", &candidate.version)?;
+        entry.set_item("source", candidate.source)?;
+        list.append(entry)?;
+    }
+
+    let dict = PyDict::new(py);
+    dict.set_item("cms", best.cms)?;
+    dict.set_item("version", &best.version)?;
+    dict.set_item("source", best.source)?;
+    dict.set_item("candidates", list)?;
+    Ok(Some(dict.into()))
+}
+
+/// Detect the page builder behind a WordPress site: "Elementor", "Divi",
+/// "WPBakery", "Beaver Builder", "Oxygen", or "Gutenberg" for block-heavy
+/// pages. Non-WordPress pages always return None, so copied class names
+/// ("vc_row" on a static site) don't count.
+#[pyfunction]
+pub fn detect_page_builder(html: &str) -> Option<String> {
+    let scan = PageScan::new(html);
+    if cms_of(&scan).as_deref() != Some("WordPress") {
+        return None;
+    }
+    page_builder_of(&scan).map(str::to_string)
+}
+
+/// Detect the active WordPress theme from /wp-content/themes/<slug>/ asset
+/// URLs. When a parent and child theme both appear the most frequent slug
+/// is reported and `is_child_theme_guess` is set.
+///
+/// Returns None for pages without theme assets, otherwise a dict with keys
+/// "slug" (raw, lowercase), "name" (title-cased for display) and
+/// "is_child_theme_guess".
+#[pyfunction]
+pub fn detect_wp_theme(py: Python<'_>, html: &str) -> PyResult<Option<PyObject>> {
+    wp_theme_of(html)
+        .map(|theme| wp_theme_dict(py, theme))
+        .transpose()
+}
+
+/// WordPress plugin slugs visible in the page ("woocommerce",
+/// "contact-form-7", "wordpress-seo", ...), from /wp-content/plugins/ asset
+/// paths plus inline markers for plugins that don't expose paths. Sorted by
+/// first appearance and capped at 50; non-WordPress pages return [].
+#[pyfunction]
+pub fn detect_wp_plugins(html: &str) -> Vec<String> {
+    wp_plugins_of(html, &PageScan::new(html))
+}
+
+/// Detect an online store and the platform running it.
+///
+/// Returns None when neither a known platform (WooCommerce, Shopify,
+/// BigCommerce, Magento, Squarespace Commerce, Ecwid, Neto, Wix Stores) nor
+/// cart markup is found. Otherwise a dict with keys:
+///   - "platform": platform name, or None for an unrecognised store
+///   - "signals": platform signatures matched
+///   - "has_cart": add-to-cart buttons or cart/checkout links present
+#[pyfunction]
+pub fn detect_ecommerce(py: Python<'_>, html: &str) -> PyResult<Option<PyObject>> {
+    let check = check_ecommerce(html, &PageScan::new(html));
+    if check.platform.is_none() && !check.has_cart {
+        return Ok(None);
+    }
+    let dict = PyDict::new(py);
+    dict.set_item("platform", check.platform)?;
+    dict.set_item("signals", check.signals)?;
+    dict.set_item("has_cart", check.has_cart)?;
+    Ok(Some(dict.into()))
+}
+
+/// Payment and buy-now-pay-later providers embedded on the page (Stripe,
+/// Square, PayPal, Afterpay, Zip, Klarna, eWAY, Tyro), ordered by first
+/// appearance. Only script hosts and widget markup count, not brand names
+/// in copy.
+#[pyfunction]
+pub fn detect_payment_providers(html: &str) -> Vec<String> {
+    payment_providers_of(&PageScan::new(html))
+        .into_iter()
+        .map(String::from)
+        .collect()
+}
+
+/// The live chat vendor embedded on the page (Intercom, Drift, Tawk.to,
+/// Crisp, Zendesk Chat, LiveChat, Tidio, HubSpot Chat, Facebook Customer
+/// Chat, Podium), or None. Snippets inside HTML comments are ignored.
+#[pyfunction]
+pub fn detect_chat_widget(html: &str) -> Option<String> {
+    check_chat_widget(html, &PageScan::new(html))
+        .vendor
+        .map(String::from)
+}
+
+/// Marketing automation and CRM tools embedded on the page (HubSpot,
+/// ActiveCampaign, Klaviyo, Mailchimp forms, Pardot, Marketo, Omnisend),
+/// ordered by first appearance.
+#[pyfunction]
+pub fn detect_marketing_tools(html: &str) -> Vec<String> {
+    marketing_tools_of(&PageScan::new(html))
+        .into_iter()
+        .map(String::from)
+        .collect()
+}
+
+/// Review and social-proof widgets embedded on the page (Trustpilot,
+/// Elfsight, EmbedSocial, ProductReview, Yotpo, Judge.me, Stamped, Birdeye,
+/// Podium), ordered by first appearance, then "Google Reviews" for the
+/// common Google reviews widget classes. Links to a reviews page don't
+/// count.
+#[pyfunction]
+pub fn detect_review_widgets(html: &str) -> Vec<String> {
+    review_widgets_of(html, &PageScan::new(html))
+        .into_iter()
+        .map(String::from)
+        .collect()
+}
+
+/// Which analytics and ad trackers the page loads.
+///
+/// Every key is always present: google_analytics (GA4 or Universal
+/// Analytics), google_tag_manager, ga4, universal_analytics, facebook_pixel,
+/// google_ads, hotjar, microsoft_clarity, tiktok_pixel, linkedin_insight,
+/// pinterest_tag, snapchat_pixel.
+#[pyfunction]
+pub fn detect_tracking(html: &str) -> HashMap<String, bool> {
+    tracking_of(html, &PageScan::new(html))
+}
+
+/// Analytics and pixel IDs found in tracking snippets, for spotting sites
+/// that share an owner or agency.
+///
+/// Returns a dict of ga4_ids, ua_ids, gtm_ids and fb_pixel_ids, each a
+/// deduplicated list of at most 10 IDs. IDs are only read from gtag/ga/fbq
+/// calls, the GTM snippet and loader URLs.
+#[pyfunction]
+pub fn extract_tracking_ids(py: Python<'_>, html: &str) -> PyResult<PyObject> {
+    Ok(tracking_ids_dict(py, tracking_ids_of(html))?.into())
+}
+
+#[pyfunction]
+pub fn detect_booking_system(html: &str) -> bool {
+    booking_match_of(&PageScan::new(html)).is_some()
+}
+
+/// Name the booking system a page uses.
+///
+/// Returns None when no booking signal is found. Otherwise a dict with keys:
+///   - "provider": provider name ("Calendly", "Timely", ...), or "generic"
+///     for a bare book-now / book-online button
+///   - "signal": the signature that matched
+///   - "confidence": 1.0 for a named provider, 0.4 for "generic"
+#[pyfunction]
+pub fn detect_booking_system_detailed(py: Python<'_>, html: &str) -> PyResult<Option<PyObject>> {
+    let Some(found) = booking_match_of(&PageScan::new(html)) else {
+        return Ok(None);
+    };
+    let dict = PyDict::new(py);
+    dict.set_item("provider", found.provider)?;
+    dict.set_item("signal", found.signal)?;
+    dict.set_item("confidence", found.confidence)?;
+    Ok(Some(dict.into()))
+}
+
+/// Add booking signature patterns for a provider at runtime. Patterns are
+/// matched case-insensitively as substrings; an existing provider
+/// (case-insensitive) gains the patterns. Returns the number of patterns
+/// added.
+#[pyfunction]
+pub fn register_booking_signatures(provider: &str, patterns: Vec<String>) -> PyResult<usize> {
+    apply_signature(
+        &BOOKING_SIGNATURES,
+        "Booking provider",
+        provider,
+        &patterns,
+        false,
+    )
+}
+
+/// Front-end frameworks and libraries the page loads, in table order. Only
+/// script/stylesheet URLs, inline scripts and attribute names are checked.
+#[pyfunction]
+pub fn detect_frameworks(html: &str) -> Vec<String> {
+    frameworks_in(&framework_contexts(html))
+        .into_iter()
+        .map(String::from)
+        .collect()
+}
+
+/// Versions of jQuery, jQuery UI, Bootstrap, Moment, AngularJS (1.x) and
+/// Modernizr loaded by the page, read from versioned CDN URLs
+/// (code.jquery.com, cdnjs, ajax.googleapis.com, jsDelivr/unpkg "@x.y.z")
+/// and WordPress "?ver=" query strings.
+///
+/// Returns a list of dicts with keys:
+///   - "library": e.g. "jQuery"
+///   - "version": e.g. "1.8.3"
+///   - "latest_known_major": from the compiled-in table, or as updated by
+///     register_library_latest
+///   - "outdated": at least one major version behind
+#[pyfunction]
+pub fn detect_outdated_libraries(py: Python<'_>, html: &str) -> PyResult<PyObject> {
+    let found = py.allow_threads(|| library_versions_of(html));
+    let list = PyList::empty(py);
+    for lib in &found {
+        list.append(library_version_dict(py, lib)?)?;
+    }
+    Ok(list.into())
+}
+
+/// Set the latest major version known for a library detect_outdated_libraries
+/// reports ("jQuery", "Bootstrap", ...; case-insensitive). Raises ValueError
+/// for a library it doesn't know.
+#[pyfunction]
+pub fn register_library_latest(library: &str, major: u32) -> PyResult<()> {
+    let lib = JS_LIBRARIES
+        .iter()
+        .find(|lib| lib.name.eq_ignore_ascii_case(library.trim()))
+        .ok_or_else(|| PyValueError::new_err(format!("Unknown library {:?}", library)))?;
+    LIBRARY_LATEST.write().unwrap().insert(lib.name, major);
+    Ok(())
+}
+
+/// Legacy-technology red flags: "flash", "frameset", "marquee",
+/// "frontpage_generator", "dreamweaver_generator", "table_layout",
+/// "spacer_gif", "mhtml" and "vbscript", in that order. table_layout needs
+/// width-sized tables nested in each other on a page without Bootstrap or
+/// Tailwind, so data tables on modern sites aren't flagged.
+#[pyfunction]
+pub fn detect_legacy_tech(py: Python<'_>, html: &str) -> Vec<&'static str> {
+    py.allow_threads(|| legacy_flags_of(html, &framework_contexts(html)))
+}
+
+#[pyfunction]
+pub fn detect_responsive(html: &str) -> bool {
+    check_responsive(html, &framework_contexts(html)).responsive
+}
+
+/// Why a page does or doesn't look mobile-friendly.
+///
+/// Returns a dict with keys:
+///   - "responsive": overall verdict, as detect_responsive
+///   - "has_viewport_meta": `<meta name="viewport">` with width=device-width
+///   - "has_media_queries": min/max-width @media rules in inline styles, a
+///     width media attribute on a stylesheet link, or a responsive*.css
+///     stylesheet
+///   - "framework_hint": "Bootstrap" (3+) or "Tailwind" when one is loaded
+#[pyfunction]
+pub fn detect_responsive_detailed(py: Python<'_>, html: &str) -> PyResult<PyObject> {
+    let check = check_responsive(html, &framework_contexts(html));
+    let dict = PyDict::new(py);
+    dict.set_item("responsive", check.responsive)?;
+    dict.set_item("has_viewport_meta", check.has_viewport_meta)?;
+    dict.set_item("has_media_queries", check.has_media_queries)?;
+    dict.set_item("framework_hint", check.framework_hint)?;
+    Ok(dict.into())
+}
+
+/// AMP status of a page.
+///
+/// Returns a dict with keys:
+///   - "is_amp": the page is itself AMP (`amp` or `⚡` on the `<html>` tag)
+///   - "has_amp_link": the page links an AMP version (`<link rel="amphtml">`)
+///   - "amp_url": that link's href, or None
+///
+/// Loading the AMP runtime (as some third-party embeds do) doesn't make a
+/// page AMP.
+#[pyfunction]
+pub fn detect_amp(py: Python<'_>, html: &str) -> PyResult<PyObject> {
+    let check = py.allow_threads(|| amp_of(html));
+    let dict = PyDict::new(py);
+    dict.set_item("is_amp", check.is_amp)?;
+    dict.set_item("has_amp_link", check.amp_url.is_some())?;
+    dict.set_item("amp_url", check.amp_url)?;
+    Ok(dict.into())
+}
+
+/// The href of an alternate (e.g. media="only screen and (max-width: 640px)")
+/// or canonical `<link>` pointing at a separate m.<domain> mobile site, or
+/// None.
+#[pyfunction]
+pub fn detect_mdot_reference(html: &str, domain: &str) -> Option<String> {
+    mdot_reference_of(html, domain)
+}
+
+/// Progressive web app capabilities of a page.
+///
+/// Returns a dict with keys:
+///   - "has_manifest": a `<link rel="manifest">` is present
+///   - "manifest_url": its href, or None
+///   - "registers_service_worker": an inline script calls
+///     navigator.serviceWorker.register(), or a Workbox / next-pwa /
+///     vite-plugin-pwa registration script is loaded
+///   - "has_theme_color": `<meta name="theme-color">` is set
+///
+/// Commented-out registrations and scripts inside an iframe's srcdoc don't
+/// count.
+#[pyfunction]
+pub fn detect_pwa(py: Python<'_>, html: &str) -> PyResult<PyObject> {
+    let check = py.allow_threads(|| pwa_of(html));
+    let dict = PyDict::new(py);
+    dict.set_item("has_manifest", check.manifest_url.is_some())?;
+    dict.set_item("manifest_url", check.manifest_url)?;
+    dict.set_item("registers_service_worker", check.registers_service_worker)?;
+    dict.set_item("has_theme_color", check.has_theme_color)?;
+    Ok(dict.into())
+}
+
+/// Detect registrar parking pages and "domain for sale" landers.
+///
+/// Returns a dict with `parked`, `provider` (e.g. "Sedo", "GoDaddy") and a
+/// human-readable `reason` listing the signals found.
+#[pyfunction]
+pub fn detect_parked_domain(py: Python<'_>, html: &str) -> PyResult<PyObject> {
+    let check = check_parked(&PageScan::new(html));
+    let dict = PyDict::new(py);
+    dict.set_item("parked", check.parked)?;
+    dict.set_item("provider", check.provider)?;
+    dict.set_item("reason", check.reason)?;
+    Ok(dict.into())
+}
+
+/// Detect placeholder pages that stand in for a real website: builder
+/// coming-soon templates, web server default pages, maintenance mode and
+/// hosting "account suspended" pages.
+///
+/// Returns a dict with `is_placeholder`, `kind` ("coming_soon",
+/// "default_server", "maintenance", "suspended" or None) and a
+/// human-readable `signal` naming the evidence. "Coming soon" on its own is
+/// only trusted in the title or <h1> of a near-empty page, so "new location
+/// coming soon" banners don't count.
+#[pyfunction]
+pub fn detect_placeholder_page(py: Python<'_>, html: &str) -> PyResult<PyObject> {
+    let check = py.allow_threads(|| check_placeholder(html, &PageScan::new(html)));
+    let dict = PyDict::new(py);
+    dict.set_item("is_placeholder", check.kind.is_some())?;
+    dict.set_item("kind", check.kind)?;
+    dict.set_item("signal", check.signal)?;
+    Ok(dict.into())
+}
+
+/// Detect "soft 404s": error pages served with HTTP 200.
+///
+/// Returns a dict with `likely_404`, `confidence` (0.0-1.0) and `signals`
+/// (e.g. "body_class:error404", "title:page not found", "h1:oops",
+/// "thin_with_search:42", "url:404"). Phrases are only read from the title
+/// and first <h1>, never body prose, and headings about 404s ("How to fix
+/// 404 errors") are ignored. A "not found" template body class counts most;
+/// `url`, the page's final URL, adds a signal when it ends in /404 or
+/// /not-found.
+#[pyfunction]
+#[pyo3(signature = (html, url=None))]
+pub fn detect_soft_404(py: Python<'_>, html: &str, url: Option<&str>) -> PyResult<PyObject> {
+    let check = py.allow_threads(|| check_soft_404(html, url));
+    let dict = PyDict::new(py);
+    dict.set_item("likely_404", check.likely_404)?;
+    dict.set_item(
+        "confidence",
+        (f64::from(check.confidence) * 100.0).round() / 100.0,
+    )?;
+    dict.set_item("signals", check.signals)?;
+    Ok(dict.into())
+}
+
+/// The business's street address from the page text, for when there is no
+/// schema.org address.
+///
+/// Looks in <address> elements, elements whose class or id mentions
+/// address, location or contact, the footer, text after "Contact us" /
+/// "Find us" style headings, and then the rest of the page, for Australian
+/// addresses with a street number and type (or a PO Box) followed by a
+/// suburb and state. A list of suburb names is not an address.
+///
+/// Returns None when there is none, else the parse_au_address fields plus:
+///   - "po_box": True for PO Box addresses
+///   - "raw": the matched text
+///   - "source": "address_tag", "address_element", "footer",
+///     "contact_section" or "page"
+///   - "others": further distinct addresses in the same shape (without
+///     "others"), at most 10, for multi-location businesses
+#[pyfunction]
+pub fn extract_address(py: Python<'_>, html: &str) -> PyResult<Option<PyObject>> {
+    let mut addresses = py.allow_threads(|| addresses_of(html)).into_iter();
+    let Some(primary) = addresses.next() else {
+        return Ok(None);
+    };
+
+    let address_dict = |(address, raw, source): (AuAddress, String, &str)| {
+        let dict = au_address_dict(py, &address)?;
+        dict.set_item("po_box", address.is_po_box())?;
+        dict.set_item("raw", raw)?;
+        dict.set_item("source", source)?;
+        Ok::<_, PyErr>(dict)
+    };
+    let dict = address_dict(primary)?;
+    let others = PyList::empty(py);
+    for other in addresses.take(MAX_OTHER_ADDRESSES) {
+        others.append(address_dict(other)?)?;
+    }
+    dict.set_item("others", others)?;
+    Ok(Some(dict.into()))
+}
+
+/// Detect aggregator / directory listing pages from their content.
+///
+/// Returns a dict with `is_directory`, `confidence` (0.0-1.0, how much
+/// directory evidence was found) and `signals` (e.g. "listing_grid:24",
+/// "claim_listing:claim this business", "pagination:results:348"). Several
+/// independent signals are required, so multi-location business sites are
+/// not flagged.
+#[pyfunction]
+pub fn detect_directory_page(py: Python<'_>, html: &str) -> PyResult<PyObject> {
+    let check = check_directory_page(html);
+    let dict = PyDict::new(py);
+    dict.set_item("is_directory", check.is_directory)?;
+    dict.set_item("confidence", (check.confidence * 100.0).round() / 100.0)?;
+    dict.set_item("signals", check.signals)?;
+    Ok(dict.into())
+}
+
+/// The page's heading outline in document order.
+///
+/// Returns a list of {"level": 1-6, "text": str} for h1..h`max_level`, at
+/// most `limit` of them. Entities are decoded and markup inside headings
+/// stripped; empty headings are left out. Headings inside <template> are
+/// never included; with `skip_boilerplate`, neither are those in
+/// <noscript>, <nav>, <footer>, <aside> or navigation landmarks.
+#[pyfunction]
+#[pyo3(signature = (html, max_level=3, limit=100, skip_boilerplate=false))]
+pub fn extract_headings(
+    py: Python<'_>,
+    html: &str,
+    max_level: u8,
+    limit: usize,
+    skip_boilerplate: bool,
+) -> PyResult<PyObject> {
+    let headings = py.allow_threads(|| {
+        headings_in(
+            &Html::parse_document(html),
+            max_level,
+            limit,
+            skip_boilerplate,
+        )
+    });
+    let list = PyList::empty(py);
+    for (level, text) in headings {
+        let dict = PyDict::new(py);
+        dict.set_item("level", level)?;
+        dict.set_item("text", text)?;
+        list.append(dict)?;
+    }
+    Ok(list.into())
+}
+
+/// The page's visible text: script, style, noscript, template and svg
+/// contents, comments and CDATA sections removed, tags stripped, entities
+/// decoded and whitespace collapsed. With `max_len` the text is cut to at
+/// most that many characters.
+///
+/// Streams over the markup without building a DOM, so unclosed tags and
+/// inline JSON blobs cannot leak into the text.
+#[pyfunction]
+#[pyo3(signature = (html, max_len=None))]
+pub fn extract_visible_text(py: Python<'_>, html: &str, max_len: Option<usize>) -> String {
+    py.allow_threads(|| visible_text_of(html, max_len))
+}
+
+/// stream_visible_text cut to at most `max_len` chars, without trailing
+/// whitespace at the cut.
+fn visible_text_of(html: &str, max_len: Option<usize>) -> String {
+    let mut text = stream_visible_text(html);
+    if let Some((cut, _)) = max_len.and_then(|max| text.char_indices().nth(max)) {
+        text.truncate(cut);
+        text.truncate(text.trim_end().len());
+    }
+    text
+}
+
+/// Number of words in extract_visible_text's output; tokens without a
+/// letter or digit ("|", "-") don't count.
+#[pyfunction]
+pub fn visible_word_count(py: Python<'_>, html: &str) -> usize {
+    py.allow_threads(|| word_count(&stream_visible_text(html)))
+}
+
+/// How much real copy the page has, for spotting thin template sites.
+///
+/// Text inside <nav>, <header> and <footer> is left out, as are scripts
+/// and other invisible content. Returns a dict with keys:
+///   - "word_count": words of the remaining visible text
+///   - "paragraph_count": <p> elements with any words in them
+///   - "is_thin": word_count < thin_words
+///   - "bucket": "empty" (no words), "thin" (< thin_words), "rich"
+///     (> rich_words) or "moderate"
+#[pyfunction]
+#[pyo3(signature = (html, thin_words=CONTENT_THIN_WORDS, rich_words=CONTENT_RICH_WORDS))]
+pub fn analyze_content_depth(
+    py: Python<'_>,
+    html: &str,
+    thin_words: usize,
+    rich_words: usize,
+) -> PyResult<PyObject> {
+    if thin_words > rich_words {
+        return Err(PyValueError::new_err(format!(
+            "thin_words ({}) is above rich_words ({})",
+            thin_words, rich_words
+        )));
+    }
+    let depth = py.allow_threads(|| content_depth_of(html, thin_words, rich_words));
+
+    let dict = PyDict::new(py);
+    dict.set_item("word_count", depth.word_count)?;
+    dict.set_item("paragraph_count", depth.paragraph_count)?;
+    dict.set_item("is_thin", depth.word_count < thin_words)?;
+    dict.set_item("bucket", depth.bucket)?;
+    Ok(dict.into())
+}
+
+/// Guess the language of the page copy from its text, for when the html
+/// lang attribute is missing or wrong.
+///
+/// Returns a dict with keys:
+///   - "language": ISO 639-1 code ("en", "vi", "zh")
+///   - "confidence": 0.0-1.0, lower when the page mixes languages
+///
+/// or None when the text outside nav, header and footer has fewer than 40
+/// words (each Chinese, Japanese or Thai character counts as one).
+#[pyfunction]
+pub fn detect_content_language(py: Python<'_>, html: &str) -> PyResult<Option<PyObject>> {
+    let Some(language) = py.allow_threads(|| content_language_of(html)) else {
+        return Ok(None);
+    };
+    Ok(Some(content_language_dict(py, language)?))
+}
+
+/// The page's copyright notice year, a cheap sign of whether anyone still
+/// maintains the site.
+///
+/// Notices are ©, (c) or "copyright" followed by a year in the visible
+/// text; for a range ("2012–2019") or several notices the latest year wins.
+/// Years before 1990 or after next year are ignored. When the year is
+/// written by script (new Date().getFullYear() next to the notice) the
+/// result has "dynamic": True and "year" is the current year, since that is
+/// what visitors see.
+///
+/// Returns a dict with keys "year" (int), "raw" (the notice text) and
+/// "dynamic" (bool), or None when there is no dated notice.
+#[pyfunction]
+pub fn extract_copyright_year(py: Python<'_>, html: &str) -> PyResult<Option<PyObject>> {
+    let Some(copyright) = py.allow_threads(|| copyright_year_of(html)) else {
+        return Ok(None);
+    };
+    let dict = PyDict::new(py);
+    dict.set_item("year", copyright.year)?;
+    dict.set_item("raw", copyright.raw)?;
+    dict.set_item("dynamic", copyright.dynamic)?;
+    Ok(Some(dict.into()))
+}
+
+/// True when the copyright year is at least `threshold_years` behind the
+/// current year. Pages without a dated notice and script-written years are
+/// never stale.
+#[pyfunction]
+#[pyo3(signature = (html, threshold_years=2))]
+pub fn is_copyright_stale(py: Python<'_>, html: &str, threshold_years: i32) -> bool {
+    py.allow_threads(|| copyright_is_stale(html, threshold_years))
+}
+
+fn copyright_is_stale(html: &str, threshold_years: i32) -> bool {
+    copyright_year_of(html)
+        .is_some_and(|c| !c.dynamic && current_year() - c.year >= threshold_years)
+}
+
+/// Title, meta description and the other on-page SEO basics.
+///
+/// Returns a dict with keys:
+///   - "title": the document's first <title> (SVG titles don't count) | None
+///   - "title_length": length of the title in characters, 0 when missing
+///   - "meta_description": the first meta description | None
+///   - "meta_description_length": in characters, 0 when missing
+///   - "has_meta_robots_noindex": noindex (or none) in a robots or
+///     googlebot meta tag
+///   - "canonical_url": the rel=canonical href | None
+///   - "h1_count": number of non-empty <h1> headings, as extract_headings
+///     counts them
+///   - "h1_text": their texts
+///
+/// Text is entity-decoded and whitespace-collapsed.
+#[pyfunction]
+pub fn extract_meta_seo(py: Python<'_>, html: &str) -> PyResult<PyObject> {
+    let seo = py.allow_threads(|| meta_seo_of(html));
+    let title_length = seo.title.as_ref().map_or(0, |t| t.chars().count());
+    let description_length = seo
+        .meta_description
+        .as_ref()
+        .map_or(0, |d| d.chars().count());
+
+    let dict = PyDict::new(py);
+    dict.set_item("title", seo.title)?;
+    dict.set_item("title_length", title_length)?;
+    dict.set_item("meta_description", seo.meta_description)?;
+    dict.set_item("meta_description_length", description_length)?;
+    dict.set_item("has_meta_robots_noindex", seo.noindex)?;
+    dict.set_item("canonical_url", seo.canonical_url)?;
+    dict.set_item("h1_count", seo.h1_count)?;
+    dict.set_item("h1_text", seo.h1_text)?;
+    Ok(dict.into())
+}
+
+/// On-page SEO audit in one call.
+///
+/// Returns a dict with keys:
+///   - "title", "title_length", "meta_description_length": as in
+///     extract_meta_seo
+///   - "has_title", "has_meta_description", "has_canonical",
+///     "has_schema_markup" (JSON-LD or microdata), "has_viewport_meta"
+///     (width=device-width): bool
+///   - "h1_count", "image_count", "images_missing_alt" (no alt attribute;
+///     alt="" is decorative and fine): int, tracking pixels not counted
+///   - "internal_links", "external_links": <a href> counts; internal means
+///     relative or on the host of `url` (else of the canonical URL);
+///     fragment, mailto:, tel: and javascript: links are not counted
+///   - "word_count": words of visible text
+///   - "issues": list of findings, in this order. The names are stable: new
+///     checks add names to this list, existing ones are never renamed or
+///     removed:
+///       "missing_title", "title_too_short" (< 30 chars),
+///       "title_too_long" (> 60 chars), "missing_meta_description",
+///       "meta_description_too_short" (< 70 chars),
+///       "meta_description_too_long" (> 160 chars), "missing_h1",
+///       "multiple_h1", "images_missing_alt",
+///       "large_inline_image" (a base64 image over 20 KB decoded),
+///       "no_modern_image_formats", "no_responsive_images" (no srcset),
+///       "no_lazy_loading" (these three only with 5+ images),
+///       "missing_canonical",
+///       "no_schema_markup", "missing_viewport_meta",
+///       "render_blocking_scripts" (> 2 in the head),
+///       "render_blocking_styles" (> 4 in the head),
+///       "thin_content" (< 300 words), "noindex"
+#[pyfunction]
+#[pyo3(signature = (html, url=None))]
+pub fn seo_audit(py: Python<'_>, html: &str, url: Option<&str>) -> PyResult<PyObject> {
+    let audit = py.allow_threads(|| seo_audit_of(html, url));
+    let title_length = audit.meta.title.as_ref().map_or(0, |t| t.chars().count());
+    let description_length = audit
+        .meta
+        .meta_description
+        .as_ref()
+        .map_or(0, |d| d.chars().count());
+
+    let dict = PyDict::new(py);
+    dict.set_item("has_title", audit.meta.title.is_some())?;
+    dict.set_item("title", audit.meta.title)?;
+    dict.set_item("title_length", title_length)?;
+    dict.set_item(
+        "has_meta_description",
+        audit.meta.meta_description.is_some(),
+    )?;
+    dict.set_item("meta_description_length", description_length)?;
+    dict.set_item("h1_count", audit.meta.h1_count)?;
+    dict.set_item("image_count", audit.images.image_count)?;
+    dict.set_item("images_missing_alt", audit.images.missing_alt)?;
+    dict.set_item("has_canonical", audit.meta.canonical_url.is_some())?;
+    dict.set_item("has_schema_markup", audit.has_schema_markup)?;
+    dict.set_item("internal_links", audit.internal_links)?;
+    dict.set_item("external_links", audit.external_links)?;
+    dict.set_item("word_count", audit.word_count)?;
+    dict.set_item("has_viewport_meta", audit.has_viewport_meta)?;
+    dict.set_item("issues", audit.issues)?;
+    Ok(dict.into())
+}
+
+/// Image optimisation audit: `<img>` tags and their `<picture>` sources,
+/// plus background images in inline styles.
+///
+/// Returns a dict with keys:
+///   - "image_count": `<img>` tags, 1x1 tracking pixels from known tracker
+///     hosts left out (as in every count below)
+///   - "missing_alt_count": no alt attribute (alt="" is decorative)
+///   - "lazy_loaded_count": loading="lazy" or a lazy-loader's data-src
+///   - "webp_or_avif_count": a WebP/AVIF src, srcset candidate or
+///     `<picture>` source
+///   - "srcset_count": a srcset on the image or its `<picture>` sources
+///   - "inline_base64_count", "largest_inline_base64_bytes": base64 data:
+///     URIs and the decoded size of the largest
+///   - "background_image_count": background url()s in style attributes
+#[pyfunction]
+pub fn analyze_images(py: Python<'_>, html: &str) -> PyResult<PyObject> {
+    let audit = py.allow_threads(|| image_audit_in(&Html::parse_document(html)));
+    let dict = PyDict::new(py);
+    dict.set_item("image_count", audit.image_count)?;
+    dict.set_item("missing_alt_count", audit.missing_alt)?;
+    dict.set_item("lazy_loaded_count", audit.lazy_loaded)?;
+    dict.set_item("webp_or_avif_count", audit.webp_or_avif)?;
+    dict.set_item("srcset_count", audit.srcset)?;
+    dict.set_item("inline_base64_count", audit.inline_base64)?;
+    dict.set_item(
+        "largest_inline_base64_bytes",
+        audit.largest_inline_base64_bytes,
+    )?;
+    dict.set_item("background_image_count", audit.background_images)?;
+    Ok(dict.into())
+}
+
+/// Everything the page's markup says about how it is built. `final_url`
+/// is the address the page was fetched from after redirects; from it come
+/// has_ssl (https://), "final_domain" (normalize_domain of it, to spot a
+/// redirect to another domain) and "canonical_scheme_mismatch" (the
+/// canonical or og:url on the other scheme). All three are None without
+/// it. On an https page "mixed_content" holds the detect_mixed_content
+/// result; otherwise None.
+#[pyfunction]
+#[pyo3(signature = (html, final_url=None))]
+pub fn analyze_tech_stack(
+    py: Python<'_>,
+    html: &str,
+    final_url: Option<&str>,
+) -> PyResult<PyObject> {
+    let stack = py.allow_threads(|| tech_stack_of(html, final_url));
+    tech_stack_dict(py, stack)
+}
+
+/// http:// subresources on an https page, which browsers block or warn
+/// about.
+///
+/// Returns a dict with keys:
+///   - "has_mixed_content": bool
+///   - "active_count": scripts, iframes, stylesheets, plugins and CSS
+///     @imports loaded over http://
+///   - "passive_count": images, media, icons and CSS url()s
+///   - "sample_urls": up to 10 of the URLs, deduplicated
+///
+/// Links and canonical/og meta don't load anything and references inside
+/// HTML comments are skipped. Nothing is mixed when `page_is_https` is
+/// False.
+#[pyfunction]
+pub fn detect_mixed_content(py: Python<'_>, html: &str, page_is_https: bool) -> PyResult<PyObject> {
+    let mixed = if page_is_https {
+        py.allow_threads(|| mixed_content_of(html))
+    } else {
+        MixedContent::default()
+    };
+    mixed_content_dict(py, mixed)
+}
+
+/// Page bloat estimated from the HTML alone, without fetching any asset.
+///
+/// Returns a dict with keys:
+///   - "external_scripts": `<script src>` tags
+///   - "external_styles": `<link rel="stylesheet">` tags
+///   - "inline_script_bytes", "inline_style_bytes": bytes of inline
+///     `<script>` bodies, and of `<style>` bodies plus style attributes
+///   - "font_files": distinct font files preloaded or referenced from
+///     inline CSS
+///   - "third_party_hosts": registrable domains (up to 50, in page order)
+///     serving scripts, styles, fonts, images, media or frames, other than
+///     `page_domain`'s (else the canonical URL's)
+///   - "third_party_host_count": their number
+///   - "html_bytes": size of the HTML
+///
+/// HTML comments are ignored.
+#[pyfunction]
+#[pyo3(signature = (html, page_domain=None))]
+pub fn analyze_page_weight(
+    py: Python<'_>,
+    html: &str,
+    page_domain: Option<&str>,
+) -> PyResult<PyObject> {
+    let weight = py.allow_threads(|| page_weight_of(html, page_domain));
+    let dict = PyDict::new(py);
+    dict.set_item("external_scripts", weight.external_scripts)?;
+    dict.set_item("external_styles", weight.external_styles)?;
+    dict.set_item("inline_script_bytes", weight.inline_script_bytes)?;
+    dict.set_item("inline_style_bytes", weight.inline_style_bytes)?;
+    dict.set_item("font_files", weight.font_files)?;
+    dict.set_item("third_party_host_count", weight.third_party_hosts.len())?;
+    dict.set_item("third_party_hosts", weight.third_party_hosts)?;
+    dict.set_item("html_bytes", weight.html_bytes)?;
+    Ok(dict.into())
+}
+
+/// Render-blocking resources in the page's `<head>`.
+///
+/// Returns a dict with keys:
+///   - "blocking_scripts": `<script src>` without async or defer; modules
+///     and non-JavaScript types don't block
+///   - "blocking_styles": stylesheets other than media="print" or
+///     alternate ones; preload and prefetch links don't count
+///   - "has_inlined_critical_css": an inline `<style>` over 2 KB
+///   - "sample": up to 10 blocking resource URLs, in page order
+///
+/// Elements after the head are never counted. A page without a `<head>`
+/// is read the way a browser would, with anything before the first body
+/// content in an implied head.
+#[pyfunction]
+pub fn detect_render_blocking(py: Python<'_>, html: &str) -> PyResult<PyObject> {
+    let blocking = py.allow_threads(|| render_blocking_in(&Html::parse_document(html)));
+    let dict = PyDict::new(py);
+    dict.set_item("blocking_scripts", blocking.scripts)?;
+    dict.set_item("blocking_styles", blocking.styles)?;
+    dict.set_item("has_inlined_critical_css", blocking.inlined_critical_css)?;
+    dict.set_item("sample", blocking.sample)?;
+    Ok(dict.into())
+}
+
+/// tech_stack_of for each page in input order, in parallel once the batch
+/// is big enough to pay for it. `urls` must be as long as `htmls`.
+fn tech_stacks_of(htmls: &[String], urls: Option<&[Option<String>]>) -> Vec<TechStack> {
+    let analyze = |(idx, html): (usize, &String)| {
+        let url = urls.and_then(|urls| urls[idx].as_deref());
+        tech_stack_of(html, url)
+    };
+    if htmls.len() < TECH_STACK_PARALLEL_THRESHOLD {
+        htmls.iter().enumerate().map(analyze).collect()
+    } else {
+        htmls.par_iter().enumerate().map(analyze).collect()
+    }
+}
+
+/// analyze_tech_stack over many pages with the GIL released, in input
+/// order. `urls` optionally gives each page's final URL (same length, None
+/// where unknown), as final_url does for a single call.
+#[pyfunction]
+#[pyo3(signature = (htmls, urls=None))]
+pub fn analyze_tech_stack_batch(
+    py: Python<'_>,
+    htmls: Vec<String>,
+    urls: Option<Vec<Option<String>>>,
+) -> PyResult<Vec<PyObject>> {
+    if let Some(ref urls) = urls {
+        if urls.len() != htmls.len() {
+            return Err(PyValueError::new_err(format!(
+                "urls has {} entries but htmls has {}",
+                urls.len(),
+                htmls.len()
+            )));
+        }
+    }
+
+    let stacks = py.allow_threads(|| tech_stacks_of(&htmls, urls.as_deref()));
+    stacks
+        .into_iter()
+        .map(|stack| tech_stack_dict(py, stack))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn phone_labels(html: &str) -> Vec<(String, &'static str)> {
+        labelled_phones_of(html, 6)
+            .into_iter()
+            .map(|(number, label, _)| (number, label.as_str()))
+            .collect()
+    }
+
+    // Phone context labels
+
+    #[test]
+    fn specific_phone_labels_beat_generic_phone_word() {
+        assert_eq!(
+            phone_labels("<p>Mobile phone: 0412 987 654</p>"),
+            vec![("0412 987 654".to_string(), "mobile")]
+        );
+        assert_eq!(
+            phone_labels("<p>Fax number (tel): 02 9876 5432</p>"),
+            vec![("02 9876 5432".to_string(), "fax")]
+        );
+        assert_eq!(
+            phone_labels("<p>After hours phone 0412 111 222</p>"),
+            vec![("0412 111 222".to_string(), "after_hours")]
+        );
+    }
+
+    #[test]
+    fn generic_and_unlabelled_phones() {
+        assert_eq!(
+            phone_labels(
+                "<p>Phone: 02 9876 5432</p><p>Call 0412 987 654</p><p>or 03 9123 4567</p>"
+            ),
+            vec![
+                ("02 9876 5432".to_string(), "office"),
+                ("0412 987 654".to_string(), "mobile"),
+                ("03 9123 4567".to_string(), "unknown"),
+            ]
+        );
+    }
+
+    #[test]
+    fn phone_labels_in_sibling_cells() {
+        let html = "<table><tr><td>Office</td><td>(07) 3123 4567</td></tr>\
+            <tr><td>Fax</td><td>(07) 3123 4568</td></tr></table>\
+            <dl><dt>After hours</dt><dd>0413 222 333</dd></dl>";
+        let labels: Vec<&str> = phone_labels(html).into_iter().map(|(_, l)| l).collect();
+        assert_eq!(labels, vec!["office", "fax", "after_hours"]);
+    }
+
+    #[test]
+    fn mobile_number_with_mobile_context_is_never_office() {
+        for context in [
+            "Mobile phone:",
+            "Tel / mobile:",
+            "Phone (mob):",
+            "Office mobile:",
+        ] {
+            let html = format!("<p>{} 0412 987 654</p>", context);
+            assert_eq!(phone_labels(&html)[0].1, "mobile", "{}", context);
+        }
+    }
+
+    // Email de-obfuscation
+
+    #[test]
+    fn entity_encoded_emails() {
+        assert_eq!(
+            extract_emails("<p>info&#64;acmeplumbing.com.au</p>"),
+            vec!["info@acmeplumbing.com.au"]
+        );
+        assert_eq!(
+            extract_emails("<p>sales&commat;acme&period;com</p>"),
+            vec!["sales@acme.com"]
+        );
+    }
+
+    #[test]
+    fn bracket_style_emails() {
+        assert_eq!(
+            extract_emails("<p>info [at] acmeplumbing [dot] com [dot] au</p>"),
+            vec!["info@acmeplumbing.com.au"]
+        );
+        assert_eq!(
+            extract_emails("<p>jobs(at)acme(dot)com</p>"),
+            vec!["jobs@acme.com"]
+        );
+    }
+
+    #[test]
+    fn mixed_style_emails() {
+        assert_eq!(
+            extract_emails("<p>info [at] acme.com.au</p>"),
+            vec!["info@acme.com.au"]
+        );
+        assert_eq!(
+            extract_emails("<p>info AT acme.com.au</p>"),
+            vec!["info@acme.com.au"]
+        );
+        assert_eq!(
+            extract_emails("<p>office at acme [dot] com</p>"),
+            vec!["office@acme.com"]
+        );
+    }
+
+    #[test]
+    fn obfuscated_and_plain_form_found_once() {
+        let html =
+            "<p>info [at] acme [dot] com</p><a href=\"mailto:info@acme.com\">info@acme.com</a>";
+        assert_eq!(extract_emails(html), vec!["info@acme.com"]);
+    }
+
+    #[test]
+    fn prose_is_not_rebuilt_into_emails() {
+        for html in [
+            "<p>meet at home dot com</p>",
+            "<p>US AT ACME.COM</p>",
+            "<p>Call us at home.com for details</p>",
+            "<p>Find us at westfield dot com</p>",
+            "<p>We are based at acme.com.au</p>",
+        ] {
+            assert!(extract_emails(html).is_empty(), "{}", html);
+        }
+    }
+
+    // Email sources and offsets
+
+    fn email_hits(html: &str) -> Vec<(String, &'static str, usize)> {
+        collect_email_hits(html)
+            .into_iter()
+            .map(|h| (h.email, h.source.as_str(), h.offset))
+            .collect()
+    }
+
+    #[test]
+    fn mailto_address_beats_junk_json_hits() {
+        let html = concat!(
+            "<script>{\"a\":\"track1@vendor.io\",\"b\":\"track2@vendor.io\",",
+            "\"c\":\"track3@vendor.io\",\"d\":\"track4@vendor.io\",",
+            "\"e\":\"track5@vendor.io\",\"f\":\"track6@vendor.io\"}</script>",
+            "<a href=\"mailto:owner@business.com.au?subject=Quote%20request\">Email us</a>",
+        );
+        let emails = extract_emails(html);
+        assert_eq!(emails.len(), 5);
+        assert_eq!(emails[0], "owner@business.com.au");
+
+        let hits = email_hits(html);
+        assert_eq!(hits[0].1, "mailto");
+        assert!(hits[1..].iter().all(|h| h.1 == "script"));
+    }
+
+    #[test]
+    fn mailto_percent_encoding_is_decoded() {
+        assert_eq!(
+            extract_emails("<a href=\"mailto:info%40acme.com.au\">Mail</a>"),
+            vec!["info@acme.com.au"]
+        );
+    }
+
+    #[test]
+    fn email_offsets_refer_to_the_original_html() {
+        let html = "<p>caf&eacute; &amp; bar: hello@acme.com</p>";
+        let hits = email_hits(html);
+        assert_eq!(
+            hits,
+            vec![(
+                "hello@acme.com".to_string(),
+                "text",
+                html.find("hello").unwrap()
+            )]
+        );
+
+        let html = "<p>&nbsp;info&#64;acme.com</p>";
+        assert_eq!(email_hits(html)[0].2, html.find("info").unwrap());
+
+        let html = "<p>x</p><p>info [at] acme [dot] com</p>";
+        assert_eq!(email_hits(html)[0].2, html.find("info").unwrap());
+    }
+
+    #[test]
+    fn escaped_angle_bracket_is_text_not_attribute() {
+        let html = "<p>Write to &lt;info@acme.com&gt;</p>";
+        assert_eq!(
+            email_hits(html),
+            vec![(
+                "info@acme.com".to_string(),
+                "text",
+                html.find("info").unwrap()
+            )]
+        );
+        assert_eq!(
+            email_hits("<p>&lt;script&gt;sales@acme.com</p>")[0].1,
+            "text"
+        );
+        assert_eq!(
+            email_hits("<a title=\"info@acme.com\">x</a>")[0].1,
+            "attribute"
+        );
+    }
+
+    #[test]
+    fn disposable_addresses_are_not_extracted() {
+        assert_eq!(
+            extract_emails("<p>spam@abc.mailinator.com or info@acme.com.au</p>"),
+            vec!["info@acme.com.au"]
+        );
+    }
+
+    #[test]
+    fn wix_settings_json_yields_the_real_address_only() {
+        let html = concat!(
+            "<script type=\"application/json\" id=\"wix-viewer-model\">",
+            "{\"sentryDsn\":\"https://605a7baede844d278b89dc95ae0a9123@sentry-next.wixpress.com/68\",",
+            "\"errorReporter\":\"8b4f2a@o12345.ingest.sentry.io\",",
+            "\"siteSettings\":{\"contactEmail\":\"info\\u0040acmeplumbing.com.au\"}}",
+            "</script><p>Call us today</p>",
+        );
+        assert_eq!(extract_emails(html), vec!["info@acmeplumbing.com.au"]);
+        assert_eq!(email_hits(html)[0].1, "script");
+    }
+
+    #[test]
+    fn attribute_and_meta_addresses() {
+        let html = concat!(
+            "<meta name=\"contact\" content=\"sales@acme.com.au\">",
+            "<button data-email=\"info%40acme.com.au\">Email</button>",
+        );
+        let hits = email_hits(html);
+        assert_eq!(
+            hits.iter().map(|h| (h.0.as_str(), h.1)).collect::<Vec<_>>(),
+            vec![
+                ("sales@acme.com.au", "attribute"),
+                ("info@acme.com.au", "attribute")
+            ]
+        );
+    }
+
+    #[test]
+    fn visible_addresses_beat_json_blob_addresses() {
+        let html = concat!(
+            "<script type=\"application/ld+json\">{\"@type\":\"LocalBusiness\",",
+            "\"email\":\"a@acme.com.au\",\"founder\":{\"email\":\"b@acme.com.au\"}}</script>",
+            "<script type=\"application/json\">{\"c\":\"c\\u0040acme.com.au\",",
+            "\"d\":\"d@acme.com.au\",\"e\":\"e@acme.com.au\"}</script>",
+            "<footer>Email owner@acme.com.au</footer>",
+        );
+        let emails = extract_emails(html);
+        assert_eq!(emails.len(), 5);
+        assert_eq!(emails[0], "owner@acme.com.au");
+    }
+
+    // Parked domains
+
+    fn parked(html: &str) -> (bool, Option<&'static str>) {
+        let check = check_parked(&PageScan::new(html));
+        (check.parked, check.provider)
+    }
+
+    #[test]
+    fn parking_pages_need_two_signals() {
+        assert_eq!(
+            parked(concat!(
+                "<script src=\"https://img.sedoparking.com/js/ads.js\"></script>",
+                "<h1>This domain is for sale</h1>",
+            )),
+            (true, Some("Sedo"))
+        );
+        assert_eq!(
+            parked(concat!(
+                "<script src=\"//www.google.com/adsense/domains/caf.js\"></script>",
+                "<div>Related Searches</div>",
+            )),
+            (true, None)
+        );
+        assert_eq!(parked("<h1>Buy this domain</h1>"), (false, None));
+    }
+
+    #[test]
+    fn own_caf_script_is_not_a_parking_signal() {
+        let html = concat!(
+            "<script src=\"/assets/caf.js\"></script>",
+            "<p>Related searches: plumbing, gas fitting</p>",
+        );
+        assert_eq!(parked(html), (false, None));
+    }
+
+    #[test]
+    fn domain_broker_site_is_not_parked() {
+        let html = concat!(
+            "<title>Acme Domains - domain brokerage</title>",
+            "<p>We help you buy premium names. Make an offer on this domain list today.</p>",
+        );
+        assert_eq!(parked(html), (false, None));
+    }
+
+    // Directory pages
+
+    fn directory_page(html: &str) -> (bool, Vec<String>) {
+        let check = check_directory_page(html);
+        (check.is_directory, check.signals)
+    }
+
+    fn listing_page(count: usize) -> String {
+        let names = [
+            "Acme Plumbing",
+            "Bright Sparks Electrical",
+            "Cool Air Conditioning",
+            "Dan's Roofing",
+            "Eastside Painters",
+            "Fast Fix Locksmiths",
+            "Green Thumb Landscaping",
+            "Harbour Tiling",
+        ];
+        let cards: String = names
+            .iter()
+            .take(count)
+            .enumerate()
+            .map(|(i, name)| {
+                format!(
+                    "<div class=\"listing-card\"><h3>{}</h3><p>Call 07 3{:03} 45{:02}</p>\
+                     <a>Get a quote</a><a>View profile</a></div>",
+                    name,
+                    100 + i,
+                    10 + i
+                )
+            })
+            .collect();
+        format!(
+            "<html><body><h1>Plumbers in Brisbane</h1>{}\
+             <p>Showing 1-{} of 348 results</p><p>Claim this business</p></body></html>",
+            cards, count
+        )
+    }
+
+    #[test]
+    fn aggregator_listing_is_a_directory() {
+        let (is_directory, signals) = directory_page(&listing_page(8));
+        assert!(is_directory, "{:?}", signals);
+        for prefix in [
+            "listing_grid:",
+            "repeated_cta:",
+            "claim_listing:",
+            "pagination:",
+            "phones:",
+        ] {
+            assert!(
+                signals.iter().any(|s| s.starts_with(prefix)),
+                "{} in {:?}",
+                prefix,
+                signals
+            );
+        }
+    }
+
+    #[test]
+    fn itemlist_of_local_businesses_counts() {
+        let items: String = (1..=6)
+            .map(|i| {
+                format!(
+                    "{{\"@type\":\"ListItem\",\"position\":{},\"item\":{{\"@type\":\"LocalBusiness\",\"name\":\"Biz {}\"}}}},",
+                    i, i
+                )
+            })
+            .collect();
+        let html = format!(
+            "<script type=\"application/ld+json\">{{\"@type\":\"ItemList\",\"itemListElement\":[{}]}}</script>",
+            items.trim_end_matches(',')
+        );
+        assert_eq!(directory_page(&html).1, vec!["itemlist:6".to_string()]);
+    }
+
+    #[test]
+    fn multi_branch_business_is_not_a_directory() {
+        let branches: String = ["Toowong", "Chermside", "Carindale", "Indooroopilly"]
+            .iter()
+            .enumerate()
+            .map(|(i, suburb)| {
+                format!(
+                    "<div class=\"location\"><h3>Acme Plumbing {}</h3>\
+                     <p>Phone 07 3{:03} 4500</p><a>Get a quote</a></div>",
+                    suburb,
+                    200 + i
+                )
+            })
+            .collect();
+        let html = format!(
+            "<html><body><h1>Acme Plumbing</h1><p>Four branches across Brisbane.</p>{}</body></html>",
+            branches
+        );
+        let (is_directory, signals) = directory_page(&html);
+        assert!(!is_directory, "{:?}", signals);
+        assert!(signals.len() < MIN_DIRECTORY_SIGNALS);
+    }
+
+    // Non-ASCII robustness
+
+    /// Multibyte fragments placed in text, attributes, scripts, mailto and
+    /// tel: links, right against the delimiters and digits the extractors scan.
+    fn non_ascii_pages() -> Vec<String> {
+        let fragments = [
+            "🔧",
+            "👨‍👩‍👧‍👦",
+            "👍🏽",
+            "東京配管",
+            "سباكة",
+            "שרברב",
+            "\u{202E}",
+            "e\u{301}",
+            "İ",
+            "\u{200B}",
+            "４",
+        ];
+        let templates = [
+            "<title>{} Acme | Plumbing {}</title><h1>{}</h1><p>{}</p>",
+            "<p>Call 07 3{}123 4567 or +61 4{}12 345 678, 1300 {}123 456</p>",
+            "<a href=\"tel:+61{}412345678\">{}</a><a href=\"https://wa.me/61{}412345678\">WhatsApp</a>",
+            "<p>info{}@acme.com.au, jane [at] acme{} [dot] com, sales&#64;{}acme.com.au</p>",
+            "<a href=\"mailto:{}info%40acme.com.au\">{}</a><div data-email=\"{}@x.com\"></div>",
+            "<script type=\"application/json\">{\"e\":\"info\\u0040{}.com\",\"n\":\"{}\"}</script>",
+            "<address>Unit 3/45{} Smith St, Fortitude Valley QLD 4006{}</address>",
+            "<footer>© 20{}23 {} Pty Ltd. ABN 12{}345</footer>",
+            "<meta name=\"description\" content=\"{}\"><link rel=\"canonical\" href=\"https://{}.com\">",
+            "<a href=\"https://facebook.com/{}acme\">f</a><a href=\"https://g.page/{}\">g</a>",
+            "<div class=\"listing-card\"><h3>{} Acme Plumbing - Sydney</h3><a>Get a quote</a></div>",
+            "<img src=\"{}.webp\" alt=\"{}\"><script src=\"/{}/jquery-1.{}.js\"></script>",
+            "{}<{}{}>&{};&#{};",
+        ];
+        let mut pages = Vec::new();
+        for template in templates {
+            for fragment in fragments {
+                pages.push(template.replace("{}", fragment));
+            }
+            pages.push(template.replace("{}", &fragments.concat()));
+        }
+        pages.push(templates.concat().replace("{}", &fragments.concat()));
+        pages
+    }
+
+    #[test]
+    fn page_functions_never_panic_on_non_ascii() {
+        // Panics fail the test; returned Strings are valid UTF-8 by type
+        for html in non_ascii_pages() {
+            let _ = extract_emails(&html);
+            let _ = collect_email_hits(&html);
+            let _ = phone_sources_of(&html, true);
+            let _ = labelled_phones_of(&html, 5);
+            let _ = addresses_of(&html);
+            let _ = social_links_of(&html);
+            let _ = messaging_links_of(&html);
+            let _ = stream_visible_text(&html);
+            let _ = headings_in(&Html::parse_document(&html), 6, 20, true);
+            let _ = content_language_of(&html);
+            let _ = copyright_year_of(&html);
+            let _ = seo_audit_of(&html, Some("https://acme.com.au/"));
+            let _ = check_directory_page(&html);
+            let _ = check_parked(&PageScan::new(&html));
+            let _ = mdot_reference_of(&html, "acme.com.au");
+            let _ = tech_stack_of(&html, Some("https://acme.com.au/"));
+            let _ = page_weight_of(&html, Some("acme.com.au"));
+        }
+    }
+
+    // CMS detection
+
+    fn cms_ranking(html: &str) -> Vec<(String, f64)> {
+        match_cms(&PageScan::new(html))
+            .into_iter()
+            .map(|m| (m.cms.clone(), m.confidence()))
+            .collect()
+    }
+
+    #[test]
+    fn shopify_store_mentioning_wordpress_is_shopify() {
+        let html = concat!(
+            "<link rel=\"stylesheet\" href=\"//cdn.shopify.com/s/files/1/theme.css\">",
+            "<article><h2>Our WordPress migration story</h2>",
+            "<p>We moved off WordPress last year.</p></article>",
+        );
+        assert_eq!(detect_cms(html).as_deref(), Some("Shopify"));
+        let ranking = cms_ranking(html);
+        assert_eq!(ranking[0].0, "Shopify");
+        assert_eq!(ranking[1], ("WordPress".to_string(), 0.125));
+    }
+
+    #[test]
+    fn brand_word_alone_is_not_a_cms() {
+        assert_eq!(detect_cms("<p>Ask us about WordPress training.</p>"), None);
+        assert_eq!(
+            cms_ranking("<p>wordpress</p>"),
+            vec![("WordPress".to_string(), 0.125)]
+        );
+    }
+
+    #[test]
+    fn headless_wordpress_is_wordpress() {
+        let html = concat!(
+            "<script src=\"/_next/static/chunks/main.js\"></script>",
+            "<img src=\"https://cms.acme.com.au/wp-content/uploads/2024/01/hero.jpg\">",
+            "<script>fetch(\"https://cms.acme.com.au/wp-json/wp/v2/posts\")</script>",
+        );
+        assert_eq!(detect_cms(html).as_deref(), Some("WordPress"));
+        assert_eq!(cms_ranking(html), vec![("WordPress".to_string(), 1.0)]);
+    }
+
+    // CMS versions
+
+    fn cms_versions(html: &str) -> Vec<(&'static str, String, &'static str)> {
+        cms_version_candidates(html)
+            .into_iter()
+            .map(|c| (c.cms, c.version, c.source))
+            .collect()
+    }
+
+    #[test]
+    fn wordpress_generator_and_asset_versions() {
+        let html = concat!(
+            "<meta name=\"generator\" content=\"WordPress 5.2.4\" />",
+            "<script src=\"/wp-includes/js/jquery/jquery.js?ver=1.12.4\"></script>",
+        );
+        assert_eq!(
+            cms_versions(html)[0],
+            ("WordPress", "5.2.4".to_string(), "generator")
+        );
+        assert_eq!(
+            tech_stack_of(html, None).cms_version.as_deref(),
+            Some("5.2.4")
+        );
+
+        let html = concat!(
+            "<link rel=\"stylesheet\" href=\"/wp-includes/css/dist/block-library/style.min.css?ver=6.4.2\">",
+            "<script src=\"/wp-includes/js/wp-emoji-release.min.js?ver=6.4.2\"></script>",
+        );
+        assert_eq!(
+            cms_versions(html),
+            vec![("WordPress", "6.4.2".to_string(), "asset_ver")]
+        );
+    }
+
+    #[test]
+    fn conflicting_versions_prefer_the_generator_and_keep_both() {
+        let html = concat!(
+            "<meta content=\"WordPress 6.4\" name=\"generator\">",
+            "<script src=\"/wp-includes/js/wp-embed.min.js?ver=5.2\"></script>",
+        );
+        assert_eq!(
+            cms_versions(html),
+            vec![
+                ("WordPress", "6.4".to_string(), "generator"),
+                ("WordPress", "5.2".to_string(), "asset_ver"),
+            ]
+        );
+    }
+
+    #[test]
+    fn joomla_and_drupal_versions() {
+        assert_eq!(
+            cms_versions("<meta name=\"generator\" content=\"Joomla! 1.5 - Open Source Content Management\">"),
+            vec![("Joomla", "1.5".to_string(), "generator")]
+        );
+        // Joomla 3+ drops the version from the tag: no guess
+        assert!(cms_versions(
+            "<meta name=\"generator\" content=\"Joomla! - Open Source Content Management\">"
+        )
+        .is_empty());
+        assert_eq!(
+            cms_versions("<meta name=\"Generator\" content=\"Drupal 9 (https://www.drupal.org)\">")
+                [0],
+            ("Drupal", "9".to_string(), "generator")
+        );
+        assert_eq!(
+            cms_versions("<script>jQuery.extend(Drupal.settings, {\"basePath\":\"/\"});</script>"),
+            vec![("Drupal", "7".to_string(), "drupal_settings")]
+        );
+        assert_eq!(
+            cms_versions("<script data-drupal-selector=\"drupal-settings-json\">drupalSettings = {}</script>"),
+            vec![("Drupal", "8+".to_string(), "drupal_settings")]
+        );
+    }
+
+    #[test]
+    fn stripped_generator_gives_no_version() {
+        let html = "<link rel=\"stylesheet\" href=\"/wp-content/themes/astra/style.css\">";
+        assert!(cms_versions(html).is_empty());
+    }
+
+    // Runtime CMS signatures
+
+    fn strings(items: &[&str]) -> Vec<String> {
+        items.iter().map(|s| s.to_string()).collect()
+    }
+
+    fn merge_into(
+        table: &RwLock<SignatureTable>,
+        name: &str,
+        patterns: &[&str],
+        replace: bool,
+    ) -> usize {
+        let (name, patterns) = signature_entry("CMS", name, &strings(patterns)).unwrap();
+        merge_signature(table, name, patterns, replace)
+    }
+
+    fn local_cms_table() -> RwLock<SignatureTable> {
+        RwLock::new(signature_table(DEFAULT_CMS_SIGNATURES))
+    }
+
+    fn patterns_of(table: &RwLock<SignatureTable>, name: &str) -> Option<Vec<String>> {
+        table
+            .read()
+            .unwrap()
+            .iter()
+            .find(|(n, _)| n == name)
+            .map(|(_, sigs)| sigs.clone())
+    }
+
+    #[test]
+    fn registered_builder_is_detected() {
+        // The one end-to-end registration; its name and patterns are unique
+        // to this test so no other page can match them
+        let html = "<script src=\"https://assets.zorblaxsites.test/runtime.js\"></script>";
+        assert_eq!(detect_cms(html), None);
+        let (name, patterns) = signature_entry(
+            "CMS",
+            "Zorblax Sites",
+            &strings(&["assets.zorblaxsites.test", "ZorblaxSites"]),
+        )
+        .unwrap();
+        assert_eq!(merge_signature(&CMS_SIGNATURES, name, patterns, false), 2);
+        assert_eq!(detect_cms(html).as_deref(), Some("Zorblax Sites"));
+        assert_eq!(
+            tech_stack_of(html, None).cms.as_deref(),
+            Some("Zorblax Sites")
+        );
+    }
+
+    #[test]
+    fn merging_extends_an_entry_case_insensitively() {
+        let table = local_cms_table();
+        assert_eq!(
+            merge_into(&table, "Qorvex Builder", &["qorvex-cloud/"], false),
+            1
+        );
+        assert_eq!(
+            merge_into(
+                &table,
+                "qorvex builder",
+                &["qorvex-cloud/", "qorvex.app"],
+                false
+            ),
+            1
+        );
+        assert_eq!(
+            patterns_of(&table, "Qorvex Builder"),
+            Some(strings(&["qorvex-cloud/", "qorvex.app"]))
+        );
+        // A new name with no patterns adds nothing
+        assert_eq!(merge_into(&table, "Empty Builder", &[], false), 0);
+        assert_eq!(patterns_of(&table, "Empty Builder"), None);
+    }
+
+    #[test]
+    fn replacing_patterns_overrides_a_builtin() {
+        let table = local_cms_table();
+        assert_eq!(
+            merge_into(
+                &table,
+                "shopify",
+                &["cdn.shopify.com", "myshopify.com"],
+                true
+            ),
+            2
+        );
+        assert_eq!(
+            patterns_of(&table, "Shopify"),
+            Some(strings(&["cdn.shopify.com", "myshopify.com"]))
+        );
+        assert_eq!(table.read().unwrap().len(), DEFAULT_CMS_SIGNATURES.len());
+    }
+
+    #[test]
+    fn replacing_with_nothing_removes_a_cms() {
+        let table = local_cms_table();
+        assert_eq!(merge_into(&table, "Carrd", &[], true), 0);
+        assert_eq!(patterns_of(&table, "Carrd"), None);
+        assert_eq!(
+            table.read().unwrap().len(),
+            DEFAULT_CMS_SIGNATURES.len() - 1
+        );
+    }
+
+    #[test]
+    fn godaddy_builder_needs_its_own_assets() {
+        // Registrar and parking links are not GoDaddy-built sites
+        for html in [
+            "<p>Domain registered at <a href=\"https://www.godaddy.com\">godaddy.com</a></p>",
+            "<img src=\"https://img1.wsimg.com/parking-lander/static/logo.png\">",
+        ] {
+            assert_eq!(detect_cms(html), None, "{}", html);
+        }
+        assert_eq!(
+            detect_cms(
+                "<img src=\"//img1.wsimg.com/isteam/ip/5d1c9a/logo.png/:/rs=h:120\" alt=\"Acme\">"
+            )
+            .as_deref(),
+            Some("GoDaddy Website Builder")
+        );
+    }
+
+    #[test]
+    fn invalid_signature_entries_are_rejected() {
+        assert!(signature_entry("CMS", "  ", &strings(&["x"])).is_err());
+        assert!(signature_entry("CMS", "Acme", &strings(&["ok", " "])).is_err());
+        assert_eq!(
+            signature_entry("CMS", " Acme ", &strings(&["A", "a", " B "])),
+            Ok(("Acme", strings(&["a", "b"])))
+        );
+    }
+
+    // WordPress page builders
+
+    const WP_HEAD: &str =
+        "<link rel=\"stylesheet\" href=\"/wp-content/themes/astra/style.css\"><script src=\"/wp-includes/js/jquery/jquery.min.js\"></script>";
+
+    #[test]
+    fn page_builder_per_builder_markup() {
+        for (markup, builder) in [
+            (
+                "<link id=\"elementor-frontend-css\" href=\"/wp-content/plugins/elementor/assets/css/frontend.min.css\"><div data-elementor-type=\"wp-page\"></div>",
+                "Elementor",
+            ),
+            ("<div class=\"et_pb_section et_pb_section_0\"><div class=\"et_pb_row\"></div></div>", "Divi"),
+            ("<div class=\"vc_row wpb_row\"><div class=\"wpb_wrapper\"></div></div>", "WPBakery"),
+            ("<div class=\"fl-builder-content\"><div class=\"fl-row\"></div></div>", "Beaver Builder"),
+            ("<section class=\"ct-section\" id=\"section-1\"><div class=\"ct_section\"></div></section>", "Oxygen"),
+        ] {
+            let html = format!("{}{}", WP_HEAD, markup);
+            assert_eq!(detect_page_builder(&html).as_deref(), Some(builder), "{}", markup);
+            assert_eq!(tech_stack_of(&html, None).page_builder, Some(builder));
+        }
+    }
+
+    #[test]
+    fn gutenberg_needs_block_density() {
+        let blocks = "<div class=\"wp-block-group\"></div>".repeat(MIN_GUTENBERG_BLOCKS);
+        let html = format!("{}{}", WP_HEAD, blocks);
+        assert_eq!(detect_page_builder(&html).as_deref(), Some("Gutenberg"));
+
+        let html = format!("{}<div class=\"wp-block-image\"></div>", WP_HEAD);
+        assert_eq!(detect_page_builder(&html), None);
+    }
+
+    #[test]
+    fn builder_classes_off_wordpress_are_ignored() {
+        let html = "<html><body><div class=\"vc_row\"><p>Static site</p></div></body></html>";
+        assert_eq!(detect_page_builder(html), None);
+        assert_eq!(tech_stack_of(html, None).page_builder, None);
+    }
+
+    // WordPress themes
+
+    fn wp_theme(html: &str) -> Option<(String, String, bool)> {
+        wp_theme_of(html).map(|t| (t.slug.clone(), t.name(), t.is_child_theme_guess))
+    }
+
+    #[test]
+    fn single_theme() {
+        let html = concat!(
+            "<link rel=\"stylesheet\" href=\"/wp-content/themes/Avada/style.css?ver=7.11\">",
+            "<script src=\"/wp-content/themes/Avada/includes/lib/assets/min/js/general/awb.js\"></script>",
+        );
+        assert_eq!(
+            wp_theme(html),
+            Some(("avada".to_string(), "Avada".to_string(), false))
+        );
+    }
+
+    #[test]
+    fn parent_and_child_theme_assets() {
+        let html = concat!(
+            "<link rel=\"stylesheet\" href=\"/wp-content/themes/astra/assets/css/minified/main.min.css\">",
+            "<script src=\"/wp-content/themes/astra/assets/js/minified/frontend.min.js\"></script>",
+            "<link rel=\"stylesheet\" href=\"/wp-content/themes/astra-child/style.css\">",
+        );
+        assert_eq!(
+            wp_theme(html),
+            Some(("astra".to_string(), "Astra".to_string(), true))
+        );
+
+        let html = concat!(
+            "<link rel=\"stylesheet\" href=\"/wp-content/themes/twentytwelve/style.css\">",
+            "<link rel=\"stylesheet\" href=\"/wp-content/themes/acme_child/style.css\">",
+            "<img src=\"/wp-content/themes/acme_child/images/logo.png\">",
+        );
+        assert_eq!(
+            wp_theme(html),
+            Some(("acme_child".to_string(), "Acme Child".to_string(), true))
+        );
+    }
+
+    #[test]
+    fn cdn_rewritten_theme_assets() {
+        let html = concat!(
+            "<link rel=\"stylesheet\" href=\"https://cdn-abcd.kxcdn.com/wp-content/themes/generatepress/style.min.css\">",
+            "<script src=\"https://i0.wp.com/acme.com.au/wp-content/themes/generatepress/js/menu.js\"></script>",
+        );
+        assert_eq!(
+            wp_theme(html).map(|t| t.0),
+            Some("generatepress".to_string())
+        );
+        assert_eq!(
+            tech_stack_of(html, None).wp_theme.map(|t| t.slug),
+            Some("generatepress".to_string())
+        );
+    }
+
+    #[test]
+    fn no_theme_assets_no_theme() {
+        assert_eq!(
+            wp_theme("<link rel=\"stylesheet\" href=\"/assets/site.css\">"),
+            None
+        );
+        assert_eq!(wp_theme("<p>We love /wp-content/themes</p>"), None);
+    }
+
+    // WordPress plugins
+
+    #[test]
+    fn kitchen_sink_wordpress_plugins_in_page_order() {
+        let html = concat!(
+            "<!-- This site is optimized with the Yoast SEO plugin v21.5 -->",
+            "<link rel=\"stylesheet\" href=\"/wp-content/plugins/woocommerce/assets/css/woocommerce.css\">",
+            "<link rel=\"stylesheet\" href=\"/wp-content/plugins/contact-form-7/includes/css/styles.css\">",
+            "<script src=\"/wp-content/plugins/woocommerce/assets/js/frontend/cart.min.js\"></script>",
+            "<div class=\"wpforms-container\"><form class=\"wpforms-form\"></form></div>",
+            "<script src=\"https://cdn.acme.com.au/wp-content/plugins/Elementor/assets/js/frontend.min.js\"></script>",
+            "<!-- Performance optimized by WP Rocket. -->",
+        );
+        assert_eq!(
+            detect_wp_plugins(html),
+            vec![
+                "wordpress-seo",
+                "woocommerce",
+                "contact-form-7",
+                "wpforms",
+                "elementor",
+                "wp-rocket"
+            ]
+        );
+        assert_eq!(
+            tech_stack_of(html, None).wp_plugins,
+            detect_wp_plugins(html)
+        );
+    }
+
+    #[test]
+    fn non_wordpress_page_has_no_plugins() {
+        let html = "<div class=\"wpforms-container\"></div><p>We build WordPress plugins.</p>";
+        assert!(detect_wp_plugins(html).is_empty());
+        assert!(detect_wp_plugins("").is_empty());
+    }
+
+    #[test]
+    fn plugin_list_is_capped_on_huge_pages() {
+        let html: String = (0..5000)
+            .map(|i| {
+                format!(
+                    "<script src=\"/wp-content/plugins/plugin-{}/a.js\"></script>",
+                    i
+                )
+            })
+            .collect();
+        let plugins = detect_wp_plugins(&html);
+        assert_eq!(plugins.len(), MAX_WP_PLUGINS);
+        assert_eq!(plugins[0], "plugin-0");
+    }
+
+    // E-commerce
+
+    fn ecommerce(html: &str) -> (Option<&'static str>, bool) {
+        let check = check_ecommerce(html, &PageScan::new(html));
+        (check.platform, check.has_cart)
+    }
+
+    #[test]
+    fn store_platform_per_signature() {
+        for (html, platform) in [
+            (
+                "<body class=\"woocommerce-cart\"><script>var wc_add_to_cart_params = {\"ajax_url\":\"/?wc-ajax=%%endpoint%%\"};</script>",
+                "WooCommerce",
+            ),
+            ("<script src=\"//cdn.shopify.com/s/files/1/theme.js\"></script><script>Shopify.theme = {};</script>", "Shopify"),
+            ("<link href=\"https://cdn11.bigcommerce.com/s-abc123/stencil/theme.css\">", "BigCommerce"),
+            ("<script type=\"text/x-magento-init\">{}</script><div data-mage-init='{}'></div>", "Magento"),
+            ("<div class=\"sqs-add-to-cart-button\"></div><div class=\"sqs-shopping-cart\"></div>", "Squarespace Commerce"),
+            ("<script src=\"https://app.ecwid.com/script.js?123\"></script><div class=\"ecwid-productBrowser\"></div>", "Ecwid"),
+            ("<link href=\"https://cdn.neto.com.au/assets/neto-cdn/jquery.css\">", "Neto"),
+            ("<div data-hook=\"product-list\" class=\"wixstores-product\"></div>", "Wix Stores"),
+        ] {
+            assert_eq!(ecommerce(html).0, Some(platform), "{}", html);
+        }
+    }
+
+    #[test]
+    fn cart_heuristic_for_unknown_platforms() {
+        assert_eq!(
+            ecommerce("<button class=\"btn\">Add to cart</button>"),
+            (None, true)
+        );
+        assert_eq!(
+            ecommerce("<a href=\"/checkout/\">Checkout</a>"),
+            (None, true)
+        );
+        assert_eq!(
+            ecommerce("<span class=\"cart-count\">0</span>"),
+            (None, true)
+        );
+    }
+
+    #[test]
+    fn brochure_site_mentioning_shopping_is_not_a_store() {
+        let html = concat!(
+            "<link rel=\"stylesheet\" href=\"/wp-content/themes/astra/style.css\">",
+            "<h2>Shop local this Christmas</h2><p>Visit our shop on Main St. We love to shop around ",
+            "for the best parts, and our cart is always full of tools.</p>",
+            "<a href=\"/shop-local-guide\">Shop local guide</a>",
+        );
+        assert_eq!(ecommerce(html), (None, false));
+        let stack = tech_stack_of(html, None);
+        assert_eq!(stack.ecommerce.platform, None);
+        assert!(!stack.ecommerce.has_cart);
+    }
+
+    // Payment providers
+
+    #[test]
+    fn stripe_and_afterpay_storefront() {
+        let html = concat!(
+            "<script src=\"https://static.afterpay.com/afterpay-placement.js\"></script>",
+            "<p>Pay in 4 with <afterpay-placement data-amount=\"99.00\"></afterpay-placement></p>",
+            "<script src=\"https://js.stripe.com/v3/\"></script>",
+            "<script src=\"https://www.paypal.com/sdk/js?client-id=abc\"></script>",
+        );
+        assert_eq!(
+            detect_payment_providers(html),
+            vec!["Afterpay", "Stripe", "PayPal"]
+        );
+        assert_eq!(
+            tech_stack_of(html, None).payment_providers,
+            vec!["Afterpay", "Stripe", "PayPal"]
+        );
+    }
+
+    #[test]
+    fn payment_provider_per_signature() {
+        for (html, provider) in [
+            ("<script src=\"https://web.squarecdn.com/v1/square.js\"></script>", "Square"),
+            ("<script src=\"https://static.zipmoney.com.au/lib/js/zm-widget-js/dist/zip-widget.min.js\"></script>", "Zip"),
+            ("<klarna-placement data-key=\"credit-promotion-badge\"></klarna-placement>", "Klarna"),
+            ("<script src=\"https://secure.ewaypayments.com/scripts/eCrypt.min.js\"></script>", "eWAY"),
+            ("<script src=\"https://pay.connect.tyro.com/v1/tyro.js\"></script>", "Tyro"),
+        ] {
+            assert_eq!(detect_payment_providers(html), vec![provider], "{}", html);
+        }
+    }
+
+    #[test]
+    fn brand_mentions_are_not_payment_providers() {
+        let html = concat!(
+            "<article><p>Afterpay available at other stores, but we take cash, PayPal ",
+            "transfers, Stripe invoices and Zip-ties.</p></article>",
+        );
+        assert!(detect_payment_providers(html).is_empty());
+    }
+
+    // Chat widgets
+
+    fn chat(html: &str) -> (Option<&'static str>, Option<bool>) {
+        let check = check_chat_widget(html, &PageScan::new(html));
+        (check.vendor, check.has_chat)
+    }
+
+    #[test]
+    fn chat_vendor_per_signature() {
+        for (html, vendor) in [
+            ("<script>window.intercomSettings = {app_id: \"abc\"};</script><script src=\"https://widget.intercom.io/widget/abc\"></script>", "Intercom"),
+            ("<script src=\"https://js.driftt.com/include/123/abc.js\"></script>", "Drift"),
+            ("<script>var Tawk_API=Tawk_API||{};s1.src='https://embed.tawk.to/abc/default';</script>", "Tawk.to"),
+            ("<script>window.$crisp=[];window.CRISP_WEBSITE_ID=\"abc\";</script>", "Crisp"),
+            ("<script id=\"ze-snippet\" src=\"https://static.zdassets.com/ekr/snippet.js?key=abc\"></script>", "Zendesk Chat"),
+            ("<script>window.__lc = window.__lc || {}; window.__lc.license = 123;</script>", "LiveChat"),
+            ("<script src=\"//code.tidio.co/abc.js\" async></script>", "Tidio"),
+            ("<script src=\"//js.usemessages.com/conversations-embed.js\"></script>", "HubSpot Chat"),
+            ("<div class=\"fb-customerchat\" page_id=\"123\"></div>", "Facebook Customer Chat"),
+            ("<script src=\"https://connect.podium.com/widget.js#ORG_TOKEN=abc\" id=\"podium-widget\"></script>", "Podium"),
+        ] {
+            assert_eq!(chat(html), (Some(vendor), Some(true)), "{}", html);
+        }
+    }
+
+    #[test]
+    fn chat_is_tri_state() {
+        assert_eq!(chat("<div id=\"chat-launcher\"></div>"), (None, Some(true)));
+        assert_eq!(chat("<p>Call us on 07 3123 4567</p>"), (None, Some(false)));
+        assert_eq!(chat(""), (None, None));
+        assert_eq!(
+            chat("<script async src=\"https://www.googletagmanager.com/gtm.js?id=GTM-ABC123\"></script>"),
+            (None, None)
+        );
+    }
+
+    #[test]
+    fn commented_out_chat_snippet_does_not_count() {
+        let html = concat!(
+            "<p>Contact us</p>",
+            "<!-- <script src=\"https://embed.tawk.to/abc/default\"></script> -->",
+            "<!-- <div class=\"live-chat\"></div> -->",
+        );
+        assert_eq!(chat(html), (None, Some(false)));
+    }
+
+    // Marketing tools
+
+    #[test]
+    fn marketing_tool_per_signature() {
+        for (html, tool) in [
+            ("<script id=\"hs-script-loader\" src=\"//js.hs-scripts.com/123.js\"></script>", "HubSpot"),
+            ("<script>hbspt.forms.create({portalId: \"123\"});</script>", "HubSpot"),
+            ("<script>u='https://trackcmp.net/visit?actid=123'</script>", "ActiveCampaign"),
+            ("<script src=\"//static.klaviyo.com/onsite/js/klaviyo.js?company_id=ab\"></script>", "Klaviyo"),
+            ("<script>var _learnq = _learnq || [];</script>", "Klaviyo"),
+            ("<form id=\"mc-embedded-subscribe-form\" action=\"https://acme.us1.list-manage.com/subscribe/post?u=1\"></form>", "Mailchimp"),
+            ("<script src=\"https://pi.pardot.com/pd.js\"></script>", "Pardot"),
+            ("<script>Munchkin.init('123-ABC-456');</script>", "Marketo"),
+            ("<script src=\"https://omnisnippet1.com/inshop/launcher-v2.js\"></script>", "Omnisend"),
+        ] {
+            assert_eq!(detect_marketing_tools(html), vec![tool], "{}", html);
+        }
+    }
+
+    #[test]
+    fn marketing_tools_are_deduplicated_and_counted() {
+        let html = concat!(
+            "<script src=\"//js.hs-scripts.com/1.js\"></script><script>hbspt.forms.create({});</script>",
+            "<script src=\"//static.klaviyo.com/onsite/js/klaviyo.js\"></script>",
+        );
+        assert_eq!(detect_marketing_tools(html), vec!["HubSpot", "Klaviyo"]);
+        assert_eq!(tech_stack_of(html, None).marketing_tools.len(), 2);
+    }
+
+    #[test]
+    fn mailchimp_unsubscribe_link_is_not_a_tool() {
+        let html = concat!(
+            "<footer>Newsletter powered by <a href=\"https://mailchimp.com\">Mailchimp</a>. ",
+            "<a href=\"https://mailchimp.com/legal/privacy/\">Unsubscribe</a></footer>",
+        );
+        assert!(detect_marketing_tools(html).is_empty());
+    }
+
+    // Tracking
+
+    fn trackers(html: &str) -> Vec<String> {
+        let mut found: Vec<String> = tracking_of(html, &PageScan::new(html))
+            .into_iter()
+            .filter(|(_, present)| *present)
+            .map(|(tracker, _)| tracker)
+            .collect();
+        found.sort();
+        found
+    }
+
+    #[test]
+    fn tracker_per_signature() {
+        for (html, expected) in [
+            (
+                "<script src=\"https://www.googletagmanager.com/gtm.js?id=GTM-ABC123\"></script>",
+                &["google_tag_manager"][..],
+            ),
+            (
+                "<script async src=\"https://www.googletagmanager.com/gtag/js?id=G-ABCDEF1234\"></script><script>gtag('config', 'G-ABCDEF1234');</script>",
+                &["ga4", "google_analytics"][..],
+            ),
+            (
+                "<script src=\"https://www.google-analytics.com/analytics.js\"></script><script>ga('create', 'UA-12345-1', 'auto');</script>",
+                &["google_analytics", "universal_analytics"][..],
+            ),
+            ("<script>fbq('init', '123456789012345');</script>", &["facebook_pixel"][..]),
+            ("<script src=\"https://www.googleadservices.com/pagead/conversion.js\"></script>", &["google_ads"][..]),
+            ("<script>(function(h){h._hjSettings={hjid:1};})(window);</script>", &["hotjar"][..]),
+            ("<script>t.src=\"https://www.clarity.ms/tag/\"+i;</script>", &["microsoft_clarity"][..]),
+            ("<script>ttq.load('C123');</script>", &["tiktok_pixel"][..]),
+            ("<script>_linkedin_partner_id = \"123\";</script>", &["linkedin_insight"][..]),
+            ("<script src=\"https://s.pinimg.com/ct/core.js\"></script>", &["pinterest_tag"][..]),
+            ("<script>snaptr('init', 'abc');</script>", &["snapchat_pixel"][..]),
+        ] {
+            assert_eq!(trackers(html), expected, "{}", html);
+        }
+    }
+
+    #[test]
+    fn gtm_without_analytics_is_its_own_key() {
+        let html = "<noscript><iframe src=\"https://www.googletagmanager.com/ns.html?id=GTM-ABC123\"></iframe></noscript>";
+        let result = tracking_of(html, &PageScan::new(html));
+        assert!(result["google_tag_manager"]);
+        assert!(!result["google_analytics"]);
+    }
+
+    #[test]
+    fn every_key_present_and_prefix_words_do_not_match() {
+        let html = "<p>Our G-FORCE big-data aqua-blue draw-bar ua-ready gadgets</p>";
+        let result = tracking_of(html, &PageScan::new(html));
+        assert_eq!(result.len(), TRACKING_SIGNATURES.len() + 1);
+        assert!(result.values().all(|present| !present), "{:?}", result);
+
+        // Quoted class names that start like an ID prefix
+        let html = r#"<div class="g-recaptcha" data-sitekey="x"></div>
+            <div class="g-signin2"></div><span class="UA-badge">"AW-"</span>"#;
+        let result = tracking_of(html, &PageScan::new(html));
+        assert!(result.values().all(|present| !present), "{:?}", result);
+    }
+
+    // Tracking IDs
+
+    fn tracking_ids(html: &str, key: &str) -> Vec<String> {
+        tracking_ids_of(html)
+            .into_iter()
+            .find(|(k, _)| *k == key)
+            .map(|(_, ids)| ids)
+            .unwrap()
+    }
+
+    #[test]
+    fn gtag_config_blocks() {
+        let html = concat!(
+            "<script async src=\"https://www.googletagmanager.com/gtag/js?id=G-ABC1234567\"></script>",
+            "<script>gtag('js', new Date()); gtag('config', 'G-ABC1234567'); ",
+            "gtag(\"config\", \"G-XYZ9876543\"); gtag('config', 'UA-1234567-2');</script>",
+            "<p>Try our G-FORCE2000 blender</p>",
+        );
+        assert_eq!(
+            tracking_ids(html, "ga4_ids"),
+            vec!["G-ABC1234567", "G-XYZ9876543"]
+        );
+        assert_eq!(tracking_ids(html, "ua_ids"), vec!["UA-1234567-2"]);
+        assert_eq!(
+            tracking_ids(
+                "<script>ga('create', 'UA-555555-1', 'auto');</script>",
+                "ua_ids"
+            ),
+            vec!["UA-555555-1"]
+        );
+    }
+
+    #[test]
+    fn gtm_noscript_iframe_and_loader() {
+        let html = concat!(
+            "<script>(function(w,d,s,l,i){})(window,document,'script','dataLayer','GTM-K9X2PQ7');</script>",
+            "<noscript><iframe src=\"https://www.googletagmanager.com/ns.html?id=GTM-K9X2PQ7\"></iframe></noscript>",
+            "<noscript><iframe src=\"https://www.googletagmanager.com/ns.html?id=GTM-AB12\"></iframe></noscript>",
+        );
+        // GTM-AB12 is too short to be a container ID
+        assert_eq!(tracking_ids(html, "gtm_ids"), vec!["GTM-K9X2PQ7"]);
+    }
+
+    #[test]
+    fn fbq_init_snippets() {
+        let html = concat!(
+            "<script>fbq('init', '123456789012345'); fbq(\"init\", 1234567890123456);",
+            "fbq('init', '12345'); fbq('track', 'PageView');</script>",
+            "<noscript><img src=\"https://www.facebook.com/tr?id=123456789012345&ev=PageView\"></noscript>",
+        );
+        assert_eq!(
+            tracking_ids(html, "fb_pixel_ids"),
+            vec!["123456789012345", "1234567890123456"]
+        );
+    }
+
+    #[test]
+    fn tracking_id_lists_are_capped() {
+        let html: String = (0..25)
+            .map(|i| format!("<script>gtag('config', 'G-ABCDEF{:04}');</script>", i))
+            .collect();
+        let ids = tracking_ids(&html, "ga4_ids");
+        assert_eq!(ids.len(), MAX_TRACKING_IDS);
+        assert_eq!(ids[0], "G-ABCDEF0000");
+    }
+
+    // Booking systems
+
+    fn booking(html: &str) -> Option<(String, String, f64)> {
+        booking_match_of(&PageScan::new(html)).map(|m| (m.provider, m.signal, m.confidence))
+    }
+
+    #[test]
+    fn booking_provider_per_signature() {
+        for (html, provider) in [
+            ("<a href=\"https://calendly.com/acme/30min\">Book</a>", "Calendly"),
+            ("<iframe src=\"https://app.acuityscheduling.com/schedule.php?owner=1\"></iframe>", "Acuity Scheduling"),
+            ("<a href=\"https://www.fresha.com/a/acme-hair\">Book now</a>", "Fresha"),
+            ("<a href=\"https://bookings.gettimely.com/acme/book\">Book</a>", "Timely"),
+            ("<a href=\"https://healthengine.com.au/dentist/qld/acme\">Book</a>", "HealthEngine"),
+            ("<a href=\"https://www.hotdoc.com.au/medical-centres/acme\">Book</a>", "HotDoc"),
+            ("<iframe src=\"https://acme.au2.cliniko.com/bookings\"></iframe>", "Cliniko"),
+            ("<script src=\"https://widgets.mindbodyonline.com/javascripts/healcode.js\"></script>", "Mindbody"),
+            ("<a href=\"https://book.servicem8.com/request_booking?uuid=abc\">Book a job</a>", "ServiceM8"),
+            ("<script src=\"https://online-booking.housecallpro.com/script.js\"></script>", "Housecall Pro"),
+        ] {
+            let found = booking(html).unwrap();
+            assert_eq!((found.0.as_str(), found.2), (provider, BOOKING_PROVIDER_CONFIDENCE), "{}", html);
+        }
+        assert!(detect_booking_system(
+            "<a href=\"https://calendly.com/acme\">Book</a>"
+        ));
+    }
+
+    #[test]
+    fn generic_booking_buttons_are_low_confidence() {
+        assert_eq!(
+            booking("<a class=\"btn book-now\" href=\"/contact\">Book now</a>"),
+            Some((
+                "generic".to_string(),
+                "book-now".to_string(),
+                GENERIC_BOOKING_CONFIDENCE
+            ))
+        );
+        // A real provider beats the generic button
+        assert_eq!(
+            booking("<a class=\"book-now\" href=\"https://calendly.com/acme\">Book</a>")
+                .unwrap()
+                .0,
+            "Calendly"
+        );
+        assert_eq!(booking("<p>Call to book</p>"), None);
+        assert!(!detect_booking_system("<p>Call to book</p>"));
+    }
+
+    #[test]
+    fn registered_booking_provider_is_detected() {
+        let html = "<iframe src=\"https://widget.zorblaxbook.test/acme\"></iframe>";
+        assert_eq!(booking(html), None);
+        let (name, patterns) = signature_entry(
+            "Booking provider",
+            "Zorblax Book",
+            &strings(&["zorblaxbook.test"]),
+        )
+        .unwrap();
+        assert_eq!(
+            merge_signature(&BOOKING_SIGNATURES, name, patterns, false),
+            1
+        );
+        assert_eq!(
+            booking(html),
+            Some((
+                "Zorblax Book".to_string(),
+                "zorblaxbook.test".to_string(),
+                BOOKING_PROVIDER_CONFIDENCE
+            ))
+        );
+        assert_eq!(
+            tech_stack_of(html, None).booking.map(|b| b.provider),
+            Some("Zorblax Book".to_string())
+        );
+    }
+
+    // Frameworks
+
+    #[test]
+    fn framework_words_in_prose_do_not_count() {
+        // Each of these matched as a bare substring before contexts were split
+        for html in [
+            "<p>Come in and react to our new dishes!</p>",
+            "<p>Escape angular brackets in templates with &lt; and &gt;.</p>",
+            "<h2>Vue.js meetup at our cafe</h2><p>Enjoy the vue from our terrace</p>",
+            "<p>Our next static site talk and the nuxt big thing</p>",
+            "<p>We use a svelte design and alpinejs-style mountains</p>",
+            "<a href=\"/menu\" title=\"ng-app react-dom\">Menu</a>",
+        ] {
+            assert!(detect_frameworks(html).is_empty(), "{}", html);
+        }
+    }
+
+    #[test]
+    fn genuine_spa_markup() {
+        for (html, expected) in [
+            (
+                "<div id=\"root\" data-reactroot=\"\"></div><script src=\"/static/js/react-dom.production.min.js\"></script>",
+                &["React"][..],
+            ),
+            ("<div id=\"app\" data-v-7ba5bd90></div>", &["Vue.js"][..]),
+            ("<html ng-app=\"shop\"><body ng-controller=\"Main\"></body></html>", &["Angular"][..]),
+            (
+                "<script src=\"/_next/static/chunks/main-abc.js\"></script><script id=\"__NEXT_DATA__\" type=\"application/json\">{}</script>",
+                &["Next.js"][..],
+            ),
+            ("<script src=\"/_nuxt/app.js\"></script><script>window.__NUXT__={}</script>", &["Nuxt"][..]),
+            ("<link rel=\"modulepreload\" href=\"/_app/immutable/entry/start.js\"><p data-svelte-h=\"svelte-1\">Hi</p>", &["Svelte"][..]),
+            ("<div x-data=\"{ open: false }\"></div><script src=\"https://unpkg.com/alpinejs@3\"></script>", &["Alpine.js"][..]),
+            (
+                "<link href=\"/css/bootstrap.min.css\" rel=\"stylesheet\"><script src=\"/js/jquery-3.7.1.min.js\"></script>",
+                &["jQuery", "Bootstrap"][..],
+            ),
+        ] {
+            assert_eq!(detect_frameworks(html), expected, "{}", html);
+        }
+    }
+
+    // Responsive design
+
+    fn responsive(html: &str) -> (bool, bool, bool, Option<&'static str>) {
+        let check = check_responsive(html, &framework_contexts(html));
+        (
+            check.responsive,
+            check.has_viewport_meta,
+            check.has_media_queries,
+            check.framework_hint,
+        )
+    }
+
+    #[test]
+    fn mobile_site_link_is_not_responsive() {
+        let html = concat!(
+            "<html><head><link rel=\"stylesheet\" href=\"/style.css\" media=\"screen\"></head>",
+            "<body><table width=\"960\"><tr><td><a href=\"http://m.acme.com.au\">Visit our mobile site</a>",
+            "</td></tr></table></body></html>",
+        );
+        assert_eq!(responsive(html), (false, false, false, None));
+        assert!(!detect_responsive(html));
+    }
+
+    #[test]
+    fn responsive_page_without_a_framework() {
+        let html = concat!(
+            "<meta name=\"viewport\" content=\"width = device-width, initial-scale=1\">",
+            "<style>.nav{display:flex}@media (max-width: 768px){.nav{display:block}}</style>",
+        );
+        assert_eq!(responsive(html), (true, true, true, None));
+    }
+
+    #[test]
+    fn secondary_responsive_signals() {
+        assert_eq!(
+            responsive("<style>@media screen and (min-width:1024px){body{margin:0}}</style>"),
+            (true, false, true, None)
+        );
+        assert_eq!(
+            responsive("<link rel=\"stylesheet\" href=\"https://cdn.jsdelivr.net/npm/bootstrap@5.3.2/dist/css/bootstrap.min.css\">"),
+            (true, false, false, Some("Bootstrap"))
+        );
+        assert_eq!(
+            responsive("<link rel=\"stylesheet\" href=\"/css/bootstrap-2.3.2/bootstrap.min.css\">")
+                .3,
+            None
+        );
+        assert_eq!(
+            responsive("<style>@media print{body{color:#000}}</style>"),
+            (false, false, false, None)
+        );
+    }
+
+    // Signature index
+
+    /// A hundred varied pages built from the snippets the detectors look
+    /// for, so each page mixes several platforms, trackers and widgets.
+    fn fixture_pages() -> Vec<String> {
+        let parts = [
+            "<meta name=\"generator\" content=\"WordPress 6.4.2\"><link rel=\"stylesheet\" href=\"/wp-content/themes/astra/style.css?ver=4.1\">",
+            "<script src=\"/wp-includes/js/jquery/jquery.min.js?ver=3.7.1\"></script>",
+            "<div class=\"elementor-frontend\" data-elementor-type=\"wp-page\"><div class=\"et_pb_row\"></div></div>",
+            "<script src=\"/wp-content/plugins/woocommerce/assets/js/cart.js\"></script><button>Add to cart</button>",
+            "<link rel=\"stylesheet\" href=\"//cdn.shopify.com/s/files/theme.css\"><script>Shopify.theme = {};</script>",
+            "<script src=\"https://static.parastorage.com/x.js\"></script><div id=\"wix-code\"></div>",
+            "<script src=\"https://www.googletagmanager.com/gtm.js?id=GTM-K9X2PQ7\"></script>",
+            "<script>gtag('config', 'G-ABC1234567'); fbq('init', '123456789012345');</script>",
+            "<script src=\"https://widget.intercom.io/widget/abc\"></script>",
+            "<!-- <script src=\"https://embed.tawk.to/abc/default\"></script> -->",
+            "<script src=\"https://js.stripe.com/v3/\"></script><afterpay-placement></afterpay-placement>",
+            "<a href=\"https://calendly.com/acme\">Book</a><a class=\"book-now\">Book now</a>",
+            "<script src=\"//js.hs-scripts.com/1.js\"></script><form id=\"mc-embedded-subscribe-form\"></form>",
+            "<div id=\"root\" data-reactroot></div><script src=\"/_next/static/chunks/main.js\"></script>",
+            "<meta name=\"viewport\" content=\"width=device-width, initial-scale=1\"><style>@media (max-width: 600px){a{b:c}}</style>",
+            "<link href=\"/css/bootstrap.min.css\" rel=\"stylesheet\">",
+            "<h1>Acme Plumbing Brisbane</h1><p>Call 07 3123 4567 or email info@acmeplumbing.com.au</p>",
+            "<p>This domain is for sale! Buy this domain today.</p><script src=\"https://www.google.com/adsense/domains/caf.js\"></script>",
+            "<p>We moved from WordPress and Squarespace to something else. Afterpay available elsewhere.</p>",
+            "<iframe src=\"https://www.google.com/maps/embed?pb=abc\"></iframe><iframe src=\"https://www.youtube.com/embed/xyz\"></iframe>",
+            "<div class=\"wp-block-group\"></div><div class=\"wp-block-group\"></div><div class=\"wp-block-columns\"></div>",
+            "<p>İstanbul Café 👨‍👩‍👧 ÅNGSTRÖM WP-CONTENT</p>",
+        ];
+        (0..100)
+            .map(|i: usize| {
+                let body: String = parts
+                    .iter()
+                    .enumerate()
+                    .filter(|(j, _)| (i * 7 + j * 13) % 5 < 2 || (i + j).is_multiple_of(11))
+                    .map(|(_, part)| *part)
+                    .collect();
+                match i {
+                    0 => String::new(),
+                    1 => "   ".to_string(),
+                    _ => format!(
+                        "<!DOCTYPE html><html><head><title>Page {}</title></head><body>{}</body></html>",
+                        i, body
+                    ),
+                }
+            })
+            .collect()
+    }
+
+    #[test]
+    fn page_scan_matches_plain_substring_search() {
+        // The pre-index implementation: str::find / contains / matches on
+        // the lowercased page, with comment hits skipped for live lookups
+        let index = Arc::clone(&SIGNATURE_INDEX.read().unwrap());
+        let literals: Vec<&String> = index.ids.keys().collect();
+
+        let mut pages = fixture_pages();
+        let all: String = literals
+            .iter()
+            .map(|l| l.as_str())
+            .collect::<Vec<_>>()
+            .join(" ");
+        pages.push(all.clone());
+        pages.push(all.to_uppercase());
+        pages.push(format!("<!-- {} --> {}", all, literals[0]));
+        // Back-to-back and self-overlapping repeats of every literal
+        pages.push(literals.iter().map(|l| format!("{0}{0}{0}", l)).collect());
+        pages.push(
+            literals
+                .iter()
+                .map(|l| format!("{}{}", &l[..l.len() / 2], l))
+                .collect(),
+        );
+        pages.push("wp-block-wp-block-wp-block-".repeat(10));
+        pages.push(format!("<p>{}</p>", "aaaa".repeat(100)));
+
+        for page in &pages {
+            let scan = PageScan::new(page);
+            let lower = page.to_lowercase();
+            let comments = comment_ranges(&lower);
+            for literal in &literals {
+                let sig = literal.as_str();
+                assert_eq!(scan.find(sig), lower.find(sig), "find {:?}", sig);
+                assert_eq!(
+                    scan.contains(sig),
+                    lower.contains(sig),
+                    "contains {:?}",
+                    sig
+                );
+                assert_eq!(
+                    scan.count(sig),
+                    lower.matches(sig).count(),
+                    "count {:?}",
+                    sig
+                );
+                let live = lower
+                    .match_indices(sig)
+                    .map(|(pos, _)| pos)
+                    .find(|pos| !in_ranges(&comments, *pos));
+                assert_eq!(scan.find_live(sig), live, "find_live {:?}", sig);
+            }
+        }
+    }
+
+    #[test]
+    fn indexed_counts_skip_self_overlapping_repeats() {
+        // A pattern that overlaps itself must count like str::matches,
+        // which the page builder and Gutenberg scores assume
+        let index = Arc::new(signature_index_of(&strings(&["zqzq", "zqzq"])));
+        let scan = PageScan::with_index("zqzqzqzq", index);
+        assert_eq!(scan.count("zqzq"), "zqzqzqzq".matches("zqzq").count());
+        assert_eq!(scan.count("zqzq"), 2);
+        assert_eq!(scan.find("qzqz"), Some(1));
+    }
+
+    // Saved pages
+
+    /// The pages under fixtures/pages, by file name.
+    fn saved_pages() -> Vec<(String, String)> {
+        let dir = std::path::Path::new(env!("CARGO_MANIFEST_DIR")).join("fixtures/pages");
+        let mut pages: Vec<(String, String)> = std::fs::read_dir(dir)
+            .unwrap()
+            .map(|entry| entry.unwrap().path())
+            .filter(|path| path.extension().is_some_and(|ext| ext == "html"))
+            .map(|path| {
+                let name = path.file_name().unwrap().to_string_lossy().to_string();
+                (name, std::fs::read_to_string(&path).unwrap())
+            })
+            .collect();
+        pages.sort();
+        pages
+    }
+
+    /// The analyze_tech_stack keys the pre-index code returned, less
+    /// has_ssl.
+    fn pre_index_tech_stack(stack: TechStack) -> serde_json::Value {
+        serde_json::json!({
+            "cms": stack.cms,
+            "cms_version": stack.cms_version,
+            "page_builder": stack.page_builder,
+            "wp_plugins": stack.wp_plugins,
+            "payment_providers": stack.payment_providers,
+            "marketing_tool_count": stack.marketing_tools.len(),
+            "marketing_tools": stack.marketing_tools,
+            "chat_widget": stack.chat.vendor,
+            "has_chat": stack.chat.has_chat,
+            "ecommerce": stack.ecommerce.platform,
+            "has_online_store": stack.ecommerce.platform.is_some() || stack.ecommerce.has_cart,
+            "wp_theme": stack.wp_theme.map(|theme| serde_json::json!({
+                "name": theme.name(),
+                "slug": theme.slug,
+                "is_child_theme_guess": theme.is_child_theme_guess,
+            })),
+            "tracking": stack.tracking,
+            "tracking_ids": stack.tracking_ids.into_iter().collect::<HashMap<_, _>>(),
+            "has_booking": stack.booking.is_some(),
+            "booking_provider": stack.booking.map(|b| b.provider),
+            "frameworks": stack.frameworks,
+            "has_responsive": stack.has_responsive,
+            "parked": stack.parked,
+        })
+    }
+
+    #[test]
+    fn detectors_match_pre_index_outputs_on_saved_pages() {
+        // Recorded from the per-detector substring scans by
+        // fixtures/pages/generate_expected.py
+        let path =
+            std::path::Path::new(env!("CARGO_MANIFEST_DIR")).join("fixtures/pages/expected.json");
+        let expected: serde_json::Value =
+            serde_json::from_str(&std::fs::read_to_string(path).unwrap()).unwrap();
+        let pages = saved_pages();
+        assert_eq!(pages.len(), expected.as_object().unwrap().len());
+
+        for (name, html) in &pages {
+            let want = &expected[name.as_str()];
+            assert_eq!(
+                serde_json::json!(detect_cms(html)),
+                want["detect_cms"],
+                "{}",
+                name
+            );
+            assert_eq!(
+                serde_json::json!(detect_tracking(html)),
+                want["detect_tracking"],
+                "{}",
+                name
+            );
+            assert_eq!(
+                pre_index_tech_stack(tech_stack_of(html, None)),
+                want["analyze_tech_stack"],
+                "{}",
+                name
+            );
+        }
+    }
+
+    #[test]
+    fn indexed_tech_stack_matches_substring_scan() {
+        let pages = saved_pages()
+            .into_iter()
+            .map(|(_, html)| html)
+            .chain(fixture_pages());
+        for html in pages {
+            assert_eq!(
+                tech_stack_in(&html, None, &PageScan::new(&html)),
+                tech_stack_in(&html, None, &PageScan::unindexed(&html)),
+                "{}",
+                html
+            );
+        }
+    }
+
+    #[test]
+    fn tech_stack_batch_matches_per_page_analysis() {
+        let pages = fixture_pages();
+        let urls: Vec<Option<String>> = (0..pages.len())
+            .map(|i| match i % 4 {
+                0 => None,
+                1 => Some(format!("https://www.acme{}.com.au/", i)),
+                2 => Some(format!("http://acme{}.com.au/contact", i)),
+                _ => Some("not a url".to_string()),
+            })
+            .collect();
+        assert!(pages.len() >= TECH_STACK_PARALLEL_THRESHOLD);
+
+        let batch = tech_stacks_of(&pages, Some(&urls));
+        assert_eq!(batch.len(), pages.len());
+        // The fixtures cover both detected and undetected platforms
+        assert!(batch.iter().any(|stack| stack.cms.is_some()));
+        assert!(batch.iter().any(|stack| stack.cms.is_none()));
+        assert!(batch.iter().any(|stack| stack.booking.is_some()));
+        for (i, stack) in batch.iter().enumerate() {
+            assert_eq!(
+                *stack,
+                tech_stack_of(&pages[i], urls[i].as_deref()),
+                "page {}",
+                i
+            );
+        }
+
+        // Without urls, and below the parallel threshold
+        let batch = tech_stacks_of(&pages, None);
+        for (i, stack) in batch.iter().enumerate() {
+            assert_eq!(*stack, tech_stack_of(&pages[i], None), "page {}", i);
+        }
+        let small = &pages[..TECH_STACK_PARALLEL_THRESHOLD - 1];
+        let batch = tech_stacks_of(small, Some(&urls[..small.len()]));
+        for (i, stack) in batch.iter().enumerate() {
+            assert_eq!(
+                *stack,
+                tech_stack_of(&small[i], urls[i].as_deref()),
+                "page {}",
+                i
+            );
+        }
+    }
+
+    // Contacts
+
+    #[test]
+    fn contacts_match_the_single_extractors() {
+        let mut pages = fixture_pages();
+        pages.push(
+            "<a href=\"mailto:a@acme.com.au\">a</a> b@acme.com.au c@acme.com.au \
+             d@acme.com.au e@acme.com.au f@acme.com.au <a href=\"tel:0731234567\">call</a> \
+             <a href=\"https://wa.me/61412345678\">chat</a> 1300 123 456"
+                .to_string(),
+        );
+        for page in &pages {
+            let contacts = contacts_of(page);
+            assert_eq!(contacts.emails, extract_emails(page));
+            assert_eq!(contacts.phones, phones_of(page));
+            assert_eq!(contacts.social_links, social_links_of(page));
+        }
+        let contacts = contacts_of(pages.last().unwrap());
+        assert_eq!(contacts.emails.len(), 5);
+        assert_eq!(contacts.emails[0], "a@acme.com.au");
+        assert!(!contacts.phones.contains(&"0412345678".to_string()));
+    }
+
+    // Social links
+
+    fn social_picks(html: &str) -> HashMap<&'static str, Option<String>> {
+        social_links_of(html)
+            .into_iter()
+            .map(|(platform, best, _)| (platform, best))
+            .collect()
+    }
+
+    fn social_all(html: &str, platform: &str) -> Vec<String> {
+        social_links_of(html)
+            .into_iter()
+            .find(|(p, _, _)| *p == platform)
+            .map(|(_, _, all)| all)
+            .unwrap_or_default()
+    }
+
+    #[test]
+    fn share_buttons_are_not_profiles() {
+        let html = r#"<html><head><title>Acme Plumbing</title></head><body>
+            <div class="share">
+              <a href="https://www.facebook.com/sharer/sharer.php?u=https%3A%2F%2Facme.com.au">Share</a>
+              <a href="https://facebook.com/sharer.php?u=x">Share</a>
+              <a href="https://twitter.com/intent/tweet?url=x">Tweet</a>
+              <a href="https://x.com/share?url=x">Post</a>
+              <a href="https://www.linkedin.com/shareArticle?mini=true&amp;url=x">Share</a>
+              <a href="https://pinterest.com/pin/create/button/?url=x">Pin</a>
+            </div>
+            <footer><a href="https://www.facebook.com/acmeplumbing/">Facebook</a></footer>
+            </body></html>"#;
+        let picks = social_picks(html);
+        assert_eq!(
+            picks["facebook"].as_deref(),
+            Some("https://facebook.com/acmeplumbing")
+        );
+        assert_eq!(
+            social_all(html, "facebook"),
+            strings(&["https://facebook.com/acmeplumbing"])
+        );
+        for platform in [
+            "x_twitter",
+            "linkedin_company",
+            "linkedin_personal",
+            "pinterest",
+        ] {
+            assert_eq!(picks[platform], None, "{}", platform);
+        }
+    }
+
+    #[test]
+    fn instagram_embed_is_not_the_profile() {
+        let embed = r#"<blockquote class="instagram-media" data-instgrm-permalink="https://www.instagram.com/p/Cx1AbC/">
+            <a href="https://www.instagram.com/p/Cx1AbC/?utm_source=ig_embed">View this post</a>
+            <a href="https://www.instagram.com/someinfluencer/">someinfluencer</a>
+            </blockquote>"#;
+        let only_embed = format!("<body>{}</body>", embed);
+        assert_eq!(social_picks(&only_embed)["instagram"], None);
+        assert!(social_all(&only_embed, "instagram").is_empty());
+
+        // Tagged as an embed outside a blockquote, and a bare post link
+        let tagged = r#"<a href="https://instagram.com/someinfluencer?utm_source=ig_embed">x</a>
+            <a href="https://www.instagram.com/reel/Cx9/">reel</a>"#;
+        assert_eq!(social_picks(tagged)["instagram"], None);
+
+        let with_profile = format!(
+            r#"<body>{}<footer><a href="https://instagram.com/acme.plumbing?igshid=abc">Instagram</a></footer></body>"#,
+            embed
+        );
+        assert_eq!(
+            social_picks(&with_profile)["instagram"].as_deref(),
+            Some("https://instagram.com/acme.plumbing")
+        );
+    }
+
+    #[test]
+    fn social_profiles_are_canonicalised() {
+        let cases = [
+            (
+                "https://m.facebook.com/AcmePlumbing/?ref=page_internal",
+                "facebook",
+                "https://facebook.com/AcmePlumbing",
+            ),
+            (
+                "//www.facebook.com/pages/Acme-Plumbing/1234567890/about",
+                "facebook",
+                "https://facebook.com/pages/Acme-Plumbing/1234567890",
+            ),
+            (
+                "https://www.facebook.com/profile.php?id=100012345&amp;sk=about",
+                "facebook",
+                "https://facebook.com/profile.php?id=100012345",
+            ),
+            (
+                "https://au.linkedin.com/company/acme-plumbing/about/",
+                "linkedin_company",
+                "https://linkedin.com/company/acme-plumbing",
+            ),
+            (
+                "https://www.linkedin.com/in/jane-smith-123/",
+                "linkedin_personal",
+                "https://linkedin.com/in/jane-smith-123",
+            ),
+            (
+                "https://www.youtube.com/@AcmePlumbing/videos",
+                "youtube",
+                "https://youtube.com/@AcmePlumbing",
+            ),
+            (
+                "https://www.youtube.com/channel/UC123abc",
+                "youtube",
+                "https://youtube.com/channel/UC123abc",
+            ),
+            (
+                "https://www.tiktok.com/@acmeplumbing?lang=en",
+                "tiktok",
+                "https://tiktok.com/@acmeplumbing",
+            ),
+            (
+                "https://twitter.com/AcmePlumbing#top",
+                "x_twitter",
+                "https://x.com/AcmePlumbing",
+            ),
+            (
+                "https://www.pinterest.com.au/acmeplumbing/",
+                "pinterest",
+                "https://pinterest.com/acmeplumbing",
+            ),
+        ];
+        for (href, platform, expected) in cases {
+            let html = format!(r#"<a href="{}">link</a>"#, href);
+            assert_eq!(
+                social_picks(&html)[platform].as_deref(),
+                Some(expected),
+                "{}",
+                href
+            );
+        }
+
+        for href in [
+            "https://www.tiktok.com/@acmeplumbing/video/7234567890",
+            "https://www.youtube.com/watch?v=abc123",
+            "https://www.facebook.com/plugins/page.php?href=x",
+            "https://www.linkedin.com/feed/",
+            "mailto:hello@facebook.com",
+        ] {
+            let html = format!(r#"<a href="{}">link</a>"#, href);
+            assert!(
+                social_picks(&html).values().all(Option::is_none),
+                "{}",
+                href
+            );
+        }
+    }
+
+    #[test]
+    fn social_profiles_prefer_chrome_and_matching_handles() {
+        // Body link first, header link wins
+        let html = r#"<body><main><a href="https://facebook.com/someoneelse">Partner</a></main>
+            <header><a href="https://facebook.com/mainpage">Facebook</a></header></body>"#;
+        assert_eq!(
+            social_picks(html)["facebook"].as_deref(),
+            Some("https://facebook.com/mainpage")
+        );
+        assert_eq!(
+            social_all(html, "facebook"),
+            strings(&[
+                "https://facebook.com/someoneelse",
+                "https://facebook.com/mainpage"
+            ])
+        );
+
+        // A handle that resembles the domain beats a footer link
+        let html = r#"<head><link rel="canonical" href="https://www.bobsbakery.com.au/"></head>
+            <body><p>Find us <a href="https://instagram.com/bobsbakery">here</a></p>
+            <footer><a href="https://instagram.com/webdesignco">Site by</a></footer></body>"#;
+        assert_eq!(
+            social_picks(html)["instagram"].as_deref(),
+            Some("https://instagram.com/bobsbakery")
+        );
+
+        // Or the title words
+        let html = r#"<head><title>Bob Bakery | Fresh Bread</title></head>
+            <body><a href="https://instagram.com/someblogger">a</a>
+            <a href="https://instagram.com/bobbakery_official">b</a></body>"#;
+        assert_eq!(
+            social_picks(html)["instagram"].as_deref(),
+            Some("https://instagram.com/bobbakery_official")
+        );
+
+        // Repeated links beat a single mention, then the first one wins
+        let html = r#"<a href="https://x.com/first">a</a><a href="https://x.com/second">b</a>
+            <a href="https://twitter.com/second">c</a>"#;
+        assert_eq!(
+            social_picks(html)["x_twitter"].as_deref(),
+            Some("https://x.com/second")
+        );
+        let html = r#"<a href="https://x.com/first">a</a><a href="https://x.com/second">b</a>"#;
+        assert_eq!(
+            social_picks(html)["x_twitter"].as_deref(),
+            Some("https://x.com/first")
+        );
+    }
+
+    // Click-to-chat links
+
+    fn messaging(html: &str) -> HashMap<&'static str, Option<String>> {
+        messaging_links_of(html).into_iter().collect()
+    }
+
+    fn messaging_href(href: &str) -> HashMap<&'static str, Option<String>> {
+        messaging(&format!(r#"<a href="{}">Chat</a>"#, href))
+    }
+
+    #[test]
+    fn whatsapp_links_give_e164_numbers() {
+        let cases = [
+            ("https://wa.me/61412345678", "+61412345678"),
+            ("https://wa.me/0412345678?text=Hi", "+61412345678"),
+            ("https://wa.me/+61 412 345 678", "+61412345678"),
+            ("//wa.me/447911123456", "+447911123456"),
+            (
+                "https://api.whatsapp.com/send?phone=61412345678&amp;text=Hello",
+                "+61412345678",
+            ),
+            (
+                "https://api.whatsapp.com/send?text=Hi&phone=%2B61%20412%20345%20678",
+                "+61412345678",
+            ),
+            (
+                "https://api.whatsapp.com/send/?phone=%2B61298765432&type=phone_number",
+                "+61298765432",
+            ),
+            (
+                "https://web.whatsapp.com/send?phone=0412345678",
+                "+61412345678",
+            ),
+            ("whatsapp://send?phone=61412345678", "+61412345678"),
+        ];
+        for (href, expected) in cases {
+            assert_eq!(
+                messaging_href(href)["whatsapp"].as_deref(),
+                Some(expected),
+                "{}",
+                href
+            );
+        }
+    }
+
+    #[test]
+    fn sms_links_give_e164_numbers() {
+        let cases = [
+            ("sms:0412345678", "+61412345678"),
+            ("sms:+61412345678?body=Hi", "+61412345678"),
+            ("SMS://0412%20345%20678", "+61412345678"),
+            ("sms:0412-345-678;body=Quote", "+61412345678"),
+            ("sms:%2B61412345678&body=x", "+61412345678"),
+        ];
+        for (href, expected) in cases {
+            assert_eq!(
+                messaging_href(href)["sms"].as_deref(),
+                Some(expected),
+                "{}",
+                href
+            );
+        }
+    }
+
+    #[test]
+    fn messenger_and_telegram_links_give_canonical_urls() {
+        let cases = [
+            (
+                "https://m.me/acmeplumbing?ref=site",
+                "messenger",
+                "https://m.me/acmeplumbing",
+            ),
+            (
+                "https://www.messenger.com/t/acme.plumbing",
+                "messenger",
+                "https://m.me/acme.plumbing",
+            ),
+            (
+                "https://t.me/acme_plumbing",
+                "telegram",
+                "https://t.me/acme_plumbing",
+            ),
+            (
+                "https://telegram.me/s/acmenews",
+                "telegram",
+                "https://t.me/acmenews",
+            ),
+        ];
+        for (href, kind, expected) in cases {
+            assert_eq!(
+                messaging_href(href)[kind].as_deref(),
+                Some(expected),
+                "{}",
+                href
+            );
+        }
+        for href in [
+            "https://m.me/{{page}}",
+            "https://t.me/share/url?url=x",
+            "https://t.me/joinchat/AbCdEf",
+            "https://t.me/+AbCdEf",
+            "https://m.me/ab",
+        ] {
+            assert!(
+                messaging_href(href).values().all(Option::is_none),
+                "{}",
+                href
+            );
+        }
+    }
+
+    #[test]
+    fn placeholder_numbers_are_ignored_but_real_ones_kept() {
+        for raw in [
+            "0000000000",
+            "+61 000 000 000",
+            "61444444444",
+            "0123456789",
+            "1234567890",
+            "123",
+            "0412",
+            "6141234567890",
+        ] {
+            assert_eq!(phone_e164(raw), None, "{}", raw);
+        }
+        // Long runs and ascending digits inside a real number are fine
+        for (raw, expected) in [
+            ("61412345678", "+61412345678"),
+            ("0412345678", "+61412345678"),
+            ("0400000001", "+61400000001"),
+            ("+61 2 9000 0000", "+61290000000"),
+            ("0212345678", "+61212345678"),
+            ("0061412345678", "+61412345678"),
+        ] {
+            assert_eq!(phone_e164(raw).as_deref(), Some(expected), "{}", raw);
+        }
+        assert_eq!(messaging_href("https://wa.me/0000000000")["whatsapp"], None);
+        assert_eq!(messaging_href("sms:1234567890")["sms"], None);
+    }
+
+    #[test]
+    fn whatsapp_numbers_merge_into_extract_phones() {
+        let html = r#"<p>Call 07 3123 4567</p>
+            <a href="https://wa.me/61412345678">WhatsApp</a>
+            <a href="https://api.whatsapp.com/send?phone=%2B447911123456">UK</a>
+            <a href="https://wa.me/61731234567">Same office line</a>"#;
+        assert_eq!(
+            phone_sources_of(html, false),
+            vec![("07 3123 4567".to_string(), "text")]
+        );
+        assert_eq!(
+            phone_sources_of(html, true),
+            vec![
+                ("07 3123 4567".to_string(), "text"),
+                ("0412 345 678".to_string(), "whatsapp"),
+                ("+447911123456".to_string(), "whatsapp"),
+            ]
+        );
+        assert_eq!(messaging(html)["whatsapp"].as_deref(), Some("+61412345678"));
+    }
+
+    #[test]
+    fn whatsapp_placeholders_are_not_phones() {
+        let html = r#"<a href="https://wa.me/0000000000">Chat</a>
+            <a href="https://wa.me/1234567890">Chat</a>
+            <a href="https://api.whatsapp.com/send?phone=0123456789">Chat</a>
+            <a href="whatsapp://send?phone=6100000000">Chat</a>
+            <a href="https://wa.me/1234">Too short</a>
+            <a href="whatsapp://send?phone=61412345678">Real</a>"#;
+        assert_eq!(
+            phone_sources_of(html, true),
+            vec![("0412 345 678".to_string(), "whatsapp")]
+        );
+    }
+
+    // Google Business Profile links
+
+    #[test]
+    fn gbp_link_shapes() {
+        // (href, anchor text, Some(is review link) or None)
+        let cases = [
+            ("https://g.page/acme-plumbing", "", Some(false)),
+            ("https://g.page/acme-plumbing/review?rc", "", Some(true)),
+            ("https://g.page/r/CabcDEF123/review", "", Some(true)),
+            ("https://maps.app.goo.gl/AbC123xyz", "", Some(false)),
+            ("https://goo.gl/maps/AbC123", "", Some(false)),
+            ("https://g.co/kgs/AbC123", "", Some(false)),
+            (
+                "https://maps.google.com/?cid=1234567890123456789",
+                "",
+                Some(false),
+            ),
+            (
+                "https://www.google.com.au/maps?cid=1234567890123456789",
+                "",
+                Some(false),
+            ),
+            (
+                "https://business.google.com/dashboard/l/123",
+                "",
+                Some(false),
+            ),
+            (
+                "https://search.google.com/local/writereview?placeid=ChIJabc",
+                "",
+                Some(true),
+            ),
+            (
+                "https://search.google.com/local/reviews?placeid=ChIJabc",
+                "",
+                Some(false),
+            ),
+            (
+                "https://www.google.com/search?q=acme#lrd=0x6b91:0x5e,3,,,",
+                "",
+                Some(true),
+            ),
+            (
+                "https://www.google.com/search?q=acme#lrd=0x6b91:0x5e,1,,,",
+                "",
+                Some(false),
+            ),
+            (
+                "https://www.google.com/search?q=acme+plumbing",
+                "Review us on Google",
+                Some(false),
+            ),
+            (
+                "https://www.google.com/search?q=acme+plumbing",
+                "Leave a Google review",
+                Some(false),
+            ),
+            // Not profile links
+            (
+                "https://www.google.com/search?q=acme+plumbing",
+                "Search",
+                None,
+            ),
+            (
+                "https://www.google.com/maps/place/12+Smith+St,+Brisbane",
+                "Directions",
+                None,
+            ),
+            ("https://maps.google.com/maps?q=12+Smith+St", "Map", None),
+            ("https://g.page/", "", None),
+            ("https://goo.gl/AbC123", "", None),
+            ("https://g.co/doodle", "", None),
+            ("mailto:hello@g.page", "", None),
+        ];
+        for (href, text, expected) in cases {
+            assert_eq!(gbp_link_of(href, text), expected, "{} {:?}", href, text);
+        }
+    }
+
+    #[test]
+    fn gbp_links_dedupe_and_skip_map_embeds() {
+        let html = r#"<body>
+            <iframe src="https://www.google.com/maps/embed?pb=!1m18!1s0x6b91"></iframe>
+            <a href="https://www.google.com/maps/place/12+Smith+St">Get directions</a>
+            <header><a href="https://g.page/acme-plumbing">Find us</a></header>
+            <a href="https://g.page/acme-plumbing">Find us</a>
+            <a href="https://www.google.com/search?q=acme" class="btn"><span>Review us on Google</span></a>
+            </body>"#;
+        let links = gbp_links_of(html);
+        assert_eq!(
+            links.urls,
+            strings(&[
+                "https://g.page/acme-plumbing",
+                "https://www.google.com/search?q=acme"
+            ])
+        );
+        assert!(!links.review_link);
+
+        let html = r#"<a href="https://search.google.com/local/writereview?placeid=ChIJabc&amp;hl=en">Leave a review</a>"#;
+        let links = gbp_links_of(html);
+        assert_eq!(
+            links.urls,
+            strings(&["https://search.google.com/local/writereview?placeid=ChIJabc&hl=en"])
+        );
+        assert!(links.review_link);
+
+        // An embed alone is not a claimed profile
+        let html = r#"<iframe src="https://maps.google.com/maps?q=12%20Smith%20St&output=embed"></iframe>"#;
+        assert!(gbp_links_of(html).urls.is_empty());
+        assert!(!tech_stack_of(html, None).has_gbp_link);
+        assert!(tech_stack_of(r#"<a href="https://g.page/acme">x</a>"#, None).has_gbp_link);
+    }
+
+    #[test]
+    fn tech_stack_reports_schema_types() {
+        let html = r#"<script type="application/ld+json">{"@type": ["Thing", "HVACBusiness"]}</script>
+            <div itemscope itemtype="https://schema.org/FAQPage"></div>"#;
+        let stack = tech_stack_of(html, None);
+        assert_eq!(
+            stack.schema_types,
+            strings(&["Thing", "HVACBusiness", "FAQPage"])
+        );
+        assert!(stack.schema_types.iter().any(|t| is_local_business_type(t)));
+
+        let html = r#"<script type="application/ld+json">{"@type": "Organization"}</script>"#;
+        let stack = tech_stack_of(html, None);
+        assert!(!stack.schema_types.iter().any(|t| is_local_business_type(t)));
+    }
+
+    // Meta title and description
+
+    #[test]
+    fn meta_title_skips_svg_titles() {
+        let html = r#"<html><head><title>
+            Acme Plumbing &amp; Gas |   Brisbane</title></head>
+            <body><svg><title>Phone icon</title></svg><h1>Acme</h1></body></html>"#;
+        assert_eq!(
+            meta_seo_of(html).title.as_deref(),
+            Some("Acme Plumbing & Gas | Brisbane")
+        );
+
+        // A page whose only titles are SVG ones has none
+        let html = r#"<head></head><body><svg><title>Logo</title></svg></body>"#;
+        assert_eq!(meta_seo_of(html).title, None);
+        assert_eq!(meta_seo_of("<title>   </title>").title, None);
+    }
+
+    #[test]
+    fn first_meta_description_wins() {
+        let html = r#"<head>
+            <meta name="Description" content="  Licensed plumbers in   Brisbane &mdash; 24/7. ">
+            <meta name="description" content="A later, plugin-added description">
+            </head>"#;
+        let seo = meta_seo_of(html);
+        assert_eq!(
+            seo.meta_description.as_deref(),
+            Some("Licensed plumbers in Brisbane \u{2014} 24/7.")
+        );
+        // Lengths are in characters, not bytes
+        assert_eq!(seo.meta_description.unwrap().chars().count(), 37);
+
+        let html = r#"<meta name="description" content=""><meta name="description" content="x">"#;
+        assert_eq!(meta_seo_of(html).meta_description, None);
+        assert_eq!(
+            meta_seo_of(r#"<meta property="og:description" content="x">"#).meta_description,
+            None
+        );
+    }
+
+    #[test]
+    fn noindex_in_robots_or_googlebot() {
+        for tag in [
+            r#"<meta name="robots" content="noindex">"#,
+            r#"<meta name="ROBOTS" content="NOINDEX, NOFOLLOW">"#,
+            r#"<meta name="robots" content="nofollow,noindex">"#,
+            r#"<meta name="googlebot" content="noindex">"#,
+            r#"<meta name="robots" content="none">"#,
+            r#"<meta name="robots" content="index, follow"><meta name="googlebot" content="noindex">"#,
+        ] {
+            assert!(meta_seo_of(tag).noindex, "{}", tag);
+        }
+        for tag in [
+            r#"<meta name="robots" content="index, follow">"#,
+            r#"<meta name="robots" content="noimageindex">"#,
+            r#"<meta name="bingbot" content="noindex">"#,
+            r#"<meta name="description" content="noindex">"#,
+            "",
+        ] {
+            assert!(!meta_seo_of(tag).noindex, "{}", tag);
+        }
+    }
+
+    #[test]
+    fn canonical_and_h1s() {
+        let html = r#"<head><link rel="Canonical" href=" https://acme.com.au/ "></head>
+            <body><h1>Acme <em>Plumbing</em></h1><h1><img src="logo.png" alt="Acme logo"></h1>
+            <h1>  </h1><h2>Services</h2><template><h1>Hidden</h1></template></body>"#;
+        let seo = meta_seo_of(html);
+        assert_eq!(seo.canonical_url.as_deref(), Some("https://acme.com.au/"));
+        assert_eq!(seo.h1_count, 2);
+        assert_eq!(seo.h1_text, strings(&["Acme Plumbing", "Acme logo"]));
+
+        let seo = meta_seo_of("");
+        assert_eq!(seo.canonical_url, None);
+        assert_eq!(seo.h1_count, 0);
+    }
+
+    // SEO audit
+
+    fn seo_words(count: usize) -> String {
+        let words = [
+            "licensed",
+            "plumbers",
+            "serving",
+            "brisbane",
+            "homes",
+            "and",
+            "businesses",
+            "since",
+        ];
+        (0..count)
+            .map(|i| words[i % words.len()])
+            .collect::<Vec<_>>()
+            .join(" ")
+    }
+
+    fn good_seo_page() -> String {
+        format!(
+            r##"<!DOCTYPE html><html lang="en"><head>
+            <title>Acme Plumbing | Licensed Brisbane Plumbers</title>
+            <meta name="description" content="Licensed Brisbane plumbers for blocked drains, hot water and gas fitting. Same-day service across the northside.">
+            <meta name="viewport" content="width=device-width, initial-scale=1">
+            <link rel="canonical" href="https://www.acmeplumbing.com.au/">
+            <script type="application/ld+json">{{"@type": "Plumber", "name": "Acme Plumbing"}}</script>
+            <script src="/js/site.js" defer></script>
+            </head><body>
+            <header><a href="/">Home</a> <a href="/services">Services</a>
+            <a href="https://www.acmeplumbing.com.au/contact">Contact</a></header>
+            <h1>Brisbane Plumbers</h1>
+            <img src="/img/van.jpg" alt="Our van">
+            <img src="/img/divider.png" alt="">
+            <p>{}</p>
+            <a href="https://www.facebook.com/acmeplumbing">Facebook</a>
+            <a href="tel:0731234567">Call</a> <a href="#top">Top</a>
+            </body></html>"##,
+            seo_words(320)
+        )
+    }
+
+    fn bad_seo_page() -> String {
+        // A ~21 KB base64 PNG hero and four more plain JPEGs
+        let hero = format!("data:image/png;base64,{}", "A".repeat(28_000));
+        r#"<html><head>
+            <meta name="robots" content="noindex">
+            <script src="/js/jquery.js"></script><script src="/js/slider.js"></script>
+            <script src="/js/site.js"></script>
+            <link rel="stylesheet" href="/css/reset.css"><link rel="stylesheet" href="/css/grid.css">
+            <link rel="stylesheet" href="/css/slider.css"><link rel="stylesheet" href="/css/site.css">
+            <link rel="stylesheet" href="/css/theme.css">
+            </head><body>
+            <h1>Welcome</h1><h1>Our Services</h1>
+            <img src="HERO">
+            <img src="/img/hero.jpg">
+            <img src="/img/team.jpg" alt="Team"><img src="/img/van.jpg" alt="Van">
+            <img src="/img/job-1.jpg" alt="Job"><img src="/img/job-2.jpg" alt="Job">
+            <p>Coming soon. Call us!</p>
+            <a href="http://other-site.com/">Partner</a>
+            <!-- <script type="application/ld+json">{"@type": "Plumber"}</script> -->
+            </body></html>"#
+            .replace("HERO", &hero)
+    }
+
+    #[test]
+    fn seo_audit_good_page_issues() {
+        let audit = seo_audit_of(&good_seo_page(), None);
+        assert_eq!(audit.issues, Vec::<&str>::new());
+        assert_eq!(audit.meta.h1_count, 1);
+        assert_eq!((audit.images.image_count, audit.images.missing_alt), (2, 0));
+        assert!(audit.has_schema_markup && audit.has_viewport_meta);
+        // Relative and same-host links are internal; fragment and tel: are
+        // not counted
+        assert_eq!((audit.internal_links, audit.external_links), (3, 1));
+        assert!(audit.word_count >= SEO_THIN_CONTENT_WORDS);
+    }
+
+    #[test]
+    fn seo_audit_bad_page_issues() {
+        let audit = seo_audit_of(&bad_seo_page(), None);
+        assert_eq!(
+            audit.issues,
+            [
+                "missing_title",
+                "missing_meta_description",
+                "multiple_h1",
+                "images_missing_alt",
+                "large_inline_image",
+                "no_modern_image_formats",
+                "no_responsive_images",
+                "no_lazy_loading",
+                "missing_canonical",
+                "no_schema_markup",
+                "missing_viewport_meta",
+                "render_blocking_scripts",
+                "render_blocking_styles",
+                "thin_content",
+                "noindex",
+            ]
+        );
+        assert_eq!((audit.internal_links, audit.external_links), (0, 1));
+    }
+
+    #[test]
+    fn seo_audit_length_thresholds() {
+        let page = |title: &str, description: &str, body: &str| {
+            good_seo_page()
+                .replace("Acme Plumbing | Licensed Brisbane Plumbers", title)
+                .replace(
+                    "Licensed Brisbane plumbers for blocked drains, hot water and gas fitting. Same-day service across the northside.",
+                    description,
+                )
+                .replace("<h1>Brisbane Plumbers</h1>", body)
+        };
+        let issues = |html: String| seo_audit_of(&html, None).issues;
+
+        assert_eq!(
+            issues(page("Acme", "Plumbers.", "")),
+            [
+                "title_too_short",
+                "meta_description_too_short",
+                "missing_h1"
+            ]
+        );
+        assert_eq!(
+            issues(page(
+                &"Acme Plumbing ".repeat(5),
+                &"Plumbing ".repeat(20),
+                "<h1>x</h1>"
+            )),
+            ["title_too_long", "meta_description_too_long"]
+        );
+        // Boundaries are inclusive and counted in characters
+        assert_eq!(
+            issues(page(
+                &"é".repeat(SEO_TITLE_MAX),
+                &"é".repeat(SEO_DESCRIPTION_MIN),
+                "<h1>x</h1>"
+            )),
+            Vec::<&str>::new()
+        );
+
+        // Internal links are judged against `url` before the canonical
+        let audit = seo_audit_of(&good_seo_page(), Some("https://acme.example.com/"));
+        assert_eq!((audit.internal_links, audit.external_links), (2, 2));
+    }
+
+    // Heading outline
+
+    fn outline(
+        html: &str,
+        max_level: u8,
+        limit: usize,
+        skip_boilerplate: bool,
+    ) -> Vec<(u8, String)> {
+        headings_in(
+            &Html::parse_document(html),
+            max_level,
+            limit,
+            skip_boilerplate,
+        )
+    }
+
+    fn heading(level: u8, text: &str) -> (u8, String) {
+        (level, text.to_string())
+    }
+
+    #[test]
+    fn headings_strip_nested_markup_in_document_order() {
+        let html = r#"<h1 class="hero" id='top'>Acme &amp; Sons <small>Plumbing</small></h1>
+            <div><h2 data-x="1"><span class="a"><b>Blocked</b> drains</span>
+              &nbsp;<span>&amp; leaks</span></h2></div>
+            <H3>Hot   water</H3><h4>Too deep</h4>
+            <h2><a href="/"><img src="logo.png" alt="Acme logo"></a></h2>
+            <h2>   </h2><h2><br/></h2>"#;
+        assert_eq!(
+            outline(html, 3, 100, false),
+            [
+                heading(1, "Acme & Sons Plumbing"),
+                heading(2, "Blocked drains & leaks"),
+                heading(3, "Hot water"),
+                heading(2, "Acme logo"),
+            ]
+        );
+        assert_eq!(
+            outline(html, 1, 100, false),
+            [heading(1, "Acme & Sons Plumbing")]
+        );
+        assert_eq!(outline(html, 6, 2, false).len(), 2);
+        assert_eq!(outline(html, 6, 100, false)[3], heading(4, "Too deep"));
+    }
+
+    #[test]
+    fn headings_survive_malformed_markup() {
+        // Unclosed headings end at the next heading, any heading end tag
+        // closes the open one, and stray close tags and self-closing junk
+        // are ignored
+        let html = r#"<h1>Welcome<h2>Services</h3><p>text<h2/>After self-closing</h2>
+            </h1></h2><h3>Last <span>unclosed"#;
+        assert_eq!(
+            outline(html, 3, 100, false),
+            [
+                heading(1, "Welcome"),
+                heading(2, "Services"),
+                heading(2, "After self-closing"),
+                heading(3, "Last unclosed"),
+            ]
+        );
+        assert!(outline("", 3, 100, false).is_empty());
+        assert!(outline("<h1", 3, 100, false).is_empty());
+    }
+
+    #[test]
+    fn boilerplate_headings_are_skipped_on_request() {
+        let html = r#"<template><h1>Template</h1></template>
+            <nav><h2>Menu</h2></nav>
+            <noscript><h2>Enable JavaScript</h2></noscript>
+            <main><h1>Acme Plumbing</h1><aside><h3>Related</h3></aside></main>
+            <div role="navigation"><h3>Quick links</h3></div>
+            <footer><h3>Contact</h3></footer>
+            <div role="contentinfo"><h3>Legal</h3></div>"#;
+        let all: Vec<String> = outline(html, 3, 100, false)
+            .into_iter()
+            .map(|(_, t)| t)
+            .collect();
+        assert!(!all.contains(&"Template".to_string()));
+        for text in [
+            "Menu",
+            "Acme Plumbing",
+            "Related",
+            "Quick links",
+            "Contact",
+            "Legal",
+        ] {
+            assert!(all.contains(&text.to_string()), "{}", text);
+        }
+        assert_eq!(outline(html, 3, 100, true), [heading(1, "Acme Plumbing")]);
+    }
+
+    #[test]
+    fn seo_audit_counts_h1s_from_the_outline() {
+        let html = r#"<nav><h1>Logo</h1></nav><h1>Acme <em>Plumbing</em></h1>
+            <template><h1>Hidden</h1></template>"#;
+        let h1s = outline(html, 1, usize::MAX, false);
+        let audit = seo_audit_of(html, None);
+        assert_eq!(audit.meta.h1_count, h1s.len());
+        assert_eq!(audit.meta.h1_text, strings(&["Logo", "Acme Plumbing"]));
+        assert!(audit.issues.contains(&"multiple_h1"));
+    }
+
+    // Visible text
+
+    #[test]
+    fn visible_text_drops_scripts_and_json_blobs() {
+        let html = r#"<html><head><title>Acme</title>
+            <style>.x { content: "STYLE_LEAK"; }</style>
+            <script type="application/ld+json">{"@type": "Plumber", "name": "JSON_LEAK"}</script>
+            <script>window.__NEXT_DATA__ = {"props": {"page": "NEXT_LEAK </div> <p>"}};</script>
+            <script id="wix-settings" type="application/json">{"email": "WIX_LEAK"}</script>
+            </head><body>
+            <noscript>NOSCRIPT_LEAK</noscript><template><p>TEMPLATE_LEAK</p></template>
+            <svg><text>SVG_LEAK</text><script>SVG_SCRIPT_LEAK</script></svg>
+            <!-- COMMENT_LEAK <p>x</p> --><![CDATA[ CDATA_LEAK ]]>
+            <p>Blocked&nbsp;drains &amp; <b>hot</b>water</p><div>Call&#32;us</div>
+            <SCRIPT>UPPER_LEAK</SCRIPT ><p>a < b</p>
+            </body></html>"#;
+        let text = visible_text_of(html, None);
+        assert!(!text.contains("LEAK"), "{}", text);
+        assert_eq!(text, "Acme Blocked drains & hotwater Call us a < b");
+    }
+
+    #[test]
+    fn visible_text_survives_unclosed_markup() {
+        // An unclosed script swallows the rest rather than leaking into it
+        assert_eq!(
+            visible_text_of("<p>Before</p><script>var x = '<p>LEAK</p>'", None),
+            "Before"
+        );
+        assert_eq!(visible_text_of("<p>Kept <b>bold", None), "Kept bold");
+        assert_eq!(visible_text_of("<p>Text <a href=\"x", None), "Text");
+        assert_eq!(visible_text_of("<!-- never closed <p>gone</p>", None), "");
+        assert_eq!(visible_text_of("<![CDATA[ never closed", None), "");
+        assert_eq!(
+            visible_text_of("<style/><p>After self-closing</p>", None),
+            "After self-closing"
+        );
+        assert_eq!(visible_text_of("", None), "");
+        assert_eq!(visible_text_of("<", None), "<");
+    }
+
+    #[test]
+    fn visible_text_truncates_on_char_boundaries() {
+        let html = "<p>Café — ünïcode 👨‍👩‍👧 text</p>";
+        assert_eq!(visible_text_of(html, None), "Café — ünïcode 👨‍👩‍👧 text");
+        assert_eq!(visible_text_of(html, Some(4)), "Café");
+        // Trailing whitespace at the cut is dropped
+        assert_eq!(visible_text_of(html, Some(5)), "Café");
+        assert_eq!(visible_text_of(html, Some(7)), "Café —");
+        assert_eq!(visible_text_of(html, Some(0)), "");
+        assert_eq!(
+            visible_text_of(html, Some(1000)),
+            visible_text_of(html, None)
+        );
+        for max in 0..40 {
+            assert!(visible_text_of(html, Some(max)).chars().count() <= max);
+        }
+    }
+
+    #[test]
+    fn visible_word_count_skips_punctuation_tokens() {
+        let html =
+            "<nav>Home | About | Contact</nav><p>Fixing leaks - since 1999 &middot; 24/7</p>";
+        let text = stream_visible_text(html);
+        assert_eq!(word_count(&text), 8);
+        assert_eq!(word_count(""), 0);
+        assert_eq!(word_count("| - · —"), 0);
+    }
+
+    // Content depth
+
+    #[test]
+    fn heavy_nav_does_not_hide_thin_copy() {
+        let menu: String = (0..60)
+            .map(|i| {
+                format!(
+                    r#"<li><a href="/service-{0}">Emergency plumbing service {0}</a></li>"#,
+                    i
+                )
+            })
+            .collect();
+        let html = format!(
+            r#"<header><div class="cookie">We use cookies to improve your experience.</div>
+            <nav><ul>{}</ul></nav></header>
+            <main><h1>Acme Plumbing</h1><p>Family owned plumbers in Brisbane.</p>
+            <p>Call us today for a free quote.</p><p>   </p></main>
+            <footer><nav><a href="/privacy">Privacy policy</a></nav> &copy; 2024 Acme Plumbing</footer>"#,
+            menu
+        );
+        // The menu alone is well past the thin threshold
+        assert!(word_count(&stream_visible_text(&html)) > CONTENT_THIN_WORDS);
+
+        let depth = content_depth_of(&html, CONTENT_THIN_WORDS, CONTENT_RICH_WORDS);
+        assert_eq!(depth.word_count, 14);
+        assert_eq!(depth.paragraph_count, 2);
+        assert_eq!(depth.bucket, "thin");
+        assert_eq!(tech_stack_of(&html, None).content_bucket, "thin");
+    }
+
+    #[test]
+    fn content_depth_buckets_and_thresholds() {
+        let page = |words: usize| format!("<p>{}</p>", seo_words(words));
+        let bucket = |html: &str, thin, rich| content_depth_of(html, thin, rich).bucket;
+
+        assert_eq!(bucket("", CONTENT_THIN_WORDS, CONTENT_RICH_WORDS), "empty");
+        assert_eq!(
+            bucket("<nav>Only a menu</nav><p> | </p>", 250, 1500),
+            "empty"
+        );
+        assert_eq!(bucket(&page(249), 250, 1500), "thin");
+        assert_eq!(bucket(&page(250), 250, 1500), "moderate");
+        assert_eq!(bucket(&page(1500), 250, 1500), "moderate");
+        assert_eq!(bucket(&page(1501), 250, 1500), "rich");
+        // Custom thresholds
+        assert_eq!(bucket(&page(100), 50, 80), "rich");
+        assert_eq!(bucket(&page(60), 50, 80), "moderate");
+        assert_eq!(bucket(&page(10), 50, 80), "thin");
+
+        // Unclosed paragraphs run to the next <p>
+        let html = "<p>One<p>Two<p><p>Three";
+        assert_eq!(content_depth_of(html, 250, 1500).paragraph_count, 3);
+    }
+
+    // Copyright year
+
+    fn copyright(html: &str) -> Option<(i32, String, bool)> {
+        copyright_year_of(html).map(|c| (c.year, c.raw, c.dynamic))
+    }
+
+    #[test]
+    fn copyright_year_ranges_and_lists() {
+        let cases = [
+            ("<footer>© 2017 Acme Plumbing</footer>", 2017, "© 2017"),
+            (
+                "<footer>&copy; 2012–2019 Acme</footer>",
+                2019,
+                "© 2012–2019",
+            ),
+            ("<footer>(c) 2012-19 Acme</footer>", 2019, "(c) 2012-19"),
+            (
+                "<footer>Copyright 2015, 2018 &amp; 2021 Acme</footer>",
+                2021,
+                "Copyright 2015, 2018 & 2021",
+            ),
+            (
+                "<footer>© Copyright 2019 Acme Pty Ltd. All rights reserved.</footer>",
+                2019,
+                "© Copyright 2019",
+            ),
+            (
+                "<footer>COPYRIGHT &#169; ACME PLUMBING 2016</footer>",
+                2016,
+                "COPYRIGHT © ACME PLUMBING 2016",
+            ),
+            (
+                "<footer>© 2010 to 2014 Acme</footer>",
+                2014,
+                "© 2010 to 2014",
+            ),
+        ];
+        for (html, year, raw) in cases {
+            assert_eq!(
+                copyright(html),
+                Some((year, raw.to_string(), false)),
+                "{}",
+                html
+            );
+        }
+    }
+
+    #[test]
+    fn latest_of_several_notices_wins() {
+        let html = "<p>Photos © 2014 Jane Smith</p><footer>© 2018 Acme Plumbing</footer>\
+                    <p>Theme copyright 2016 ThemeCo</p>";
+        assert_eq!(copyright(html), Some((2018, "© 2018".to_string(), false)));
+
+        // Years outside 1990..=next year don't count
+        let next = current_year() + 1;
+        let html = format!(
+            "<footer>© 1985 Acme</footer><footer>© {} Acme</footer>",
+            next + 1
+        );
+        assert_eq!(copyright(&html), None);
+        let html = format!("<footer>© 1985-{} Acme</footer>", next);
+        assert_eq!(copyright(&html).map(|c| c.0), Some(next));
+        assert_eq!(copyright("<p>Established 2009. Call 2019 2020.</p>"), None);
+        // Years in script blocks are not notices
+        assert_eq!(
+            copyright("<script>var c = '© 2011';</script><p>Hi</p>"),
+            None
+        );
+    }
+
+    #[test]
+    fn script_written_years_are_dynamic() {
+        let current = current_year();
+        let cases = [
+            // Adjacent to the notice in the markup
+            "<footer>&copy; <script>document.write(new Date().getFullYear())</script> Acme</footer>",
+            // The notice has an open range the script fills in
+            "<footer>© 2015 – <span id=\"y\"></span> Acme</footer>\
+             <script>document.getElementById('y').textContent = new Date().getFullYear();</script>",
+            // Yearless notice, year set elsewhere on the page
+            "<footer>Copyright <span class=\"year\"></span> Acme.</footer>\
+             <script src=\"x.js\"></script><script>$('.year').text(new Date().getUTCFullYear());</script>",
+            // A stale-looking static year beside a script that replaces it
+            "<footer>© <span id=\"yr\">2016</span><script>yr.innerHTML=new Date().getFullYear()</script></footer>",
+        ];
+        for html in cases {
+            let (year, _, dynamic) = copyright(html).unwrap_or_else(|| panic!("{}", html));
+            assert!(dynamic, "{}", html);
+            assert_eq!(year, current, "{}", html);
+            assert!(!copyright_is_stale(html, 2), "{}", html);
+        }
+
+        // getFullYear far from a dated, closed notice leaves it static
+        let html = format!(
+            "<footer>© 2016 Acme</footer>{}<script>var age = new Date().getFullYear() - 1980;</script>",
+            "<p>filler</p>".repeat(50)
+        );
+        assert_eq!(copyright(&html), Some((2016, "© 2016".to_string(), false)));
+    }
+
+    #[test]
+    fn copyright_staleness_threshold() {
+        let current = current_year();
+        let page = |year: i32| format!("<footer>© {} Acme</footer>", year);
+        assert!(copyright_is_stale(&page(current - 2), 2));
+        assert!(!copyright_is_stale(&page(current - 1), 2));
+        assert!(!copyright_is_stale(&page(current), 2));
+        assert!(copyright_is_stale(&page(current - 1), 1));
+        assert!(copyright_is_stale(&page(current), 0));
+        assert!(!copyright_is_stale("<footer>Acme Plumbing</footer>", 2));
+        assert!(!copyright_is_stale("", 2));
+    }
+
+    // Placeholder pages
+
+    fn placeholder(html: &str) -> (Option<&'static str>, String) {
+        let check = check_placeholder(html, &PageScan::new(html));
+        (check.kind, check.signal)
+    }
+
+    #[test]
+    fn placeholder_templates() {
+        let cases = [
+            (
+                r#"<html><head><title>Welcome to nginx!</title></head><body><h1>Welcome to nginx!</h1>
+                <p>If you see this page, the nginx web server is successfully installed and working.</p></body></html>"#,
+                "default_server",
+                "nginx default page",
+            ),
+            (
+                r#"<title>Apache2 Ubuntu Default Page: It works</title><div class="page_header">Apache2 Ubuntu Default Page</div>"#,
+                "default_server",
+                "Apache default page",
+            ),
+            (
+                r#"<html><head><meta http-equiv="refresh" content="0;url=/cgi-sys/suspendedpage.cgi"></head></html>"#,
+                "suspended",
+                "cPanel suspended page",
+            ),
+            (
+                r#"<body><h1>Account Suspended</h1><p>This Account has been suspended.</p>
+                <p>Contact your hosting provider for more information.</p></body>"#,
+                "suspended",
+                "cPanel suspended page",
+            ),
+            (
+                r#"<body><h2>Looks like this domain isn&#39;t connected to a website yet!</h2></body>"#,
+                "coming_soon",
+                "Wix unconnected domain",
+            ),
+            (
+                "<body><h2>Looks Like This Domain Isn\u{2019}t Connected To A Website Yet!</h2></body>",
+                "coming_soon",
+                "Wix unconnected domain",
+            ),
+            (
+                r#"<body class="template-password"><h1>Opening soon</h1></body>"#,
+                "coming_soon",
+                "Shopify password page",
+            ),
+            (
+                r#"<body><p>Briefly unavailable for scheduled maintenance. Check back in a minute.</p></body>"#,
+                "maintenance",
+                "WordPress maintenance page",
+            ),
+        ];
+        for (html, kind, signal) in cases {
+            assert_eq!(
+                placeholder(html),
+                (Some(kind), signal.to_string()),
+                "{}",
+                html
+            );
+        }
+    }
+
+    #[test]
+    fn placeholder_phrases_need_backing() {
+        // A Wix-style coming-soon page: phrase in the title, next to no copy
+        let html = r#"<html><head><title>Acme Plumbing | Coming Soon</title></head>
+            <body><div id="SITE_CONTAINER"><h2>Something great is on its way</h2>
+            <p>Subscribe for updates</p></div></body></html>"#;
+        assert_eq!(
+            placeholder(html),
+            (
+                Some("coming_soon"),
+                "\"coming soon\" in title; 13 words on the page".to_string()
+            )
+        );
+
+        // A coming-soon plugin agrees with the h1
+        let html = format!(
+            r#"<link rel="stylesheet" href="/wp-content/plugins/coming-soon/public/css/app.css">
+            <h1>Under Maintenance</h1><p>{}</p>"#,
+            seo_words(400)
+        );
+        assert_eq!(
+            placeholder(&html),
+            (
+                Some("maintenance"),
+                "SeedProd plugin; \"under maintenance\" in h1".to_string()
+            )
+        );
+
+        // The plugin alone, left installed after launch, is not enough
+        let html = format!(
+            r#"<link rel="stylesheet" href="/wp-content/plugins/coming-soon/public/css/app.css">
+            <title>Acme Plumbing</title><h1>Brisbane plumbers</h1><p>{}</p>"#,
+            seo_words(400)
+        );
+        assert_eq!(placeholder(&html).0, None);
+    }
+
+    #[test]
+    fn coming_soon_banner_on_a_real_site_is_not_a_placeholder() {
+        let html = format!(
+            r#"<title>Acme Plumbing | Brisbane Plumbers</title>
+            <div class="banner">New Northside location coming soon!</div>
+            <h1>Brisbane's local plumbers</h1><p>{}</p>"#,
+            seo_words(300)
+        );
+        assert_eq!(
+            placeholder(&html),
+            (None, "no placeholder signals".to_string())
+        );
+
+        // The phrase in the title of a substantial page still doesn't count
+        let html = format!(
+            "<title>New Store Coming Soon | Acme</title><h1>Acme</h1><p>{}</p>",
+            seo_words(300)
+        );
+        let (kind, signal) = placeholder(&html);
+        assert_eq!(kind, None);
+        assert!(
+            signal.starts_with("\"coming soon\" in title but"),
+            "{}",
+            signal
+        );
+
+        // Template markers inside comments are ignored
+        let html = "<!-- Welcome to nginx! --><title>Acme</title><p>Real page</p>";
+        assert_eq!(placeholder(html).0, None);
+    }
+
+    // Soft 404
+
+    #[test]
+    fn wordpress_error404_body_class() {
+        let html = r#"<html><head><title>Page not found &#8211; Acme Plumbing</title></head>
+            <body class="error404 wp-custom-logo hfeed"><h1 class="page-title">Oops! That page can&rsquo;t be found.</h1>
+            <p>It looks like nothing was found at this location. Maybe try a search?</p>
+            <form role="search"><input type="search" name="s"></form></body></html>"#;
+        let check = check_soft_404(html, None);
+        assert!(check.likely_404);
+        assert_eq!(check.confidence, 1.0);
+        assert_eq!(
+            check.signals,
+            strings(&[
+                "body_class:error404",
+                "title:not found",
+                "h1:can’t be found",
+                "thin_with_search:24"
+            ])
+        );
+
+        // The body class alone is enough
+        let check = check_soft_404(r#"<body class="page template-404"><p>Hi</p></body>"#, None);
+        assert!(check.likely_404);
+        assert_eq!(check.signals, strings(&["body_class:template-404"]));
+    }
+
+    #[test]
+    fn custom_404_pages() {
+        let html = r#"<title>404 | Acme</title><h1>Sorry, this page doesn't exist</h1>"#;
+        let check = check_soft_404(html, None);
+        assert!(check.likely_404);
+        assert_eq!(check.signals, strings(&["title:404", "h1:doesn't exist"]));
+        assert_eq!(check.confidence, 0.8);
+
+        // One strong heading plus a redirect to /404
+        let html = "<title>Acme Plumbing</title><h1>Page not found</h1>";
+        assert!(!check_soft_404(html, None).likely_404);
+        let check = check_soft_404(html, Some("https://acme.com.au/404/"));
+        assert!(check.likely_404);
+        assert_eq!(check.signals, strings(&["h1:not found", "url:404"]));
+
+        // "Whoops" is weak: it needs company
+        let html = r#"<title>Whoops!</title><h1>Uh-oh</h1><input type="search">"#;
+        let check = check_soft_404(html, None);
+        assert!(check.likely_404);
+        assert_eq!(
+            check.signals,
+            strings(&["title:whoops", "h1:uh-oh", "thin_with_search:2"])
+        );
+        assert!(!check_soft_404("<h1>Whoops, we're closed today</h1>", None).likely_404);
+    }
+
+    #[test]
+    fn blog_posts_about_404s_are_not_404s() {
+        let html = format!(
+            r#"<title>How to Fix 404 Not Found Errors in WordPress</title>
+            <body class="post-template-default single"><h1>How to fix 404 errors</h1>
+            <p>A 404 page not found error happens when... {}</p>
+            <form role="search"><input name="s"></form></body>"#,
+            seo_words(400)
+        );
+        let check = check_soft_404(&html, Some("https://blog.example.com/fixing-404-errors/"));
+        assert!(!check.likely_404);
+        assert!(check.signals.is_empty(), "{:?}", check.signals);
+        assert_eq!(check.confidence, 0.0);
+
+        // Long headings and prose mentions don't count either
+        let html = "<h1>Our team found the perfect solution for every home in Brisbane, \
+                    even when others said it could not be found anywhere</h1><p>404 not found</p>";
+        assert!(check_soft_404(html, None).signals.is_empty());
+        assert!(!check_soft_404("", None).likely_404);
+    }
+
+    // Addresses
+
+    /// (key, raw, source) for each address found, key as
+    /// unit|number|name|type|suburb|state|postcode.
+    fn addresses(html: &str) -> Vec<(String, String, &'static str)> {
+        addresses_of(html)
+            .into_iter()
+            .map(|(address, raw, source)| (address.key(), raw, source))
+            .collect()
+    }
+
+    #[test]
+    fn footer_address_split_across_spans() {
+        let html = r#"<main><p>Quality plumbing since 1998.</p></main>
+            <footer><div class="col">
+              <span>Unit 4/</span><span>27 Enterprise Drive</span>,
+              <span>Rowville</span> <span>VIC</span> <span>3178</span>
+            </div></footer>"#;
+        let found = addresses(html);
+        assert_eq!(found.len(), 1);
+        assert_eq!(found[0].0, "4|27|enterprise|dr|rowville|vic|3178");
+        assert_eq!(found[0].2, "footer");
+    }
+
+    #[test]
+    fn address_sources_in_priority_order() {
+        let html = r#"<body>
+            <p>Visit 1 George Street, Sydney NSW 2000 or call.</p>
+            <h2>Contact us</h2><p>Head office: 88 Smith Road, Springvale VIC 3171</p>
+            <div class="site-location">12 Beach Parade, Surfers Paradise QLD 4217</div>
+            <address>5 Main St, Ballarat VIC 3350</address>
+            <footer>1 George Street, Sydney NSW 2000</footer>
+            </body>"#;
+        let found = addresses(html);
+        let sources: Vec<_> = found
+            .iter()
+            .map(|(key, _, source)| (key.as_str(), *source))
+            .collect();
+        assert_eq!(
+            sources,
+            [
+                ("|5|main|st|ballarat|vic|3350", "address_tag"),
+                ("|12|beach|pde|surfers paradise|qld|4217", "address_element"),
+                ("|1|george|st|sydney|nsw|2000", "footer"),
+                ("|88|smith|rd|springvale|vic|3171", "contact_section"),
+            ]
+        );
+        assert_eq!(found[0].1, "5 Main St, Ballarat VIC 3350");
+    }
+
+    #[test]
+    fn po_boxes_are_found_and_flagged() {
+        let html = "<footer>Postal: PO Box 123, Toowong QLD 4066</footer>";
+        let found = addresses_of(html);
+        assert_eq!(found.len(), 1);
+        assert!(found[0].0.is_po_box());
+        assert_eq!(
+            found[0].0.key(),
+            "||PO Box 123||toowong|qld|4066".to_lowercase()
+        );
+
+        let html =
+            "<footer>P.O. Box 9, Fremantle WA 6959 | 3 Pakenham Street, Fremantle WA 6160</footer>";
+        let found = addresses_of(html);
+        assert_eq!(
+            found
+                .iter()
+                .map(|(address, _, _)| address.is_po_box())
+                .collect::<Vec<_>>(),
+            [true, false]
+        );
+    }
+
+    #[test]
+    fn service_area_suburb_lists_are_not_addresses() {
+        let html = r#"<h2>Areas we service</h2>
+            <p>Parramatta, Penrith, Blacktown, Castle Hill, Baulkham Hills, Bondi,
+            Manly, Chatswood NSW 2067, Ryde NSW, Hornsby, Sydney NSW 2000</p>
+            <ul><li>North Sydney NSW</li><li>Surry Hills NSW 2010</li></ul>"#;
+        assert!(addresses(html).is_empty());
+    }
+
+    #[test]
+    fn multi_location_addresses_dedupe() {
+        let html = format!(
+            "<footer>{}</footer><p>Our main shop: 1 Shop Street, Geelong VIC 3220</p>",
+            (1..=12)
+                .map(|n| format!("<p>{n} Shop Street, Geelong VIC 3220</p>"))
+                .collect::<String>()
+        );
+        let found = addresses(&html);
+        assert_eq!(found.len(), 12);
+        assert_eq!(found[0].0, "|1|shop|st|geelong|vic|3220");
+        assert!(found.iter().all(|(_, _, source)| *source == "footer"));
+    }
+
+    // Embedded maps
+
+    fn map_embeds(html: &str) -> (Option<&'static str>, usize, bool) {
+        let embeds = map_embeds_of(html);
+        (embeds.provider, embeds.count, embeds.is_static)
+    }
+
+    #[test]
+    fn map_embeds_by_provider() {
+        let cases = [
+            (
+                r#"<iframe src="https://www.google.com/maps/embed?pb=!1m18!1m12" width="600"></iframe>"#,
+                (Some("google"), 1, false),
+            ),
+            (
+                r#"<iframe data-src="https://maps.google.com.au/maps?q=acme&amp;output=embed"></iframe>"#,
+                (Some("google"), 1, false),
+            ),
+            (
+                r#"<script async src="https://maps.googleapis.com/maps/api/js?key=KEY&callback=initMap"></script>"#,
+                (Some("google"), 1, false),
+            ),
+            (
+                r#"<link href="https://api.mapbox.com/mapbox-gl-js/v3.1.0/mapbox-gl.css" rel="stylesheet">
+                   <script src="https://api.mapbox.com/mapbox-gl-js/v3.1.0/mapbox-gl.js"></script>"#,
+                (Some("mapbox"), 1, false),
+            ),
+            (
+                r#"<iframe src="https://api.mapbox.com/styles/v1/acme/ckx/draft.html?title=false"></iframe>"#,
+                (Some("mapbox"), 1, false),
+            ),
+            (
+                r#"<link rel="stylesheet" href="https://unpkg.com/leaflet@1.9.4/dist/leaflet.css">
+                   <script src="https://unpkg.com/leaflet@1.9.4/dist/leaflet.js"></script>"#,
+                (Some("leaflet"), 1, false),
+            ),
+            (
+                r#"<script src="https://cdn.apple-mapkit.com/mk/5.x.x/mapkit.js"></script>"#,
+                (Some("apple"), 1, false),
+            ),
+        ];
+        for (html, expected) in cases {
+            assert_eq!(map_embeds(html), expected, "{}", html);
+        }
+    }
+
+    #[test]
+    fn static_map_images_are_flagged() {
+        let html = r#"<img src="https://maps.googleapis.com/maps/api/staticmap?center=-27.47,153.02&zoom=15&size=600x300&key=KEY" alt="Map">"#;
+        assert_eq!(map_embeds(html), (Some("google"), 1, true));
+
+        let html = r#"<img src="https://api.mapbox.com/styles/v1/mapbox/streets-v12/static/153.02,-27.47,14/600x300?access_token=pk">"#;
+        assert_eq!(map_embeds(html), (Some("mapbox"), 1, true));
+
+        // An interactive map first keeps its provider; the static flag still shows
+        let html = r#"<script src="https://unpkg.com/leaflet/dist/leaflet.js"></script>
+            <img src="https://maps.googleapis.com/maps/api/staticmap?center=Brisbane">"#;
+        assert_eq!(map_embeds(html), (Some("leaflet"), 2, true));
+    }
+
+    #[test]
+    fn map_links_and_commented_embeds_are_not_embeds() {
+        for html in [
+            r#"<a href="https://www.google.com/maps/place/Acme/@-27.47,153.02,17z">Find us</a>"#,
+            r#"<a href="https://maps.app.goo.gl/AbCdEf">Directions</a>"#,
+            r#"<a href="https://maps.googleapis.com/maps/api/staticmap?center=x">Static map</a>"#,
+            r#"<!-- <iframe src="https://www.google.com/maps/embed?pb=!1m18"></iframe> -->"#,
+            r#"<img src="/images/leaflet-flyer.jpg" alt="Our flyer">"#,
+            "<p>See the map on our contact page</p>",
+        ] {
+            assert_eq!(map_embeds(html), (None, 0, false), "{}", html);
+        }
+    }
+
+    #[test]
+    fn map_embed_counts_and_tech_stack_flag() {
+        let html = r#"<iframe src="https://www.google.com/maps/embed?pb=!1m18!1"></iframe>
+            <iframe src="https://www.google.com/maps/embed?pb=!1m18!2"></iframe>
+            <script src="https://maps.googleapis.com/maps/api/js?key=A"></script>
+            <script src="https://maps.googleapis.com/maps/api/js?key=A&libraries=places"></script>
+            <a href="https://www.google.com/maps?cid=123">Review us</a>"#;
+        assert_eq!(map_embeds(html), (Some("google"), 3, false));
+        assert!(tech_stack_of(html, None).has_map);
+        assert!(!tech_stack_of(r#"<a href="https://maps.app.goo.gl/x">Map</a>"#, None).has_map);
+    }
+
+    // Contact forms
+
+    /// (form_count, field_count_min, has_phone_field, has_file_upload, provider)
+    fn contact_forms(html: &str) -> (usize, Option<usize>, bool, bool, Option<&'static str>) {
+        let forms = contact_forms_of(html);
+        (
+            forms.count,
+            forms.field_count_min,
+            forms.has_phone_field,
+            forms.has_file_upload,
+            forms.provider,
+        )
+    }
+
+    #[test]
+    fn contact_form_7_markup() {
+        let html = r#"<div class="wpcf7 no-js" id="wpcf7-f42-o1">
+            <form action="/contact/#wpcf7-f42-o1" method="post" class="wpcf7-form init">
+              <div style="display: none;"><input type="hidden" name="_wpcf7" value="42"></div>
+              <input type="text" name="your-name" placeholder="Your name">
+              <input type="email" name="your-email">
+              <input type="tel" name="your-phone">
+              <select name="service"><option>Plumbing</option></select>
+              <input type="file" name="photos">
+              <textarea name="your-message"></textarea>
+              <span class="wpcf7-form-control-wrap" aria-hidden="true"><input type="text" name="website-hp"></span>
+              <input type="submit" value="Send">
+            </form></div>"#;
+        assert_eq!(
+            contact_forms(html),
+            (1, Some(6), true, true, Some("Contact Form 7"))
+        );
+        assert!(tech_stack_of(html, None).has_contact_form);
+    }
+
+    #[test]
+    fn hosted_form_embeds() {
+        let html = r#"<div data-tf-widget="a1B2c3" style="width:100%;height:500px;"></div>
+            <script src="//embed.typeform.com/next/embed.js"></script>"#;
+        assert_eq!(
+            contact_forms(html),
+            (1, None, false, false, Some("Typeform"))
+        );
+
+        let html = r#"<iframe src="https://form.typeform.com/to/a1B2c3?typeform-embed=embed-widget"></iframe>"#;
+        assert_eq!(contact_forms(html).4, Some("Typeform"));
+
+        let html =
+            r#"<iframe id="JotFormIFrame-2301" src="https://form.jotform.com/2301"></iframe>"#;
+        assert_eq!(
+            contact_forms(html),
+            (1, None, false, false, Some("Jotform"))
+        );
+
+        let html = r#"<script charset="utf-8" src="//js.hsforms.net/forms/embed/v2.js"></script>
+            <script>hbspt.forms.create({region: "na1", portalId: "123", formId: "abc"});</script>"#;
+        assert_eq!(
+            contact_forms(html),
+            (1, None, false, false, Some("HubSpot"))
+        );
+    }
+
+    #[test]
+    fn search_login_and_newsletter_forms_are_not_contact_forms() {
+        let search_page = r#"<form role="search" action="/"><input type="search" name="s"><button>Search</button></form>
+            <form action="/find"><input type="text" name="q"><button>Go</button></form>
+            <form id="login"><input type="text" name="user"><input type="password" name="pass"></form>"#;
+        assert_eq!(contact_forms(search_page), (0, None, false, false, None));
+        assert!(!tech_stack_of(search_page, None).has_contact_form);
+
+        let newsletter_footer = r#"<footer><h4>Stay in the loop</h4>
+            <form action="/subscribe" method="post"><input type="email" name="email" placeholder="you@example.com">
+            <button type="submit">Subscribe</button></form></footer>"#;
+        assert_eq!(contact_forms(newsletter_footer).0, 0);
+        assert_eq!(email_capture_of(newsletter_footer), ["newsletter_form"]);
+    }
+
+    #[test]
+    fn commented_and_template_forms_do_not_count() {
+        let html = r#"<!-- <form class="wpcf7-form"><input name="your-name"><textarea name="msg"></textarea></form> -->
+            <template id="enquiry"><form><input name="name"><textarea name="message"></textarea></form></template>"#;
+        assert_eq!(contact_forms(html), (0, None, false, false, None));
+    }
+
+    #[test]
+    fn field_count_min_is_the_shortest_form() {
+        let html = r#"<form class="gform_wrapper"><input name="name"><input name="email" type="email">
+                <input name="phone_number"><textarea name="message"></textarea></form>
+            <form class="quick-quote"><input name="name"><input name="postcode"></form>"#;
+        assert_eq!(
+            contact_forms(html),
+            (2, Some(2), true, false, Some("Gravity Forms"))
+        );
+    }
+
+    // CAPTCHA widgets
+
+    const RECAPTCHA_KEY: &str = "6LAbCdEfGhIjKlMnOpQrStUvWxYz0123456789_-";
+    const HCAPTCHA_KEY: &str = "10000000-ffff-ffff-ffff-000000000001";
+    const TURNSTILE_KEY: &str = "0x4AAAAAAABkMYinukE8nzY";
+
+    fn captcha(html: &str) -> Option<(&'static str, Option<String>)> {
+        captcha_of(html).map(|c| (c.provider, c.site_key))
+    }
+
+    #[test]
+    fn captcha_per_provider() {
+        let v2 = format!(
+            r#"<script src="https://www.google.com/recaptcha/api.js" async defer></script>
+            <form><div class="g-recaptcha" data-sitekey="{RECAPTCHA_KEY}"></div></form>"#
+        );
+        assert_eq!(
+            captcha(&v2),
+            Some(("recaptcha_v2", Some(RECAPTCHA_KEY.to_string())))
+        );
+
+        let v3_loader = format!(
+            r#"<script src="https://www.google.com/recaptcha/api.js?render={RECAPTCHA_KEY}"></script>"#
+        );
+        assert_eq!(
+            captcha(&v3_loader),
+            Some(("recaptcha_v3", Some(RECAPTCHA_KEY.to_string())))
+        );
+
+        let v3_execute = format!(
+            r#"<script>grecaptcha.ready(function() {{
+                grecaptcha.execute('{RECAPTCHA_KEY}', {{action: 'submit'}}).then(send);
+            }});</script>"#
+        );
+        assert_eq!(
+            captcha(&v3_execute),
+            Some(("recaptcha_v3", Some(RECAPTCHA_KEY.to_string())))
+        );
+
+        let hcaptcha = format!(
+            r#"<script src="https://js.hcaptcha.com/1/api.js" async></script>
+            <div class="h-captcha" data-sitekey="{HCAPTCHA_KEY}"></div>"#
+        );
+        assert_eq!(
+            captcha(&hcaptcha),
+            Some(("hcaptcha", Some(HCAPTCHA_KEY.to_string())))
+        );
+
+        let turnstile = format!(
+            r#"<script src="https://challenges.cloudflare.com/turnstile/v0/api.js" defer></script>
+            <div class="cf-turnstile" data-sitekey="{TURNSTILE_KEY}"></div>"#
+        );
+        assert_eq!(
+            captcha(&turnstile),
+            Some(("turnstile", Some(TURNSTILE_KEY.to_string())))
+        );
+        assert_eq!(tech_stack_of(&turnstile, None).captcha, Some("turnstile"));
+    }
+
+    #[test]
+    fn captcha_explicit_render_and_bare_site_keys() {
+        let html = format!(
+            r#"<script src="https://www.google.com/recaptcha/api.js?render=explicit&onload=cb"></script>
+            <script>function cb() {{ grecaptcha.render('box', {{'sitekey': '{RECAPTCHA_KEY}'}}); }}</script>"#
+        );
+        assert_eq!(
+            captcha(&html),
+            Some(("recaptcha_v2", Some(RECAPTCHA_KEY.to_string())))
+        );
+
+        let html = format!(r#"<div id="c" data-sitekey="{HCAPTCHA_KEY}"></div>"#);
+        assert_eq!(
+            captcha(&html),
+            Some(("hcaptcha", Some(HCAPTCHA_KEY.to_string())))
+        );
+
+        // hCaptcha's reCAPTCHA compatibility mode reuses the g-recaptcha class
+        let html = format!(
+            r#"<script src="https://hcaptcha.com/1/api.js?recaptchacompat=on"></script>
+            <div class="g-recaptcha" data-sitekey="{HCAPTCHA_KEY}"></div>"#
+        );
+        assert_eq!(captcha(&html).map(|c| c.0), Some("hcaptcha"));
+    }
+
+    #[test]
+    fn captcha_site_keys_are_shape_checked() {
+        let html = r#"<div class="g-recaptcha" data-sitekey="YOUR_SITE_KEY"></div>"#;
+        assert_eq!(captcha(html), Some(("recaptcha_v2", None)));
+        let html = r#"<div class="cf-turnstile" data-sitekey="{{ site_key }}"></div>"#;
+        assert_eq!(captcha(html), Some(("turnstile", None)));
+        assert_eq!(captcha(r#"<div data-sitekey="abc123"></div>"#), None);
+    }
+
+    #[test]
+    fn bundled_grecaptcha_mentions_are_not_captchas() {
+        let html = r#"<script>
+            !function(e){var t=window.grecaptcha;if(typeof grecaptcha!=="undefined"&&t.ready){t.ready(e)}}(init);
+            window.___grecaptcha_cfg = window.___grecaptcha_cfg || {};
+            </script>
+            <!-- <div class="g-recaptcha" data-sitekey="6LAbCdEfGhIjKlMnOpQrStUvWxYz0123456789_-"></div> -->
+            <p>Protected by reCAPTCHA</p>"#;
+        assert_eq!(captcha(html), None);
+        assert_eq!(tech_stack_of(html, None).captcha, None);
+    }
+
+    // Email capture
+
+    #[test]
+    fn email_capture_per_vendor() {
+        let cases = [
+            (
+                r#"<div id="mc_embed_signup"><form action="https://acme.us1.list-manage.com/subscribe/post?u=1&amp;id=2"
+                    id="mc-embedded-subscribe-form"><input type="email" name="EMAIL"><input type="text" name="FNAME">
+                    <input type="submit" value="Subscribe" id="mc-embedded-subscribe"></form></div>"#,
+                vec!["mailchimp_form"],
+            ),
+            (
+                r#"<script async src="https://static.klaviyo.com/onsite/js/klaviyo.js?company_id=AbC"></script>
+                    <div class="klaviyo-form-XyZ123"></div>"#,
+                vec!["klaviyo_form"],
+            ),
+            (
+                r#"<script async data-uid="abc" src="https://acme.ck.page/abc/index.js"></script>"#,
+                vec!["convertkit_form"],
+            ),
+            (
+                r#"<div class="ml-embedded" data-form="Xy12"></div>
+                    <script src="https://assets.mailerlite.com/js/universal.js"></script>"#,
+                vec!["mailerlite_form"],
+            ),
+            (
+                r#"<script src="https://a.omappapi.com/app/js/api.min.js" data-account="1" async></script>"#,
+                vec!["optinmonster_popup"],
+            ),
+            (
+                r#"<script async src="//load.sumo.com/" data-sumo-site-id="abc"></script>"#,
+                vec!["sumo_popup"],
+            ),
+            (
+                r#"<script src="https://widget.privy.com/assets/widget.js"></script>"#,
+                vec!["privy_popup"],
+            ),
+            (
+                r#"<script id="pixel-script-poptin" src="https://cdn.popt.in/pixel.js?id=abc" async></script>"#,
+                vec!["poptin_popup"],
+            ),
+        ];
+        for (html, expected) in cases {
+            assert_eq!(email_capture_of(html), expected, "{}", html);
+        }
+    }
+
+    #[test]
+    fn generic_newsletter_forms_and_lead_magnets() {
+        let html = r#"<section><h3>Download our free renovation guide</h3>
+            <form class="guide-form"><input type="email" name="email">
+            <button>Send me the guide</button></form></section>"#;
+        assert_eq!(email_capture_of(html), ["lead_magnet"]);
+
+        let html = r#"<footer><form><input type="email" name="newsletter-email">
+            <button>Join our newsletter</button></form></footer>
+            <p>Grab your free pricing checklist when you sign up.</p>"#;
+        assert_eq!(email_capture_of(html), ["newsletter_form", "lead_magnet"]);
+
+        // Offer wording with nowhere to leave an address isn't gated content
+        assert!(email_capture_of("<p>Download our free guide (PDF, 2 MB)</p>").is_empty());
+        assert!(email_capture_of(
+            r#"<!-- <script src="https://widget.privy.com/assets/widget.js"></script> -->"#
+        )
+        .is_empty());
+    }
+
+    #[test]
+    fn newsletter_forms_are_not_contact_forms() {
+        let html = r#"<form action="https://acme.us1.list-manage.com/subscribe/post" class="validate">
+            <input type="email" name="EMAIL"><input type="text" name="FNAME"><input type="text" name="LNAME">
+            <input type="submit" value="Sign up"></form>"#;
+        assert_eq!(email_capture_of(html), ["mailchimp_form"]);
+        assert_eq!(contact_forms_of(html).count, 0);
+    }
+
+    #[test]
+    fn contact_form_with_newsletter_opt_in_is_a_contact_form() {
+        let html = r#"<form class="wpcf7-form" action="/contact/">
+            <input type="text" name="your-name"><input type="email" name="your-email">
+            <textarea name="your-message"></textarea>
+            <label><input type="checkbox" name="newsletter" value="1"> Subscribe to our newsletter</label>
+            <input type="submit" value="Send enquiry"></form>"#;
+        assert!(email_capture_of(html).is_empty());
+        let forms = contact_forms_of(html);
+        assert_eq!(forms.count, 1);
+        assert_eq!(forms.provider, Some("Contact Form 7"));
+    }
+
+    // Review widgets
+
+    fn review_widgets(html: &str) -> Vec<&'static str> {
+        review_widgets_of(html, &PageScan::new(html))
+    }
+
+    #[test]
+    fn review_widgets_per_vendor() {
+        let cases = [
+            (
+                r#"<script src="//widget.trustpilot.com/bootstrap/v5/tp.widget.bootstrap.min.js" async></script>
+                    <div class="trustpilot-widget" data-template-id="abc"></div>"#,
+                "Trustpilot",
+            ),
+            (
+                r#"<script src="https://static.elfsight.com/platform/platform.js" defer></script>
+                    <div class="elfsight-app-1234 eapps-google-reviews"></div>"#,
+                "Elfsight",
+            ),
+            (
+                r#"<div class="embedsocial-reviews" data-ref="abc"></div>"#,
+                "EmbedSocial",
+            ),
+            (
+                r#"<script src="https://www.productreview.com.au/widget/embed.js"></script>"#,
+                "ProductReview",
+            ),
+            (
+                r#"<script src="https://cdn-widgetsrepository.yotpo.com/v1/loader/abc" async></script>"#,
+                "Yotpo",
+            ),
+            (
+                r#"<div class="jdgm-widget jdgm-preview-badge" data-id="1"></div>"#,
+                "Judge.me",
+            ),
+            (
+                r#"<div id="stamped-main-widget" data-product-id="1"></div>"#,
+                "Stamped",
+            ),
+            (
+                r#"<iframe src="https://birdeye.com/embed/review-widget/123"></iframe>"#,
+                "Birdeye",
+            ),
+            (
+                r#"<script src="https://reviews.podium.com/widget.js"></script>"#,
+                "Podium",
+            ),
+            (
+                r#"<div class="wp-google-reviews wp-gr-list"><div class="wp-google-review">Great</div></div>"#,
+                "Google Reviews",
+            ),
+            (
+                r#"<section class="google-reviews-carousel"></section>"#,
+                "Google Reviews",
+            ),
+        ];
+        for (html, widget) in cases {
+            assert_eq!(review_widgets(html), [widget], "{}", html);
+        }
+    }
+
+    #[test]
+    fn review_widgets_dedupe_and_feed_the_tech_stack() {
+        let html = r#"<div class="trustpilot-widget"></div><div class="trustpilot-widget"></div>
+            <script src="https://widget.trustpilot.com/bootstrap/v5/tp.widget.bootstrap.min.js"></script>
+            <div class="jdgm-widget"></div>"#;
+        assert_eq!(review_widgets(html), ["Trustpilot", "Judge.me"]);
+        assert_eq!(
+            tech_stack_of(html, None).review_widgets,
+            ["Trustpilot", "Judge.me"]
+        );
+    }
+
+    #[test]
+    fn google_review_links_are_not_widgets() {
+        let html = r#"<footer>
+            <a href="https://search.google.com/local/writereview?placeid=ChIJ123">Review us on Google</a>
+            <a href="https://g.page/r/abc/review" class="google-review-link-text">Leave a review</a>
+            <a href="https://www.trustpilot.com/review/acme.com.au">Trustpilot</a>
+            <!-- <div class="trustpilot-widget"></div> -->
+            </footer>"#;
+        assert!(review_widgets(html).is_empty());
+        let stack = tech_stack_of(html, None);
+        assert!(stack.review_widgets.is_empty());
+        assert!(stack.has_gbp_link);
+    }
+
+    // Testimonials
+
+    fn testimonials(html: &str) -> (bool, usize, Vec<&'static str>) {
+        let found = testimonials_of(html);
+        (found.has_testimonials, found.count, found.signals)
+    }
+
+    #[test]
+    fn hand_rolled_testimonial_carousel() {
+        let slide = |name: &str, extra: &str| {
+            format!(
+                r#"<div class="swiper-slide testimonial-item{extra}"><p>"Turned up on time and fixed the leak fast."</p>
+                <span class="testimonial-author">{name}</span></div>"#
+            )
+        };
+        let html = format!(
+            r#"<section class="testimonials"><h2>What our customers say</h2><div class="swiper-wrapper">{}{}{}{}</div></section>"#,
+            slide("Jo B.", ""),
+            slide("Sam T.", ""),
+            slide("Alex P.", ""),
+            slide("Jo B.", " swiper-slide-duplicate"),
+        );
+        assert_eq!(
+            testimonials(&html),
+            (true, 3, vec!["heading", "testimonial_markup"])
+        );
+    }
+
+    #[test]
+    fn attributed_quotes_need_a_pair_or_heading() {
+        let quote = |name: &str| {
+            format!("<blockquote><p>Best electrician in town, highly recommend.</p><cite>{name}</cite></blockquote>")
+        };
+        let html = format!("<main>{}{}</main>", quote("Priya"), quote("Tom"));
+        assert_eq!(testimonials(&html), (true, 2, vec!["quote_blocks"]));
+
+        // One attributed quote in an article is a pull quote
+        let html = format!(
+            "<article><h1>Our history</h1>{}</article>",
+            quote("Founder")
+        );
+        assert_eq!(testimonials(&html), (false, 0, vec![]));
+
+        let html = format!(
+            "<figure><blockquote>Fantastic service from start to finish.</blockquote><figcaption>Lee</figcaption></figure>
+             <h3>Reviews</h3>{}",
+            quote("Kim")
+        );
+        assert_eq!(
+            testimonials(&html),
+            (true, 2, vec!["heading", "quote_blocks"])
+        );
+    }
+
+    #[test]
+    fn schema_person_reviews_count() {
+        let review = |name: &str| {
+            format!(
+                r#"{{"@type": "Review", "author": {{"@type": "Person", "name": "{name}"}},
+                    "reviewBody": "Great work", "reviewRating": {{"ratingValue": 5}}}}"#
+            )
+        };
+        let html = format!(
+            r#"<script type="application/ld+json">{{"@type": "Plumber", "name": "Acme",
+                "review": [{}, {}, {{"@type": "Review", "author": {{"@type": "Organization", "name": "Houzz"}}}}]}}</script>"#,
+            review("Ann"),
+            review("Ben")
+        );
+        assert_eq!(testimonials(&html), (true, 2, vec!["schema_review"]));
+    }
+
+    #[test]
+    fn review_nav_links_and_widgets_are_not_testimonials() {
+        let html = r#"<nav><a href="/">Home</a><a href="https://www.productreview.com.au/listings/acme">Reviews</a>
+            <a href="/testimonials">Testimonials</a></nav>
+            <footer><h4>Reviews</h4><a href="https://g.page/r/acme/review">Review us on Google</a></footer>"#;
+        assert_eq!(testimonials(html), (false, 0, vec![]));
+
+        let html = r#"<section><h2>Reviews</h2><div class="trustpilot-widget">
+            <div class="review-card">Great service, would use again</div></div></section>"#;
+        assert_eq!(testimonials(html), (false, 0, vec![]));
+
+        // A bare "Reviews" heading needs blocks under it
+        assert_eq!(
+            testimonials("<h2>Reviews</h2><p>Coming soon.</p>"),
+            (false, 0, vec!["heading"])
+        );
+    }
+
+    #[test]
+    fn testimonial_count_is_capped() {
+        let html = (0..60)
+            .map(|i| format!(r#"<div class="testimonial">Customer {i} loved the work</div>"#))
+            .collect::<String>();
+        assert_eq!(testimonials(&html).1, MAX_TESTIMONIALS);
+    }
+
+    // Hiring signals
+
+    fn hiring(html: &str) -> (bool, Vec<&'static str>, Vec<String>) {
+        let found = hiring_signals_of(html);
+        (found.is_hiring(), found.signals.clone(), found.job_titles)
+    }
+
+    #[test]
+    fn job_posting_schema_titles() {
+        let posting = |title: &str| {
+            format!(
+                r#"{{"@context": "https://schema.org", "@type": "JobPosting", "title": "{title}",
+                    "hiringOrganization": {{"@type": "Organization", "name": "Acme Plumbing"}}}}"#
+            )
+        };
+        let html = format!(
+            r#"<script type="application/ld+json">[{}, {}, {}]</script>"#,
+            posting("Licensed Plumber"),
+            posting("Apprentice Plumber"),
+            posting("Licensed Plumber"),
+        );
+        assert_eq!(
+            hiring(&html),
+            (
+                true,
+                vec!["job_posting_schema"],
+                strings(&["Licensed Plumber", "Apprentice Plumber"])
+            )
+        );
+    }
+
+    #[test]
+    fn careers_links_in_navigation() {
+        for html in [
+            r#"<nav><a href="/">Home</a><a href="/careers/">Careers</a></nav>"#,
+            r#"<header><ul class="menu"><li><a href="/about/join-our-team">Join Our Team</a></li></ul></header>"#,
+            r#"<footer><a href="https://acme.com.au/work-with-us/">Work with us</a></footer>"#,
+            r#"<div class="main-menu"><a href="/en-au/jobs">Opportunities</a></div>"#,
+        ] {
+            assert_eq!(
+                hiring(html),
+                (true, vec!["careers_link"], vec![]),
+                "{}",
+                html
+            );
+        }
+        // The same link in body copy isn't navigation
+        let html =
+            r#"<p>Read about <a href="/blog/careers-in-plumbing">careers in plumbing</a>.</p>"#;
+        assert!(!hiring(html).0);
+    }
+
+    #[test]
+    fn hiring_headings_list_job_titles() {
+        let html = r#"<h2>We're hiring!</h2>
+            <ul><li>Qualified Electrician</li><li>Apprentice Electrician (2nd year)</li>
+                <li>We offer competitive pay, a company vehicle and ongoing training for the right people.</li></ul>
+            <h2>About us</h2><ul><li>Family owned</li></ul>
+            <iframe src="https://www.seek.com.au/companies/acme-123/jobs"></iframe>"#;
+        assert_eq!(
+            hiring(html),
+            (
+                true,
+                vec!["job_widget", "hiring_heading"],
+                strings(&["Qualified Electrician", "Apprentice Electrician (2nd year)"])
+            )
+        );
+    }
+
+    #[test]
+    fn hiring_a_tradesperson_blog_post_is_not_hiring() {
+        let html = r#"<article><h1>Hiring the right plumber for your renovation</h1>
+            <p>Before hiring a plumber, check their licence. Many firms are currently hiring
+            apprentices, so ask who will do the work.</p>
+            <h2>Questions to ask</h2><ul><li>Are you licensed?</li><li>Do you offer a warranty</li></ul></article>"#;
+        assert_eq!(hiring(html), (false, vec!["hiring_phrase"], vec![]));
+        assert!(!hiring("<p>Hire us for your next job.</p>").0);
+    }
+
+    #[test]
+    fn job_titles_are_capped() {
+        let html = format!(
+            "<h2>Current vacancies</h2><ul>{}</ul>",
+            (1..=25)
+                .map(|i| format!("<li>Role {i}</li>"))
+                .collect::<String>()
+        );
+        let (is_hiring, _, titles) = hiring(&html);
+        assert!(is_hiring);
+        assert_eq!(titles.len(), MAX_JOB_TITLES);
+        assert_eq!(titles[0], "Role 1");
+    }
+
+    // Pricing
+
+    fn pricing(html: &str) -> (Vec<&'static str>, Vec<String>) {
+        let found = pricing_of(html);
+        (found.signals, found.sample_prices)
+    }
+
+    #[test]
+    fn price_list_table() {
+        let html = r#"<table class="services">
+            <tr><td>Blocked drain</td><td>$180</td></tr>
+            <tr><td>Hot water service</td><td>AUD 1,450.00</td></tr>
+            <tr><td>Tap washer</td><td>$ 85.50</td></tr>
+            <tr><td>Gas fitting (per hour)</td><td>120 AUD</td></tr></table>"#;
+        assert_eq!(
+            pricing(html),
+            (
+                vec!["price_list"],
+                strings(&["$180", "$1,450", "$85.50", "$120"])
+            )
+        );
+    }
+
+    #[test]
+    fn from_price_hero_line() {
+        let html = r#"<section class="hero"><h1>Emergency electrician</h1>
+            <p>Call-outs from just $99 &mdash; 7 days a week</p></section>"#;
+        assert_eq!(pricing(html), (vec!["from_price"], strings(&["$99"])));
+
+        let html = "<p>Websites starting at NZ$2,500.</p>";
+        assert_eq!(pricing(html), (vec!["from_price"], strings(&["$2,500"])));
+    }
+
+    #[test]
+    fn links_headings_and_schema_offers() {
+        let html = r#"<nav><a href="/our-prices/">Prices</a></nav>
+            <h2>Fees &amp; charges</h2><p>Standard consult $75.</p>
+            <script type="application/ld+json">{"@type": "Service", "name": "Consult",
+                "offers": {"@type": "Offer", "price": "75.00", "priceCurrency": "AUD"}}</script>"#;
+        assert_eq!(
+            pricing(html),
+            (
+                vec!["pricing_link", "pricing_heading", "schema_offer"],
+                strings(&["$75"])
+            )
+        );
+    }
+
+    #[test]
+    fn incidental_amounts_are_not_pricing() {
+        let blog = r#"<article><h1>Five ways to save water</h1>
+            <p>Fixing a dripping tap can save the average household $150 a year.</p>
+            <p><a href="/donate">Donate $10 to Clean Up Australia</a></p></article>"#;
+        assert_eq!(pricing(blog), (vec![], vec![]));
+
+        let crypto =
+            r#"<ul><li>$0.42 ETH</li><li>$1,200 in BTC</li><li>$3 worth of doge</li></ul>"#;
+        assert_eq!(pricing(crypto), (vec![], vec![]));
+
+        let turnover =
+            "<ul><li>$2,000,000 turnover</li><li>$5,000,000 projects</li><li>$9,999,999</li></ul>";
+        assert_eq!(pricing(turnover), (vec![], vec![]));
+    }
+
+    #[test]
+    fn sample_prices_dedupe_and_cap() {
+        let html = format!(
+            "<h2>Price list</h2><ul>{}<li>$10</li></ul>",
+            (1..=12)
+                .map(|i| format!("<li>${}</li>", i * 10))
+                .collect::<String>()
+        );
+        let (signals, samples) = pricing(&html);
+        assert_eq!(signals, ["pricing_heading", "price_list"]);
+        assert_eq!(samples.len(), MAX_SAMPLE_PRICES);
+        assert_eq!(samples[..3], strings(&["$10", "$20", "$30"]));
+    }
+
+    // Video
+
+    fn videos(html: &str) -> (Vec<&'static str>, usize) {
+        let found = videos_of(html);
+        (found.providers, found.count)
+    }
+
+    #[test]
+    fn videos_per_provider() {
+        let cases = [
+            (
+                r#"<iframe width="560" src="https://www.youtube.com/embed/dQw4w9WgXcQ?rel=0" allowfullscreen></iframe>"#,
+                "youtube",
+            ),
+            (
+                r#"<iframe data-src="https://www.youtube-nocookie.com/embed/dQw4w9WgXcQ" class="lazyload"></iframe>"#,
+                "youtube",
+            ),
+            (
+                r#"<lite-youtube videoid="dQw4w9WgXcQ"></lite-youtube>"#,
+                "youtube",
+            ),
+            (
+                r#"<iframe src="https://player.vimeo.com/video/76979871?h=8272103f6e"></iframe>"#,
+                "vimeo",
+            ),
+            (r#"<lite-vimeo videoid="76979871"></lite-vimeo>"#, "vimeo"),
+            (
+                r#"<div class="wistia_embed wistia_async_abc123xyz videoFoam=true"></div>"#,
+                "wistia",
+            ),
+            (
+                r#"<iframe src="https://fast.wistia.net/embed/iframe/abc123xyz?videoFoam=true"></iframe>"#,
+                "wistia",
+            ),
+            (
+                r#"<img class="vidyard-player-embed" src="https://play.vidyard.com/AbCdEfGhIjKl.jpg" data-uuid="AbCdEfGhIjKl" data-v="4">"#,
+                "vidyard",
+            ),
+            (
+                r#"<video controls poster="/img/tour.jpg"><source src="/media/tour.mp4" type="video/mp4"></video>"#,
+                "html5",
+            ),
+            (
+                r#"<section class="hero"><video autoplay muted loop playsinline src="/media/hero.webm"></video></section>"#,
+                "background_video",
+            ),
+        ];
+        for (html, provider) in cases {
+            assert_eq!(videos(html), (vec![provider], 1), "{}", html);
+        }
+    }
+
+    #[test]
+    fn the_same_video_embedded_twice_counts_once() {
+        let html = r#"<iframe src="https://www.youtube.com/embed/dQw4w9WgXcQ"></iframe>
+            <lite-youtube videoid="dQw4w9WgXcQ"></lite-youtube>
+            <iframe src="https://www.youtube-nocookie.com/embed/dQw4w9WgXcQ?start=30"></iframe>
+            <iframe src="https://www.youtube.com/embed/9bZkp7q19f0"></iframe>
+            <iframe src="https://player.vimeo.com/video/76979871"></iframe>"#;
+        assert_eq!(videos(html), (vec!["youtube", "vimeo"], 3));
+        assert!(tech_stack_of(html, None).has_video);
+    }
+
+    #[test]
+    fn video_links_and_empty_players_are_not_videos() {
+        let html = r##"<div class="instagram-feed">
+            <a href="https://www.youtube.com/watch?v=dQw4w9WgXcQ"><img src="https://i.ytimg.com/vi/dQw4w9WgXcQ/hqdefault.jpg"></a>
+            <a href="https://vimeo.com/76979871">Watch on Vimeo</a></div>
+            <video autoplay muted loop></video>
+            <video><source src="#"></video>
+            <iframe src="https://www.youtube.com/subscribe_embed?channel=acme"></iframe>
+            <template><iframe src="https://www.youtube.com/embed/dQw4w9WgXcQ"></iframe></template>"##;
+        assert_eq!(videos(html), (vec![], 0));
+        assert!(!tech_stack_of(html, None).has_video);
+    }
+
+    // Blog
+
+    #[test]
+    fn nav_only_homepage_links_the_blog() {
+        let html = r#"<header><nav><a href="/">Home</a><a href="/services">Services</a>
+            <a href="news/">Latest News</a></nav></header>
+            <main><h1>Plumbing you can trust</h1><p>Serving Brisbane since 12 March 2001.</p></main>
+            <footer>&copy; 2019 Acme Plumbing</footer>"#;
+        let blog = blog_check_of(html, Some("https://acme.com.au/about/"));
+        assert_eq!(
+            blog.blog_url.as_deref(),
+            Some("https://acme.com.au/about/news/")
+        );
+        assert!(blog.post_dates.is_empty());
+
+        let blog = blog_check_of(html, None);
+        assert_eq!(blog.blog_url.as_deref(), Some("news/"));
+
+        // A blog link in body copy isn't the site's blog
+        let html = r#"<p>As featured on <a href="https://example.com/blog">their blog</a>.</p>"#;
+        assert_eq!(blog_check_of(html, None).blog_url, None);
+    }
+
+    #[test]
+    fn blog_index_with_mixed_date_formats() {
+        let html = r#"<nav><a href="/blog/">Blog</a></nav>
+            <main>
+              <article><h2>Winter pipe care</h2><time datetime="2023-06-01T08:00:00+10:00">1 June</time></article>
+              <article><h2>Choosing a hot water system</h2><p>Posted 3rd Feb, 2022</p></article>
+              <article><h2>Gas safety</h2><p>March 5, 2021</p></article>
+              <article><h2>Storm season</h2><p>14/07/2019 &middot; 4 min read</p></article>
+              <article><h2>Drain myths</h2><p>2020-11-30</p></article>
+              <article><h2>Coming soon</h2><p>31 December 2099</p></article>
+              <article><h2>Not a date</h2><p>Version 1.2/2021, 30/02/2021</p></article>
+            </main>
+            <footer>&copy; Acme Pty Ltd, updated 2 January 2025</footer>"#;
+        let blog = blog_check_of(html, Some("https://acme.com.au/blog/"));
+        assert_eq!(blog.blog_url.as_deref(), Some("https://acme.com.au/blog/"));
+        assert_eq!(
+            blog.post_dates,
+            strings(&[
+                "2023-06-01",
+                "2022-02-03",
+                "2021-03-05",
+                "2020-11-30",
+                "2019-07-14"
+            ])
+        );
+    }
+
+    #[test]
+    fn schema_date_published_on_any_page() {
+        let html = r#"<script type="application/ld+json">{"@type": "BlogPosting",
+                "headline": "Why your tap drips", "datePublished": "2018-09-04T10:30:00+10:00"}</script>
+            <article><h1>Why your tap drips</h1><p>Updated 12 March 2021</p></article>"#;
+        // One post page isn't an index, so only the schema date counts
+        assert_eq!(
+            blog_check_of(html, None).post_dates,
+            strings(&["2018-09-04"])
+        );
+        // On a blog URL the written date is read too
+        assert_eq!(
+            blog_check_of(html, Some("https://acme.com.au/blog/why-your-tap-drips")).post_dates,
+            strings(&["2021-03-12", "2018-09-04"])
+        );
+    }
+
+    // Languages
+
+    fn languages(html: &str) -> (Vec<String>, Option<&'static str>) {
+        let found = languages_of(html, &PageScan::new(html));
+        (found.languages, found.mechanism)
+    }
+
+    #[test]
+    fn hreflang_clusters() {
+        let html = r#"<html lang="en-AU"><head>
+            <link rel="alternate" hreflang="en-au" href="https://acme.com.au/">
+            <link rel="alternate" hreflang="en-NZ" href="https://acme.co.nz/">
+            <link rel="alternate" hreflang="zh-Hans" href="https://acme.com.au/zh/">
+            <link rel="alternate" hreflang="vi" href="https://acme.com.au/vi/">
+            <link rel="alternate" hreflang="iw" href="https://acme.com.au/he/">
+            <link rel="alternate" hreflang="x-default" href="https://acme.com.au/">
+            </head></html>"#;
+        assert_eq!(
+            languages(html),
+            (strings(&["en", "zh", "vi", "he"]), Some("hreflang"))
+        );
+        assert_eq!(
+            tech_stack_of(html, None).languages,
+            strings(&["en", "zh", "vi", "he"])
+        );
+    }
+
+    #[test]
+    fn translation_plugins_and_services() {
+        let html = r#"<html lang="en"><script type="text/javascript" src="https://cdn.weglot.com/weglot.min.js"></script>
+            <script>Weglot.initialize({api_key: 'wg_123'});</script></html>"#;
+        assert_eq!(languages(html), (strings(&["en"]), Some("Weglot")));
+
+        let html = r#"<html lang="en-US"><div class="wpml-ls-statics-shortcode_actions wpml-ls">
+            <a href="/fr/">Français</a></div></html>"#;
+        assert_eq!(languages(html), (strings(&["en", "fr"]), Some("WPML")));
+
+        let html = r#"<li class="pll-parent-menu-item"><a href="/it/">Italiano</a></li>"#;
+        assert_eq!(languages(html), (strings(&["it"]), Some("Polylang")));
+    }
+
+    #[test]
+    fn language_switcher_links() {
+        let html = r#"<html lang="en"><nav><a href="/en/">EN</a> | <a href="/zh/">中文</a>
+            | <a href="/ko/about">한국어</a> | <a href="/es/">Spanish menu</a></nav></html>"#;
+        assert_eq!(
+            languages(html),
+            (strings(&["en", "zh", "ko"]), Some("switcher"))
+        );
+    }
+
+    #[test]
+    fn monolingual_pages_are_not_multilingual() {
+        let html = r#"<html lang="en-AU"><head><link rel="canonical" href="https://acme.com.au/"></head>
+            <body><a href="/about/">About</a><a href="/de/">Our German range</a></body></html>"#;
+        assert_eq!(languages(html), (strings(&["en"]), None));
+        assert_eq!(languages("<p>No lang attribute</p>"), (vec![], None));
+    }
+
+    // Content language
+
+    const ENGLISH_COPY: &str = "We are a family owned plumbing business that has been looking after homes \
+        and businesses across the northern suburbs for more than twenty years. Our licensed team can help \
+        with blocked drains, burst pipes, hot water systems and bathroom renovations, and we always give \
+        you a clear written quote before any work begins. Call us today to book a time that suits you.";
+    const VIETNAMESE_COPY: &str = "Chúng tôi là một doanh nghiệp gia đình chuyên cung cấp dịch vụ sửa chữa \
+        ống nước cho các hộ gia đình và cửa hàng trong khu vực. Đội ngũ thợ có giấy phép của chúng tôi luôn \
+        sẵn sàng giúp bạn xử lý cống bị tắc, đường ống bị vỡ và máy nước nóng. Chúng tôi luôn báo giá rõ \
+        ràng trước khi bắt đầu công việc. Hãy gọi cho chúng tôi ngay hôm nay để đặt lịch hẹn phù hợp.";
+    const CHINESE_COPY: &str =
+        "我们是一家家族经营的水管维修公司，二十多年来一直为北区的家庭和企业提供服务。\
+        我们的持牌技师可以帮助您处理下水道堵塞、水管爆裂、热水器和浴室翻新等问题。\
+        在开始任何工作之前，我们都会为您提供清晰的书面报价。欢迎今天就致电预约。";
+
+    #[test]
+    fn content_language_per_script() {
+        for (copy, language) in [
+            (ENGLISH_COPY, "en"),
+            (VIETNAMESE_COPY, "vi"),
+            (CHINESE_COPY, "zh"),
+        ] {
+            let html = format!("<html lang=\"en\"><main><p>{copy}</p></main></html>");
+            let found = content_language_of(&html).unwrap();
+            assert_eq!(found.language, language, "{}", copy);
+            assert!(found.confidence > 0.5, "{} {}", language, found.confidence);
+        }
+    }
+
+    #[test]
+    fn short_and_boilerplate_pages_have_no_language() {
+        assert_eq!(
+            content_language_of("<p>Welcome to our website. Call us today.</p>"),
+            None
+        );
+        let html = format!(
+            "<nav>{ENGLISH_COPY}</nav><header>{ENGLISH_COPY}</header><main><p>Hello there.</p></main><footer>{ENGLISH_COPY}</footer>"
+        );
+        assert_eq!(content_language_of(&html), None);
+        assert_eq!(content_language_of(""), None);
+    }
+
+    #[test]
+    fn mixed_language_pages_report_lower_confidence() {
+        let english =
+            content_language_of(&format!("<p>{ENGLISH_COPY} {ENGLISH_COPY}</p>")).unwrap();
+        let mixed = content_language_of(&format!(
+            "<p>{ENGLISH_COPY} {ENGLISH_COPY}</p><p>{VIETNAMESE_COPY}</p>"
+        ))
+        .unwrap();
+        assert_eq!(mixed.language, "en");
+        assert!(
+            mixed.confidence < english.confidence,
+            "{} {}",
+            mixed.confidence,
+            english.confidence
+        );
+
+        let html = format!("<main><p>{CHINESE_COPY}</p></main>");
+        assert_eq!(
+            tech_stack_of(&html, None)
+                .content_language
+                .map(|c| c.language),
+            Some("zh")
+        );
+    }
+
+    // AMP and m-dot sites
+
+    #[test]
+    fn canonical_page_linking_its_amp_version() {
+        let html = r#"<!doctype html><html lang="en"><head>
+            <link rel="canonical" href="https://acme.com.au/services/">
+            <link rel="amphtml" href="https://acme.com.au/services/amp/">
+            </head><body></body></html>"#;
+        let expected = AmpCheck {
+            is_amp: false,
+            amp_url: Some("https://acme.com.au/services/amp/".to_string()),
+        };
+        assert_eq!(amp_of(html), expected);
+        assert_eq!(tech_stack_of(html, None).amp, expected);
+    }
+
+    #[test]
+    fn actual_amp_pages() {
+        for html in [
+            r#"<!doctype html><html amp lang="en"><head><script async src="https://cdn.ampproject.org/v0.js"></script></head></html>"#,
+            r#"<!doctype html><html ⚡ lang="en"><head><link rel="canonical" href="https://acme.com.au/"></head></html>"#,
+            r#"<!doctype html><html AMP><head></head></html>"#,
+        ] {
+            assert_eq!(
+                amp_of(html),
+                AmpCheck {
+                    is_amp: true,
+                    amp_url: None
+                },
+                "{}",
+                html
+            );
+        }
+    }
+
+    #[test]
+    fn amp_runtime_from_an_embed_is_not_amp() {
+        let html = r#"<!doctype html><html lang="en"><body data-amp="1">
+            <div class="amp-story-embed"><script async src="https://cdn.ampproject.org/amp-story-player-v0.js"></script>
+            <amp-story-player><a href="https://stories.example.com/amp/story">Story</a></amp-story-player></div>
+            <a rel="amphtml" href="/not-a-link-element">AMP</a>
+            <link rel="amphtml" href="  ">
+            </body></html>"#;
+        assert_eq!(
+            amp_of(html),
+            AmpCheck {
+                is_amp: false,
+                amp_url: None
+            }
+        );
+    }
+
+    #[test]
+    fn mdot_references() {
+        let html = r#"<head>
+            <link rel="canonical" href="https://www.acme.com.au/">
+            <link rel="alternate" media="only screen and (max-width: 640px)" href="https://m.acme.com.au/">
+            </head>"#;
+        assert_eq!(
+            mdot_reference_of(html, "www.acme.com.au").as_deref(),
+            Some("https://m.acme.com.au/")
+        );
+        assert_eq!(
+            mdot_reference_of(html, "https://acme.com.au/contact").as_deref(),
+            Some("https://m.acme.com.au/")
+        );
+
+        let html = r#"<link rel="canonical" href="//m.acme.com.au/about">"#;
+        assert_eq!(
+            mdot_reference_of(html, "acme.com.au").as_deref(),
+            Some("//m.acme.com.au/about")
+        );
+
+        for html in [
+            // Another site's m-dot host, a relative path and a plain link
+            r#"<link rel="alternate" href="https://m.facebook.com/acme">"#,
+            r#"<link rel="alternate" href="/m.acme.com.au/">"#,
+            r#"<a href="https://m.acme.com.au/">Mobile site</a>"#,
+            r#"<link rel="alternate" href="https://mm.acme.com.au/">"#,
+        ] {
+            assert_eq!(mdot_reference_of(html, "acme.com.au"), None, "{}", html);
+        }
+    }
+
+    // PWA
+
+    fn pwa(html: &str) -> (Option<String>, bool, bool) {
+        let found = pwa_of(html);
+        (
+            found.manifest_url,
+            found.registers_service_worker,
+            found.has_theme_color,
+        )
+    }
+
+    #[test]
+    fn next_js_pwa() {
+        let html = r##"<!DOCTYPE html><html><head>
+            <link rel="manifest" href="/manifest.json"><meta name="theme-color" content="#0f172a">
+            <script src="/_next/static/chunks/webpack-3f2a.js" defer></script></head>
+            <body><div id="__next"></div>
+            <script>var cdn = "https://cdn.acme.com.au/";
+            if ("serviceWorker" in navigator) { window.addEventListener("load", function () {
+              navigator.serviceWorker.register("/sw.js", { scope: "/" }); }); }</script></body></html>"##;
+        assert_eq!(pwa(html), (Some("/manifest.json".to_string()), true, true));
+        assert!(tech_stack_of(html, None).is_pwa);
+
+        for loader in [
+            r#"<script type="module" src="/assets/workbox-window.prod.es5.mjs"></script>"#,
+            r#"<script src="/registerSW.js"></script>"#,
+            "<script>const wb = new Workbox('/sw.js'); wb.register();</script>",
+        ] {
+            assert!(pwa(loader).1, "{}", loader);
+        }
+    }
+
+    #[test]
+    fn manifest_only_site_is_not_a_pwa() {
+        let html = r#"<head><link rel="manifest" href="/site.webmanifest">
+            <meta name="theme-color" content=""></head>"#;
+        assert_eq!(
+            pwa(html),
+            (Some("/site.webmanifest".to_string()), false, false)
+        );
+        assert!(!tech_stack_of(html, None).is_pwa);
+    }
+
+    #[test]
+    fn commented_and_srcdoc_registrations_do_not_count() {
+        let html = r#"<link rel="manifest" href="/manifest.json">
+            <script>
+              // navigator.serviceWorker.register('/sw.js');
+              /* if ('serviceWorker' in navigator) {
+                   navigator.serviceWorker.register('/sw.js');
+                 } */
+            </script>
+            <!-- <script>navigator.serviceWorker.register('/sw.js')</script> -->
+            <iframe srcdoc="<script>navigator.serviceWorker.register('/widget-sw.js')</script>"></iframe>"#;
+        assert_eq!(
+            pwa(html),
+            (Some("/manifest.json".to_string()), false, false)
+        );
+        assert!(!tech_stack_of(html, None).is_pwa);
+    }
+
+    // App store links
+
+    fn app_links(html: &str) -> (Option<String>, Option<String>, Vec<String>) {
+        let links = app_links_of(html);
+        (links.ios_url, links.android_url, links.app_ids)
+    }
+
+    #[test]
+    fn smart_banner_meta() {
+        let html = r#"<meta name="apple-itunes-app" content="app-id=1234567890, app-argument=myapp://home">"#;
+        assert_eq!(
+            app_links(html),
+            (
+                Some("https://apps.apple.com/app/id1234567890".to_string()),
+                None,
+                strings(&["1234567890"])
+            )
+        );
+    }
+
+    #[test]
+    fn footer_store_badges() {
+        let html = r#"<meta name="apple-itunes-app" content="app-id=1234567890">
+            <footer><a href="https://apps.apple.com/au/app/acme-rewards/id1234567890?mt=8"><img alt="App Store"></a>
+            <a href="https://play.google.com/store/apps/details?id=au.com.acme.rewards&hl=en_AU"><img alt="Google Play"></a>
+            <a href="https://play.google.com/store/apps/developer?id=Acme">More apps</a></footer>"#;
+        assert_eq!(
+            app_links(html),
+            (
+                Some("https://apps.apple.com/app/id1234567890".to_string()),
+                Some(
+                    "https://play.google.com/store/apps/details?id=au.com.acme.rewards&hl=en_AU"
+                        .to_string()
+                ),
+                strings(&["1234567890", "au.com.acme.rewards"])
+            )
+        );
+    }
+
+    #[test]
+    fn blog_links_to_other_apps_do_not_count() {
+        let html = r#"<article class="post"><h1>Five apps every renovator needs</h1>
+            <p>I edit site photos in the Snapseed app
+              (<a href="https://play.google.com/store/apps/details?id=com.niksoftware.snapseed">Snapseed</a>).</p>
+            <p>For measuring, try <a href="https://apps.apple.com/us/app/measure/id1383426740">Measure</a>.</p>
+            </article>"#;
+        assert_eq!(app_links(html), (None, None, vec![]));
+
+        // A post announcing the business's own app does
+        let html = r#"<div class="entry-content"><p>Book jobs faster with our new app:
+            <a href="https://apps.apple.com/au/app/acme/id987654321">iPhone</a></p></div>"#;
+        assert_eq!(app_links(html).2, strings(&["987654321"]));
+        let html = r#"<article><a href="https://play.google.com/store/apps/details?id=au.acme">
+            <img alt="Get it on Google Play"></a></article>"#;
+        assert_eq!(app_links(html).2, strings(&["au.acme"]));
+    }
+
+    // Outdated libraries
+
+    fn library_versions(html: &str) -> Vec<(&'static str, String, bool)> {
+        library_versions_of(html)
+            .into_iter()
+            .map(|lib| (lib.library, lib.version, lib.outdated))
+            .collect()
+    }
+
+    #[test]
+    fn library_versions_per_cdn_pattern() {
+        let cases = [
+            ("https://code.jquery.com/jquery-1.8.3.min.js", ("jQuery", "1.8.3", true)),
+            ("https://ajax.googleapis.com/ajax/libs/jquery/1.12.4/jquery.min.js", ("jQuery", "1.12.4", true)),
+            ("https://cdn.jsdelivr.net/npm/jquery@3.7.1/dist/jquery.min.js", ("jQuery", "3.7.1", false)),
+            ("https://code.jquery.com/ui/1.12.1/jquery-ui.min.js", ("jQuery UI", "1.12.1", false)),
+            ("https://ajax.googleapis.com/ajax/libs/jqueryui/1.9.2/jquery-ui.min.js", ("jQuery UI", "1.9.2", false)),
+            (
+                "https://cdnjs.cloudflare.com/ajax/libs/twitter-bootstrap/3.3.7/js/bootstrap.min.js",
+                ("Bootstrap", "3.3.7", true),
+            ),
+            (
+                "https://maxcdn.bootstrapcdn.com/bootstrap/4.0.0/css/bootstrap.min.css",
+                ("Bootstrap", "4.0.0", true),
+            ),
+            (
+                "https://cdn.jsdelivr.net/npm/bootstrap@5.3.2/dist/css/bootstrap.min.css",
+                ("Bootstrap", "5.3.2", false),
+            ),
+            ("https://cdnjs.cloudflare.com/ajax/libs/moment.js/2.29.4/moment.min.js", ("Moment", "2.29.4", false)),
+            (
+                "https://ajax.googleapis.com/ajax/libs/angularjs/1.8.2/angular.min.js",
+                ("AngularJS", "1.8.2", false),
+            ),
+            ("https://cdnjs.cloudflare.com/ajax/libs/modernizr/2.8.3/modernizr.min.js", ("Modernizr", "2.8.3", true)),
+        ];
+        for (url, (library, version, outdated)) in cases {
+            let html = format!(r#"<script src="{url}"></script>"#);
+            assert_eq!(
+                library_versions(&html),
+                [(library, version.to_string(), outdated)],
+                "{}",
+                url
+            );
+        }
+    }
+
+    #[test]
+    fn library_versions_from_wordpress_ver_queries() {
+        let html = r#"<script src="https://acme.com.au/wp-includes/js/jquery/jquery.min.js?ver=3.7.1" id="jquery-core-js"></script>
+            <script src="https://acme.com.au/wp-includes/js/jquery/jquery-migrate.min.js?ver=3.4.1"></script>
+            <script src="/wp-includes/js/jquery/ui/core.min.js?ver=1.13.2"></script>
+            <link rel="stylesheet" href="/wp-content/themes/acme/css/bootstrap.min.css?ver=4.6.2" media="all">
+            <script src="/wp-content/themes/acme/js/modernizr.custom.js?v=1&#038;ver=2.6.2"></script>"#;
+        assert_eq!(
+            library_versions(html),
+            [
+                ("jQuery", "3.7.1".to_string(), false),
+                ("jQuery UI", "1.13.2".to_string(), false),
+                ("Bootstrap", "4.6.2".to_string(), true),
+                ("Modernizr", "2.6.2".to_string(), true),
+            ]
+        );
+    }
+
+    #[test]
+    fn commented_and_unversioned_libraries_do_not_count() {
+        let html = r#"<!-- <script src="https://code.jquery.com/jquery-1.4.2.min.js"></script> -->
+            <script src="/js/jquery.min.js"></script>
+            <script src="https://cdn.jsdelivr.net/npm/@angular/core@16.2.0/fesm2022/core.mjs"></script>
+            <script src="https://code.jquery.com/jquery-3.7.1.min.js"></script>
+            <script src="https://code.jquery.com/jquery-3.7.1.min.js"></script>
+            <script src="https://code.jquery.com/jquery-1.11.0.min.js"></script>"#;
+        assert_eq!(
+            library_versions(html),
+            [
+                ("jQuery", "3.7.1".to_string(), false),
+                ("jQuery", "1.11.0".to_string(), true)
+            ]
+        );
+        assert_eq!(
+            tech_stack_of(html, None).libraries,
+            library_versions_of(html)
+        );
+    }
+
+    // Legacy technology
+
+    fn legacy_flags(html: &str) -> Vec<&'static str> {
+        legacy_flags_of(html, &framework_contexts(html))
+    }
+
+    #[test]
+    fn genuine_2004_era_page() {
+        let html = r##"<!DOCTYPE HTML PUBLIC "-//W3C//DTD HTML 4.01 Transitional//EN">
+            <html><head><title>Acme Plumbing - Home</title>
+            <meta name="GENERATOR" content="Microsoft FrontPage 5.0">
+            <meta name="ProgId" content="FrontPage.Editor.Document">
+            <script language="VBScript">Sub Window_OnLoad : MsgBox "Welcome" : End Sub</script>
+            </head><body bgcolor="#FFFFFF">
+            <table width="760" border="0" cellpadding="0" cellspacing="0" align="center">
+              <tr><td width="160" valign="top"><img src="images/spacer.gif" width="1" height="20"></td>
+              <td width="600"><table width="100%" border="0"><tr><td>
+                <marquee behavior="scroll">Now servicing the western suburbs!</marquee>
+                <object classid="clsid:D27CDB6E-AE6D-11cf-96B8-444553540000" width="600" height="120">
+                  <param name="movie" value="banner.swf"><embed src="banner.swf" width="600" height="120"></embed>
+                </object>
+                <a href="brochure.mht">Download our brochure</a>
+              </td></tr></table></td></tr></table>
+            </body></html>"##;
+        assert_eq!(
+            legacy_flags(html),
+            [
+                "flash",
+                "marquee",
+                "frontpage_generator",
+                "table_layout",
+                "spacer_gif",
+                "mhtml",
+                "vbscript"
+            ]
+        );
+        assert_eq!(tech_stack_of(html, None).legacy_flags, legacy_flags(html));
+    }
+
+    #[test]
+    fn frameset_dreamweaver_page() {
+        let html = r#"<html><head><meta name="generator" content="Adobe Dreamweaver CS3"></head>
+            <frameset cols="180,*"><frame src="menu.htm" name="menu"><frame src="main.htm" name="main"></frameset>
+            </html>"#;
+        assert_eq!(legacy_flags(html), ["frameset", "dreamweaver_generator"]);
+    }
+
+    #[test]
+    fn modern_page_with_a_pricing_table_has_no_flags() {
+        let html = r#"<!doctype html><html lang="en"><head>
+            <meta name="generator" content="WordPress 6.4.2">
+            <link rel="stylesheet" href="https://cdn.jsdelivr.net/npm/bootstrap@5.3.2/dist/css/bootstrap.min.css">
+            </head><body><div class="container">
+            <table width="100%" class="table table-striped"><thead><tr><th>Service</th><th>Price</th></tr></thead>
+              <tbody><tr><td><table width="100%"><tr><td>Blocked drain</td><td>1 hour</td></tr></table></td><td>$180</td></tr>
+              <tr><td>Tap washer</td><td>$85</td></tr></tbody></table>
+            <img src="data:image/gif;base64,R0lGODlhAQABAAAAACw=" data-src="/img/team.jpg" alt="Team">
+            <img src="/img/clear.gif" data-lazy-src="/img/van.jpg" alt="Van">
+            <!-- <marquee>Old banner</marquee> <embed src="intro.swf"> -->
+            <a href="https://www.youtube.com/watch?v=abc">Video</a></div></body></html>"#;
+        assert!(legacy_flags(html).is_empty());
+
+        // Without a CSS framework a single data table still isn't layout
+        let html = r#"<table width="100%"><tr><th>Service</th><th>Price</th></tr>
+            <tr><td>Blocked drain</td><td>$180</td></tr></table>"#;
+        assert!(legacy_flags(html).is_empty());
+    }
+
+    // Mixed content
+
+    #[test]
+    fn active_mixed_content() {
+        let html = r#"<head><script src="http://cdn.example.com/jquery.js"></script>
+            <link rel="stylesheet" href="HTTP://fonts.example.com/css?family=Roboto">
+            <link rel="preload" as="script" href="http://cdn.example.com/app.js">
+            <style>@import url("http://cdn.example.com/theme.css"); @import 'http://cdn.example.com/print.css';</style>
+            </head><body><iframe src="http://maps.example.com/embed"></iframe>
+            <object data="http://cdn.example.com/intro.swf"></object></body>"#;
+        let mixed = mixed_content_of(html);
+        assert_eq!((mixed.active, mixed.passive), (7, 0));
+        assert_eq!(mixed.samples[0], "http://cdn.example.com/jquery.js");
+    }
+
+    #[test]
+    fn passive_mixed_content() {
+        let html = r#"<link rel="icon" href="http://acme.com.au/favicon.ico">
+            <img src="http://acme.com.au/logo.png" srcset="http://acme.com.au/logo.png 1x, https://acme.com.au/logo@2x.png 2x">
+            <video poster="/poster.jpg"><source src="http://media.example.com/tour.mp4" type="video/mp4"></video>
+            <div style="background-image: url('http://acme.com.au/hero.jpg')"></div>
+            <style>.card { background: url(http://acme.com.au/card.png) }</style>"#;
+        let mixed = mixed_content_of(html);
+        assert_eq!((mixed.active, mixed.passive), (0, 6));
+        assert_eq!(
+            mixed.samples,
+            strings(&[
+                "http://acme.com.au/favicon.ico",
+                "http://acme.com.au/logo.png",
+                "http://media.example.com/tour.mp4",
+                "http://acme.com.au/hero.jpg",
+                "http://acme.com.au/card.png",
+            ])
+        );
+    }
+
+    #[test]
+    fn references_that_do_not_load_are_not_mixed_content() {
+        let html = r#"<head><link rel="canonical" href="http://acme.com.au/">
+            <link rel="alternate" hreflang="en" href="http://acme.com.au/en/">
+            <meta property="og:url" content="http://acme.com.au/">
+            <meta property="og:image" content="http://acme.com.au/share.jpg"></head>
+            <body><a href="http://partner.example.com/">Our partner</a>
+            <!-- <script src="http://old.example.com/tracker.js"></script> -->
+            <img src="https://acme.com.au/logo.png"><img src="//cdn.acme.com.au/van.jpg"><img src="/team.jpg">
+            <script src="https://cdn.example.com/app.js"></script></body>"#;
+        assert_eq!(mixed_content_of(html), MixedContent::default());
+    }
+
+    #[test]
+    fn mixed_content_samples_are_capped() {
+        let html = (0..15)
+            .map(|i| format!(r#"<img src="http://acme.com.au/{i}.jpg"><img src="http://acme.com.au/{i}.jpg">"#))
+            .collect::<String>();
+        let mixed = mixed_content_of(&html);
+        assert_eq!(mixed.passive, 30);
+        assert_eq!(mixed.samples.len(), MAX_MIXED_CONTENT_SAMPLES);
+
+        // Only an https page can have mixed content
+        assert_eq!(
+            tech_stack_of(&html, Some("http://acme.com.au/")).mixed_content,
+            None
+        );
+        assert_eq!(tech_stack_of(&html, None).mixed_content, None);
+        assert_eq!(
+            tech_stack_of(&html, Some("https://acme.com.au/")).mixed_content,
+            Some(mixed)
+        );
+    }
+
+    // Scheme signals
+
+    /// (has_ssl, final_domain, canonical_scheme_mismatch)
+    fn scheme_signals(
+        html: &str,
+        final_url: Option<&str>,
+    ) -> (Option<bool>, Option<String>, Option<bool>) {
+        let stack = tech_stack_of(html, final_url);
+        (
+            stack.has_ssl,
+            stack.final_domain,
+            stack.canonical_scheme_mismatch,
+        )
+    }
+
+    #[test]
+    fn scheme_signals_are_none_without_a_url() {
+        let html = r#"<link rel="canonical" href="https://acme.com.au/">"#;
+        assert_eq!(scheme_signals(html, None), (None, None, None));
+    }
+
+    #[test]
+    fn has_ssl_and_final_domain_from_the_final_url() {
+        assert_eq!(
+            scheme_signals("<p>Hi</p>", Some("https://www.Acme.com.au/contact?ref=gbp")),
+            (Some(true), Some("acme.com.au".to_string()), Some(false))
+        );
+        assert_eq!(
+            scheme_signals("<p>Hi</p>", Some("  HTTP://shop.acme.com.au:8080/")),
+            (
+                Some(false),
+                Some("shop.acme.com.au".to_string()),
+                Some(false)
+            )
+        );
+        // A redirect to a parked or sold domain shows up as another host
+        assert_eq!(
+            scheme_signals(
+                "",
+                Some("https://www.hugedomains.com/domain_profile.cfm?d=acme")
+            )
+            .1
+            .as_deref(),
+            Some("hugedomains.com")
+        );
+    }
+
+    #[test]
+    fn canonical_scheme_mismatch_either_way() {
+        let https_canonical = r#"<link rel="canonical" href="https://acme.com.au/">"#;
+        let http_og_url = r#"<meta property="og:url" content="http://acme.com.au/">"#;
+        assert_eq!(
+            scheme_signals(https_canonical, Some("http://acme.com.au/")).2,
+            Some(true)
+        );
+        assert_eq!(
+            scheme_signals(https_canonical, Some("https://acme.com.au/")).2,
+            Some(false)
+        );
+        assert_eq!(
+            scheme_signals(http_og_url, Some("https://acme.com.au/")).2,
+            Some(true)
+        );
+        assert_eq!(
+            scheme_signals(http_og_url, Some("http://acme.com.au/")).2,
+            Some(false)
+        );
+
+        for html in [
+            r#"<link rel="canonical" href="/about/">"#,
+            r#"<link rel="canonical" href="//acme.com.au/">"#,
+            r#"<!-- <link rel="canonical" href="http://acme.com.au/"> -->"#,
+            r#"<a href="http://acme.com.au/">Home</a>"#,
+        ] {
+            assert_eq!(
+                scheme_signals(html, Some("https://acme.com.au/")).2,
+                Some(false),
+                "{}",
+                html
+            );
+        }
+    }
+
+    // Image audit
+
+    fn images(html: &str) -> ImageAudit {
+        image_audit_in(&Html::parse_document(html))
+    }
+
+    fn image_counts(audit: &ImageAudit) -> (usize, usize, usize, usize, usize) {
+        (
+            audit.image_count,
+            audit.missing_alt,
+            audit.lazy_loaded,
+            audit.webp_or_avif,
+            audit.srcset,
+        )
+    }
+
+    #[test]
+    fn image_audit_reads_srcset_picture_and_lazy_loading() {
+        let audit = images(
+            r#"<picture>
+              <source type="image/avif" srcset="/img/hero.avif 1x, /img/hero@2x.avif 2x">
+              <img src="/img/hero.jpg" alt="Hero">
+            </picture>
+            <picture><source srcset="/img/van.webp"><img src="/img/van.jpg"></picture>
+            <img src="/img/team.jpg" srcset="/img/team-640.jpg 640w, /img/team-1280.jpg 1280w"
+                 alt="Team" loading="lazy">
+            <img data-src="/img/job.jpg" src="/img/placeholder.gif" alt="">
+            <img src="/img/logo.png?v=2.webp" alt="Logo" loading="eager">"#,
+        );
+        // Only the van has no alt; lazy by attribute and by data-src; avif
+        // by source type, webp by source extension; the logo's ".webp" is
+        // in the query string
+        assert_eq!(image_counts(&audit), (5, 1, 2, 2, 3));
+        assert_eq!((audit.inline_base64, audit.background_images), (0, 0));
+
+        assert_eq!(
+            srcset_urls("/a.webp 1x, /b.webp 2x"),
+            ["/a.webp", "/b.webp"]
+        );
+        assert_eq!(
+            srcset_urls("data:image/webp;base64,UklGRg== 1x"),
+            ["data:image/webp;base64,UklGRg=="]
+        );
+    }
+
+    #[test]
+    fn image_audit_measures_base64_heroes() {
+        assert_eq!(base64_data_uri_bytes("data:image/png;base64,QUJD"), Some(3));
+        assert_eq!(
+            base64_data_uri_bytes(" DATA:image/gif;base64,QQ== "),
+            Some(1)
+        );
+        assert_eq!(base64_data_uri_bytes("data:image/svg+xml,<svg/>"), None);
+        assert_eq!(base64_data_uri_bytes("/img/hero.png"), None);
+
+        let hero = "A".repeat(40_000);
+        let audit = images(&format!(
+            r#"<img src="data:image/png;base64,{hero}" alt="Hero">
+            <img src="/img/a.jpg" srcset="data:image/webp;base64,QUJD 1x" alt="A">
+            <div style="background-image: url('data:image/jpeg;base64,{hero}{hero}')"></div>
+            <section style="color: red; background: #fff url(/img/bg.jpg) no-repeat"></section>"#
+        ));
+        assert_eq!(image_counts(&audit), (2, 0, 0, 1, 1));
+        // Each data: URI counts once; the largest is the background's
+        assert_eq!(audit.inline_base64, 3);
+        assert_eq!(audit.largest_inline_base64_bytes, 60_000);
+        assert_eq!(audit.background_images, 2);
+
+        let audit = seo_audit_of(
+            &good_seo_page().replace("/img/van.jpg", &format!("data:image/png;base64,{hero}")),
+            None,
+        );
+        assert_eq!(audit.issues, ["large_inline_image"]);
+    }
+
+    #[test]
+    fn image_audit_leaves_out_tracking_pixels() {
+        let audit = images(
+            r#"<img height="1" width="1" style="display:none"
+                 src="https://www.facebook.com/tr?id=123&ev=PageView&noscript=1">
+            <img src="https://px.ads.linkedin.com/collect/?pid=1" width="1px" height="1px">
+            <img src="https://stats.g.doubleclick.net/p.gif" width="0" height="0">
+            <img src="https://cdn.acme.com.au/dot.gif" width="1" height="1">
+            <img src="https://www.facebook.com/images/logo.png" width="120" height="40">"#,
+        );
+        // A 1x1 from our own CDN and a full-size image from a tracker host
+        // are still content images
+        assert_eq!(image_counts(&audit), (2, 2, 0, 0, 0));
+
+        // Five images is the threshold for the format checks
+        let gallery = |n: usize| {
+            (0..n)
+                .map(|i| format!(r#"<img src="/img/{i}.jpg" alt="Job {i}">"#))
+                .collect::<String>()
+        };
+        let issues =
+            |html: String| seo_audit_of(&good_seo_page().replace("<p>", &html), None).issues;
+        assert_eq!(
+            issues(gallery(SEO_IMAGE_FORMAT_MIN_IMAGES - 3)),
+            Vec::<&str>::new()
+        );
+        assert_eq!(
+            issues(gallery(SEO_IMAGE_FORMAT_MIN_IMAGES - 2)),
+            [
+                "no_modern_image_formats",
+                "no_responsive_images",
+                "no_lazy_loading"
+            ]
+        );
+    }
+
+    // Page weight
+
+    fn weight_counts(weight: &PageWeight) -> (usize, usize, usize, usize, usize) {
+        (
+            weight.external_scripts,
+            weight.external_styles,
+            weight.inline_script_bytes,
+            weight.inline_style_bytes,
+            weight.font_files,
+        )
+    }
+
+    const SCRIPT_HEAVY_PAGE: &str = r#"<html><head>
+        <link rel="canonical" href="https://www.acmeplumbing.com.au/">
+        <script src="https://www.googletagmanager.com/gtm.js?id=GTM-ABC123"></script>
+        <script src="https://code.jquery.com/jquery-3.7.1.min.js"></script>
+        <script src="https://cdn.jsdelivr.net/npm/bootstrap@5.3.2/dist/js/bootstrap.min.js"></script>
+        <script src="https://static.acmeplumbing.com.au/js/app.js"></script>
+        <script src="/js/site.js" defer></script>
+        <script>var dataLayer = [];</script>
+        <link rel="stylesheet" href="https://fonts.googleapis.com/css2?family=Inter">
+        <link rel="stylesheet" href="/css/site.css">
+        <link rel="preload" as="font" href="https://fonts.gstatic.com/s/inter/v12/inter.woff2" crossorigin>
+        <link rel="alternate" hreflang="en" href="https://www.acmeplumbing.co.nz/">
+        <style>@font-face { src: url(/fonts/icons.woff2) format("woff2"), url('/fonts/icons.ttf'); }
+        .hero { background: url(/fonts/icons.woff2); }</style>
+        <!-- <script src="https://old-tracker.example.org/t.js"></script> -->
+        </head><body>
+        <div style="color:red">Hi</div>
+        <a href="https://twitter.com/acmeplumbing">Twitter</a>
+        <iframe src="https://www.youtube.com/embed/abc123"></iframe>
+        <img src="//i.ytimg.com/vi/abc123/hqdefault.jpg" alt="">
+        <img src="https://cdn.jsdelivr.net/gh/acme/logo.png" alt="">
+        <script src="https://connect.facebook.net/en_US/fbevents.js" async></script>
+        </body></html>"#;
+
+    #[test]
+    fn page_weight_of_a_script_heavy_page() {
+        let weight = page_weight_of(SCRIPT_HEAVY_PAGE, None);
+        let inline_css = SCRIPT_HEAVY_PAGE
+            .split("<style>")
+            .nth(1)
+            .and_then(|rest| rest.split("</style>").next())
+            .unwrap()
+            .len();
+        // Deferred and async scripts count, the commented-out one doesn't;
+        // fonts are deduplicated across the preload and the inline CSS
+        assert_eq!(
+            weight_counts(&weight),
+            (
+                6,
+                2,
+                "var dataLayer = [];".len(),
+                inline_css + "color:red".len(),
+                3
+            )
+        );
+        // The canonical's domain (and its subdomains) is first party; page
+        // links and alternates don't load anything
+        assert_eq!(
+            weight.third_party_hosts,
+            strings(&[
+                "googletagmanager.com",
+                "jquery.com",
+                "jsdelivr.net",
+                "googleapis.com",
+                "gstatic.com",
+                "youtube.com",
+                "ytimg.com",
+                "facebook.net",
+            ])
+        );
+        assert_eq!(weight.html_bytes, SCRIPT_HEAVY_PAGE.len());
+
+        // page_domain wins over the canonical
+        let weight = page_weight_of(SCRIPT_HEAVY_PAGE, Some("https://jquery.com/"));
+        assert!(weight
+            .third_party_hosts
+            .contains(&"acmeplumbing.com.au".to_string()));
+        assert!(!weight.third_party_hosts.contains(&"jquery.com".to_string()));
+    }
+
+    #[test]
+    fn page_weight_of_a_clean_page() {
+        let html = r#"<html><head><title>Acme</title>
+            <link rel="stylesheet" href="/css/site.css">
+            <link rel="icon" href="https://www.acme.com.au/favicon.ico">
+            <script src="/js/site.js" defer></script>
+            </head><body><h1>Acme</h1><img src="/img/van.jpg" alt="Van"></body></html>"#;
+        let weight = page_weight_of(html, Some("acme.com.au"));
+        assert_eq!(weight_counts(&weight), (1, 1, 0, 0, 0));
+        assert_eq!(weight.third_party_hosts, Vec::<String>::new());
+        assert_eq!(weight.html_bytes, html.len());
+
+        // Without a page domain or canonical, every absolute host is foreign
+        let weight = page_weight_of(html, None);
+        assert_eq!(weight.third_party_hosts, strings(&["acme.com.au"]));
+    }
+
+    #[test]
+    fn page_weight_caps_third_party_hosts() {
+        let html: String = (0..MAX_THIRD_PARTY_HOSTS + 10)
+            .map(|i| format!(r#"<script src="https://cdn{i}.example{i}.com/a.js"></script>"#))
+            .collect();
+        let weight = page_weight_of(&html, None);
+        assert_eq!(weight.external_scripts, MAX_THIRD_PARTY_HOSTS + 10);
+        assert_eq!(weight.third_party_hosts.len(), MAX_THIRD_PARTY_HOSTS);
+        assert_eq!(weight.third_party_hosts[0], "example0.com");
+        assert_eq!(weight.third_party_hosts[49], "example49.com");
+    }
+
+    // Render-blocking resources
+
+    fn render_blocking(html: &str) -> (usize, usize, bool, Vec<String>) {
+        let blocking = render_blocking_in(&Html::parse_document(html));
+        (
+            blocking.scripts,
+            blocking.styles,
+            blocking.inlined_critical_css,
+            blocking.sample,
+        )
+    }
+
+    #[test]
+    fn render_blocking_honours_defer_async_and_module() {
+        let html = r#"<html><head>
+            <script src="/js/jquery.js"></script>
+            <script src="/js/legacy.js" type="text/javascript"></script>
+            <script src="/js/app.js" defer></script>
+            <script src="/js/analytics.js" async></script>
+            <script src="/js/main.mjs" type="module"></script>
+            <script src="/js/nomodule.js" nomodule defer></script>
+            <script src="/data/menu.json" type="application/json"></script>
+            <script>var inline = true;</script>
+            <link rel="stylesheet" href="/css/site.css">
+            <link rel="stylesheet" href="/css/screen.css" media="screen">
+            <link rel="stylesheet" href="/css/print.css" media="print">
+            <link rel="alternate stylesheet" href="/css/contrast.css">
+            <link rel="preload" as="style" href="/css/later.css">
+            <link rel="prefetch" href="/js/next.js">
+            <style>.hero { color: red; }</style>
+            </head><body>
+            <script src="/js/footer.js"></script>
+            <link rel="stylesheet" href="/css/footer.css">
+            </body></html>"#;
+        assert_eq!(
+            render_blocking(html),
+            (
+                2,
+                2,
+                false,
+                strings(&[
+                    "/js/jquery.js",
+                    "/js/legacy.js",
+                    "/css/site.css",
+                    "/css/screen.css"
+                ])
+            )
+        );
+    }
+
+    #[test]
+    fn render_blocking_flags_inlined_critical_css() {
+        let style = |bytes: usize| {
+            format!(
+                "<html><head><style>{}</style></head><body><style>{}</style></body></html>",
+                "a".repeat(bytes),
+                "b".repeat(CRITICAL_CSS_MIN_BYTES * 2)
+            )
+        };
+        // Only head styles count, and only over 2 KB
+        assert!(!render_blocking(&style(CRITICAL_CSS_MIN_BYTES)).2);
+        assert!(render_blocking(&style(CRITICAL_CSS_MIN_BYTES + 1)).2);
+    }
+
+    #[test]
+    fn render_blocking_without_a_head() {
+        // Resources before the first body content land in the implied head
+        let html = r#"<script src="/js/a.js"></script><link rel=stylesheet href=/css/a.css>
+            <p>Welcome</p><script src="/js/b.js"></script>"#;
+        assert_eq!(
+            render_blocking(html),
+            (1, 1, false, strings(&["/js/a.js", "/css/a.css"]))
+        );
+        for malformed in [
+            "",
+            "</head></head><head>",
+            "<head><script src=",
+            "<<<script src='/x.js'>>>",
+        ] {
+            render_blocking(malformed);
+        }
+
+        let many: String = (0..MAX_RENDER_BLOCKING_SAMPLES + 5)
+            .map(|i| format!(r#"<script src="/js/{i}.js"></script>"#))
+            .collect();
+        let (scripts, _, _, sample) = render_blocking(&format!("<head>{many}</head>"));
+        assert_eq!(scripts, MAX_RENDER_BLOCKING_SAMPLES + 5);
+        assert_eq!(sample.len(), MAX_RENDER_BLOCKING_SAMPLES);
+    }
+
+    // Cloudflare email protection
+
+    fn cf_encode(email: &str, key: u8) -> String {
+        std::iter::once(key)
+            .chain(email.bytes().map(|b| b ^ key))
+            .map(|b| format!("{:02x}", b))
+            .collect()
+    }
+
+    #[test]
+    fn cloudflare_protected_emails_are_decoded() {
+        let encoded = cf_encode("Info@AcmePlumbing.com.au", 0x8b);
+        assert_eq!(
+            decode_cfemail(&encoded).as_deref(),
+            Some("info@acmeplumbing.com.au")
+        );
+        assert_eq!(
+            decode_cfemail(&encoded.to_ascii_uppercase()).as_deref(),
+            Some("info@acmeplumbing.com.au")
+        );
+
+        let html = format!(
+            r#"<p>Email <a href="/cdn-cgi/l/email-protection" class="__cf_email__"
+                data-cfemail="{}">[email&#160;protected]</a></p>
+            <a href="/cdn-cgi/l/email-protection#{}">Accounts</a>"#,
+            encoded,
+            cf_encode("accounts@acmeplumbing.com.au", 0x3f)
+        );
+        assert_eq!(
+            email_hits(&html)
+                .into_iter()
+                .map(|(email, source, _)| (email, source))
+                .collect::<Vec<_>>(),
+            [
+                ("info@acmeplumbing.com.au".to_string(), "attribute"),
+                ("accounts@acmeplumbing.com.au".to_string(), "attribute"),
+            ]
+        );
+    }
+
+    #[test]
+    fn malformed_cloudflare_payloads_are_ignored() {
+        for hex in [
+            "",
+            "8b",
+            &cf_encode("info@acme.com.au", 0x8b)[1..],
+            "8bzz",
+            &cf_encode("not an email", 0x42),
+            &cf_encode("info@acme", 0x42),
+            "8b4b",
+        ] {
+            assert_eq!(decode_cfemail(hex), None, "{}", hex);
+        }
+        let html = format!(
+            r#"<span class="__cf_email__" data-cfemail="{}">[email protected]</span>
+            <span class="__cf_email__" data-cfemail="abc">[email protected]</span>"#,
+            cf_encode("hello world", 0x10)
+        );
+        assert_eq!(extract_emails(&html), Vec::<String>::new());
+    }
+
+    #[test]
+    fn cloudflare_emails_rank_ahead_of_the_result_cap() {
+        let text_emails: String = (1..=6)
+            .map(|i| format!("<p>staff{}@acme.com.au</p>", i))
+            .collect();
+        let html = format!(
+            r#"{}<footer><a class="__cf_email__" data-cfemail="{}">[email protected]</a>
+            <a href="/cdn-cgi/l/email-protection#{}">Email</a></footer>"#,
+            text_emails,
+            cf_encode("owner@acme.com.au", 0x5a),
+            cf_encode("owner@acme.com.au", 0x77)
+        );
+        let emails = extract_emails(&html);
+        assert_eq!(emails.len(), 5);
+        // Both encodings decode to one address, listed before text hits
+        assert_eq!(emails[0], "owner@acme.com.au");
+        assert_eq!(
+            &emails[1..],
+            [
+                "staff1@acme.com.au",
+                "staff2@acme.com.au",
+                "staff3@acme.com.au",
+                "staff4@acme.com.au"
+            ]
+        );
+    }
+}
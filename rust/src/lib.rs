@@ -1,5 +1,6 @@
 use pyo3::prelude::*;
 
+mod dedup;
 mod export;
 mod geo;
 mod html;
@@ -7,39 +8,166 @@ mod metadata;
 mod scoring;
 mod text;
 
+#[cfg(feature = "bench")]
+pub use html::{bench_signature_detectors, bench_tech_stack};
+
 /// Native performance extensions for LeadSwarm.
 #[pymodule]
 fn _leadswarm_native(m: &Bound<'_, PyModule>) -> PyResult<()> {
     m.add_function(wrap_pyfunction!(text::normalize_domain, m)?)?;
+    m.add_function(wrap_pyfunction!(text::normalize_domains_batch, m)?)?;
+    m.add_function(wrap_pyfunction!(text::register_extra_tlds, m)?)?;
+    m.add_function(wrap_pyfunction!(text::unique_domains, m)?)?;
+    m.add_function(wrap_pyfunction!(text::canonicalize_url, m)?)?;
+    m.add_function(wrap_pyfunction!(text::canonicalize_urls_batch, m)?)?;
+    m.add_function(wrap_pyfunction!(text::display_domain, m)?)?;
+    m.add_function(wrap_pyfunction!(text::canonical_domain, m)?)?;
     m.add_function(wrap_pyfunction!(text::normalize_name, m)?)?;
     m.add_function(wrap_pyfunction!(text::clean_business_name, m)?)?;
+    m.add_function(wrap_pyfunction!(text::parse_business_name, m)?)?;
+    m.add_function(wrap_pyfunction!(text::strip_location_from_name, m)?)?;
+    m.add_function(wrap_pyfunction!(text::register_localities, m)?)?;
+    m.add_function(wrap_pyfunction!(text::detect_franchise, m)?)?;
+    m.add_function(wrap_pyfunction!(text::register_franchise_brands, m)?)?;
+    m.add_function(wrap_pyfunction!(text::is_placeholder_name, m)?)?;
+    m.add_function(wrap_pyfunction!(text::placeholder_name_reason, m)?)?;
+    m.add_function(wrap_pyfunction!(text::is_placeholder_name_batch, m)?)?;
+    m.add_function(wrap_pyfunction!(text::slugify, m)?)?;
+    m.add_function(wrap_pyfunction!(text::slugify_unique, m)?)?;
+    m.add_function(wrap_pyfunction!(text::name_from_domain, m)?)?;
+    m.add_function(wrap_pyfunction!(text::parse_au_address, m)?)?;
+    m.add_function(wrap_pyfunction!(text::normalize_au_address, m)?)?;
+    m.add_function(wrap_pyfunction!(text::extract_postcode_state, m)?)?;
+    m.add_function(wrap_pyfunction!(text::extract_postcode_state_batch, m)?)?;
+    m.add_function(wrap_pyfunction!(text::name_tokens, m)?)?;
+    m.add_function(wrap_pyfunction!(text::set_name_stopwords, m)?)?;
+    m.add_function(wrap_pyfunction!(text::list_name_stopwords, m)?)?;
+    m.add_function(wrap_pyfunction!(text::name_similarity, m)?)?;
+    m.add_function(wrap_pyfunction!(text::names_match, m)?)?;
+    m.add_function(wrap_pyfunction!(text::match_names, m)?)?;
     m.add_function(wrap_pyfunction!(text::normalize_phone, m)?)?;
     m.add_function(wrap_pyfunction!(text::is_directory_domain, m)?)?;
+    m.add_function(wrap_pyfunction!(text::classify_domain, m)?)?;
     m.add_function(wrap_pyfunction!(text::is_directory_url, m)?)?;
+    m.add_function(wrap_pyfunction!(text::register_directory_domains, m)?)?;
+    m.add_function(wrap_pyfunction!(text::register_directory_url_patterns, m)?)?;
+    m.add_function(wrap_pyfunction!(text::list_directory_domains, m)?)?;
+    m.add_function(wrap_pyfunction!(text::list_directory_url_patterns, m)?)?;
+    m.add_function(wrap_pyfunction!(text::reset_directory_overrides, m)?)?;
+    m.add_function(wrap_pyfunction!(text::registrable_domain, m)?)?;
+    m.add_function(wrap_pyfunction!(text::split_domain, m)?)?;
     m.add_function(wrap_pyfunction!(text::validate_email_domain, m)?)?;
+    m.add_function(wrap_pyfunction!(text::validate_email_domain_legacy, m)?)?;
     m.add_function(wrap_pyfunction!(text::filter_emails_for_domain, m)?)?;
+    m.add_function(wrap_pyfunction!(
+        text::filter_emails_for_domain_detailed,
+        m
+    )?)?;
+    m.add_function(wrap_pyfunction!(text::dedupe_and_rank_emails, m)?)?;
+    m.add_function(wrap_pyfunction!(text::validate_email_syntax, m)?)?;
+    m.add_function(wrap_pyfunction!(text::is_disposable_email, m)?)?;
+    m.add_function(wrap_pyfunction!(text::register_disposable_domains, m)?)?;
+    m.add_function(wrap_pyfunction!(text::register_generic_providers, m)?)?;
+    m.add_function(wrap_pyfunction!(text::list_generic_providers, m)?)?;
+    m.add_function(wrap_pyfunction!(text::classify_email, m)?)?;
+    m.add_function(wrap_pyfunction!(text::classify_emails_batch, m)?)?;
+    m.add_function(wrap_pyfunction!(text::infer_name_from_email, m)?)?;
+    m.add_function(wrap_pyfunction!(text::infer_name_from_email_batch, m)?)?;
 
     m.add_function(wrap_pyfunction!(html::extract_emails, m)?)?;
+    m.add_function(wrap_pyfunction!(html::extract_emails_detailed, m)?)?;
     m.add_function(wrap_pyfunction!(html::extract_phones, m)?)?;
+    m.add_function(wrap_pyfunction!(html::extract_contacts, m)?)?;
+    m.add_function(wrap_pyfunction!(html::extract_social_links, m)?)?;
+    m.add_function(wrap_pyfunction!(html::detect_messaging_links, m)?)?;
+    m.add_function(wrap_pyfunction!(html::detect_gbp_links, m)?)?;
+    m.add_function(wrap_pyfunction!(html::detect_map_embed, m)?)?;
+    m.add_function(wrap_pyfunction!(html::detect_video, m)?)?;
+    m.add_function(wrap_pyfunction!(html::detect_contact_form, m)?)?;
+    m.add_function(wrap_pyfunction!(html::detect_email_capture, m)?)?;
+    m.add_function(wrap_pyfunction!(html::detect_captcha, m)?)?;
+    m.add_function(wrap_pyfunction!(html::extract_phones_with_labels, m)?)?;
     m.add_function(wrap_pyfunction!(html::detect_cms, m)?)?;
+    m.add_function(wrap_pyfunction!(html::detect_cms_detailed, m)?)?;
+    m.add_function(wrap_pyfunction!(html::detect_cms_version, m)?)?;
+    m.add_function(wrap_pyfunction!(html::detect_page_builder, m)?)?;
+    m.add_function(wrap_pyfunction!(html::detect_wp_theme, m)?)?;
+    m.add_function(wrap_pyfunction!(html::detect_wp_plugins, m)?)?;
+    m.add_function(wrap_pyfunction!(html::detect_ecommerce, m)?)?;
+    m.add_function(wrap_pyfunction!(html::detect_payment_providers, m)?)?;
+    m.add_function(wrap_pyfunction!(html::detect_chat_widget, m)?)?;
+    m.add_function(wrap_pyfunction!(html::detect_marketing_tools, m)?)?;
+    m.add_function(wrap_pyfunction!(html::detect_review_widgets, m)?)?;
+    m.add_function(wrap_pyfunction!(html::detect_testimonials, m)?)?;
+    m.add_function(wrap_pyfunction!(html::detect_hiring_signals, m)?)?;
+    m.add_function(wrap_pyfunction!(html::detect_pricing, m)?)?;
+    m.add_function(wrap_pyfunction!(html::detect_blog, m)?)?;
+    m.add_function(wrap_pyfunction!(html::detect_languages, m)?)?;
+    m.add_function(wrap_pyfunction!(html::detect_app_links, m)?)?;
+    m.add_function(wrap_pyfunction!(html::register_cms_signature, m)?)?;
+    m.add_function(wrap_pyfunction!(html::load_cms_signatures, m)?)?;
+    m.add_function(wrap_pyfunction!(html::list_cms_signatures, m)?)?;
+    m.add_function(wrap_pyfunction!(html::reset_cms_signatures, m)?)?;
     m.add_function(wrap_pyfunction!(html::detect_tracking, m)?)?;
+    m.add_function(wrap_pyfunction!(html::extract_tracking_ids, m)?)?;
     m.add_function(wrap_pyfunction!(html::detect_booking_system, m)?)?;
+    m.add_function(wrap_pyfunction!(html::detect_booking_system_detailed, m)?)?;
+    m.add_function(wrap_pyfunction!(html::register_booking_signatures, m)?)?;
     m.add_function(wrap_pyfunction!(html::detect_frameworks, m)?)?;
+    m.add_function(wrap_pyfunction!(html::detect_outdated_libraries, m)?)?;
+    m.add_function(wrap_pyfunction!(html::register_library_latest, m)?)?;
+    m.add_function(wrap_pyfunction!(html::detect_legacy_tech, m)?)?;
     m.add_function(wrap_pyfunction!(html::detect_responsive, m)?)?;
+    m.add_function(wrap_pyfunction!(html::detect_responsive_detailed, m)?)?;
+    m.add_function(wrap_pyfunction!(html::detect_amp, m)?)?;
+    m.add_function(wrap_pyfunction!(html::detect_mdot_reference, m)?)?;
+    m.add_function(wrap_pyfunction!(html::detect_pwa, m)?)?;
+    m.add_function(wrap_pyfunction!(html::detect_parked_domain, m)?)?;
+    m.add_function(wrap_pyfunction!(html::detect_placeholder_page, m)?)?;
+    m.add_function(wrap_pyfunction!(html::detect_soft_404, m)?)?;
+    m.add_function(wrap_pyfunction!(html::extract_address, m)?)?;
+    m.add_function(wrap_pyfunction!(html::detect_directory_page, m)?)?;
+    m.add_function(wrap_pyfunction!(html::extract_headings, m)?)?;
+    m.add_function(wrap_pyfunction!(html::extract_visible_text, m)?)?;
+    m.add_function(wrap_pyfunction!(html::visible_word_count, m)?)?;
+    m.add_function(wrap_pyfunction!(html::analyze_content_depth, m)?)?;
+    m.add_function(wrap_pyfunction!(html::detect_content_language, m)?)?;
+    m.add_function(wrap_pyfunction!(html::extract_copyright_year, m)?)?;
+    m.add_function(wrap_pyfunction!(html::is_copyright_stale, m)?)?;
+    m.add_function(wrap_pyfunction!(html::extract_meta_seo, m)?)?;
+    m.add_function(wrap_pyfunction!(html::seo_audit, m)?)?;
+    m.add_function(wrap_pyfunction!(html::analyze_images, m)?)?;
     m.add_function(wrap_pyfunction!(html::analyze_tech_stack, m)?)?;
+    m.add_function(wrap_pyfunction!(html::detect_mixed_content, m)?)?;
+    m.add_function(wrap_pyfunction!(html::analyze_page_weight, m)?)?;
+    m.add_function(wrap_pyfunction!(html::detect_render_blocking, m)?)?;
+    m.add_function(wrap_pyfunction!(html::analyze_tech_stack_batch, m)?)?;
 
     m.add_function(wrap_pyfunction!(scoring::calculate_fit_score, m)?)?;
     m.add_function(wrap_pyfunction!(scoring::calculate_opportunity_score, m)?)?;
     m.add_function(wrap_pyfunction!(scoring::score_prospects_batch, m)?)?;
 
+    m.add_function(wrap_pyfunction!(dedup::dedupe_prospects, m)?)?;
+
     m.add_function(wrap_pyfunction!(geo::fast_cache_key, m)?)?;
     m.add_function(wrap_pyfunction!(geo::haversine_distance, m)?)?;
     m.add_function(wrap_pyfunction!(geo::batch_haversine, m)?)?;
+    m.add_function(wrap_pyfunction!(geo::infer_country, m)?)?;
+    m.add_function(wrap_pyfunction!(geo::extract_geo, m)?)?;
 
     m.add_function(wrap_pyfunction!(export::serialize_prospects_csv, m)?)?;
     m.add_function(wrap_pyfunction!(export::serialize_prospects_json, m)?)?;
 
     m.add_function(wrap_pyfunction!(metadata::extract_html_metadata, m)?)?;
+    m.add_function(wrap_pyfunction!(
+        metadata::extract_local_business_schema,
+        m
+    )?)?;
+    m.add_function(wrap_pyfunction!(metadata::extract_aggregate_rating, m)?)?;
+    m.add_function(wrap_pyfunction!(metadata::detect_schema_types, m)?)?;
+    m.add_function(wrap_pyfunction!(metadata::extract_og_metadata, m)?)?;
+    m.add_function(wrap_pyfunction!(metadata::best_business_name, m)?)?;
+    m.add_function(wrap_pyfunction!(metadata::extract_opening_hours, m)?)?;
 
     Ok(())
 }
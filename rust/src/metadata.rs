@@ -1,8 +1,17 @@
 use pyo3::prelude::*;
 use pyo3::types::{PyDict, PyList};
-use scraper::{Html, Selector};
+use regex::Regex;
+use scraper::{ElementRef, Html, Selector};
+use serde_json::{Map, Value};
+use std::collections::{HashMap, HashSet};
 use std::sync::LazyLock;
 
+use crate::html::{
+    comment_ranges, decode_html_entities, in_ranges, is_rejected_email, is_whole_email,
+    normalize_phone, stream_visible_text, TITLE_TEXT_RE,
+};
+use crate::text::clean_business_name;
+
 // Social media domains to match against <a href="..."> links
 static SOCIAL_DOMAINS: &[&str] = &[
     "facebook.com",
@@ -82,3 +91,2400 @@ pub fn extract_html_metadata(py: Python<'_>, html: &str) -> PyResult<PyObject> {
 
     Ok(dict.into())
 }
+
+// ---------------------------------------------------------------------------
+// JSON-LD structured data
+// ---------------------------------------------------------------------------
+
+static LD_JSON_RE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(
+        r#"(?is)<script\b[^>]*\btype\s*=\s*["']?application/ld\+json\b[^>]*>(.*?)</script\s*>"#,
+    )
+    .unwrap()
+});
+
+/// LocalBusiness and its schema.org subtypes.
+static LOCAL_BUSINESS_TYPES: &[&str] = &[
+    "LocalBusiness",
+    "AnimalShelter",
+    "ArchiveOrganization",
+    "AutomotiveBusiness",
+    "AutoBodyShop",
+    "AutoDealer",
+    "AutoPartsStore",
+    "AutoRental",
+    "AutoRepair",
+    "AutoWash",
+    "GasStation",
+    "MotorcycleDealer",
+    "MotorcycleRepair",
+    "ChildCare",
+    "Dentist",
+    "DryCleaningOrLaundry",
+    "EmergencyService",
+    "FireStation",
+    "Hospital",
+    "PoliceStation",
+    "EmploymentAgency",
+    "EntertainmentBusiness",
+    "AdultEntertainment",
+    "AmusementPark",
+    "ArtGallery",
+    "Casino",
+    "ComedyClub",
+    "MovieTheater",
+    "NightClub",
+    "FinancialService",
+    "AccountingService",
+    "AutomatedTeller",
+    "BankOrCreditUnion",
+    "InsuranceAgency",
+    "FoodEstablishment",
+    "Bakery",
+    "BarOrPub",
+    "Brewery",
+    "CafeOrCoffeeShop",
+    "Distillery",
+    "FastFoodRestaurant",
+    "IceCreamShop",
+    "Restaurant",
+    "Winery",
+    "GovernmentOffice",
+    "PostOffice",
+    "HealthAndBeautyBusiness",
+    "BeautySalon",
+    "DaySpa",
+    "HairSalon",
+    "HealthClub",
+    "NailSalon",
+    "TattooParlor",
+    "HomeAndConstructionBusiness",
+    "Electrician",
+    "GeneralContractor",
+    "HVACBusiness",
+    "HousePainter",
+    "Locksmith",
+    "MovingCompany",
+    "Plumber",
+    "RoofingContractor",
+    "InternetCafe",
+    "LegalService",
+    "Attorney",
+    "Notary",
+    "Library",
+    "LodgingBusiness",
+    "BedAndBreakfast",
+    "Campground",
+    "Hostel",
+    "Hotel",
+    "Motel",
+    "Resort",
+    "VacationRental",
+    "MedicalBusiness",
+    "MedicalClinic",
+    "Optician",
+    "Pharmacy",
+    "Physician",
+    "Physiotherapy",
+    "ProfessionalService",
+    "RadioStation",
+    "RealEstateAgent",
+    "RecyclingCenter",
+    "SelfStorage",
+    "ShoppingCenter",
+    "SportsActivityLocation",
+    "BowlingAlley",
+    "ExerciseGym",
+    "GolfCourse",
+    "PublicSwimmingPool",
+    "SkiResort",
+    "SportsClub",
+    "StadiumOrArena",
+    "TennisComplex",
+    "Store",
+    "BikeStore",
+    "BookStore",
+    "ClothingStore",
+    "ComputerStore",
+    "ConvenienceStore",
+    "DepartmentStore",
+    "ElectronicsStore",
+    "Florist",
+    "FurnitureStore",
+    "GardenStore",
+    "GroceryStore",
+    "HardwareStore",
+    "HobbyShop",
+    "HomeGoodsStore",
+    "JewelryStore",
+    "LiquorStore",
+    "MensClothingStore",
+    "MobilePhoneStore",
+    "MusicStore",
+    "OfficeEquipmentStore",
+    "OutletStore",
+    "PawnShop",
+    "PetStore",
+    "ShoeStore",
+    "SportingGoodsStore",
+    "TireShop",
+    "ToyStore",
+    "WholesaleStore",
+    "TelevisionStation",
+    "TouristInformationCenter",
+    "TravelAgency",
+];
+
+/// schema.org day names and the two-letter form openingHours uses.
+static DAY_ABBREVIATIONS: &[(&str, &str)] = &[
+    ("Monday", "Mo"),
+    ("Tuesday", "Tu"),
+    ("Wednesday", "We"),
+    ("Thursday", "Th"),
+    ("Friday", "Fr"),
+    ("Saturday", "Sa"),
+    ("Sunday", "Su"),
+    ("PublicHolidays", "PH"),
+];
+
+/// Every top-level JSON-LD value on the page. Blocks that fail to parse or
+/// are commented out are skipped; HTML comment and CDATA wrappers some CMSes
+/// add inside the script are removed first.
+fn ld_json_blocks(html: &str) -> Vec<Value> {
+    let comments = comment_ranges(html);
+    LD_JSON_RE
+        .captures_iter(html)
+        .filter(|caps| !in_ranges(&comments, caps.get(0).map_or(0, |m| m.start())))
+        .filter_map(|caps| {
+            let body = caps[1]
+                .trim()
+                .trim_start_matches("<!--")
+                .trim_end_matches("-->")
+                .trim()
+                .trim_start_matches("//<![CDATA[")
+                .trim_end_matches("//]]>")
+                .trim();
+            serde_json::from_str(body).ok()
+        })
+        .collect()
+}
+
+/// Every object in a JSON-LD value that has an @type, depth first: array
+/// items, @graph members and nested properties (a WebPage's mainEntity).
+fn ld_json_nodes<'a>(value: &'a Value, out: &mut Vec<&'a Map<String, Value>>) {
+    match value {
+        Value::Array(items) => items.iter().for_each(|item| ld_json_nodes(item, out)),
+        Value::Object(object) => {
+            if object.contains_key("@type") {
+                out.push(object);
+            }
+            object.values().for_each(|v| ld_json_nodes(v, out));
+        }
+        _ => {}
+    }
+}
+
+/// Local names of a node's @type ("http://schema.org/Plumber" -> "Plumber").
+fn ld_json_types(node: &Map<String, Value>) -> Vec<&str> {
+    fn local(t: &Value) -> Option<&str> {
+        t.as_str().map(|s| s.rsplit(['/', ':']).next().unwrap_or(s))
+    }
+    match node.get("@type") {
+        Some(Value::Array(types)) => types.iter().filter_map(local).collect(),
+        Some(t) => local(t).into_iter().collect(),
+        None => Vec::new(),
+    }
+}
+
+/// First item of a property that may be a single value or an array.
+fn ld_json_first<'a>(node: &'a Map<String, Value>, key: &str) -> Option<&'a Value> {
+    match node.get(key)? {
+        Value::Array(items) => items.first(),
+        value => Some(value),
+    }
+}
+
+/// An object-valued property, following {"@id": ...} references to the node
+/// defined elsewhere on the page.
+fn ld_json_object<'a>(
+    node: &'a Map<String, Value>,
+    key: &str,
+    ids: &HashMap<&str, &'a Map<String, Value>>,
+) -> Option<&'a Map<String, Value>> {
+    let object = ld_json_first(node, key)?.as_object()?;
+    match object.get("@id").and_then(Value::as_str) {
+        Some(id) if object.len() == 1 => ids.get(id).copied(),
+        _ => Some(object),
+    }
+}
+
+/// A text property, trimmed and entity-decoded; numbers are accepted too.
+fn ld_json_text(node: &Map<String, Value>, key: &str) -> Option<String> {
+    let text = match ld_json_first(node, key)? {
+        Value::String(s) => decode_html_entities(s).trim().to_string(),
+        Value::Number(n) => n.to_string(),
+        _ => return None,
+    };
+    (!text.is_empty()).then_some(text)
+}
+
+/// A decimal that may be written as a string, with a comma as the decimal
+/// separator ("4,8").
+fn parse_decimal(text: &str) -> Option<f64> {
+    text.trim()
+        .replace(',', ".")
+        .parse()
+        .ok()
+        .filter(|n: &f64| n.is_finite())
+}
+
+/// A count that may be written as a string with thousands separators or a
+/// unit ("1,234", "127 reviews"). Negative counts parse as negative so they
+/// can be rejected.
+fn parse_count(text: &str) -> Option<i64> {
+    let text = text.trim();
+    let digits: String = text
+        .trim_start_matches('-')
+        .chars()
+        .take_while(|c| c.is_ascii_digit() || matches!(c, ',' | '.' | ' '))
+        .filter(char::is_ascii_digit)
+        .collect();
+    let count: i64 = digits.parse().ok()?;
+    Some(if text.starts_with('-') { -count } else { count })
+}
+
+fn ld_json_number(node: &Map<String, Value>, key: &str) -> Option<f64> {
+    match ld_json_first(node, key)? {
+        Value::Number(n) => n.as_f64().filter(|n| n.is_finite()),
+        Value::String(s) => parse_decimal(s),
+        _ => None,
+    }
+}
+
+fn ld_json_count(node: &Map<String, Value>, key: &str) -> Option<i64> {
+    match ld_json_first(node, key)? {
+        Value::Number(n) => n.as_i64().or_else(|| n.as_f64().map(|f| f as i64)),
+        Value::String(s) => parse_count(s),
+        _ => None,
+    }
+}
+
+struct AggregateRating {
+    rating: f64,
+    review_count: i64,
+    best_rating: Option<f64>,
+}
+
+/// A validated rating out of 5: ratings on another scale are rescaled by
+/// their bestRating; a rating above its best (or above 5 with none given),
+/// a negative rating or a negative count is rejected. A missing count is 0.
+fn aggregate_rating_of(
+    rating: Option<f64>,
+    review_count: Option<i64>,
+    best_rating: Option<f64>,
+) -> Option<AggregateRating> {
+    let rating = rating?;
+    let review_count = review_count.unwrap_or(0);
+    let best = best_rating.unwrap_or(5.0);
+    if rating < 0.0 || best <= 0.0 || rating > best || review_count < 0 {
+        return None;
+    }
+    let rating = if best == 5.0 {
+        rating
+    } else {
+        (rating / best * 5.0 * 100.0).round() / 100.0
+    };
+    Some(AggregateRating {
+        rating,
+        review_count,
+        best_rating,
+    })
+}
+
+fn ld_json_rating(rating: &Map<String, Value>) -> Option<AggregateRating> {
+    aggregate_rating_of(
+        ld_json_number(rating, "ratingValue"),
+        ld_json_count(rating, "reviewCount").or_else(|| ld_json_count(rating, "ratingCount")),
+        ld_json_number(rating, "bestRating"),
+    )
+}
+
+struct PostalAddress {
+    street: Option<String>,
+    suburb: Option<String>,
+    state: Option<String>,
+    postcode: Option<String>,
+}
+
+struct LocalBusinessSchema {
+    kind: String,
+    name: Option<String>,
+    telephone: Option<String>,
+    email: Option<String>,
+    address: Option<PostalAddress>,
+    geo: Option<(f64, f64)>,
+    opening_hours: Vec<String>,
+    url: Option<String>,
+    price_range: Option<String>,
+    aggregate_rating: Option<AggregateRating>,
+}
+
+impl LocalBusinessSchema {
+    /// How many fields are filled in, to pick the fullest of several nodes.
+    fn completeness(&self) -> usize {
+        [
+            self.name.is_some(),
+            self.telephone.is_some(),
+            self.email.is_some(),
+            self.address.is_some(),
+            self.geo.is_some(),
+            !self.opening_hours.is_empty(),
+            self.url.is_some(),
+            self.price_range.is_some(),
+            self.aggregate_rating.is_some(),
+        ]
+        .iter()
+        .filter(|filled| **filled)
+        .count()
+    }
+}
+
+/// "08:00:00" and "8:00" both become "08:00".
+fn short_time(time: &str) -> String {
+    let mut parts = time.trim().split(':');
+    match (parts.next(), parts.next()) {
+        (Some(h), Some(m)) if h.len() <= 2 && m.len() == 2 => format!("{:0>2}:{}", h, m),
+        _ => time.trim().to_string(),
+    }
+}
+
+/// openingHours strings as given ("Mo-Fr 08:00-17:00"), plus each
+/// openingHoursSpecification rendered the same way ("Mo,Tu 08:00-17:00").
+fn opening_hours_of(
+    node: &Map<String, Value>,
+    ids: &HashMap<&str, &Map<String, Value>>,
+) -> Vec<String> {
+    let mut hours: Vec<String> = Vec::new();
+    match node.get("openingHours") {
+        Some(Value::String(s)) => hours.push(s.trim().to_string()),
+        Some(Value::Array(items)) => hours.extend(
+            items
+                .iter()
+                .filter_map(Value::as_str)
+                .map(|s| s.trim().to_string()),
+        ),
+        _ => {}
+    }
+
+    let specs: Vec<&Map<String, Value>> = match node.get("openingHoursSpecification") {
+        Some(Value::Array(items)) => items.iter().filter_map(Value::as_object).collect(),
+        Some(Value::Object(object)) => vec![object],
+        _ => Vec::new(),
+    };
+    for spec in specs {
+        let spec = match spec.get("@id").and_then(Value::as_str) {
+            Some(id) if spec.len() == 1 => match ids.get(id) {
+                Some(target) => *target,
+                None => continue,
+            },
+            _ => spec,
+        };
+        let days: Vec<&str> = match spec.get("dayOfWeek") {
+            Some(Value::Array(items)) => items.iter().filter_map(Value::as_str).collect(),
+            Some(Value::String(s)) => vec![s.as_str()],
+            _ => Vec::new(),
+        };
+        let days: Vec<&str> = days
+            .iter()
+            .map(|d| {
+                let local = d.rsplit('/').next().unwrap_or(d);
+                DAY_ABBREVIATIONS
+                    .iter()
+                    .find(|(name, _)| name.eq_ignore_ascii_case(local))
+                    .map_or(local, |(_, abbreviation)| *abbreviation)
+            })
+            .collect();
+        let (Some(opens), Some(closes)) =
+            (ld_json_text(spec, "opens"), ld_json_text(spec, "closes"))
+        else {
+            continue;
+        };
+        let times = format!("{}-{}", short_time(&opens), short_time(&closes));
+        hours.push(if days.is_empty() {
+            times
+        } else {
+            format!("{} {}", days.join(","), times)
+        });
+    }
+
+    hours.retain(|h| !h.is_empty());
+    hours.dedup();
+    hours
+}
+
+fn local_business_of(
+    node: &Map<String, Value>,
+    kind: &str,
+    ids: &HashMap<&str, &Map<String, Value>>,
+) -> LocalBusinessSchema {
+    let telephone = ld_json_text(node, "telephone")
+        .map(|phone| normalize_phone(&phone))
+        .filter(|phone| !phone.is_empty());
+    let email = ld_json_text(node, "email")
+        .map(|email| {
+            let email = email.trim_start_matches("mailto:").trim().to_lowercase();
+            email.split('?').next().unwrap_or("").to_string()
+        })
+        .filter(|email| is_whole_email(email) && !is_rejected_email(email));
+
+    let address = match ld_json_first(node, "address") {
+        Some(Value::String(s)) if !s.trim().is_empty() => Some(PostalAddress {
+            street: Some(decode_html_entities(s).trim().to_string()),
+            suburb: None,
+            state: None,
+            postcode: None,
+        }),
+        _ => ld_json_object(node, "address", ids).map(|a| PostalAddress {
+            street: ld_json_text(a, "streetAddress"),
+            suburb: ld_json_text(a, "addressLocality"),
+            state: ld_json_text(a, "addressRegion"),
+            postcode: ld_json_text(a, "postalCode"),
+        }),
+    }
+    .filter(|a| {
+        a.street.is_some() || a.suburb.is_some() || a.state.is_some() || a.postcode.is_some()
+    });
+
+    let geo = ld_json_object(node, "geo", ids).and_then(|g| {
+        let lat = ld_json_number(g, "latitude")?;
+        let lng = ld_json_number(g, "longitude")?;
+        ((-90.0..=90.0).contains(&lat) && (-180.0..=180.0).contains(&lng)).then_some((lat, lng))
+    });
+
+    let aggregate_rating = ld_json_object(node, "aggregateRating", ids).and_then(ld_json_rating);
+
+    LocalBusinessSchema {
+        kind: kind.to_string(),
+        name: ld_json_text(node, "name"),
+        telephone,
+        email,
+        address,
+        geo,
+        opening_hours: opening_hours_of(node, ids),
+        url: ld_json_text(node, "url"),
+        price_range: ld_json_text(node, "priceRange"),
+        aggregate_rating,
+    }
+}
+
+type LdJsonIds<'a> = HashMap<&'a str, &'a Map<String, Value>>;
+
+/// The typed nodes of every block in page order, and the nodes with an @id
+/// (more than a bare reference) by id.
+fn ld_json_index(blocks: &[Value]) -> (Vec<&Map<String, Value>>, LdJsonIds<'_>) {
+    let mut nodes: Vec<&Map<String, Value>> = Vec::new();
+    for block in blocks {
+        ld_json_nodes(block, &mut nodes);
+    }
+    let ids = nodes
+        .iter()
+        .filter(|node| node.len() > 1)
+        .filter_map(|node| Some((node.get("@id")?.as_str()?, *node)))
+        .collect();
+    (nodes, ids)
+}
+
+/// Every LocalBusiness (or subtype) node on the page, in page order.
+fn local_businesses_of(html: &str) -> Vec<LocalBusinessSchema> {
+    let blocks = ld_json_blocks(html);
+    let (nodes, ids) = ld_json_index(&blocks);
+
+    nodes
+        .iter()
+        .filter_map(|node| {
+            let kind = ld_json_types(node)
+                .into_iter()
+                .find(|t| LOCAL_BUSINESS_TYPES.contains(t))?;
+            Some(local_business_of(node, kind, &ids))
+        })
+        .collect()
+}
+
+static LATITUDE_SEL: LazyLock<Selector> =
+    LazyLock::new(|| Selector::parse(r#"[itemprop="latitude"]"#).unwrap());
+static LONGITUDE_SEL: LazyLock<Selector> =
+    LazyLock::new(|| Selector::parse(r#"[itemprop="longitude"]"#).unwrap());
+
+/// Coordinates from schema.org markup: a LocalBusiness's geo first, then
+/// any JSON-LD node's, then microdata latitude/longitude properties.
+/// Out-of-range pairs are skipped.
+pub(crate) fn schema_geo_of(html: &str) -> Option<(f64, f64)> {
+    let valid = |(lat, lng): (f64, f64)| {
+        ((-90.0..=90.0).contains(&lat) && (-180.0..=180.0).contains(&lng)).then_some((lat, lng))
+    };
+    let blocks = ld_json_blocks(html);
+    let (nodes, ids) = ld_json_index(&blocks);
+    let geo_of = |node: &Map<String, Value>| {
+        let g = ld_json_object(node, "geo", &ids)?;
+        valid((
+            ld_json_number(g, "latitude")?,
+            ld_json_number(g, "longitude")?,
+        ))
+    };
+    let is_business = |node: &&&Map<String, Value>| {
+        ld_json_types(node)
+            .into_iter()
+            .any(|t| LOCAL_BUSINESS_TYPES.contains(&t))
+    };
+    if let Some(geo) = nodes
+        .iter()
+        .filter(is_business)
+        .find_map(|node| geo_of(node))
+        .or_else(|| nodes.iter().find_map(|node| geo_of(node)))
+    {
+        return Some(geo);
+    }
+
+    let document = Html::parse_document(html);
+    let value = |element: ElementRef| {
+        let text = match element.value().attr("content") {
+            Some(content) => content.to_string(),
+            None => element.text().collect::<String>(),
+        };
+        parse_decimal(&text)
+    };
+    let lat = document.select(&LATITUDE_SEL).find_map(value)?;
+    let lng = document.select(&LONGITUDE_SEL).find_map(value)?;
+    valid((lat, lng))
+}
+
+static ITEMTYPE_SEL: LazyLock<Selector> = LazyLock::new(|| Selector::parse("[itemtype]").unwrap());
+static AUTHOR_ITEM_SEL: LazyLock<Selector> =
+    LazyLock::new(|| Selector::parse(r#"[itemprop~="author"][itemtype]"#).unwrap());
+
+/// schema.org Reviews with a Person author, the markup hand-rolled
+/// testimonials carry: JSON-LD Review nodes, or microdata Review items when
+/// the page has no JSON-LD ones.
+pub(crate) fn schema_person_reviews_of(html: &str) -> usize {
+    let blocks = ld_json_blocks(html);
+    let (nodes, ids) = ld_json_index(&blocks);
+    let ld_json = nodes
+        .iter()
+        .filter(|node| ld_json_types(node).contains(&"Review"))
+        .filter(|node| {
+            ld_json_object(node, "author", &ids)
+                .is_some_and(|author| ld_json_types(author).contains(&"Person"))
+        })
+        .count();
+    if ld_json > 0 {
+        return ld_json;
+    }
+
+    let document = Html::parse_document(html);
+    document
+        .select(&ITEMTYPE_SEL)
+        .filter(|item| microdata_types(item).contains(&"Review"))
+        .filter(|item| {
+            item.select(&AUTHOR_ITEM_SEL)
+                .any(|author| microdata_types(&author).contains(&"Person"))
+        })
+        .count()
+}
+
+/// Titles of the JSON-LD JobPosting nodes on the page, in page order and
+/// deduplicated, or None when there are no JobPosting nodes.
+pub(crate) fn job_posting_titles_of(html: &str) -> Option<Vec<String>> {
+    let blocks = ld_json_blocks(html);
+    let (nodes, _) = ld_json_index(&blocks);
+    let postings: Vec<_> = nodes
+        .iter()
+        .filter(|node| ld_json_types(node).contains(&"JobPosting"))
+        .collect();
+    if postings.is_empty() {
+        return None;
+    }
+    let mut titles: Vec<String> = Vec::new();
+    for title in postings
+        .iter()
+        .filter_map(|node| ld_json_text(node, "title"))
+    {
+        if !titles.contains(&title) {
+            titles.push(title);
+        }
+    }
+    Some(titles)
+}
+
+// Offer-like nodes and the currencies whose prices we report
+static PRICE_NODE_TYPES: &[&str] = &[
+    "Offer",
+    "AggregateOffer",
+    "PriceSpecification",
+    "UnitPriceSpecification",
+];
+static DOLLAR_CURRENCIES: &[&str] = &["AUD", "NZD", "USD"];
+
+/// Dollar prices from JSON-LD Offer and priceSpecification nodes (price,
+/// else lowPrice), in page order and deduplicated. Prices in other
+/// currencies are skipped.
+pub(crate) fn schema_offer_prices_of(html: &str) -> Vec<f64> {
+    let blocks = ld_json_blocks(html);
+    let (nodes, _) = ld_json_index(&blocks);
+    let offers = nodes.iter().filter(|node| {
+        ld_json_types(node)
+            .iter()
+            .any(|t| PRICE_NODE_TYPES.contains(t))
+    });
+    let mut prices: Vec<f64> = Vec::new();
+    for node in offers {
+        let currency = ld_json_text(node, "priceCurrency").map(|c| c.to_ascii_uppercase());
+        if currency.is_some_and(|c| !DOLLAR_CURRENCIES.contains(&c.as_str())) {
+            continue;
+        }
+        let price = ld_json_number(node, "price").or_else(|| ld_json_number(node, "lowPrice"));
+        if let Some(price) = price.filter(|p| *p > 0.0) {
+            if !prices.contains(&price) {
+                prices.push(price);
+            }
+        }
+    }
+    prices
+}
+
+// Nodes for a single post, as on a blog index or an article page
+static POST_NODE_TYPES: &[&str] = &[
+    "BlogPosting",
+    "Article",
+    "NewsArticle",
+    "SocialMediaPosting",
+];
+
+/// datePublished values of the JSON-LD posts on the page, in page order.
+pub(crate) fn schema_post_dates_of(html: &str) -> Vec<String> {
+    let blocks = ld_json_blocks(html);
+    let (nodes, _) = ld_json_index(&blocks);
+    nodes
+        .iter()
+        .filter(|node| {
+            ld_json_types(node)
+                .iter()
+                .any(|t| POST_NODE_TYPES.contains(t))
+        })
+        .filter_map(|node| ld_json_text(node, "datePublished"))
+        .collect()
+}
+
+fn local_business_dict(
+    py: Python<'_>,
+    business: LocalBusinessSchema,
+) -> PyResult<Bound<'_, PyDict>> {
+    let dict = PyDict::new(py);
+    dict.set_item("type", business.kind)?;
+    dict.set_item("name", business.name)?;
+    dict.set_item("telephone", business.telephone)?;
+    dict.set_item("email", business.email)?;
+    match business.address {
+        Some(address) => {
+            let a = PyDict::new(py);
+            a.set_item("street", address.street)?;
+            a.set_item("suburb", address.suburb)?;
+            a.set_item("state", address.state)?;
+            a.set_item("postcode", address.postcode)?;
+            dict.set_item("address", a)?;
+        }
+        None => dict.set_item("address", py.None())?,
+    }
+    match business.geo {
+        Some((lat, lng)) => {
+            let g = PyDict::new(py);
+            g.set_item("lat", lat)?;
+            g.set_item("lng", lng)?;
+            dict.set_item("geo", g)?;
+        }
+        None => dict.set_item("geo", py.None())?,
+    }
+    dict.set_item("opening_hours", business.opening_hours)?;
+    dict.set_item("url", business.url)?;
+    dict.set_item("price_range", business.price_range)?;
+    match business.aggregate_rating {
+        Some(rating) => {
+            let r = PyDict::new(py);
+            r.set_item("value", rating.rating)?;
+            r.set_item("count", rating.review_count)?;
+            dict.set_item("aggregate_rating", r)?;
+        }
+        None => dict.set_item("aggregate_rating", py.None())?,
+    }
+    Ok(dict)
+}
+
+/// The most complete business and the rest in page order; the first on the
+/// page breaks ties.
+fn primary_business_of(
+    mut businesses: Vec<LocalBusinessSchema>,
+) -> Option<(LocalBusinessSchema, Vec<LocalBusinessSchema>)> {
+    let best = businesses
+        .iter()
+        .enumerate()
+        .max_by_key(|(i, b)| (b.completeness(), std::cmp::Reverse(*i)))
+        .map(|(i, _)| i)?;
+    let primary = businesses.remove(best);
+    Some((primary, businesses))
+}
+
+/// Extract the business described by the page's JSON-LD structured data.
+///
+/// Looks through every `<script type="application/ld+json">` block,
+/// including arrays and @graph containers, for nodes whose @type is
+/// LocalBusiness or a subtype (Plumber, Dentist, Restaurant, ...). Blocks
+/// that are not valid JSON are skipped.
+///
+/// Returns None when there is no such node, else a dict with keys:
+///   - "type": the matched @type
+///   - "name", "telephone", "email", "url", "price_range": str | None;
+///     the phone is normalized and the email filtered like extract_emails
+///   - "address": {street, suburb, state, postcode} | None
+///   - "geo": {lat, lng} | None
+///   - "opening_hours": list of "Mo-Fr 08:00-17:00" style strings
+///   - "aggregate_rating": {value, count} | None, validated and rescaled to
+///     5 like extract_aggregate_rating
+///   - "others": the remaining LocalBusiness nodes in the same shape (without
+///     "others"), when the page has several; the most complete one is
+///     returned at the top level
+#[pyfunction]
+pub fn extract_local_business_schema(py: Python<'_>, html: &str) -> PyResult<Option<PyObject>> {
+    let Some((primary, businesses)) =
+        py.allow_threads(|| primary_business_of(local_businesses_of(html)))
+    else {
+        return Ok(None);
+    };
+
+    let dict = local_business_dict(py, primary)?;
+    let others = PyList::empty(py);
+    for business in businesses {
+        others.append(local_business_dict(py, business)?)?;
+    }
+    dict.set_item("others", others)?;
+    Ok(Some(dict.into()))
+}
+
+// ---------------------------------------------------------------------------
+// Aggregate ratings
+// ---------------------------------------------------------------------------
+
+static ORGANIZATION_TYPES: &[&str] = &[
+    "Organization",
+    "Corporation",
+    "OnlineBusiness",
+    "NGO",
+    "EducationalOrganization",
+    "MedicalOrganization",
+    "SportsOrganization",
+];
+
+static RATING_VALUE_SEL: LazyLock<Selector> =
+    LazyLock::new(|| Selector::parse(r#"[itemprop~="ratingValue"]"#).unwrap());
+static ITEMPROP_SEL: LazyLock<Selector> = LazyLock::new(|| Selector::parse("[itemprop]").unwrap());
+
+pub(crate) fn is_local_business_type(kind: &str) -> bool {
+    LOCAL_BUSINESS_TYPES.contains(&kind)
+}
+
+fn is_business_type(kind: &str) -> bool {
+    LOCAL_BUSINESS_TYPES.contains(&kind) || ORGANIZATION_TYPES.contains(&kind)
+}
+
+struct RatingCandidate {
+    rating: AggregateRating,
+    source: &'static str,
+    on_business: bool,
+}
+
+/// Ratings in JSON-LD: aggregateRating properties, and standalone
+/// AggregateRating nodes judged by their itemReviewed.
+fn ld_json_ratings(html: &str) -> Vec<RatingCandidate> {
+    let blocks = ld_json_blocks(html);
+    let (nodes, ids) = ld_json_index(&blocks);
+    let mut seen: HashSet<*const Map<String, Value>> = HashSet::new();
+    let mut candidates = Vec::new();
+
+    for node in &nodes {
+        if let Some(rating) = ld_json_object(node, "aggregateRating", &ids) {
+            seen.insert(rating);
+            if let Some(rating) = ld_json_rating(rating) {
+                candidates.push(RatingCandidate {
+                    rating,
+                    source: "json-ld",
+                    on_business: ld_json_types(node).into_iter().any(is_business_type),
+                });
+            }
+        }
+    }
+    for node in &nodes {
+        if !ld_json_types(node).contains(&"AggregateRating") || seen.contains(&(*node as *const _))
+        {
+            continue;
+        }
+        if let Some(rating) = ld_json_rating(node) {
+            let on_business = ld_json_object(node, "itemReviewed", &ids)
+                .is_some_and(|item| ld_json_types(item).into_iter().any(is_business_type));
+            candidates.push(RatingCandidate {
+                rating,
+                source: "json-ld",
+                on_business,
+            });
+        }
+    }
+    candidates
+}
+
+/// Local names of an element's itemtype ("https://schema.org/Dentist").
+fn microdata_types<'a>(element: &ElementRef<'a>) -> Vec<&'a str> {
+    element
+        .value()
+        .attr("itemtype")
+        .map(|types| {
+            types
+                .split_whitespace()
+                .map(|t| t.trim_end_matches('/').rsplit('/').next().unwrap_or(t))
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+fn nearest_scope<'a>(element: &ElementRef<'a>) -> Option<ElementRef<'a>> {
+    element
+        .ancestors()
+        .filter_map(ElementRef::wrap)
+        .find(|e| e.value().attr("itemscope").is_some())
+}
+
+/// Ratings in microdata: every scope holding an itemprop="ratingValue",
+/// whether an AggregateRating scope nested in the item it rates or the
+/// rating properties placed directly on the item.
+fn microdata_ratings(html: &str) -> Vec<RatingCandidate> {
+    let document = Html::parse_document(html);
+    let mut seen = Vec::new();
+    let mut candidates = Vec::new();
+
+    for value in document.select(&RATING_VALUE_SEL) {
+        let Some(scope) = nearest_scope(&value) else {
+            continue;
+        };
+        if seen.contains(&scope.id()) {
+            continue;
+        }
+        seen.push(scope.id());
+        let is_rating_scope = microdata_types(&scope).contains(&"AggregateRating")
+            || scope
+                .value()
+                .attr("itemprop")
+                .is_some_and(|p| p.split_whitespace().any(|p| p == "aggregateRating"));
+        let owner = if is_rating_scope {
+            nearest_scope(&scope)
+        } else {
+            Some(scope)
+        };
+
+        let mut props: HashMap<&str, String> = HashMap::new();
+        for element in scope.select(&ITEMPROP_SEL) {
+            let value = element
+                .value()
+                .attr("content")
+                .map(str::to_string)
+                .unwrap_or_else(|| element.text().collect::<String>());
+            for name in element
+                .value()
+                .attr("itemprop")
+                .unwrap_or("")
+                .split_whitespace()
+            {
+                props
+                    .entry(name)
+                    .or_insert_with(|| value.trim().to_string());
+            }
+        }
+        let rating = aggregate_rating_of(
+            props.get("ratingValue").and_then(|v| parse_decimal(v)),
+            props
+                .get("reviewCount")
+                .or_else(|| props.get("ratingCount"))
+                .and_then(|v| parse_count(v)),
+            props.get("bestRating").and_then(|v| parse_decimal(v)),
+        );
+        if let Some(rating) = rating {
+            candidates.push(RatingCandidate {
+                rating,
+                source: "microdata",
+                on_business: owner
+                    .is_some_and(|o| microdata_types(&o).into_iter().any(is_business_type)),
+            });
+        }
+    }
+    candidates
+}
+
+/// The business's rating if one is marked up, else the first rating,
+/// JSON-LD before microdata.
+fn best_rating_of(html: &str) -> Option<RatingCandidate> {
+    if html.is_empty() {
+        return None;
+    }
+
+    let mut candidates = ld_json_ratings(html);
+    if html.contains("ratingValue") {
+        candidates.extend(microdata_ratings(html));
+    }
+    candidates
+        .iter()
+        .position(|c| c.on_business)
+        .or((!candidates.is_empty()).then_some(0))
+        .map(|i| candidates.swap_remove(i))
+}
+
+/// Extract the review rating published in schema.org markup, from JSON-LD
+/// or microdata (itemprop="ratingValue" / "reviewCount").
+///
+/// Returns None when there is no valid rating, else a dict with keys:
+///   - "rating": out of 5; ratings on another scale are rescaled by
+///     bestRating
+///   - "review_count": reviewCount (or ratingCount), 0 when not given
+///   - "best_rating": the bestRating as published, or None
+///   - "source": "json-ld" | "microdata"
+///
+/// String-typed numbers ("4.8", "1,234") are accepted. Ratings above their
+/// bestRating and negative counts are rejected. A rating on a LocalBusiness
+/// or Organization beats product and article ratings; otherwise the first
+/// one wins, JSON-LD before microdata.
+#[pyfunction]
+pub fn extract_aggregate_rating(py: Python<'_>, html: &str) -> PyResult<Option<PyObject>> {
+    let Some(best) = py.allow_threads(|| best_rating_of(html)) else {
+        return Ok(None);
+    };
+
+    let dict = PyDict::new(py);
+    dict.set_item("rating", best.rating.rating)?;
+    dict.set_item("review_count", best.rating.review_count)?;
+    dict.set_item("best_rating", best.rating.best_rating)?;
+    dict.set_item("source", best.source)?;
+    Ok(Some(dict.into()))
+}
+
+// ---------------------------------------------------------------------------
+// Schema types
+// ---------------------------------------------------------------------------
+
+const MAX_SCHEMA_TYPES: usize = 30;
+
+static ITEMTYPE_ATTR_RE: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r#"(?i)<[a-z][^>]*?\sitemtype\s*=\s*["']([^"']+)["']"#).unwrap());
+
+/// Bare schema.org type names used on the page: @type values from JSON-LD
+/// (nested and @graph nodes included) then microdata itemtypes, in page
+/// order, deduplicated and capped at MAX_SCHEMA_TYPES. Markup inside HTML
+/// comments does not count, nor do type names in string values.
+pub(crate) fn schema_types_of(html: &str) -> Vec<String> {
+    let mut types: Vec<String> = Vec::new();
+    let mut add = |kind: &str| {
+        let kind = kind
+            .trim_end_matches('/')
+            .rsplit(['/', ':', '#'])
+            .next()
+            .unwrap_or(kind);
+        if !kind.is_empty()
+            && kind.chars().all(|c| c.is_ascii_alphanumeric() || c == '_')
+            && types.len() < MAX_SCHEMA_TYPES
+            && !types.iter().any(|t| t == kind)
+        {
+            types.push(kind.to_string());
+        }
+    };
+
+    let blocks = ld_json_blocks(html);
+    let (nodes, _) = ld_json_index(&blocks);
+    for node in nodes {
+        ld_json_types(node).into_iter().for_each(&mut add);
+    }
+
+    let comments = comment_ranges(html);
+    for caps in ITEMTYPE_ATTR_RE.captures_iter(html) {
+        if !in_ranges(&comments, caps.get(0).map_or(0, |m| m.start())) {
+            caps[1].split_whitespace().for_each(&mut add);
+        }
+    }
+    types
+}
+
+/// schema.org types present on the page, from JSON-LD @type values and
+/// microdata itemtype URLs, as bare names ("LocalBusiness", "FAQPage"), in
+/// page order and capped at 30. Markup inside HTML comments is ignored.
+#[pyfunction]
+pub fn detect_schema_types(py: Python<'_>, html: &str) -> Vec<String> {
+    py.allow_threads(|| schema_types_of(html))
+}
+
+// ---------------------------------------------------------------------------
+// Open Graph / Twitter cards
+// ---------------------------------------------------------------------------
+
+static META_TAG_RE: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"(?is)<meta\b[^>]*>").unwrap());
+
+static META_ATTR_RE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r#"(?is)\b(property|name|content)\s*=\s*(?:"([^"]*)"|'([^']*)'|([^\s"'>]+))"#)
+        .unwrap()
+});
+
+/// Output key per meta property; og:image falls back to its :url and
+/// :secure_url variants.
+static OG_PROPERTIES: &[(&str, &[&str])] = &[
+    ("og_title", &["og:title"]),
+    ("og_description", &["og:description"]),
+    (
+        "og_image",
+        &["og:image", "og:image:url", "og:image:secure_url"],
+    ),
+    ("og_site_name", &["og:site_name"]),
+    ("og_type", &["og:type"]),
+    ("twitter_card", &["twitter:card"]),
+    ("twitter_site", &["twitter:site"]),
+];
+
+/// Page titles that name the page, not the business ("Home | Acme").
+const TITLE_SEPARATORS: [char; 6] = ['|', '–', '—', '-', '•', ':'];
+
+static GENERIC_PAGE_TITLES: &[&str] = &[
+    "home",
+    "homepage",
+    "home page",
+    "welcome",
+    "index",
+    "about",
+    "about us",
+    "contact",
+    "contact us",
+];
+
+/// Every meta property/name -> content pair, first occurrence winning,
+/// entity-decoded and whitespace-normalised. Attribute order and quoting do
+/// not matter.
+fn meta_properties_of(html: &str) -> HashMap<String, String> {
+    let mut properties: HashMap<String, String> = HashMap::new();
+    for tag in META_TAG_RE.find_iter(html) {
+        let mut key = None;
+        let mut content = None;
+        for caps in META_ATTR_RE.captures_iter(tag.as_str()) {
+            let value = caps
+                .get(2)
+                .or_else(|| caps.get(3))
+                .or_else(|| caps.get(4))
+                .map_or("", |m| m.as_str());
+            if caps[1].eq_ignore_ascii_case("content") {
+                content.get_or_insert(value);
+            } else {
+                key.get_or_insert(value.trim().to_ascii_lowercase());
+            }
+        }
+        let (Some(key), Some(content)) = (key, content) else {
+            continue;
+        };
+        let content = decode_html_entities(content)
+            .split_whitespace()
+            .collect::<Vec<_>>()
+            .join(" ");
+        if !content.is_empty() {
+            properties.entry(key).or_insert(content);
+        }
+    }
+    properties
+}
+
+/// A relative og:image resolved against `base_url`; protocol-relative URLs
+/// get https when there is no base.
+fn resolve_image_url(image: &str, base_url: Option<&str>) -> String {
+    if let Ok(url) = url::Url::parse(image) {
+        return url.to_string();
+    }
+    if let Some(joined) = base_url
+        .and_then(|base| url::Url::parse(base.trim()).ok())
+        .and_then(|base| base.join(image).ok())
+    {
+        return joined.to_string();
+    }
+    match image.strip_prefix("//") {
+        Some(rest) => format!("https://{}", rest),
+        None => image.to_string(),
+    }
+}
+
+fn og_metadata_of(html: &str, base_url: Option<&str>) -> Vec<(&'static str, Option<String>)> {
+    let mut properties = meta_properties_of(html);
+    OG_PROPERTIES
+        .iter()
+        .map(|(key, names)| {
+            let value = names.iter().find_map(|name| properties.remove(*name));
+            let value = match *key {
+                "og_image" => value.map(|image| resolve_image_url(&image, base_url)),
+                _ => value,
+            };
+            (*key, value)
+        })
+        .collect()
+}
+
+/// Extract Open Graph and Twitter card metadata.
+///
+/// Returns a dict with og_title, og_description, og_image, og_site_name,
+/// og_type, twitter_card and twitter_site, each a str or None. Values are
+/// HTML-entity-decoded and whitespace-normalised; a relative og:image is
+/// resolved against `base_url` when given.
+#[pyfunction]
+#[pyo3(signature = (html, base_url=None))]
+pub fn extract_og_metadata(
+    py: Python<'_>,
+    html: &str,
+    base_url: Option<&str>,
+) -> PyResult<PyObject> {
+    let dict = PyDict::new(py);
+    for (key, value) in og_metadata_of(html, base_url) {
+        dict.set_item(key, value)?;
+    }
+    Ok(dict.into())
+}
+
+/// The name from the <title>, cleaned; when the first segment is only the
+/// page's name ("Home | Acme Plumbing") the last segment is used instead.
+fn business_name_from_title(title: &str) -> Option<String> {
+    let is_generic = |s: &str| GENERIC_PAGE_TITLES.contains(&s.trim().to_lowercase().as_str());
+    // Only a separator followed by a space ends the head, so "Home-Made
+    // Pies" isn't read as the "Home" page
+    let head = title
+        .char_indices()
+        .find(|&(i, c)| TITLE_SEPARATORS.contains(&c) && title[i + c.len_utf8()..].starts_with(' '))
+        .map_or(title, |(i, _)| &title[..i]);
+    let cleaned = clean_business_name(title, true);
+    if !is_generic(head) && !is_generic(&cleaned) {
+        return Some(cleaned).filter(|name| !name.is_empty());
+    }
+    title
+        .rsplit(TITLE_SEPARATORS)
+        .map(|segment| clean_business_name(segment.trim(), true))
+        .find(|name| !name.is_empty() && !is_generic(name))
+}
+
+/// The business's name as the page states it: og:site_name, else the
+/// JSON-LD LocalBusiness (or Organization) name, else the cleaned <title>.
+/// Each is run through clean_business_name.
+#[pyfunction]
+pub fn best_business_name(py: Python<'_>, html: &str) -> Option<String> {
+    py.allow_threads(|| best_business_name_of(html))
+}
+
+fn best_business_name_of(html: &str) -> Option<String> {
+    let cleaned = |name: &str| {
+        let name = clean_business_name(name, true);
+        (!name.is_empty()).then_some(name)
+    };
+
+    if let Some(name) = meta_properties_of(html)
+        .get("og:site_name")
+        .and_then(|name| cleaned(name))
+    {
+        return Some(name);
+    }
+
+    let blocks = ld_json_blocks(html);
+    let (nodes, _) = ld_json_index(&blocks);
+    let named = |is_kind: fn(&str) -> bool| {
+        nodes
+            .iter()
+            .filter(|node| ld_json_types(node).into_iter().any(is_kind))
+            .find_map(|node| ld_json_text(node, "name").and_then(|name| cleaned(&name)))
+    };
+    if let Some(name) = named(is_local_business_type).or_else(|| named(is_business_type)) {
+        return Some(name);
+    }
+
+    TITLE_TEXT_RE
+        .captures(html)
+        .map(|caps| {
+            decode_html_entities(&caps[1])
+                .split_whitespace()
+                .collect::<Vec<_>>()
+                .join(" ")
+        })
+        .and_then(|title| business_name_from_title(&title))
+}
+
+// ---------------------------------------------------------------------------
+// Opening hours
+// ---------------------------------------------------------------------------
+
+/// Output keys for the days of the week, Monday first.
+const WEEKDAY_KEYS: [&str; 7] = ["mon", "tue", "wed", "thu", "fri", "sat", "sun"];
+
+/// How far past an "Opening hours" heading the hours may run, in chars.
+const HOURS_WINDOW_CHARS: usize = 400;
+
+// Day names and groups. Holidays match so "Sun & Public Holidays: Closed"
+// still parses, but map to no weekday.
+const DAY_PATTERN: &str = concat!(
+    r"\b(?:mon(?:day)?s?|tue(?:s(?:day)?)?s?|wed(?:nesday)?s?|thu(?:r(?:s(?:day)?)?)?s?",
+    r"|fri(?:day)?s?|sat(?:urday)?s?|sun(?:day)?s?|weekdays|weekends|daily|every\s*day",
+    r"|7\s*days(?:\s+a\s+week)?|(?:public\s+)?holidays?)\b\.?"
+);
+const DAY_RANGE_SEP: &str = r"\s*(?:-|–|—|to|thru|through)\s*";
+const DAY_LIST_SEP: &str = r"\s*(?:,|&|\+|/|and)\s*";
+const TIME_PATTERN: &str =
+    r"(?:\b[0-9]{1,2}(?:[:.][0-9]{2})?(?:\s*[ap]\.?\s?m\b\.?)?|\bnoon\b|\bmidday\b|\bmidnight\b)";
+const TIME_RANGE_SEP: &str = r"\s*(?:-|–|—|to|until|till)\s*";
+
+static DAY_RE: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(&format!("(?i){}", DAY_PATTERN)).unwrap());
+
+static DAY_RANGE_SEP_RE: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(&format!("(?i)^{}$", DAY_RANGE_SEP)).unwrap());
+
+static TIME_RANGE_RE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(&format!(
+        "(?i)({t}){sep}({t})",
+        t = TIME_PATTERN,
+        sep = TIME_RANGE_SEP
+    ))
+    .unwrap()
+});
+
+/// One line of an hours list, in any of three shapes:
+///   - days then hours or a status: "Mon–Fri: 7am–5pm", "Sunday Closed",
+///     "Sat & Sun by appointment"
+///   - "Closed" then days: "Closed Sundays", "Closed on Sat & Sun"
+///   - hours then days: "7am–5pm Mon–Fri"
+static HOURS_ENTRY_RE: LazyLock<Regex> = LazyLock::new(|| {
+    let days = format!(
+        "{d}(?:{r}{d})?(?:{l}{d}(?:{r}{d})?)*",
+        d = DAY_PATTERN,
+        r = DAY_RANGE_SEP,
+        l = DAY_LIST_SEP
+    );
+    let times = format!(
+        "{t}{sep}{t}(?:\\s*(?:,|&|and|/)\\s*{t}{sep}{t})*",
+        t = TIME_PATTERN,
+        sep = TIME_RANGE_SEP
+    );
+    let status = r"closed\b|by\s+appointment(?:\s+only)?|(?:open\s+)?24\s*(?:hours|hrs|h)\b";
+    Regex::new(&format!(
+        concat!(
+            r"(?i)(?P<days>{days})\s*(?::|-|–|—)?\s*(?:(?P<times>{times})|(?P<status>{status}))",
+            r"|\bclosed\s*(?:on\s+)?:?\s*(?P<closed_days>{days})",
+            r"|(?P<times_first>{times})\s*,?\s*(?:on\s+)?(?P<days_after>{days})",
+        ),
+        days = days,
+        times = times,
+        status = status
+    ))
+    .unwrap()
+});
+
+static TIME_PARTS_RE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"(?i)^([0-9]{1,2})(?:[:.]([0-9]{2}))?\s*(?:([ap])\.?\s?m\.?)?$").unwrap()
+});
+
+static HOURS_HEADING_RE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(concat!(
+        r"(?i)\b(?:opening|trading|business|office|store|shop|clinic|operating|our|open)\s+hours\b",
+        r"|\bhours\s+of\s+(?:operation|business)\b|\bhours\s*:",
+    ))
+    .unwrap()
+});
+
+static ALWAYS_OPEN_RE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"(?i)\b24\s*/\s*7\b|\b24\s*hours?,?\s*(?:a\s+day,?\s*)?7\s*days(?:\s+a\s+week)?\b")
+        .unwrap()
+});
+
+static FOOTER_BLOCK_RE: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"(?is)<footer\b.*?</footer\s*>").unwrap());
+
+/// A day's hours as (open, close) minutes after midnight; close may be
+/// 1440 (midnight at the end of the day).
+type DayHours = Vec<(u16, u16)>;
+
+#[derive(Clone, PartialEq)]
+enum DayState {
+    Unknown,
+    Open(DayHours),
+    Closed,
+    ByAppointment,
+    /// Listed twice with different hours (several branches on one page)
+    Conflict,
+}
+
+struct OpeningHours {
+    days: [DayState; 7],
+    raw: String,
+    source: &'static str,
+}
+
+/// Weekday indexes (Monday = 0) a day token stands for.
+fn day_indexes(token: &str) -> Vec<usize> {
+    let token = token.to_lowercase();
+    if token.starts_with("weekday") {
+        return (0..5).collect();
+    }
+    if token.starts_with("weekend") {
+        return vec![5, 6];
+    }
+    if token.starts_with("daily") || token.starts_with("every") || token.starts_with('7') {
+        return (0..7).collect();
+    }
+    ["mon", "tue", "wed", "thu", "fri", "sat", "sun"]
+        .iter()
+        .position(|prefix| token.starts_with(prefix))
+        .into_iter()
+        .collect()
+}
+
+/// Weekday indexes of a day list: "Mon–Fri", "Sat & Sun", "Mon, Wed, Fri",
+/// "Fri–Mon" (wrapping past Sunday).
+fn days_of(days: &str) -> Vec<usize> {
+    let mut out: Vec<usize> = Vec::new();
+    let mut previous: Option<(usize, Vec<usize>)> = None;
+    for token in DAY_RE.find_iter(days) {
+        let indexes = day_indexes(token.as_str());
+        let ranged = previous
+            .as_ref()
+            .filter(|(end, _)| DAY_RANGE_SEP_RE.is_match(&days[*end..token.start()]));
+        match (ranged, indexes.as_slice()) {
+            (Some((_, from)), [to]) if from.len() == 1 => {
+                let (from, to) = (from[0], *to);
+                let mut day = from;
+                while day != to {
+                    day = (day + 1) % 7;
+                    out.push(day);
+                }
+            }
+            _ => out.extend(&indexes),
+        }
+        previous = Some((token.end(), indexes));
+    }
+    let mut seen = HashSet::new();
+    out.retain(|day| seen.insert(*day));
+    out
+}
+
+/// A clock time as (hour, minute, pm) with pm None when not written;
+/// noon and midnight come back as 12 pm and 12 am.
+fn clock_time(text: &str) -> Option<(u16, u16, Option<bool>)> {
+    let text = text.trim();
+    match text.to_lowercase().as_str() {
+        "noon" | "midday" => return Some((12, 0, Some(true))),
+        "midnight" => return Some((12, 0, Some(false))),
+        _ => {}
+    }
+    let caps = TIME_PARTS_RE.captures(text)?;
+    let hour: u16 = caps[1].parse().ok()?;
+    let minute: u16 = caps.get(2).map_or(Some(0), |m| m.as_str().parse().ok())?;
+    let pm = caps.get(3).map(|m| m.as_str().eq_ignore_ascii_case("p"));
+    let valid = minute < 60
+        && if pm.is_some() {
+            (1..=12).contains(&hour)
+        } else {
+            hour <= 24
+        };
+    valid.then_some((hour, minute, pm))
+}
+
+fn to_minutes(hour: u16, minute: u16, pm: bool) -> u16 {
+    (hour % 12 + if pm { 12 } else { 0 }) * 60 + minute
+}
+
+/// Resolve an opening range to minutes, borrowing a missing am/pm from the
+/// other end ("8–5pm", "7am–5") or reading bare hours as a working day
+/// ("8–12", "9–5"). Returns None when the reading is a guess: bare hours
+/// starting between 1 and 4, or an end that isn't after the start.
+fn time_range(start: &str, end: &str) -> Option<(u16, u16)> {
+    let (sh, sm, spm) = clock_time(start)?;
+    let (eh, em, epm) = clock_time(end)?;
+
+    let (open, close) = match (spm, epm) {
+        (Some(sp), Some(ep)) => (to_minutes(sh, sm, sp), to_minutes(eh, em, ep)),
+        (None, Some(ep)) => {
+            let close = to_minutes(eh, em, ep);
+            let same = to_minutes(sh, sm, ep);
+            (
+                if same < close {
+                    same
+                } else {
+                    to_minutes(sh, sm, false)
+                },
+                close,
+            )
+        }
+        (Some(sp), None) => {
+            let open = to_minutes(sh, sm, sp);
+            let same = to_minutes(eh, em, sp);
+            (
+                open,
+                if same > open || sp {
+                    same
+                } else {
+                    to_minutes(eh, em, true)
+                },
+            )
+        }
+        (None, None) if sh > 12 || eh > 12 || (sh == 0 && sm == 0 && start.starts_with('0')) => {
+            (sh * 60 + sm, eh * 60 + em)
+        }
+        (None, None) => {
+            let open = match sh {
+                5..=11 => sh * 60 + sm,
+                12 => 12 * 60 + sm,
+                _ => return None,
+            };
+            let literal = eh * 60 + em;
+            (
+                open,
+                if literal > open {
+                    literal
+                } else {
+                    literal + 12 * 60
+                },
+            )
+        }
+    };
+    // "12am" at the end of a range is the end of the day
+    let close = if close == 0 { 24 * 60 } else { close };
+    (close > open && close <= 24 * 60).then_some((open, close))
+}
+
+fn set_day(days: &mut [DayState; 7], day: usize, state: DayState) {
+    days[day] = match &days[day] {
+        DayState::Unknown => state,
+        current if *current == state => state,
+        _ => DayState::Conflict,
+    };
+}
+
+/// Hours entries in `text`, or None when it has none. The raw text runs
+/// from the first entry to the last.
+fn hours_in_text(text: &str) -> Option<([DayState; 7], String)> {
+    let mut days: [DayState; 7] = std::array::from_fn(|_| DayState::Unknown);
+    let mut span: Option<(usize, usize)> = None;
+
+    for caps in HOURS_ENTRY_RE.captures_iter(text) {
+        let (day_list, state) = if let Some(closed) = caps.name("closed_days") {
+            (closed.as_str(), Some(DayState::Closed))
+        } else {
+            let (day_list, times, status) = match caps.name("days") {
+                Some(d) => (d.as_str(), caps.name("times"), caps.name("status")),
+                None => (&caps["days_after"], caps.name("times_first"), None),
+            };
+            let state = match (times, status) {
+                (Some(times), _) => {
+                    let ranges: Option<DayHours> = TIME_RANGE_RE
+                        .captures_iter(times.as_str())
+                        .map(|r| time_range(&r[1], &r[2]))
+                        .collect();
+                    ranges.map(DayState::Open)
+                }
+                (None, Some(status)) => {
+                    let status = status.as_str().to_lowercase();
+                    Some(if status.starts_with("closed") {
+                        DayState::Closed
+                    } else if status.starts_with("by") {
+                        DayState::ByAppointment
+                    } else {
+                        DayState::Open(vec![(0, 24 * 60)])
+                    })
+                }
+                (None, None) => None,
+            };
+            (day_list, state)
+        };
+        // An unreadable time leaves the days unknown rather than guessed
+        let Some(state) = state else { continue };
+        let indexes = days_of(day_list);
+        if indexes.is_empty() {
+            continue;
+        }
+        for day in indexes {
+            set_day(&mut days, day, state.clone());
+        }
+        let m = caps.get(0).unwrap();
+        span = Some(span.map_or((m.start(), m.end()), |(s, _)| (s, m.end())));
+    }
+
+    if let Some((start, end)) = span {
+        let raw = text[start..end].trim().trim_end_matches(['.', ',']);
+        return Some((days, raw.to_string()));
+    }
+    ALWAYS_OPEN_RE.find(text).map(|m| {
+        (
+            std::array::from_fn(|_| DayState::Open(vec![(0, 24 * 60)])),
+            m.as_str().to_string(),
+        )
+    })
+}
+
+/// Hours from the page text, looking first just after "Opening hours" style
+/// headings, then in the footer, then anywhere on the page.
+fn text_opening_hours(html: &str) -> Option<([DayState; 7], String)> {
+    let text = stream_visible_text(html);
+    for heading in HOURS_HEADING_RE.find_iter(&text) {
+        let window_end = text[heading.end()..]
+            .char_indices()
+            .nth(HOURS_WINDOW_CHARS)
+            .map_or(text.len(), |(i, _)| heading.end() + i);
+        if let Some(found) = hours_in_text(&text[heading.end()..window_end]) {
+            return Some(found);
+        }
+    }
+    for footer in FOOTER_BLOCK_RE.find_iter(html) {
+        if let Some(found) = hours_in_text(&stream_visible_text(footer.as_str())) {
+            return Some(found);
+        }
+    }
+    hours_in_text(&text)
+}
+
+/// Per-day hours from schema.org openingHours strings as opening_hours_of
+/// renders them ("Mo-Fr 08:00-17:00", "Sa,Su 00:00-00:00" for closed).
+fn schema_day_hours(hours: &[String]) -> [DayState; 7] {
+    let mut days: [DayState; 7] = std::array::from_fn(|_| DayState::Unknown);
+    let abbreviation_index = |abbr: &str| {
+        DAY_ABBREVIATIONS[..7]
+            .iter()
+            .position(|(_, a)| a.eq_ignore_ascii_case(abbr))
+    };
+
+    for entry in hours {
+        let (day_part, time_part) = match entry.split_once(char::is_whitespace) {
+            Some((d, t)) if d.chars().next().is_some_and(|c| c.is_ascii_alphabetic()) => {
+                (Some(d), t)
+            }
+            _ => (None, entry.as_str()),
+        };
+        let mut indexes: Vec<usize> = Vec::new();
+        match day_part {
+            None => indexes.extend(0..7),
+            Some(d) => {
+                for item in d.split(',') {
+                    match item.split_once('-') {
+                        Some((from, to)) => {
+                            let (Some(from), Some(to)) =
+                                (abbreviation_index(from), abbreviation_index(to))
+                            else {
+                                continue;
+                            };
+                            let mut day = from;
+                            indexes.push(day);
+                            while day != to {
+                                day = (day + 1) % 7;
+                                indexes.push(day);
+                            }
+                        }
+                        None => indexes.extend(abbreviation_index(item)),
+                    }
+                }
+            }
+        }
+
+        let mut ranges: DayHours = Vec::new();
+        for range in time_part.split(',') {
+            let Some((open, close)) = range.trim().split_once('-') else {
+                continue;
+            };
+            let minutes = |t: &str| {
+                let (h, m) = t.trim().split_once(':')?;
+                Some(h.parse::<u16>().ok()? * 60 + m.get(..2)?.parse::<u16>().ok()?)
+            };
+            if let (Some(open), Some(close)) = (minutes(open), minutes(close)) {
+                ranges.push((open, close));
+            }
+        }
+        let state = match ranges.as_slice() {
+            [] => continue,
+            [(0, 0)] => DayState::Closed,
+            // "23:59" is how schema writers say "until midnight"
+            _ => DayState::Open(
+                ranges
+                    .iter()
+                    .map(|&(o, c)| {
+                        (
+                            o,
+                            if c == 0 || c == 23 * 60 + 59 {
+                                24 * 60
+                            } else {
+                                c
+                            },
+                        )
+                    })
+                    .collect(),
+            ),
+        };
+        for day in indexes {
+            set_day(&mut days, day, state.clone());
+        }
+    }
+    days
+}
+
+/// Text hours merged with the primary LocalBusiness's schema hours; schema
+/// wins for every day it covers.
+fn opening_hours_from(html: &str) -> Option<OpeningHours> {
+    let schema = local_businesses_of(html)
+        .into_iter()
+        .filter(|b| !b.opening_hours.is_empty())
+        .max_by_key(LocalBusinessSchema::completeness)
+        .map(|b| {
+            (
+                schema_day_hours(&b.opening_hours),
+                b.opening_hours.join("; "),
+            )
+        })
+        .filter(|(days, _)| days.iter().any(|d| *d != DayState::Unknown));
+    let text = text_opening_hours(html);
+
+    match (schema, text) {
+        (None, None) => None,
+        (Some((days, raw)), None) => Some(OpeningHours {
+            days,
+            raw,
+            source: "schema",
+        }),
+        (None, Some((days, raw))) => Some(OpeningHours {
+            days,
+            raw,
+            source: "text",
+        }),
+        (Some((schema_days, _)), Some((text_days, raw))) => {
+            let days = std::array::from_fn(|i| match &schema_days[i] {
+                DayState::Unknown => text_days[i].clone(),
+                known => known.clone(),
+            });
+            Some(OpeningHours {
+                days,
+                raw,
+                source: "schema+text",
+            })
+        }
+    }
+}
+
+fn clock(minutes: u16) -> String {
+    format!("{:02}:{:02}", minutes / 60, minutes % 60)
+}
+
+/// The business's opening hours, normalized per day.
+///
+/// Reads schema.org openingHours / openingHoursSpecification and hours
+/// written in the page text ("Mon–Fri 7am–5pm, Sat 8–12, Closed Sunday"),
+/// preferring text after an "Opening hours" / "Trading hours" heading, then
+/// the footer, then the rest of the page. Day ranges, "&" lists, 12-hour
+/// times with or without minutes, "closed", "by appointment" and "24/7"
+/// are understood. When both sources exist, schema wins for the days it
+/// covers.
+///
+/// Returns None when no hours are found, else a dict with keys:
+///   - "mon" ... "sun": list of ["HH:MM", "HH:MM"] open/close pairs ("24:00"
+///     closes at midnight), [] when closed, or None when unknown, by
+///     appointment, or listed with conflicting hours
+///   - "by_appointment": day keys open by appointment only
+///   - "raw": the matched text (the schema strings when only schema had hours)
+///   - "source": "schema", "text" or "schema+text"
+///
+/// Fragments whose times can't be read without guessing ("1–4" with no
+/// am/pm) are left out.
+#[pyfunction]
+pub fn extract_opening_hours(py: Python<'_>, html: &str) -> PyResult<Option<PyObject>> {
+    let Some(hours) = py.allow_threads(|| opening_hours_from(html)) else {
+        return Ok(None);
+    };
+
+    let dict = PyDict::new(py);
+    let mut by_appointment = Vec::new();
+    for (key, state) in WEEKDAY_KEYS.iter().zip(hours.days) {
+        match state {
+            DayState::Open(ranges) => {
+                let pairs: Vec<[String; 2]> =
+                    ranges.iter().map(|&(o, c)| [clock(o), clock(c)]).collect();
+                dict.set_item(key, pairs)?;
+            }
+            DayState::Closed => dict.set_item(key, PyList::empty(py))?,
+            DayState::ByAppointment => {
+                by_appointment.push(*key);
+                dict.set_item(key, py.None())?;
+            }
+            DayState::Unknown | DayState::Conflict => dict.set_item(key, py.None())?,
+        }
+    }
+    dict.set_item("by_appointment", by_appointment)?;
+    dict.set_item("raw", hours.raw)?;
+    dict.set_item("source", hours.source)?;
+    Ok(Some(dict.into()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Business name from <title>
+
+    #[test]
+    fn business_name_from_title_skips_page_names() {
+        for (title, expected) in [
+            ("Acme Plumbing | Home", "Acme Plumbing"),
+            ("Home | Acme Plumbing", "Acme Plumbing"),
+            ("Contact Us - Acme Plumbing", "Acme Plumbing"),
+            ("Ray White | Paddington", "Ray White | Paddington"),
+            ("Home-Made Pies | Contact", "Home-Made Pies"),
+        ] {
+            assert_eq!(
+                business_name_from_title(title).as_deref(),
+                Some(expected),
+                "{}",
+                title
+            );
+        }
+    }
+
+    // JSON-LD LocalBusiness
+
+    fn ld_json_page(blocks: &[&str]) -> String {
+        blocks
+            .iter()
+            .map(|block| format!(r#"<script type="application/ld+json">{}</script>"#, block))
+            .collect()
+    }
+
+    #[test]
+    fn local_business_from_nested_graph() {
+        let html = ld_json_page(&[r##"{
+            "@context": "https://schema.org",
+            "@graph": [
+                {"@type": "WebSite", "@id": "https://acme.com.au/#website", "name": "Acme Website"},
+                {"@type": "PostalAddress", "@id": "#addr", "streetAddress": "12 Smith St",
+                 "addressLocality": "Paddington", "addressRegion": "QLD", "postalCode": "4064"},
+                {"@type": "OpeningHoursSpecification", "@id": "#sat",
+                 "dayOfWeek": "https://schema.org/Saturday", "opens": "8:00", "closes": "12:00:00"},
+                {"@type": "Plumber", "@id": "https://acme.com.au/#business",
+                 "name": "Acme Plumbing", "telephone": "+61 7 3123 4567",
+                 "email": "mailto:Info@AcmePlumbing.com.au", "url": "https://acme.com.au/",
+                 "address": {"@id": "#addr"},
+                 "geo": {"@type": "GeoCoordinates", "latitude": "-27.46", "longitude": 153.0},
+                 "openingHours": ["Mo-Fr 07:00-17:00"],
+                 "openingHoursSpecification": [{"@id": "#sat"}],
+                 "priceRange": "$$",
+                 "aggregateRating": {"@type": "AggregateRating", "ratingValue": "4.8", "reviewCount": "127"}}
+            ]
+        }"##]);
+        let (business, others) = primary_business_of(local_businesses_of(&html)).unwrap();
+        assert!(others.is_empty());
+        assert_eq!(business.kind, "Plumber");
+        assert_eq!(business.name.as_deref(), Some("Acme Plumbing"));
+        assert_eq!(business.telephone.as_deref(), Some("07 3123 4567"));
+        assert_eq!(business.email.as_deref(), Some("info@acmeplumbing.com.au"));
+        let address = business.address.unwrap();
+        assert_eq!(address.street.as_deref(), Some("12 Smith St"));
+        assert_eq!(address.suburb.as_deref(), Some("Paddington"));
+        assert_eq!(address.state.as_deref(), Some("QLD"));
+        assert_eq!(address.postcode.as_deref(), Some("4064"));
+        assert_eq!(business.geo, Some((-27.46, 153.0)));
+        assert_eq!(
+            business.opening_hours,
+            vec![
+                "Mo-Fr 07:00-17:00".to_string(),
+                "Sa 08:00-12:00".to_string()
+            ]
+        );
+        assert_eq!(business.url.as_deref(), Some("https://acme.com.au/"));
+        assert_eq!(business.price_range.as_deref(), Some("$$"));
+        let rating = business.aggregate_rating.unwrap();
+        assert_eq!((rating.rating, rating.review_count), (4.8, 127));
+    }
+
+    #[test]
+    fn local_business_type_arrays_and_top_level_arrays() {
+        let html = ld_json_page(&[r#"[
+            {"@type": "Organization", "name": "Parent Co"},
+            {"@type": ["Thing", "Dentist"], "name": "Smile Dental", "telephone": "(02) 9876 5432"}
+        ]"#]);
+        let businesses = local_businesses_of(&html);
+        assert_eq!(businesses.len(), 1);
+        assert_eq!(businesses[0].kind, "Dentist");
+        assert_eq!(businesses[0].telephone.as_deref(), Some("02 9876 5432"));
+
+        // Organization alone, and unknown types, are not local businesses
+        let html = ld_json_page(&[r#"{"@type": ["Organization", "WebPage"], "name": "Acme"}"#]);
+        assert!(primary_business_of(local_businesses_of(&html)).is_none());
+    }
+
+    #[test]
+    fn broken_json_ld_blocks_are_skipped() {
+        let html = ld_json_page(&[
+            r#"{"@type": "Restaurant", "name": "Broken Cafe", "#,
+            r#"not json at all"#,
+            r#"{"@type": "Restaurant", "name": "Good Eats", "priceRange": "$"}"#,
+            "",
+        ]);
+        let businesses = local_businesses_of(&html);
+        assert_eq!(businesses.len(), 1);
+        assert_eq!(businesses[0].name.as_deref(), Some("Good Eats"));
+        assert!(local_businesses_of(&ld_json_page(&["{", "[}"])).is_empty());
+    }
+
+    #[test]
+    fn fullest_local_business_is_primary() {
+        let html = ld_json_page(&[
+            r#"{"@type": "LocalBusiness", "name": "Acme Branch"}"#,
+            r#"{"@type": "HVACBusiness", "name": "Acme Air", "telephone": "07 3123 4567",
+                "email": "hello@acmeair.com.au", "address": "1 Main St, Brisbane QLD 4000"}"#,
+            r#"{"@type": "Electrician", "name": "Acme Sparks", "telephone": "0412 345 678"}"#,
+        ]);
+        let (primary, others) = primary_business_of(local_businesses_of(&html)).unwrap();
+        assert_eq!(primary.name.as_deref(), Some("Acme Air"));
+        assert_eq!(
+            primary.address.unwrap().street.as_deref(),
+            Some("1 Main St, Brisbane QLD 4000")
+        );
+        let names: Vec<_> = others.iter().map(|b| b.name.as_deref().unwrap()).collect();
+        assert_eq!(names, ["Acme Branch", "Acme Sparks"]);
+
+        // Ties go to the first on the page; junk emails are dropped
+        let html = ld_json_page(&[
+            r#"{"@type": "Store", "name": "First", "email": "abc123@sentry.wixpress.com"}"#,
+            r#"{"@type": "Store", "name": "Second"}"#,
+        ]);
+        let (primary, _) = primary_business_of(local_businesses_of(&html)).unwrap();
+        assert_eq!(primary.name.as_deref(), Some("First"));
+        assert_eq!(primary.email, None);
+    }
+
+    // Aggregate ratings
+
+    fn rating(html: &str) -> Option<(f64, i64, Option<f64>, &'static str)> {
+        best_rating_of(html).map(|c| {
+            (
+                c.rating.rating,
+                c.rating.review_count,
+                c.rating.best_rating,
+                c.source,
+            )
+        })
+    }
+
+    #[test]
+    fn aggregate_rating_from_json_ld() {
+        let html = ld_json_page(&[r#"{"@type": "Plumber", "name": "Acme",
+            "aggregateRating": {"@type": "AggregateRating", "ratingValue": 4.8, "reviewCount": 127}}"#]);
+        assert_eq!(rating(&html), Some((4.8, 127, None, "json-ld")));
+
+        // String numbers, ratingCount, thousands separators
+        let html = ld_json_page(&[r#"{"@type": "Dentist",
+            "aggregateRating": {"ratingValue": "4.6", "ratingCount": "1,234", "bestRating": "5"}}"#]);
+        assert_eq!(rating(&html), Some((4.6, 1234, Some(5.0), "json-ld")));
+
+        // A standalone AggregateRating node pointing at the business
+        let html = ld_json_page(&[r#"{"@type": "AggregateRating", "ratingValue": "9",
+            "bestRating": "10", "reviewCount": 20, "itemReviewed": {"@type": "LocalBusiness"}}"#]);
+        assert_eq!(rating(&html), Some((4.5, 20, Some(10.0), "json-ld")));
+    }
+
+    #[test]
+    fn aggregate_rating_from_microdata() {
+        let html = r#"<div itemscope itemtype="https://schema.org/LocalBusiness">
+            <span itemprop="name">Acme</span>
+            <div itemprop="aggregateRating" itemscope itemtype="https://schema.org/AggregateRating">
+              Rated <span itemprop="ratingValue">4.9</span> out of
+              <span itemprop="bestRating">5</span> from
+              <span itemprop="reviewCount"> 86 </span> reviews
+            </div></div>"#;
+        assert_eq!(rating(html), Some((4.9, 86, Some(5.0), "microdata")));
+
+        // Properties straight on the item, content attributes, a 100 scale
+        let html = r#"<div itemscope itemtype="http://schema.org/Restaurant">
+            <meta itemprop="ratingValue" content="90"><meta itemprop="bestRating" content="100">
+            <meta itemprop="ratingCount" content="12"></div>"#;
+        assert_eq!(rating(html), Some((4.5, 12, Some(100.0), "microdata")));
+    }
+
+    #[test]
+    fn business_rating_beats_product_rating() {
+        let product = r#"{"@type": "Product", "name": "Widget",
+            "aggregateRating": {"ratingValue": "3.1", "reviewCount": "4"}}"#;
+        let business = r#"{"@type": "Organization", "name": "Acme",
+            "aggregateRating": {"ratingValue": "4.7", "reviewCount": "52"}}"#;
+        let html = ld_json_page(&[product, business]);
+        assert_eq!(rating(&html), Some((4.7, 52, None, "json-ld")));
+        // Without a business rating the first one wins
+        assert_eq!(
+            rating(&ld_json_page(&[product])),
+            Some((3.1, 4, None, "json-ld"))
+        );
+
+        // A business rating in microdata beats a JSON-LD product rating
+        let html = format!(
+            r#"{}<div itemscope itemtype="https://schema.org/Electrician">
+            <span itemprop="ratingValue">5</span><span itemprop="reviewCount">11</span></div>"#,
+            ld_json_page(&[product])
+        );
+        assert_eq!(rating(&html), Some((5.0, 11, None, "microdata")));
+    }
+
+    #[test]
+    fn impossible_ratings_are_rejected() {
+        for block in [
+            r#"{"@type": "Store", "aggregateRating": {"ratingValue": "6", "reviewCount": "10"}}"#,
+            r#"{"@type": "Store", "aggregateRating": {"ratingValue": "8", "bestRating": "5"}}"#,
+            r#"{"@type": "Store", "aggregateRating": {"ratingValue": "-1"}}"#,
+            r#"{"@type": "Store", "aggregateRating": {"ratingValue": "4", "reviewCount": "-3"}}"#,
+            r#"{"@type": "Store", "aggregateRating": {"ratingValue": "four"}}"#,
+            r#"{"@type": "Store", "aggregateRating": {"reviewCount": "10"}}"#,
+        ] {
+            assert_eq!(rating(&ld_json_page(&[block])), None, "{}", block);
+        }
+        // A missing count is 0
+        let html =
+            ld_json_page(&[r#"{"@type": "Store", "aggregateRating": {"ratingValue": "4"}}"#]);
+        assert_eq!(rating(&html), Some((4.0, 0, None, "json-ld")));
+        assert_eq!(rating(""), None);
+    }
+
+    // Schema types
+
+    #[test]
+    fn schema_types_from_json_ld_and_microdata() {
+        let html = format!(
+            r#"{}<body itemscope itemtype="https://schema.org/WebPage">
+            <div itemscope itemtype="http://schema.org/Plumber https://schema.org/Organization/">
+            <div itemprop="review" itemscope itemtype="https://schema.org/Review"></div>
+            </div></body>"#,
+            ld_json_page(&[
+                r#"{"@context": "https://schema.org", "@graph": [
+                    {"@type": ["Plumber", "LocalBusiness"], "name": "Acme",
+                     "address": {"@type": "PostalAddress", "streetAddress": "1 Main St"}},
+                    {"@type": "FAQPage", "mainEntity": [{"@type": "Question", "name": "Why?"}]}
+                ]}"#,
+                r#"{"@type": "schema:BreadcrumbList"}"#,
+            ])
+        );
+        assert_eq!(
+            schema_types_of(&html),
+            [
+                "Plumber",
+                "LocalBusiness",
+                "PostalAddress",
+                "FAQPage",
+                "Question",
+                "BreadcrumbList",
+                "WebPage",
+                "Organization",
+                "Review",
+            ]
+        );
+    }
+
+    #[test]
+    fn schema_types_skip_comments_strings_and_broken_json() {
+        let html = format!(
+            r#"<!-- <div itemscope itemtype="https://schema.org/Dentist"></div>
+            <script type="application/ld+json">{{"@type": "Restaurant"}}</script> -->
+            {}"#,
+            ld_json_page(&[
+                r#"{"@type": "Article", "description": "Why every Plumber needs LocalBusiness schema",
+                    "about": "Dentist", "keywords": ["Restaurant", "@type"]}"#,
+                r#"{"@type": "Event", "name": "#,
+                r#"{"@type": "https://schema.org/Thing", "name": "x"}"#,
+                r#"{"@type": "Not A Type", "name": "y"}"#,
+            ])
+        );
+        assert_eq!(schema_types_of(&html), ["Article", "Thing"]);
+        assert!(schema_types_of("<p>No markup here</p>").is_empty());
+        assert!(schema_types_of("").is_empty());
+    }
+
+    #[test]
+    fn schema_types_are_deduplicated_and_capped() {
+        let blocks: Vec<String> = (0..MAX_SCHEMA_TYPES + 10)
+            .map(|i| format!(r#"{{"@type": ["Type{0}", "Type{0}"]}}"#, i))
+            .collect();
+        let blocks: Vec<&str> = blocks.iter().map(String::as_str).collect();
+        let types = schema_types_of(&ld_json_page(&blocks));
+        assert_eq!(types.len(), MAX_SCHEMA_TYPES);
+        assert_eq!(types[0], "Type0");
+        assert_eq!(
+            types[MAX_SCHEMA_TYPES - 1],
+            format!("Type{}", MAX_SCHEMA_TYPES - 1)
+        );
+    }
+
+    // Open Graph / Twitter cards
+
+    fn og(html: &str, base_url: Option<&str>) -> HashMap<&'static str, Option<String>> {
+        og_metadata_of(html, base_url).into_iter().collect()
+    }
+
+    #[test]
+    fn og_metadata_ignores_attribute_order_and_quoting() {
+        let html = r#"<head>
+            <meta property="og:title" content="Acme Plumbing &amp; Gas">
+            <meta content='Fast,   friendly
+                plumbers' property='og:description'>
+            <meta content=website property=og:type />
+            <META PROPERTY="OG:SITE_NAME" CONTENT="Acme Plumbing">
+            <meta name="twitter:card" content="summary_large_image">
+            <meta content="@acmeplumbing" name="twitter:site">
+            <meta property="og:title" content="Second title">
+            <meta property="og:image" content="">
+            </head>"#;
+        let meta = og(html, None);
+        assert_eq!(meta["og_title"].as_deref(), Some("Acme Plumbing & Gas"));
+        assert_eq!(
+            meta["og_description"].as_deref(),
+            Some("Fast, friendly plumbers")
+        );
+        assert_eq!(meta["og_type"].as_deref(), Some("website"));
+        assert_eq!(meta["og_site_name"].as_deref(), Some("Acme Plumbing"));
+        assert_eq!(meta["twitter_card"].as_deref(), Some("summary_large_image"));
+        assert_eq!(meta["twitter_site"].as_deref(), Some("@acmeplumbing"));
+        assert_eq!(meta["og_image"], None);
+        assert_eq!(og("", None).len(), OG_PROPERTIES.len());
+        assert!(og("", None).values().all(Option::is_none));
+    }
+
+    #[test]
+    fn og_image_is_resolved_against_base_url() {
+        let page = |image: &str| format!(r#"<meta property="og:image" content="{}">"#, image);
+        let base = Some("https://www.acme.com.au/services/plumbing");
+        let cases = [
+            ("/img/logo.png", None, "/img/logo.png"),
+            (
+                "/img/logo.png",
+                base,
+                "https://www.acme.com.au/img/logo.png",
+            ),
+            (
+                "hero.jpg",
+                base,
+                "https://www.acme.com.au/services/hero.jpg",
+            ),
+            (
+                "//cdn.acme.com.au/hero.jpg",
+                None,
+                "https://cdn.acme.com.au/hero.jpg",
+            ),
+            (
+                "//cdn.acme.com.au/hero.jpg",
+                Some("http://acme.com.au/"),
+                "http://cdn.acme.com.au/hero.jpg",
+            ),
+            (
+                "https://cdn.acme.com.au/a b.jpg",
+                base,
+                "https://cdn.acme.com.au/a%20b.jpg",
+            ),
+            ("/img/logo.png", Some("not a url"), "/img/logo.png"),
+        ];
+        for (image, base_url, expected) in cases {
+            assert_eq!(
+                og(&page(image), base_url)["og_image"].as_deref(),
+                Some(expected),
+                "{}",
+                image
+            );
+        }
+        // og:image:secure_url stands in for a missing og:image
+        let html = r#"<meta property="og:image:secure_url" content="https://acme.com.au/x.png">"#;
+        assert_eq!(
+            og(html, None)["og_image"].as_deref(),
+            Some("https://acme.com.au/x.png")
+        );
+    }
+
+    #[test]
+    fn best_business_name_prefers_site_name_then_schema_then_title() {
+        let title = "<title>Home | Acme Plumbing Pty Ltd</title>";
+        let schema = ld_json_page(&[r#"{"@type": "Plumber", "name": "Acme Plumbing Services"}"#]);
+        let site_name = r#"<meta property="og:site_name" content="Acme Plumbing Co.">"#;
+
+        assert_eq!(
+            best_business_name_of(&format!("{}{}{}", title, schema, site_name)).as_deref(),
+            Some(clean_business_name("Acme Plumbing Co.", true).as_str())
+        );
+        assert_eq!(
+            best_business_name_of(&format!("{}{}", title, schema)).as_deref(),
+            Some(clean_business_name("Acme Plumbing Services", true).as_str())
+        );
+        assert_eq!(
+            best_business_name_of(title).as_deref(),
+            Some(clean_business_name("Acme Plumbing Pty Ltd", true).as_str())
+        );
+
+        // An Organization name is used only without a LocalBusiness one
+        let html = ld_json_page(&[
+            r#"{"@type": "Organization", "name": "Acme Group"}"#,
+            r#"{"@type": "Electrician", "name": "Acme Sparks"}"#,
+        ]);
+        assert_eq!(best_business_name_of(&html).as_deref(), Some("Acme Sparks"));
+        let html = ld_json_page(&[r#"{"@type": "Organization", "name": "Acme Group"}"#]);
+        assert_eq!(best_business_name_of(&html).as_deref(), Some("Acme Group"));
+        assert_eq!(best_business_name_of("<title>Home</title>"), None);
+        assert_eq!(best_business_name_of(""), None);
+    }
+
+    // Opening hours
+
+    /// Each day as "07:00-17:00" (ranges joined with ","), "closed",
+    /// "appt", "?" (unknown) or "conflict".
+    fn week_of(days: &[DayState; 7]) -> Vec<String> {
+        days.iter()
+            .map(|day| match day {
+                DayState::Open(ranges) => ranges
+                    .iter()
+                    .map(|&(o, c)| format!("{}-{}", clock(o), clock(c)))
+                    .collect::<Vec<_>>()
+                    .join(","),
+                DayState::Closed => "closed".to_string(),
+                DayState::ByAppointment => "appt".to_string(),
+                DayState::Unknown => "?".to_string(),
+                DayState::Conflict => "conflict".to_string(),
+            })
+            .collect()
+    }
+
+    fn text_week(text: &str) -> Option<Vec<String>> {
+        hours_in_text(text).map(|(days, _)| week_of(&days))
+    }
+
+    #[test]
+    fn opening_hours_text_formats() {
+        let nine_five = "09:00-17:00";
+        let cases: &[(&str, [&str; 7])] = &[
+            (
+                "Mon–Fri 7am–5pm, Sat 8–12, Closed Sunday",
+                [
+                    "07:00-17:00",
+                    "07:00-17:00",
+                    "07:00-17:00",
+                    "07:00-17:00",
+                    "07:00-17:00",
+                    "08:00-12:00",
+                    "closed",
+                ],
+            ),
+            (
+                "Monday - Friday: 9:00am - 5:00pm | Saturday: 9am - 1pm | Sunday: Closed",
+                [
+                    nine_five,
+                    nine_five,
+                    nine_five,
+                    nine_five,
+                    nine_five,
+                    "09:00-13:00",
+                    "closed",
+                ],
+            ),
+            (
+                "Mon, Wed & Fri 9-5",
+                [nine_five, "?", nine_five, "?", nine_five, "?", "?"],
+            ),
+            (
+                "Tues to Thurs 8.30am to 4.30pm",
+                [
+                    "?",
+                    "08:30-16:30",
+                    "08:30-16:30",
+                    "08:30-16:30",
+                    "?",
+                    "?",
+                    "?",
+                ],
+            ),
+            (
+                "Weekdays 7:30 AM – 6 PM; Weekends by appointment only",
+                [
+                    "07:30-18:00",
+                    "07:30-18:00",
+                    "07:30-18:00",
+                    "07:30-18:00",
+                    "07:30-18:00",
+                    "appt",
+                    "appt",
+                ],
+            ),
+            (
+                "Fri–Mon 11am–10pm",
+                [
+                    "11:00-22:00",
+                    "?",
+                    "?",
+                    "?",
+                    "11:00-22:00",
+                    "11:00-22:00",
+                    "11:00-22:00",
+                ],
+            ),
+            (
+                "Daily 6am - midnight",
+                [
+                    "06:00-24:00",
+                    "06:00-24:00",
+                    "06:00-24:00",
+                    "06:00-24:00",
+                    "06:00-24:00",
+                    "06:00-24:00",
+                    "06:00-24:00",
+                ],
+            ),
+            (
+                "Mon-Fri 8am-12pm & 1pm-5pm",
+                [
+                    "08:00-12:00,13:00-17:00",
+                    "08:00-12:00,13:00-17:00",
+                    "08:00-12:00,13:00-17:00",
+                    "08:00-12:00,13:00-17:00",
+                    "08:00-12:00,13:00-17:00",
+                    "?",
+                    "?",
+                ],
+            ),
+            (
+                "Sat 8-noon. Sun & Public Holidays: Closed",
+                ["?", "?", "?", "?", "?", "08:00-12:00", "closed"],
+            ),
+            (
+                "Closed on Sat & Sun",
+                ["?", "?", "?", "?", "?", "closed", "closed"],
+            ),
+            (
+                "7am-3pm Mon-Thu",
+                [
+                    "07:00-15:00",
+                    "07:00-15:00",
+                    "07:00-15:00",
+                    "07:00-15:00",
+                    "?",
+                    "?",
+                    "?",
+                ],
+            ),
+            (
+                "Mon-Sun 17:00-23:30",
+                [
+                    "17:00-23:30",
+                    "17:00-23:30",
+                    "17:00-23:30",
+                    "17:00-23:30",
+                    "17:00-23:30",
+                    "17:00-23:30",
+                    "17:00-23:30",
+                ],
+            ),
+            (
+                "Open 24/7 for emergencies",
+                [
+                    "00:00-24:00",
+                    "00:00-24:00",
+                    "00:00-24:00",
+                    "00:00-24:00",
+                    "00:00-24:00",
+                    "00:00-24:00",
+                    "00:00-24:00",
+                ],
+            ),
+            (
+                "Thursday open 24 hours",
+                ["?", "?", "?", "00:00-24:00", "?", "?", "?"],
+            ),
+        ];
+        for (text, expected) in cases {
+            assert_eq!(
+                text_week(text),
+                Some(expected.iter().map(|d| d.to_string()).collect()),
+                "{}",
+                text
+            );
+        }
+    }
+
+    #[test]
+    fn ambiguous_hours_are_left_out() {
+        // Bare hours starting between 1 and 4 could be am or pm
+        assert_eq!(text_week("Sat 1-4"), None);
+        assert_eq!(
+            text_week("Mon-Fri 9-5, Sat 2-4"),
+            Some(
+                ["09:00-17:00"; 5]
+                    .iter()
+                    .chain(&["?", "?"])
+                    .map(|d| d.to_string())
+                    .collect()
+            )
+        );
+        // Backwards and out-of-range times
+        assert_eq!(text_week("Mon 5pm-9am"), None);
+        assert_eq!(text_week("Tue 25:00-26:00"), None);
+        assert_eq!(text_week("Wed 9:75am-5pm"), None);
+        // Times without days, and prose
+        assert_eq!(text_week("Call between 9am and 5pm"), None);
+        assert_eq!(text_week("Established 1995, 20-30 staff"), None);
+        // The same day listed twice with different hours
+        assert_eq!(
+            text_week("Mon 9-5, Mon 8-4").map(|w| w[0].clone()),
+            Some("conflict".to_string())
+        );
+    }
+
+    #[test]
+    fn opening_hours_prefer_headings_then_footer() {
+        let html = format!(
+            r#"<p>Our Brisbane store: Mon-Fri 10am-4pm</p>
+            <h2>Opening Hours</h2><p>Monday to Friday 7am - 5pm</p><p>{}</p>
+            <footer>Mon-Fri 8am-6pm</footer>"#,
+            "Friendly local team. ".repeat(25)
+        );
+        let (days, raw) = text_opening_hours(&html).unwrap();
+        assert_eq!(week_of(&days)[0], "07:00-17:00");
+        assert_eq!(raw, "Monday to Friday 7am - 5pm");
+
+        let html =
+            r#"<p>Sale ends Mon-Fri 10am-4pm</p><footer><p>Trading: Mon-Sat 8am-6pm</p></footer>"#;
+        let (days, _) = text_opening_hours(html).unwrap();
+        assert_eq!(week_of(&days)[5], "08:00-18:00");
+
+        assert!(text_opening_hours("<p>No hours here</p>").is_none());
+    }
+
+    #[test]
+    fn schema_hours_win_over_text() {
+        let html = format!(
+            r#"{}<h2>Opening hours</h2><p>Mon-Fri 9am-5pm, Sat 9am-1pm, Sun closed</p>"#,
+            ld_json_page(&[r#"{"@type": "Dentist", "name": "Smile",
+                "openingHours": ["Mo-Fr 08:00-18:00"],
+                "openingHoursSpecification": {"dayOfWeek": "Saturday", "opens": "00:00", "closes": "00:00"}}"#])
+        );
+        let hours = opening_hours_from(&html).unwrap();
+        assert_eq!(hours.source, "schema+text");
+        assert_eq!(
+            week_of(&hours.days),
+            [
+                "08:00-18:00",
+                "08:00-18:00",
+                "08:00-18:00",
+                "08:00-18:00",
+                "08:00-18:00",
+                "closed",
+                "closed"
+            ]
+        );
+
+        let html = ld_json_page(&[r#"{"@type": "Bakery", "openingHours": "Mo-Su 06:00-23:59"}"#]);
+        let hours = opening_hours_from(&html).unwrap();
+        assert_eq!(hours.source, "schema");
+        assert_eq!(hours.raw, "Mo-Su 06:00-23:59");
+        assert_eq!(week_of(&hours.days), ["06:00-24:00"; 7]);
+
+        let hours = opening_hours_from("<p>Hours: Sat 8am-12pm</p>").unwrap();
+        assert_eq!(hours.source, "text");
+        assert!(opening_hours_from("").is_none());
+    }
+}
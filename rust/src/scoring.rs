@@ -6,7 +6,11 @@ use std::collections::HashMap;
 // Helpers for extracting values from the Python dict
 // ---------------------------------------------------------------------------
 
-fn extract_opt_string(py: Python<'_>, map: &HashMap<String, PyObject>, key: &str) -> Option<String> {
+pub(crate) fn extract_opt_string(
+    py: Python<'_>,
+    map: &HashMap<String, PyObject>,
+    key: &str,
+) -> Option<String> {
     map.get(key)
         .and_then(|obj| obj.extract::<Option<String>>(py).ok())
         .flatten()
@@ -18,7 +22,11 @@ fn extract_opt_f64(py: Python<'_>, map: &HashMap<String, PyObject>, key: &str) -
         .flatten()
 }
 
-fn extract_opt_i64(py: Python<'_>, map: &HashMap<String, PyObject>, key: &str) -> Option<i64> {
+pub(crate) fn extract_opt_i64(
+    py: Python<'_>,
+    map: &HashMap<String, PyObject>,
+    key: &str,
+) -> Option<i64> {
     map.get(key)
         .and_then(|obj| obj.extract::<Option<i64>>(py).ok())
         .flatten()
@@ -133,6 +141,16 @@ fn opportunity_score_inner(py: Python<'_>, prospect: &HashMap<String, PyObject>)
         None => return 50, // can't analyse
     };
 
+    // Parked / for-sale domain → same as no website
+    if extract_bool(py, &signals, "is_parked") {
+        return 80;
+    }
+
+    // Coming-soon / default server / suspended page → same as no website
+    if extract_bool(py, &signals, "is_placeholder") {
+        return 80;
+    }
+
     let mut score: i32 = 0;
 
     // Missing GA (confirmed false) → +15
@@ -1,102 +1,146 @@
+use pyo3::exceptions::{PyDeprecationWarning, PyValueError};
 use pyo3::prelude::*;
+use pyo3::types::PyDict;
+use rayon::prelude::*;
 use regex::Regex;
-use std::collections::HashSet;
-use std::sync::LazyLock;
-use url::Url;
+use std::collections::{HashMap, HashSet};
+use std::sync::{LazyLock, RwLock};
+use unicode_normalization::char::is_combining_mark;
+use unicode_normalization::UnicodeNormalization;
+use url::{Host, Url};
+
+use crate::html::{is_hash_like_local_part, is_spam_email, matches_exclude_pattern};
 
 // ---------------------------------------------------------------------------
 // Static data
 // ---------------------------------------------------------------------------
 
-static DIRECTORY_DOMAINS: LazyLock<HashSet<&'static str>> = LazyLock::new(|| {
-    HashSet::from([
-        // Social media
-        "facebook.com",
-        "linkedin.com",
-        "instagram.com",
-        "twitter.com",
-        "x.com",
-        "youtube.com",
-        "tiktok.com",
-        "reddit.com",
-        "quora.com",
-        "pinterest.com",
-        "threads.net",
-        // Australian directories
-        "yelp.com",
-        "yelp.com.au",
-        "yellowpages.com.au",
-        "yellowpages.com",
-        "truelocal.com.au",
-        "hotfrog.com.au",
-        "oneflare.com.au",
-        "hipages.com.au",
-        "productreview.com.au",
-        "localsearch.com.au",
-        "startlocal.com.au",
-        "whereis.com",
-        "whitepages.com.au",
-        "aussieweb.com.au",
-        "fyple.com.au",
-        "brownbook.net",
-        "wordofmouth.com.au",
-        "findabusiness.com.au",
-        "cylex.com.au",
-        "opendi.com.au",
-        "tuugo.com.au",
-        "yalwa.com.au",
-        // Marketplaces
-        "airtasker.com",
-        "airtasker.com.au",
-        "serviceseeking.com.au",
-        "bark.com",
-        "bark.com.au",
-        "thumbtack.com",
-        "homeadvisor.com",
-        "angi.com",
-        "angieslist.com",
-        "taskrabbit.com",
-        "fiverr.com",
-        "upwork.com",
-        "freelancer.com",
-        "freelancer.com.au",
-        // Job boards
-        "seek.com.au",
-        "indeed.com",
-        "indeed.com.au",
-        "au.indeed.com",
-        "glassdoor.com",
-        "glassdoor.com.au",
-        "jora.com",
-        "careerone.com.au",
-        // Review aggregators
-        "birdeye.com",
-        "trustpilot.com",
-        "reviews.io",
-        "podium.com",
-        // Generic/tech
-        "wikipedia.org",
-        "google.com",
-        "bing.com",
-        "duckduckgo.com",
-        "apple.com",
-        "g2.com",
-        "capterra.com",
-        "crunchbase.com",
-        "medium.com",
-        "github.com",
-        "stackoverflow.com",
-        // News/media
-        "news.com.au",
-        "smh.com.au",
-        "theaustralian.com.au",
-        "abc.net.au",
-        "9news.com.au",
-        "7news.com.au",
-        "sbs.com.au",
-    ])
+// Social media
+static DIRECTORY_SOCIAL: &[&str] = &[
+    "facebook.com",
+    "linkedin.com",
+    "instagram.com",
+    "twitter.com",
+    "x.com",
+    "youtube.com",
+    "tiktok.com",
+    "reddit.com",
+    "quora.com",
+    "pinterest.com",
+    "threads.net",
+];
+
+// Australian directories
+static DIRECTORY_LISTINGS: &[&str] = &[
+    "yelp.com",
+    "yelp.com.au",
+    "yellowpages.com.au",
+    "yellowpages.com",
+    "truelocal.com.au",
+    "hotfrog.com.au",
+    "oneflare.com.au",
+    "hipages.com.au",
+    "productreview.com.au",
+    "localsearch.com.au",
+    "startlocal.com.au",
+    "whereis.com",
+    "whitepages.com.au",
+    "aussieweb.com.au",
+    "fyple.com.au",
+    "brownbook.net",
+    "wordofmouth.com.au",
+    "findabusiness.com.au",
+    "cylex.com.au",
+    "opendi.com.au",
+    "tuugo.com.au",
+    "yalwa.com.au",
+];
+
+// Marketplaces
+static DIRECTORY_MARKETPLACES: &[&str] = &[
+    "airtasker.com",
+    "airtasker.com.au",
+    "serviceseeking.com.au",
+    "bark.com",
+    "bark.com.au",
+    "thumbtack.com",
+    "homeadvisor.com",
+    "angi.com",
+    "angieslist.com",
+    "taskrabbit.com",
+    "fiverr.com",
+    "upwork.com",
+    "freelancer.com",
+    "freelancer.com.au",
+];
+
+// Job boards
+static DIRECTORY_JOB_BOARDS: &[&str] = &[
+    "seek.com.au",
+    "indeed.com",
+    "indeed.com.au",
+    "au.indeed.com",
+    "glassdoor.com",
+    "glassdoor.com.au",
+    "jora.com",
+    "careerone.com.au",
+];
+
+// Review aggregators
+static DIRECTORY_REVIEW_SITES: &[&str] =
+    &["birdeye.com", "trustpilot.com", "reviews.io", "podium.com"];
+
+// Generic/tech
+static DIRECTORY_TECH: &[&str] = &[
+    "wikipedia.org",
+    "google.com",
+    "bing.com",
+    "duckduckgo.com",
+    "apple.com",
+    "g2.com",
+    "capterra.com",
+    "crunchbase.com",
+    "medium.com",
+    "github.com",
+    "stackoverflow.com",
+];
+
+// News/media
+static DIRECTORY_NEWS: &[&str] = &[
+    "news.com.au",
+    "smh.com.au",
+    "theaustralian.com.au",
+    "abc.net.au",
+    "9news.com.au",
+    "7news.com.au",
+    "sbs.com.au",
+];
+
+// Directory category → domains; a host matches if it or a parent domain is listed
+static DIRECTORY_CATEGORIES: &[(&str, &[&str])] = &[
+    ("social", DIRECTORY_SOCIAL),
+    ("directory", DIRECTORY_LISTINGS),
+    ("marketplace", DIRECTORY_MARKETPLACES),
+    ("job_board", DIRECTORY_JOB_BOARDS),
+    ("review", DIRECTORY_REVIEW_SITES),
+    ("tech", DIRECTORY_TECH),
+    ("news", DIRECTORY_NEWS),
+];
+
+static DIRECTORY_DOMAINS: LazyLock<HashMap<&'static str, &'static str>> = LazyLock::new(|| {
+    DIRECTORY_CATEGORIES
+        .iter()
+        .flat_map(|(category, domains)| domains.iter().map(move |d| (*d, *category)))
+        .collect()
 });
 
+// Runtime additions via register_directory_domains / register_directory_url_patterns
+static EXTRA_DIRECTORY_DOMAINS: LazyLock<RwLock<HashMap<String, &'static str>>> =
+    LazyLock::new(|| RwLock::new(HashMap::new()));
+
+static EXTRA_DIRECTORY_URL_PATTERNS: LazyLock<RwLock<Vec<String>>> =
+    LazyLock::new(|| RwLock::new(Vec::new()));
+
 static DIRECTORY_URL_PATTERNS: &[&str] = &[
     "/r/",
     "/company/",
@@ -119,32 +163,459 @@ static DIRECTORY_URL_PATTERNS: &[&str] = &[
     "/tradies/",
 ];
 
-static GENERIC_EMAIL_PROVIDERS: &[&str] = &[
-    "gmail.com",
-    "yahoo.com",
-    "hotmail.com",
-    "outlook.com",
-    "live.com",
-    "icloud.com",
-    "me.com",
-    "aol.com",
-    "mail.com",
-    "protonmail.com",
-    "zoho.com",
-    "bigpond.com",
-    "bigpond.net.au",
-    "optusnet.com.au",
-    "telstra.com",
-    "tpg.com.au",
-    "internode.on.net",
+// Leading labels that mirror the main site rather than naming a distinct one
+static COMMON_SUBDOMAINS: &[&str] = &[
+    "www", "www1", "www2", "www3", "m", "mobile", "amp", "en", "l", "lm", "shop",
+];
+
+// Query parameters that only carry click/campaign tracking (utm_* handled separately)
+static TRACKING_QUERY_PARAMS: &[&str] = &[
+    "fbclid", "gclid", "gbraid", "wbraid", "dclid", "msclkid", "yclid", "mc_cid", "mc_eid", "_ga",
+    "_gl", "igshid", "ref", "ref_src", "srsltid",
+];
+
+// Special-use / internal TLDs that never belong to a real business site
+static RESERVED_TLDS: &[&str] = &["localhost", "local", "internal", "test", "invalid"];
+
+// Delegated TLDs, compiled in from data/ and extendable at runtime via
+// register_extra_tlds()
+static KNOWN_TLDS: LazyLock<HashSet<&'static str>> = LazyLock::new(|| {
+    include_str!("data/tlds.txt")
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .collect()
+});
+
+static EXTRA_TLDS: LazyLock<RwLock<HashSet<String>>> =
+    LazyLock::new(|| RwLock::new(HashSet::new()));
+
+// URL patterns that also occur on ordinary business sites (WooCommerce
+// categories, "our business" pages, review pages)
+static AMBIGUOUS_DIRECTORY_URL_PATTERNS: &[&str] = &[
+    "/company/",
+    "/local/",
+    "/business/",
+    "/pages/",
+    "/profile/",
+    "/user/",
+    "/comments/",
+    "/questions/",
+    "/listing/",
+    "/review/",
+    "/reviews/",
+    "/category/",
+];
+
+// Words in a domain that suggest an aggregator rather than a single business
+static DIRECTORY_DOMAIN_HINTS: &[&str] = &[
+    "directory",
+    "listing",
+    "local",
+    "pages",
+    "review",
+    "find",
+    "search",
+    "guide",
+    "compare",
+    "yellow",
+    "tradie",
+    "quote",
+];
+
+// Consumer webmail and ISP mailboxes, compiled in from data/ and extendable
+// at runtime via register_generic_providers()
+static GENERIC_EMAIL_PROVIDERS: LazyLock<HashSet<&'static str>> = LazyLock::new(|| {
+    include_str!("data/generic_email_providers.txt")
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .collect()
+});
+
+static EXTRA_GENERIC_PROVIDERS: LazyLock<RwLock<HashSet<String>>> =
+    LazyLock::new(|| RwLock::new(HashSet::new()));
+
+// Public suffixes (com.au, co.nz, github.io, ...) used to find registrable domains
+static PUBLIC_SUFFIXES: LazyLock<HashSet<&'static str>> = LazyLock::new(|| {
+    include_str!("data/public_suffixes.txt")
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .collect()
+});
+
+// Disposable / temporary email providers, compiled in from data/ and
+// extendable at runtime via register_disposable_domains()
+static DISPOSABLE_DOMAINS: LazyLock<HashSet<&'static str>> = LazyLock::new(|| {
+    include_str!("data/disposable_domains.txt")
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .collect()
+});
+
+static EXTRA_DISPOSABLE_DOMAINS: LazyLock<RwLock<HashSet<String>>> =
+    LazyLock::new(|| RwLock::new(HashSet::new()));
+
+// Australian localities for location qualifiers in business names, compiled
+// in from data/ and extendable at runtime
+static AU_LOCALITIES: LazyLock<HashSet<&'static str>> = LazyLock::new(|| {
+    include_str!("data/au_localities.txt")
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .collect()
+});
+
+static EXTRA_LOCALITIES: LazyLock<RwLock<HashSet<String>>> =
+    LazyLock::new(|| RwLock::new(HashSet::new()));
+
+// National franchise brands (display names), compiled in from data/
+static FRANCHISE_BRANDS: LazyLock<Vec<&'static str>> = LazyLock::new(|| {
+    include_str!("data/franchise_brands.txt")
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .collect()
+});
+
+static EXTRA_FRANCHISE_BRANDS: LazyLock<RwLock<Vec<String>>> =
+    LazyLock::new(|| RwLock::new(Vec::new()));
+
+// Trade and common words from data/domain_words.txt
+static VOCABULARY_WORDS: LazyLock<HashSet<&'static str>> = LazyLock::new(|| {
+    include_str!("data/domain_words.txt")
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .collect()
+});
+
+// Dictionary for segmenting concatenated domain labels: data/ word list plus
+// every word of the built-in localities
+static DOMAIN_WORDS: LazyLock<HashSet<&'static str>> = LazyLock::new(|| {
+    VOCABULARY_WORDS
+        .iter()
+        .copied()
+        .chain(
+            AU_LOCALITIES
+                .iter()
+                .flat_map(|l| l.split_whitespace())
+                .filter(|w| w.len() >= 3),
+        )
+        .collect()
+});
+
+// Words that can't stand alone as a business name once a location is removed
+static GENERIC_NAME_WORDS: &[&str] = &[
+    "best",
+    "top",
+    "local",
+    "the",
+    "and",
+    "of",
+    "plumbing",
+    "plumber",
+    "plumbers",
+    "electrical",
+    "electrician",
+    "electricians",
+    "roofing",
+    "roofers",
+    "painting",
+    "painters",
+    "dental",
+    "dentist",
+    "dentists",
+    "cleaning",
+    "cleaners",
+    "landscaping",
+    "builders",
+    "building",
+    "construction",
+    "removals",
+    "movers",
+    "mechanics",
+    "lawyers",
+    "accountants",
+    "services",
+    "service",
+    "solutions",
+    "experts",
+    "specialists",
+    "pest",
+    "control",
+    "air",
+    "conditioning",
 ];
 
+// Local parts that denote a shared inbox rather than a person
+static ROLE_LOCAL_PARTS: LazyLock<HashSet<&'static str>> = LazyLock::new(|| {
+    HashSet::from([
+        "info",
+        "admin",
+        "administration",
+        "sales",
+        "accounts",
+        "account",
+        "accounting",
+        "bookings",
+        "booking",
+        "book",
+        "enquiries",
+        "enquiry",
+        "enquire",
+        "inquiries",
+        "inquiry",
+        "hello",
+        "hi",
+        "office",
+        "reception",
+        "contact",
+        "contactus",
+        "support",
+        "help",
+        "service",
+        "services",
+        "team",
+        "mail",
+        "email",
+        "jobs",
+        "careers",
+        "hr",
+        "marketing",
+        "media",
+        "press",
+        "billing",
+        "finance",
+        "orders",
+        "order",
+        "quotes",
+        "quote",
+        "admin1",
+        "general",
+        "manager",
+        "management",
+        "webmaster",
+        "web",
+        "studio",
+        "shop",
+        "store",
+        "clinic",
+        "appointments",
+        "bookkeeping",
+        "payroll",
+        "operations",
+        "ops",
+        "dispatch",
+        "projects",
+        "estimating",
+        "tenders",
+        "feedback",
+        "customerservice",
+        "care",
+        "mailbox",
+        "hq",
+        "headoffice",
+        "frontdesk",
+        "welcome",
+        "staff",
+        "privacy",
+        "legal",
+    ])
+});
+
+// Common first names used as a confidence check when splitting local parts
+static COMMON_FIRST_NAMES: LazyLock<HashSet<&'static str>> = LazyLock::new(|| {
+    HashSet::from([
+        "aaron",
+        "adam",
+        "adrian",
+        "aidan",
+        "alan",
+        "alex",
+        "alexander",
+        "alice",
+        "alicia",
+        "alison",
+        "amanda",
+        "amy",
+        "andrew",
+        "angela",
+        "anna",
+        "anne",
+        "anthony",
+        "ashley",
+        "ben",
+        "benjamin",
+        "beth",
+        "bill",
+        "brad",
+        "brendan",
+        "brett",
+        "brian",
+        "bruce",
+        "carl",
+        "caroline",
+        "catherine",
+        "charles",
+        "charlotte",
+        "chloe",
+        "chris",
+        "christine",
+        "claire",
+        "craig",
+        "dan",
+        "daniel",
+        "darren",
+        "dave",
+        "david",
+        "dean",
+        "deborah",
+        "dylan",
+        "ed",
+        "edward",
+        "elizabeth",
+        "ella",
+        "emily",
+        "emma",
+        "eric",
+        "fiona",
+        "frank",
+        "gary",
+        "george",
+        "georgia",
+        "glenn",
+        "grace",
+        "graham",
+        "greg",
+        "hannah",
+        "harry",
+        "heather",
+        "helen",
+        "ian",
+        "jack",
+        "jackson",
+        "jacob",
+        "james",
+        "jane",
+        "jason",
+        "jeff",
+        "jen",
+        "jenny",
+        "jennifer",
+        "jess",
+        "jessica",
+        "jim",
+        "joanne",
+        "joe",
+        "john",
+        "jon",
+        "jordan",
+        "josh",
+        "joshua",
+        "julie",
+        "justin",
+        "karen",
+        "kate",
+        "katie",
+        "kelly",
+        "kevin",
+        "kim",
+        "kylie",
+        "laura",
+        "lauren",
+        "leah",
+        "liam",
+        "linda",
+        "lisa",
+        "liz",
+        "luke",
+        "maria",
+        "mark",
+        "martin",
+        "matt",
+        "matthew",
+        "megan",
+        "melissa",
+        "michael",
+        "michelle",
+        "mick",
+        "mike",
+        "mitch",
+        "natalie",
+        "nathan",
+        "nick",
+        "nicole",
+        "noah",
+        "oliver",
+        "olivia",
+        "paul",
+        "peter",
+        "phil",
+        "rachel",
+        "rebecca",
+        "richard",
+        "rob",
+        "robert",
+        "ross",
+        "ryan",
+        "sam",
+        "samantha",
+        "sarah",
+        "scott",
+        "sean",
+        "shane",
+        "sharon",
+        "simon",
+        "sophie",
+        "stephen",
+        "steve",
+        "steven",
+        "stuart",
+        "sue",
+        "susan",
+        "tim",
+        "tom",
+        "tony",
+        "tracey",
+        "troy",
+        "wayne",
+        "will",
+        "william",
+        "zoe",
+    ])
+});
+
+// Common surnames, used to accept "flast" style local parts (jsmith)
+static COMMON_SURNAMES: LazyLock<HashSet<&'static str>> = LazyLock::new(|| {
+    HashSet::from([
+        "smith", "jones", "williams", "brown", "wilson", "taylor", "johnson", "white", "martin",
+        "anderson", "thompson", "nguyen", "thomas", "walker", "harris", "lee", "ryan", "robinson",
+        "kelly", "king", "davis", "wright", "evans", "roberts", "green", "hall", "wood", "jackson",
+        "clarke", "clark", "patel", "khan", "lewis", "james", "phillips", "mitchell", "campbell",
+        "young", "allen", "scott", "baker", "turner", "hill", "moore", "cooper", "ward", "morris",
+        "murphy", "hughes", "edwards", "collins", "stewart", "cook", "morgan", "bell", "murray",
+        "kennedy", "graham", "watson", "reid", "bennett", "russell", "chen", "wang", "li", "zhang",
+        "tran", "singh", "miller",
+    ])
+});
+
 // ---------------------------------------------------------------------------
 // Lazy-compiled regexes
 // ---------------------------------------------------------------------------
 
-static RE_STAR_EMOJIS: LazyLock<Regex> =
-    LazyLock::new(|| Regex::new(r"[\u{2B50}\u{2605}\u{2606}\u{2729}\u{272A}\u{2730}\u{1F31F}]+").unwrap());
+// Emoji (incl. ZWJ joiners, variation selectors, skin tones, flags, keycaps
+// and tags), dingbats, checkmarks, arrows, stars and box-drawing characters
+static RE_DECORATIVE_SYMBOLS: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(concat!(
+        r"[\p{Extended_Pictographic}",
+        r"\u{200D}\u{20E3}\u{FE0E}\u{FE0F}\u{1F3FB}-\u{1F3FF}\u{1F1E6}-\u{1F1FF}\u{E0020}-\u{E007F}",
+        r"\u{2190}-\u{21FF}\u{2500}-\u{27BF}\u{2900}-\u{297F}\u{2B00}-\u{2BFF}]+",
+    ))
+    .unwrap()
+});
 
 static RE_REVIEW_COUNT: LazyLock<Regex> =
     LazyLock::new(|| Regex::new(r"(?i)\d+\.?\d*[Kk]?\+?\s*reviews?").unwrap());
@@ -155,34 +626,95 @@ static RE_REVIEW_COUNT_PARENS: LazyLock<Regex> =
 static RE_NON_WORD_SPACE: LazyLock<Regex> =
     LazyLock::new(|| Regex::new(r"[^\w\s]").unwrap());
 
+// "Rock'n'Roll", "Fish n' Chips", "Fish 'n Chips" (after quote folding)
+static RE_N_CONTRACTION: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"(?i)'n'|\bn'|'n\b").unwrap());
+
+// "+" used as a conjunction ("Bed + Breakfast", "Bed+Breakfast"), not a
+// grade ("A+ Electrical")
+static RE_PLUS_CONJUNCTION: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"\s\+|\+(\w)").unwrap());
+
+static RE_DUPLICATE_SLASHES: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"/{2,}").unwrap());
+
 static RE_NORMALIZE_PHONE: LazyLock<Regex> =
-    LazyLock::new(|| Regex::new(r"[^\d+]").unwrap());
+    LazyLock::new(|| Regex::new(r"[^0-9+]").unwrap());
 
 static MARKETING_SUFFIX_PATTERNS: LazyLock<Vec<Regex>> = LazyLock::new(|| {
     let patterns = [
-        r"(?i)\s*-\s*local\s*&\s*reliable.*",
-        r"(?i)\s*-\s*trusted.*",
-        r"(?i)\s*-\s*best\s*reviewed.*",
-        r"(?i)\s*-\s*same[- ]?day.*",
+        r"(?i)\s*[-–—]\s*local\s*&\s*reliable.*",
+        r"(?i)\s*[-–—]\s*trusted.*",
+        r"(?i)\s*[-–—]\s*best\s*reviewed.*",
+        r"(?i)\s*[-–—]\s*same[- ]?day.*",
         r"(?i)\s*\d+\+?\s*local.*",
-        r"(?i)\s*-\s*#1\s*rated.*",
-        r"(?i)\s*-\s*fast\s*&\s*reliable.*",
-        r"(?i)\s*-\s*affordable.*",
-        r"(?i)\s*-\s*professional.*",
-        r"(?i)\s*-\s*expert.*",
-        r"(?i)\s*-\s*your\s*local.*",
-        r"(?i)\s*-\s*licensed\s*&\s*insured.*",
-        r"(?i)\s*-\s*24/7.*",
-        r"(?i)\s*-\s*free\s*quotes?.*",
+        r"(?i)\s*[-–—]\s*#1\s*rated.*",
+        r"(?i)\s*[-–—]\s*fast\s*&\s*reliable.*",
+        r"(?i)\s*[-–—]\s*affordable.*",
+        r"(?i)\s*[-–—]\s*professional.*",
+        r"(?i)\s*[-–—]\s*expert.*",
+        r"(?i)\s*[-–—]\s*your\s*local.*",
+        r"(?i)\s*[-–—]\s*licensed\s*&\s*insured.*",
+        r"(?i)\s*[-–—]\s*24/7.*",
+        r"(?i)\s*[-–—]\s*free\s*quotes?.*",
     ];
     patterns.iter().map(|p| Regex::new(p).unwrap()).collect()
 });
 
+// Title separators that always end the business name
+static RE_TITLE_DELIMITER: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"\s\|\s|\s*[•·]\s*|:\s").unwrap());
+
+// Dashes separate marketing tails but also appear in real names ("Ray White - Paddington")
+static RE_DASH_DELIMITER: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"\s+-\s+|\s*[–—]\s*").unwrap());
+
+// Page names that follow a "|" in <title>s ("Acme Plumbing | Contact Us")
+static PAGE_NAME_WORDS: &[&str] = &[
+    "home", "homepage", "page", "welcome", "index", "about", "contact", "us", "blog", "news",
+    "gallery", "faq", "faqs", "services", "our", "shop",
+];
+
+// Marketing vocabulary in the text after a separator
+static RE_MARKETING_TAIL: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(
+        r"(?i)\b(?:trusted|best|reviewed|same[- ]?day|rated|fast|reliable|affordable|cheap|professional|experts?|local|licensed|insured|24/7|free|quotes?|call|today|leading|top|quality|award|guaranteed|emergency|near\s+you|no\.?\s*1)\b|#1",
+    )
+    .unwrap()
+});
+
+// A place name on its own: "Paddington", "Gold Coast", "Sydney NSW 2000"
+static RE_LOCATION_TAIL: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"^[A-Z][a-z']+(?:\s+[A-Z][a-z']+){0,2}(?:,?\s+(?:NSW|VIC|QLD|SA|WA|TAS|NT|ACT))?(?:\s+\d{4})?$")
+        .unwrap()
+});
+
 // Build suffix regexes for normalize_name
 static NAME_SUFFIX_PATTERNS: LazyLock<Vec<Regex>> = LazyLock::new(|| {
     let suffixes = [
-        "pty ltd", "pty\\. ltd\\.", "pty\\. ltd", "pty ltd\\.", "limited", "ltd", "inc", "llc",
-        "corp", "co",
+        "pty ltd",
+        "pty\\. ltd\\.",
+        "pty\\. ltd",
+        "pty ltd\\.",
+        "p/l",
+        "proprietary limited",
+        "pty",
+        "limited",
+        "ltd",
+        "incorporated",
+        "inc",
+        "llc",
+        "corporation",
+        "corp",
+        "and co",
+        "and sons",
+        "co",
+        "group",
+        "holdings",
+        "enterprises",
+        "unit trust",
+        "family trust",
+        "discretionary trust",
+        "trust",
+        r"\((?:aust|australia|au|qld|nsw|vic|sa|wa|tas|nt|act)\)",
     ];
     suffixes
         .iter()
@@ -190,12 +722,39 @@ static NAME_SUFFIX_PATTERNS: LazyLock<Vec<Regex>> = LazyLock::new(|| {
         .collect()
 });
 
+// "Acme Pty Ltd t/a Acme Plumbing", "... trading as ..."
+static RE_TRADING_AS: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"(?i)\s+(?:t/as?|trading\s+as)\s+").unwrap());
+
+// "Smith Pty Ltd ATF Smith Family Trust", "The Trustee for Smith Family Trust"
+static RE_AS_TRUSTEE_FOR: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"(?i)\s+(?:atf|as\s+trustee\s+for)\s+.*$").unwrap());
+
+static RE_THE_TRUSTEE_FOR: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"(?i)^the\s+trustee\s+for\s+").unwrap());
+
 // ---------------------------------------------------------------------------
 // Functions
 // ---------------------------------------------------------------------------
 
+/// Normalize a URL or bare host to its canonical domain.
+///
+/// Returns None for unparseable input, IP literals (unless `allow_ip`),
+/// localhost, reserved/internal TLDs (.local, .internal, .test, .invalid),
+/// single-label hosts and all-numeric TLDs. With `strict` (the default) the
+/// final label must also be a delegated TLD (see register_extra_tlds), so
+/// "acme.plumbing-brisbane" and "business.loc" are rejected; punycode TLDs
+/// that pass IDNA validation are always accepted. With
+/// `strip_common_subdomains`, leading m. / mobile. / amp. / www2. style
+/// labels are removed as well.
 #[pyfunction]
-pub fn normalize_domain(raw_url: &str) -> Option<String> {
+#[pyo3(signature = (raw_url, allow_ip=false, strip_common_subdomains=false, strict=true))]
+pub fn normalize_domain(
+    raw_url: &str,
+    allow_ip: bool,
+    strip_common_subdomains: bool,
+    strict: bool,
+) -> Option<String> {
     let url = raw_url.trim();
     if url.is_empty() {
         return None;
@@ -207,13 +766,19 @@ pub fn normalize_domain(raw_url: &str) -> Option<String> {
     }
 
     // Add scheme if missing
-    let with_scheme = if url.starts_with("http://") || url.starts_with("https://") {
+    let lower = url.to_ascii_lowercase();
+    let with_scheme = if lower.starts_with("http://") || lower.starts_with("https://") {
         url.to_string()
     } else {
         format!("https://{}", url)
     };
 
     let parsed = Url::parse(&with_scheme).ok()?;
+    match parsed.host()? {
+        Host::Ipv4(ip) => return allow_ip.then(|| ip.to_string()),
+        Host::Ipv6(ip) => return allow_ip.then(|| ip.to_string()),
+        Host::Domain(_) => {}
+    }
     let host = parsed.host_str()?;
     let mut domain = host.to_lowercase();
 
@@ -223,32 +788,340 @@ pub fn normalize_domain(raw_url: &str) -> Option<String> {
     }
 
     // Remove port (already handled by Url::host_str, but just in case of host:port in input)
-    if let Some(idx) = domain.find(':') {
-        domain = domain[..idx].to_string();
+    if let Some((host, _port)) = domain.split_once(':') {
+        domain = host.to_string();
     }
 
     // Validate
     if !domain.contains('.') || domain.len() < 4 {
         return None;
     }
-    if domain.contains(|c: char| matches!(c, ' ' | '<' | '>' | '"' | '\'' | ';')) {
+    if domain.contains([' ', '<', '>', '"', '\'', ';']) {
+        return None;
+    }
+
+    // Dev / internal hosts that directories sometimes embed
+    let tld = domain.rsplit('.').next().unwrap_or_default();
+    if RESERVED_TLDS.contains(&tld) || tld.chars().all(|c| c.is_ascii_digit()) {
+        return None;
+    }
+
+    // Canonical form is lowercase punycode. Url already converts Unicode
+    // hosts; the strict pass rejects bad xn-- labels, disallowed characters
+    // and overlong labels instead of returning half-converted output.
+    let domain = idna::domain_to_ascii_strict(&domain).ok()?;
+    if strict && !is_known_tld(domain.rsplit('.').next().unwrap_or_default()) {
         return None;
     }
 
+    if strip_common_subdomains {
+        return Some(strip_common_subdomains_of(&domain).to_string());
+    }
     Some(domain)
 }
 
+/// True for a delegated (or registered) TLD. Any punycode TLD counts, since
+/// new IDN TLDs are rare enough not to be junk and IDNA already validated it.
+fn is_known_tld(tld: &str) -> bool {
+    tld.starts_with("xn--") || KNOWN_TLDS.contains(tld) || EXTRA_TLDS.read().unwrap().contains(tld)
+}
+
+/// Add TLDs to the known set at runtime (leading dots are ignored; Unicode
+/// TLDs are stored as punycode). Returns the number of newly added TLDs.
+#[pyfunction]
+pub fn register_extra_tlds(tlds: Vec<String>) -> PyResult<usize> {
+    let normalized = tlds
+        .iter()
+        .map(|entry| {
+            tld_entry(entry)
+                .ok_or_else(|| PyValueError::new_err(format!("Invalid TLD entry: {:?}", entry)))
+        })
+        .collect::<PyResult<Vec<_>>>()?;
+    Ok(add_extra_tlds(normalized))
+}
+
+fn tld_entry(entry: &str) -> Option<String> {
+    let tld = entry.trim().trim_start_matches('.');
+    idna::domain_to_ascii_strict(tld).ok().filter(|t| {
+        !t.is_empty() && !t.contains('.') && !t.starts_with(|c: char| c.is_ascii_digit())
+    })
+}
+
+/// Merge already-normalized TLDs into the runtime set.
+fn add_extra_tlds(tlds: Vec<String>) -> usize {
+    let mut extra = EXTRA_TLDS.write().unwrap();
+    tlds.into_iter()
+        .filter(|t| !KNOWN_TLDS.contains(t.as_str()) && extra.insert(t.clone()))
+        .count()
+}
+
+/// Drop leading mobile/mirror labels (m., mobile., amp., www2., ...) as long
+/// as what remains still has a registrable domain, so "m.com.au" is kept.
+fn strip_common_subdomains_of(host: &str) -> &str {
+    let mut host = host;
+    while let Some((label, rest)) = host.split_once('.') {
+        if !COMMON_SUBDOMAINS.contains(&label) || registrable_domain_of(rest).is_none() {
+            break;
+        }
+        host = rest;
+    }
+    host
+}
+
+/// Registrable domain for a URL: "https://m.brisbane.acme.com.au/x" →
+/// "acme.com.au". Use normalize_domain when host-level precision matters.
+#[pyfunction]
+pub fn canonical_domain(raw_url: &str) -> Option<String> {
+    let host = normalize_domain(raw_url, false, false, true)?;
+    registrable_domain_of(&host).map(str::to_string)
+}
+
+// Below this many URLs the rayon fan-out costs more than it saves
+const BATCH_PARALLEL_THRESHOLD: usize = 1000;
+
+/// normalize_domain over many URLs with the GIL released. Output order and
+/// length always match the input; invalid URLs map to None.
+#[pyfunction]
+#[pyo3(signature = (urls, allow_ip=false, strip_common_subdomains=false, strict=true))]
+pub fn normalize_domains_batch(
+    py: Python<'_>,
+    urls: Vec<String>,
+    allow_ip: bool,
+    strip_common_subdomains: bool,
+    strict: bool,
+) -> Vec<Option<String>> {
+    py.allow_threads(|| normalize_domains_of(&urls, allow_ip, strip_common_subdomains, strict))
+}
+
+fn normalize_domains_of(
+    urls: &[String],
+    allow_ip: bool,
+    strip_common_subdomains: bool,
+    strict: bool,
+) -> Vec<Option<String>> {
+    let normalize = |url: &String| normalize_domain(url, allow_ip, strip_common_subdomains, strict);
+    if urls.len() < BATCH_PARALLEL_THRESHOLD {
+        urls.iter().map(normalize).collect()
+    } else {
+        urls.par_iter().map(normalize).collect()
+    }
+}
+
+/// Deduplicated normalized domains with the index of the first input URL
+/// that produced each, in first-seen order. Invalid URLs are skipped.
+#[pyfunction]
+pub fn unique_domains(py: Python<'_>, urls: Vec<String>) -> Vec<(String, usize)> {
+    let domains = normalize_domains_batch(py, urls, false, false, true);
+    first_seen_domains(&domains)
+}
+
+fn first_seen_domains(domains: &[Option<String>]) -> Vec<(String, usize)> {
+    let mut seen: HashSet<&str> = HashSet::new();
+    let mut unique = Vec::new();
+
+    for (idx, domain) in domains.iter().enumerate() {
+        if let Some(domain) = domain {
+            if seen.insert(domain) {
+                unique.push((domain.clone(), idx));
+            }
+        }
+    }
+
+    unique
+}
+
+fn is_tracking_param(key: &str, extra: &[String]) -> bool {
+    let key = key.to_ascii_lowercase();
+    key.starts_with("utm_")
+        || TRACKING_QUERY_PARAMS.contains(&key.as_str())
+        || extra.iter().any(|p| p.eq_ignore_ascii_case(&key))
+}
+
+fn canonicalize_url_inner(raw_url: &str, extra_params: &[String]) -> Option<String> {
+    let url = raw_url.trim();
+    if url.is_empty() {
+        return None;
+    }
+
+    let lower = url.to_ascii_lowercase();
+    let with_scheme = if lower.starts_with("http://") || lower.starts_with("https://") {
+        url.to_string()
+    } else if lower.contains("://") {
+        return None;
+    } else {
+        format!("https://{}", url)
+    };
+
+    // Url lowercases scheme and host and drops default ports
+    let mut parsed = Url::parse(&with_scheme).ok()?;
+    parsed.host()?;
+    parsed.set_fragment(None);
+
+    let mut params: Vec<(String, String)> = parsed
+        .query_pairs()
+        .filter(|(k, _)| !is_tracking_param(k, extra_params))
+        .map(|(k, v)| (k.into_owned(), v.into_owned()))
+        .collect();
+    params.sort();
+
+    if params.is_empty() {
+        parsed.set_query(None);
+    } else {
+        let query = url::form_urlencoded::Serializer::new(String::new())
+            .extend_pairs(&params)
+            .finish();
+        parsed.set_query(Some(&query));
+    }
+
+    let mut path = RE_DUPLICATE_SLASHES
+        .replace_all(parsed.path(), "/")
+        .into_owned();
+    if params.is_empty() && path.len() > 1 && path.ends_with('/') {
+        path.pop();
+    }
+    parsed.set_path(&path);
+
+    Some(parsed.to_string())
+}
+
+/// Canonical form of a page URL for dedup: lowercase scheme/host, no default
+/// port, no fragment, tracking params (utm_*, fbclid, gclid, ...) and any
+/// `extra_params` removed, remaining params sorted, duplicate slashes
+/// collapsed and no trailing slash on path-only URLs. None if unparseable.
+#[pyfunction]
+#[pyo3(signature = (url, extra_params=None))]
+pub fn canonicalize_url(url: &str, extra_params: Option<Vec<String>>) -> Option<String> {
+    canonicalize_url_inner(url, &extra_params.unwrap_or_default())
+}
+
+/// canonicalize_url over many URLs with the GIL released; order preserved.
+#[pyfunction]
+#[pyo3(signature = (urls, extra_params=None))]
+pub fn canonicalize_urls_batch(
+    py: Python<'_>,
+    urls: Vec<String>,
+    extra_params: Option<Vec<String>>,
+) -> Vec<Option<String>> {
+    let extra = extra_params.unwrap_or_default();
+    py.allow_threads(|| {
+        let canonicalize = |url: &String| canonicalize_url_inner(url, &extra);
+        if urls.len() < BATCH_PARALLEL_THRESHOLD {
+            urls.iter().map(canonicalize).collect()
+        } else {
+            urls.par_iter().map(canonicalize).collect()
+        }
+    })
+}
+
+/// Convert a (punycode) domain back to Unicode for display.
+/// "xn--mnchner-bckerei-8kb31b.de" → "münchner-bäckerei.de". Returns the
+/// input lowercased when it isn't valid IDNA.
+#[pyfunction]
+pub fn display_domain(domain: &str) -> String {
+    let (unicode, result) = idna::domain_to_unicode(domain.trim());
+    match result {
+        Ok(()) => unicode,
+        Err(_) => domain.trim().to_lowercase(),
+    }
+}
+
+/// Transliterate to plain ASCII letters where possible (NFKD, dropping
+/// combining marks) and fold curly quotes and unicode dashes.
+fn fold_unicode(s: &str) -> String {
+    let mut out = String::with_capacity(s.len());
+    for c in s.nfkd() {
+        if is_combining_mark(c) {
+            continue;
+        }
+        match c {
+            '\u{2018}' | '\u{2019}' | '\u{201A}' | '\u{201B}' | '\u{2032}' | '`' => out.push('\''),
+            '\u{201C}' | '\u{201D}' | '\u{201E}' | '\u{2033}' => out.push('"'),
+            '\u{2010}'..='\u{2015}' | '\u{2212}' => out.push('-'),
+            'ß' => out.push_str("ss"),
+            'æ' => out.push_str("ae"),
+            'Æ' => out.push_str("AE"),
+            'œ' => out.push_str("oe"),
+            'Œ' => out.push_str("OE"),
+            'ø' => out.push('o'),
+            'Ø' => out.push('O'),
+            'ł' => out.push('l'),
+            'Ł' => out.push('L'),
+            'đ' => out.push('d'),
+            'Đ' => out.push('D'),
+            'þ' => out.push_str("th"),
+            _ => out.push(c),
+        }
+    }
+    out
+}
+
+/// Split "Legal t/a Trading" into its two halves.
+fn split_trading_as(name: &str) -> Option<(&str, &str)> {
+    let m = RE_TRADING_AS.find(name)?;
+    let legal = name[..m.start()].trim();
+    let trading = name[m.end()..].trim();
+    (!legal.is_empty() && !trading.is_empty()).then_some((legal, trading))
+}
+
+/// Split a registered name into legal and trading names.
+///
+/// "Smith Pty Ltd t/a Acme Plumbing" → {legal_name: "Smith Pty Ltd",
+/// trading_name: "Acme Plumbing"}. Without a "t/a" / "trading as" marker the
+/// whole name is the legal name and trading_name is None.
+#[pyfunction]
+pub fn parse_business_name(py: Python<'_>, name: &str) -> PyResult<PyObject> {
+    let name = name.split_whitespace().collect::<Vec<_>>().join(" ");
+    let dict = PyDict::new(py);
+    match split_trading_as(&name) {
+        Some((legal, trading)) => {
+            dict.set_item("legal_name", legal)?;
+            dict.set_item("trading_name", trading)?;
+        }
+        None => {
+            dict.set_item("legal_name", &name)?;
+            dict.set_item("trading_name", py.None())?;
+        }
+    }
+    Ok(dict.into())
+}
+
+/// Normalize a business name for comparison: transliterated, lowercased,
+/// "&"/"+" read as "and", trading name preferred over the legal entity,
+/// legal suffixes removed, punctuation stripped. Idempotent.
 #[pyfunction]
 pub fn normalize_name(name: &str) -> String {
     if name.is_empty() {
         return String::new();
     }
 
-    let mut normalized = name.to_lowercase();
+    let folded = fold_unicode(name);
+    let folded = RE_N_CONTRACTION.replace_all(&folded, " n ");
+    let folded = RE_PLUS_CONJUNCTION.replace_all(&folded, |caps: &regex::Captures| {
+        format!(" and {}", caps.get(1).map_or("", |m| m.as_str()))
+    });
+    let mut normalized = folded
+        .to_lowercase()
+        .replace('&', " and ")
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ");
+
+    // "X t/a Y" → the trading name Y; drop trustee clauses
+    if let Some((_, trading)) = split_trading_as(&normalized) {
+        normalized = trading.to_string();
+    }
+    normalized = RE_AS_TRUSTEE_FOR.replace(&normalized, "").to_string();
+    normalized = RE_THE_TRUSTEE_FOR.replace(&normalized, "").to_string();
 
-    // Remove common business suffixes
-    for re in NAME_SUFFIX_PATTERNS.iter() {
-        normalized = re.replace(&normalized, "").to_string();
+    // Remove common business suffixes until none are left ("Acme Co Pty Ltd")
+    loop {
+        let before = normalized.len();
+        for re in NAME_SUFFIX_PATTERNS.iter() {
+            normalized = re.replace(&normalized, "").to_string();
+        }
+        if normalized.len() == before {
+            break;
+        }
     }
 
     // Remove special characters except spaces (equivalent to [^\w\s])
@@ -258,25 +1131,84 @@ pub fn normalize_name(name: &str) -> String {
     normalized.split_whitespace().collect::<Vec<_>>().join(" ")
 }
 
+/// A title segment that is only a place name. Page names ("Contact Us") and
+/// trade phrases ("Plumbers Brisbane") are capitalised the same way, so
+/// every word must be part of a known locality or outside the trade and
+/// page-name vocabulary.
+fn is_place_segment(segment: &str) -> bool {
+    if !RE_LOCATION_TAIL.is_match(segment) {
+        return false;
+    }
+    let extra = EXTRA_LOCALITIES.read().unwrap();
+    segment
+        .split(|c: char| c.is_whitespace() || c == ',')
+        .filter(|w| !w.is_empty())
+        .map(str::to_lowercase)
+        .all(|word| {
+            AU_LOCALITIES
+                .iter()
+                .any(|l| l.split(' ').any(|part| part == word))
+                || extra.iter().any(|l| l.split(' ').any(|part| part == word))
+                || !(VOCABULARY_WORDS.contains(word.as_str())
+                    || PAGE_NAME_WORDS.contains(&word.as_str()))
+        })
+}
+
+/// Clean a business name scraped from a SERP/Maps title.
+///
+/// Removes emoji and decorative symbols, review counts and marketing fluff,
+/// and cuts at title separators. Tails read as marketing ("Acme Plumbing –
+/// Brisbane's Trusted Plumbers") are always cut; a tail that is just a
+/// place name is only cut when `keep_location` is false, so franchise
+/// offices like "Ray White - Paddington" or "Ray White | Paddington" stay
+/// distinct by default. Any other tail after "|", ":" or "•" is cut, while
+/// one after a dash is kept.
 #[pyfunction]
-pub fn clean_business_name(name: &str) -> String {
+#[pyo3(signature = (name, keep_location=true))]
+pub fn clean_business_name(name: &str, keep_location: bool) -> String {
     if name.is_empty() {
         return String::new();
     }
 
     let mut result = name.to_string();
 
-    // Remove star emojis
-    result = RE_STAR_EMOJIS.replace_all(&result, "").to_string();
+    // Remove emoji and decorative symbols
+    result = RE_DECORATIVE_SYMBOLS.replace_all(&result, " ").to_string();
 
     // Remove review counts (parenthesized first, then bare)
     result = RE_REVIEW_COUNT_PARENS.replace_all(&result, "").to_string();
     result = RE_REVIEW_COUNT.replace_all(&result, "").to_string();
 
-    // Split at delimiters, keep first part
-    for delimiter in [" | ", " - ", ": "] {
-        if let Some(idx) = result.find(delimiter) {
-            result = result[..idx].to_string();
+    // Cut at the first separator whose segment is marketing or a location
+    // we aren't keeping. "|", ":" and "•" usually start a page-title tail,
+    // so they also cut before anything else except a kept place name
+    // ("Ray White | Paddington").
+    let mut separators: Vec<(usize, usize, bool)> = RE_TITLE_DELIMITER
+        .find_iter(&result)
+        .map(|m| (m.start(), m.end(), true))
+        .chain(
+            RE_DASH_DELIMITER
+                .find_iter(&result)
+                .map(|m| (m.start(), m.end(), false)),
+        )
+        .collect();
+    separators.sort_unstable();
+    for (k, &(start, end, hard)) in separators.iter().enumerate() {
+        let segment_end = separators
+            .get(k + 1)
+            .map_or(result.len(), |next| next.0.max(end));
+        let segment = result[end..segment_end].trim();
+        let location = if hard {
+            is_place_segment(segment)
+        } else {
+            RE_LOCATION_TAIL.is_match(segment)
+        };
+        let cut = RE_MARKETING_TAIL.is_match(segment)
+            || (location && !keep_location)
+            || (hard && !location);
+        if cut {
+            result.truncate(start);
+            break;
         }
     }
 
@@ -309,117 +1241,4257 @@ pub fn normalize_phone(phone: &str) -> String {
     digits
 }
 
-#[pyfunction]
-pub fn is_directory_domain(domain: &str) -> bool {
-    if domain.is_empty() {
-        return false;
-    }
-    let domain_lower = domain.to_lowercase();
-    for dir_domain in DIRECTORY_DOMAINS.iter() {
-        if domain_lower == *dir_domain {
-            return true;
+/// Directory category of a lowercased host, checking the host and each
+/// parent down to the registrable domain so facebook.com also matches
+/// m.facebook.com.
+fn directory_category_of(domain_lower: &str) -> Option<&'static str> {
+    let floor = split_host(domain_lower)
+        .registrable
+        .map_or(domain_lower.len(), str::len);
+    let extra = EXTRA_DIRECTORY_DOMAINS.read().unwrap();
+    let mut candidate = domain_lower;
+    loop {
+        if let Some(category) = DIRECTORY_DOMAINS.get(candidate) {
+            return Some(category);
         }
-        if domain_lower.ends_with(&format!(".{}", dir_domain)) {
-            return true;
+        if let Some(category) = extra.get(candidate) {
+            return Some(category);
+        }
+        match candidate.split_once('.') {
+            Some((_, parent)) if parent.len() >= floor => candidate = parent,
+            _ => return None,
         }
     }
-    false
 }
 
 #[pyfunction]
-pub fn is_directory_url(url: &str, domain: &str) -> bool {
+pub fn is_directory_domain(domain: &str) -> bool {
     if domain.is_empty() {
         return false;
     }
+    directory_category_of(&domain.to_lowercase()).is_some()
+}
 
-    // Check domain
-    if is_directory_domain(domain) {
-        return true;
-    }
-
-    // Check URL patterns
-    if !url.is_empty() {
-        let url_lower = url.to_lowercase();
-        for pattern in DIRECTORY_URL_PATTERNS {
-            if url_lower.contains(pattern) {
-                return true;
-            }
-        }
+/// Category of a directory/platform domain: "social", "directory",
+/// "marketplace", "job_board", "review", "news" or "tech". None for normal
+/// business domains.
+#[pyfunction]
+pub fn classify_domain(domain: &str) -> Option<&'static str> {
+    if domain.is_empty() {
+        return None;
     }
+    directory_category_of(&domain.to_lowercase())
+}
 
-    false
+/// Lowercased path segments of a URL and whether it carries a query string.
+/// Relative URLs ("/biz/acme", "//yelp.com/biz/acme") are resolved against a
+/// placeholder base; anything else without a scheme is taken as host + path.
+fn url_path_segments(url: &str) -> Option<(Vec<String>, bool)> {
+    let lower = url.trim().to_lowercase();
+    let parsed = if lower.starts_with("http://") || lower.starts_with("https://") {
+        Url::parse(&lower).ok()?
+    } else if lower.starts_with('/') {
+        Url::parse("https://relative.invalid/")
+            .ok()?
+            .join(&lower)
+            .ok()?
+    } else {
+        Url::parse(&format!("https://{}", lower)).ok()?
+    };
+    let segments = parsed
+        .path_segments()
+        .map(|segs| segs.filter(|s| !s.is_empty()).map(str::to_string).collect())
+        .unwrap_or_default();
+    let has_query = parsed.query().is_some_and(|q| !q.is_empty());
+    Some((segments, has_query))
 }
 
-fn get_base_domain(parts: &[&str]) -> String {
-    let len = parts.len();
-    if len >= 3 && matches!(parts[len - 2], "com" | "net" | "org" | "gov" | "edu") {
-        parts[len - 3..].join(".")
-    } else if len >= 2 {
-        parts[len - 2..].join(".")
+/// Match a directory URL pattern against whole path segments:
+/// "/biz/" → a "biz" segment, "/find-a-" → a segment starting "find-a-",
+/// "/search?" → a "search" segment with a query string.
+fn url_pattern_matches(pattern: &str, segments: &[String], has_query: bool) -> bool {
+    let pattern = pattern.trim_start_matches('/');
+    if let Some(segment) = pattern.strip_suffix('?') {
+        has_query && segments.iter().any(|s| s == segment)
+    } else if let Some(segment) = pattern.strip_suffix('/') {
+        segments.iter().any(|s| s == segment)
     } else {
-        parts.join(".")
+        segments.iter().any(|s| s.starts_with(pattern))
     }
 }
 
+/// True if the URL is a directory/listing page.
+///
+/// Domains are checked first. URL patterns are then matched against whole
+/// path segments. Ambiguous patterns ("/business/", "/category/", ...) also
+/// appear on real business sites, so with `strict` they only count when the
+/// domain itself looks like a directory.
 #[pyfunction]
-pub fn validate_email_domain(email: &str, website_domain: &str) -> (bool, String) {
-    if email.is_empty() || website_domain.is_empty() {
-        return (true, "No email or domain".to_string());
+#[pyo3(signature = (url, domain, strict=true))]
+pub fn is_directory_url(url: &str, domain: &str, strict: bool) -> bool {
+    if domain.is_empty() {
+        return false;
     }
 
-    let email_domain = match email.rsplit_once('@') {
-        Some((_, d)) => d.to_lowercase(),
-        None => return (true, "Invalid email format".to_string()),
-    };
+    // Check domain
+    if is_directory_domain(domain) {
+        return true;
+    }
 
-    if email_domain.is_empty() {
-        return (true, "Invalid email format".to_string());
+    // Check URL patterns
+    if url.is_empty() {
+        return false;
     }
+    let Some((segments, has_query)) = url_path_segments(url) else {
+        return false;
+    };
 
-    let website = website_domain.to_lowercase().replace("www.", "");
+    let domain_lower = domain.to_lowercase();
+    let directory_ish = DIRECTORY_DOMAIN_HINTS
+        .iter()
+        .any(|hint| domain_lower.contains(hint));
 
-    // Exact match
-    if email_domain == website {
-        return (true, "Exact match".to_string());
+    for pattern in DIRECTORY_URL_PATTERNS {
+        if strict && !directory_ish && AMBIGUOUS_DIRECTORY_URL_PATTERNS.contains(pattern) {
+            continue;
+        }
+        if url_pattern_matches(pattern, &segments, has_query) {
+            return true;
+        }
     }
 
-    // Subdomain
-    if email_domain.ends_with(&format!(".{}", website)) {
-        return (true, "Subdomain".to_string());
-    }
+    let extra = EXTRA_DIRECTORY_URL_PATTERNS.read().unwrap();
+    extra
+        .iter()
+        .any(|pattern| url_pattern_matches(pattern, &segments, has_query))
+}
 
-    // Parent domain
-    if website.ends_with(&format!(".{}", email_domain)) {
-        return (true, "Parent domain".to_string());
-    }
+/// Add directory domains at runtime under `category` (default "directory").
+/// Entries are lowercased with leading "." / "@" stripped; invalid entries
+/// or unknown categories raise ValueError. Returns the number newly added.
+#[pyfunction]
+#[pyo3(signature = (domains, category="directory"))]
+pub fn register_directory_domains(domains: Vec<String>, category: &str) -> PyResult<usize> {
+    let category = DIRECTORY_CATEGORIES
+        .iter()
+        .map(|(name, _)| *name)
+        .find(|name| *name == category)
+        .ok_or_else(|| {
+            PyValueError::new_err(format!("Unknown directory category: {:?}", category))
+        })?;
 
-    // Same base domain
-    let email_parts: Vec<&str> = email_domain.split('.').collect();
-    let website_parts: Vec<&str> = website.split('.').collect();
-    if get_base_domain(&email_parts) == get_base_domain(&website_parts) {
-        return (true, "Same base domain".to_string());
-    }
+    let normalized = domains
+        .iter()
+        .map(|d| normalize_domain_entry(d))
+        .collect::<PyResult<Vec<_>>>()?;
+    Ok(add_directory_domains(normalized, category))
+}
 
-    // Generic providers
-    for provider in GENERIC_EMAIL_PROVIDERS {
-        if email_domain == *provider {
-            return (true, "Generic provider".to_string());
+/// Merge already-normalized domains into the runtime directory set.
+fn add_directory_domains(domains: Vec<String>, category: &'static str) -> usize {
+    let mut extra = EXTRA_DIRECTORY_DOMAINS.write().unwrap();
+    let mut added = 0;
+    for domain in domains {
+        // A domain keeps the category it was first registered under
+        if !DIRECTORY_DOMAINS.contains_key(domain.as_str()) && !extra.contains_key(&domain) {
+            extra.insert(domain, category);
+            added += 1;
         }
     }
-
-    (
-        false,
-        format!("Domain mismatch: {} vs {}", email_domain, website),
-    )
+    added
 }
 
+/// Add URL path patterns (e.g. "/listing/") that mark directory pages.
+/// Patterns must start with "/" and contain no whitespace, otherwise
+/// ValueError is raised. Returns the number newly added.
 #[pyfunction]
-pub fn filter_emails_for_domain(emails: Vec<String>, website_domain: &str) -> Vec<String> {
-    if emails.is_empty() {
-        return Vec::new();
-    }
-    emails
+pub fn register_directory_url_patterns(patterns: Vec<String>) -> PyResult<usize> {
+    let normalized = patterns
+        .iter()
+        .map(|p| {
+            directory_url_pattern_entry(p)
+                .ok_or_else(|| PyValueError::new_err(format!("Invalid URL pattern: {:?}", p)))
+        })
+        .collect::<PyResult<Vec<_>>>()?;
+    Ok(add_directory_url_patterns(normalized))
+}
+
+/// Lowercased URL pattern, or None unless it starts with "/" and has no
+/// whitespace.
+fn directory_url_pattern_entry(pattern: &str) -> Option<String> {
+    let pattern = pattern.trim().to_lowercase();
+    let valid =
+        pattern.len() >= 2 && pattern.starts_with('/') && !pattern.contains(char::is_whitespace);
+    valid.then_some(pattern)
+}
+
+/// Merge already-normalized URL patterns into the runtime pattern list.
+fn add_directory_url_patterns(patterns: Vec<String>) -> usize {
+    let mut extra = EXTRA_DIRECTORY_URL_PATTERNS.write().unwrap();
+    let mut added = 0;
+    for pattern in patterns {
+        if !DIRECTORY_URL_PATTERNS.contains(&pattern.as_str()) && !extra.contains(&pattern) {
+            extra.push(pattern);
+            added += 1;
+        }
+    }
+    added
+}
+
+/// All active directory domains (built-in plus runtime additions), sorted.
+#[pyfunction]
+pub fn list_directory_domains() -> Vec<String> {
+    let extra = EXTRA_DIRECTORY_DOMAINS.read().unwrap();
+    let mut domains: Vec<String> = DIRECTORY_DOMAINS
+        .keys()
+        .map(|d| d.to_string())
+        .chain(extra.keys().cloned())
+        .collect();
+    domains.sort();
+    domains
+}
+
+/// All active directory URL patterns (built-in first, then runtime additions).
+#[pyfunction]
+pub fn list_directory_url_patterns() -> Vec<String> {
+    let extra = EXTRA_DIRECTORY_URL_PATTERNS.read().unwrap();
+    DIRECTORY_URL_PATTERNS
+        .iter()
+        .map(|p| p.to_string())
+        .chain(extra.iter().cloned())
+        .collect()
+}
+
+/// Drop every runtime-registered directory domain and URL pattern.
+#[pyfunction]
+pub fn reset_directory_overrides() {
+    EXTRA_DIRECTORY_DOMAINS.write().unwrap().clear();
+    EXTRA_DIRECTORY_URL_PATTERNS.write().unwrap().clear();
+}
+
+/// Length in bytes of the public suffix of `host` (e.g. "com.au" in
+/// "acme.com.au"). Unlisted TLDs fall back to the last label.
+fn public_suffix_len(host: &str) -> usize {
+    let mut candidate = host;
+    loop {
+        if PUBLIC_SUFFIXES.contains(candidate) {
+            return candidate.len();
+        }
+        match candidate.split_once('.') {
+            Some((_, rest)) => candidate = rest,
+            None => return candidate.len(),
+        }
+    }
+}
+
+/// A lowercased host split on its public suffix:
+/// "bookings.acmeplumbing.com.au" → ("bookings", "acmeplumbing.com.au", "com.au").
+struct DomainParts<'a> {
+    subdomain: Option<&'a str>,
+    registrable: Option<&'a str>,
+    suffix: &'a str,
+}
+
+fn split_host(host: &str) -> DomainParts<'_> {
+    let suffix_len = public_suffix_len(host);
+    let suffix = &host[host.len() - suffix_len..];
+    if suffix_len >= host.len() {
+        return DomainParts {
+            subdomain: None,
+            registrable: None,
+            suffix,
+        };
+    }
+
+    let prefix = &host[..host.len() - suffix_len - 1];
+    let (subdomain, start) = match prefix.rfind('.') {
+        Some(i) => (Some(&prefix[..i]), i + 1),
+        None => (None, 0),
+    };
+    DomainParts {
+        subdomain,
+        registrable: Some(&host[start..]),
+        suffix,
+    }
+}
+
+/// Registrable domain of a lowercased host, None for a bare public suffix.
+fn registrable_domain_of(host: &str) -> Option<&str> {
+    split_host(host).registrable
+}
+
+/// Lowercase, trim and ASCII-encode a user-supplied host for split_host.
+fn prepare_host(host: &str) -> Option<String> {
+    let host = host.trim().trim_end_matches('.').to_lowercase();
+    if host.is_empty() {
+        return None;
+    }
+    if host.is_ascii() {
+        Some(host)
+    } else {
+        idna::domain_to_ascii(&host).ok()
+    }
+}
+
+/// Split a host into subdomain, registrable domain and public suffix.
+///
+/// Returns a dict with keys:
+///   - "subdomain": e.g. "bookings", or None
+///   - "registrable": e.g. "acmeplumbing.com.au", or None for a bare suffix
+///   - "suffix": e.g. "com.au", or None for empty / invalid input
+///
+/// Unicode hosts are converted to punycode first.
+#[pyfunction]
+pub fn split_domain(py: Python<'_>, host: &str) -> PyResult<PyObject> {
+    let dict = PyDict::new(py);
+    let host = prepare_host(host);
+    let parts = host.as_deref().map(split_host);
+
+    dict.set_item("subdomain", parts.as_ref().and_then(|p| p.subdomain))?;
+    dict.set_item("registrable", parts.as_ref().and_then(|p| p.registrable))?;
+    dict.set_item("suffix", parts.as_ref().map(|p| p.suffix))?;
+    Ok(dict.into())
+}
+
+/// Registrable domain for a host, using the compiled-in public suffix list.
+/// "bookings.acmeplumbing.com.au" → "acmeplumbing.com.au"; None for bare
+/// suffixes such as "com.au".
+#[pyfunction]
+pub fn registrable_domain(host: &str) -> Option<String> {
+    let host = prepare_host(host)?;
+    registrable_domain_of(&host).map(str::to_string)
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum DomainMatchType {
+    Exact,
+    Subdomain,
+    Parent,
+    BaseDomain,
+    GenericProvider,
+    Disposable,
+    Mismatch,
+    NoData,
+    InvalidFormat,
+}
+
+impl DomainMatchType {
+    fn as_str(self) -> &'static str {
+        match self {
+            DomainMatchType::Exact => "exact",
+            DomainMatchType::Subdomain => "subdomain",
+            DomainMatchType::Parent => "parent",
+            DomainMatchType::BaseDomain => "base_domain",
+            DomainMatchType::GenericProvider => "generic_provider",
+            DomainMatchType::Disposable => "disposable",
+            DomainMatchType::Mismatch => "mismatch",
+            DomainMatchType::NoData => "no_data",
+            DomainMatchType::InvalidFormat => "invalid_format",
+        }
+    }
+
+    /// Kind of mailbox behind the email domain: a consumer webmail/ISP
+    /// inbox, a disposable inbox, or a business's own domain (which may or
+    /// may not be the website's business). None when there was nothing to
+    /// check.
+    fn provider(self) -> Option<&'static str> {
+        match self {
+            DomainMatchType::GenericProvider => Some("consumer"),
+            DomainMatchType::Disposable => Some("disposable"),
+            DomainMatchType::NoData | DomainMatchType::InvalidFormat => None,
+            _ => Some("business"),
+        }
+    }
+}
+
+struct DomainValidation {
+    valid: bool,
+    match_type: DomainMatchType,
+    email_domain: String,
+    website_domain: String,
+    message: String,
+}
+
+fn check_email_domain(email: &str, website_domain: &str) -> DomainValidation {
+    let website = website_domain.to_lowercase().replace("www.", "");
+    let email_domain = email
+        .rsplit_once('@')
+        .map(|(_, d)| d.to_lowercase())
+        .unwrap_or_default();
+
+    let (valid, match_type, message) = if email.is_empty() || website_domain.is_empty() {
+        (
+            true,
+            DomainMatchType::NoData,
+            "No email or domain".to_string(),
+        )
+    } else if email_domain.is_empty() {
+        (
+            true,
+            DomainMatchType::InvalidFormat,
+            "Invalid email format".to_string(),
+        )
+    } else if email_domain == website {
+        (true, DomainMatchType::Exact, "Exact match".to_string())
+    } else if email_domain.ends_with(&format!(".{}", website)) {
+        (true, DomainMatchType::Subdomain, "Subdomain".to_string())
+    } else if website.ends_with(&format!(".{}", email_domain)) {
+        (true, DomainMatchType::Parent, "Parent domain".to_string())
+    } else if split_host(&email_domain)
+        .registrable
+        .is_some_and(|base| Some(base) == split_host(&website).registrable)
+    {
+        (
+            true,
+            DomainMatchType::BaseDomain,
+            "Same base domain".to_string(),
+        )
+    } else if is_disposable_domain(&email_domain) {
+        // Throwaway inboxes are never a real contact
+        (
+            false,
+            DomainMatchType::Disposable,
+            "Disposable provider".to_string(),
+        )
+    } else if is_generic_provider(&email_domain) {
+        (
+            true,
+            DomainMatchType::GenericProvider,
+            "Generic provider".to_string(),
+        )
+    } else {
+        (
+            false,
+            DomainMatchType::Mismatch,
+            format!("Domain mismatch: {} vs {}", email_domain, website),
+        )
+    };
+
+    DomainValidation {
+        valid,
+        match_type,
+        email_domain,
+        website_domain: website,
+        message,
+    }
+}
+
+/// Check whether an email's domain matches or is related to the website domain.
+///
+/// Returns a dict with keys:
+///   - "valid": bool
+///   - "match_type": "exact" | "subdomain" | "parent" | "base_domain" | "generic_provider"
+///     | "disposable" | "mismatch" | "no_data" | "invalid_format"
+///   - "provider": "consumer" (webmail / ISP inbox) | "disposable" | "business"
+///     | None, so a generic inbox can be told apart from another business's
+///     domain
+///   - "email_domain": lowercased domain of the email ("" if none)
+///   - "website_domain": lowercased website domain without "www."
+///   - "message": human-readable reason
+#[pyfunction]
+pub fn validate_email_domain(
+    py: Python<'_>,
+    email: &str,
+    website_domain: &str,
+) -> PyResult<PyObject> {
+    let result = check_email_domain(email, website_domain);
+    let dict = PyDict::new(py);
+    dict.set_item("valid", result.valid)?;
+    dict.set_item("match_type", result.match_type.as_str())?;
+    dict.set_item("provider", result.match_type.provider())?;
+    dict.set_item("email_domain", result.email_domain)?;
+    dict.set_item("website_domain", result.website_domain)?;
+    dict.set_item("message", result.message)?;
+    Ok(dict.into())
+}
+
+/// Deprecated: tuple-returning form of validate_email_domain, kept for one
+/// release. Returns (valid, message).
+#[pyfunction]
+pub fn validate_email_domain_legacy(
+    py: Python<'_>,
+    email: &str,
+    website_domain: &str,
+) -> PyResult<(bool, String)> {
+    PyErr::warn(
+        py,
+        &py.get_type::<PyDeprecationWarning>(),
+        c"validate_email_domain_legacy is deprecated; use validate_email_domain",
+        1,
+    )?;
+    let result = check_email_domain(email, website_domain);
+    Ok((result.valid, result.message))
+}
+
+#[pyfunction]
+#[pyo3(signature = (emails, website_domain, sort_roles=false, strict=false, exclude_generic=false))]
+pub fn filter_emails_for_domain(
+    emails: Vec<String>,
+    website_domain: &str,
+    sort_roles: bool,
+    strict: bool,
+    exclude_generic: bool,
+) -> Vec<String> {
+    if emails.is_empty() {
+        return Vec::new();
+    }
+    let mut kept: Vec<String> = emails
+        .into_iter()
+        .filter(|email| !strict || check_email_syntax(email).is_ok())
+        .filter(|email| {
+            let result = check_email_domain(email, website_domain);
+            result.valid
+                && !(exclude_generic && result.match_type == DomainMatchType::GenericProvider)
+        })
+        .collect();
+
+    // Stable, so personal and role addresses each keep their original order
+    if sort_roles {
+        kept.sort_by_key(|email| classify_email_inner(email).category == EmailCategory::Role);
+    }
+    kept
+}
+
+/// Like filter_emails_for_domain, but reports why each email was dropped.
+///
+/// Returns (kept, rejected) where rejected is a list of dicts with keys:
+///   - "email": the input address
+///   - "reason": "spam" | "excluded_pattern" | "disposable" | "domain_mismatch"
+///   - "match_type": match_type from validate_email_domain
+#[pyfunction]
+pub fn filter_emails_for_domain_detailed(
+    py: Python<'_>,
+    emails: Vec<String>,
+    website_domain: &str,
+) -> PyResult<(Vec<String>, Vec<PyObject>)> {
+    let (kept, rejected) = filter_emails_with_reasons(emails, website_domain);
+    let rejected = rejected
+        .into_iter()
+        .map(|r| {
+            let dict = PyDict::new(py);
+            dict.set_item("email", r.email)?;
+            dict.set_item("reason", r.reason)?;
+            dict.set_item("match_type", r.match_type.as_str())?;
+            Ok(dict.into())
+        })
+        .collect::<PyResult<_>>()?;
+    Ok((kept, rejected))
+}
+
+struct RejectedEmail {
+    email: String,
+    reason: &'static str,
+    match_type: DomainMatchType,
+}
+
+/// Kept emails, and each rejected email with why it was dropped.
+fn filter_emails_with_reasons(
+    emails: Vec<String>,
+    website_domain: &str,
+) -> (Vec<String>, Vec<RejectedEmail>) {
+    let mut kept = Vec::new();
+    let mut rejected = Vec::new();
+
+    for email in emails {
+        let email_lower = email.trim().to_lowercase();
+        let result = check_email_domain(&email, website_domain);
+
+        let reason = if is_spam_email(&email_lower) {
+            Some("spam")
+        } else if matches_exclude_pattern(&email_lower) {
+            Some("excluded_pattern")
+        } else if result.match_type == DomainMatchType::Disposable {
+            Some("disposable")
+        } else if !result.valid {
+            Some("domain_mismatch")
+        } else {
+            None
+        };
+
+        match reason {
+            Some(reason) => rejected.push(RejectedEmail {
+                email,
+                reason,
+                match_type: result.match_type,
+            }),
+            None => kept.push(email),
+        }
+    }
+
+    (kept, rejected)
+}
+
+/// Lowercase, dedupe and rank a prospect's emails against its website domain.
+///
+/// Spam, disposable and mismatched addresses are dropped. The rest are
+/// ordered: exact-domain personal, exact-domain role, related domains
+/// (subdomain / parent / same base), then generic providers. Order within a
+/// tier follows first appearance.
+#[pyfunction]
+pub fn dedupe_and_rank_emails(emails: Vec<String>, website_domain: &str) -> Vec<String> {
+    let mut seen: HashSet<String> = HashSet::with_capacity(emails.len());
+    let mut ranked: Vec<(u8, String)> = Vec::with_capacity(emails.len());
+
+    for email in emails {
+        let email = email.trim().to_lowercase();
+        if email.is_empty() || !seen.insert(email.clone()) {
+            continue;
+        }
+        if is_spam_email(&email) || is_disposable_email(&email) {
+            continue;
+        }
+
+        let result = check_email_domain(&email, website_domain);
+        if !result.valid {
+            continue;
+        }
+        let tier = match result.match_type {
+            DomainMatchType::Exact
+                if classify_email_inner(&email).category == EmailCategory::Role =>
+            {
+                1
+            }
+            DomainMatchType::Exact => 0,
+            DomainMatchType::GenericProvider => 3,
+            DomainMatchType::InvalidFormat => continue,
+            _ => 2,
+        };
+        ranked.push((tier, email));
+    }
+
+    ranked.sort_by_key(|(tier, _)| *tier);
+    ranked.into_iter().map(|(_, email)| email).collect()
+}
+
+// ---------------------------------------------------------------------------
+// Strict email syntax validation
+// ---------------------------------------------------------------------------
+
+const LOCAL_PART_SPECIALS: &str = "!#$%&'*+/=?^_`{|}~.-";
+
+/// Purely syntactic RFC-ish validation (no DNS). Returns the canonical form
+/// with a lowercased domain, or the reason the address was rejected.
+/// Quoted local parts are always rejected.
+fn check_email_syntax(email: &str) -> Result<String, &'static str> {
+    let email = email.trim();
+    if email.is_empty() {
+        return Err("Empty email");
+    }
+    if email.len() > 254 {
+        return Err("Email longer than 254 characters");
+    }
+
+    let mut parts = email.split('@');
+    let (Some(local), Some(domain), None) = (parts.next(), parts.next(), parts.next()) else {
+        return Err("Must contain exactly one @");
+    };
+
+    // Local part
+    if local.is_empty() {
+        return Err("Empty local part");
+    }
+    if local.len() > 64 {
+        return Err("Local part longer than 64 characters");
+    }
+    if local.starts_with('"') {
+        return Err("Quoted local parts are not supported");
+    }
+    if !local
+        .chars()
+        .all(|c| c.is_ascii_alphanumeric() || LOCAL_PART_SPECIALS.contains(c))
+    {
+        return Err("Invalid character in local part");
+    }
+    if local.starts_with('.') || local.ends_with('.') || local.contains("..") {
+        return Err("Misplaced dot in local part");
+    }
+
+    // Domain
+    if domain.is_empty() {
+        return Err("Empty domain");
+    }
+    if domain.starts_with('[') {
+        return Err("IP-literal domains are not allowed");
+    }
+    if domain.starts_with('.') || domain.ends_with('.') || domain.contains("..") {
+        return Err("Misplaced dot in domain");
+    }
+
+    let labels: Vec<&str> = domain.split('.').collect();
+    if labels.len() < 2 {
+        return Err("Domain must contain a dot");
+    }
+    for label in &labels {
+        if label.len() > 63 {
+            return Err("Domain label longer than 63 characters");
+        }
+        if !label.chars().all(|c| c.is_ascii_alphanumeric() || c == '-') {
+            return Err("Invalid character in domain");
+        }
+        if label.starts_with('-') || label.ends_with('-') {
+            return Err("Domain label starts or ends with a hyphen");
+        }
+    }
+
+    let tld = labels[labels.len() - 1];
+    if tld.len() < 2 || !tld.chars().all(|c| c.is_ascii_alphabetic()) {
+        return Err("Invalid TLD");
+    }
+
+    Ok(format!("{}@{}", local, domain.to_lowercase()))
+}
+
+/// Strict syntactic validation for user-supplied / imported emails.
+///
+/// Returns a dict with keys:
+///   - "valid": bool
+///   - "reason": "Valid" or why the address was rejected
+///   - "email": canonical form (domain lowercased) or None when invalid
+#[pyfunction]
+pub fn validate_email_syntax(py: Python<'_>, email: &str) -> PyResult<PyObject> {
+    let dict = PyDict::new(py);
+
+    match check_email_syntax(email) {
+        Ok(canonical) => {
+            dict.set_item("valid", true)?;
+            dict.set_item("reason", "Valid")?;
+            dict.set_item("email", canonical)?;
+        }
+        Err(reason) => {
+            dict.set_item("valid", false)?;
+            dict.set_item("reason", reason)?;
+            dict.set_item("email", py.None())?;
+        }
+    }
+
+    Ok(dict.into())
+}
+
+// ---------------------------------------------------------------------------
+// Disposable email providers
+// ---------------------------------------------------------------------------
+
+/// Lowercase a user-supplied domain entry and strip leading "@" / ".";
+/// rejects anything that can't be a hostname.
+fn normalize_domain_entry(entry: &str) -> PyResult<String> {
+    domain_entry(entry)
+        .ok_or_else(|| PyValueError::new_err(format!("Invalid domain entry: {:?}", entry)))
+}
+
+fn domain_entry(entry: &str) -> Option<String> {
+    let domain = entry.trim().trim_start_matches(['@', '.']).to_lowercase();
+    let valid = domain.contains('.')
+        && !domain.ends_with('.')
+        && domain
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '.' | '-'));
+    valid.then_some(domain)
+}
+
+/// True if the domain or any parent domain is a known disposable provider.
+fn is_disposable_domain(domain: &str) -> bool {
+    let domain = domain.to_lowercase();
+    let extra = EXTRA_DISPOSABLE_DOMAINS.read().unwrap();
+    let mut candidate = domain.as_str();
+    loop {
+        if DISPOSABLE_DOMAINS.contains(candidate) || extra.contains(candidate) {
+            return true;
+        }
+        match candidate.split_once('.') {
+            Some((_, parent)) if parent.contains('.') => candidate = parent,
+            _ => return false,
+        }
+    }
+}
+
+/// True if the email's domain (or a parent of it) is a disposable provider.
+/// A bare domain is accepted as well.
+#[pyfunction]
+pub fn is_disposable_email(email: &str) -> bool {
+    let domain = email.rsplit_once('@').map_or(email, |(_, d)| d).trim();
+    !domain.is_empty() && is_disposable_domain(domain)
+}
+
+/// Add domains to the disposable provider set at runtime.
+/// Returns the number of newly added domains.
+#[pyfunction]
+pub fn register_disposable_domains(domains: Vec<String>) -> PyResult<usize> {
+    let normalized = domains
+        .iter()
+        .map(|d| normalize_domain_entry(d))
+        .collect::<PyResult<Vec<_>>>()?;
+
+    let mut extra = EXTRA_DISPOSABLE_DOMAINS.write().unwrap();
+    Ok(normalized
+        .into_iter()
+        .filter(|d| extra.insert(d.clone()))
+        .count())
+}
+
+/// True if the domain or any parent domain is a consumer webmail / ISP
+/// provider, so "members.optusnet.com.au" counts as optusnet.com.au.
+fn is_generic_provider(domain: &str) -> bool {
+    let domain = domain.to_lowercase();
+    let extra = EXTRA_GENERIC_PROVIDERS.read().unwrap();
+    let mut candidate = domain.as_str();
+    loop {
+        if GENERIC_EMAIL_PROVIDERS.contains(candidate) || extra.contains(candidate) {
+            return true;
+        }
+        match candidate.split_once('.') {
+            Some((_, parent)) if parent.contains('.') => candidate = parent,
+            _ => return false,
+        }
+    }
+}
+
+/// Add domains to the generic (consumer webmail / ISP) provider set at
+/// runtime. Returns the number of newly added domains.
+#[pyfunction]
+pub fn register_generic_providers(domains: Vec<String>) -> PyResult<usize> {
+    let normalized = domains
+        .iter()
+        .map(|d| normalize_domain_entry(d))
+        .collect::<PyResult<Vec<_>>>()?;
+    Ok(add_generic_providers(normalized))
+}
+
+/// Merge already-normalized domains into the runtime generic provider set.
+fn add_generic_providers(domains: Vec<String>) -> usize {
+    let mut extra = EXTRA_GENERIC_PROVIDERS.write().unwrap();
+    domains
         .into_iter()
-        .filter(|email| validate_email_domain(email, website_domain).0)
+        .filter(|d| !GENERIC_EMAIL_PROVIDERS.contains(d.as_str()) && extra.insert(d.clone()))
+        .count()
+}
+
+/// Current generic provider domains (built-in and registered), sorted.
+#[pyfunction]
+pub fn list_generic_providers() -> Vec<String> {
+    let mut domains: Vec<String> = GENERIC_EMAIL_PROVIDERS
+        .iter()
+        .map(|d| d.to_string())
+        .chain(EXTRA_GENERIC_PROVIDERS.read().unwrap().iter().cloned())
+        .collect();
+    domains.sort_unstable();
+    domains.dedup();
+    domains
+}
+
+// ---------------------------------------------------------------------------
+// Email classification (role inbox vs person)
+// ---------------------------------------------------------------------------
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum EmailCategory {
+    Role,
+    Personal,
+    Unknown,
+}
+
+impl EmailCategory {
+    fn as_str(self) -> &'static str {
+        match self {
+            EmailCategory::Role => "role",
+            EmailCategory::Personal => "personal",
+            EmailCategory::Unknown => "unknown",
+        }
+    }
+}
+
+struct EmailClassification {
+    category: EmailCategory,
+    role: Option<String>,
+    first_name: Option<String>,
+    last_name: Option<String>,
+}
+
+impl EmailClassification {
+    fn unknown() -> Self {
+        EmailClassification {
+            category: EmailCategory::Unknown,
+            role: None,
+            first_name: None,
+            last_name: None,
+        }
+    }
+
+    fn person(first_name: Option<&str>, last_name: Option<&str>) -> Self {
+        EmailClassification {
+            category: EmailCategory::Personal,
+            role: None,
+            first_name: first_name.map(capitalize_name),
+            last_name: last_name.map(capitalize_name),
+        }
+    }
+}
+
+/// "smith-jones" → "Smith-Jones"
+fn capitalize_name(name: &str) -> String {
+    name.split('-')
+        .map(|part| {
+            let mut chars = part.chars();
+            match chars.next() {
+                Some(c) => c.to_uppercase().chain(chars).collect::<String>(),
+                None => String::new(),
+            }
+        })
+        .collect::<Vec<_>>()
+        .join("-")
+}
+
+fn is_name_token(token: &str) -> bool {
+    token.len() >= 2
+        && token.chars().all(|c| c.is_ascii_alphabetic() || c == '-')
+        && token.starts_with(|c: char| c.is_ascii_alphabetic())
+        && token.ends_with(|c: char| c.is_ascii_alphabetic())
+}
+
+/// The business name itself used as the mailbox ("acmeplumbing@acmeplumbing.com.au").
+fn is_business_local_part(local: &str, domain: &str) -> bool {
+    let squashed: String = local
+        .chars()
+        .filter(|c| c.is_ascii_alphanumeric())
+        .collect();
+    let domain_label = domain.split('.').next().unwrap_or_default();
+    squashed.len() >= 4
+        && (domain_label.contains(squashed.as_str())
+            || (domain_label.len() >= 4 && squashed.contains(domain_label)))
+}
+
+fn classify_email_inner(email: &str) -> EmailClassification {
+    let email = email.trim().to_lowercase();
+    let Some((local, domain)) = email.rsplit_once('@') else {
+        return EmailClassification::unknown();
+    };
+    if local.is_empty() {
+        return EmailClassification::unknown();
+    }
+
+    // Role inboxes: "info", "info2", "sales.brisbane", "bookings-team"
+    let bare = local.trim_end_matches(|c: char| c.is_ascii_digit());
+    let first_token = bare.split(['.', '_', '-', '+']).next().unwrap_or_default();
+    for candidate in [bare, first_token] {
+        if ROLE_LOCAL_PARTS.contains(candidate) {
+            return EmailClassification {
+                category: EmailCategory::Role,
+                role: Some(candidate.to_string()),
+                first_name: None,
+                last_name: None,
+            };
+        }
+    }
+
+    if is_business_local_part(bare, domain) {
+        return EmailClassification::unknown();
+    }
+
+    let tokens: Vec<&str> = bare.split(['.', '_']).filter(|t| !t.is_empty()).collect();
+    match tokens.as_slice() {
+        // first.last / first_last (last may be hyphenated)
+        [first, last] if is_name_token(first) && is_name_token(last) => {
+            if COMMON_FIRST_NAMES.contains(first) {
+                EmailClassification::person(Some(first), Some(last))
+            } else {
+                EmailClassification::unknown()
+            }
+        }
+        [single] if is_name_token(single) => {
+            if COMMON_FIRST_NAMES.contains(single) {
+                return EmailClassification::person(Some(single), None);
+            }
+            // flast: "jsmith"
+            if let Some(surname) = single.get(1..) {
+                if COMMON_SURNAMES.contains(surname) {
+                    return EmailClassification::person(None, Some(surname));
+                }
+            }
+            // firstlast: "janesmith"
+            for split in 2..single.len().saturating_sub(1) {
+                let (first, last) = single.split_at(split);
+                if COMMON_FIRST_NAMES.contains(first) && COMMON_SURNAMES.contains(last) {
+                    return EmailClassification::person(Some(first), Some(last));
+                }
+            }
+            EmailClassification::unknown()
+        }
+        _ => EmailClassification::unknown(),
+    }
+}
+
+fn email_classification_dict(py: Python<'_>, email: &str) -> PyResult<PyObject> {
+    let c = classify_email_inner(email);
+    let dict = PyDict::new(py);
+    dict.set_item("category", c.category.as_str())?;
+    dict.set_item("role", c.role)?;
+    dict.set_item("first_name", c.first_name)?;
+    dict.set_item("last_name", c.last_name)?;
+    Ok(dict.into())
+}
+
+/// Classify an email as a shared role inbox or a person.
+///
+/// Returns a dict with keys:
+///   - "category": "role" | "personal" | "unknown"
+///   - "role": matched role local part (e.g. "info") or None
+///   - "first_name" / "last_name": capitalised name parts or None
+#[pyfunction]
+pub fn classify_email(py: Python<'_>, email: &str) -> PyResult<PyObject> {
+    email_classification_dict(py, email)
+}
+
+/// Batch variant of classify_email, one dict per input email.
+#[pyfunction]
+pub fn classify_emails_batch(py: Python<'_>, emails: Vec<String>) -> PyResult<Vec<PyObject>> {
+    emails
+        .iter()
+        .map(|email| email_classification_dict(py, email))
         .collect()
 }
+
+// ---------------------------------------------------------------------------
+// Name inference from email local parts
+// ---------------------------------------------------------------------------
+
+// Confidence by how unambiguous the local-part pattern is
+const NAME_CONFIDENCE_HIGH: f64 = 0.9;
+const NAME_CONFIDENCE_MEDIUM: f64 = 0.6;
+const NAME_CONFIDENCE_LOW: f64 = 0.3;
+
+struct InferredName {
+    first: Option<String>,
+    last: Option<String>,
+    confidence: f64,
+}
+
+impl InferredName {
+    fn new(first: Option<&str>, last: Option<&str>, confidence: f64) -> Option<Self> {
+        Some(InferredName {
+            first: first.map(capitalize_name),
+            last: last.map(capitalize_name),
+            confidence,
+        })
+    }
+}
+
+fn is_initial(token: &str) -> bool {
+    token.len() == 1 && token.chars().all(|c| c.is_ascii_alphabetic())
+}
+
+/// Split "janesmith" at the longest common first name that leaves a
+/// plausible surname behind. Known surnames win over longer first names.
+fn split_first_last(single: &str) -> Option<(&str, &str, bool)> {
+    let mut best: Option<(&str, &str, bool)> = None;
+    for split in 2..single.len().saturating_sub(1) {
+        let (first, last) = single.split_at(split);
+        if !COMMON_FIRST_NAMES.contains(first) || !is_name_token(last) {
+            continue;
+        }
+        let known_surname = COMMON_SURNAMES.contains(last);
+        if best.is_none_or(|(_, _, known)| known_surname || !known) {
+            best = Some((first, last, known_surname));
+        }
+    }
+    best
+}
+
+fn infer_name_inner(email: &str) -> Option<InferredName> {
+    let email = email.trim().to_lowercase();
+    let (local, domain) = email.rsplit_once('@')?;
+    if local.is_empty() || is_hash_like_local_part(local) {
+        return None;
+    }
+    if classify_email_inner(&email).category == EmailCategory::Role {
+        return None;
+    }
+
+    // "jane.smith+quotes" / "jsmith82"
+    let local = local.split_once('+').map_or(local, |(base, _)| base);
+    let bare = local.trim_end_matches(|c: char| c.is_ascii_digit());
+    if is_business_local_part(bare, domain) {
+        return None;
+    }
+
+    let tokens: Vec<&str> = bare.split(['.', '_']).filter(|t| !t.is_empty()).collect();
+    match tokens.as_slice() {
+        // first.last, first_last, first.m.last
+        [first, last] | [first, _, last]
+            if is_name_token(first)
+                && is_name_token(last)
+                && (tokens.len() == 2 || is_initial(tokens[1])) =>
+        {
+            InferredName::new(Some(first), Some(last), NAME_CONFIDENCE_HIGH)
+        }
+        // f.last
+        [initial, last] if is_initial(initial) && is_name_token(last) => {
+            InferredName::new(None, Some(last), NAME_CONFIDENCE_MEDIUM)
+        }
+        [single] if is_name_token(single) => {
+            if COMMON_FIRST_NAMES.contains(single) {
+                return InferredName::new(Some(single), None, NAME_CONFIDENCE_LOW);
+            }
+            if let Some((first, last, _)) = split_first_last(single) {
+                return InferredName::new(Some(first), Some(last), NAME_CONFIDENCE_MEDIUM);
+            }
+            // flast: "jsmith"
+            let surname = single.get(1..).filter(|s| COMMON_SURNAMES.contains(s))?;
+            InferredName::new(None, Some(surname), NAME_CONFIDENCE_LOW)
+        }
+        _ => None,
+    }
+}
+
+fn inferred_name_dict(py: Python<'_>, name: Option<InferredName>) -> PyResult<Option<PyObject>> {
+    let Some(name) = name else {
+        return Ok(None);
+    };
+    let dict = PyDict::new(py);
+    dict.set_item("first", name.first)?;
+    dict.set_item("last", name.last)?;
+    dict.set_item("confidence", name.confidence)?;
+    Ok(Some(dict.into()))
+}
+
+/// Guess a person's name from an email local part for mail-merge fields.
+///
+/// Handles first.last / first_last (high confidence), f.last and firstlast
+/// split on a common first name (medium), and a lone first name or flast
+/// (low). Role inboxes, the business name and hash-like local parts return
+/// None. Returns a dict with keys "first", "last" (title-cased or None) and
+/// "confidence".
+#[pyfunction]
+pub fn infer_name_from_email(py: Python<'_>, email: &str) -> PyResult<Option<PyObject>> {
+    inferred_name_dict(py, infer_name_inner(email))
+}
+
+/// infer_name_from_email over many emails with the GIL released.
+#[pyfunction]
+pub fn infer_name_from_email_batch(
+    py: Python<'_>,
+    emails: Vec<String>,
+) -> PyResult<Vec<Option<PyObject>>> {
+    let names: Vec<Option<InferredName>> = py.allow_threads(|| {
+        let infer = |email: &String| infer_name_inner(email);
+        if emails.len() < BATCH_PARALLEL_THRESHOLD {
+            emails.iter().map(infer).collect()
+        } else {
+            emails.par_iter().map(infer).collect()
+        }
+    });
+    names
+        .into_iter()
+        .map(|name| inferred_name_dict(py, name))
+        .collect()
+}
+
+// ---------------------------------------------------------------------------
+// Business name similarity
+// ---------------------------------------------------------------------------
+
+// Tokens this close (Jaro-Winkler) count as the same word ("plumber"/"plumbers")
+const FUZZY_TOKEN_THRESHOLD: f64 = 0.92;
+
+// Filler words that dominate token comparisons between AU business names
+static DEFAULT_NAME_STOPWORDS: &[&str] = &[
+    "the",
+    "and",
+    "of",
+    "a",
+    "an",
+    "at",
+    "by",
+    "for",
+    "on",
+    "services",
+    "service",
+    "solutions",
+    "group",
+    "australia",
+    "australian",
+    "aust",
+    "aus",
+    "enterprises",
+    "industries",
+    "co",
+    "company",
+    "specialists",
+    "experts",
+    "professionals",
+];
+
+fn default_name_stopwords() -> HashSet<String> {
+    DEFAULT_NAME_STOPWORDS
+        .iter()
+        .map(|w| w.to_string())
+        .collect()
+}
+
+static NAME_STOPWORDS: LazyLock<RwLock<HashSet<String>>> =
+    LazyLock::new(|| RwLock::new(default_name_stopwords()));
+
+fn jaro(a: &[char], b: &[char]) -> f64 {
+    if a.is_empty() && b.is_empty() {
+        return 1.0;
+    }
+    if a.is_empty() || b.is_empty() {
+        return 0.0;
+    }
+
+    let window = (a.len().max(b.len()) / 2).saturating_sub(1);
+    let mut a_matched = vec![false; a.len()];
+    let mut b_matched = vec![false; b.len()];
+    let mut matches = 0usize;
+
+    for (i, ca) in a.iter().enumerate() {
+        let lo = i.saturating_sub(window);
+        let hi = (i + window + 1).min(b.len());
+        for j in lo..hi {
+            if !b_matched[j] && b[j] == *ca {
+                a_matched[i] = true;
+                b_matched[j] = true;
+                matches += 1;
+                break;
+            }
+        }
+    }
+    if matches == 0 {
+        return 0.0;
+    }
+
+    let a_seq = a
+        .iter()
+        .zip(&a_matched)
+        .filter(|(_, m)| **m)
+        .map(|(c, _)| c);
+    let b_seq = b
+        .iter()
+        .zip(&b_matched)
+        .filter(|(_, m)| **m)
+        .map(|(c, _)| c);
+    let transpositions = a_seq.zip(b_seq).filter(|(x, y)| x != y).count() / 2;
+
+    let m = matches as f64;
+    (m / a.len() as f64 + m / b.len() as f64 + (m - transpositions as f64) / m) / 3.0
+}
+
+fn jaro_winkler(a: &str, b: &str) -> f64 {
+    let a: Vec<char> = a.chars().collect();
+    let b: Vec<char> = b.chars().collect();
+    let j = jaro(&a, &b);
+    let prefix = a.iter().zip(&b).take(4).take_while(|(x, y)| x == y).count();
+    j + prefix as f64 * 0.1 * (1.0 - j)
+}
+
+/// Light plural stemmer: "plumbers" → "plumber", "batteries" → "battery",
+/// "glasses" → "glass". Leaves "-ss", "-us" and "-is" endings alone.
+fn stem_token(token: &str) -> String {
+    if token.len() > 4 {
+        if let Some(base) = token.strip_suffix("ies") {
+            return format!("{}y", base);
+        }
+        if let Some(base) = token.strip_suffix("sses") {
+            return format!("{}ss", base);
+        }
+    }
+    if token.len() > 3
+        && token.ends_with('s')
+        && !token.ends_with("ss")
+        && !token.ends_with("us")
+        && !token.ends_with("is")
+    {
+        return token[..token.len() - 1].to_string();
+    }
+    token.to_string()
+}
+
+/// Tokens of an already-normalized name with stopwords removed, sorted and
+/// deduplicated. A name made only of stopwords keeps all its tokens.
+pub(crate) fn tokens_of_normalized(normalized: &str, stem: bool) -> Vec<String> {
+    let stopwords = NAME_STOPWORDS.read().unwrap();
+    let all: Vec<&str> = normalized.split_whitespace().collect();
+    let kept: Vec<&str> = all
+        .iter()
+        .copied()
+        .filter(|t| !stopwords.contains(*t))
+        .collect();
+    let source = if kept.is_empty() { all } else { kept };
+
+    let mut tokens: Vec<String> = source
+        .into_iter()
+        .map(|t| if stem { stem_token(t) } else { t.to_string() })
+        .collect();
+    tokens.sort_unstable();
+    tokens.dedup();
+    tokens
+}
+
+/// Token-set overlap, letting near-identical tokens match.
+fn token_set_score(a: &[String], b: &[String]) -> f64 {
+    let (small, large) = if a.len() <= b.len() { (a, b) } else { (b, a) };
+    let shared = small
+        .iter()
+        .filter(|t| {
+            large
+                .iter()
+                .any(|u| t == &u || jaro_winkler(t, u) >= FUZZY_TOKEN_THRESHOLD)
+        })
+        .count() as f64;
+    let union = (a.len() + b.len()) as f64 - shared;
+    0.5 * (shared / small.len() as f64) + 0.5 * (shared / union)
+}
+
+pub(crate) fn tokens_similarity(a: &[String], b: &[String]) -> f64 {
+    if a.is_empty() || b.is_empty() {
+        return 0.0;
+    }
+    if a == b {
+        return 1.0;
+    }
+
+    // Sorted tokens make word-order swaps irrelevant to Jaro-Winkler
+    let jw = jaro_winkler(&a.join(" "), &b.join(" "));
+    let score = 0.6 * token_set_score(a, b) + 0.4 * jw;
+    (score * 1000.0).round() / 1000.0
+}
+
+/// Matching tokens for a business name: normalize_name, split, drop
+/// stopwords ("the", "services", "group", ...) and, with `stem`, reduce
+/// plurals. Sorted and deduplicated.
+#[pyfunction]
+#[pyo3(signature = (name, stem=true))]
+pub fn name_tokens(name: &str, stem: bool) -> Vec<String> {
+    tokens_of_normalized(&normalize_name(name), stem)
+}
+
+/// Replace the stopwords used by name_tokens and name_similarity, e.g. to
+/// keep "dental" when prospecting dentists. None restores the defaults.
+#[pyfunction]
+#[pyo3(signature = (words=None))]
+pub fn set_name_stopwords(words: Option<Vec<String>>) {
+    let words = match words {
+        Some(words) => words
+            .iter()
+            .map(|w| w.trim().to_lowercase())
+            .filter(|w| !w.is_empty())
+            .collect(),
+        None => default_name_stopwords(),
+    };
+    *NAME_STOPWORDS.write().unwrap() = words;
+}
+
+/// Current name stopwords, sorted.
+#[pyfunction]
+pub fn list_name_stopwords() -> Vec<String> {
+    let mut words: Vec<String> = NAME_STOPWORDS.read().unwrap().iter().cloned().collect();
+    words.sort_unstable();
+    words
+}
+
+/// Similarity of two business names, 0.0-1.0.
+///
+/// Both names go through name_tokens (so legal suffixes, punctuation,
+/// stopwords and plurals are ignored), then a token-set overlap is blended
+/// with Jaro-Winkler on the sorted tokens. Identical normalized names
+/// always score 1.0.
+#[pyfunction]
+pub fn name_similarity(a: &str, b: &str) -> f64 {
+    let (a, b) = (normalize_name(a), normalize_name(b));
+    if a.is_empty() || b.is_empty() {
+        return 0.0;
+    }
+    if a == b {
+        return 1.0;
+    }
+    tokens_similarity(
+        &tokens_of_normalized(&a, true),
+        &tokens_of_normalized(&b, true),
+    )
+}
+
+/// True if two business names are likely the same business.
+#[pyfunction]
+#[pyo3(signature = (a, b, threshold=0.85))]
+pub fn names_match(a: &str, b: &str, threshold: f64) -> bool {
+    name_similarity(a, b) >= threshold
+}
+
+/// Indices of candidates whose name matches the target.
+#[pyfunction]
+#[pyo3(signature = (candidates, target, threshold=0.85))]
+pub fn match_names(candidates: Vec<String>, target: &str, threshold: f64) -> Vec<usize> {
+    candidates
+        .iter()
+        .enumerate()
+        .filter(|(_, c)| name_similarity(c, target) >= threshold)
+        .map(|(i, _)| i)
+        .collect()
+}
+
+// ---------------------------------------------------------------------------
+// Location qualifiers in business names
+// ---------------------------------------------------------------------------
+
+// Longest multi-word locality we look for ("australian capital territory")
+const MAX_LOCALITY_WORDS: usize = 3;
+
+fn is_locality(words: &[&str], extra_call: &HashSet<String>, extra: &HashSet<String>) -> bool {
+    let candidate = words
+        .iter()
+        .map(|w| {
+            w.trim_matches(|c: char| c == ',' || c == '.')
+                .to_lowercase()
+        })
+        .collect::<Vec<_>>()
+        .join(" ");
+    AU_LOCALITIES.contains(candidate.as_str())
+        || extra.contains(&candidate)
+        || extra_call.contains(&candidate)
+}
+
+/// Strip "<City>'s" and trailing place names, returning the remaining words,
+/// the removed location (original casing) and whether it was a leading
+/// possessive.
+fn strip_location_words<'a>(
+    words: &[&'a str],
+    extra_call: &HashSet<String>,
+) -> (Vec<&'a str>, Vec<&'a str>, bool) {
+    let extra = EXTRA_LOCALITIES.read().unwrap();
+    let mut start = 0;
+    let mut end = words.len();
+    let mut leading: Vec<&str> = Vec::new();
+    let mut trailing: Vec<&str> = Vec::new();
+
+    // Leading possessive: "Brisbane's Best Plumbing", "Gold Coast’s ..."
+    for n in (1..=MAX_LOCALITY_WORDS.min(words.len())).rev() {
+        let last = words[n - 1];
+        let Some(base) = last
+            .strip_suffix("'s")
+            .or_else(|| last.strip_suffix("\u{2019}s"))
+        else {
+            continue;
+        };
+        let mut candidate: Vec<&str> = words[..n - 1].to_vec();
+        candidate.push(base);
+        if is_locality(&candidate, extra_call, &extra) {
+            leading = candidate;
+            start = n;
+            break;
+        }
+    }
+
+    // Trailing place names, repeatedly: "Acme Plumbing Brisbane QLD"
+    'outer: while end > start {
+        for n in (1..=MAX_LOCALITY_WORDS.min(end - start)).rev() {
+            if is_locality(&words[end - n..end], extra_call, &extra) {
+                trailing.splice(0..0, words[end - n..end].iter().copied());
+                end -= n;
+                continue 'outer;
+            }
+        }
+        break;
+    }
+
+    let possessive = !leading.is_empty();
+    leading.extend(trailing);
+    (words[start..end].to_vec(), leading, possessive)
+}
+
+/// Remove location qualifiers from a business name.
+///
+/// Strips a leading "<City>'s" possessive and trailing capital cities,
+/// regional centres and state names/abbreviations, using the built-in AU
+/// locality list plus registered and per-call `locations`. The location is
+/// only removed when at least two words remain and, for a trailing place
+/// name, they aren't all generic, so "Sydney Tools" and "Plumbers Gold
+/// Coast" are left alone while "Brisbane's Best Plumbing" becomes "Best
+/// Plumbing". Returns a dict with `name` and `location` (None when nothing
+/// was removed).
+#[pyfunction]
+#[pyo3(signature = (name, locations=None))]
+pub fn strip_location_from_name(
+    py: Python<'_>,
+    name: &str,
+    locations: Option<Vec<String>>,
+) -> PyResult<PyObject> {
+    let extra_call: HashSet<String> = locations
+        .unwrap_or_default()
+        .iter()
+        .map(|l| {
+            l.split_whitespace()
+                .collect::<Vec<_>>()
+                .join(" ")
+                .to_lowercase()
+        })
+        .filter(|l| !l.is_empty())
+        .collect();
+
+    let dict = PyDict::new(py);
+    match name_location_of(name, &extra_call) {
+        Some((cleaned, location)) => {
+            dict.set_item("name", cleaned)?;
+            dict.set_item("location", location)?;
+        }
+        None => {
+            dict.set_item(
+                "name",
+                name.split_whitespace().collect::<Vec<_>>().join(" "),
+            )?;
+            dict.set_item("location", py.None())?;
+        }
+    }
+    Ok(dict.into())
+}
+
+/// (name, location) with the location qualifier removed, or None when the
+/// name should be left whole.
+fn name_location_of(name: &str, extra_call: &HashSet<String>) -> Option<(String, String)> {
+    let words: Vec<&str> = name.split_whitespace().collect();
+    let (remaining, location, possessive) = strip_location_words(&words, extra_call);
+
+    // "Brisbane's" is always a qualifier; a trailing place name may be the
+    // brand itself when only generic words precede it
+    let generic_only = remaining.iter().all(|w| {
+        let w = w
+            .trim_matches(|c: char| !c.is_alphanumeric())
+            .to_lowercase();
+        GENERIC_NAME_WORDS.contains(&w.as_str())
+    });
+    if location.is_empty() || remaining.len() < 2 || (generic_only && !possessive) {
+        return None;
+    }
+
+    let cleaned = remaining
+        .join(" ")
+        .trim_matches(|c: char| c == ',' || c == '-' || c.is_whitespace())
+        .to_string();
+    let location = location
+        .join(" ")
+        .trim_matches(|c: char| c == ',' || c == '.')
+        .to_string();
+    Some((cleaned, location))
+}
+
+/// Add localities used by strip_location_from_name.
+/// Returns the number of newly added names.
+#[pyfunction]
+pub fn register_localities(names: Vec<String>) -> usize {
+    let mut extra = EXTRA_LOCALITIES.write().unwrap();
+    names
+        .iter()
+        .map(|n| {
+            n.split_whitespace()
+                .collect::<Vec<_>>()
+                .join(" ")
+                .to_lowercase()
+        })
+        .filter(|n| !n.is_empty() && !AU_LOCALITIES.contains(n.as_str()))
+        .filter(|n| extra.insert(n.clone()))
+        .count()
+}
+
+// ---------------------------------------------------------------------------
+// Franchise brands
+// ---------------------------------------------------------------------------
+
+// Territory in trailing parentheses: "Jim's Mowing (Paddington)"
+static RE_PAREN_TERRITORY: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"\s*\(([^)]+)\)\s*$").unwrap());
+
+struct FranchiseMatch {
+    brand: String,
+    territory: Option<String>,
+}
+
+fn tidy_territory(s: &str) -> Option<String> {
+    let t = s
+        .trim_matches(|c: char| c.is_whitespace() || c == ',' || c == '-' || c == '(' || c == ')')
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ");
+    (!t.is_empty()).then_some(t)
+}
+
+/// Match one brand entry against the outlet name. `words` are the original
+/// words of the name with any parenthesised/dashed territory removed, and
+/// `flat` their normalized tokens tagged with the word they came from.
+fn match_franchise_brand(
+    entry: &str,
+    words: &[&str],
+    flat: &[(usize, &str)],
+) -> Option<FranchiseMatch> {
+    let (display, wildcard) = match entry.strip_suffix(" *") {
+        Some(prefix) => (prefix, true),
+        None => (entry, false),
+    };
+    let brand_tokens: Vec<String> = normalize_name(display)
+        .split_whitespace()
+        .map(str::to_string)
+        .collect();
+    if brand_tokens.is_empty() {
+        return None;
+    }
+
+    let needed = brand_tokens.len() + usize::from(wildcard);
+    if flat.len() < needed || !brand_tokens.iter().zip(flat).all(|(b, (_, t))| b == t) {
+        return None;
+    }
+
+    let last_brand_word = flat[needed - 1].0;
+    let brand = if wildcard {
+        format!("{} {}", display, words[last_brand_word])
+    } else {
+        display.to_string()
+    };
+    Some(FranchiseMatch {
+        brand,
+        territory: tidy_territory(&words[last_brand_word + 1..].join(" ")),
+    })
+}
+
+fn detect_franchise_inner(name: &str) -> Option<FranchiseMatch> {
+    let mut rest = name.trim().to_string();
+    let mut territory: Option<String> = None;
+
+    if let Some(caps) = RE_PAREN_TERRITORY.captures(&rest) {
+        territory = tidy_territory(&caps[1]);
+        rest.truncate(caps.get(0).unwrap().start());
+    }
+    if let Some(m) = RE_DASH_DELIMITER.find(&rest) {
+        territory = territory.or_else(|| tidy_territory(&rest[m.end()..]));
+        rest.truncate(m.start());
+    }
+    let words: Vec<&str> = rest.split_whitespace().collect();
+
+    // Normalize word by word so matched tokens map back to original words
+    let normalized: Vec<String> = words.iter().map(|w| normalize_name(w)).collect();
+    let mut flat: Vec<(usize, &str)> = Vec::new();
+    for (i, n) in normalized.iter().enumerate() {
+        flat.extend(n.split_whitespace().map(|t| (i, t)));
+    }
+
+    let extra = EXTRA_FRANCHISE_BRANDS.read().unwrap();
+    let mut entries: Vec<&str> = FRANCHISE_BRANDS
+        .iter()
+        .copied()
+        .chain(extra.iter().map(String::as_str))
+        .collect();
+    // Longest brand first so "Fernwood Fitness" wins over "Fernwood"
+    entries.sort_by_key(|e| std::cmp::Reverse(e.len()));
+
+    entries
+        .iter()
+        .find_map(|entry| match_franchise_brand(entry, &words, &flat))
+        .map(|m| FranchiseMatch {
+            brand: m.brand,
+            territory: territory.or(m.territory),
+        })
+}
+
+/// Recognise a franchise outlet name.
+///
+/// Returns {brand, territory} for names like "Jim's Mowing (Paddington)",
+/// "Poolwerx - Cannon Hill" or "Fernwood Fitness Toowong", or None when the
+/// name doesn't start with a known brand. territory is None for bare brands.
+#[pyfunction]
+pub fn detect_franchise(py: Python<'_>, name: &str) -> PyResult<Option<PyObject>> {
+    let Some(m) = detect_franchise_inner(name) else {
+        return Ok(None);
+    };
+    let dict = PyDict::new(py);
+    dict.set_item("brand", m.brand)?;
+    dict.set_item("territory", m.territory)?;
+    Ok(Some(dict.into()))
+}
+
+/// Add franchise brands at runtime. Entries use the data file format
+/// ("Brand", or "Brand *" for a brand followed by a service word).
+/// Returns the number of newly added brands.
+#[pyfunction]
+pub fn register_franchise_brands(brands: Vec<String>) -> usize {
+    let mut extra = EXTRA_FRANCHISE_BRANDS.write().unwrap();
+    let mut added = 0;
+    for brand in brands {
+        let brand = brand.split_whitespace().collect::<Vec<_>>().join(" ");
+        if brand.is_empty() || FRANCHISE_BRANDS.contains(&brand.as_str()) || extra.contains(&brand)
+        {
+            continue;
+        }
+        extra.push(brand);
+        added += 1;
+    }
+    added
+}
+
+// ---------------------------------------------------------------------------
+// Placeholder / junk business names
+// ---------------------------------------------------------------------------
+
+// Normalized names left behind by half-configured listings and test records
+static PLACEHOLDER_NAMES: &[&str] = &[
+    "test",
+    "testing",
+    "test business",
+    "test company",
+    "test listing",
+    "my business",
+    "my company",
+    "your business",
+    "your business name",
+    "your company",
+    "your company name",
+    "business name",
+    "company name",
+    "lorem ipsum",
+    "sample",
+    "sample business",
+    "example",
+    "example business",
+    "placeholder",
+    "na",
+    "n a",
+    "none",
+    "null",
+    "nil",
+    "undefined",
+    "unknown",
+    "untitled",
+    "default",
+    "demo",
+    "foo",
+    "bar",
+    "foobar",
+    "xxx",
+    "tbc",
+    "tba",
+    "new business",
+    "business",
+    "company",
+];
+
+// Keyboard rows, checked forwards and backwards for walks like "asdf"
+static KEYBOARD_ROWS: &[&str] = &["qwertyuiop", "asdfghjkl", "zxcvbnm", "1234567890"];
+
+fn placeholder_reason(name: &str) -> Option<&'static str> {
+    let trimmed = name.trim();
+
+    // Short acronyms ("JB", "KFC") are real brands
+    if (2..=3).contains(&trimmed.len()) && trimmed.chars().all(|c| c.is_ascii_uppercase()) {
+        return None;
+    }
+
+    let normalized = normalize_name(trimmed);
+    let compact: String = normalized.chars().filter(|c| !c.is_whitespace()).collect();
+
+    if compact.chars().count() < 2 {
+        return Some("too_short");
+    }
+    if compact.chars().all(|c| c.is_ascii_digit()) {
+        return Some("numeric");
+    }
+    if PLACEHOLDER_NAMES.contains(&normalized.as_str()) || normalized.starts_with("lorem ipsum") {
+        return Some("placeholder_phrase");
+    }
+
+    let mut chars = compact.chars();
+    let first = chars.next().unwrap_or_default();
+    if chars.all(|c| c == first) {
+        return Some("repeated_character");
+    }
+
+    if compact.len() >= 3
+        && KEYBOARD_ROWS.iter().any(|row| {
+            let reversed: String = row.chars().rev().collect();
+            row.contains(compact.as_str()) || reversed.contains(compact.as_str())
+        })
+    {
+        return Some("keyboard_walk");
+    }
+
+    let stopwords = NAME_STOPWORDS.read().unwrap();
+    if normalized.split_whitespace().all(|t| stopwords.contains(t)) {
+        return Some("stopwords_only");
+    }
+
+    None
+}
+
+/// True if a business name is a placeholder or junk ("Test", "asdf",
+/// "Your Company Name", "12345"). Short all-caps acronyms are allowed.
+#[pyfunction]
+pub fn is_placeholder_name(name: &str) -> bool {
+    placeholder_reason(name).is_some()
+}
+
+/// Why a name counts as a placeholder: "too_short", "numeric",
+/// "placeholder_phrase", "repeated_character", "keyboard_walk" or
+/// "stopwords_only". None for real names.
+#[pyfunction]
+pub fn placeholder_name_reason(name: &str) -> Option<&'static str> {
+    placeholder_reason(name)
+}
+
+/// is_placeholder_name over many names with the GIL released.
+#[pyfunction]
+pub fn is_placeholder_name_batch(py: Python<'_>, names: Vec<String>) -> Vec<bool> {
+    py.allow_threads(|| {
+        let check = |name: &String| placeholder_reason(name).is_some();
+        if names.len() < BATCH_PARALLEL_THRESHOLD {
+            names.iter().map(check).collect()
+        } else {
+            names.par_iter().map(check).collect()
+        }
+    })
+}
+
+// ---------------------------------------------------------------------------
+// Slugs
+// ---------------------------------------------------------------------------
+
+/// URL-safe slug: transliterated, lowercase ASCII letters and digits joined
+/// by single hyphens, "&" read as "and", cut at a word boundary so it fits
+/// in `max_len`. Slugs are persistent identifiers: keep this stable.
+fn slugify_inner(name: &str, max_len: usize) -> String {
+    let cleaned = RE_DECORATIVE_SYMBOLS.replace_all(name, " ");
+    let folded = fold_unicode(&cleaned).to_lowercase().replace('&', " and ");
+
+    let mut slug = String::with_capacity(folded.len());
+    for c in folded.chars() {
+        if c.is_ascii_alphanumeric() {
+            slug.push(c);
+        } else if !slug.is_empty() && !slug.ends_with('-') {
+            slug.push('-');
+        }
+    }
+    let slug = slug.trim_end_matches('-');
+
+    if slug.len() <= max_len {
+        return slug.to_string();
+    }
+    match slug[..=max_len].rfind('-') {
+        Some(cut) if cut > 0 => slug[..cut].to_string(),
+        _ => slug[..max_len].trim_end_matches('-').to_string(),
+    }
+}
+
+/// URL-safe slug for a business name ("Café Élan & Co" → "cafe-elan-and-co").
+#[pyfunction]
+#[pyo3(signature = (name, max_len=60))]
+pub fn slugify(name: &str, max_len: usize) -> String {
+    slugify_inner(name, max_len)
+}
+
+/// Slugs for a batch of names, unique within the batch. Later duplicates get
+/// "-2", "-3", ... in input order; empty names become "untitled".
+#[pyfunction]
+#[pyo3(signature = (names, max_len=60))]
+pub fn slugify_unique(names: Vec<String>, max_len: usize) -> Vec<String> {
+    let mut used: HashSet<String> = HashSet::new();
+    let mut slugs = Vec::with_capacity(names.len());
+
+    for name in &names {
+        let mut base = slugify_inner(name, max_len);
+        if base.is_empty() {
+            base = "untitled".to_string();
+        }
+
+        let mut slug = base.clone();
+        let mut n = 2;
+        while used.contains(&slug) {
+            let suffix = format!("-{}", n);
+            let room = max_len.saturating_sub(suffix.len()).min(base.len());
+            slug = format!("{}{}", base[..room].trim_end_matches('-'), suffix);
+            n += 1;
+        }
+        used.insert(slug.clone());
+        slugs.push(slug);
+    }
+    slugs
+}
+
+// ---------------------------------------------------------------------------
+// Business name from a bare domain
+// ---------------------------------------------------------------------------
+
+/// Cost of one segment: known words are cheap (short ones less so), unknown
+/// runs cost more per character so "acme" stays whole rather than "ac me".
+fn segment_cost(word: &str) -> f64 {
+    if DOMAIN_WORDS.contains(word) {
+        match word.len() {
+            1 => 2.5,
+            2 => 2.0,
+            _ => 1.0,
+        }
+    } else {
+        1.5 + 0.6 * word.len() as f64
+    }
+}
+
+/// Minimum-cost segmentation of a lowercase ASCII label into words. Labels
+/// are at most 63 characters, so trying every split point is cheap.
+fn segment_label(label: &str) -> Vec<&str> {
+    let n = label.len();
+    let mut best: Vec<(f64, usize)> = vec![(f64::INFINITY, 0); n + 1];
+    best[0] = (0.0, 0);
+    for end in 1..=n {
+        for start in 0..end {
+            let cost = best[start].0 + segment_cost(&label[start..end]);
+            if cost < best[end].0 {
+                best[end] = (cost, start);
+            }
+        }
+    }
+
+    let mut words = Vec::new();
+    let mut end = n;
+    while end > 0 {
+        let start = best[end].1;
+        words.push(&label[start..end]);
+        end = start;
+    }
+    words.reverse();
+    words
+}
+
+/// A segmentation is trusted unless it leans on single letters or is mostly
+/// unknown fragments.
+fn segmentation_is_confident(words: &[&str]) -> bool {
+    let single_letters = words.iter().filter(|w| w.len() == 1).count();
+    let unknown: usize = words
+        .iter()
+        .filter(|w| !DOMAIN_WORDS.contains(*w))
+        .map(|w| w.len())
+        .sum();
+    let total: usize = words.iter().map(|w| w.len()).sum();
+    single_letters < 2 && (words.len() == 1 || unknown * 2 <= total)
+}
+
+fn title_case(word: &str) -> String {
+    let mut chars = word.chars();
+    match chars.next() {
+        Some(first) => first.to_uppercase().chain(chars).collect(),
+        None => String::new(),
+    }
+}
+
+/// Guess a display name from a domain when a result has no business name.
+///
+/// "acmeplumbingbrisbane.com.au" → "Acme Plumbing Brisbane",
+/// "best-roofing.com" → "Best Roofing", "plumbing247.com.au" →
+/// "Plumbing 247". The public suffix and subdomains are dropped, the label is
+/// split on hyphens and digits, and letter runs are segmented with an
+/// embedded word list. Low-confidence segmentations keep the run whole.
+#[pyfunction]
+pub fn name_from_domain(domain: &str) -> Option<String> {
+    let host = normalize_domain(domain, false, false, true)?;
+    let registrable = split_host(&host).registrable?;
+    let label = registrable.split('.').next().unwrap_or(registrable);
+    if label.is_empty() || label.starts_with("xn--") {
+        return None;
+    }
+
+    // Runs of letters or digits, split on hyphens and letter/digit changes.
+    // normalize_domain returns punycode, so byte indexes are char boundaries.
+    let mut runs: Vec<&str> = Vec::new();
+    let mut start = 0;
+    let bytes = label.as_bytes();
+    for i in 1..=bytes.len() {
+        let boundary = i == bytes.len()
+            || bytes[i] == b'-'
+            || bytes[i - 1] == b'-'
+            || bytes[i].is_ascii_digit() != bytes[i - 1].is_ascii_digit();
+        if boundary {
+            let run = &label[start..i];
+            if !run.is_empty() && run != "-" {
+                runs.push(run);
+            }
+            start = i;
+        }
+    }
+
+    let mut words: Vec<String> = Vec::new();
+    for run in runs {
+        if run.bytes().all(|b| b.is_ascii_digit()) {
+            words.push(run.to_string());
+            continue;
+        }
+        let segmented = segment_label(run);
+        if segmentation_is_confident(&segmented) {
+            words.extend(segmented.iter().map(|w| title_case(w)));
+        } else {
+            words.push(title_case(run));
+        }
+    }
+
+    (!words.is_empty()).then(|| words.join(" "))
+}
+
+// ---------------------------------------------------------------------------
+// Australian addresses
+// ---------------------------------------------------------------------------
+
+// (state, aliases), aliases lowercase without dots
+static AU_STATES: &[(&str, &[&str])] = &[
+    ("NSW", &["nsw", "new south wales"]),
+    ("VIC", &["vic", "victoria"]),
+    ("QLD", &["qld", "queensland"]),
+    ("SA", &["sa", "south australia"]),
+    ("WA", &["wa", "western australia"]),
+    ("TAS", &["tas", "tasmania"]),
+    ("NT", &["nt", "northern territory"]),
+    ("ACT", &["act", "australian capital territory"]),
+];
+
+// (state, first, last) postcode ranges
+static AU_POSTCODE_RANGES: &[(&str, u32, u32)] = &[
+    ("ACT", 200, 299),
+    ("NT", 800, 999),
+    ("NSW", 1000, 2599),
+    ("ACT", 2600, 2618),
+    ("NSW", 2619, 2899),
+    ("ACT", 2900, 2920),
+    ("NSW", 2921, 2999),
+    ("VIC", 3000, 3999),
+    ("QLD", 4000, 4999),
+    ("SA", 5000, 5999),
+    ("WA", 6000, 6999),
+    ("TAS", 7000, 7999),
+    ("VIC", 8000, 8999),
+    ("QLD", 9000, 9999),
+];
+
+// (canonical abbreviation, spellings), spellings lowercase without dots
+static AU_STREET_TYPES: &[(&str, &[&str])] = &[
+    ("St", &["st", "street", "str"]),
+    ("Rd", &["rd", "road"]),
+    ("Ave", &["ave", "av", "avenue"]),
+    ("Cres", &["cres", "cr", "crescent"]),
+    ("Pde", &["pde", "parade"]),
+    ("Dr", &["dr", "drv", "drive"]),
+    ("Ct", &["ct", "crt", "court"]),
+    ("Pl", &["pl", "place"]),
+    ("Ln", &["ln", "lane"]),
+    ("Tce", &["tce", "terrace"]),
+    ("Hwy", &["hwy", "highway"]),
+    ("Blvd", &["blvd", "bvd", "boulevard"]),
+    ("Cl", &["cl", "close"]),
+    ("Cct", &["cct", "circuit"]),
+    ("Way", &["way"]),
+    ("Esp", &["esp", "esplanade"]),
+    ("Gr", &["gr", "grove"]),
+    ("Sq", &["sq", "square"]),
+    ("Pkwy", &["pkwy", "parkway"]),
+    ("Mews", &["mews"]),
+    ("Row", &["row"]),
+    ("Loop", &["loop"]),
+    ("Track", &["track"]),
+    ("Walk", &["walk"]),
+];
+
+// Trailing "..., QLD 4006", "... 4006 QLD", "... Queensland", "... 4006"
+static RE_ADDRESS_STATE_POSTCODE: LazyLock<Regex> = LazyLock::new(|| {
+    let states = AU_STATES
+        .iter()
+        .flat_map(|(_, aliases)| aliases.iter())
+        .map(|a| a.replace(' ', r"\s+"))
+        .collect::<Vec<_>>()
+        .join("|");
+    Regex::new(&format!(
+        r"(?i)[,\s]*\b(?:(?P<s1>{states})\.?[,\s]*(?P<p1>[0-9]{{4}})|(?P<p2>[0-9]{{4}})[,\s]*(?P<s2>{states})\.?|(?P<s3>{states})\.?|(?P<p3>[0-9]{{4}}))\s*$"
+    ))
+    .unwrap()
+});
+
+// Unit/level prefixes: "Unit 3/45", "U3 45", "Level 2, 45", "3/45"
+static RE_ADDRESS_UNIT: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"(?i)^(?:(?P<kind>unit|u|apt|apartment|flat|suite|shop|level|lvl|l)\.?\s*(?P<id>[0-9]+[a-z]?)\s*[/,]?\s*|(?P<slash>[0-9]+[a-z]?)\s*/\s*)")
+        .unwrap()
+});
+
+static RE_ADDRESS_PO_BOX: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"(?i)^(?:p\.?\s*o\.?|post\s+office)\s*box\s+(?P<num>[0-9]+)[,\s]*").unwrap()
+});
+
+// Street number (with ranges like "45-47") then the rest
+static RE_ADDRESS_NUMBER: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"(?i)^(?P<num>[0-9]+[a-z]?(?:\s*-\s*[0-9]+[a-z]?)?)\s+(?P<rest>.+)$").unwrap()
+});
+
+// A street address inside running text: an optional unit, the street
+// number, one to four name words and a street type (or a PO Box), then one
+// to four suburb words and a state with an optional postcode
+static RE_ADDRESS_IN_TEXT: LazyLock<Regex> = LazyLock::new(|| {
+    let types = AU_STREET_TYPES
+        .iter()
+        .flat_map(|(_, spellings)| spellings.iter())
+        .copied()
+        .collect::<Vec<_>>()
+        .join("|");
+    let states = AU_STATES
+        .iter()
+        .flat_map(|(_, aliases)| aliases.iter())
+        .map(|a| a.replace(' ', r"\s+"))
+        .collect::<Vec<_>>()
+        .join("|");
+    let word = r"[a-z][a-z'.\-]*";
+    Regex::new(&format!(
+        concat!(
+            r"(?i)\b(?:(?:(?:unit|u|apt|apartment|flat|suite|shop|level|lvl|l)\.?\s*[0-9]+[a-z]?\s*[/,]?\s*",
+            r"|[0-9]+[a-z]?\s*/\s*)?[0-9]+[a-z]?(?:\s*-\s*[0-9]+[a-z]?)?\s+(?:{word}\s+){{1,4}}?(?:{types})\b\.?",
+            r"|(?:p\.?\s*o\.?|post\s+office)\s*box\s+[0-9]+)",
+            r"[,\s]+(?:{word},?\s+){{1,4}}?(?:{states})\b\.?(?:[,\s]*[0-9]{{4}}\b)?",
+        ),
+        word = word,
+        types = types,
+        states = states
+    ))
+    .unwrap()
+});
+
+pub(crate) struct AuAddress {
+    unit: Option<String>,
+    street_number: Option<String>,
+    street_name: Option<String>,
+    street_type: Option<&'static str>,
+    suburb: Option<String>,
+    state: Option<&'static str>,
+    postcode: Option<String>,
+}
+
+pub(crate) fn state_for_postcode(postcode: u32) -> Option<&'static str> {
+    AU_POSTCODE_RANGES
+        .iter()
+        .find(|(_, lo, hi)| (*lo..=*hi).contains(&postcode))
+        .map(|(state, _, _)| *state)
+}
+
+pub(crate) fn canonical_state(token: &str) -> Option<&'static str> {
+    let token = token
+        .trim_end_matches('.')
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+        .to_lowercase();
+    AU_STATES
+        .iter()
+        .find(|(_, aliases)| aliases.contains(&token.as_str()))
+        .map(|(state, _)| *state)
+}
+
+fn canonical_street_type(token: &str) -> Option<&'static str> {
+    let token = token.trim_end_matches(['.', ',']).to_lowercase();
+    AU_STREET_TYPES
+        .iter()
+        .find(|(_, spellings)| spellings.contains(&token.as_str()))
+        .map(|(canonical, _)| *canonical)
+}
+
+pub(crate) fn title_case_words(s: &str) -> String {
+    s.split_whitespace()
+        .map(|w| title_case(&w.trim_matches(',').to_lowercase()))
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+impl AuAddress {
+    pub(crate) fn is_po_box(&self) -> bool {
+        self.street_number.is_none()
+            && self
+                .street_name
+                .as_deref()
+                .is_some_and(|name| name.starts_with("PO Box"))
+    }
+
+    /// Lowercased fields joined, for spotting the same address twice.
+    pub(crate) fn key(&self) -> String {
+        [
+            self.unit.as_deref(),
+            self.street_number.as_deref(),
+            self.street_name.as_deref(),
+            self.street_type,
+            self.suburb.as_deref(),
+            self.state,
+            self.postcode.as_deref(),
+        ]
+        .map(|field| field.unwrap_or("").to_lowercase())
+        .join("|")
+    }
+}
+
+/// Street addresses and PO Boxes written in `text`, in order, with the
+/// matched text. Runs of suburb names or a bare "Sydney NSW 2000" are not
+/// addresses: a street number and type (or a PO Box) are required.
+pub(crate) fn find_au_addresses(text: &str) -> Vec<(String, AuAddress)> {
+    RE_ADDRESS_IN_TEXT
+        .find_iter(text)
+        .filter_map(|m| {
+            let raw = m.as_str().trim_end_matches([',', '.', ' ']);
+            let address = parse_au_address_inner(raw)?;
+            (address.street_number.is_some() || address.is_po_box())
+                .then(|| (raw.to_string(), address))
+        })
+        .collect()
+}
+
+pub(crate) fn au_address_dict<'py>(
+    py: Python<'py>,
+    address: &AuAddress,
+) -> PyResult<Bound<'py, PyDict>> {
+    let dict = PyDict::new(py);
+    dict.set_item("unit", &address.unit)?;
+    dict.set_item("street_number", &address.street_number)?;
+    dict.set_item("street_name", &address.street_name)?;
+    dict.set_item("street_type", address.street_type)?;
+    dict.set_item("suburb", &address.suburb)?;
+    dict.set_item("state", address.state)?;
+    dict.set_item("postcode", &address.postcode)?;
+    Ok(dict)
+}
+
+fn parse_au_address_inner(text: &str) -> Option<AuAddress> {
+    let mut rest = text.split_whitespace().collect::<Vec<_>>().join(" ");
+    for tail in [", australia", " australia"] {
+        // Compare the tail bytes directly: lowercasing the whole string can
+        // change its byte length ("İ"), which would skew the cut point
+        let cut = rest.len().saturating_sub(tail.len());
+        if rest
+            .get(cut..)
+            .is_some_and(|end| end.eq_ignore_ascii_case(tail))
+        {
+            rest.truncate(cut);
+        }
+    }
+
+    // State and postcode from the end; give up without either
+    let caps = RE_ADDRESS_STATE_POSTCODE.captures(&rest)?;
+    let state_token = caps.name("s1").or(caps.name("s2")).or(caps.name("s3"));
+    let postcode = caps
+        .name("p1")
+        .or(caps.name("p2"))
+        .or(caps.name("p3"))
+        .map(|m| m.as_str().to_string());
+    let mut state = state_token.and_then(|m| canonical_state(m.as_str()));
+    let postcode_state = postcode
+        .as_deref()
+        .and_then(|p| p.parse::<u32>().ok())
+        .and_then(state_for_postcode);
+    match (state, postcode_state) {
+        (Some(s), Some(p)) if s != p => return None,
+        (None, Some(p)) => state = Some(p),
+        (_, None) if postcode.is_some() => return None,
+        _ => {}
+    }
+    rest.truncate(caps.get(0).unwrap().start());
+    let mut rest = rest.trim_matches([',', ' ']).to_string();
+
+    let mut unit = None;
+    if let Some(caps) = RE_ADDRESS_UNIT.captures(&rest) {
+        unit = match (caps.name("kind"), caps.name("id"), caps.name("slash")) {
+            (Some(kind), Some(id), _) => {
+                let kind = kind.as_str().to_lowercase();
+                let id = id.as_str().to_uppercase();
+                Some(match kind.as_str() {
+                    "level" | "lvl" | "l" => format!("Level {}", id),
+                    "suite" => format!("Suite {}", id),
+                    "shop" => format!("Shop {}", id),
+                    _ => id,
+                })
+            }
+            (_, _, Some(id)) => Some(id.as_str().to_uppercase()),
+            _ => None,
+        };
+        rest = rest[caps.get(0).unwrap().end()..]
+            .trim_start_matches([',', ' '])
+            .to_string();
+    }
+
+    let mut street_number = None;
+    let mut street_name = None;
+    let mut street_type = None;
+    let mut suburb_part = rest.as_str();
+
+    if let Some(caps) = RE_ADDRESS_PO_BOX.captures(&rest) {
+        street_name = Some(format!("PO Box {}", &caps["num"]));
+        suburb_part = &rest[caps.get(0).unwrap().end()..];
+    } else if let Some(caps) = RE_ADDRESS_NUMBER.captures(&rest) {
+        let words: Vec<&str> = caps["rest"].split_whitespace().collect();
+        // Street type is the first type word after at least one name word
+        if let Some(pos) = (1..words.len()).find(|&i| canonical_street_type(words[i]).is_some()) {
+            street_number = Some(
+                caps["num"]
+                    .split_whitespace()
+                    .collect::<String>()
+                    .to_uppercase(),
+            );
+            if words[..pos].len() == 1 && words[0].eq_ignore_ascii_case("the") {
+                // "The Esplanade", "The Strand": the type word is the name
+                street_name = Some(title_case_words(&words[..=pos].join(" ")));
+            } else {
+                street_name = Some(title_case_words(&words[..pos].join(" ")));
+                street_type = canonical_street_type(words[pos]);
+            }
+            let after = caps.name("rest").unwrap();
+            let offset = words[..=pos].iter().map(|w| w.len() + 1).sum::<usize>();
+            suburb_part = rest[after.start()..].get(offset..).unwrap_or("");
+        }
+    }
+
+    let suburb_part = suburb_part.trim_matches([',', ' ']);
+    let suburb = (!suburb_part.is_empty() && !suburb_part.chars().any(|c| c.is_ascii_digit()))
+        .then(|| title_case_words(&suburb_part.replace(',', " ")));
+
+    Some(AuAddress {
+        unit,
+        street_number,
+        street_name,
+        street_type,
+        suburb,
+        state,
+        postcode,
+    })
+}
+
+/// Parse a free-text Australian address.
+///
+/// Returns a dict with unit, street_number, street_name, street_type
+/// (canonical abbreviation: "St", "Rd", "Cres", ...), suburb, state and
+/// postcode; fields that can't be found are None. Returns None when there is
+/// neither a postcode nor a state, or when they contradict each other.
+#[pyfunction]
+pub fn parse_au_address(py: Python<'_>, text: &str) -> PyResult<Option<PyObject>> {
+    let Some(address) = parse_au_address_inner(text) else {
+        return Ok(None);
+    };
+    Ok(Some(au_address_dict(py, &address)?.into()))
+}
+
+/// Canonical single-line form of an Australian address for comparison:
+/// "Unit 3/45 Smith Street, Fortitude Valley QLD 4006" →
+/// "3/45 Smith St, Fortitude Valley QLD 4006". None when parse_au_address
+/// would return None.
+#[pyfunction]
+pub fn normalize_au_address(text: &str) -> Option<String> {
+    let a = parse_au_address_inner(text)?;
+
+    let mut street = String::new();
+    if a.street_number.is_none() {
+        street.push_str(a.street_name.as_deref().unwrap_or(""));
+    }
+    if let Some(number) = &a.street_number {
+        match &a.unit {
+            Some(unit) if unit.contains(' ') => street.push_str(&format!("{}, {}", unit, number)),
+            Some(unit) => street.push_str(&format!("{}/{}", unit, number)),
+            None => street.push_str(number),
+        }
+        if let Some(name) = &a.street_name {
+            street.push(' ');
+            street.push_str(name);
+        }
+        if let Some(kind) = a.street_type {
+            street.push(' ');
+            street.push_str(kind);
+        }
+    }
+
+    let locality = [a.suburb.as_deref(), a.state, a.postcode.as_deref()]
+        .into_iter()
+        .flatten()
+        .collect::<Vec<_>>()
+        .join(" ");
+
+    Some(
+        [street.as_str(), locality.as_str()]
+            .into_iter()
+            .filter(|p| !p.is_empty())
+            .collect::<Vec<_>>()
+            .join(", "),
+    )
+}
+
+// ---------------------------------------------------------------------------
+// Postcode / state extraction
+// ---------------------------------------------------------------------------
+
+static RE_POSTCODE_CANDIDATE: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"\b[0-9]{4}\b").unwrap());
+
+static RE_STATE_TOKEN: LazyLock<Regex> = LazyLock::new(|| {
+    let states = AU_STATES
+        .iter()
+        .flat_map(|(_, aliases)| aliases.iter())
+        .map(|a| a.replace(' ', r"\s+"))
+        .collect::<Vec<_>>()
+        .join("|");
+    Regex::new(&format!(r"(?i)\b(?:{})\b\.?", states)).unwrap()
+});
+
+// Words that make a following 19xx/20xx number a year, not a postcode
+static YEAR_CONTEXT_WORDS: &[&str] = &[
+    "since",
+    "est",
+    "established",
+    "founded",
+    "copyright",
+    "©",
+    "(c)",
+    "in",
+    "from",
+    "year",
+    "until",
+    "to",
+    "by",
+    "circa",
+    "c",
+];
+
+/// False for 4-digit numbers that are prices, phone fragments or years.
+fn is_postcode_context(text: &str, start: usize, end: usize, number: u32) -> bool {
+    let before = text[..start].trim_end_matches(' ');
+    let after = &text[end..];
+
+    // Prices: "$2000", "AUD 2000"
+    let before_lower = before.to_lowercase();
+    if before.ends_with('$') || before_lower.ends_with("aud") || before_lower.ends_with("usd") {
+        return false;
+    }
+    // Ranges and phone numbers: "2000-2010", "1300 123 456", "07 3123 4567"
+    let after_trimmed = after.trim_start_matches([' ', '-', '.']);
+    if after.starts_with(['-', '.', ' ']) && after_trimmed.starts_with(|c: char| c.is_ascii_digit())
+    {
+        return false;
+    }
+    if before
+        .ends_with(|c: char| c.is_ascii_digit() || c == '-' || c == '+' || c == '(' || c == ')')
+    {
+        return false;
+    }
+    // Years: "since 2015", "© 2023"
+    if (1900..=2099).contains(&number) {
+        let previous = before_lower
+            .rsplit(|c: char| c.is_whitespace())
+            .next()
+            .unwrap_or("")
+            .trim_end_matches([',', '.', ':']);
+        if YEAR_CONTEXT_WORDS.contains(&previous) || after.starts_with(['–', '—']) {
+            return false;
+        }
+    }
+    true
+}
+
+fn extract_postcode_state_inner(text: &str) -> Option<(String, Option<&'static str>)> {
+    let states: Vec<(usize, usize, &'static str)> = RE_STATE_TOKEN
+        .find_iter(text)
+        .filter_map(|m| canonical_state(m.as_str()).map(|s| (m.start(), m.end(), s)))
+        .collect();
+    let is_gap = |gap: &str| gap.len() <= 3 && gap.chars().all(|c| c == ' ' || c == ',');
+
+    let mut best: Option<(u8, String, &'static str)> = None;
+    for m in RE_POSTCODE_CANDIDATE.find_iter(text) {
+        let Ok(number) = m.as_str().parse::<u32>() else {
+            continue;
+        };
+        let Some(postcode_state) = state_for_postcode(number) else {
+            continue;
+        };
+        if !is_postcode_context(text, m.start(), m.end(), number) {
+            continue;
+        }
+
+        let adjacent_state = states.iter().find(|(s_start, s_end, _)| {
+            (*s_end <= m.start() && is_gap(&text[*s_end..m.start()]))
+                || (*s_start >= m.end() && is_gap(&text[m.end()..*s_start]))
+        });
+        let score = match adjacent_state {
+            Some((_, _, state)) if *state == postcode_state => 2,
+            Some(_) => continue,
+            None => 1,
+        };
+        // Later candidates win ties: addresses end with the postcode
+        if best
+            .as_ref()
+            .is_none_or(|(best_score, _, _)| score >= *best_score)
+        {
+            best = Some((score, m.as_str().to_string(), postcode_state));
+        }
+    }
+
+    best.map(|(_, postcode, state)| (postcode, Some(state)))
+}
+
+/// Find an Australian postcode and its state in free text.
+///
+/// Postcodes must fall in a real state range; ones next to a state token
+/// ("QLD 4034", "4034 Queensland") are preferred and must agree with it.
+/// Without a state token the state is inferred from the postcode. Years,
+/// prices and phone-number fragments are ignored.
+#[pyfunction]
+pub fn extract_postcode_state(text: &str) -> Option<(String, Option<&'static str>)> {
+    extract_postcode_state_inner(text)
+}
+
+/// extract_postcode_state over many texts with the GIL released.
+#[pyfunction]
+pub fn extract_postcode_state_batch(
+    py: Python<'_>,
+    texts: Vec<String>,
+) -> Vec<Option<(String, Option<&'static str>)>> {
+    py.allow_threads(|| {
+        let extract = |text: &String| extract_postcode_state_inner(text);
+        if texts.len() < BATCH_PARALLEL_THRESHOLD {
+            texts.iter().map(extract).collect()
+        } else {
+            texts.par_iter().map(extract).collect()
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn classified(email: &str) -> (&'static str, Option<String>, Option<String>, Option<String>) {
+        let c = classify_email_inner(email);
+        (c.category.as_str(), c.role, c.first_name, c.last_name)
+    }
+
+    // Email classification
+
+    #[test]
+    fn role_inboxes() {
+        assert_eq!(
+            classified("info@acme.com.au"),
+            ("role", Some("info".into()), None, None)
+        );
+        assert_eq!(
+            classified("Sales.Brisbane@acme.com.au").1,
+            Some("sales".into())
+        );
+        assert_eq!(classified("team@acme.com.au").0, "role");
+        assert_eq!(classified("bookings2@acme.com.au").0, "role");
+    }
+
+    #[test]
+    fn personal_addresses() {
+        assert_eq!(
+            classified("jane.smith@acme.com.au"),
+            ("personal", None, Some("Jane".into()), Some("Smith".into()))
+        );
+        assert_eq!(
+            classified("jane_smith-jones@acme.com.au").3,
+            Some("Smith-Jones".into())
+        );
+        assert_eq!(classified("jsmith@acme.com.au").3, Some("Smith".into()));
+    }
+
+    #[test]
+    fn ambiguous_local_parts_are_unknown() {
+        for email in [
+            "jd@acme.com.au",
+            "j.s@acme.com.au",
+            "acmeplumbing@acmeplumbing.com.au",
+            "not-an-email",
+            "@acme.com.au",
+        ] {
+            assert_eq!(classified(email).0, "unknown", "{}", email);
+        }
+    }
+
+    // Disposable providers
+
+    #[test]
+    fn disposable_providers_and_their_subdomains() {
+        assert!(is_disposable_email("bob@mailinator.com"));
+        assert!(is_disposable_email("bob@abc.mailinator.com"));
+        assert!(is_disposable_email("Bob@ABC.Mailinator.COM"));
+        assert!(is_disposable_email("guerrillamail.com"));
+        assert!(!is_disposable_email("info@acmeplumbing.com.au"));
+        assert!(!is_disposable_email("info@notmailinator.com"));
+        assert!(!is_disposable_email(""));
+    }
+
+    #[test]
+    fn disposable_domain_is_its_own_validation_result() {
+        let result = check_email_domain("bob@abc.mailinator.com", "acme.com.au");
+        assert!(!result.valid);
+        assert_eq!(result.match_type.as_str(), "disposable");
+        assert_eq!(result.message, "Disposable provider");
+
+        let result = check_email_domain("bob@gmail.com", "acme.com.au");
+        assert!(result.valid);
+        assert_eq!(result.match_type.as_str(), "generic_provider");
+    }
+
+    // Strict email syntax
+
+    #[test]
+    fn email_syntax_table() {
+        let long_local = format!("{}@acme.com", "a".repeat(65));
+        let long_label = format!("info@{}.com", "a".repeat(64));
+        let long_email = format!("{}@{}.com", "a".repeat(64), "b.".repeat(95));
+        let cases: &[(&str, Result<&str, &str>)] = &[
+            ("info@acme.com.au", Ok("info@acme.com.au")),
+            ("Jane.Smith@ACME.Com.AU", Ok("Jane.Smith@acme.com.au")),
+            ("  info@acme.com  ", Ok("info@acme.com")),
+            (
+                "o'brien+quotes@acme-plumbing.com",
+                Ok("o'brien+quotes@acme-plumbing.com"),
+            ),
+            ("", Err("Empty email")),
+            ("info.acme.com", Err("Must contain exactly one @")),
+            ("a@b@acme.com", Err("Must contain exactly one @")),
+            ("@acme.com", Err("Empty local part")),
+            (&long_local, Err("Local part longer than 64 characters")),
+            (&long_email, Err("Email longer than 254 characters")),
+            (
+                "\"jane smith\"@acme.com",
+                Err("Quoted local parts are not supported"),
+            ),
+            (
+                "jane smith@acme.com",
+                Err("Invalid character in local part"),
+            ),
+            (".info@acme.com", Err("Misplaced dot in local part")),
+            ("info.@acme.com", Err("Misplaced dot in local part")),
+            ("in..fo@acme.com", Err("Misplaced dot in local part")),
+            ("info@", Err("Empty domain")),
+            (
+                "info@[192.168.0.1]",
+                Err("IP-literal domains are not allowed"),
+            ),
+            ("info@acme..com", Err("Misplaced dot in domain")),
+            ("info@.acme.com", Err("Misplaced dot in domain")),
+            ("info@localhost", Err("Domain must contain a dot")),
+            (&long_label, Err("Domain label longer than 63 characters")),
+            ("info@acme_plumbing.com", Err("Invalid character in domain")),
+            (
+                "info@-acme.com",
+                Err("Domain label starts or ends with a hyphen"),
+            ),
+            ("info@acme.c", Err("Invalid TLD")),
+            ("info@acme.c0m", Err("Invalid TLD")),
+            ("info@192.168.0.1", Err("Invalid TLD")),
+        ];
+        for (email, expected) in cases {
+            assert_eq!(
+                check_email_syntax(email).as_deref(),
+                expected.as_deref(),
+                "{}",
+                email
+            );
+        }
+    }
+
+    #[test]
+    fn strict_filter_drops_malformed_emails() {
+        let emails = vec!["info@acme.com".to_string(), "in..fo@acme.com".to_string()];
+        assert_eq!(
+            filter_emails_for_domain(emails.clone(), "acme.com", false, false, false),
+            emails
+        );
+        assert_eq!(
+            filter_emails_for_domain(emails, "acme.com", false, true, false),
+            vec!["info@acme.com"]
+        );
+    }
+
+    // Email dedupe and ranking
+
+    fn strings(items: &[&str]) -> Vec<String> {
+        items.iter().map(|s| s.to_string()).collect()
+    }
+
+    #[test]
+    fn dedupe_and_rank_orders_by_tier() {
+        let emails = strings(&[
+            "acme.plumbing@gmail.com",
+            "info@acmeplumbing.com.au",
+            "bookings@acmeplumbing.com.au",
+            "INFO@acmeplumbing.com.au",
+            "jane.smith@acmeplumbing.com.au",
+            "office@mail.acmeplumbing.com.au",
+            "someone@mailinator.com",
+            "jane@otherbusiness.com.au",
+        ]);
+        assert_eq!(
+            dedupe_and_rank_emails(emails, "www.acmeplumbing.com.au"),
+            strings(&[
+                "jane.smith@acmeplumbing.com.au",
+                "info@acmeplumbing.com.au",
+                "bookings@acmeplumbing.com.au",
+                "office@mail.acmeplumbing.com.au",
+                "acme.plumbing@gmail.com",
+            ])
+        );
+    }
+
+    #[test]
+    fn dedupe_and_rank_is_stable_within_a_tier() {
+        let emails = strings(&[
+            "sales@acme.com.au",
+            "accounts@acme.com.au",
+            "info@acme.com.au",
+            "sales@acme.com.au",
+        ]);
+        assert_eq!(
+            dedupe_and_rank_emails(emails, "acme.com.au"),
+            strings(&[
+                "sales@acme.com.au",
+                "accounts@acme.com.au",
+                "info@acme.com.au"
+            ])
+        );
+    }
+
+    #[test]
+    fn dedupe_and_rank_handles_thousands_of_addresses() {
+        let emails: Vec<String> = (0..5000)
+            .map(|i| format!("person{}@acme.com.au", i % 2500))
+            .collect();
+        assert_eq!(dedupe_and_rank_emails(emails, "acme.com.au").len(), 2500);
+    }
+
+    // Detailed domain filtering
+
+    #[test]
+    fn detailed_filter_reports_every_rejection_reason() {
+        let emails = strings(&[
+            "info@acme.com.au",
+            "noreply@acme.com.au",
+            "user@example.com",
+            "bob@mailinator.com",
+            "jane@otherbusiness.com.au",
+            "acme@gmail.com",
+        ]);
+        let (kept, rejected) = filter_emails_with_reasons(emails, "acme.com.au");
+        assert_eq!(kept, strings(&["info@acme.com.au", "acme@gmail.com"]));
+        let reasons: Vec<(&str, &str, &str)> = rejected
+            .iter()
+            .map(|r| (r.email.as_str(), r.reason, r.match_type.as_str()))
+            .collect();
+        assert_eq!(
+            reasons,
+            vec![
+                ("noreply@acme.com.au", "spam", "exact"),
+                ("user@example.com", "excluded_pattern", "mismatch"),
+                ("bob@mailinator.com", "disposable", "disposable"),
+                ("jane@otherbusiness.com.au", "domain_mismatch", "mismatch"),
+            ]
+        );
+    }
+
+    // Public suffixes
+
+    #[test]
+    fn registrable_domains() {
+        let cases = [
+            ("bookings.acmeplumbing.com.au", Some("acmeplumbing.com.au")),
+            ("acmeplumbing.net.au", Some("acmeplumbing.net.au")),
+            ("business.qld.gov.au", Some("business.qld.gov.au")),
+            ("www.health.gov.au", Some("health.gov.au")),
+            ("smith.id.au", Some("smith.id.au")),
+            ("club.asn.au", Some("club.asn.au")),
+            ("shop.acme.co.nz", Some("acme.co.nz")),
+            ("www.acme.co.uk", Some("acme.co.uk")),
+            ("www.Acme.COM", Some("acme.com")),
+            ("com.au", None),
+            ("qld.gov.au", None),
+            ("", None),
+        ];
+        for (host, expected) in cases {
+            assert_eq!(registrable_domain(host).as_deref(), expected, "{}", host);
+        }
+    }
+
+    #[test]
+    fn shared_suffix_is_not_a_shared_base_domain() {
+        for (email, website) in [
+            ("info@plumbing.com.au", "electrical.com.au"),
+            ("info@acme.net.au", "other.net.au"),
+            ("info@business.qld.gov.au", "health.qld.gov.au"),
+            ("info@smith.id.au", "jones.id.au"),
+            ("info@acme.co.nz", "other.co.nz"),
+        ] {
+            let result = check_email_domain(email, website);
+            assert_eq!(
+                result.match_type.as_str(),
+                "mismatch",
+                "{} vs {}",
+                email,
+                website
+            );
+        }
+    }
+
+    #[test]
+    fn same_registrable_domain_is_a_base_domain_match() {
+        let result = check_email_domain("info@mail.acme.com.au", "shop.acme.com.au");
+        assert_eq!(result.match_type.as_str(), "base_domain");
+        // com.au and net.au registrations are different businesses
+        let result = check_email_domain("info@acme.net.au", "acme.com.au");
+        assert_eq!(result.match_type.as_str(), "mismatch");
+    }
+
+    // Internationalised domains
+
+    #[test]
+    fn unicode_and_punycode_domains_normalize_to_one_value() {
+        let spellings = [
+            "münchner-bäckerei.de",
+            "https://MÜNCHNER-Bäckerei.DE/path",
+            "http://www.xn--mnchner-bckerei-8kb31b.de",
+            "XN--MNCHNER-BCKEREI-8KB31B.DE",
+        ];
+        for raw in spellings {
+            assert_eq!(
+                normalize_domain(raw, false, false, true).as_deref(),
+                Some("xn--mnchner-bckerei-8kb31b.de"),
+                "{}",
+                raw
+            );
+        }
+        assert_eq!(
+            normalize_domain("café-sydney.com.au", false, false, true),
+            normalize_domain("https://xn--caf-sydney-d7a.com.au/", false, false, true)
+        );
+    }
+
+    #[test]
+    fn display_domain_round_trips() {
+        assert_eq!(
+            display_domain("xn--mnchner-bckerei-8kb31b.de"),
+            "münchner-bäckerei.de"
+        );
+        assert_eq!(display_domain("ACME.com.au"), "acme.com.au");
+    }
+
+    #[test]
+    fn invalid_idna_is_rejected() {
+        let overlong = format!("{}.com", "ä".repeat(70));
+        for raw in [
+            "xn--zz.com",
+            "bad\u{FFFD}host.com.au",
+            "acme\u{2488}.com",
+            overlong.as_str(),
+        ] {
+            assert_eq!(normalize_domain(raw, false, false, true), None, "{}", raw);
+        }
+    }
+
+    // Internal hosts and IP literals
+
+    #[test]
+    fn internal_hosts_and_ips_are_rejected() {
+        for raw in [
+            "http://192.168.0.10/",
+            "http://192.168.0.10:8080/admin",
+            "http://[::1]:3000/",
+            "https://[2001:db8::1]/",
+            "http://localhost:3000",
+            "printer.local",
+            "api.internal",
+            "site.test",
+            "foo.invalid",
+            "intranet",
+            "acme.123",
+        ] {
+            assert_eq!(normalize_domain(raw, false, false, true), None, "{}", raw);
+        }
+    }
+
+    #[test]
+    fn allow_ip_keeps_ip_literals() {
+        assert_eq!(
+            normalize_domain("http://192.168.0.10:8080/", true, false, true).as_deref(),
+            Some("192.168.0.10")
+        );
+        assert_eq!(
+            normalize_domain("https://[2001:db8::1]:8443/x", true, false, true).as_deref(),
+            Some("2001:db8::1")
+        );
+        assert_eq!(
+            normalize_domain("http://localhost:3000", true, false, true),
+            None
+        );
+    }
+
+    #[test]
+    fn ports_are_stripped_from_real_hosts() {
+        assert_eq!(
+            normalize_domain("https://www.acme.com.au:8443/contact", false, false, true).as_deref(),
+            Some("acme.com.au")
+        );
+        assert_eq!(
+            normalize_domain("acme.com.au:80", false, false, true).as_deref(),
+            Some("acme.com.au")
+        );
+    }
+
+    // Common subdomains
+
+    #[test]
+    fn strip_common_subdomains() {
+        let strip = |raw: &str| normalize_domain(raw, false, true, true);
+        assert_eq!(
+            strip("https://m.example.com.au/").as_deref(),
+            Some("example.com.au")
+        );
+        assert_eq!(
+            strip("www.example.com.au").as_deref(),
+            Some("example.com.au")
+        );
+        assert_eq!(
+            strip("amp.example.com.au").as_deref(),
+            Some("example.com.au")
+        );
+        assert_eq!(
+            strip("mobile.www2.example.com.au").as_deref(),
+            Some("example.com.au")
+        );
+        assert_eq!(
+            strip("brisbane.acmegroup.com.au").as_deref(),
+            Some("brisbane.acmegroup.com.au")
+        );
+        assert_eq!(strip("m.com.au").as_deref(), Some("m.com.au"));
+    }
+
+    #[test]
+    fn host_and_registrable_domain_are_both_available() {
+        let url = "https://m.brisbane.acmegroup.com.au/contact";
+        assert_eq!(
+            normalize_domain(url, false, false, true).as_deref(),
+            Some("m.brisbane.acmegroup.com.au")
+        );
+        assert_eq!(
+            normalize_domain(url, false, true, true).as_deref(),
+            Some("brisbane.acmegroup.com.au")
+        );
+        assert_eq!(canonical_domain(url).as_deref(), Some("acmegroup.com.au"));
+    }
+
+    // Batch normalization
+
+    #[test]
+    fn batch_normalization_matches_single_calls_on_50k_urls() {
+        let urls: Vec<String> = (0..50_000)
+            .map(|i| match i % 5 {
+                0 => format!(
+                    "https://www.business{}.com.au/contact?utm_source=x",
+                    i % 7000
+                ),
+                1 => format!("http://m.business{}.com.au", i % 7000),
+                2 => format!("business{}.co.nz/about", i % 3000),
+                3 => "not a url".to_string(),
+                _ => format!("http://192.168.0.{}/", i % 250),
+            })
+            .collect();
+
+        let batch = normalize_domains_of(&urls, false, false, true);
+        assert_eq!(batch.len(), urls.len());
+        for (url, domain) in urls.iter().zip(&batch) {
+            assert_eq!(
+                domain,
+                &normalize_domain(url, false, false, true),
+                "{}",
+                url
+            );
+        }
+        assert_eq!(batch[3], None);
+    }
+
+    #[test]
+    fn first_seen_domains_maps_back_to_rows() {
+        let urls = strings(&[
+            "https://www.acme.com.au/",
+            "junk",
+            "acme.com.au/contact",
+            "https://other.co.nz",
+            "http://ACME.com.au",
+        ]);
+        let domains = normalize_domains_of(&urls, false, false, true);
+        assert_eq!(
+            first_seen_domains(&domains),
+            vec![
+                ("acme.com.au".to_string(), 0),
+                ("other.co.nz".to_string(), 3)
+            ]
+        );
+    }
+
+    // URL canonicalization
+
+    #[test]
+    fn canonicalize_url_strips_tracking_and_fragments() {
+        let canonical = Some("https://acme.com.au/contact".to_string());
+        for url in [
+            "https://acme.com.au/contact?utm_source=gmb&fbclid=xyz#map",
+            "https://acme.com.au/contact/",
+            "HTTPS://ACME.com.au:443//contact",
+            "acme.com.au/contact?gclid=1&msclkid=2&mc_cid=3&ref=home",
+        ] {
+            assert_eq!(canonicalize_url_inner(url, &[]), canonical, "{}", url);
+        }
+    }
+
+    #[test]
+    fn canonicalize_url_keeps_meaningful_params_sorted() {
+        assert_eq!(
+            canonicalize_url_inner("https://acme.com.au/shop/?page=2&id=7&utm_medium=cpc", &[])
+                .as_deref(),
+            Some("https://acme.com.au/shop/?id=7&page=2")
+        );
+        assert_eq!(
+            canonicalize_url_inner(
+                "http://acme.com.au:8080/?session=1&page=2",
+                &strings(&["session"])
+            )
+            .as_deref(),
+            Some("http://acme.com.au:8080/?page=2")
+        );
+    }
+
+    #[test]
+    fn canonicalize_url_rejects_unparseable_input() {
+        for url in [
+            "",
+            "   ",
+            "ftp://acme.com.au/file",
+            "https://",
+            "http://[oops/",
+        ] {
+            assert_eq!(canonicalize_url_inner(url, &[]), None, "{:?}", url);
+        }
+    }
+
+    // Domain parts
+
+    fn parts(host: &str) -> Option<(Option<String>, Option<String>, String)> {
+        let host = prepare_host(host)?;
+        let p = split_host(&host);
+        Some((
+            p.subdomain.map(str::to_string),
+            p.registrable.map(str::to_string),
+            p.suffix.to_string(),
+        ))
+    }
+
+    #[test]
+    fn split_domain_parts() {
+        let own = |s: &str| Some(s.to_string());
+        assert_eq!(
+            parts("bookings.acmeplumbing.com.au"),
+            Some((own("bookings"), own("acmeplumbing.com.au"), "com.au".into()))
+        );
+        assert_eq!(
+            parts("a.b.acme.co.nz"),
+            Some((own("a.b"), own("acme.co.nz"), "co.nz".into()))
+        );
+        assert_eq!(
+            parts("acme.com"),
+            Some((None, own("acme.com"), "com".into()))
+        );
+        assert_eq!(parts("com.au"), Some((None, None, "com.au".into())));
+        assert_eq!(
+            parts("Shop.Café-Sydney.com.au."),
+            Some((
+                own("shop"),
+                own("xn--caf-sydney-d7a.com.au"),
+                "com.au".into()
+            ))
+        );
+        assert_eq!(parts("  "), None);
+    }
+
+    // Directory categories
+
+    #[test]
+    fn classify_domain_categories() {
+        let cases = [
+            ("facebook.com", Some("social")),
+            ("m.facebook.com", Some("social")),
+            ("www.yellowpages.com.au", Some("directory")),
+            ("airtasker.com.au", Some("marketplace")),
+            ("au.indeed.com", Some("job_board")),
+            ("uk.trustpilot.com", Some("review")),
+            ("www.abc.net.au", Some("news")),
+            ("en.wikipedia.org", Some("tech")),
+            ("acmeplumbing.com.au", None),
+            ("notfacebook.com", None),
+            ("facebook.com.evil.net", None),
+            ("", None),
+        ];
+        for (domain, expected) in cases {
+            assert_eq!(classify_domain(domain), expected, "{}", domain);
+            assert_eq!(
+                is_directory_domain(domain),
+                expected.is_some(),
+                "{}",
+                domain
+            );
+        }
+    }
+
+    #[test]
+    fn directory_subdomain_matching_is_unchanged() {
+        // The original check: exact match or a "." + listed domain suffix
+        let listed = |host: &str| {
+            DIRECTORY_CATEGORIES.iter().any(|(_, domains)| {
+                domains
+                    .iter()
+                    .any(|d| host == *d || host.ends_with(&format!(".{}", d)))
+            })
+        };
+        for (_, domains) in DIRECTORY_CATEGORIES {
+            for domain in domains.iter() {
+                for host in [
+                    domain.to_string(),
+                    format!("m.{}", domain),
+                    format!("a.b.{}", domain),
+                    format!("x{}", domain),
+                    format!("{}.au", domain),
+                ] {
+                    assert_eq!(is_directory_domain(&host), listed(&host), "{}", host);
+                }
+            }
+        }
+    }
+
+    // Runtime directory overrides
+
+    #[test]
+    fn registered_directory_entries_apply_immediately() {
+        assert!(!is_directory_domain("serviceseekers.co.nz"));
+        let entry = domain_entry(".ServiceSeekers.co.nz").unwrap();
+        assert_eq!(entry, "serviceseekers.co.nz");
+        assert_eq!(add_directory_domains(vec![entry], "marketplace"), 1);
+        assert_eq!(
+            add_directory_domains(strings(&["serviceseekers.co.nz", "yelp.com"]), "directory"),
+            0
+        );
+        assert!(is_directory_domain("www.serviceseekers.co.nz"));
+        assert_eq!(classify_domain("serviceseekers.co.nz"), Some("marketplace"));
+        assert!(list_directory_domains().contains(&"serviceseekers.co.nz".to_string()));
+
+        let pattern = directory_url_pattern_entry(" /Classifieds/ ").unwrap();
+        assert_eq!(add_directory_url_patterns(vec![pattern]), 1);
+        assert!(is_directory_url(
+            "https://acme.com.au/classifieds/tools",
+            "acme.com.au",
+            true
+        ));
+        assert!(list_directory_url_patterns().contains(&"/classifieds/".to_string()));
+    }
+
+    #[test]
+    fn invalid_directory_entries_are_rejected() {
+        for entry in [
+            "not a domain",
+            "localhost",
+            "acme.com.",
+            "acme_plumbing.com",
+            "",
+        ] {
+            assert_eq!(domain_entry(entry), None, "{:?}", entry);
+        }
+        for pattern in ["listing/", "/", "/a b/", ""] {
+            assert_eq!(directory_url_pattern_entry(pattern), None, "{:?}", pattern);
+        }
+    }
+
+    #[test]
+    fn directory_registration_is_thread_safe() {
+        let handles: Vec<_> = (0..8)
+            .map(|t| {
+                std::thread::spawn(move || {
+                    for i in 0..50 {
+                        let domain = format!("threaded{}-{}.com.au", t, i);
+                        add_directory_domains(vec![domain.clone()], "directory");
+                        assert!(is_directory_domain(&domain));
+                    }
+                })
+            })
+            .collect();
+        for handle in handles {
+            handle.join().unwrap();
+        }
+        assert!(list_directory_domains().contains(&"threaded7-49.com.au".to_string()));
+    }
+
+    // Directory URL patterns
+
+    #[test]
+    fn directory_url_patterns_match_whole_segments() {
+        let cases = [
+            (
+                "https://acmephysio.com.au/services/ankle-review/",
+                "acmephysio.com.au",
+                false,
+            ),
+            (
+                "https://shop.acme.com.au/category/taps/",
+                "shop.acme.com.au",
+                false,
+            ),
+            (
+                "https://acme.com.au/reviews-and-news/",
+                "acme.com.au",
+                false,
+            ),
+            (
+                "https://www.yelp.com.au/biz/acme-plumbing-sydney",
+                "www.yelp.com.au",
+                true,
+            ),
+            (
+                "https://example.com.au/biz/acme-plumbing",
+                "example.com.au",
+                true,
+            ),
+            (
+                "https://example.com.au/search?q=plumber",
+                "example.com.au",
+                true,
+            ),
+            ("https://example.com.au/search/", "example.com.au", false),
+        ];
+        for (url, domain, expected) in cases {
+            assert_eq!(is_directory_url(url, domain, true), expected, "{}", url);
+        }
+    }
+
+    #[test]
+    fn relative_directory_urls_are_resolved() {
+        assert!(is_directory_url("/biz/acme", "example.com.au", true));
+        assert!(is_directory_url(
+            "//example.com.au/biz/acme",
+            "example.com.au",
+            true
+        ));
+        assert!(!is_directory_url(
+            "/services/ankle-review/",
+            "acme.com.au",
+            true
+        ));
+        assert_eq!(
+            url_path_segments("/biz/acme?x=1"),
+            Some((strings(&["biz", "acme"]), true))
+        );
+        assert_eq!(
+            url_path_segments("yelp.com.au/biz/acme"),
+            Some((strings(&["biz", "acme"]), false))
+        );
+    }
+
+    #[test]
+    fn ambiguous_patterns_need_a_directory_domain_when_strict() {
+        let url = "https://acme.com.au/business/plumbing";
+        assert!(!is_directory_url(url, "acme.com.au", true));
+        assert!(is_directory_url(url, "acme.com.au", false));
+    }
+
+    // Name similarity
+
+    #[test]
+    fn same_business_name_pairs_match() {
+        for (a, b) in [
+            ("ACME Plumbing Pty Ltd", "Acme Plumbing"),
+            ("Smith & Sons Electrical", "Smith and Sons Electrical"),
+            ("Plumbing Acme", "Acme Plumbing"),
+            ("Jims Mowing Paddington", "Jim's Mowing Paddington"),
+            ("Bayside Roofing Pty. Ltd.", "BAYSIDE ROOFING"),
+            ("ACME Plumbing Pty Ltd", "Acme Plumbing & Gas"),
+            ("O'Brien Electrical Services", "OBrien Electrical"),
+        ] {
+            assert!(
+                names_match(a, b, 0.85),
+                "{} / {}: {}",
+                a,
+                b,
+                name_similarity(a, b)
+            );
+        }
+        assert_eq!(
+            name_similarity("Acme Plumbing", "acme plumbing pty ltd"),
+            1.0
+        );
+    }
+
+    #[test]
+    fn different_business_name_pairs_do_not_match() {
+        for (a, b) in [
+            ("Acme Plumbing", "Apex Electrical"),
+            ("Brisbane Plumbing Group", "Gold Coast Roofing"),
+            ("Smith Plumbing", "Jones Plumbing"),
+            ("Sydney Tree Services", "Sydney Pool Services"),
+        ] {
+            assert!(
+                !names_match(a, b, 0.85),
+                "{} / {}: {}",
+                a,
+                b,
+                name_similarity(a, b)
+            );
+        }
+        assert_eq!(name_similarity("", "Acme"), 0.0);
+    }
+
+    #[test]
+    fn match_names_returns_candidate_indices() {
+        let candidates = strings(&[
+            "Apex Electrical",
+            "ACME PLUMBING PTY LTD",
+            "Acme Plumbing & Gas",
+            "Plumbing Acme",
+        ]);
+        assert_eq!(
+            match_names(candidates, "Acme Plumbing", 0.85),
+            vec![1, 2, 3]
+        );
+    }
+
+    // Name tokens and stopwords
+
+    #[test]
+    fn name_tokens_drop_stopwords_and_stem() {
+        assert_eq!(
+            name_tokens("The Plumbers Group Australia Pty Ltd", true),
+            strings(&["plumber"])
+        );
+        assert_eq!(
+            name_tokens("Brisbane Batteries & Glasses", true),
+            strings(&["battery", "brisbane", "glass"])
+        );
+        assert_eq!(
+            name_tokens("Brisbane Plumbers", false),
+            strings(&["brisbane", "plumbers"])
+        );
+        // A name made only of stopwords keeps them
+        assert_eq!(
+            name_tokens("Services and Solutions", false),
+            strings(&["and", "services", "solutions"])
+        );
+    }
+
+    #[test]
+    fn stopwords_stop_filler_words_deciding_similarity() {
+        let raw_tokens = |name: &str| {
+            let mut tokens: Vec<String> = normalize_name(name)
+                .split_whitespace()
+                .map(str::to_string)
+                .collect();
+            tokens.sort_unstable();
+            tokens
+        };
+        let raw_similarity = |a: &str, b: &str| tokens_similarity(&raw_tokens(a), &raw_tokens(b));
+
+        // Same business, different filler
+        let (a, b) = (
+            "The Acme Plumbing Group Australia",
+            "Acme Plumbing Services",
+        );
+        assert!(name_similarity(a, b) > raw_similarity(a, b));
+        assert!(names_match(a, b, 0.85));
+
+        // Different businesses that only share filler and a trade
+        let (a, b) = ("The Plumbing Group Australia", "Brisbane Plumbing Group");
+        assert!(!names_match(a, b, 0.85), "{}", name_similarity(a, b));
+        let (a, b) = ("Acme Services Australia", "Apex Services Australia");
+        assert!(name_similarity(a, b) < raw_similarity(a, b));
+        assert!(!names_match(a, b, 0.85));
+    }
+
+    #[test]
+    fn stopwords_do_not_break_exact_matches() {
+        for name in ["The Plumbing Group", "Acme Services", "Australia Post"] {
+            assert_eq!(name_similarity(name, name), 1.0, "{}", name);
+        }
+        assert_eq!(
+            name_similarity("Acme Services", "ACME SERVICES PTY LTD"),
+            1.0
+        );
+    }
+
+    // Name normalization
+
+    #[test]
+    fn normalize_name_folds_ampersands_accents_and_punctuation() {
+        for (a, b) in [
+            ("Smith & Sons Plumbing", "Smith and Sons Plumbing"),
+            ("Smith + Sons Plumbing", "Smith and Sons Plumbing"),
+            ("Café Élan", "Cafe Elan"),
+            ("Nguyễn Bakery", "Nguyen Bakery"),
+            ("Rock\u{2019}n\u{2019}Roll Removals", "Rock n Roll Removals"),
+            ("Rock'n'Roll Removals", "Rock n Roll Removals"),
+            ("O\u{2019}Brien \u{2013} Electrical", "O'Brien - Electrical"),
+            ("Straße Bäckerei", "Strasse Backerei"),
+        ] {
+            assert_eq!(normalize_name(a), normalize_name(b), "{} / {}", a, b);
+        }
+        assert_eq!(normalize_name("Café Élan"), "cafe elan");
+        assert_eq!(normalize_name("Rock'n'Roll"), "rock n roll");
+    }
+
+    #[test]
+    fn normalize_name_is_idempotent() {
+        for name in [
+            "Smith & Sons Pty Ltd",
+            "Café Élan (QLD)",
+            "Rock\u{2019}n\u{2019}Roll + Co",
+            "The Trustee for Smith Family Trust t/a Acme Plumbing",
+            "“Quoted” — Name™",
+            "",
+        ] {
+            let once = normalize_name(name);
+            assert_eq!(normalize_name(&once), once, "{}", name);
+        }
+    }
+
+    // Legal suffixes and trading names
+
+    #[test]
+    fn chained_legal_suffixes_are_stripped() {
+        for (name, expected) in [
+            ("Acme Co Pty Ltd", "acme"),
+            ("Acme Plumbing Pty. Ltd.", "acme plumbing"),
+            ("Acme Holdings Group Pty Ltd", "acme"),
+            ("Smith & Sons Enterprises (Aust)", "smith"),
+            ("Acme Plumbing (QLD) Pty Ltd", "acme plumbing"),
+            ("Acme & Co", "acme"),
+            ("Coco Republic", "coco republic"),
+            ("Coco", "coco"),
+        ] {
+            assert_eq!(normalize_name(name), expected, "{}", name);
+        }
+    }
+
+    #[test]
+    fn trading_as_prefers_the_trading_name() {
+        for (name, legal, trading) in [
+            (
+                "Smith Pty Ltd t/a Acme Plumbing",
+                "Smith Pty Ltd",
+                "Acme Plumbing",
+            ),
+            (
+                "SMITH PTY LTD T/A ACME PLUMBING",
+                "SMITH PTY LTD",
+                "ACME PLUMBING",
+            ),
+            (
+                "J Smith trading as Acme Plumbing",
+                "J Smith",
+                "Acme Plumbing",
+            ),
+        ] {
+            assert_eq!(split_trading_as(name), Some((legal, trading)), "{}", name);
+            assert_eq!(normalize_name(name), "acme plumbing", "{}", name);
+        }
+        assert_eq!(split_trading_as("Acme Plumbing"), None);
+        assert_eq!(
+            normalize_name("The Trustee for Smith Family Trust t/a Acme Plumbing"),
+            "acme plumbing"
+        );
+        assert_eq!(
+            normalize_name("Acme Plumbing ATF Smith Family Trust"),
+            "acme plumbing"
+        );
+        assert_eq!(
+            normalize_name("Acme Plumbing as trustee for the Smith Trust"),
+            "acme plumbing"
+        );
+    }
+
+    // Business name cleaning
+
+    #[test]
+    fn marketing_tails_are_cut_at_any_separator() {
+        for (name, expected) in [
+            (
+                "Acme Plumbing – Brisbane's Trusted Plumbers",
+                "Acme Plumbing",
+            ),
+            ("Acme Plumbing — Same Day Service", "Acme Plumbing"),
+            ("Acme Plumbing - Licensed & Insured", "Acme Plumbing"),
+            ("Acme Plumbing | Best Plumbers in Brisbane", "Acme Plumbing"),
+            ("Acme Plumbing: Free Quotes Today", "Acme Plumbing"),
+            ("Acme Plumbing • Emergency Call Outs", "Acme Plumbing"),
+        ] {
+            assert_eq!(clean_business_name(name, true), expected, "{}", name);
+        }
+    }
+
+    #[test]
+    fn franchise_locations_follow_keep_location() {
+        for name in [
+            "Ray White - Paddington",
+            "Ray White – Paddington",
+            "Ray White | Paddington",
+            "Ray White: Paddington",
+            "Ray White • Paddington",
+        ] {
+            assert_eq!(clean_business_name(name, true), name, "{}", name);
+            assert_eq!(clean_business_name(name, false), "Ray White", "{}", name);
+        }
+    }
+
+    #[test]
+    fn page_title_tails_after_hard_separators_are_cut() {
+        assert_eq!(
+            clean_business_name("Acme Plumbing | plumber in brisbane", true),
+            "Acme Plumbing"
+        );
+        assert_eq!(
+            clean_business_name("Ray White | Paddington | Real estate agent", true),
+            "Ray White | Paddington"
+        );
+        // A dash in the name itself stays
+        assert_eq!(
+            clean_business_name("Smith - Jones Lawyers", true),
+            "Smith - Jones Lawyers"
+        );
+    }
+
+    #[test]
+    fn page_names_after_hard_separators_are_cut() {
+        for (name, expected) in [
+            ("Acme Plumbing | Home", "Acme Plumbing"),
+            ("Acme Plumbing | Contact Us", "Acme Plumbing"),
+            ("Acme Plumbing | Plumbers Brisbane", "Acme Plumbing"),
+            ("Acme Plumbing: Our Services", "Acme Plumbing"),
+            ("Acme Plumbing | Gold Coast", "Acme Plumbing | Gold Coast"),
+            (
+                "Acme Plumbing | Paddington QLD 4064",
+                "Acme Plumbing | Paddington QLD 4064",
+            ),
+        ] {
+            assert_eq!(clean_business_name(name, true), expected, "{}", name);
+        }
+    }
+
+    #[test]
+    fn decorative_symbols_are_removed() {
+        for (name, expected) in [
+            ("🔧 Acme Plumbing ✅ 📞", "Acme Plumbing"),
+            ("⭐⭐⭐⭐⭐ Acme Plumbing 🏆", "Acme Plumbing"),
+            ("Acme ⚡ Electrical ❤️", "Acme Electrical"),
+            ("👨\u{200D}👩\u{200D}👧 Family Dental 👍🏽", "Family Dental"),
+            ("🏳️\u{200D}🌈 Rainbow Cafe", "Rainbow Cafe"),
+            ("➡ Acme Roofing ✔ ┃", "Acme Roofing"),
+        ] {
+            assert_eq!(clean_business_name(name, true), expected, "{:?}", name);
+        }
+    }
+
+    #[test]
+    fn non_ascii_letters_survive_symbol_removal() {
+        for name in [
+            "Café Noir",
+            "Nguyễn Bros",
+            "Phở Hà Nội",
+            "Zoë's Bakery",
+            "Ōtautahi Builders",
+        ] {
+            assert_eq!(clean_business_name(name, true), name);
+        }
+    }
+
+    // Location qualifiers
+
+    fn name_location(name: &str) -> Option<(String, String)> {
+        name_location_of(name, &HashSet::new())
+    }
+
+    #[test]
+    fn leading_possessive_location_is_stripped() {
+        for (name, cleaned, location) in [
+            ("Brisbane's Best Plumbing", "Best Plumbing", "Brisbane"),
+            ("Sydney's Best Plumbing", "Best Plumbing", "Sydney"),
+            (
+                "Gold Coast\u{2019}s Favourite Cafe",
+                "Favourite Cafe",
+                "Gold Coast",
+            ),
+        ] {
+            assert_eq!(
+                name_location(name),
+                Some((cleaned.to_string(), location.to_string())),
+                "{}",
+                name
+            );
+        }
+    }
+
+    #[test]
+    fn trailing_location_is_stripped() {
+        assert_eq!(
+            name_location("Acme Plumbing Sydney"),
+            Some(("Acme Plumbing".to_string(), "Sydney".to_string()))
+        );
+        assert_eq!(
+            name_location("Acme Plumbing Brisbane QLD"),
+            Some(("Acme Plumbing".to_string(), "Brisbane QLD".to_string()))
+        );
+        assert_eq!(
+            name_location_of(
+                "Acme Plumbing Paddington",
+                &HashSet::from(["paddington".to_string()])
+            ),
+            Some(("Acme Plumbing".to_string(), "Paddington".to_string()))
+        );
+    }
+
+    #[test]
+    fn integral_locations_are_kept() {
+        for name in [
+            "Sydney Tools",
+            "Brisbane City Council",
+            "Plumbers Gold Coast",
+            "Plumbing Services Brisbane",
+            "Brisbane's Plumbing",
+            "Acme Plumbing",
+        ] {
+            assert_eq!(name_location(name), None, "{}", name);
+        }
+    }
+
+    fn franchise(name: &str) -> Option<(String, Option<String>)> {
+        detect_franchise_inner(name).map(|m| (m.brand, m.territory))
+    }
+
+    #[test]
+    fn franchise_territory_in_each_format() {
+        let paddington = Some(("Jim's Mowing".to_string(), Some("Paddington".to_string())));
+        assert_eq!(franchise("Jim's Mowing (Paddington)"), paddington);
+        assert_eq!(franchise("Jim's Mowing - Paddington"), paddington);
+        assert_eq!(franchise("Jim's Mowing Paddington"), paddington);
+        assert_eq!(
+            franchise("Poolwerx Cannon Hill"),
+            Some(("Poolwerx".to_string(), Some("Cannon Hill".to_string())))
+        );
+        assert_eq!(
+            franchise("Fernwood Fitness Toowong"),
+            Some(("Fernwood Fitness".to_string(), Some("Toowong".to_string())))
+        );
+    }
+
+    #[test]
+    fn bare_brands_and_non_franchises() {
+        assert_eq!(franchise("Poolwerx"), Some(("Poolwerx".to_string(), None)));
+        assert_eq!(franchise("Acme Plumbing (Paddington)"), None);
+        assert_eq!(franchise(""), None);
+    }
+
+    #[test]
+    fn registered_brands_are_detected() {
+        let brand = "Zorblax Test Cleaning";
+        assert_eq!(franchise("Zorblax Test Cleaning Toowong"), None);
+        assert_eq!(register_franchise_brands(vec![brand.to_string()]), 1);
+        assert_eq!(register_franchise_brands(vec![brand.to_string()]), 0);
+        assert_eq!(
+            franchise("Zorblax Test Cleaning Toowong"),
+            Some((brand.to_string(), Some("Toowong".to_string())))
+        );
+    }
+
+    #[test]
+    fn placeholder_names_give_a_reason() {
+        for (name, reason) in [
+            ("Test", "placeholder_phrase"),
+            ("Your Company Name", "placeholder_phrase"),
+            ("My Business", "placeholder_phrase"),
+            ("Lorem ipsum dolor", "placeholder_phrase"),
+            ("Asdf", "keyboard_walk"),
+            ("QWERTY", "keyboard_walk"),
+            ("xxxx", "repeated_character"),
+            ("12345", "numeric"),
+            ("x", "too_short"),
+            ("", "too_short"),
+            ("The And Of", "stopwords_only"),
+        ] {
+            assert_eq!(placeholder_reason(name), Some(reason), "{}", name);
+        }
+    }
+
+    #[test]
+    fn real_names_are_not_placeholders() {
+        for name in [
+            "JB",
+            "KFC",
+            "Acme Plumbing",
+            "Testa Plumbing",
+            "Bunnings",
+            "7-Eleven",
+            "Jim's Mowing",
+        ] {
+            assert_eq!(placeholder_reason(name), None, "{}", name);
+        }
+    }
+
+    #[test]
+    fn slugs_fold_unicode_and_punctuation() {
+        for (name, slug) in [
+            ("Acme Plumbing Brisbane", "acme-plumbing-brisbane"),
+            ("Café Élan & Co", "cafe-elan-and-co"),
+            ("  --Acme!!  Plumbing--  ", "acme-plumbing"),
+            ("Phở Hà Nội", "pho-ha-noi"),
+            ("Müller's Bäckerei", "muller-s-backerei"),
+        ] {
+            assert_eq!(slugify_inner(name, 60), slug, "{}", name);
+        }
+    }
+
+    #[test]
+    fn slugs_drop_emoji() {
+        assert_eq!(slugify_inner("🌟 Acme Plumbing 🔧🚿", 60), "acme-plumbing");
+        assert_eq!(slugify_inner("Pizza 🍕 & Pasta 👨‍👩‍👧", 60), "pizza-and-pasta");
+        assert_eq!(slugify_inner("🔥🔥🔥", 60), "");
+    }
+
+    #[test]
+    fn slugs_truncate_at_a_word_boundary() {
+        assert_eq!(slugify_inner("Acme Plumbing Brisbane", 15), "acme-plumbing");
+        assert_eq!(slugify_inner("Acme Plumbing Brisbane", 13), "acme-plumbing");
+        assert_eq!(slugify_inner("Supercalifragilistic", 10), "supercalif");
+        let long = "word ".repeat(40);
+        assert!(slugify_inner(&long, 60).len() <= 60);
+    }
+
+    #[test]
+    fn unique_slugs_number_collisions_in_input_order() {
+        assert_eq!(
+            slugify_unique(
+                strings(&["Acme", "ACME", "Acmé", "Other", "", "🔥", "Acme 2"]),
+                60
+            ),
+            strings(&[
+                "acme",
+                "acme-2",
+                "acme-3",
+                "other",
+                "untitled",
+                "untitled-2",
+                "acme-2-2"
+            ])
+        );
+        let slugs = slugify_unique(strings(&["Acme Plumbing", "Acme Plumbing"]), 13);
+        assert_eq!(slugs, strings(&["acme-plumbing", "acme-plumbi-2"]));
+    }
+
+    #[test]
+    fn names_from_domains() {
+        for (domain, name) in [
+            ("acmeplumbingbrisbane.com.au", "Acme Plumbing Brisbane"),
+            (
+                "https://www.acmeplumbingbrisbane.com.au/contact",
+                "Acme Plumbing Brisbane",
+            ),
+            ("best-roofing.com", "Best Roofing"),
+            (
+                "sydney-electrical-services.com.au",
+                "Sydney Electrical Services",
+            ),
+            ("plumbing247.com.au", "Plumbing 247"),
+            ("24-7-locksmiths.com.au", "24 7 Locksmiths"),
+        ] {
+            assert_eq!(
+                name_from_domain(domain).as_deref(),
+                Some(name),
+                "{}",
+                domain
+            );
+        }
+    }
+
+    #[test]
+    fn low_confidence_labels_stay_whole() {
+        assert_eq!(name_from_domain("xqzvbk.com").as_deref(), Some("Xqzvbk"));
+        assert_eq!(name_from_domain("not a domain"), None);
+        assert_eq!(name_from_domain("xn--caf-sydney-d7a.com.au"), None);
+    }
+
+    fn address_fields(text: &str) -> Option<[Option<String>; 7]> {
+        parse_au_address_inner(text).map(|a| {
+            [
+                a.unit,
+                a.street_number,
+                a.street_name,
+                a.street_type.map(String::from),
+                a.suburb,
+                a.state.map(String::from),
+                a.postcode,
+            ]
+        })
+    }
+
+    #[test]
+    fn parses_messy_au_addresses() {
+        let table: &[(&str, [Option<&str>; 7])] = &[
+            (
+                "Unit 3/45 Smith St, Fortitude Valley QLD 4006",
+                [
+                    Some("3"),
+                    Some("45"),
+                    Some("Smith"),
+                    Some("St"),
+                    Some("Fortitude Valley"),
+                    Some("QLD"),
+                    Some("4006"),
+                ],
+            ),
+            (
+                "45-47 Smith Street Fortitude Valley 4006 QLD",
+                [
+                    None,
+                    Some("45-47"),
+                    Some("Smith"),
+                    Some("St"),
+                    Some("Fortitude Valley"),
+                    Some("QLD"),
+                    Some("4006"),
+                ],
+            ),
+            (
+                "3/45 smith st fortitude valley qld",
+                [
+                    Some("3"),
+                    Some("45"),
+                    Some("Smith"),
+                    Some("St"),
+                    Some("Fortitude Valley"),
+                    Some("QLD"),
+                    None,
+                ],
+            ),
+            (
+                "Level 2, 100 George Street, Sydney NSW 2000, Australia",
+                [
+                    Some("Level 2"),
+                    Some("100"),
+                    Some("George"),
+                    Some("St"),
+                    Some("Sydney"),
+                    Some("NSW"),
+                    Some("2000"),
+                ],
+            ),
+            (
+                "12A Ocean Parade, Coffs Harbour, New South Wales 2450",
+                [
+                    None,
+                    Some("12A"),
+                    Some("Ocean"),
+                    Some("Pde"),
+                    Some("Coffs Harbour"),
+                    Some("NSW"),
+                    Some("2450"),
+                ],
+            ),
+            (
+                "7 The Esplanade, Cairns Qld. 4870",
+                [
+                    None,
+                    Some("7"),
+                    Some("The Esplanade"),
+                    None,
+                    Some("Cairns"),
+                    Some("QLD"),
+                    Some("4870"),
+                ],
+            ),
+            (
+                "PO Box 123, Toowong QLD 4066",
+                [
+                    None,
+                    None,
+                    Some("PO Box 123"),
+                    None,
+                    Some("Toowong"),
+                    Some("QLD"),
+                    Some("4066"),
+                ],
+            ),
+            (
+                "Carlton 3053",
+                [
+                    None,
+                    None,
+                    None,
+                    None,
+                    Some("Carlton"),
+                    Some("VIC"),
+                    Some("3053"),
+                ],
+            ),
+            (
+                "Shop 4 22 Mary Anne Cres Hobart Tasmania",
+                [
+                    Some("Shop 4"),
+                    Some("22"),
+                    Some("Mary Anne"),
+                    Some("Cres"),
+                    Some("Hobart"),
+                    Some("TAS"),
+                    None,
+                ],
+            ),
+        ];
+        for (text, expected) in table {
+            let expected = expected.map(|f| f.map(String::from));
+            assert_eq!(address_fields(text), Some(expected), "{}", text);
+        }
+    }
+
+    #[test]
+    fn low_confidence_addresses_are_none() {
+        for text in [
+            "45 Smith Street Fortitude Valley",
+            "Call us today",
+            "",
+            // The postcode belongs to NSW, not VIC
+            "45 Smith St, Sydney VIC 2000",
+            // No state starts with 0 outside the NT/ACT ranges
+            "45 Smith St 0100",
+        ] {
+            assert!(address_fields(text).is_none(), "{}", text);
+        }
+    }
+
+    #[test]
+    fn normalized_addresses_compare_equal() {
+        let canonical = Some("3/45 Smith St, Fortitude Valley QLD 4006".to_string());
+        for text in [
+            "Unit 3/45 Smith Street, Fortitude Valley QLD 4006",
+            "U3 45 Smith St Fortitude Valley Queensland 4006",
+            "3 / 45  SMITH ST., FORTITUDE VALLEY, QLD, 4006, Australia",
+        ] {
+            assert_eq!(normalize_au_address(text), canonical, "{}", text);
+        }
+        assert_eq!(
+            normalize_au_address("Level 2, 100 George Street, Sydney NSW 2000").as_deref(),
+            Some("Level 2, 100 George St, Sydney NSW 2000")
+        );
+        assert_eq!(
+            normalize_au_address("PO Box 123 Toowong Qld 4066").as_deref(),
+            Some("PO Box 123, Toowong QLD 4066")
+        );
+    }
+
+    fn postcode(text: &str) -> Option<(String, Option<&'static str>)> {
+        extract_postcode_state_inner(text)
+    }
+
+    #[test]
+    fn postcodes_with_and_without_state_tokens() {
+        let northside = Some(("4034".to_string(), Some("QLD")));
+        assert_eq!(postcode("Servicing Brisbane Northside 4034"), northside);
+        assert_eq!(
+            postcode("Servicing Brisbane Northside, QLD 4034"),
+            northside
+        );
+        assert_eq!(postcode("Chermside 4034 Queensland, Australia"), northside);
+        assert_eq!(
+            postcode("Level 2/100 George St, Sydney NSW 2000, Australia"),
+            Some(("2000".to_string(), Some("NSW")))
+        );
+        assert_eq!(
+            postcode("Canberra ACT 2601"),
+            Some(("2601".to_string(), Some("ACT")))
+        );
+        assert_eq!(
+            postcode("Darwin 0800"),
+            Some(("0800".to_string(), Some("NT")))
+        );
+    }
+
+    #[test]
+    fn adjacent_state_beats_a_bare_postcode() {
+        assert_eq!(
+            postcode("Head office 3000, servicing Carlton Vic. 3053 and surrounds"),
+            Some(("3053".to_string(), Some("VIC")))
+        );
+        // A postcode that contradicts its state token is skipped
+        assert_eq!(postcode("Sydney VIC 2000"), None);
+    }
+
+    #[test]
+    fn year_price_and_phone_decoys_are_ignored() {
+        for text in [
+            "Established 2015",
+            "Since 1998, family owned",
+            "© 2023 Acme Plumbing",
+            "Hot water systems from $2000",
+            "Quotes up to AUD 3500",
+            "Call 1300 123 456",
+            "Call (07) 3123 4567",
+            "Open 2020-2024",
+            "Part 9999x",
+        ] {
+            assert_eq!(postcode(text), None, "{}", text);
+        }
+        assert_eq!(
+            postcode("Since 2015, servicing Toowong QLD 4066. Call 07 3123 4567"),
+            Some(("4066".to_string(), Some("QLD")))
+        );
+    }
+
+    // Generic providers
+
+    #[test]
+    fn nz_and_uk_isp_addresses_are_generic_not_mismatched() {
+        for email in [
+            "bob@xtra.co.nz",
+            "bob@orcon.net.nz",
+            "bob@btinternet.com",
+            "bob@westnet.com.au",
+            "bob@members.optusnet.com.au",
+        ] {
+            let result = check_email_domain(email, "acmeplumbing.co.nz");
+            assert!(result.valid, "{}", email);
+            assert_eq!(result.match_type.as_str(), "generic_provider", "{}", email);
+        }
+        assert_eq!(
+            filter_emails_for_domain(
+                strings(&["bob@xtra.co.nz", "jo@orcon.net.nz", "x@rival.co.nz"]),
+                "acmeplumbing.co.nz",
+                false,
+                true,
+                false,
+            ),
+            strings(&["bob@xtra.co.nz", "jo@orcon.net.nz"])
+        );
+    }
+
+    #[test]
+    fn business_mismatch_is_distinct_from_generic() {
+        let result = check_email_domain("bob@rival.co.nz", "acmeplumbing.co.nz");
+        assert!(!result.valid);
+        assert_eq!(result.match_type.as_str(), "mismatch");
+        // A provider's name inside another domain is not the provider
+        assert!(!is_generic_provider("notxtra.co.nz"));
+        assert!(!is_generic_provider("co.nz"));
+    }
+
+    #[test]
+    fn registered_generic_providers() {
+        assert!(!is_generic_provider("mail.zorblax-isp.net.nz"));
+        assert_eq!(
+            add_generic_providers(strings(&["zorblax-isp.net.nz", "gmail.com"])),
+            1
+        );
+        assert_eq!(add_generic_providers(strings(&["zorblax-isp.net.nz"])), 0);
+        assert!(is_generic_provider("mail.zorblax-isp.net.nz"));
+        let listed = list_generic_providers();
+        assert!(listed.contains(&"zorblax-isp.net.nz".to_string()));
+        assert!(listed.contains(&"xtra.co.nz".to_string()));
+        assert!(listed.windows(2).all(|w| w[0] < w[1]));
+    }
+
+    // Names from email local parts
+
+    fn inferred(email: &str) -> Option<(Option<String>, Option<String>, f64)> {
+        infer_name_inner(email).map(|n| (n.first, n.last, n.confidence))
+    }
+
+    fn name(
+        first: Option<&str>,
+        last: Option<&str>,
+        confidence: f64,
+    ) -> Option<(Option<String>, Option<String>, f64)> {
+        Some((first.map(String::from), last.map(String::from), confidence))
+    }
+
+    #[test]
+    fn name_patterns_and_confidence() {
+        let jane_smith = name(Some("Jane"), Some("Smith"), NAME_CONFIDENCE_HIGH);
+        assert_eq!(inferred("jane.smith@acme.com.au"), jane_smith);
+        assert_eq!(inferred("Jane_Smith@acme.com.au"), jane_smith);
+        assert_eq!(inferred("jane.m.smith@acme.com.au"), jane_smith);
+        assert_eq!(inferred("jane.smith+quotes@acme.com.au"), jane_smith);
+        assert_eq!(
+            inferred("janesmith@acme.com.au"),
+            name(Some("Jane"), Some("Smith"), NAME_CONFIDENCE_MEDIUM)
+        );
+        assert_eq!(
+            inferred("j.smith@acme.com.au"),
+            name(None, Some("Smith"), NAME_CONFIDENCE_MEDIUM)
+        );
+        assert_eq!(
+            inferred("jane@acme.com.au"),
+            name(Some("Jane"), None, NAME_CONFIDENCE_LOW)
+        );
+    }
+
+    #[test]
+    fn role_hash_and_business_mailboxes_have_no_name() {
+        for email in [
+            "info@acme.com.au",
+            "sales.team@acme.com.au",
+            "a3f9c2e1b7d84f60a1c2@sentry.io",
+            "acmeplumbing@acmeplumbing.com.au",
+            "not-an-email",
+            "@acme.com.au",
+        ] {
+            assert_eq!(inferred(email), None, "{}", email);
+        }
+    }
+
+    // TLD validation
+
+    #[test]
+    fn made_up_tlds_are_rejected_by_default() {
+        for host in [
+            "acme.plumbing-brisbane",
+            "business.loc",
+            "acme.comau",
+            "https://acme.notarealtld/contact",
+        ] {
+            assert_eq!(normalize_domain(host, false, false, true), None, "{}", host);
+        }
+        assert_eq!(
+            normalize_domain("business.loc", false, false, false).as_deref(),
+            Some("business.loc")
+        );
+    }
+
+    #[test]
+    fn new_and_punycode_tlds_are_kept() {
+        for (host, domain) in [
+            ("acme.plumbing", "acme.plumbing"),
+            ("https://www.Acme.SYDNEY/", "acme.sydney"),
+            ("acme.melbourne", "acme.melbourne"),
+            ("acme.COM.AU", "acme.com.au"),
+            ("example.xn--p1ai", "example.xn--p1ai"),
+            ("пример.рф", "xn--e1afmkfd.xn--p1ai"),
+        ] {
+            assert_eq!(
+                normalize_domain(host, false, false, true).as_deref(),
+                Some(domain),
+                "{}",
+                host
+            );
+        }
+    }
+
+    #[test]
+    fn registered_tlds_are_accepted() {
+        assert_eq!(
+            normalize_domain("acme.zorblaxtld", false, false, true),
+            None
+        );
+        let entries: Vec<String> = [".ZorblaxTLD", "plumbing"]
+            .iter()
+            .filter_map(|e| tld_entry(e))
+            .collect();
+        assert_eq!(entries, strings(&["zorblaxtld", "plumbing"]));
+        assert_eq!(add_extra_tlds(entries), 1);
+        assert_eq!(
+            normalize_domain("acme.zorblaxtld", false, false, true).as_deref(),
+            Some("acme.zorblaxtld")
+        );
+        for bad in ["", "com.au", "1abc", "bad tld"] {
+            assert_eq!(tld_entry(bad), None, "{:?}", bad);
+        }
+    }
+
+    // Non-ASCII robustness
+
+    /// Emoji, ZWJ sequences, CJK, RTL, combining marks and case-mapping
+    /// oddities spliced into the ASCII shapes the parsers look for, so every
+    /// delimiter, digit run and suffix sits next to a multibyte character.
+    fn non_ascii_corpus() -> Vec<String> {
+        let fragments = [
+            "🔧",
+            "👨‍👩‍👧‍👦",
+            "👍🏽",
+            "🏳️‍🌈",
+            "⭐️",
+            "東京配管",
+            "배관공",
+            "سباكة",
+            "שרברב",
+            "\u{202E}evil\u{202C}",
+            "e\u{301}",
+            "İ",
+            "ß",
+            "\u{200B}",
+            "\u{FEFF}",
+            "ﬁ",
+            "Ⅻ",
+            "４０００",
+        ];
+        let templates = [
+            "{}",
+            "{} Acme Plumbing | Brisbane",
+            "Acme {} Plumbing - Sydney's Best",
+            "Brisbane's {} Plumbing Pty Ltd t/a {}",
+            "Unit 3/45{} Smith St, Fortitude Valley QLD 4006{}",
+            "{}45-47 Smith Street {} 4006 QLD",
+            "Since 2015{} servicing Toowong QLD 4066 call 07 3123 {}4567",
+            "info{}@acme{}.com.au",
+            "jane.smith{}@{}gmail.com",
+            "https://www.{}acme.com.au:{}8080/path?q={}",
+            "{}.com.au",
+            "xn--{}.com",
+            "Jim's Mowing ({})",
+            "Fernwood Fitness {}",
+            "+61 {}412 345 678",
+            "1300{}123{}456",
+            "{}:{}/{}@{}",
+        ];
+        let mut corpus = Vec::new();
+        for template in templates {
+            for fragment in fragments {
+                corpus.push(template.replace("{}", fragment));
+            }
+            corpus.push(template.replace("{}", &fragments.concat()));
+        }
+        corpus
+    }
+
+    #[test]
+    fn text_functions_never_panic_on_non_ascii() {
+        // Panics fail the test; returned Strings are valid UTF-8 by type
+        let corpus = non_ascii_corpus();
+        let no_locations = HashSet::new();
+        for input in &corpus {
+            let _ = normalize_domain(input, true, true, true);
+            let _ = normalize_domain(input, false, false, false);
+            let _ = canonical_domain(input);
+            let _ = canonicalize_url_inner(input, &strings(&["q"]));
+            let _ = display_domain(input);
+            let _ = split_host(input);
+            let _ = registrable_domain(input);
+            let _ = is_directory_domain(input);
+            let _ = classify_domain(input);
+            let _ = is_directory_url(input, input, true);
+            let _ = split_trading_as(input);
+            let _ = normalize_name(input);
+            let _ = clean_business_name(input, true);
+            let _ = clean_business_name(input, false);
+            let _ = normalize_phone(input);
+            let _ = check_email_domain(input, input);
+            let _ = check_email_domain(input, "acme.com.au");
+            let _ = check_email_syntax(input);
+            let _ = is_disposable_email(input);
+            let _ = classify_email_inner(input);
+            let _ = infer_name_inner(input);
+            let _ = name_tokens(input, true);
+            let _ = name_similarity(input, "Acme Plumbing");
+            let _ = name_location_of(input, &no_locations);
+            let _ = detect_franchise_inner(input);
+            let _ = placeholder_reason(input);
+            let _ = slugify_inner(input, 60);
+            let _ = slugify_inner(input, 5);
+            let _ = name_from_domain(input);
+            let _ = parse_au_address_inner(input);
+            let _ = normalize_au_address(input);
+            let _ = find_au_addresses(input);
+            let _ = extract_postcode_state_inner(input);
+        }
+        let _ = filter_emails_for_domain(corpus.clone(), "acme.com.au", true, true, true);
+        let _ = filter_emails_with_reasons(corpus.clone(), "acme.com.au");
+        let _ = dedupe_and_rank_emails(corpus.clone(), "acme.com.au");
+        let _ = match_names(corpus.clone(), "Acme Plumbing", 0.5);
+        let _ = slugify_unique(corpus.clone(), 8);
+        let _ = first_seen_domains(&normalize_domains_of(&corpus, true, true, false));
+    }
+}